@@ -0,0 +1,148 @@
+//! Command-line arguments: files to open at startup, or a headless export
+//! that runs without creating a window.
+
+use crate::export::pdf::PdfExporter;
+use crate::export::settings::{ExportSettings, PageOrientation, PageSize, PlotStyle, ScaleType};
+use crate::model::project::ProjectData;
+use std::path::{Path, PathBuf};
+
+pub const USAGE: &str = "\
+Usage:
+  MuginCAD [FILE.mugin ...]
+  MuginCAD --export-pdf OUT.pdf [OPTIONS] FILE.mugin
+
+Options:
+  --export-pdf OUT.pdf           Export FILE to PDF and exit without a window
+  --scale N                      Print at 1:N (default: fit to page)
+  --page A4|A3|WxH               Page size, WxH in millimetres (default: A4)
+  --orientation portrait|landscape
+                                 Page orientation (default: landscape)
+  --margin MM                    Page margin in millimetres (default: 10)
+  --scale-bar                    Draw a graphic scale bar
+  --plot color|gray|mono         Print colors (default: mono)
+  -h, --help                     Show this help";
+
+/// What the process should do
+pub enum CliAction {
+    /// Start the GUI, opening each file in its own tab
+    Gui {
+        files: Vec<PathBuf>,
+    },
+    /// Export a project to PDF and exit
+    ExportPdf {
+        input: PathBuf,
+        output: PathBuf,
+        settings: ExportSettings,
+    },
+    Help,
+}
+
+/// Parse arguments (without the program name)
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<CliAction, String> {
+    let mut files = Vec::new();
+    let mut output: Option<PathBuf> = None;
+    let mut settings = ExportSettings::default();
+    let mut export_flag_used = None;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| {
+            args.next()
+                .ok_or_else(|| format!("missing value for {}", flag))
+        };
+
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(CliAction::Help),
+            "--export-pdf" => output = Some(PathBuf::from(value("--export-pdf")?)),
+            "--scale" => {
+                let scale = parse_positive(&value("--scale")?, "--scale")?;
+                settings.scale_type = ScaleType::Custom(scale);
+                export_flag_used = Some("--scale");
+            }
+            "--page" => {
+                settings.page_size = parse_page(&value("--page")?)?;
+                export_flag_used = Some("--page");
+            }
+            "--orientation" => {
+                settings.orientation = match value("--orientation")?.to_lowercase().as_str() {
+                    "portrait" => PageOrientation::Portrait,
+                    "landscape" => PageOrientation::Landscape,
+                    other => return Err(format!("unknown orientation '{}'", other)),
+                };
+                export_flag_used = Some("--orientation");
+            }
+            "--margin" => {
+                settings.margin_mm = value("--margin")?
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|m| m.is_finite() && *m >= 0.0)
+                    .ok_or("--margin expects a non-negative number")?;
+                export_flag_used = Some("--margin");
+            }
+            "--plot" => {
+                settings.plot_style = match value("--plot")?.to_lowercase().as_str() {
+                    "color" => PlotStyle::FullColor,
+                    "gray" => PlotStyle::Grayscale,
+                    "mono" => PlotStyle::Monochrome,
+                    other => return Err(format!("unknown plot style '{}'", other)),
+                };
+                export_flag_used = Some("--plot");
+            }
+            "--scale-bar" => {
+                settings.scale_bar = true;
+                export_flag_used = Some("--scale-bar");
+            }
+            flag if flag.starts_with("--") => return Err(format!("unknown option '{}'", flag)),
+            _ => files.push(PathBuf::from(arg)),
+        }
+    }
+
+    match output {
+        Some(output) => {
+            let [input] = <[PathBuf; 1]>::try_from(files)
+                .map_err(|_| "--export-pdf expects exactly one project file".to_string())?;
+            Ok(CliAction::ExportPdf {
+                input,
+                output,
+                settings,
+            })
+        }
+        None => match export_flag_used {
+            Some(flag) => Err(format!("{} is only valid with --export-pdf", flag)),
+            None => Ok(CliAction::Gui { files }),
+        },
+    }
+}
+
+/// Load a project and write it to PDF. Touches neither `rfd` nor eframe.
+pub fn export_pdf(input: &Path, output: &Path, settings: &ExportSettings) -> Result<(), String> {
+    let project = ProjectData::read(input)?;
+    let dim_style = project.config.dimension_style.clone();
+    let model = project.into_model();
+
+    PdfExporter::export_to_file(&model, settings, &dim_style, output)
+        .map_err(|e| format!("export to {} failed: {}", output.display(), e))
+}
+
+fn parse_positive(text: &str, flag: &str) -> Result<f32, String> {
+    text.parse::<f32>()
+        .ok()
+        .filter(|v| v.is_finite() && *v > 0.0)
+        .ok_or_else(|| format!("{} expects a positive number", flag))
+}
+
+fn parse_page(text: &str) -> Result<PageSize, String> {
+    match text.to_uppercase().as_str() {
+        "A4" => Ok(PageSize::A4),
+        "A3" => Ok(PageSize::A3),
+        custom => {
+            let (w, h) = custom
+                .split_once('X')
+                .ok_or_else(|| format!("unknown page size '{}'", text))?;
+            Ok(PageSize::Custom(
+                parse_positive(w, "--page")?,
+                parse_positive(h, "--page")?,
+            ))
+        }
+    }
+}
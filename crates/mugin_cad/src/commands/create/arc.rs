@@ -1,8 +1,13 @@
+#[cfg(feature = "gui")]
 use crate::commands::preview;
-use crate::commands::{Command, CommandCategory, CommandContext, InputResult, PointResult};
+use crate::commands::{
+    Command, CommandCategory, CommandContext, CommandError, CommandOption, InputResult, PointResult,
+};
 use crate::model::shapes::arc::Arc;
 use crate::model::{Entity, Vector2};
 
+const REVERSE: CommandOption = CommandOption::new("r", "Reverse");
+
 define_command!(ArcCommand,
     filled: bool = false,
     clockwise: bool = false
@@ -13,6 +18,13 @@ impl ArcCommand {
     pub fn toggle_direction(&mut self) {
         self.clockwise = !self.clockwise;
     }
+
+    fn end_prompt(&self) -> String {
+        format!(
+            "Specify end point [{}] (type 'r' to reverse):",
+            if self.clockwise { "CW" } else { "CCW" }
+        )
+    }
 }
 
 impl Command for ArcCommand {
@@ -28,19 +40,24 @@ impl Command for ArcCommand {
         "ARC Specify center point:".to_string()
     }
 
+    fn options(&self) -> &[CommandOption] {
+        if self.points.len() == 2 {
+            &[REVERSE]
+        } else {
+            &[]
+        }
+    }
+
     fn push_point(&mut self, pos: Vector2, ctx: &mut CommandContext) -> PointResult {
         self.points.push(pos);
-        self.filled = ctx.filled_mode;
+        self.filled = ctx.defaults.filled;
 
         match self.points.len() {
             1 => PointResult::NeedMore {
                 prompt: "Specify start point of arc:".to_string(),
             },
             2 => PointResult::NeedMore {
-                prompt: format!(
-                    "Specify end point [{}] (type 'r' to reverse):",
-                    if self.clockwise { "CW" } else { "CCW" }
-                ),
+                prompt: self.end_prompt(),
             },
             3 => {
                 let arc = Arc::from_three_points_directed(
@@ -50,7 +67,22 @@ impl Command for ArcCommand {
                     self.filled,
                     self.clockwise,
                 );
-                ctx.model.add_entity(Entity::arc(arc));
+                let entity = Entity::arc(arc);
+                if let Err(error) = ctx.check(&entity) {
+                    // A start on the center leaves nothing to sweep; pick it again
+                    let prompt = match &error {
+                        CommandError::Degenerate(problem) if problem.what == "Arc radius" => {
+                            self.points.truncate(1);
+                            "Specify start point of arc:".to_string()
+                        }
+                        _ => {
+                            self.points.truncate(2);
+                            self.end_prompt()
+                        }
+                    };
+                    return PointResult::Rejected { error, prompt };
+                }
+                ctx.add_entity(entity);
                 PointResult::Complete
             }
             _ => PointResult::Complete,
@@ -61,13 +93,10 @@ impl Command for ArcCommand {
         let clean = input.trim().to_lowercase();
 
         // Handle 'r' or 'reverse' to toggle direction (only when we have 2 points)
-        if self.points.len() == 2 && (clean == "r" || clean == "reverse") {
+        if self.points.len() == 2 && (clean == REVERSE.key || clean == "reverse") {
             self.toggle_direction();
             return InputResult::Parameter(PointResult::NeedMore {
-                prompt: format!(
-                    "Specify end point [{}] (type 'r' to reverse):",
-                    if self.clockwise { "CW" } else { "CCW" }
-                ),
+                prompt: self.end_prompt(),
             });
         }
 
@@ -75,15 +104,14 @@ impl Command for ArcCommand {
         if let Some(pos) = crate::commands::parse_point(input) {
             InputResult::Point(self.push_point(pos, ctx))
         } else {
-            InputResult::Invalid {
-                message: format!(
-                    "Invalid input \"{}\". Enter point or 'r' to reverse.",
-                    input
-                ),
-            }
+            InputResult::Invalid(CommandError::InvalidInput {
+                input: input.to_string(),
+                hint: Some("Enter point or 'r' to reverse."),
+            })
         }
     }
 
+    #[cfg(feature = "gui")]
     fn draw_preview(
         &self,
         ctx: &crate::view::rendering::context::DrawContext,
@@ -110,38 +138,19 @@ impl Command for ArcCommand {
             2 => {
                 let center = points[0];
                 let start = points[1];
-                let start_angle = (start.y - center.y).atan2(start.x - center.x);
-                let end_angle = (current_cad.y - center.y).atan2(current_cad.x - center.x);
-
-                let is_clockwise = self.clockwise;
-
-                let segments = 32;
-                let angle_range = if is_clockwise {
-                    let mut range = start_angle - end_angle;
-                    if range < 0.0 {
-                        range += std::f32::consts::PI * 2.0;
-                    }
-                    -range
-                } else {
-                    let mut range = end_angle - start_angle;
-                    if range < 0.0 {
-                        range += std::f32::consts::PI * 2.0;
-                    }
-                    range
-                };
-
-                let angle_step = angle_range / segments as f32;
-                let radius = center.dist(start);
-
-                let mut arc_points = Vec::with_capacity(segments + 1);
-                for i in 0..=segments {
-                    let angle = start_angle + angle_step * i as f32;
-                    let pt = Vector2::new(
-                        center.x + radius * angle.cos(),
-                        center.y + radius * angle.sin(),
-                    );
-                    arc_points.push(ctx.to_screen(pt));
-                }
+                // Same sampling as the placed arc, so both directions match
+                let arc = Arc::from_three_points_directed(
+                    center,
+                    start,
+                    current_cad,
+                    false,
+                    self.clockwise,
+                );
+                let arc_points: Vec<egui::Pos2> = arc
+                    .points(32)
+                    .into_iter()
+                    .map(|pt| ctx.to_screen(pt))
+                    .collect();
 
                 let stroke = preview::preview_stroke();
                 for i in 0..arc_points.len() - 1 {
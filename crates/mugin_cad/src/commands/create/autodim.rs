@@ -0,0 +1,406 @@
+use crate::commands::output::TerminalLine;
+use crate::commands::{
+    Command, CommandCategory, CommandContext, CommandError, CommandOption, InputResult,
+    PointResult, parse_point,
+};
+use crate::model::axis::AxisOrientation;
+use crate::model::shapes::annotation::TextAnnotation;
+use crate::model::{Entity, Shape, Vector2};
+
+/// Name of the container the generated dimensions go into
+pub const DIMENSIONS_GROUP: &str = "Dimensions";
+/// Lines closer than this along the chain count as one position
+const DUPLICATE_TOLERANCE: f32 = 0.01;
+/// Largest sine of the angle between lines still taken as parallel (~0.5°)
+const PARALLEL_TOLERANCE: f32 = 0.01;
+
+const VERTICAL: CommandOption = CommandOption::new("v", "Vertical axes");
+const HORIZONTAL: CommandOption = CommandOption::new("h", "Horizontal axes");
+const SPACING: CommandOption = CommandOption::new("s", "Spacing");
+
+/// A position along the chain, with the extent of what stands there
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Station {
+    /// Distance along the chain direction
+    pub position: f32,
+    /// Extent along the measured lines
+    pub start: f32,
+    pub end: f32,
+}
+
+/// Parallel lines ordered along the direction across them
+#[derive(Debug, Clone, Default)]
+pub struct DimensionChain {
+    /// Unit direction the chain runs in, across the lines
+    pub across: Vector2,
+    /// Unit direction of the lines
+    pub along: Vector2,
+    /// Distinct positions, in chain order
+    pub stations: Vec<Station>,
+    /// Lines left out for not running parallel to the first
+    pub skipped: usize,
+    /// Lines dropped for standing where another already does
+    pub duplicates: usize,
+}
+
+impl DimensionChain {
+    /// Chain across `lines`, in the direction most of them share. Lines
+    /// at an angle to it are skipped.
+    pub fn from_lines(lines: &[(Vector2, Vector2)]) -> Self {
+        let mut chain = Self::default();
+        let directions: Vec<Vector2> = lines
+            .iter()
+            .map(|&(start, end)| end - start)
+            .filter(|d| d.length() > 1e-4)
+            .map(|d| d.normalized())
+            .collect();
+        let parallel = |a: Vector2, b: Vector2| a.cross(b).abs() <= PARALLEL_TOLERANCE;
+        // Earliest of the most common directions, so ties stay put
+        let Some(direction) = directions.iter().copied().rev().max_by_key(|&d| {
+            directions
+                .iter()
+                .filter(|&&other| parallel(d, other))
+                .count()
+        }) else {
+            return chain;
+        };
+        let mut across = direction.perp();
+        // Chains run left to right, or bottom to top
+        if across.x < -1e-4 || (across.x.abs() <= 1e-4 && across.y < 0.0) {
+            across = -across;
+        }
+        chain.across = across;
+        chain.along = across.perp();
+
+        let mut stations = Vec::new();
+        for &(start, end) in lines {
+            let d = end - start;
+            if d.length() <= 1e-4 || !parallel(d.normalized(), direction) {
+                chain.skipped += 1;
+                continue;
+            }
+            let (a, b) = (start.dot(chain.along), end.dot(chain.along));
+            stations.push(Station {
+                position: start.lerp(end, 0.5).dot(across),
+                start: a.min(b),
+                end: a.max(b),
+            });
+        }
+        chain.add_stations(stations);
+        chain
+    }
+
+    /// Chain across the model's axes of one orientation. Axes have no
+    /// ends, so they are taken to span `extent` along their direction.
+    pub fn from_axes(positions: &[f32], orientation: AxisOrientation, extent: (f32, f32)) -> Self {
+        let across = match orientation {
+            AxisOrientation::Vertical => Vector2::new(1.0, 0.0),
+            AxisOrientation::Horizontal => Vector2::new(0.0, 1.0),
+        };
+        let mut chain = Self {
+            across,
+            along: across.perp(),
+            ..Default::default()
+        };
+        let (start, end) = match orientation {
+            // Extents come in world x, which runs against `along` here
+            AxisOrientation::Horizontal => (-extent.1, -extent.0),
+            AxisOrientation::Vertical => extent,
+        };
+        chain.add_stations(
+            positions
+                .iter()
+                .map(|&position| Station {
+                    position,
+                    start,
+                    end,
+                })
+                .collect(),
+        );
+        chain
+    }
+
+    /// Sort `stations` along the chain, merging the ones at the same place
+    fn add_stations(&mut self, mut stations: Vec<Station>) {
+        stations.sort_by(|a, b| a.position.total_cmp(&b.position));
+        for station in stations {
+            match self.stations.last_mut() {
+                Some(last) if station.position - last.position < DUPLICATE_TOLERANCE => {
+                    last.start = last.start.min(station.start);
+                    last.end = last.end.max(station.end);
+                    self.duplicates += 1;
+                }
+                _ => self.stations.push(station),
+            }
+        }
+    }
+
+    /// Dimensions placed on the side of the lines `pick` is on: one per
+    /// consecutive pair at the pick's distance, then the overall one
+    /// `spacing` further out. Each is the two measured points and the
+    /// label position.
+    pub fn layout(&self, pick: Vector2, spacing: f32) -> Vec<[Vector2; 3]> {
+        let (Some(first), Some(last)) = (self.stations.first(), self.stations.last()) else {
+            return Vec::new();
+        };
+        if self.stations.len() < 2 {
+            return Vec::new();
+        }
+        let low = self
+            .stations
+            .iter()
+            .map(|s| s.start)
+            .fold(f32::MAX, f32::min);
+        let high = self.stations.iter().map(|s| s.end).fold(f32::MIN, f32::max);
+        let t = pick.dot(self.along);
+        // Measure from the ends nearest the pick, so every extension line
+        // starts on the same baseline
+        let (side, base) = if t >= (low + high) / 2.0 {
+            (1.0, high)
+        } else {
+            (-1.0, low)
+        };
+        // A pick between the ends still puts the chain outside them
+        let distance = match (t - base) * side {
+            d if d > 1e-3 => d,
+            _ => spacing,
+        };
+        let point = |station: &Station| self.across * station.position + self.along * base;
+        let place = |a: Vector2, b: Vector2, offset: f32| {
+            [a, b, a.lerp(b, 0.5) + self.along * (side * offset)]
+        };
+
+        let mut dims: Vec<[Vector2; 3]> = self
+            .stations
+            .windows(2)
+            .map(|pair| place(point(&pair[0]), point(&pair[1]), distance))
+            .collect();
+        if self.stations.len() > 2 {
+            dims.push(place(point(first), point(last), distance + spacing));
+        }
+        dims
+    }
+}
+
+/// What the next typed value or pick is for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Step {
+    Side,
+    Spacing,
+}
+
+define_command!(
+    AutoDimCommand,
+    chain: DimensionChain = DimensionChain::default(),
+    spacing: f32 = 40.0,
+    step: Step = Step::Side,
+);
+
+impl AutoDimCommand {
+    fn prompt(&self) -> String {
+        match self.step {
+            Step::Spacing => format!("Enter spacing between dimension rows <{}>:", self.spacing),
+            Step::Side if self.chain.stations.len() < 2 => {
+                "AUTODIM Select parallel lines first, or dimension the axes (V, H):".to_string()
+            }
+            Step::Side => format!(
+                "AUTODIM Specify side for {} positions (S: Spacing {}):",
+                self.chain.stations.len(),
+                self.spacing
+            ),
+        }
+    }
+
+    fn need(&self) -> PointResult {
+        PointResult::NeedMore {
+            prompt: self.prompt(),
+        }
+    }
+
+    /// Chain across the model's axes of `orientation`, spanning the drawing
+    fn use_axes(&mut self, orientation: AxisOrientation, ctx: &CommandContext) -> InputResult {
+        let positions: Vec<f32> = ctx
+            .model
+            .axis_manager
+            .axes
+            .iter()
+            .filter(|axis| axis.orientation == orientation)
+            .map(|axis| axis.position)
+            .collect();
+        let extent = if ctx.model.entities.is_empty() {
+            (0.0, 0.0)
+        } else {
+            let (min, max) = ctx.model.bounds();
+            match orientation {
+                AxisOrientation::Vertical => (min.y, max.y),
+                AxisOrientation::Horizontal => (min.x, max.x),
+            }
+        };
+        let chain = DimensionChain::from_axes(&positions, orientation, extent);
+        if chain.stations.len() < 2 {
+            return InputResult::Invalid(CommandError::MissingPrerequisite(
+                "Dimensioning needs at least two axes of that orientation.".to_string(),
+            ));
+        }
+        self.chain = chain;
+        InputResult::Parameter(self.need())
+    }
+
+    /// Add the dimensions as one container on the side of `pick`
+    fn create(&self, pick: Vector2, ctx: &mut CommandContext) {
+        let mut group = Entity::empty(DIMENSIONS_GROUP);
+        group.layer_id = ctx.layer_id;
+        let group_id = group.id;
+        ctx.place(group);
+
+        let dims = self.chain.layout(pick, self.spacing);
+        for &[a, b, label] in &dims {
+            let mut annotation = TextAnnotation::new_distance(a, b);
+            annotation.position = label;
+            let mut entity = Entity::text(annotation);
+            entity.layer_id = ctx.layer_id;
+            entity.color = ctx.defaults.color;
+            ctx.model.add_child_entity(group_id, entity);
+        }
+
+        let overall = self
+            .chain
+            .stations
+            .last()
+            .zip(self.chain.stations.first())
+            .map_or(0.0, |(last, first)| last.position - first.position);
+        ctx.report(TerminalLine::result(format!(
+            "{} dimensions, overall {}",
+            dims.len(),
+            ctx.dimension_style.format_length(overall)
+        )));
+        if self.chain.duplicates > 0 {
+            ctx.report(TerminalLine::info(format!(
+                "{} line(s) at an already dimensioned position merged",
+                self.chain.duplicates
+            )));
+        }
+        if self.chain.skipped > 0 {
+            ctx.report(TerminalLine::warning(format!(
+                "{} line(s) not parallel to the others skipped",
+                self.chain.skipped
+            )));
+        }
+    }
+}
+
+impl Command for AutoDimCommand {
+    fn name(&self) -> &'static str {
+        "AUTODIM"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::Creation
+    }
+
+    fn initial_prompt(&self) -> String {
+        self.prompt()
+    }
+
+    fn on_start(&mut self, ctx: &CommandContext) {
+        self.spacing = ctx.dimension_style.chain_spacing;
+        let lines: Vec<(Vector2, Vector2)> = ctx
+            .selected_ids
+            .iter()
+            .filter_map(|&id| ctx.model.find_by_id(id))
+            .filter_map(|entity| match &entity.shape {
+                Shape::Line(line) => {
+                    let world = |p: Vector2| -> Vector2 {
+                        entity.world_transform.transform_point2(p.into()).into()
+                    };
+                    Some((world(line.start), world(line.end)))
+                }
+                _ => None,
+            })
+            .collect();
+        self.chain = DimensionChain::from_lines(&lines);
+    }
+
+    fn options(&self) -> &[CommandOption] {
+        match self.step {
+            Step::Side if self.chain.stations.len() < 2 => &[VERTICAL, HORIZONTAL],
+            Step::Side => &[SPACING, VERTICAL, HORIZONTAL],
+            Step::Spacing => &[],
+        }
+    }
+
+    fn process_input(&mut self, input: &str, ctx: &mut CommandContext) -> InputResult {
+        let input = input.trim();
+        if self.step == Step::Spacing {
+            return match input.parse::<f32>() {
+                Ok(spacing) if spacing > 0.0 => {
+                    self.spacing = spacing;
+                    self.step = Step::Side;
+                    InputResult::Parameter(self.need())
+                }
+                _ => InputResult::Invalid(CommandError::InvalidValue(
+                    "Spacing must be a positive number.".to_string(),
+                )),
+            };
+        }
+        match input {
+            _ if input == VERTICAL.key => return self.use_axes(AxisOrientation::Vertical, ctx),
+            _ if input == HORIZONTAL.key => {
+                return self.use_axes(AxisOrientation::Horizontal, ctx);
+            }
+            _ if input == SPACING.key => {
+                self.step = Step::Spacing;
+                return InputResult::Parameter(self.need());
+            }
+            _ => {}
+        }
+        match parse_point(input) {
+            Some(pos) => InputResult::Point(self.push_point(pos, ctx)),
+            None => InputResult::Invalid(CommandError::invalid_input(input)),
+        }
+    }
+
+    fn push_point(&mut self, pos: Vector2, ctx: &mut CommandContext) -> PointResult {
+        if self.step != Step::Side {
+            return self.need();
+        }
+        if self.chain.stations.len() < 2 {
+            return PointResult::Rejected {
+                error: CommandError::NothingSelected {
+                    what: "parallel lines",
+                },
+                prompt: self.prompt(),
+            };
+        }
+        self.points.push(pos);
+        self.create(pos, ctx);
+        PointResult::Complete
+    }
+
+    impl_command_common!(AutoDimCommand);
+
+    #[cfg(feature = "gui")]
+    fn draw_preview(
+        &self,
+        ctx: &crate::view::rendering::context::DrawContext,
+        _points: &[Vector2],
+        cursor_pos_cad: Vector2,
+    ) {
+        use crate::commands::preview;
+
+        if self.step != Step::Side {
+            return;
+        }
+        for [a, b, label] in self.chain.layout(cursor_pos_cad, self.spacing) {
+            let offset = self.chain.along * (label - a).dot(self.chain.along);
+            for [from, to] in [[a, a + offset], [b, b + offset], [a + offset, b + offset]] {
+                ctx.painter.line_segment(
+                    [ctx.to_screen(from), ctx.to_screen(to)],
+                    preview::preview_stroke(),
+                );
+            }
+            let text = ctx.dim_style.format_length(a.dist(b));
+            preview::draw_dimension_text(ctx, ctx.to_screen(label), text);
+        }
+    }
+}
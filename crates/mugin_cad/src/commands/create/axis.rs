@@ -1,4 +1,6 @@
-use crate::commands::{Command, CommandCategory, CommandContext, InputResult, PointResult};
+use crate::commands::{
+    Command, CommandCategory, CommandContext, CommandError, InputResult, PointResult,
+};
 use crate::model::Vector2;
 use crate::model::axis::AxisOrientation;
 
@@ -71,9 +73,10 @@ impl Command for AxisCommand {
                     });
                 }
                 _ => {
-                    return InputResult::Invalid {
-                        message: "Enter H for horizontal or V for vertical".to_string(),
-                    };
+                    return InputResult::Invalid(CommandError::InvalidInput {
+                        input: clean,
+                        hint: Some("Enter H for horizontal or V for vertical."),
+                    });
                 }
             }
         }
@@ -107,12 +110,10 @@ impl Command for AxisCommand {
             return InputResult::Point(self.push_point(pos, ctx));
         }
 
-        InputResult::Invalid {
-            message: format!(
-                "Invalid input \"{}\". Enter coordinate or click position.",
-                input
-            ),
-        }
+        InputResult::Invalid(CommandError::InvalidInput {
+            input: input.to_string(),
+            hint: Some("Enter coordinate or click position."),
+        })
     }
 
     impl_command_common!(AxisCommand);
@@ -1,5 +1,8 @@
+#[cfg(feature = "gui")]
 use crate::commands::preview;
-use crate::commands::{Command, CommandCategory, CommandContext, PointResult};
+use crate::commands::{
+    Command, CommandCategory, CommandContext, CommandError, CommandOption, InputResult, PointResult,
+};
 use crate::model::structure::beam::BeamData;
 use crate::model::structure::beam_type::BeamType;
 use crate::model::{Entity, Vector2};
@@ -12,8 +15,13 @@ pub struct BeamCommand {
     current_anchor_index: usize,
     active_beam_type_id: Option<u64>,
     cached_beam_type: Option<BeamType>,
+    /// Continue each beam from the end of the previous one
+    chain: bool,
 }
 
+const CHAIN: CommandOption = CommandOption::new("c", "Chain");
+const ANCHOR: CommandOption = CommandOption::new("a", "Anchor");
+
 impl BeamCommand {
     pub fn new() -> Self {
         Self {
@@ -21,6 +29,7 @@ impl BeamCommand {
             current_anchor_index: 0,
             active_beam_type_id: None,
             cached_beam_type: None,
+            chain: true,
         }
     }
 
@@ -36,6 +45,18 @@ impl BeamCommand {
             self.current_anchor_index = 1; // Bottom to Top
         }
     }
+
+    fn prompt(&self) -> String {
+        let chain = if self.chain { "on" } else { "off" };
+        if self.points.is_empty() {
+            format!(
+                "Specify beam start point, chain {} (Q: Anchor, E: Flip):",
+                chain
+            )
+        } else {
+            format!("Specify beam end point, chain {}:", chain)
+        }
+    }
 }
 
 impl Command for BeamCommand {
@@ -49,12 +70,12 @@ impl Command for BeamCommand {
 
     fn on_start(&mut self, ctx: &CommandContext) {
         // Use the active beam type from context if available
-        if let Some(id) = ctx.active_beam_type_id {
-            if let Some(beam_type) = ctx.model.definitions.beam_types.get(&id) {
-                self.active_beam_type_id = Some(id);
-                self.cached_beam_type = Some(beam_type.clone());
-                return;
-            }
+        if let Some(id) = ctx.active_beam_type_id
+            && let Some(beam_type) = ctx.model.definitions.beam_types.get(&id)
+        {
+            self.active_beam_type_id = Some(id);
+            self.cached_beam_type = Some(beam_type.clone());
+            return;
         }
 
         // Fallback to first available if none selected or selection invalid
@@ -65,7 +86,30 @@ impl Command for BeamCommand {
     }
 
     fn initial_prompt(&self) -> String {
-        "Specify beam start point (Q: Anchor, E: Flip):".to_string()
+        self.prompt()
+    }
+
+    fn options(&self) -> &[CommandOption] {
+        &[CHAIN, ANCHOR]
+    }
+
+    fn process_input(&mut self, input: &str, ctx: &mut CommandContext) -> InputResult {
+        if input == CHAIN.key {
+            self.chain = !self.chain;
+            return InputResult::Parameter(PointResult::NeedMore {
+                prompt: self.prompt(),
+            });
+        }
+        if input == ANCHOR.key {
+            self.cycle_anchor();
+            return InputResult::Parameter(PointResult::NeedMore {
+                prompt: self.prompt(),
+            });
+        }
+        match crate::commands::parse_point(input) {
+            Some(pos) => InputResult::Point(self.push_point(pos, ctx)),
+            None => InputResult::Invalid(CommandError::invalid_input(input)),
+        }
     }
 
     fn push_point(&mut self, pos: Vector2, ctx: &mut CommandContext) -> PointResult {
@@ -80,7 +124,7 @@ impl Command for BeamCommand {
 
         if self.points.len() == 1 {
             return PointResult::NeedMore {
-                prompt: "Specify beam end point:".to_string(),
+                prompt: self.prompt(),
             };
         }
 
@@ -101,12 +145,18 @@ impl Command for BeamCommand {
                 _ => crate::model::structure::beam::BeamAnchor::Center,
             };
 
-            let beam_data = BeamData::new(start, end, type_id, name, anchor);
-            ctx.model.add_entity(Entity::beam(beam_data));
+            // Ends in a column stop at its face and follow it around. The
+            // next beam still chains from the clicked point.
+            let mut beam_data = BeamData::new(start, end, type_id, name, anchor);
+            beam_data.storey_id = ctx.model.storeys.active_id();
+            ctx.model.attach_beam(&mut beam_data);
+            ctx.add_entity(Entity::beam(beam_data));
 
-            // Chain placement like lines
+            if !self.chain {
+                self.points.clear();
+            }
             return PointResult::NeedMore {
-                prompt: "Specify next beam point:".to_string(),
+                prompt: self.prompt(),
             };
         }
 
@@ -117,6 +167,12 @@ impl Command for BeamCommand {
         &self.points
     }
 
+    fn reset_step(&mut self) -> bool {
+        let in_progress = !self.points.is_empty();
+        self.points.clear();
+        in_progress
+    }
+
     fn clone_box(&self) -> Box<dyn Command> {
         Box::new(self.clone())
     }
@@ -125,6 +181,7 @@ impl Command for BeamCommand {
         Some(self)
     }
 
+    #[cfg(feature = "gui")]
     fn draw_preview(
         &self,
         ctx: &crate::view::rendering::context::DrawContext,
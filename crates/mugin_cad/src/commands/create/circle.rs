@@ -1,8 +1,132 @@
+#[cfg(feature = "gui")]
 use crate::commands::preview;
-use crate::commands::{Command, CommandContext, InputResult, PointResult, parse_point};
-use crate::model::{Entity, Vector2};
+use crate::commands::{
+    Command, CommandContext, CommandError, CommandOption, DynamicInput, InputResult, PointResult,
+    parse_point,
+};
+use crate::model::math::{geometry, segment};
+use crate::model::{CadModel, Entity, Shape, Vector2};
 
-define_command!(CircleCommand);
+const TWO_POINT: CommandOption = CommandOption::new("2p", "2P");
+const THREE_POINT: CommandOption = CommandOption::new("3p", "3P");
+const TTR: CommandOption = CommandOption::new("ttr", "TTR");
+
+/// How the circle is specified
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircleMode {
+    /// Center point, then radius
+    CenterRadius,
+    /// Two ends of a diameter
+    TwoPoint,
+    /// Three points on the circumference
+    ThreePoint,
+    /// Tangent to two lines, with a given radius
+    TangentTangentRadius,
+}
+
+define_command!(CircleCommand,
+    mode: CircleMode = CircleMode::CenterRadius,
+    tangent_lines: Vec<(Vector2, Vector2)> = Vec::new()
+);
+
+impl CircleCommand {
+    fn first_prompt(&self) -> &'static str {
+        match self.mode {
+            CircleMode::CenterRadius => "CIRCLE Specify center point:",
+            CircleMode::TwoPoint => "CIRCLE Specify first end point of diameter:",
+            CircleMode::ThreePoint => "CIRCLE Specify first point on circle:",
+            CircleMode::TangentTangentRadius => "CIRCLE Specify point on first tangent line:",
+        }
+    }
+
+    fn set_mode(&mut self, mode: CircleMode) -> PointResult {
+        self.mode = mode;
+        PointResult::NeedMore {
+            prompt: self.first_prompt().to_string(),
+        }
+    }
+
+    fn need(prompt: &str) -> PointResult {
+        PointResult::NeedMore {
+            prompt: prompt.to_string(),
+        }
+    }
+
+    /// Add the circle, or refuse it if it is too small to keep and go back
+    /// to asking for the last point or radius
+    fn finish(&mut self, center: Vector2, radius: f32, ctx: &mut CommandContext) -> PointResult {
+        let circle = Entity::circle(center, radius, ctx.defaults.filled);
+        if let Err(error) = ctx.check(&circle) {
+            let (kept, prompt) = match self.mode {
+                CircleMode::CenterRadius => (1, "Specify radius point or enter radius:"),
+                CircleMode::TwoPoint => (1, "Specify second end point of diameter:"),
+                CircleMode::ThreePoint => (2, "Specify third point on circle:"),
+                CircleMode::TangentTangentRadius => (2, "Specify radius:"),
+            };
+            self.points.truncate(kept);
+            return PointResult::Rejected {
+                error,
+                prompt: prompt.to_string(),
+            };
+        }
+        ctx.add_entity(circle);
+        PointResult::Complete
+    }
+
+    /// Center of the circle of `radius` touching both picked lines, on the
+    /// side of their intersection where the lines were picked
+    fn tangent_center(&self, radius: f32) -> Option<Vector2> {
+        let [(a1, b1), (a2, b2)] = self.tangent_lines[..] else {
+            return None;
+        };
+        let corner = segment::line_intersection(a1, b1, a2, b2)?;
+
+        let toward = |a: Vector2, b: Vector2, pick: Vector2| {
+            let dir = (b - a).normalized();
+            if (pick - corner).dot(dir) < 0.0 {
+                dir * -1.0
+            } else {
+                dir
+            }
+        };
+        let d1 = toward(a1, b1, self.points[0]);
+        let d2 = toward(a2, b2, self.points[1]);
+
+        let half_sin = ((1.0 - d1.dot(d2)) / 2.0).sqrt();
+        if half_sin < 1e-6 {
+            return None;
+        }
+        Some(corner + (d1 + d2).normalized() * (radius / half_sin))
+    }
+
+    fn tangent_circle(&mut self, radius: f32, ctx: &mut CommandContext) -> PointResult {
+        match self.tangent_center(radius) {
+            Some(center) => self.finish(center, radius, ctx),
+            None => {
+                self.points.clear();
+                Self::need("Lines are parallel. Specify point on first tangent line:")
+            }
+        }
+    }
+
+    fn push_tangent_pick(&mut self, pos: Vector2, model: &CadModel) -> PointResult {
+        let Some(line) = nearest_line(model, pos) else {
+            return PointResult::Rejected {
+                error: CommandError::NothingThere { what: "line" },
+                prompt: "Specify point on tangent line:".to_string(),
+            };
+        };
+        self.tangent_lines.truncate(self.points.len());
+        self.tangent_lines.push(line);
+        self.points.push(pos);
+
+        if self.points.len() == 1 {
+            Self::need("Specify point on second tangent line:")
+        } else {
+            Self::need("Specify radius:")
+        }
+    }
+}
 
 impl Command for CircleCommand {
     fn name(&self) -> &'static str {
@@ -10,72 +134,177 @@ impl Command for CircleCommand {
     }
 
     fn initial_prompt(&self) -> String {
-        "CIRCLE Specify center point:".to_string()
+        self.first_prompt().to_string()
     }
 
-    fn push_point(&mut self, pos: Vector2, ctx: &mut CommandContext) -> PointResult {
-        self.points.push(pos);
-
-        if self.points.len() == 2 {
-            let center = self.points[0];
-            let radius = center.dist(self.points[1]);
-            ctx.model
-                .add_entity(Entity::circle(center, radius, ctx.filled_mode));
-            PointResult::Complete
+    fn options(&self) -> &[CommandOption] {
+        if self.points.is_empty() {
+            &[THREE_POINT, TWO_POINT, TTR]
         } else {
-            PointResult::NeedMore {
-                prompt: "Specify radius point or enter radius:".to_string(),
+            &[]
+        }
+    }
+
+    fn push_point(&mut self, pos: Vector2, ctx: &mut CommandContext) -> PointResult {
+        match self.mode {
+            CircleMode::CenterRadius => {
+                self.points.push(pos);
+                if self.points.len() == 2 {
+                    let center = self.points[0];
+                    self.finish(center, center.dist(pos), ctx)
+                } else {
+                    Self::need("Specify radius point or enter radius:")
+                }
+            }
+            CircleMode::TwoPoint => {
+                self.points.push(pos);
+                if self.points.len() == 2 {
+                    let start = self.points[0];
+                    self.finish((start + pos) * 0.5, start.dist(pos) / 2.0, ctx)
+                } else {
+                    Self::need("Specify second end point of diameter:")
+                }
+            }
+            CircleMode::ThreePoint => {
+                self.points.push(pos);
+                match self.points[..] {
+                    [a, b, c] => match geometry::circle_through_points(a, b, c) {
+                        Some((center, radius)) => self.finish(center, radius, ctx),
+                        None => {
+                            self.points.pop();
+                            Self::need("Points are collinear. Specify third point on circle:")
+                        }
+                    },
+                    [_, _] => Self::need("Specify third point on circle:"),
+                    _ => Self::need("Specify second point on circle:"),
+                }
+            }
+            CircleMode::TangentTangentRadius => {
+                if self.points.len() < 2 {
+                    self.push_tangent_pick(pos, ctx.model)
+                } else {
+                    // A third point gives the radius as its distance from the second pick
+                    let radius = self.points[1].dist(pos);
+                    self.tangent_circle(radius, ctx)
+                }
             }
         }
     }
 
     fn process_input(&mut self, input: &str, ctx: &mut CommandContext) -> InputResult {
+        if self.points.is_empty()
+            && let Some((_, mode)) = [
+                (TWO_POINT, CircleMode::TwoPoint),
+                (THREE_POINT, CircleMode::ThreePoint),
+                (TTR, CircleMode::TangentTangentRadius),
+            ]
+            .into_iter()
+            .find(|(option, _)| option.key == input)
+        {
+            return InputResult::Parameter(self.set_mode(mode));
+        }
+
         // Try to parse as point first
         if let Some(pos) = parse_point(input) {
             return InputResult::Point(self.push_point(pos, ctx));
         }
 
-        // If we have center, try to parse as radius
-        if self.points.len() == 1 {
-            if let Ok(radius) = input.parse::<f32>() {
-                if radius > 0.0 {
-                    let center = self.points[0];
-                    ctx.model
-                        .add_entity(Entity::circle(center, radius, ctx.filled_mode));
-                    return InputResult::Parameter(PointResult::Complete);
+        // Once the center (or both tangents) are known, accept a typed radius
+        if let Ok(radius) = input.parse::<f32>()
+            && radius > 0.0
+        {
+            match (self.mode, self.points.len()) {
+                (CircleMode::CenterRadius, 1) => {
+                    return InputResult::Parameter(self.finish(self.points[0], radius, ctx));
+                }
+                (CircleMode::TangentTangentRadius, 2) => {
+                    return InputResult::Parameter(self.tangent_circle(radius, ctx));
                 }
+                _ => {}
             }
         }
 
-        InputResult::Invalid {
-            message: format!("Invalid input \"{}\".", input),
-        }
+        InputResult::Invalid(CommandError::invalid_input(input))
     }
 
+    #[cfg(feature = "gui")]
     fn draw_preview(
         &self,
         ctx: &crate::view::rendering::context::DrawContext,
         points: &[Vector2],
         current_cad: Vector2,
     ) {
-        if let Some(&center) = points.first() {
-            let cad_radius = center.dist(current_cad);
-            let screen_radius = cad_radius * ctx.zoom;
-
-            // Draw circle
+        let draw_circle = |center: Vector2, radius: f32| {
             ctx.painter.circle_stroke(
                 ctx.to_screen(center),
-                screen_radius,
+                radius * ctx.zoom,
                 preview::preview_stroke(),
             );
+        };
 
-            // Draw radius line
-            preview::draw_line_to_cursor(ctx, center, current_cad);
+        match (self.mode, points) {
+            (CircleMode::CenterRadius, [center, ..]) => {
+                draw_circle(*center, center.dist(current_cad));
+                // Draw radius line
+                preview::draw_line_to_cursor(ctx, *center, current_cad);
+                preview::draw_center_marker(ctx, *center);
+            }
+            (CircleMode::TwoPoint, [start, ..]) => {
+                let center = (*start + current_cad) * 0.5;
+                draw_circle(center, start.dist(current_cad) / 2.0);
+                preview::draw_line_to_cursor(ctx, *start, current_cad);
+                preview::draw_center_marker(ctx, center);
+            }
+            (CircleMode::ThreePoint, [a]) => {
+                preview::draw_line_to_cursor(ctx, *a, current_cad);
+            }
+            (CircleMode::ThreePoint, [a, b, ..]) => {
+                if let Some((center, radius)) = geometry::circle_through_points(*a, *b, current_cad)
+                {
+                    draw_circle(center, radius);
+                    preview::draw_center_marker(ctx, center);
+                }
+            }
+            (CircleMode::TangentTangentRadius, picks) => {
+                for &pick in picks {
+                    preview::draw_point_marker(ctx, pick, eframe::egui::Color32::YELLOW);
+                }
+                if let [_, second] = picks
+                    && let Some(center) = self.tangent_center(second.dist(current_cad))
+                {
+                    draw_circle(center, second.dist(current_cad));
+                }
+            }
+            _ => {}
+        }
+    }
 
-            // Draw center marker
-            preview::draw_center_marker(ctx, center);
+    fn dynamic_input(&self) -> Option<DynamicInput> {
+        match (self.mode, &self.points[..]) {
+            (CircleMode::CenterRadius, &[center]) => Some(DynamicInput::Radius { center }),
+            _ => None,
         }
     }
 
     impl_command_common!(CircleCommand);
 }
+
+/// World-space end points of the top-level line closest to `pos`
+fn nearest_line(model: &CadModel, pos: Vector2) -> Option<(Vector2, Vector2)> {
+    let tolerance = 10.0;
+    model
+        .entities
+        .iter()
+        .filter_map(|entity| match &entity.shape {
+            Shape::Line(line) => {
+                let to_world =
+                    |p: Vector2| entity.world_transform.transform_point2(p.into()).into();
+                Some((to_world(line.start), to_world(line.end)))
+            }
+            _ => None,
+        })
+        .map(|(start, end)| (segment::distance(pos, start, end), start, end))
+        .filter(|(dist, _, _)| *dist < tolerance)
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, start, end)| (start, end))
+}
@@ -0,0 +1,115 @@
+#[cfg(feature = "gui")]
+use crate::commands::preview;
+use crate::commands::{
+    Command, CommandCategory, CommandContext, CommandError, CommandOption, InputResult,
+    PointResult, parse_point,
+};
+use crate::model::{Entity, Flooring, Vector2};
+
+const CLOSE: CommandOption = CommandOption::new("c", "Close");
+
+define_command!(FlooringCommand, thickness: f32 = 15.0);
+
+impl FlooringCommand {
+    fn prompt(&self) -> String {
+        match self.points.len() {
+            0 => format!(
+                "FLOORING Specify first corner or enter thickness <{}>:",
+                self.thickness
+            ),
+            1 | 2 => "Specify next corner:".to_string(),
+            _ => "Specify next corner or close at the first corner:".to_string(),
+        }
+    }
+
+    fn need(&self) -> PointResult {
+        PointResult::NeedMore {
+            prompt: self.prompt(),
+        }
+    }
+
+    fn close(&mut self, ctx: &mut CommandContext) -> PointResult {
+        let mut floor = Flooring::new(std::mem::take(&mut self.points), 1, self.thickness);
+        floor.storey_id = ctx.model.storeys.active_id();
+        ctx.add_entity(Entity::flooring(floor));
+        PointResult::Complete
+    }
+}
+
+impl Command for FlooringCommand {
+    fn name(&self) -> &'static str {
+        "FLOORING"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::Creation
+    }
+
+    fn initial_prompt(&self) -> String {
+        self.prompt()
+    }
+
+    fn options(&self) -> &[CommandOption] {
+        if self.points.len() >= 3 {
+            &[CLOSE]
+        } else {
+            &[]
+        }
+    }
+
+    fn process_input(&mut self, input: &str, ctx: &mut CommandContext) -> InputResult {
+        if input == CLOSE.key && self.points.len() >= 3 {
+            return InputResult::Parameter(self.close(ctx));
+        }
+        if let Some(pos) = parse_point(input) {
+            return InputResult::Point(self.push_point(pos, ctx));
+        }
+        if self.points.is_empty()
+            && let Ok(thickness) = input.parse::<f32>()
+            && thickness > 0.0
+        {
+            self.thickness = thickness;
+            return InputResult::Parameter(self.need());
+        }
+        InputResult::Invalid(CommandError::invalid_input(input))
+    }
+
+    fn push_point(&mut self, pos: Vector2, ctx: &mut CommandContext) -> PointResult {
+        let pos = match self.points.last() {
+            Some(&last) => self.constrain_point(pos, Some(last), ctx.modifiers),
+            None => pos,
+        };
+        // Picking the first corner again closes the outline
+        if let Some(&first) = self.points.first()
+            && first.dist(pos) <= f32::EPSILON
+        {
+            return if self.points.len() >= 3 {
+                self.close(ctx)
+            } else {
+                self.need()
+            };
+        }
+        self.points.push(pos);
+        self.need()
+    }
+
+    #[cfg(feature = "gui")]
+    fn draw_preview(
+        &self,
+        ctx: &crate::view::rendering::context::DrawContext,
+        points: &[Vector2],
+        current_cad: Vector2,
+    ) {
+        for pair in points.windows(2) {
+            preview::draw_line_to_cursor(ctx, pair[0], pair[1]);
+        }
+        if let (Some(&first), Some(&last)) = (points.first(), points.last()) {
+            preview::draw_line_to_cursor(ctx, last, current_cad);
+            if points.len() >= 2 {
+                preview::draw_line_to_cursor(ctx, current_cad, first);
+            }
+        }
+    }
+
+    impl_command_common!(FlooringCommand);
+}
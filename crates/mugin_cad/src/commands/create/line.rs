@@ -1,8 +1,71 @@
+use crate::commands::output::TerminalLine;
+#[cfg(feature = "gui")]
 use crate::commands::preview;
-use crate::commands::{Command, CommandContext, PointResult};
-use crate::model::{Entity, Vector2};
+use crate::commands::{
+    Command, CommandContext, CommandError, CommandOption, DynamicInput, InputResult, PointResult,
+    parse_point,
+};
+use crate::model::{Entity, Shape, Vector2};
+use std::collections::HashSet;
 
-define_command!(LineCommand);
+const UNDO: CommandOption = CommandOption::new("u", "Undo");
+const CLOSE: CommandOption = CommandOption::new("c", "Close");
+const NEXT_POINT: &str = "Specify next point (Shift for ortho):";
+
+define_command!(LineCommand, segment_ids: Vec<u64> = Vec::new());
+
+impl LineCommand {
+    fn next_point_prompt() -> PointResult {
+        PointResult::NeedMore {
+            prompt: NEXT_POINT.to_string(),
+        }
+    }
+
+    fn rejected(error: CommandError) -> PointResult {
+        PointResult::Rejected {
+            error,
+            prompt: NEXT_POINT.to_string(),
+        }
+    }
+
+    /// Add the segment, or refuse it if it is too short to keep
+    fn add_segment(
+        &mut self,
+        start: Vector2,
+        end: Vector2,
+        ctx: &mut CommandContext,
+    ) -> Result<(), CommandError> {
+        let mut entity = Entity::line(start, end);
+        ctx.check(&entity)?;
+        entity.modify(|shape| {
+            if let Shape::Line(line) = shape {
+                line.show_length = ctx.defaults.show_line_labels;
+            }
+        });
+        self.segment_ids.push(ctx.add_entity(entity));
+        Ok(())
+    }
+
+    /// Remove the last drawn segment and step back to its start point
+    fn undo_segment(&mut self, ctx: &mut CommandContext) -> PointResult {
+        if let Some(id) = self.segment_ids.pop() {
+            ctx.model.remove_entities_by_ids(&HashSet::from([id]));
+        }
+        self.points.pop();
+        ctx.report(TerminalLine::info("Last segment removed."));
+        Self::next_point_prompt()
+    }
+
+    /// Join the last point back to the first and finish
+    fn close(&mut self, ctx: &mut CommandContext) -> PointResult {
+        if let (Some(&first), Some(&last)) = (self.points.first(), self.points.last())
+            && let Err(error) = self.add_segment(last, first, ctx)
+        {
+            return Self::rejected(error);
+        }
+        PointResult::Complete
+    }
+}
 
 impl Command for LineCommand {
     fn name(&self) -> &'static str {
@@ -13,6 +76,14 @@ impl Command for LineCommand {
         "LINE Specify first point:".to_string()
     }
 
+    fn options(&self) -> &[CommandOption] {
+        match self.points.len() {
+            0 | 1 => &[],
+            2 => &[UNDO],
+            _ => &[CLOSE, UNDO],
+        }
+    }
+
     fn push_point(&mut self, pos: Vector2, ctx: &mut CommandContext) -> PointResult {
         // Apply ortho constraint if shift is pressed
         let constrained_pos = if let Some(&last) = self.points.last() {
@@ -21,19 +92,31 @@ impl Command for LineCommand {
             pos
         };
 
+        if let Some(&start) = self.points.last()
+            && let Err(error) = self.add_segment(start, constrained_pos, ctx)
+        {
+            return Self::rejected(error);
+        }
         self.points.push(constrained_pos);
 
-        if self.points.len() >= 2 {
-            let start = self.points[self.points.len() - 2];
-            let end = self.points[self.points.len() - 1];
-            ctx.model.add_entity(Entity::line(start, end));
+        Self::next_point_prompt()
+    }
+
+    fn process_input(&mut self, input: &str, ctx: &mut CommandContext) -> InputResult {
+        if input == UNDO.key && self.options().contains(&UNDO) {
+            return InputResult::Parameter(self.undo_segment(ctx));
+        }
+        if input == CLOSE.key && self.options().contains(&CLOSE) {
+            return InputResult::Parameter(self.close(ctx));
         }
 
-        PointResult::NeedMore {
-            prompt: "Specify next point (Shift for ortho):".to_string(),
+        if let Some(pos) = parse_point(input) {
+            return InputResult::Point(self.push_point(pos, ctx));
         }
+        InputResult::Invalid(CommandError::invalid_input(input))
     }
 
+    #[cfg(feature = "gui")]
     fn draw_preview(
         &self,
         ctx: &crate::view::rendering::context::DrawContext,
@@ -45,5 +128,24 @@ impl Command for LineCommand {
         }
     }
 
-    impl_command_common!(LineCommand);
+    fn dynamic_input(&self) -> Option<DynamicInput> {
+        let &base = self.points.last()?;
+        Some(DynamicInput::Polar { base })
+    }
+
+    fn get_points(&self) -> &[Vector2] {
+        &self.points
+    }
+
+    /// Start a new chain; segments already drawn are no longer undone by "u"
+    fn reset_step(&mut self) -> bool {
+        let in_progress = !self.points.is_empty();
+        self.points.clear();
+        self.segment_ids.clear();
+        in_progress
+    }
+
+    fn clone_box(&self) -> Box<dyn Command> {
+        Box::new(self.clone())
+    }
 }
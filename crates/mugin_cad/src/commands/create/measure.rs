@@ -1,4 +1,8 @@
-use crate::commands::{Command, CommandCategory, CommandContext, InputResult, PointResult};
+use crate::commands::measure::{Measurement, MeasurementKind};
+use crate::commands::output::TerminalLine;
+use crate::commands::{
+    Command, CommandCategory, CommandContext, CommandError, InputResult, PointResult,
+};
 use crate::model::shapes::annotation::TextAnnotation;
 use crate::model::{Entity, Vector2};
 
@@ -31,7 +35,16 @@ impl Command for MeasureCommand {
         let end = self.points[1];
 
         let annotation = TextAnnotation::new_distance(start, end);
-        ctx.model.add_entity(Entity::text(annotation));
+        ctx.report(TerminalLine::result(format!(
+            "Distance: {:.2}",
+            start.dist(end)
+        )));
+        ctx.add_entity(Entity::text(annotation));
+        *ctx.last_measurement = Some(Measurement {
+            kind: MeasurementKind::Distance,
+            value: start.dist(end),
+            target: (start + end) * 0.5,
+        });
 
         PointResult::Complete
     }
@@ -40,9 +53,10 @@ impl Command for MeasureCommand {
         if let Some(pos) = crate::commands::parse_point(input) {
             InputResult::Point(self.push_point(pos, ctx))
         } else {
-            InputResult::Invalid {
-                message: "Please specify a point or click.".to_string(),
-            }
+            InputResult::Invalid(CommandError::InvalidInput {
+                input: input.to_string(),
+                hint: Some("Specify a point or click."),
+            })
         }
     }
 
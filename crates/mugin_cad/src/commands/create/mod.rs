@@ -1,9 +1,16 @@
 pub mod arc;
+pub mod autodim;
 pub mod axis;
 pub mod beam;
 pub mod circle;
+pub mod flooring;
 pub mod line;
 pub mod measure;
+pub mod opening;
 pub mod place_column;
 pub mod rectangle;
+pub mod revision_cloud;
+pub mod section;
+pub mod stair;
+pub mod symbol;
 pub mod text;
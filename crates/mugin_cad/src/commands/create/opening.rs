@@ -0,0 +1,133 @@
+use crate::commands::output::TerminalLine;
+#[cfg(feature = "gui")]
+use crate::commands::preview;
+use crate::commands::{
+    Command, CommandCategory, CommandContext, CommandError, CommandOption, InputResult,
+    PointResult, parse_point,
+};
+use crate::model::Vector2;
+
+const CLOSE: CommandOption = CommandOption::new("c", "Close");
+const RECTANGLE: CommandOption = CommandOption::new("r", "Rectangle");
+
+define_command!(OpeningCommand, rectangle: bool = false);
+
+/// Corners of the rectangle spanned by two opposite corners
+fn rectangle_corners(a: Vector2, b: Vector2) -> Vec<Vector2> {
+    vec![a, Vector2::new(b.x, a.y), b, Vector2::new(a.x, b.y)]
+}
+
+impl OpeningCommand {
+    fn prompt(&self) -> String {
+        match (self.rectangle, self.points.len()) {
+            (true, 0) => "OPENING Specify first corner of rectangle:".to_string(),
+            (true, _) => "Specify opposite corner:".to_string(),
+            (false, 0) => "OPENING Specify first corner of opening (R: Rectangle):".to_string(),
+            (false, 1 | 2) => "Specify next corner:".to_string(),
+            (false, _) => "Specify next corner or close at the first corner:".to_string(),
+        }
+    }
+
+    fn need(&self) -> PointResult {
+        PointResult::NeedMore {
+            prompt: self.prompt(),
+        }
+    }
+
+    /// Cut the outline out of the slab it lies in, or start over
+    fn cut(&mut self, hole: Vec<Vector2>, ctx: &mut CommandContext) -> PointResult {
+        self.points.clear();
+        if let Some(id) = ctx.model.cut_opening(&hole) {
+            ctx.report(TerminalLine::info(format!(
+                "Opening cut in flooring #{}",
+                id
+            )));
+            return PointResult::Complete;
+        }
+        ctx.report(TerminalLine::warning(
+            "The opening must lie inside a slab, clear of its other openings",
+        ));
+        self.need()
+    }
+}
+
+impl Command for OpeningCommand {
+    fn name(&self) -> &'static str {
+        "OPENING"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::Creation
+    }
+
+    fn initial_prompt(&self) -> String {
+        self.prompt()
+    }
+
+    fn options(&self) -> &[CommandOption] {
+        match (self.rectangle, self.points.len()) {
+            (false, 0) => &[RECTANGLE],
+            (false, 3..) => &[CLOSE],
+            _ => &[],
+        }
+    }
+
+    fn process_input(&mut self, input: &str, ctx: &mut CommandContext) -> InputResult {
+        if input == RECTANGLE.key && self.points.is_empty() {
+            self.rectangle = true;
+            return InputResult::Parameter(self.need());
+        }
+        if input == CLOSE.key && !self.rectangle && self.points.len() >= 3 {
+            let hole = self.points.clone();
+            return InputResult::Parameter(self.cut(hole, ctx));
+        }
+        match parse_point(input) {
+            Some(pos) => InputResult::Point(self.push_point(pos, ctx)),
+            None => InputResult::Invalid(CommandError::invalid_input(input)),
+        }
+    }
+
+    fn push_point(&mut self, pos: Vector2, ctx: &mut CommandContext) -> PointResult {
+        let pos = match self.points.last() {
+            Some(&last) => self.constrain_point(pos, Some(last), ctx.modifiers),
+            None => pos,
+        };
+        let Some(&first) = self.points.first() else {
+            self.points.push(pos);
+            return self.need();
+        };
+        if self.rectangle {
+            return self.cut(rectangle_corners(first, pos), ctx);
+        }
+        // Picking the first corner again closes the outline
+        if first.dist(pos) <= f32::EPSILON {
+            if self.points.len() < 3 {
+                return self.need();
+            }
+            let hole = self.points.clone();
+            return self.cut(hole, ctx);
+        }
+        self.points.push(pos);
+        self.need()
+    }
+
+    #[cfg(feature = "gui")]
+    fn draw_preview(
+        &self,
+        ctx: &crate::view::rendering::context::DrawContext,
+        points: &[Vector2],
+        current_cad: Vector2,
+    ) {
+        let outline = match points {
+            [] => return,
+            [first] if self.rectangle => rectangle_corners(*first, current_cad),
+            _ => points.iter().copied().chain([current_cad]).collect(),
+        };
+        for (i, &a) in outline.iter().enumerate() {
+            let b = outline[(i + 1) % outline.len()];
+            preview::draw_line_to_cursor(ctx, a, b);
+        }
+    }
+
+    impl_command_common!(OpeningCommand);
+}
@@ -1,33 +1,43 @@
-use crate::commands::{Command, CommandCategory, CommandContext, PointResult};
-use crate::model::structure::column::ColumnData;
+use crate::commands::{
+    Command, CommandCategory, CommandContext, CommandError, CommandOption, InputModifiers,
+    InputResult, PointResult, parse_point,
+};
+use crate::model::axis::{Axis, AxisOrientation};
+use crate::model::structure::column::{ColumnAnchor, ColumnData};
 use crate::model::structure::column_type::ColumnType;
 use crate::model::{Entity, Vector2};
 use std::any::Any;
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
 
 #[derive(Debug, Clone)]
 pub struct CmdPlaceColumn {
+    /// Insertion point, once picked; the next point sets the rotation
     points: Vec<Vector2>,
-    /// 0=Center, 1=TL, 2=TR, 3=BR, 4=BL
-    current_anchor_index: usize,
+    /// Point of the column that lands on the picked point
+    anchor: ColumnAnchor,
+    /// Rotation shown before the insertion point is picked
     rotation: f32,
     active_column_type_id: Option<u64>,
     /// We cache the column definition for preview rendering
     cached_col_type: Option<ColumnType>,
+    /// Construction axes, for face-snapping the rotation
+    axes: Vec<Axis>,
 }
 
 impl CmdPlaceColumn {
     pub fn new() -> Self {
         Self {
             points: Vec::new(),
-            current_anchor_index: 0,
+            anchor: ColumnAnchor::Center,
             rotation: 0.0,
             active_column_type_id: None,
             cached_col_type: None,
+            axes: Vec::new(),
         }
     }
 
     pub fn cycle_anchor(&mut self) {
-        self.current_anchor_index = (self.current_anchor_index + 1) % 5;
+        self.anchor = self.anchor.next();
     }
 
     pub fn rotate_cw(&mut self) {
@@ -35,6 +45,108 @@ impl CmdPlaceColumn {
     }
 }
 
+const ANCHOR: CommandOption = CommandOption::new("a", "Anchor");
+
+/// Columns need a type to place; the column manager defines them
+fn no_column_types(prompt: String) -> PointResult {
+    PointResult::Rejected {
+        error: CommandError::MissingPrerequisite("No column types defined.".to_string()),
+        prompt,
+    }
+}
+
+impl CmdPlaceColumn {
+    fn prompt(&self) -> String {
+        if self.points.is_empty() {
+            format!(
+                "Specify insertion point, anchor {} (Q: Anchor, E: Rotate):",
+                self.anchor.label()
+            )
+        } else {
+            "Specify rotation or angle (Shift: free rotation):".to_string()
+        }
+    }
+
+    /// Column of the cached type with its anchor on `pos`
+    fn column_at(
+        &self,
+        type_id: u64,
+        col_type: &ColumnType,
+        pos: Vector2,
+        rotation: f32,
+    ) -> ColumnData {
+        let mut col_data = ColumnData::new(
+            pos,
+            col_type.width,
+            col_type.depth,
+            type_id,
+            col_type.name.clone(),
+            self.anchor,
+        );
+        col_data.rotation = rotation;
+        col_data.place_anchor_at(pos);
+        col_data
+    }
+
+    /// Rotation that turns the column's width toward `cursor`. Snaps to
+    /// quarter turns, or to an axis' direction while the cursor is within
+    /// a column size of that axis, unless `free`.
+    fn rotation_toward(&self, base: Vector2, cursor: Vector2, free: bool) -> f32 {
+        let delta = cursor - base;
+        if delta.length() < f32::EPSILON {
+            return self.rotation;
+        }
+        let angle = delta.y.atan2(delta.x);
+        if free {
+            return angle;
+        }
+
+        let capture = self
+            .cached_col_type
+            .as_ref()
+            .map_or(0.0, |col| col.width.max(col.depth));
+        let mut near_horizontal = false;
+        let mut near_vertical = false;
+        for axis in &self.axes {
+            match axis.orientation {
+                AxisOrientation::Horizontal => {
+                    near_horizontal |= (cursor.y - axis.position).abs() <= capture
+                }
+                AxisOrientation::Vertical => {
+                    near_vertical |= (cursor.x - axis.position).abs() <= capture
+                }
+            }
+        }
+        let step = match (near_horizontal, near_vertical) {
+            // Along the axis the cursor is on, either way
+            (true, false) => (angle / PI).round() * PI,
+            (false, true) => ((angle - FRAC_PI_2) / PI).round() * PI + FRAC_PI_2,
+            _ => (angle / FRAC_PI_2).round() * FRAC_PI_2,
+        };
+        step.rem_euclid(TAU)
+    }
+
+    fn place(&mut self, rotation: f32, ctx: &mut CommandContext) -> PointResult {
+        let Some(base) = self.points.pop() else {
+            return PointResult::NeedMore {
+                prompt: self.prompt(),
+            };
+        };
+        let (Some(type_id), Some(col_type)) = (self.active_column_type_id, &self.cached_col_type)
+        else {
+            return no_column_types(self.prompt());
+        };
+        let mut col_data = self.column_at(type_id, col_type, base, rotation);
+        col_data.storey_id = ctx.model.storeys.active_id();
+        ctx.add_entity(Entity::column(col_data));
+        // The next column starts out turned like this one
+        self.rotation = rotation;
+        PointResult::NeedMore {
+            prompt: self.prompt(),
+        }
+    }
+}
+
 impl Command for CmdPlaceColumn {
     fn name(&self) -> &'static str {
         "Place Column"
@@ -45,13 +157,15 @@ impl Command for CmdPlaceColumn {
     }
 
     fn on_start(&mut self, ctx: &CommandContext) {
+        self.anchor = ctx.defaults.column_anchor;
+        self.axes = ctx.model.axis_manager.axes.clone();
         if self.cached_col_type.is_none() {
-            if let Some(id) = ctx.active_column_type_id {
-                if let Some(col) = ctx.model.definitions.column_types.get(&id) {
-                    self.active_column_type_id = Some(id);
-                    self.cached_col_type = Some(col.clone());
-                    return;
-                }
+            if let Some(id) = ctx.active_column_type_id
+                && let Some(col) = ctx.model.definitions.column_types.get(&id)
+            {
+                self.active_column_type_id = Some(id);
+                self.cached_col_type = Some(col.clone());
+                return;
             }
 
             if let Some((id, col)) = ctx.model.definitions.column_types.iter().next() {
@@ -62,71 +176,71 @@ impl Command for CmdPlaceColumn {
     }
 
     fn initial_prompt(&self) -> String {
-        "Specify insertion point (Q: Anchor, E: Rotate):".to_string()
+        self.prompt()
     }
 
-    fn push_point(&mut self, pos: Vector2, ctx: &mut CommandContext) -> PointResult {
-        let (type_id, col_type) =
-            if let (Some(id), Some(col)) = (self.active_column_type_id, &self.cached_col_type) {
-                (id, col)
-            } else {
-                if let Some((id, col)) = ctx.model.definitions.column_types.iter().next() {
-                    self.active_column_type_id = Some(*id);
-                    self.cached_col_type = Some(col.clone());
-                    (*id, col)
-                } else {
-                    return PointResult::NeedMore {
-                        prompt: "No column types defined!".to_string(),
-                    };
-                }
-            };
-
-        let width = col_type.width;
-        let depth = col_type.depth;
-
-        let half_w = width / 2.0;
-        let half_h = depth / 2.0;
-
-        let (sin, cos) = self.rotation.sin_cos();
+    fn options(&self) -> &[CommandOption] {
+        &[ANCHOR]
+    }
 
-        let local_anchor = match self.current_anchor_index {
-            1 => Vector2::new(-half_w, -half_h), // TL
-            2 => Vector2::new(half_w, -half_h),  // TR
-            3 => Vector2::new(half_w, half_h),   // BR
-            4 => Vector2::new(-half_w, half_h),  // BL
-            _ => Vector2::new(0.0, 0.0),         // Center
-        };
+    fn process_input(&mut self, input: &str, ctx: &mut CommandContext) -> InputResult {
+        if input == ANCHOR.key {
+            self.cycle_anchor();
+            return InputResult::Parameter(PointResult::NeedMore {
+                prompt: self.prompt(),
+            });
+        }
+        // A bare angle at the rotation step is the rotation
+        if !self.points.is_empty()
+            && let Some(angle) = ctx.dimension_style.angles.parse_rotation(input)
+        {
+            return InputResult::Parameter(self.place(angle, ctx));
+        }
+        match parse_point(input) {
+            Some(pos) => InputResult::Point(self.push_point(pos, ctx)),
+            None => InputResult::Invalid(CommandError::invalid_input(input)),
+        }
+    }
 
-        let rotated_anchor = Vector2::new(
-            local_anchor.x * cos - local_anchor.y * sin,
-            local_anchor.x * sin + local_anchor.y * cos,
-        );
+    fn push_point(&mut self, pos: Vector2, ctx: &mut CommandContext) -> PointResult {
+        if self.cached_col_type.is_none() {
+            let Some((id, col)) = ctx.model.definitions.column_types.iter().next() else {
+                return no_column_types(self.prompt());
+            };
+            self.active_column_type_id = Some(*id);
+            self.cached_col_type = Some(col.clone());
+        }
 
-        let final_center = pos - rotated_anchor;
+        match self.points.last() {
+            None => {
+                self.points.push(pos);
+                PointResult::NeedMore {
+                    prompt: self.prompt(),
+                }
+            }
+            Some(&base) => {
+                let rotation = self.rotation_toward(base, pos, ctx.modifiers.shift);
+                self.place(rotation, ctx)
+            }
+        }
+    }
 
-        let anchor_enum = match self.current_anchor_index {
-            1 => crate::model::structure::column::ColumnAnchor::TopLeft,
-            2 => crate::model::structure::column::ColumnAnchor::TopRight,
-            3 => crate::model::structure::column::ColumnAnchor::BottomRight,
-            4 => crate::model::structure::column::ColumnAnchor::BottomLeft,
-            _ => crate::model::structure::column::ColumnAnchor::Center,
+    fn constrain_point(
+        &self,
+        pos: Vector2,
+        last_point: Option<Vector2>,
+        modifiers: InputModifiers,
+    ) -> Vector2 {
+        // Keep the distance, turn the direction to the snapped rotation
+        let Some(base) = last_point else {
+            return pos;
         };
+        let rotation = self.rotation_toward(base, pos, modifiers.shift);
+        base + Vector2::new(rotation.cos(), rotation.sin()) * (pos - base).length()
+    }
 
-        let mut col_data = ColumnData::new(
-            final_center,
-            width,
-            depth,
-            type_id,
-            col_type.name.clone(),
-            anchor_enum,
-        );
-        col_data.rotation = self.rotation;
-
-        ctx.model.add_entity(Entity::column(col_data));
-
-        PointResult::NeedMore {
-            prompt: "Specify insertion point (Q: Anchor, E: Rotate):".to_string(),
-        }
+    fn reset_step(&mut self) -> bool {
+        self.points.pop().is_some()
     }
 
     fn get_points(&self) -> &[Vector2] {
@@ -141,46 +255,36 @@ impl Command for CmdPlaceColumn {
         Some(self)
     }
 
+    #[cfg(feature = "gui")]
     fn draw_preview(
         &self,
         ctx: &crate::view::rendering::context::DrawContext,
         _points: &[Vector2],
         cursor_pos_cad: Vector2,
     ) {
-        if let Some(col_type) = &self.cached_col_type {
-            let width = col_type.width;
-            let depth = col_type.depth;
-            let half_w = width / 2.0;
-            let half_h = depth / 2.0;
-
-            let (sin, cos) = self.rotation.sin_cos();
-
-            let local_anchor = match self.current_anchor_index {
-                1 => Vector2::new(-half_w, -half_h),
-                2 => Vector2::new(half_w, -half_h),
-                3 => Vector2::new(half_w, half_h),
-                4 => Vector2::new(-half_w, half_h),
-                _ => Vector2::new(0.0, 0.0),
-            };
-
-            let rotated_anchor = Vector2::new(
-                local_anchor.x * cos - local_anchor.y * sin,
-                local_anchor.x * sin + local_anchor.y * cos,
-            );
-
-            let center_cad = cursor_pos_cad - rotated_anchor;
-            let center_screen = ctx.to_screen(center_cad);
+        let Some(col_type) = &self.cached_col_type else {
+            return;
+        };
+        let (base, rotation) = match self.points.last() {
+            // The cursor is already constrained to the snapped direction
+            Some(&base) => (base, self.rotation_toward(base, cursor_pos_cad, true)),
+            None => (cursor_pos_cad, self.rotation),
+        };
+        let center_cad = self.column_at(0, col_type, base, rotation).center;
+        let center_screen = ctx.to_screen(center_cad);
 
-            let scale = ctx.zoom;
+        // Screen Y points down, so the turn is mirrored
+        crate::view::rendering::structure::draw_column(
+            ctx.painter,
+            center_screen,
+            -rotation,
+            ctx.zoom,
+            col_type,
+            0.5, // Alpha
+        );
 
-            crate::view::rendering::structure::draw_column(
-                ctx.painter,
-                center_screen,
-                self.rotation,
-                scale,
-                col_type,
-                0.5, // Alpha
-            );
+        if let Some(&base) = self.points.last() {
+            crate::commands::preview::draw_line_to_cursor(ctx, base, cursor_pos_cad);
         }
     }
 }
@@ -1,8 +1,81 @@
+#[cfg(feature = "gui")]
 use crate::commands::preview;
-use crate::commands::{Command, CommandContext, PointResult};
+use crate::commands::{
+    Command, CommandContext, CommandError, CommandOption, DynamicInput, InputResult, PointResult,
+    parse_point,
+};
 use crate::model::{Entity, Vector2};
 
-define_command!(RectangleCommand);
+const CENTER: CommandOption = CommandOption::new("c", "Center");
+const THREE_POINT: CommandOption = CommandOption::new("3p", "3P");
+
+/// How the rectangle is specified
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RectangleMode {
+    /// Two opposite corners
+    Corners,
+    /// Center point, then a corner
+    Center,
+    /// One edge by its two ends, then a point setting the height (may be rotated)
+    ThreePoint,
+}
+
+define_command!(RectangleCommand, mode: RectangleMode = RectangleMode::Corners);
+
+impl RectangleCommand {
+    fn first_prompt(&self) -> &'static str {
+        match self.mode {
+            RectangleMode::Corners => "RECTANGLE Specify first corner:",
+            RectangleMode::Center => "RECTANGLE Specify center point:",
+            RectangleMode::ThreePoint => "RECTANGLE Specify start of first edge:",
+        }
+    }
+
+    /// Opposite corners of the axis-aligned rectangle from the first point to `pos`
+    fn corners(&self, first: Vector2, pos: Vector2) -> (Vector2, Vector2) {
+        let (p1, p2) = match self.mode {
+            RectangleMode::Center => (first * 2.0 - pos, pos),
+            _ => (first, pos),
+        };
+        (
+            Vector2::new(p1.x.min(p2.x), p1.y.min(p2.y)),
+            Vector2::new(p1.x.max(p2.x), p1.y.max(p2.y)),
+        )
+    }
+
+    /// Add the rectangle, or refuse it if it is too thin to keep and go
+    /// back to the pick that made it so
+    fn finish(&mut self, entity: Entity, ctx: &mut CommandContext) -> PointResult {
+        let Err(error) = ctx.check(&entity) else {
+            ctx.add_entity(entity);
+            return PointResult::Complete;
+        };
+        let edge_too_short = matches!(
+            &error,
+            CommandError::Degenerate(problem) if problem.what == "Rectangle width"
+        );
+        let (kept, prompt) = match self.mode {
+            RectangleMode::ThreePoint if edge_too_short => (1, "Specify end of first edge:"),
+            RectangleMode::ThreePoint => (2, "Specify height point:"),
+            _ => (1, "Specify other corner:"),
+        };
+        self.points.truncate(kept);
+        PointResult::Rejected {
+            error,
+            prompt: prompt.to_string(),
+        }
+    }
+
+    /// Four corners of the rectangle with edge `a`-`b`, extended to `pos`'s
+    /// side by its distance from that edge
+    #[cfg(feature = "gui")]
+    fn three_point_corners(a: Vector2, b: Vector2, pos: Vector2) -> [Vector2; 4] {
+        let dir = (b - a).normalized();
+        let normal = Vector2::new(-dir.y, dir.x);
+        let offset = normal * (pos - a).dot(normal);
+        [a, b, b + offset, a + offset]
+    }
+}
 
 impl Command for RectangleCommand {
     fn name(&self) -> &'static str {
@@ -10,27 +83,71 @@ impl Command for RectangleCommand {
     }
 
     fn initial_prompt(&self) -> String {
-        "RECTANGLE Specify first corner:".to_string()
+        self.first_prompt().to_string()
+    }
+
+    fn options(&self) -> &[CommandOption] {
+        if self.points.is_empty() {
+            &[CENTER, THREE_POINT]
+        } else {
+            &[]
+        }
     }
 
     fn push_point(&mut self, pos: Vector2, ctx: &mut CommandContext) -> PointResult {
         self.points.push(pos);
 
-        if self.points.len() == 2 {
-            let p1 = self.points[0];
-            let p2 = self.points[1];
-            let min = Vector2::new(p1.x.min(p2.x), p1.y.min(p2.y));
-            let max = Vector2::new(p1.x.max(p2.x), p1.y.max(p2.y));
-            ctx.model
-                .add_entity(Entity::rectangle(min, max, ctx.filled_mode));
-            PointResult::Complete
-        } else {
-            PointResult::NeedMore {
-                prompt: "Specify other corner:".to_string(),
+        match (self.mode, &self.points[..]) {
+            (RectangleMode::ThreePoint, [a, b, _]) => {
+                let (a, b) = (*a, *b);
+                let dir = (b - a).normalized();
+                let height = (pos - a).dot(Vector2::new(-dir.y, dir.x));
+                // Build it axis-aligned at `a`, then turn it onto the first edge
+                let min = Vector2::new(a.x, a.y + height.min(0.0));
+                let max = Vector2::new(a.x + a.dist(b), a.y + height.max(0.0));
+                let mut entity = Entity::rectangle(min, max, ctx.defaults.filled);
+                entity.rotate(a, dir.y.atan2(dir.x));
+                self.finish(entity, ctx)
+            }
+            (RectangleMode::ThreePoint, [_, _]) => PointResult::NeedMore {
+                prompt: "Specify height point:".to_string(),
+            },
+            (RectangleMode::ThreePoint, _) => PointResult::NeedMore {
+                prompt: "Specify end of first edge:".to_string(),
+            },
+            (_, [first, _]) => {
+                let (min, max) = self.corners(*first, pos);
+                let entity = Entity::rectangle(min, max, ctx.defaults.filled);
+                self.finish(entity, ctx)
             }
+            _ => PointResult::NeedMore {
+                prompt: "Specify other corner:".to_string(),
+            },
+        }
+    }
+
+    fn process_input(&mut self, input: &str, ctx: &mut CommandContext) -> InputResult {
+        if self.points.is_empty()
+            && let Some((_, mode)) = [
+                (CENTER, RectangleMode::Center),
+                (THREE_POINT, RectangleMode::ThreePoint),
+            ]
+            .into_iter()
+            .find(|(option, _)| option.key == input)
+        {
+            self.mode = mode;
+            return InputResult::Parameter(PointResult::NeedMore {
+                prompt: self.first_prompt().to_string(),
+            });
         }
+
+        if let Some(pos) = parse_point(input) {
+            return InputResult::Point(self.push_point(pos, ctx));
+        }
+        InputResult::Invalid(CommandError::invalid_input(input))
     }
 
+    #[cfg(feature = "gui")]
     fn draw_preview(
         &self,
         ctx: &crate::view::rendering::context::DrawContext,
@@ -39,9 +156,22 @@ impl Command for RectangleCommand {
     ) {
         use eframe::egui;
 
+        if self.mode == RectangleMode::ThreePoint {
+            match points {
+                [a] => preview::draw_line_to_cursor(ctx, *a, current_cad),
+                [a, b, ..] => {
+                    let corners = Self::three_point_corners(*a, *b, current_cad);
+                    for (i, &corner) in corners.iter().enumerate() {
+                        preview::draw_line_to_cursor(ctx, corner, corners[(i + 1) % 4]);
+                    }
+                }
+                _ => {}
+            }
+            return;
+        }
+
         if let Some(&start) = points.first() {
-            let min = Vector2::new(start.x.min(current_cad.x), start.y.min(current_cad.y));
-            let max = Vector2::new(start.x.max(current_cad.x), start.y.max(current_cad.y));
+            let (min, max) = self.corners(start, current_cad);
             let rect_screen = egui::Rect::from_min_max(
                 ctx.to_screen(Vector2::new(min.x, max.y)),
                 ctx.to_screen(Vector2::new(max.x, min.y)),
@@ -68,5 +198,12 @@ impl Command for RectangleCommand {
         }
     }
 
+    fn dynamic_input(&self) -> Option<DynamicInput> {
+        match (self.mode, &self.points[..]) {
+            (RectangleMode::Corners, &[base]) => Some(DynamicInput::Size { base }),
+            _ => None,
+        }
+    }
+
     impl_command_common!(RectangleCommand);
 }
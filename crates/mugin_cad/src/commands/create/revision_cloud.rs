@@ -0,0 +1,259 @@
+use crate::commands::{
+    Command, CommandCategory, CommandContext, CommandError, CommandOption, InputResult,
+    PointResult, parse_point,
+};
+use crate::model::math::geometry;
+pub use crate::model::tools::markup::MARKUP_LAYER;
+use crate::model::tools::markup::markup_layer;
+use crate::model::{Entity, Geometry, RevisionCloud, Shape, Vector2};
+use std::collections::HashSet;
+
+/// How close a click must be to a shape to convert it
+const PICK_TOLERANCE: f32 = 10.0;
+
+const CLOSE: CommandOption = CommandOption::new("c", "Close");
+const OBJECT: CommandOption = CommandOption::new("o", "Object");
+const ARC_SIZE: CommandOption = CommandOption::new("a", "Arc size");
+const TAG: CommandOption = CommandOption::new("t", "Tag");
+
+/// What the next typed value is for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Step {
+    Boundary,
+    Object,
+    ArcSize,
+    Tag,
+}
+
+define_command!(
+    RevisionCloudCommand,
+    arc_size: f32 = 30.0,
+    tag: Option<String> = None,
+    step: Step = Step::Boundary,
+);
+
+impl RevisionCloudCommand {
+    fn prompt(&self) -> String {
+        match self.step {
+            Step::ArcSize => format!("Enter arc size <{}>:", self.arc_size),
+            Step::Tag => "Enter revision tag, or - for none:".to_string(),
+            Step::Object => "Select rectangle, circle, floor or closed outline:".to_string(),
+            Step::Boundary => match self.points.len() {
+                0 => {
+                    let tag = self.tag.as_deref().unwrap_or("none");
+                    format!(
+                        "REVCLOUD Specify first corner (O: Object, A: Arc size {}, T: Tag {}):",
+                        self.arc_size, tag
+                    )
+                }
+                1 | 2 => "Specify next corner:".to_string(),
+                _ => "Specify next corner or close at the first corner:".to_string(),
+            },
+        }
+    }
+
+    fn need(&self) -> PointResult {
+        PointResult::NeedMore {
+            prompt: self.prompt(),
+        }
+    }
+
+    /// Add a cloud around `boundary` on the markup layer
+    fn create(&self, boundary: Vec<Vector2>, ctx: &mut CommandContext) {
+        let mut cloud = RevisionCloud::new(boundary, self.arc_size);
+        cloud.tag = self.tag.clone();
+        let mut entity = Entity::revision_cloud(cloud);
+        entity.layer_id = markup_layer(&mut ctx.model.layer_manager);
+        entity.color = ctx.defaults.color;
+        ctx.place(entity);
+    }
+
+    fn close(&mut self, ctx: &mut CommandContext) -> PointResult {
+        let boundary = std::mem::take(&mut self.points);
+        self.create(boundary, ctx);
+        PointResult::Complete
+    }
+
+    /// Outline of a closed shape picked at `pos`: the entity under the
+    /// cursor, or else the loop of lines and arcs around it, with the ids
+    /// of the entities it replaces
+    fn outline_at(&self, pos: Vector2, ctx: &CommandContext) -> Option<(Vec<u64>, Vec<Vector2>)> {
+        let picked = ctx
+            .model
+            .pick_entity_id(pos, PICK_TOLERANCE)
+            .and_then(|id| ctx.model.find_by_id(id));
+        if let Some(entity) = picked {
+            let world = |p: Vector2| -> Vector2 {
+                entity.world_transform.transform_point2(p.into()).into()
+            };
+            let outline = match &entity.shape {
+                // Circles would otherwise get one tiny bulge per facet
+                Shape::Circle(circle) => {
+                    let circumference = std::f32::consts::TAU * circle.radius;
+                    let count = (circumference / self.arc_size).round().max(6.0) as usize;
+                    (0..count)
+                        .map(|i| {
+                            let t = std::f32::consts::TAU * i as f32 / count as f32;
+                            circle.center + Vector2::new(t.cos(), t.sin()) * circle.radius
+                        })
+                        .collect()
+                }
+                Shape::RevisionCloud(_) => return None,
+                shape if shape.is_closed() => shape.as_polyline(),
+                _ => Vec::new(),
+            };
+            if outline.len() >= 3 {
+                return Some((vec![entity.id], outline.into_iter().map(world).collect()));
+            }
+        }
+        let (indices, vertices) = geometry::find_closed_region(ctx.model, pos)?;
+        let ids = indices
+            .into_iter()
+            .filter_map(|i| ctx.model.entities.get(i).map(|e| e.id))
+            .collect();
+        Some((ids, vertices))
+    }
+
+    fn convert(&mut self, pos: Vector2, ctx: &mut CommandContext) -> PointResult {
+        let Some((ids, mut outline)) = self.outline_at(pos, ctx) else {
+            return PointResult::Rejected {
+                error: CommandError::NothingThere {
+                    what: "closed shape",
+                },
+                prompt: self.prompt(),
+            };
+        };
+        // Outlines repeating their first corner would get an empty edge
+        if outline.len() > 1 && outline.first() == outline.last() {
+            outline.pop();
+        }
+        ctx.model
+            .remove_entities_by_ids(&ids.into_iter().collect::<HashSet<_>>());
+        self.create(outline, ctx);
+        PointResult::Complete
+    }
+}
+
+impl Command for RevisionCloudCommand {
+    fn name(&self) -> &'static str {
+        "REVCLOUD"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::Creation
+    }
+
+    fn initial_prompt(&self) -> String {
+        self.prompt()
+    }
+
+    fn options(&self) -> &[CommandOption] {
+        match (self.step, self.points.len()) {
+            (Step::Boundary, 0) => &[OBJECT, ARC_SIZE, TAG],
+            (Step::Boundary, n) if n >= 3 => &[CLOSE],
+            _ => &[],
+        }
+    }
+
+    fn takes_text(&self) -> bool {
+        self.step == Step::Tag
+    }
+
+    fn process_input(&mut self, input: &str, ctx: &mut CommandContext) -> InputResult {
+        let input = input.trim();
+        match self.step {
+            Step::ArcSize => {
+                return match input.parse::<f32>() {
+                    Ok(size) if size > 0.0 => {
+                        self.arc_size = size;
+                        self.step = Step::Boundary;
+                        InputResult::Parameter(self.need())
+                    }
+                    _ => InputResult::Invalid(CommandError::InvalidValue(
+                        "Arc size must be a positive number.".to_string(),
+                    )),
+                };
+            }
+            Step::Tag => {
+                // Revision letters are capitals whatever case was typed
+                self.tag = (input != "-").then(|| input.to_uppercase());
+                self.step = Step::Boundary;
+                return InputResult::Parameter(self.need());
+            }
+            Step::Object | Step::Boundary => {}
+        }
+        if self.step == Step::Boundary && self.points.is_empty() {
+            let next = match input {
+                _ if input == OBJECT.key => Some(Step::Object),
+                _ if input == ARC_SIZE.key => Some(Step::ArcSize),
+                _ if input == TAG.key => Some(Step::Tag),
+                _ => None,
+            };
+            if let Some(step) = next {
+                self.step = step;
+                return InputResult::Parameter(self.need());
+            }
+        }
+        if input == CLOSE.key && self.points.len() >= 3 {
+            return InputResult::Parameter(self.close(ctx));
+        }
+        match parse_point(input) {
+            Some(pos) => InputResult::Point(self.push_point(pos, ctx)),
+            None => InputResult::Invalid(CommandError::invalid_input(input)),
+        }
+    }
+
+    fn push_point(&mut self, pos: Vector2, ctx: &mut CommandContext) -> PointResult {
+        match self.step {
+            Step::Object => return self.convert(pos, ctx),
+            Step::ArcSize | Step::Tag => return self.need(),
+            Step::Boundary => {}
+        }
+        let pos = match self.points.last() {
+            Some(&last) => self.constrain_point(pos, Some(last), ctx.modifiers),
+            None => pos,
+        };
+        // Picking the first corner again closes the outline
+        if let Some(&first) = self.points.first()
+            && first.dist(pos) <= f32::EPSILON
+        {
+            return if self.points.len() >= 3 {
+                self.close(ctx)
+            } else {
+                self.need()
+            };
+        }
+        self.points.push(pos);
+        self.need()
+    }
+
+    impl_command_common!(RevisionCloudCommand);
+
+    #[cfg(feature = "gui")]
+    fn draw_preview(
+        &self,
+        ctx: &crate::view::rendering::context::DrawContext,
+        points: &[Vector2],
+        cursor_pos_cad: Vector2,
+    ) {
+        use crate::commands::preview;
+        use eframe::egui;
+
+        if self.step != Step::Boundary || points.is_empty() {
+            return;
+        }
+        let mut boundary = points.to_vec();
+        boundary.push(cursor_pos_cad);
+        if boundary.len() < 3 {
+            preview::draw_line_to_cursor(ctx, points[0], cursor_pos_cad);
+            return;
+        }
+        let outline: Vec<egui::Pos2> = RevisionCloud::new(boundary, self.arc_size)
+            .as_polyline()
+            .into_iter()
+            .map(|p| ctx.to_screen(p))
+            .collect();
+        ctx.painter
+            .add(egui::Shape::closed_line(outline, preview::preview_stroke()));
+    }
+}
@@ -0,0 +1,81 @@
+#[cfg(feature = "gui")]
+use crate::commands::preview;
+use crate::commands::{Command, CommandCategory, CommandContext, PointResult};
+use crate::model::{CadModel, Entity, SectionMark, Shape, Vector2};
+
+define_command!(SectionCommand);
+
+fn collect_labels(entities: &[Entity], used: &mut Vec<String>) {
+    for entity in entities {
+        if let Shape::Section(mark) = &entity.shape {
+            used.push(mark.label.clone());
+        }
+        collect_labels(&entity.children, used);
+    }
+}
+
+/// First letter not yet used by a section mark in the drawing
+fn next_label(model: &CadModel) -> String {
+    let mut used = Vec::new();
+    collect_labels(&model.entities, &mut used);
+    ('A'..='Z')
+        .map(String::from)
+        .find(|label| !used.contains(label))
+        .unwrap_or_else(|| format!("A{}", used.len()))
+}
+
+impl Command for SectionCommand {
+    fn name(&self) -> &'static str {
+        "SECTION"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::Creation
+    }
+
+    fn initial_prompt(&self) -> String {
+        "SECTION Specify start of cut line:".to_string()
+    }
+
+    fn push_point(&mut self, pos: Vector2, ctx: &mut CommandContext) -> PointResult {
+        self.points.push(pos);
+        match self.points[..] {
+            [start, end, side] => {
+                let mut mark = SectionMark::new(start, end, next_label(ctx.model));
+                mark.look_toward(side);
+                ctx.add_entity(Entity::section(mark));
+                PointResult::Complete
+            }
+            [_, _] => PointResult::NeedMore {
+                prompt: "Specify viewing side:".to_string(),
+            },
+            _ => PointResult::NeedMore {
+                prompt: "Specify end of cut line:".to_string(),
+            },
+        }
+    }
+
+    #[cfg(feature = "gui")]
+    fn draw_preview(
+        &self,
+        ctx: &crate::view::rendering::context::DrawContext,
+        points: &[Vector2],
+        current_cad: Vector2,
+    ) {
+        match points {
+            [start] => preview::draw_line_to_cursor(ctx, *start, current_cad),
+            [start, end, ..] => {
+                let mut mark = SectionMark::new(*start, *end, "");
+                mark.look_toward(current_cad);
+                preview::draw_line_to_cursor(ctx, *start, *end);
+                // Arrows at both ends toward the chosen side
+                let arrow = mark.direction() * (start.dist(*end) * 0.1);
+                preview::draw_line_to_cursor(ctx, *start, *start + arrow);
+                preview::draw_line_to_cursor(ctx, *end, *end + arrow);
+            }
+            _ => {}
+        }
+    }
+
+    impl_command_common!(SectionCommand);
+}
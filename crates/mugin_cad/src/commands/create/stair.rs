@@ -0,0 +1,107 @@
+#[cfg(feature = "gui")]
+use crate::commands::preview;
+use crate::commands::{
+    Command, CommandCategory, CommandContext, CommandError, InputResult, PointResult, parse_point,
+};
+use crate::model::{Entity, StairData, Vector2};
+
+define_command!(StairCommand, width: f32 = 120.0, rise: f32 = 300.0);
+
+impl StairCommand {
+    fn prompt(&self) -> String {
+        if self.points.is_empty() {
+            format!(
+                "STAIR Specify start of flight or enter width <{}>:",
+                self.width
+            )
+        } else {
+            "Specify end of flight:".to_string()
+        }
+    }
+}
+
+impl Command for StairCommand {
+    fn name(&self) -> &'static str {
+        "STAIR"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::Creation
+    }
+
+    fn initial_prompt(&self) -> String {
+        self.prompt()
+    }
+
+    fn on_start(&mut self, ctx: &CommandContext) {
+        // One flight climbs a whole storey
+        self.rise = ctx.defaults.storey_height;
+    }
+
+    fn process_input(&mut self, input: &str, ctx: &mut CommandContext) -> InputResult {
+        if let Some(pos) = parse_point(input) {
+            return InputResult::Point(self.push_point(pos, ctx));
+        }
+        if self.points.is_empty()
+            && let Ok(width) = input.parse::<f32>()
+            && width > 0.0
+        {
+            self.width = width;
+            return InputResult::Parameter(PointResult::NeedMore {
+                prompt: self.prompt(),
+            });
+        }
+        InputResult::Invalid(CommandError::invalid_input(input))
+    }
+
+    fn push_point(&mut self, pos: Vector2, ctx: &mut CommandContext) -> PointResult {
+        let pos = match self.points.last() {
+            Some(&last) => self.constrain_point(pos, Some(last), ctx.modifiers),
+            None => pos,
+        };
+        let Some(&start) = self.points.first() else {
+            self.points.push(pos);
+            return PointResult::NeedMore {
+                prompt: self.prompt(),
+            };
+        };
+        if start.dist(pos) <= f32::EPSILON {
+            return PointResult::NeedMore {
+                prompt: self.prompt(),
+            };
+        }
+        let mut stair = StairData::spanning(start, pos, self.width, self.rise);
+        stair.storey_id = ctx.model.storeys.active_id();
+        ctx.add_entity(Entity::stair(stair));
+        PointResult::Complete
+    }
+
+    #[cfg(feature = "gui")]
+    fn draw_preview(
+        &self,
+        ctx: &crate::view::rendering::context::DrawContext,
+        points: &[Vector2],
+        current_cad: Vector2,
+    ) {
+        let [start] = points else {
+            return;
+        };
+        if start.dist(current_cad) <= f32::EPSILON {
+            return;
+        }
+        let stair = StairData::spanning(*start, current_cad, self.width, self.rise);
+        let corners = stair.corners();
+        for i in 0..4 {
+            preview::draw_line_to_cursor(ctx, corners[i], corners[(i + 1) % 4]);
+        }
+        let (a, b) = stair.riser_line(1);
+        preview::draw_line_to_cursor(ctx, a, b);
+        preview::draw_dimension_text(
+            ctx,
+            ctx.to_screen(current_cad) + eframe::egui::vec2(12.0, -12.0),
+            format!("{} risers", stair.risers),
+        );
+    }
+
+    impl_command_common!(StairCommand);
+}
@@ -0,0 +1,176 @@
+use crate::commands::{
+    Command, CommandCategory, CommandContext, CommandError, CommandOption, InputResult,
+    PointResult, parse_point,
+};
+use crate::model::Vector2;
+use crate::model::system::symbols::SymbolDefinition;
+
+define_command!(
+    SymbolCommand,
+    symbol: Option<SymbolDefinition> = None,
+    rotation: f32 = 0.0,
+    choices: Vec<String> = Vec::new(),
+);
+
+const NAME: CommandOption = CommandOption::new("n", "Name");
+
+impl SymbolCommand {
+    fn prompt(&self) -> String {
+        match (&self.symbol, self.points.is_empty()) {
+            (None, _) => format!("Enter symbol name ({}):", self.choices.join(", ")),
+            (Some(symbol), true) => {
+                format!("Specify insertion point for {} (N: Name):", symbol.name)
+            }
+            (Some(_), false) => "Specify rotation angle or point:".to_string(),
+        }
+    }
+
+    fn need_more(&self) -> PointResult {
+        PointResult::NeedMore {
+            prompt: self.prompt(),
+        }
+    }
+
+    /// Rotation that turns the symbol's x axis toward `cursor`
+    fn rotation_toward(&self, base: Vector2, cursor: Vector2) -> f32 {
+        let delta = cursor - base;
+        if delta.length() < f32::EPSILON {
+            self.rotation
+        } else {
+            delta.y.atan2(delta.x)
+        }
+    }
+
+    fn place(&mut self, rotation: f32, ctx: &mut CommandContext) -> PointResult {
+        let (Some(symbol), Some(base)) = (&self.symbol, self.points.pop()) else {
+            return self.need_more();
+        };
+        let mut instance = symbol.instance(base, rotation);
+        for child in &mut instance.children {
+            child.layer_id = ctx.layer_id;
+        }
+        ctx.add_entity(instance);
+        // The next copy starts out turned like this one
+        self.rotation = rotation;
+        self.need_more()
+    }
+}
+
+impl Command for SymbolCommand {
+    fn name(&self) -> &'static str {
+        "Symbol"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::Creation
+    }
+
+    fn on_start(&mut self, ctx: &CommandContext) {
+        self.choices = ctx.symbols.symbols().map(|s| s.name.clone()).collect();
+        self.symbol = ctx
+            .active_symbol
+            .and_then(|name| ctx.symbols.get(name))
+            .cloned();
+    }
+
+    fn initial_prompt(&self) -> String {
+        self.prompt()
+    }
+
+    fn options(&self) -> &[CommandOption] {
+        match self.symbol {
+            Some(_) => &[NAME],
+            None => &[],
+        }
+    }
+
+    fn push_point(&mut self, pos: Vector2, ctx: &mut CommandContext) -> PointResult {
+        if self.symbol.is_none() {
+            return self.need_more();
+        }
+        match self.points.last() {
+            None => {
+                self.points.push(pos);
+                self.need_more()
+            }
+            Some(&base) => {
+                let rotation = self.rotation_toward(base, pos);
+                self.place(rotation, ctx)
+            }
+        }
+    }
+
+    fn process_input(&mut self, input: &str, ctx: &mut CommandContext) -> InputResult {
+        if self.symbol.is_some() && input == NAME.key {
+            self.symbol = None;
+            self.points.clear();
+            return InputResult::Parameter(self.need_more());
+        }
+        if self.symbol.is_none() {
+            return match ctx.symbols.get(input) {
+                Some(symbol) => {
+                    self.symbol = Some(symbol.clone());
+                    InputResult::Parameter(self.need_more())
+                }
+                None => InputResult::Invalid(CommandError::NotFound {
+                    kind: "symbol",
+                    name: input.trim().to_string(),
+                }),
+            };
+        }
+        // A bare angle at the rotation step is the rotation
+        if !self.points.is_empty()
+            && let Some(angle) = ctx.dimension_style.angles.parse_rotation(input)
+        {
+            return InputResult::Parameter(self.place(angle, ctx));
+        }
+        match parse_point(input) {
+            Some(pos) => InputResult::Point(self.push_point(pos, ctx)),
+            None => InputResult::Invalid(CommandError::invalid_input(input)),
+        }
+    }
+
+    impl_command_common!(SymbolCommand);
+
+    #[cfg(feature = "gui")]
+    fn draw_preview(
+        &self,
+        ctx: &crate::view::rendering::context::DrawContext,
+        _points: &[Vector2],
+        cursor_pos_cad: Vector2,
+    ) {
+        use crate::commands::preview;
+        use eframe::egui;
+
+        let Some(symbol) = &self.symbol else {
+            return;
+        };
+        let (base, rotation) = match self.points.last() {
+            Some(&base) => (base, self.rotation_toward(base, cursor_pos_cad)),
+            None => (cursor_pos_cad, self.rotation),
+        };
+
+        let stroke = preview::preview_stroke();
+        for (points, closed) in symbol.outlines(base, rotation) {
+            let points: Vec<egui::Pos2> = points.into_iter().map(|p| ctx.to_screen(p)).collect();
+            if closed {
+                ctx.painter.add(egui::Shape::closed_line(points, stroke));
+            } else {
+                ctx.painter.add(egui::Shape::line(points, stroke));
+            }
+        }
+        for (position, text) in symbol.texts(base, rotation) {
+            ctx.painter.text(
+                ctx.to_screen(position),
+                egui::Align2::CENTER_CENTER,
+                text,
+                egui::FontId::proportional(12.0),
+                stroke.color,
+            );
+        }
+
+        if self.points.last().is_some() {
+            preview::draw_line_to_cursor(ctx, base, cursor_pos_cad);
+        }
+    }
+}
@@ -1,23 +1,97 @@
-use crate::commands::{Command, CommandCategory, CommandContext, InputResult, PointResult};
+use crate::commands::{
+    Command, CommandCategory, CommandContext, CommandError, InputResult, PointResult,
+};
 use crate::model::shapes::annotation::TextAnnotation;
 use crate::model::{Entity, Vector2};
 
-define_command!(TextCommand);
+/// Typed in TEXT content to stand for the last measured value
+pub const LAST_RESULT_TOKEN: &str = "lastresult";
+
+/// LABEL or a `lastresult` before anything was measured
+fn nothing_measured() -> CommandError {
+    CommandError::MissingPrerequisite(
+        "Nothing measured yet. Run DISTANCE or AREA first.".to_string(),
+    )
+}
+
+define_command!(
+    TextCommand,
+    preset: Option<String> = None,
+    leader_to: Option<Vector2> = None,
+    from_measurement: bool = false,
+);
+
+impl TextCommand {
+    /// Place `text` with one click instead of asking for it, with a leader
+    /// to `leader_to` when given
+    pub fn with_text(text: impl Into<String>, leader_to: Option<Vector2>) -> Self {
+        Self {
+            preset: Some(text.into()),
+            leader_to,
+            ..Self::new()
+        }
+    }
+
+    /// LABEL: the last measured value, with a leader to where it was measured
+    pub fn label() -> Self {
+        Self {
+            from_measurement: true,
+            ..Self::new()
+        }
+    }
+
+    fn create(&self, position: Vector2, text: String, ctx: &mut CommandContext) {
+        let mut annotation = match self.leader_to {
+            Some(target) => TextAnnotation::new_leader(target, position, text),
+            None => TextAnnotation::new_custom(position, text),
+        };
+        annotation.style.font_size = ctx.defaults.text_font_size;
+        ctx.add_entity(Entity::text(annotation));
+    }
+}
 
 impl Command for TextCommand {
     fn name(&self) -> &'static str {
-        "Text"
+        if self.from_measurement {
+            "Label"
+        } else {
+            "Text"
+        }
     }
 
     fn category(&self) -> CommandCategory {
         CommandCategory::Creation
     }
 
+    fn can_execute(&self, ctx: &CommandContext) -> bool {
+        !self.from_measurement || ctx.last_measurement.is_some()
+    }
+
+    fn cannot_execute(&self) -> CommandError {
+        nothing_measured()
+    }
+
+    fn on_start(&mut self, ctx: &CommandContext) {
+        if self.from_measurement
+            && let Some(measurement) = *ctx.last_measurement
+        {
+            self.preset = Some(measurement.label(ctx.dimension_style));
+            self.leader_to = Some(measurement.target);
+        }
+    }
+
     fn initial_prompt(&self) -> String {
-        "Specify text position:".to_string()
+        match &self.preset {
+            Some(text) => format!("Specify position for \"{}\":", text),
+            None => "Specify text position:".to_string(),
+        }
     }
 
-    fn push_point(&mut self, pos: Vector2, _ctx: &mut CommandContext) -> PointResult {
+    fn push_point(&mut self, pos: Vector2, ctx: &mut CommandContext) -> PointResult {
+        if let Some(text) = self.preset.clone() {
+            self.create(pos, text, ctx);
+            return PointResult::Complete;
+        }
         self.points.push(pos);
 
         // After first point, wait for text input
@@ -26,31 +100,36 @@ impl Command for TextCommand {
         }
     }
 
+    fn takes_text(&self) -> bool {
+        self.preset.is_none() && !self.points.is_empty()
+    }
+
     fn process_input(&mut self, input: &str, ctx: &mut CommandContext) -> InputResult {
         if self.points.is_empty() {
             // Try to parse as point first
             if let Some(pos) = crate::commands::parse_point(input) {
-                self.points.push(pos);
-                return InputResult::Point(PointResult::NeedMore {
-                    prompt: "Enter text content:".to_string(),
-                });
+                return InputResult::Point(self.push_point(pos, ctx));
             }
-            return InputResult::Invalid {
-                message: "Please specify a position first.".to_string(),
-            };
+            return InputResult::Invalid(CommandError::MissingPrerequisite(
+                "Please specify a position first.".to_string(),
+            ));
         }
 
         // We have a position, treat input as text content
-        let text = input.trim().to_string();
+        let mut text = input.trim().to_string();
         if text.is_empty() {
-            return InputResult::Invalid {
-                message: "Text cannot be empty.".to_string(),
+            return InputResult::Invalid(CommandError::InvalidValue(
+                "Text cannot be empty.".to_string(),
+            ));
+        }
+        if text.contains(LAST_RESULT_TOKEN) {
+            let Some(measurement) = *ctx.last_measurement else {
+                return InputResult::Invalid(nothing_measured());
             };
+            text = text.replace(LAST_RESULT_TOKEN, &measurement.label(ctx.dimension_style));
         }
 
-        let annotation = TextAnnotation::new_custom(self.points[0], text);
-        ctx.model.add_entity(Entity::text(annotation));
-
+        self.create(self.points[0], text, ctx);
         InputResult::Point(PointResult::Complete)
     }
 
@@ -0,0 +1,84 @@
+//! Why a command rejected input or could not start. Commands return these
+//! instead of free-form messages so callers can tell a typo from an empty
+//! selection; the wording shown to the user is decided here.
+
+use crate::commands::output::TerminalLine;
+use crate::model::tools::degenerate::Degenerate;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandError {
+    /// Nothing is registered under the typed name
+    UnknownCommand(String),
+    /// Text the current step cannot read as a point, number or option
+    InvalidInput {
+        input: String,
+        /// What the step expects, if it says more than the prompt
+        hint: Option<&'static str>,
+    },
+    /// A value of the right kind the command cannot use, e.g. a negative
+    /// distance
+    InvalidValue(String),
+    /// The command works on a selection and none of `what` is selected
+    NothingSelected { what: &'static str },
+    /// A pick that hit nothing the step can use
+    NothingThere { what: &'static str },
+    /// A name that matches nothing, e.g. an unknown symbol
+    NotFound { kind: &'static str, name: String },
+    /// The shape the step would create is below the geometry limits
+    Degenerate(Degenerate),
+    /// Something the step depends on is missing, e.g. an earlier point or
+    /// a measurement
+    MissingPrerequisite(String),
+    /// The command failed on its own account
+    Internal(String),
+}
+
+impl CommandError {
+    /// Input the current step cannot read, without a hint
+    pub fn invalid_input(input: &str) -> Self {
+        Self::InvalidInput {
+            input: input.to_string(),
+            hint: None,
+        }
+    }
+
+    /// Terminal line the error is logged as. Internal failures are errors;
+    /// everything else is the user's input not fitting, shown as a warning.
+    pub fn terminal_line(&self) -> TerminalLine {
+        match self {
+            Self::Internal(_) | Self::UnknownCommand(_) | Self::InvalidInput { .. } => {
+                TerminalLine::error(self.to_string())
+            }
+            _ => TerminalLine::warning(self.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownCommand(name) => write!(f, "Unknown command \"{}\".", name),
+            Self::InvalidInput { input, hint: None } => write!(f, "Invalid input \"{}\".", input),
+            Self::InvalidInput {
+                input,
+                hint: Some(hint),
+            } => write!(f, "Invalid input \"{}\". {}", input, hint),
+            Self::NothingSelected { what } => {
+                // "entities" -> "Select entities", "image" -> "Select an image"
+                let article = match what.chars().next() {
+                    _ if what.ends_with('s') => "",
+                    Some('a' | 'e' | 'i' | 'o' | 'u') => "an ",
+                    _ => "a ",
+                };
+                write!(f, "No {} selected. Select {}{} first.", what, article, what)
+            }
+            Self::NothingThere { what } => write!(f, "No {} there.", what),
+            Self::NotFound { kind, name } => write!(f, "No {} named \"{}\".", kind, name),
+            Self::Degenerate(problem) => write!(f, "{} — ignored.", problem),
+            Self::InvalidValue(message)
+            | Self::MissingPrerequisite(message)
+            | Self::Internal(message) => f.write_str(message),
+        }
+    }
+}
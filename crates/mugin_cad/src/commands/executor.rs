@@ -1,18 +1,31 @@
 use crate::commands::arc::ArcCommand;
 use crate::commands::axis::AxisCommand;
+use crate::commands::calibrate::CalibrateCommand;
 use crate::commands::circle::CircleCommand;
 use crate::commands::copy::CopyCommand;
 use crate::commands::distance::DistanceCommand;
 use crate::commands::line::LineCommand;
+use crate::commands::measure::Measurement;
+use crate::commands::mirror::MirrorCommand;
 use crate::commands::r#move::MoveCommand;
 use crate::commands::offset::OffsetCommand;
+use crate::commands::output::TerminalLine;
 use crate::commands::rectangle::RectangleCommand;
 use crate::commands::rotate::RotateCommand;
+use crate::commands::scale::ScaleCommand;
 use crate::commands::text::TextCommand;
 use crate::commands::trim::TrimCommand;
-use crate::commands::{Command, CommandContext, InputModifiers, InputResult, PointResult};
+use crate::commands::{
+    Command, CommandCategory, CommandContext, CommandError, CommandOption, DynamicInput,
+    InputModifiers, InputResult, MovingBounds, PointResult, TransformPreview, resolve_coordinates,
+};
+use crate::model::dimension::DimensionStyle;
+use crate::model::drafting::DraftingDefaults;
+use crate::model::system::symbols::SymbolLibrary;
+use crate::model::tools::degenerate::GeometryLimits;
 use crate::model::{CadModel, Vector2};
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 /// Factory function type for creating commands
 type CommandFactory = fn() -> Box<dyn Command>;
@@ -47,6 +60,15 @@ impl CommandRegistry {
         registry.register("rotate", || Box::new(RotateCommand::new()));
         registry.register("e", || Box::new(RotateCommand::new()));
 
+        registry.register("scale", || Box::new(ScaleCommand::new()));
+        registry.register("sc", || Box::new(ScaleCommand::new()));
+
+        registry.register("mirror", || Box::new(MirrorCommand::new()));
+        registry.register("mi", || Box::new(MirrorCommand::new()));
+
+        registry.register("calibrate", || Box::new(CalibrateCommand::new()));
+        registry.register("cal", || Box::new(CalibrateCommand::new()));
+
         // Register copy/cut commands
         registry.register("copy", || Box::new(CopyCommand::new()));
         registry.register("co", || Box::new(CopyCommand::new()));
@@ -67,12 +89,37 @@ impl CommandRegistry {
 
         // Register annotation commands
         registry.register("text", || Box::new(TextCommand::new()));
+        registry.register("label", || Box::new(TextCommand::label()));
+        registry.register("section", || {
+            Box::new(crate::commands::create::section::SectionCommand::new())
+        });
+        registry.register("symbol", || {
+            Box::new(crate::commands::create::symbol::SymbolCommand::new())
+        });
+        registry.register("sym", || {
+            Box::new(crate::commands::create::symbol::SymbolCommand::new())
+        });
+        registry.register("revcloud", || {
+            Box::new(crate::commands::create::revision_cloud::RevisionCloudCommand::new())
+        });
         registry.register("place_column", || {
             Box::new(crate::commands::create::place_column::CmdPlaceColumn::new())
         });
         registry.register("place_beam", || {
             Box::new(crate::commands::create::beam::BeamCommand::new())
         });
+        registry.register("flooring", || {
+            Box::new(crate::commands::create::flooring::FlooringCommand::new())
+        });
+        registry.register("slab", || {
+            Box::new(crate::commands::create::flooring::FlooringCommand::new())
+        });
+        registry.register("opening", || {
+            Box::new(crate::commands::create::opening::OpeningCommand::new())
+        });
+        registry.register("stair", || {
+            Box::new(crate::commands::create::stair::StairCommand::new())
+        });
         registry.register("distance", || Box::new(DistanceCommand::new()));
         registry.register("dist", || Box::new(DistanceCommand::new()));
 
@@ -82,6 +129,9 @@ impl CommandRegistry {
         registry.register("dim", || {
             Box::new(crate::commands::create::measure::MeasureCommand::new())
         });
+        registry.register("autodim", || {
+            Box::new(crate::commands::create::autodim::AutoDimCommand::new())
+        });
         registry.register("area", || {
             Box::new(crate::commands::measure::area::MeasureAreaCommand::new())
         });
@@ -102,6 +152,22 @@ impl CommandRegistry {
     pub fn create(&self, name: &str) -> Option<Box<dyn Command>> {
         self.commands.get(name).map(|factory| factory())
     }
+
+    /// Whether `name` starts a command
+    pub fn contains(&self, name: &str) -> bool {
+        self.commands.contains_key(name)
+    }
+
+    /// Shortest alias that starts the same command as `name`, if it is
+    /// shorter than `name` itself (e.g. "line" -> "l")
+    pub fn shortest_alias(&self, name: &str) -> Option<&'static str> {
+        let target = self.create(name)?.name();
+        self.commands
+            .iter()
+            .filter(|(alias, factory)| alias.len() < name.len() && factory().name() == target)
+            .map(|(alias, _)| *alias)
+            .min_by_key(|alias| (alias.len(), *alias))
+    }
 }
 
 /// Manages the active command and coordinates with the model
@@ -109,10 +175,36 @@ pub struct CommandExecutor {
     registry: CommandRegistry,
     active_command: Option<Box<dyn Command>>,
     pub status_message: String,
-    pub filled_mode: bool,
+    /// Terminal lines produced since the view model last collected them
+    pub output: Vec<TerminalLine>,
+    /// Per-project drafting defaults handed to commands
+    pub defaults: DraftingDefaults,
     pub modifiers: InputModifiers,
     pub active_column_type_id: Option<u64>,
     pub active_beam_type_id: Option<u64>,
+    /// Dimension style measured values are labelled with, kept in step
+    /// with the app's by the view model
+    pub dimension_style: DimensionStyle,
+    /// Smallest geometry commands may create, kept in step with the app's
+    /// by the view model
+    pub limits: GeometryLimits,
+    /// Result of the last DISTANCE, AREA or PERIMETER run
+    pub last_measurement: Option<Measurement>,
+    /// Symbols SYMBOL places, shared with the view model
+    pub symbols: Arc<SymbolLibrary>,
+    /// Symbol picked from the palette; SYMBOL asks for a name without one
+    pub active_symbol: Option<String>,
+    /// Group being edited, set from the selection scope by the view model
+    /// before each input
+    pub group: Option<u64>,
+    /// Temporary tracking points dropped during the active command. They
+    /// snap like geometry but are not part of the model.
+    pub tracking_points: Vec<Vector2>,
+    /// Why the last command name, input or point was rejected; cleared
+    /// by the next one that is accepted
+    pub last_error: Option<CommandError>,
+    /// Bumped each time a command starts, to tell runs of a command apart
+    run: u64,
 }
 
 impl CommandExecutor {
@@ -121,10 +213,20 @@ impl CommandExecutor {
             registry: CommandRegistry::new(),
             active_command: None,
             status_message: "Command:".to_string(),
-            filled_mode: false,
+            output: Vec::new(),
+            defaults: DraftingDefaults::default(),
             modifiers: InputModifiers::default(),
             active_column_type_id: None,
             active_beam_type_id: None,
+            dimension_style: DimensionStyle::default(),
+            limits: GeometryLimits::default(),
+            last_measurement: None,
+            symbols: Arc::default(),
+            active_symbol: None,
+            group: None,
+            tracking_points: Vec::new(),
+            last_error: None,
+            run: 0,
         }
     }
 
@@ -144,20 +246,30 @@ impl CommandExecutor {
         name: &str,
         model: &mut CadModel,
         selected_ids: &HashSet<u64>,
-    ) -> bool {
+    ) -> Result<(), CommandError> {
+        self.last_error = None;
         if let Some(mut cmd) = self.registry.create(name) {
             let ctx = CommandContext {
+                layer_id: model.layer_manager.active_layer_id,
                 model,
                 selected_ids,
-                filled_mode: self.filled_mode,
+                defaults: &self.defaults,
                 modifiers: self.modifiers,
                 active_column_type_id: self.active_column_type_id,
                 active_beam_type_id: self.active_beam_type_id,
+                output: &mut self.output,
+                dimension_style: &self.dimension_style,
+                limits: &self.limits,
+                last_measurement: &mut self.last_measurement,
+                symbols: &self.symbols,
+                active_symbol: self.active_symbol.as_deref(),
+                group: self.group,
             };
 
             if !cmd.can_execute(&ctx) {
-                self.status_message = cmd.cannot_execute_message();
-                return false;
+                let error = cmd.cannot_execute();
+                self.report(error.clone(), None);
+                return Err(error);
             }
 
             // Call on_start for commands that need initial setup
@@ -165,46 +277,168 @@ impl CommandExecutor {
 
             self.status_message = cmd.initial_prompt();
             self.active_command = Some(cmd);
-            true
+            self.tracking_points.clear();
+            self.run += 1;
+            Ok(())
         } else {
-            false
+            let error = CommandError::UnknownCommand(name.to_string());
+            self.report(error.clone(), None);
+            Err(error)
         }
     }
 
+    /// Log `error` and show it on the status bar, followed by the prompt
+    /// the command is waiting at, if it still runs
+    fn report(&mut self, error: CommandError, prompt: Option<String>) {
+        self.output.push(error.terminal_line());
+        self.status_message = match prompt {
+            Some(prompt) => format!("{} {}", error, prompt),
+            None => error.to_string(),
+        };
+        self.last_error = Some(error);
+    }
+
+    /// Keyboard shortcut or typed alias for a command, for tooltips
+    #[cfg(feature = "gui")]
+    pub fn shortcut_for(&self, name: &str) -> Option<String> {
+        crate::view::shortcuts::key_binding_for(name)
+            .map(str::to_string)
+            .or_else(|| self.registry.shortest_alias(name).map(str::to_uppercase))
+    }
+
     /// Cancel the current command
     pub fn cancel(&mut self) {
         self.active_command = None;
+        self.tracking_points.clear();
         self.status_message = "Command:".to_string();
     }
 
+    /// End the finished command. The last line it reported, if any after
+    /// the first `reported` output lines, stays on the status bar.
+    fn complete(&mut self, reported: usize) {
+        self.cancel();
+        if let Some(line) = self.output.get(reported..).and_then(|lines| lines.last()) {
+            self.status_message = line.text.clone();
+        }
+    }
+
+    /// Drop a tracking point at `pos`, or remove the one already there.
+    /// Tracking points only exist while a command runs; returns false
+    /// when none is active.
+    pub fn toggle_tracking_point(&mut self, pos: Vector2) -> bool {
+        if self.active_command.is_none() {
+            return false;
+        }
+        let before = self.tracking_points.len();
+        self.tracking_points.retain(|p| p.dist(pos) > 1e-4);
+        if self.tracking_points.len() == before {
+            self.tracking_points.push(pos);
+        }
+        true
+    }
+
+    /// Drop the active command's in-progress step, keeping it active.
+    /// Returns false when there is no step to drop.
+    pub fn cancel_step(&mut self) -> bool {
+        let Some(cmd) = &mut self.active_command else {
+            return false;
+        };
+        if !cmd.reset_step() {
+            return false;
+        }
+        self.status_message = format!("Step cancelled. {}", cmd.initial_prompt());
+        true
+    }
+
     /// Check if a command is active
     pub fn is_active(&self) -> bool {
         self.active_command.is_some()
     }
 
-    /// Process a click/point input
+    /// Display name of the active command, if any
+    pub fn active_command_name(&self) -> Option<&'static str> {
+        self.active_command.as_ref().map(|cmd| cmd.name())
+    }
+
+    /// Identifies the active command run; a restarted command gets a new one
+    pub fn active_run(&self) -> Option<u64> {
+        self.active_command.as_ref().map(|_| self.run)
+    }
+
+    /// Category of the active command, if any
+    pub fn active_category(&self) -> Option<CommandCategory> {
+        self.active_command.as_ref().map(|cmd| cmd.category())
+    }
+
+    /// Options the active command offers at its current step
+    pub fn active_options(&self) -> &[CommandOption] {
+        self.active_command
+            .as_ref()
+            .map_or(&[], |cmd| cmd.options())
+    }
+
+    /// Whether `input` picks one of the active command's options
+    pub fn is_option(&self, input: &str) -> bool {
+        let clean = input.trim().to_lowercase();
+        self.active_options().iter().any(|opt| opt.key == clean)
+    }
+
+    /// Entities the active command carries and where `cursor` would put
+    /// them
+    pub fn transform_preview(&self, cursor: Vector2) -> Option<TransformPreview> {
+        self.active_command
+            .as_ref()?
+            .transform_preview(cursor, self.modifiers)
+    }
+
+    /// Whether the active command is waiting for free text
+    pub fn takes_text(&self) -> bool {
+        self.active_command
+            .as_ref()
+            .is_some_and(|cmd| cmd.takes_text())
+    }
+
+    /// Apply the active command's constraints (e.g. Shift for ortho) to a
+    /// cursor position
+    pub fn constrain(&self, pos: Vector2, modifiers: InputModifiers) -> Vector2 {
+        match &self.active_command {
+            Some(cmd) => cmd.constrain_point(pos, cmd.get_points().last().copied(), modifiers),
+            None => pos,
+        }
+    }
+
+    /// Process a click/point input. `pos` is already snapped and
+    /// constrained, see `CadViewModel::resolve_cursor`.
     pub fn push_point(&mut self, pos: Vector2, model: &mut CadModel, selected_ids: &HashSet<u64>) {
+        self.last_error = None;
         if let Some(cmd) = &mut self.active_command {
+            let reported = self.output.len();
             let mut ctx = CommandContext {
+                layer_id: model.layer_manager.active_layer_id,
                 model,
                 selected_ids,
-                filled_mode: self.filled_mode,
+                defaults: &self.defaults,
                 modifiers: self.modifiers,
                 active_column_type_id: self.active_column_type_id,
                 active_beam_type_id: self.active_beam_type_id,
+                output: &mut self.output,
+                dimension_style: &self.dimension_style,
+                limits: &self.limits,
+                last_measurement: &mut self.last_measurement,
+                symbols: &self.symbols,
+                active_symbol: self.active_symbol.as_deref(),
+                group: self.group,
             };
 
-            // Apply constraints based on modifiers
-            let constrained_pos =
-                cmd.constrain_point(pos, cmd.get_points().last().copied(), self.modifiers);
-
-            match cmd.push_point(constrained_pos, &mut ctx) {
+            let result = cmd.push_point(pos, &mut ctx);
+            // Commands edit the model freely
+            ctx.model.changes.touch_all();
+            match result {
                 PointResult::NeedMore { prompt } => {
                     self.status_message = prompt;
                 }
-                PointResult::Complete => {
-                    self.cancel();
-                }
+                PointResult::Complete => self.complete(reported),
+                PointResult::Rejected { error, prompt } => self.report(error, Some(prompt)),
             }
         }
     }
@@ -216,45 +450,91 @@ impl CommandExecutor {
         model: &mut CadModel,
         selected_ids: &HashSet<u64>,
     ) {
-        // First, check if it's a new command
+        // First, check if it's a new command. Option keys win over aliases
+        // so "c" means Close inside LINE rather than starting CIRCLE.
+        // A command that cannot run says why, instead of being unknown.
+        // Free text a command is waiting for is never an alias either.
         let clean = input.trim().to_lowercase();
-        if self.start_command(&clean, model, selected_ids) {
+        self.last_error = None;
+        if !self.is_option(&clean) && !self.takes_text() && self.registry.contains(&clean) {
+            let _ = self.start_command(&clean, model, selected_ids);
             return;
         }
 
         // If no active command, show error
         if self.active_command.is_none() {
-            self.status_message = format!("Unknown command \"{}\".", clean);
+            self.report(CommandError::UnknownCommand(clean), None);
             return;
         }
 
+        // Relative and polar coordinates reach the command as absolute
+        // ones, so every step taking a point takes them too
+        let input = match &self.active_command {
+            Some(cmd) if !cmd.takes_text() => {
+                match resolve_coordinates(
+                    &clean,
+                    cmd.get_points().last().copied(),
+                    &self.dimension_style.angles,
+                ) {
+                    Ok(Some(point)) => format!("{},{}", point.x, point.y),
+                    Ok(None) => clean,
+                    Err(error) => {
+                        self.report(error, None);
+                        return;
+                    }
+                }
+            }
+            _ => clean,
+        };
+
         // Process with active command
         if let Some(cmd) = &mut self.active_command {
+            let reported = self.output.len();
             let mut ctx = CommandContext {
+                layer_id: model.layer_manager.active_layer_id,
                 model,
                 selected_ids,
-                filled_mode: self.filled_mode,
+                defaults: &self.defaults,
                 modifiers: self.modifiers,
                 active_column_type_id: self.active_column_type_id,
                 active_beam_type_id: self.active_beam_type_id,
+                output: &mut self.output,
+                dimension_style: &self.dimension_style,
+                limits: &self.limits,
+                last_measurement: &mut self.last_measurement,
+                symbols: &self.symbols,
+                active_symbol: self.active_symbol.as_deref(),
+                group: self.group,
             };
 
-            match cmd.process_input(&clean, &mut ctx) {
+            let result = cmd.process_input(&input, &mut ctx);
+            ctx.model.changes.touch_all();
+            match result {
                 InputResult::Point(PointResult::Complete)
-                | InputResult::Parameter(PointResult::Complete) => {
-                    self.cancel();
-                }
+                | InputResult::Parameter(PointResult::Complete) => self.complete(reported),
                 InputResult::Point(PointResult::NeedMore { prompt })
                 | InputResult::Parameter(PointResult::NeedMore { prompt }) => {
                     self.status_message = prompt;
                 }
-                InputResult::Invalid { message } => {
-                    self.status_message = message;
+                InputResult::Point(PointResult::Rejected { error, prompt })
+                | InputResult::Parameter(PointResult::Rejected { error, prompt }) => {
+                    self.report(error, Some(prompt));
                 }
+                InputResult::Invalid(error) => self.report(error, None),
             }
         }
     }
 
+    /// Dynamic input fields of the active command's next point
+    pub fn dynamic_input(&self) -> Option<DynamicInput> {
+        self.active_command.as_ref()?.dynamic_input()
+    }
+
+    /// Entities the active command carries with the cursor
+    pub fn moving_bounds(&self) -> Option<MovingBounds> {
+        self.active_command.as_ref()?.moving_bounds()
+    }
+
     /// Get points from active command for preview
     pub fn get_preview_points(&self) -> Option<(&dyn Command, &[Vector2])> {
         self.active_command
@@ -264,24 +544,21 @@ impl CommandExecutor {
 
     /// Toggle filled mode
     pub fn toggle_filled(&mut self) -> bool {
-        self.filled_mode = !self.filled_mode;
-        self.filled_mode
+        self.defaults.filled = !self.defaults.filled;
+        self.defaults.filled
     }
 
     /// Toggle arc direction (CW/CCW) if arc command is active
     pub fn toggle_arc_direction(&mut self) -> bool {
-        if let Some(cmd) = &mut self.active_command {
-            if cmd.name() == "Arc" {
-                if let Some(any) = cmd.as_any_mut() {
-                    if let Some(arc_cmd) = any.downcast_mut::<crate::commands::arc::ArcCommand>() {
-                        arc_cmd.toggle_direction();
-                        let dir = if arc_cmd.clockwise { "CW" } else { "CCW" };
-                        self.status_message =
-                            format!("Specify end point [{}] (R to reverse):", dir);
-                        return true;
-                    }
-                }
-            }
+        if let Some(cmd) = &mut self.active_command
+            && cmd.name() == "Arc"
+            && let Some(any) = cmd.as_any_mut()
+            && let Some(arc_cmd) = any.downcast_mut::<crate::commands::arc::ArcCommand>()
+        {
+            arc_cmd.toggle_direction();
+            let dir = if arc_cmd.clockwise { "CW" } else { "CCW" };
+            self.status_message = format!("Specify end point [{}] (R to reverse):", dir);
+            return true;
         }
         false
     }
@@ -290,23 +567,20 @@ impl CommandExecutor {
         if let Some(cmd) = &mut self.active_command {
             let name = cmd.name();
             if name == "Place Column" {
-                if let Some(any) = cmd.as_any_mut() {
-                    if let Some(col_cmd) =
+                if let Some(any) = cmd.as_any_mut()
+                    && let Some(col_cmd) =
                         any.downcast_mut::<crate::commands::create::place_column::CmdPlaceColumn>()
-                    {
-                        col_cmd.cycle_anchor();
-                        return true;
-                    }
-                }
-            } else if name == "Place Beam" {
-                if let Some(any) = cmd.as_any_mut() {
-                    if let Some(beam_cmd) =
-                        any.downcast_mut::<crate::commands::create::beam::BeamCommand>()
-                    {
-                        beam_cmd.cycle_anchor();
-                        return true;
-                    }
+                {
+                    col_cmd.cycle_anchor();
+                    return true;
                 }
+            } else if name == "Place Beam"
+                && let Some(any) = cmd.as_any_mut()
+                && let Some(beam_cmd) =
+                    any.downcast_mut::<crate::commands::create::beam::BeamCommand>()
+            {
+                beam_cmd.cycle_anchor();
+                return true;
             }
         }
         false
@@ -316,23 +590,20 @@ impl CommandExecutor {
         if let Some(cmd) = &mut self.active_command {
             let name = cmd.name();
             if name == "Place Column" {
-                if let Some(any) = cmd.as_any_mut() {
-                    if let Some(col_cmd) =
+                if let Some(any) = cmd.as_any_mut()
+                    && let Some(col_cmd) =
                         any.downcast_mut::<crate::commands::create::place_column::CmdPlaceColumn>()
-                    {
-                        col_cmd.rotate_cw();
-                        return true;
-                    }
-                }
-            } else if name == "Place Beam" {
-                if let Some(any) = cmd.as_any_mut() {
-                    if let Some(beam_cmd) =
-                        any.downcast_mut::<crate::commands::create::beam::BeamCommand>()
-                    {
-                        beam_cmd.rotate_cw();
-                        return true;
-                    }
+                {
+                    col_cmd.rotate_cw();
+                    return true;
                 }
+            } else if name == "Place Beam"
+                && let Some(any) = cmd.as_any_mut()
+                && let Some(beam_cmd) =
+                    any.downcast_mut::<crate::commands::create::beam::BeamCommand>()
+            {
+                beam_cmd.rotate_cw();
+                return true;
             }
         }
         false
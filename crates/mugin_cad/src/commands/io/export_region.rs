@@ -1,5 +1,6 @@
 use crate::commands::{Command, CommandCategory, CommandContext, PointResult};
 use crate::model::Vector2;
+#[cfg(feature = "gui")]
 use eframe::egui;
 
 #[derive(Debug, Clone)]
@@ -53,7 +54,13 @@ impl Command for SelectExportRegionCommand {
         &[]
     }
 
+    fn reset_step(&mut self) -> bool {
+        self.p2 = None;
+        self.p1.take().is_some()
+    }
+
     // Using draw_preview to draw the rectangle
+    #[cfg(feature = "gui")]
     fn draw_preview(
         &self,
         ctx: &crate::view::rendering::context::DrawContext,
@@ -73,7 +73,7 @@ macro_rules! define_manipulation_command {
     };
 }
 
-/// `Command` trait'inin tekrarlanan `get_points()` ve `clone_box()` impl'lerini üretir.
+/// `Command` trait'inin tekrarlanan `get_points()`, `reset_step()` ve `clone_box()` impl'lerini üretir.
 ///
 /// Kullanım: impl_command_common!(LineCommand);
 macro_rules! impl_command_common {
@@ -82,6 +82,12 @@ macro_rules! impl_command_common {
             &self.points
         }
 
+        fn reset_step(&mut self) -> bool {
+            let in_progress = !self.points.is_empty();
+            self.points.clear();
+            in_progress
+        }
+
         fn clone_box(&self) -> Box<dyn crate::commands::Command> {
             Box::new(self.clone())
         }
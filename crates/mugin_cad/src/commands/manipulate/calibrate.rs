@@ -0,0 +1,131 @@
+#[cfg(feature = "gui")]
+use crate::commands::preview;
+use crate::commands::{
+    Command, CommandCategory, CommandContext, CommandError, InputResult, PointResult, parse_point,
+};
+use crate::model::{Shape, Vector2};
+
+define_manipulation_command!(CalibrateCommand);
+
+impl CalibrateCommand {
+    fn need(prompt: &str) -> PointResult {
+        PointResult::NeedMore {
+            prompt: prompt.to_string(),
+        }
+    }
+
+    /// Scale the images so the two picked points lie `distance` apart,
+    /// keeping the first point in place
+    fn apply(&mut self, distance: f32, ctx: &mut CommandContext) -> PointResult {
+        let [first, second] = self.points[..] else {
+            return Self::need("Specify first point on image:");
+        };
+        let measured = first.dist(second);
+        if measured < 1e-6 {
+            self.points.clear();
+            return Self::need("Points coincide. Specify first point on image:");
+        }
+        if !distance.is_finite() || distance <= 0.0 {
+            return Self::need("Distance must be positive. Specify real distance:");
+        }
+
+        for &id in &self.entity_ids {
+            if let Some(entity) = ctx.model.find_by_id_mut(id) {
+                entity.scale(first, distance / measured);
+            }
+        }
+        PointResult::Complete
+    }
+}
+
+impl Command for CalibrateCommand {
+    fn name(&self) -> &'static str {
+        "CALIBRATE"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::Manipulation
+    }
+
+    fn can_execute(&self, ctx: &CommandContext) -> bool {
+        ctx.selected_ids.iter().any(|&id| {
+            matches!(
+                ctx.model.find_by_id(id).map(|e| &e.shape),
+                Some(Shape::Image(_))
+            )
+        })
+    }
+
+    fn cannot_execute(&self) -> CommandError {
+        CommandError::NothingSelected { what: "image" }
+    }
+
+    fn initial_prompt(&self) -> String {
+        "CALIBRATE Specify first point on image:".to_string()
+    }
+
+    fn on_start(&mut self, ctx: &CommandContext) {
+        self.entity_ids = ctx
+            .selected_ids
+            .iter()
+            .copied()
+            .filter(|&id| {
+                matches!(
+                    ctx.model.find_by_id(id).map(|e| &e.shape),
+                    Some(Shape::Image(_))
+                )
+            })
+            .collect();
+    }
+
+    fn push_point(&mut self, pos: Vector2, _ctx: &mut CommandContext) -> PointResult {
+        if self.points.len() < 2 {
+            self.points.push(pos);
+        }
+        if self.points.len() == 1 {
+            Self::need("Specify second point on image:")
+        } else {
+            Self::need("Specify real distance between the points:")
+        }
+    }
+
+    fn process_input(&mut self, input: &str, ctx: &mut CommandContext) -> InputResult {
+        if self.points.len() == 2 {
+            return match input.parse::<f32>() {
+                Ok(distance) => InputResult::Parameter(self.apply(distance, ctx)),
+                Err(_) => InputResult::Invalid(CommandError::InvalidInput {
+                    input: input.to_string(),
+                    hint: Some("Enter the real distance as a number."),
+                }),
+            };
+        }
+        if let Some(pos) = parse_point(input) {
+            return InputResult::Point(self.push_point(pos, ctx));
+        }
+        InputResult::Invalid(CommandError::invalid_input(input))
+    }
+
+    #[cfg(feature = "gui")]
+    fn draw_preview(
+        &self,
+        ctx: &crate::view::rendering::context::DrawContext,
+        points: &[Vector2],
+        current_cad: Vector2,
+    ) {
+        match points {
+            [first] => preview::draw_line_to_cursor(ctx, *first, current_cad),
+            [first, second] => {
+                ctx.painter.line_segment(
+                    [ctx.to_screen(*first), ctx.to_screen(*second)],
+                    preview::preview_stroke(),
+                );
+            }
+            _ => {}
+        }
+        for &point in points {
+            preview::draw_point_marker(ctx, point, eframe::egui::Color32::YELLOW);
+        }
+    }
+
+    impl_command_common!(CalibrateCommand);
+}
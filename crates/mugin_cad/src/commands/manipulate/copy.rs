@@ -1,9 +1,10 @@
-use crate::commands::{Command, CommandCategory, CommandContext, PointResult};
+use crate::commands::{Command, CommandCategory, CommandContext, MovingBounds, PointResult};
 use crate::model::{Entity, Vector2};
 
 define_manipulation_command!(CopyCommand,
     copied_entities: Vec<Entity> = Vec::new(),
-    is_cut: bool = false
+    is_cut: bool = false,
+    bounds: Option<(Vector2, Vector2)> = None
 );
 
 impl CopyCommand {
@@ -13,6 +14,7 @@ impl CopyCommand {
             entity_ids: Vec::new(),
             copied_entities: Vec::new(),
             is_cut: true,
+            bounds: None,
         }
     }
 }
@@ -26,10 +28,6 @@ impl Command for CopyCommand {
         CommandCategory::Manipulation
     }
 
-    fn cannot_execute_message(&self) -> String {
-        "No entities selected. Select entities first.".to_string()
-    }
-
     fn initial_prompt(&self) -> String {
         if self.is_cut {
             "CUT Specify base point:".to_string()
@@ -39,13 +37,14 @@ impl Command for CopyCommand {
     }
 
     fn on_start(&mut self, ctx: &CommandContext) {
-        self.entity_ids = ctx.model.get_top_level_selected_ids(&ctx.selected_ids);
+        self.entity_ids = ctx.model.get_top_level_selected_ids(ctx.selected_ids);
         // Clone the selected entities
         for &id in &self.entity_ids {
             if let Some(entity) = ctx.model.find_by_id(id) {
                 self.copied_entities.push(entity.clone());
             }
         }
+        self.bounds = ctx.model.bounds_of(self.entity_ids.iter().copied());
     }
 
     fn push_point(&mut self, pos: Vector2, ctx: &mut CommandContext) -> PointResult {
@@ -61,23 +60,32 @@ impl Command for CopyCommand {
             let to = pos;
             let delta = to - from;
 
-            // Add copied entities with translation
-            for mut entity in self.copied_entities.clone() {
-                entity.translate(delta);
-                ctx.model.add_entity(entity);
-            }
-
-            // If cut, delete the originals
+            // If cut, delete the originals first; the moved entities keep
+            // their IDs and names
             if self.is_cut {
                 let ids_set: std::collections::HashSet<u64> =
                     self.entity_ids.iter().cloned().collect();
                 ctx.model.remove_entities_by_ids(&ids_set);
             }
 
+            // Add copied entities with translation
+            for original in &self.copied_entities {
+                let mut entity = if self.is_cut {
+                    original.clone()
+                } else {
+                    let mut copy = original.duplicate();
+                    copy.name = ctx.model.unique_name(&original.name);
+                    copy
+                };
+                entity.translate(delta);
+                ctx.place(entity);
+            }
+
             PointResult::Complete
         }
     }
 
+    #[cfg(feature = "gui")]
     fn draw_preview(
         &self,
         ctx: &crate::view::rendering::context::DrawContext,
@@ -99,5 +107,20 @@ impl Command for CopyCommand {
         }
     }
 
+    fn moving_bounds(&self) -> Option<MovingBounds> {
+        let (min, max) = self.bounds?;
+        Some(MovingBounds {
+            base: *self.points.first()?,
+            min,
+            max,
+            // Copies may line up with their originals; cut ones go away
+            moving_ids: if self.is_cut {
+                self.entity_ids.clone()
+            } else {
+                Vec::new()
+            },
+        })
+    }
+
     impl_command_common!(CopyCommand);
 }
@@ -0,0 +1,138 @@
+#[cfg(feature = "gui")]
+use crate::commands::preview;
+use crate::commands::{
+    Command, CommandCategory, CommandContext, CommandError, CommandOption, EntityTransform,
+    InputModifiers, InputResult, PointResult, TransformPreview, parse_point,
+};
+use crate::model::Vector2;
+
+const ERASE: CommandOption = CommandOption::new("e", "Erase source");
+
+define_manipulation_command!(MirrorCommand, erase: bool = false);
+
+impl MirrorCommand {
+    fn second_prompt(&self) -> PointResult {
+        let prompt = if self.erase {
+            "Specify second point of mirror line (the source will be erased):"
+        } else {
+            "Specify second point of mirror line:"
+        };
+        PointResult::NeedMore {
+            prompt: prompt.to_string(),
+        }
+    }
+
+    /// Reflection across the line from the first point toward `to`, kept
+    /// horizontal or vertical with Shift
+    fn transform(&self, to: Vector2, modifiers: InputModifiers) -> Option<EntityTransform> {
+        let pivot = *self.points.first()?;
+        let mut axis = to - pivot;
+        if modifiers.shift {
+            axis = if axis.x.abs() > axis.y.abs() {
+                Vector2::new(axis.x, 0.0)
+            } else {
+                Vector2::new(0.0, axis.y)
+            };
+        }
+        (axis.length() > 1e-6).then_some(EntityTransform::Mirror { pivot, axis })
+    }
+}
+
+impl Command for MirrorCommand {
+    fn name(&self) -> &'static str {
+        "MIRROR"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::Manipulation
+    }
+
+    fn initial_prompt(&self) -> String {
+        "MIRROR Specify first point of mirror line:".to_string()
+    }
+
+    fn on_start(&mut self, ctx: &CommandContext) {
+        self.entity_ids = ctx.model.get_top_level_selected_ids(ctx.selected_ids);
+    }
+
+    fn options(&self) -> &[CommandOption] {
+        &[ERASE]
+    }
+
+    fn push_point(&mut self, pos: Vector2, ctx: &mut CommandContext) -> PointResult {
+        if self.points.is_empty() {
+            self.points.push(pos);
+            return self.second_prompt();
+        }
+        let Some(transform) = self.transform(pos, ctx.modifiers) else {
+            return PointResult::Rejected {
+                error: CommandError::MissingPrerequisite(
+                    "The mirror line needs two different points.".to_string(),
+                ),
+                prompt: "Specify second point of mirror line:".to_string(),
+            };
+        };
+        self.points.push(pos);
+
+        // The mirrored copy is kept beside the source unless asked to erase it
+        for &id in &self.entity_ids {
+            if self.erase {
+                if let Some(entity) = ctx.model.find_by_id_mut(id) {
+                    transform.apply(entity);
+                }
+            } else if let Some(entity) = ctx.model.find_by_id(id) {
+                let mut copy = entity.duplicate();
+                transform.apply(&mut copy);
+                ctx.place(copy);
+            }
+        }
+
+        PointResult::Complete
+    }
+
+    fn process_input(&mut self, input: &str, ctx: &mut CommandContext) -> InputResult {
+        if input == ERASE.key {
+            self.erase = !self.erase;
+            return InputResult::Parameter(if self.points.is_empty() {
+                PointResult::NeedMore {
+                    prompt: self.initial_prompt(),
+                }
+            } else {
+                self.second_prompt()
+            });
+        }
+
+        if let Some(pos) = parse_point(input) {
+            return InputResult::Point(self.push_point(pos, ctx));
+        }
+
+        InputResult::Invalid(CommandError::invalid_input(input))
+    }
+
+    fn transform_preview(
+        &self,
+        cursor: Vector2,
+        modifiers: InputModifiers,
+    ) -> Option<TransformPreview> {
+        Some(TransformPreview {
+            entity_ids: self.entity_ids.clone(),
+            transform: self.transform(cursor, modifiers)?,
+            copy: !self.erase,
+        })
+    }
+
+    #[cfg(feature = "gui")]
+    fn draw_preview(
+        &self,
+        ctx: &crate::view::rendering::context::DrawContext,
+        points: &[Vector2],
+        current_cad: Vector2,
+    ) {
+        if let Some(&first) = points.first() {
+            preview::draw_line_to_cursor(ctx, first, current_cad);
+            preview::draw_center_marker(ctx, first);
+        }
+    }
+
+    impl_command_common!(MirrorCommand);
+}
@@ -1,5 +1,8 @@
+pub mod calibrate;
 pub mod copy;
+pub mod mirror;
 pub mod r#move; // 'move' is a reserved keyword in Rust
 pub mod offset;
 pub mod rotate;
+pub mod scale;
 pub mod trim;
@@ -1,8 +1,28 @@
+use crate::commands::output::TerminalLine;
+#[cfg(feature = "gui")]
 use crate::commands::preview;
-use crate::commands::{Command, CommandCategory, CommandContext, PointResult};
+use crate::commands::{
+    Command, CommandCategory, CommandContext, EntityTransform, InputModifiers, MovingBounds,
+    PointResult, TransformPreview,
+};
 use crate::model::Vector2;
 
-define_manipulation_command!(MoveCommand);
+define_manipulation_command!(MoveCommand, bounds: Option<(Vector2, Vector2)> = None);
+
+impl MoveCommand {
+    /// Displacement from the base point to `to`, horizontal or vertical
+    /// with Shift
+    fn delta(&self, to: Vector2, modifiers: InputModifiers) -> Option<Vector2> {
+        let delta = to - *self.points.first()?;
+        Some(if !modifiers.shift {
+            delta
+        } else if delta.x.abs() > delta.y.abs() {
+            Vector2::new(delta.x, 0.0)
+        } else {
+            Vector2::new(0.0, delta.y)
+        })
+    }
+}
 
 impl Command for MoveCommand {
     fn name(&self) -> &'static str {
@@ -13,50 +33,52 @@ impl Command for MoveCommand {
         CommandCategory::Manipulation
     }
 
-    fn cannot_execute_message(&self) -> String {
-        "No entities selected. Select entities first.".to_string()
-    }
-
     fn initial_prompt(&self) -> String {
         "MOVE Specify base point:".to_string()
     }
 
     fn on_start(&mut self, ctx: &CommandContext) {
-        self.entity_ids = ctx.model.get_top_level_selected_ids(&ctx.selected_ids);
+        self.entity_ids = ctx.model.get_top_level_selected_ids(ctx.selected_ids);
+        self.bounds = ctx.model.bounds_of(self.entity_ids.iter().copied());
     }
 
     fn push_point(&mut self, pos: Vector2, ctx: &mut CommandContext) -> PointResult {
+        let Some(delta) = self.delta(pos, ctx.modifiers) else {
+            self.points.push(pos);
+            return PointResult::NeedMore {
+                prompt: "Specify destination point (Shift for ortho, Ctrl for connected move):"
+                    .to_string(),
+            };
+        };
         self.points.push(pos);
 
-        if self.points.len() == 1 {
-            PointResult::NeedMore {
-                prompt: "Specify destination point (Shift for ortho):".to_string(),
-            }
+        // Ctrl inverts the connected move setting
+        let connected = ctx.defaults.connected_move != ctx.modifiers.ctrl;
+        let ends = if connected {
+            ctx.model.line_ends(&self.entity_ids)
         } else {
-            // Calculate delta and move entity
-            let from = self.points[0];
-            let to = pos;
-            let mut delta = to - from;
-
-            // Apply ortho constraint if Shift is pressed
-            if ctx.modifiers.shift {
-                if delta.x.abs() > delta.y.abs() {
-                    delta = Vector2::new(delta.x, 0.0);
-                } else {
-                    delta = Vector2::new(0.0, delta.y);
-                }
-            }
+            Vec::new()
+        };
 
-            for &id in &self.entity_ids {
-                if let Some(entity) = ctx.model.find_by_id_mut(id) {
-                    entity.translate(delta);
-                }
+        let transform = EntityTransform::Translate(delta);
+        for &id in &self.entity_ids {
+            if let Some(entity) = ctx.model.find_by_id_mut(id) {
+                transform.apply(entity);
             }
+        }
 
-            PointResult::Complete
+        if connected {
+            let adjusted = ctx.model.drag_connected(&ends, delta, &self.entity_ids);
+            ctx.report(TerminalLine::info(format!(
+                "Connected move: {} neighbors adjusted",
+                adjusted
+            )));
         }
+
+        PointResult::Complete
     }
 
+    #[cfg(feature = "gui")]
     fn draw_preview(
         &self,
         ctx: &crate::view::rendering::context::DrawContext,
@@ -72,5 +94,27 @@ impl Command for MoveCommand {
         }
     }
 
+    fn transform_preview(
+        &self,
+        cursor: Vector2,
+        modifiers: InputModifiers,
+    ) -> Option<TransformPreview> {
+        Some(TransformPreview {
+            entity_ids: self.entity_ids.clone(),
+            transform: EntityTransform::Translate(self.delta(cursor, modifiers)?),
+            copy: false,
+        })
+    }
+
+    fn moving_bounds(&self) -> Option<MovingBounds> {
+        let (min, max) = self.bounds?;
+        Some(MovingBounds {
+            base: *self.points.first()?,
+            min,
+            max,
+            moving_ids: self.entity_ids.clone(),
+        })
+    }
+
     impl_command_common!(MoveCommand);
 }
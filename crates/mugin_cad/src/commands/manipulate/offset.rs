@@ -1,4 +1,8 @@
-use crate::commands::{Command, CommandCategory, CommandContext, InputResult, PointResult};
+#[cfg(feature = "gui")]
+use crate::commands::preview;
+use crate::commands::{
+    Command, CommandCategory, CommandContext, CommandError, InputResult, PointResult,
+};
 use crate::model::{Entity, Line, Shape, Vector2};
 
 define_manipulation_command!(OffsetCommand,
@@ -48,8 +52,8 @@ impl Command for OffsetCommand {
         CommandCategory::Manipulation
     }
 
-    fn cannot_execute_message(&self) -> String {
-        "No lines selected. Select lines first.".to_string()
+    fn cannot_execute(&self) -> CommandError {
+        CommandError::NothingSelected { what: "lines" }
     }
 
     fn initial_prompt(&self) -> String {
@@ -60,10 +64,10 @@ impl Command for OffsetCommand {
         self.entity_ids = ctx.selected_ids.iter().cloned().collect();
         // Only collect lines from selected entities
         for &id in &self.entity_ids {
-            if let Some(entity) = ctx.model.find_by_id(id) {
-                if let Shape::Line(line) = &entity.shape {
-                    self.selected_lines.push((id, line.clone()));
-                }
+            if let Some(entity) = ctx.model.find_by_id(id)
+                && let Shape::Line(line) = &entity.shape
+            {
+                self.selected_lines.push((id, line.clone()));
             }
         }
     }
@@ -80,15 +84,15 @@ impl Command for OffsetCommand {
 
     fn push_point(&mut self, pos: Vector2, ctx: &mut CommandContext) -> PointResult {
         // If we don't have a distance yet, use the point to calculate distance from first line
-        if self.offset_distance.is_none() {
-            if let Some((_, line)) = self.selected_lines.first() {
-                let dist = pos.dist_to_line(line.start, line.end);
-                self.offset_distance = Some(dist);
-                self.points.push(pos);
-                return PointResult::NeedMore {
-                    prompt: format!("Offset distance: {:.2}. Click side to offset:", dist),
-                };
-            }
+        if self.offset_distance.is_none()
+            && let Some((_, line)) = self.selected_lines.first()
+        {
+            let dist = pos.dist_to_line(line.start, line.end);
+            self.offset_distance = Some(dist);
+            self.points.push(pos);
+            return PointResult::NeedMore {
+                prompt: format!("Offset distance: {:.2}. Click side to offset:", dist),
+            };
         }
 
         // We have distance, use point to determine side and create offset lines
@@ -97,7 +101,7 @@ impl Command for OffsetCommand {
         if let Some(distance) = self.offset_distance {
             for (_, line) in &self.selected_lines {
                 let offset_line = self.offset_line(line, distance, pos);
-                ctx.model.add_entity(Entity::new(Shape::Line(offset_line)));
+                ctx.place(Entity::new(Shape::Line(offset_line)));
             }
         }
 
@@ -106,18 +110,18 @@ impl Command for OffsetCommand {
 
     fn process_input(&mut self, input: &str, ctx: &mut CommandContext) -> InputResult {
         // Try to parse as distance first
-        if self.offset_distance.is_none() {
-            if let Ok(dist) = input.parse::<f32>() {
-                if dist > 0.0 {
-                    self.offset_distance = Some(dist);
-                    return InputResult::Parameter(PointResult::NeedMore {
-                        prompt: format!("Offset distance: {:.2}. Click side to offset:", dist),
-                    });
-                } else {
-                    return InputResult::Invalid {
-                        message: "Offset distance must be positive.".to_string(),
-                    };
-                }
+        if self.offset_distance.is_none()
+            && let Ok(dist) = input.parse::<f32>()
+        {
+            if dist > 0.0 {
+                self.offset_distance = Some(dist);
+                return InputResult::Parameter(PointResult::NeedMore {
+                    prompt: format!("Offset distance: {:.2}. Click side to offset:", dist),
+                });
+            } else {
+                return InputResult::Invalid(CommandError::InvalidValue(
+                    "Offset distance must be positive.".to_string(),
+                ));
             }
         }
 
@@ -125,12 +129,31 @@ impl Command for OffsetCommand {
         if let Some(pos) = crate::commands::parse_point(input) {
             InputResult::Point(self.push_point(pos, ctx))
         } else {
-            InputResult::Invalid {
-                message: format!(
-                    "Invalid input \"{}\". Enter distance or coordinates.",
-                    input
-                ),
-            }
+            InputResult::Invalid(CommandError::InvalidInput {
+                input: input.to_string(),
+                hint: Some("Enter distance or coordinates."),
+            })
+        }
+    }
+
+    #[cfg(feature = "gui")]
+    fn draw_preview(
+        &self,
+        ctx: &crate::view::rendering::context::DrawContext,
+        _points: &[Vector2],
+        current_cad: Vector2,
+    ) {
+        // Until a distance is set, the cursor's distance from the first line is used
+        let distance = self.offset_distance.or_else(|| {
+            let (_, line) = self.selected_lines.first()?;
+            Some(current_cad.dist_to_line(line.start, line.end))
+        });
+        let Some(distance) = distance else {
+            return;
+        };
+        for (_, line) in &self.selected_lines {
+            let ghost = self.offset_line(line, distance, current_cad);
+            preview::draw_line_to_cursor(ctx, ghost.start, ghost.end);
         }
     }
 
@@ -1,10 +1,27 @@
+#[cfg(feature = "gui")]
 use crate::commands::preview;
-use crate::commands::{Command, CommandCategory, CommandContext, PointResult};
+use crate::commands::{
+    Command, CommandCategory, CommandContext, EntityTransform, InputModifiers, PointResult,
+    TransformPreview,
+};
 use crate::model::Vector2;
 use std::f32::consts::PI;
 
 define_manipulation_command!(RotateCommand);
 
+impl RotateCommand {
+    /// Rotation about the pivot toward `to`, in 45° steps with Shift
+    fn transform(&self, to: Vector2, modifiers: InputModifiers) -> Option<EntityTransform> {
+        let pivot = *self.points.first()?;
+        let mut angle = (to.y - pivot.y).atan2(to.x - pivot.x);
+        if modifiers.shift {
+            let snap_angle = PI / 4.0;
+            angle = (angle / snap_angle).round() * snap_angle;
+        }
+        Some(EntityTransform::Rotate { pivot, angle })
+    }
+}
+
 impl Command for RotateCommand {
     fn name(&self) -> &'static str {
         "ROTATE"
@@ -14,50 +31,45 @@ impl Command for RotateCommand {
         CommandCategory::Manipulation
     }
 
-    fn cannot_execute_message(&self) -> String {
-        "No entities selected. Select entities first.".to_string()
-    }
-
     fn initial_prompt(&self) -> String {
         "ROTATE Specify base point (pivot):".to_string()
     }
 
     fn on_start(&mut self, ctx: &CommandContext) {
-        self.entity_ids = ctx.model.get_top_level_selected_ids(&ctx.selected_ids);
+        self.entity_ids = ctx.model.get_top_level_selected_ids(ctx.selected_ids);
     }
 
     fn push_point(&mut self, pos: Vector2, ctx: &mut CommandContext) -> PointResult {
+        let Some(transform) = self.transform(pos, ctx.modifiers) else {
+            self.points.push(pos);
+            return PointResult::NeedMore {
+                prompt: "Specify rotation angle point (Shift for 45° snap):".to_string(),
+            };
+        };
         self.points.push(pos);
 
-        if self.points.len() == 1 {
-            PointResult::NeedMore {
-                prompt: "Specify rotation angle point (Shift for 45° snap):".to_string(),
-            }
-        } else {
-            // Calculate angle from base point
-            let pivot = self.points[0];
-            let to = pos;
-
-            let dx = to.x - pivot.x;
-            let dy = to.y - pivot.y;
-            let mut angle = dy.atan2(dx);
-
-            // Snap to 45 degree increments if Shift is pressed
-            if ctx.modifiers.shift {
-                let snap_angle = PI / 4.0; // 45 degrees
-                angle = (angle / snap_angle).round() * snap_angle;
+        for &id in &self.entity_ids {
+            if let Some(entity) = ctx.model.find_by_id_mut(id) {
+                transform.apply(entity);
             }
+        }
 
-            for &id in &self.entity_ids {
-                if let Some(entity) = ctx.model.find_by_id_mut(id) {
-                    entity.rotate(pivot, angle);
-                }
-            }
+        PointResult::Complete
+    }
 
-            PointResult::Complete
-        }
+    fn transform_preview(
+        &self,
+        cursor: Vector2,
+        modifiers: InputModifiers,
+    ) -> Option<TransformPreview> {
+        Some(TransformPreview {
+            entity_ids: self.entity_ids.clone(),
+            transform: self.transform(cursor, modifiers)?,
+            copy: false,
+        })
     }
 
+    #[cfg(feature = "gui")]
     fn draw_preview(
         &self,
         ctx: &crate::view::rendering::context::DrawContext,
@@ -96,6 +108,16 @@ impl Command for RotateCommand {
                     .line_segment([arc_points[i], arc_points[i + 1]], angle_stroke);
             }
 
+            // Readout of the rotation beside the middle of the arc
+            let middle = end_angle / 2.0;
+            ctx.painter.text(
+                pivot_screen + egui::vec2(middle.cos(), -middle.sin()) * (arc_radius + 12.0),
+                egui::Align2::CENTER_CENTER,
+                ctx.dim_style.angles.format_rotation(angle),
+                egui::FontId::proportional(12.0),
+                egui::Color32::from_rgb(255, 200, 100),
+            );
+
             // Draw radius line from pivot to mouse
             preview::draw_line_to_cursor(ctx, pivot, current_cad);
 
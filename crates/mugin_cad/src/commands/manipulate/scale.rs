@@ -0,0 +1,214 @@
+#[cfg(feature = "gui")]
+use crate::commands::preview;
+use crate::commands::{
+    Command, CommandCategory, CommandContext, CommandError, CommandOption, EntityTransform,
+    InputModifiers, InputResult, PointResult, TransformPreview, parse_point,
+};
+use crate::model::Vector2;
+
+const COPY: CommandOption = CommandOption::new("c", "Copy");
+const REFERENCE: CommandOption = CommandOption::new("r", "Reference");
+
+define_manipulation_command!(ScaleCommand,
+    copy: bool = false,
+    reference_mode: bool = false,
+    reference_length: Option<f32> = None
+);
+
+impl ScaleCommand {
+    fn factor_prompt(&self) -> PointResult {
+        let prompt = if self.copy {
+            "Specify scale factor (a copy will be scaled):"
+        } else {
+            "Specify scale factor:"
+        };
+        PointResult::NeedMore {
+            prompt: prompt.to_string(),
+        }
+    }
+
+    fn need(prompt: &str) -> PointResult {
+        PointResult::NeedMore {
+            prompt: prompt.to_string(),
+        }
+    }
+
+    /// Scale the selection (or a copy of it) about the base point
+    fn apply(&mut self, factor: f32, ctx: &mut CommandContext) -> PointResult {
+        if !factor.is_finite() || factor <= 0.0 {
+            return Self::need("Scale factor must be positive. Specify scale factor:");
+        }
+        let base = self.points[0];
+
+        let transform = EntityTransform::Scale { base, factor };
+        for &id in &self.entity_ids {
+            if self.copy {
+                if let Some(entity) = ctx.model.find_by_id(id) {
+                    let mut copy = entity.duplicate();
+                    transform.apply(&mut copy);
+                    ctx.place(copy);
+                }
+            } else if let Some(entity) = ctx.model.find_by_id_mut(id) {
+                transform.apply(entity);
+            }
+        }
+
+        PointResult::Complete
+    }
+
+    /// Scale factor a click at `cursor` would apply, once it can be told
+    fn factor(&self, cursor: Vector2) -> Option<f32> {
+        let base = *self.points.first()?;
+        let factor = match (self.reference_mode, self.reference_length) {
+            (false, _) => base.dist(cursor),
+            (true, Some(reference)) => base.dist(cursor) / reference,
+            (true, None) => return None,
+        };
+        (factor.is_finite() && factor > 0.0).then_some(factor)
+    }
+
+    /// Set the reference length, then ask for the new one
+    fn set_reference(&mut self, length: f32) -> PointResult {
+        if length <= 0.0 {
+            self.points.truncate(1);
+            return Self::need("Reference length must be positive. Specify reference length:");
+        }
+        self.reference_length = Some(length);
+        Self::need("Specify new length:")
+    }
+}
+
+impl Command for ScaleCommand {
+    fn name(&self) -> &'static str {
+        "SCALE"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::Manipulation
+    }
+
+    fn initial_prompt(&self) -> String {
+        "SCALE Specify base point:".to_string()
+    }
+
+    fn on_start(&mut self, ctx: &CommandContext) {
+        self.entity_ids = ctx.model.get_top_level_selected_ids(ctx.selected_ids);
+    }
+
+    fn options(&self) -> &[CommandOption] {
+        if self.points.len() == 1 && !self.reference_mode {
+            &[COPY, REFERENCE]
+        } else {
+            &[]
+        }
+    }
+
+    fn push_point(&mut self, pos: Vector2, ctx: &mut CommandContext) -> PointResult {
+        if self.points.is_empty() {
+            self.points.push(pos);
+            return self.factor_prompt();
+        }
+        let base = self.points[0];
+
+        if !self.reference_mode {
+            return self.apply(base.dist(pos), ctx);
+        }
+
+        match self.reference_length {
+            // New length is measured from the base point
+            Some(reference) => self.apply(base.dist(pos) / reference, ctx),
+            None => {
+                self.points.push(pos);
+                if self.points.len() == 3 {
+                    self.set_reference(self.points[1].dist(self.points[2]))
+                } else {
+                    Self::need("Specify second point:")
+                }
+            }
+        }
+    }
+
+    fn process_input(&mut self, input: &str, ctx: &mut CommandContext) -> InputResult {
+        if self.options().contains(&COPY) && input == COPY.key {
+            self.copy = !self.copy;
+            return InputResult::Parameter(self.factor_prompt());
+        }
+        if self.options().contains(&REFERENCE) && input == REFERENCE.key {
+            self.reference_mode = true;
+            return InputResult::Parameter(Self::need("Specify reference length or first point:"));
+        }
+
+        if let Some(pos) = parse_point(input) {
+            return InputResult::Point(self.push_point(pos, ctx));
+        }
+
+        if !self.points.is_empty()
+            && let Ok(value) = input.parse::<f32>()
+        {
+            let result = match (self.reference_mode, self.reference_length) {
+                (false, _) => self.apply(value, ctx),
+                (true, None) if self.points.len() == 1 => self.set_reference(value),
+                (true, Some(reference)) => self.apply(value / reference, ctx),
+                // Halfway through picking the reference by points
+                (true, None) => {
+                    return InputResult::Invalid(CommandError::MissingPrerequisite(
+                        "Specify the second reference point.".to_string(),
+                    ));
+                }
+            };
+            return InputResult::Parameter(result);
+        }
+
+        InputResult::Invalid(CommandError::invalid_input(input))
+    }
+
+    fn transform_preview(
+        &self,
+        cursor: Vector2,
+        _modifiers: InputModifiers,
+    ) -> Option<TransformPreview> {
+        Some(TransformPreview {
+            entity_ids: self.entity_ids.clone(),
+            transform: EntityTransform::Scale {
+                base: *self.points.first()?,
+                factor: self.factor(cursor)?,
+            },
+            copy: self.copy,
+        })
+    }
+
+    #[cfg(feature = "gui")]
+    fn draw_preview(
+        &self,
+        ctx: &crate::view::rendering::context::DrawContext,
+        points: &[Vector2],
+        current_cad: Vector2,
+    ) {
+        use eframe::egui;
+
+        let Some(&base) = points.first() else {
+            return;
+        };
+        if let [_, first] = points {
+            preview::draw_line_to_cursor(ctx, *first, current_cad);
+        } else {
+            preview::draw_line_to_cursor(ctx, base, current_cad);
+        }
+        ctx.painter.circle_stroke(
+            ctx.to_screen(base),
+            5.0,
+            egui::Stroke::new(2.0, egui::Color32::YELLOW),
+        );
+
+        if let Some(factor) = self.factor(current_cad) {
+            let cursor = ctx.to_screen(current_cad);
+            preview::draw_dimension_text(
+                ctx,
+                egui::pos2(cursor.x + 30.0, cursor.y - 14.0),
+                format!("x{:.3}", factor),
+            );
+        }
+    }
+
+    impl_command_common!(ScaleCommand);
+}
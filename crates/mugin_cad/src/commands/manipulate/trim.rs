@@ -1,5 +1,5 @@
-use crate::commands::{Command, CommandCategory, CommandContext, PointResult};
-use crate::model::math::geometry;
+use crate::commands::{Command, CommandCategory, CommandContext, CommandError, PointResult};
+use crate::model::math::segment;
 use crate::model::{Shape, Vector2};
 
 define_command!(TrimCommand);
@@ -25,7 +25,7 @@ impl Command for TrimCommand {
 
         for (i, entity) in ctx.model.entities.iter().enumerate() {
             if let Shape::Line(line) = &entity.shape {
-                let dist = geometry::point_to_line_distance(pos, line.start, line.end);
+                let dist = segment::distance(pos, line.start, line.end);
                 if dist < tolerance && dist < best_dist {
                     best_dist = dist;
                     best_line_idx = Some(i);
@@ -52,17 +52,14 @@ impl Command for TrimCommand {
 
                 match &entity.shape {
                     Shape::Line(other) => {
-                        if let Some(pt) = geometry::line_line_intersection(
-                            line.start,
-                            line.end,
-                            other.start,
-                            other.end,
-                        ) {
+                        if let Some(pt) =
+                            segment::intersection(line.start, line.end, other.start, other.end)
+                        {
                             intersections.push(pt);
                         }
                     }
                     Shape::Circle(circle) => {
-                        let pts = geometry::line_circle_intersection(
+                        let pts = segment::circle_intersections(
                             line.start,
                             line.end,
                             circle.center,
@@ -80,15 +77,13 @@ impl Command for TrimCommand {
                         for j in 0..4 {
                             let a = corners[j];
                             let b = corners[(j + 1) % 4];
-                            if let Some(pt) =
-                                geometry::line_line_intersection(line.start, line.end, a, b)
-                            {
+                            if let Some(pt) = segment::intersection(line.start, line.end, a, b) {
                                 intersections.push(pt);
                             }
                         }
                     }
                     Shape::Arc(arc) => {
-                        let pts = geometry::line_circle_intersection(
+                        let pts = segment::circle_intersections(
                             line.start, line.end, arc.center, arc.radius,
                         );
                         intersections.extend(pts);
@@ -105,17 +100,12 @@ impl Command for TrimCommand {
                 };
             }
 
-            let click_t = geometry::project_point_on_line(pos, line.start, line.end);
+            let click_t = segment::project(pos, line.start, line.end);
 
             // Sort intersections by parameter t along the line
             let mut intersection_ts: Vec<(f32, Vector2)> = intersections
                 .iter()
-                .map(|&pt| {
-                    (
-                        geometry::project_point_on_line(pt, line.start, line.end),
-                        pt,
-                    )
-                })
+                .map(|&pt| (segment::project(pt, line.start, line.end), pt))
                 .filter(|(t, _)| *t > 0.0 && *t < 1.0)
                 .collect();
 
@@ -141,27 +131,30 @@ impl Command for TrimCommand {
             }
 
             // Modify the line based on which side to trim
-            if let Shape::Line(ref mut line_entity) = ctx.model.entities[line_idx].shape {
-                match (left_intersection, right_intersection) {
-                    (Some(left), Some(_right)) => {
-                        line_entity.end = left;
-                    }
-                    (Some(left), None) => {
-                        line_entity.end = left;
-                    }
-                    (None, Some(right)) => {
-                        line_entity.start = right;
+            ctx.model.entities[line_idx].modify(|shape| {
+                if let Shape::Line(line_entity) = shape {
+                    match (left_intersection, right_intersection) {
+                        (Some(left), Some(_right)) => {
+                            line_entity.end = left;
+                        }
+                        (Some(left), None) => {
+                            line_entity.end = left;
+                        }
+                        (None, Some(right)) => {
+                            line_entity.start = right;
+                        }
+                        (None, None) => {}
                     }
-                    (None, None) => {}
                 }
-            }
+            });
 
             PointResult::NeedMore {
                 prompt: "Trimmed! Click another line or press Enter/Escape to exit:".to_string(),
             }
         } else {
-            PointResult::NeedMore {
-                prompt: "No line found. Click on a line to trim:".to_string(),
+            PointResult::Rejected {
+                error: CommandError::NothingThere { what: "line" },
+                prompt: "Click on a line to trim:".to_string(),
             }
         }
     }
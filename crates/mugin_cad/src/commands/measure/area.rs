@@ -1,3 +1,5 @@
+use crate::commands::measure::{Measurement, MeasurementKind};
+use crate::commands::output::TerminalLine;
 use crate::commands::{Command, CommandCategory, CommandContext, PointResult};
 use crate::model::Entity;
 use crate::model::Vector2;
@@ -19,15 +21,22 @@ impl Command for MeasureAreaCommand {
     }
 
     fn push_point(&mut self, pos: Vector2, ctx: &mut CommandContext) -> PointResult {
-        if let Some((_, vertices)) = geometry::find_closed_region(&ctx.model, pos) {
+        if let Some((_, vertices)) = geometry::find_closed_region(ctx.model, pos) {
             let area = geometry::calculate_polygon_area(&vertices);
             let centroid = geometry::calculate_centroid(&vertices);
 
             let annotation = crate::model::TextAnnotation::new_area(centroid, area, vertices);
-            ctx.model.add_entity(Entity::text(annotation));
+            ctx.report(TerminalLine::result(format!("Area: {:.2}", area)));
+            ctx.add_entity(Entity::text(annotation));
+            *ctx.last_measurement = Some(Measurement {
+                kind: MeasurementKind::Area,
+                value: area,
+                target: centroid,
+            });
 
             PointResult::Complete
         } else {
+            ctx.report(TerminalLine::warning("Region not closed or empty"));
             PointResult::NeedMore {
                 prompt: "Region not closed or empty. Try another point.".to_string(),
             }
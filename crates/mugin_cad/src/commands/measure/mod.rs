@@ -1,2 +1,36 @@
 pub mod area;
 pub mod perimeter;
+
+use crate::model::Vector2;
+use crate::model::dimension::DimensionStyle;
+
+/// What a measurement measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MeasurementKind {
+    Distance,
+    Area,
+    Perimeter,
+}
+
+/// Result of the last DISTANCE, AREA or PERIMETER run, kept by the
+/// executor for LABEL and the `lastresult` token in TEXT
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Measurement {
+    pub kind: MeasurementKind,
+    pub value: f32,
+    /// Point a label's leader runs to: the middle of a distance or the
+    /// centroid of a region
+    pub target: Vector2,
+}
+
+impl Measurement {
+    /// The value with the dimension style's precision and unit suffix.
+    /// Areas get a squared unit.
+    pub fn label(&self, style: &DimensionStyle) -> String {
+        let length = style.format_length(self.value);
+        match self.kind {
+            MeasurementKind::Area if !style.suffix.trim().is_empty() => format!("{}²", length),
+            _ => length,
+        }
+    }
+}
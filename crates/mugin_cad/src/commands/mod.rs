@@ -1,12 +1,15 @@
 #[macro_use]
 mod macros;
+#[cfg(feature = "gui")]
 pub mod preview;
 
 pub mod create;
+pub mod error;
 pub mod executor;
 pub mod io;
 pub mod manipulate;
 pub mod measure;
+pub mod output;
 pub mod utility;
 
 // Re-exports for easier access
@@ -17,15 +20,27 @@ pub use create::line;
 pub use create::rectangle;
 pub use create::text;
 
+pub use manipulate::calibrate;
 pub use manipulate::copy;
+pub use manipulate::mirror;
 pub use manipulate::r#move;
 pub use manipulate::offset;
 pub use manipulate::rotate;
+pub use manipulate::scale;
 pub use manipulate::trim;
 
 pub use utility::distance;
 
-use crate::model::{CadModel, Vector2};
+pub use error::CommandError;
+
+use crate::commands::measure::Measurement;
+use crate::commands::output::TerminalLine;
+use crate::model::dimension::DimensionStyle;
+use crate::model::drafting::DraftingDefaults;
+use crate::model::math::angle::AngleFormat;
+use crate::model::system::symbols::SymbolLibrary;
+use crate::model::tools::degenerate::GeometryLimits;
+use crate::model::{CadModel, Entity, Vector2};
 use std::collections::HashSet;
 
 /// Keyboard modifiers for input constraints
@@ -33,16 +48,67 @@ use std::collections::HashSet;
 pub struct InputModifiers {
     pub shift: bool,
     pub ctrl: bool,
+    pub alt: bool,
 }
 
 /// Context passed to commands for execution
 pub struct CommandContext<'a> {
     pub model: &'a mut CadModel,
     pub selected_ids: &'a HashSet<u64>,
-    pub filled_mode: bool,
+    /// Attributes for newly created entities
+    pub defaults: &'a DraftingDefaults,
+    /// Layer that new entities are placed on
+    pub layer_id: u64,
     pub modifiers: InputModifiers,
     pub active_column_type_id: Option<u64>,
     pub active_beam_type_id: Option<u64>,
+    /// Lines for the terminal log (results, warnings, errors)
+    pub output: &'a mut Vec<TerminalLine>,
+    /// Formats measured values
+    pub dimension_style: &'a DimensionStyle,
+    /// Smallest geometry the command may create
+    pub limits: &'a GeometryLimits,
+    /// Result of the last measuring command, set by those commands
+    pub last_measurement: &'a mut Option<Measurement>,
+    /// Symbols SYMBOL can place
+    pub symbols: &'a SymbolLibrary,
+    /// Symbol picked from the palette for the next SYMBOL run
+    pub active_symbol: Option<&'a str>,
+    /// Group being edited; new entities are added to it
+    pub group: Option<u64>,
+}
+
+impl CommandContext<'_> {
+    /// Write a line to the terminal log
+    pub fn report(&mut self, line: TerminalLine) {
+        self.output.push(line);
+    }
+
+    /// Add a newly created entity, stamping the current layer and default color.
+    /// Inside group editing it goes into the group. Returns the new entity's id.
+    pub fn add_entity(&mut self, mut entity: Entity) -> u64 {
+        entity.layer_id = self.layer_id;
+        entity.color = self.defaults.color;
+        let id = entity.id;
+        self.place(entity);
+        id
+    }
+
+    /// Refuse `entity` if its geometry is below the limits
+    pub fn check(&self, entity: &Entity) -> Result<(), CommandError> {
+        match self.limits.check(&entity.shape) {
+            Some(problem) => Err(CommandError::Degenerate(problem)),
+            None => Ok(()),
+        }
+    }
+
+    /// Add an entity as it is, into the edited group if there is one
+    pub fn place(&mut self, entity: Entity) {
+        match self.group {
+            Some(group) => self.model.add_child_entity(group, entity),
+            None => self.model.add_entity(entity),
+        }
+    }
 }
 
 /// Category of command - determines behavior and requirements
@@ -56,12 +122,29 @@ pub enum CommandCategory {
     Utility,
 }
 
+/// A choice offered in a command prompt, e.g. `[Close]` in LINE.
+/// Typing `key` (or clicking its chip in the terminal) triggers it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandOption {
+    /// Lowercase text that triggers the option, e.g. "c"
+    pub key: &'static str,
+    pub label: &'static str,
+}
+
+impl CommandOption {
+    pub const fn new(key: &'static str, label: &'static str) -> Self {
+        Self { key, label }
+    }
+}
+
 /// Result of processing a point in a command
 pub enum PointResult {
     /// Command needs more points
     NeedMore { prompt: String },
     /// Command is complete
     Complete,
+    /// The point was of no use; the command waits at `prompt` again
+    Rejected { error: CommandError, prompt: String },
 }
 
 /// Result of processing text input
@@ -71,7 +154,132 @@ pub enum InputResult {
     /// Input was handled as a parameter (e.g., radius)
     Parameter(PointResult),
     /// Input was not valid for this command
-    Invalid { message: String },
+    Invalid(CommandError),
+}
+
+/// Box around the entities a command carries with the cursor
+#[derive(Debug, Clone, PartialEq)]
+pub struct MovingBounds {
+    /// Cursor position at which the box is at `min`/`max`
+    pub base: Vector2,
+    pub min: Vector2,
+    pub max: Vector2,
+    /// Entities that move away from their place, so nothing aligns to them
+    pub moving_ids: Vec<u64>,
+}
+
+/// How a manipulation command moves the entities it carries
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EntityTransform {
+    Translate(Vector2),
+    Rotate {
+        pivot: Vector2,
+        angle: f32,
+    },
+    Scale {
+        base: Vector2,
+        factor: f32,
+    },
+    /// Reflection across the line through `pivot` along `axis`
+    Mirror {
+        pivot: Vector2,
+        axis: Vector2,
+    },
+}
+
+impl EntityTransform {
+    /// Apply to a top-level entity the way the command does
+    pub fn apply(&self, entity: &mut Entity) {
+        match *self {
+            EntityTransform::Translate(delta) => entity.translate(delta),
+            EntityTransform::Rotate { pivot, angle } => entity.rotate(pivot, angle),
+            EntityTransform::Scale { base, factor } => entity.scale(base, factor),
+            EntityTransform::Mirror { pivot, axis } => entity.mirror(pivot, axis),
+        }
+    }
+}
+
+/// Entities a manipulation command carries, and how the cursor would
+/// transform them
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransformPreview {
+    pub entity_ids: Vec<u64>,
+    pub transform: EntityTransform,
+    /// The originals stay put and a transformed copy is added
+    pub copy: bool,
+}
+
+/// Values typed next to the cursor for the next point (dynamic input)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DynamicInput {
+    /// Length and direction from `base`
+    Polar { base: Vector2 },
+    /// Width and height from the `base` corner
+    Size { base: Vector2 },
+    /// Distance from `center`
+    Radius { center: Vector2 },
+}
+
+impl DynamicInput {
+    /// Field labels, in Tab order
+    pub fn labels(&self) -> &'static [&'static str] {
+        match self {
+            Self::Polar { .. } => &["Length", "Angle"],
+            Self::Size { .. } => &["W", "H"],
+            Self::Radius { .. } => &["Radius"],
+        }
+    }
+
+    /// Field values for the cursor at `cursor`; angles in radians
+    pub fn values(&self, cursor: Vector2) -> Vec<f32> {
+        match *self {
+            Self::Polar { base } => {
+                let d = cursor - base;
+                vec![base.dist(cursor), d.y.atan2(d.x)]
+            }
+            Self::Size { base } => vec![cursor.x - base.x, cursor.y - base.y],
+            Self::Radius { center } => vec![center.dist(cursor)],
+        }
+    }
+
+    /// Whether `field` holds an angle
+    fn is_angle(&self, field: usize) -> bool {
+        matches!(self, Self::Polar { .. }) && field == 1
+    }
+
+    /// Text of a field value, angles in the configured format
+    pub fn field_text(&self, field: usize, value: f32, angles: &AngleFormat) -> String {
+        if self.is_angle(field) {
+            angles.format_direction(value)
+        } else {
+            format!("{:.2}", value)
+        }
+    }
+
+    /// Value typed into a field, angles in the configured format or any
+    /// unit they name
+    pub fn parse_field(&self, field: usize, text: &str, angles: &AngleFormat) -> Option<f32> {
+        if self.is_angle(field) {
+            angles.parse_direction(text)
+        } else {
+            text.trim().parse().ok()
+        }
+    }
+
+    /// Terminal input equivalent to the field values
+    pub fn terminal_input(&self, values: &[f32]) -> String {
+        match (*self, values) {
+            (Self::Polar { base }, &[length, angle]) => {
+                let (sin, cos) = angle.sin_cos();
+                format!("{},{}", base.x + length * cos, base.y + length * sin)
+            }
+            (Self::Size { base }, &[width, height]) => {
+                format!("{},{}", base.x + width, base.y + height)
+            }
+            (Self::Radius { .. }, &[radius]) => radius.to_string(),
+            _ => String::new(),
+        }
+    }
 }
 
 /// Trait that all CAD commands must implement
@@ -96,11 +304,11 @@ pub trait Command: std::fmt::Debug {
     /// Returns the initial prompt when command starts
     fn initial_prompt(&self) -> String;
 
-    /// Returns error message when can_execute fails
-    fn cannot_execute_message(&self) -> String {
+    /// Why the command cannot start, when can_execute fails
+    fn cannot_execute(&self) -> CommandError {
         match self.category() {
-            CommandCategory::Manipulation => "No entities selected.".to_string(),
-            _ => "Cannot execute command.".to_string(),
+            CommandCategory::Manipulation => CommandError::NothingSelected { what: "entities" },
+            _ => CommandError::MissingPrerequisite("Cannot execute command.".to_string()),
         }
     }
 
@@ -116,16 +324,16 @@ pub trait Command: std::fmt::Debug {
     ) -> Vector2 {
         // Default: no constraints
         // Override in specific commands for custom behavior
-        if modifiers.shift {
-            if let Some(last) = last_point {
-                // Ortho constraint: snap to horizontal or vertical
-                let dx = (pos.x - last.x).abs();
-                let dy = (pos.y - last.y).abs();
-                if dx > dy {
-                    return Vector2::new(pos.x, last.y);
-                } else {
-                    return Vector2::new(last.x, pos.y);
-                }
+        if modifiers.shift
+            && let Some(last) = last_point
+        {
+            // Ortho constraint: snap to horizontal or vertical
+            let dx = (pos.x - last.x).abs();
+            let dy = (pos.y - last.y).abs();
+            if dx > dy {
+                return Vector2::new(pos.x, last.y);
+            } else {
+                return Vector2::new(last.x, pos.y);
             }
         }
         pos
@@ -139,15 +347,51 @@ pub trait Command: std::fmt::Debug {
                 self.constrain_point(pos, self.get_points().last().copied(), ctx.modifiers);
             InputResult::Point(self.push_point(constrained, ctx))
         } else {
-            InputResult::Invalid {
-                message: format!("Invalid input \"{}\".", input),
-            }
+            InputResult::Invalid(CommandError::invalid_input(input))
         }
     }
 
+    /// Options available at the current step. Their keys are routed to
+    /// `process_input` ahead of command aliases and terminal shortcuts.
+    fn options(&self) -> &[CommandOption] {
+        &[]
+    }
+
+    /// Whether the current step reads free text, so typed words are not
+    /// taken for command aliases
+    fn takes_text(&self) -> bool {
+        false
+    }
+
     /// Get current points for preview drawing
     fn get_points(&self) -> &[Vector2];
 
+    /// Fields shown next to the cursor for the next point, if any
+    fn dynamic_input(&self) -> Option<DynamicInput> {
+        None
+    }
+
+    /// Entities following the cursor, for alignment guides
+    fn moving_bounds(&self) -> Option<MovingBounds> {
+        None
+    }
+
+    /// The selection captured at the start and how `cursor` would
+    /// transform it, once the command is waiting for its final point
+    fn transform_preview(
+        &self,
+        _cursor: Vector2,
+        _modifiers: InputModifiers,
+    ) -> Option<TransformPreview> {
+        None
+    }
+
+    /// Drop the points picked so far and start over, keeping the command
+    /// active. Returns false when no step is in progress.
+    fn reset_step(&mut self) -> bool {
+        false
+    }
+
     /// Called when command starts (for manipulation commands to capture initial state)
     fn on_start(&mut self, _ctx: &CommandContext) {}
 
@@ -161,6 +405,7 @@ pub trait Command: std::fmt::Debug {
     }
 
     /// Draw command preview
+    #[cfg(feature = "gui")]
     fn draw_preview(
         &self,
         _ctx: &crate::view::rendering::context::DrawContext,
@@ -181,3 +426,44 @@ pub fn parse_point(s: &str) -> Option<Vector2> {
     }
     None
 }
+
+/// Resolve typed relative and polar coordinates to a point: "@dx,dy" and
+/// "@dist<angle" from `last`, "dist<angle" from the origin, and "@" for
+/// `last` itself. Angles are directions in the `angles` format, or in
+/// any unit or as a bearing they name.
+/// `Ok(None)` for anything else, which the command reads as usual.
+pub fn resolve_coordinates(
+    s: &str,
+    last: Option<Vector2>,
+    angles: &AngleFormat,
+) -> Result<Option<Vector2>, CommandError> {
+    let s = s.trim();
+    let (origin, rest) = match s.strip_prefix('@') {
+        Some(rest) => (None, rest.trim()),
+        None => (Some(Vector2::new(0.0, 0.0)), s),
+    };
+    let offset = if rest.is_empty() && origin.is_none() {
+        Vector2::new(0.0, 0.0)
+    } else if let Some((dist, angle)) = rest.split_once('<') {
+        let (Ok(dist), Some(angle)) = (dist.trim().parse::<f32>(), angles.parse_direction(angle))
+        else {
+            return Err(CommandError::InvalidInput {
+                input: s.to_string(),
+                hint: Some("Polar input is distance<angle, e.g. 100<45."),
+            });
+        };
+        let (sin, cos) = angle.sin_cos();
+        Vector2::new(cos, sin) * dist
+    } else if origin.is_none() {
+        parse_point(rest).ok_or_else(|| CommandError::InvalidInput {
+            input: s.to_string(),
+            hint: Some("Relative input is @dx,dy or @distance<angle."),
+        })?
+    } else {
+        return Ok(None);
+    };
+    let origin = origin.or(last).ok_or_else(|| {
+        CommandError::MissingPrerequisite("No previous point to measure from.".to_string())
+    })?;
+    Ok(Some(origin + offset))
+}
@@ -0,0 +1,59 @@
+//! Lines written to the terminal log by the view model and by commands.
+
+/// How a terminal line is styled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    /// Something the user typed
+    Input,
+    Info,
+    Warning,
+    Error,
+    /// A measured value (distance, area, ...)
+    Result,
+}
+
+#[derive(Debug, Clone)]
+pub struct TerminalLine {
+    pub text: String,
+    pub kind: LineKind,
+}
+
+impl TerminalLine {
+    pub fn new(kind: LineKind, text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            kind,
+        }
+    }
+
+    pub fn input(text: impl Into<String>) -> Self {
+        Self::new(LineKind::Input, text)
+    }
+
+    pub fn info(text: impl Into<String>) -> Self {
+        Self::new(LineKind::Info, text)
+    }
+
+    pub fn warning(text: impl Into<String>) -> Self {
+        Self::new(LineKind::Warning, text)
+    }
+
+    pub fn error(text: impl Into<String>) -> Self {
+        Self::new(LineKind::Error, text)
+    }
+
+    pub fn result(text: impl Into<String>) -> Self {
+        Self::new(LineKind::Result, text)
+    }
+
+    /// Text with the kind's prefix, as shown and copied
+    pub fn display(&self) -> String {
+        match self.kind {
+            LineKind::Input => format!("> {}", self.text),
+            LineKind::Info => self.text.clone(),
+            LineKind::Warning => format!("Warning: {}", self.text),
+            LineKind::Error => format!("Error: {}", self.text),
+            LineKind::Result => format!("= {}", self.text),
+        }
+    }
+}
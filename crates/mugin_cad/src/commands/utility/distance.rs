@@ -1,3 +1,5 @@
+use crate::commands::measure::{Measurement, MeasurementKind};
+use crate::commands::output::TerminalLine;
 use crate::commands::{Command, CommandCategory, CommandContext, PointResult};
 use crate::model::shapes::annotation::TextAnnotation;
 use crate::model::{Entity, Vector2};
@@ -28,7 +30,16 @@ impl Command for DistanceCommand {
                 let start = self.points[0];
                 let end = self.points[1];
                 let annotation = TextAnnotation::new_distance(start, end);
-                ctx.model.add_entity(Entity::text(annotation));
+                ctx.report(TerminalLine::result(format!(
+                    "Distance: {:.2}",
+                    start.dist(end)
+                )));
+                ctx.add_entity(Entity::text(annotation));
+                *ctx.last_measurement = Some(Measurement {
+                    kind: MeasurementKind::Distance,
+                    value: start.dist(end),
+                    target: (start + end) * 0.5,
+                });
                 PointResult::Complete
             }
             _ => PointResult::Complete,
@@ -0,0 +1,66 @@
+//! Entity attributes as a CSV table, one row per entity.
+
+use crate::model::{CadModel, Entity};
+use std::collections::BTreeSet;
+
+/// Fixed columns before the attribute columns
+const BASE_COLUMNS: [&str; 4] = ["id", "name", "type", "layer"];
+
+/// CSV of every entity (children included) with one column per attribute
+/// name used anywhere in the model. Missing values are left empty.
+pub fn attributes_csv(model: &CadModel) -> String {
+    let mut entities = Vec::new();
+    for entity in &model.entities {
+        collect(entity, &mut entities);
+    }
+
+    let keys: BTreeSet<&str> = entities
+        .iter()
+        .flat_map(|e| e.attributes.keys().map(String::as_str))
+        .collect();
+
+    let mut csv = BASE_COLUMNS
+        .iter()
+        .copied()
+        .chain(keys.iter().copied())
+        .map(escape)
+        .collect::<Vec<_>>()
+        .join(",");
+    csv.push('\n');
+
+    for entity in entities {
+        let layer = model
+            .layer_manager
+            .get_layer(entity.layer_id)
+            .map(|layer| layer.name.as_str())
+            .unwrap_or("");
+        let id = entity.id.to_string();
+        let row: Vec<String> = [id.as_str(), &entity.name, entity.type_name(), layer]
+            .into_iter()
+            .chain(
+                keys.iter()
+                    .map(|key| entity.attributes.get(*key).map_or("", String::as_str)),
+            )
+            .map(escape)
+            .collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+fn collect<'a>(entity: &'a Entity, out: &mut Vec<&'a Entity>) {
+    out.push(entity);
+    for child in &entity.children {
+        collect(child, out);
+    }
+}
+
+/// Quote a field if it contains a delimiter, quote or line break
+pub(crate) fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
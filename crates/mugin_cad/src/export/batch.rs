@@ -0,0 +1,178 @@
+//! Exporting several drawings in one go: the open tabs, or the storeys of
+//! one drawing. Every sheet prints with the same settings, either to its
+//! own file or as a page of one merged file. A sheet that fails is recorded
+//! and the rest still print.
+
+use super::pdf::{PdfBook, PdfExporter};
+use super::settings::{ExportSettings, ExportSource};
+use crate::model::CadModel;
+use crate::model::dimension::DimensionStyle;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// File name pattern offered by default
+pub const DEFAULT_PATTERN: &str = "{project}-{tab}.pdf";
+
+/// One drawing of a batch
+#[derive(Clone)]
+pub struct BatchSheet {
+    /// Tab or storey name, put in for `{tab}`
+    pub name: String,
+    pub model: CadModel,
+}
+
+/// Where a batch is written
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BatchOutput {
+    /// A file per sheet in `directory`, named after `pattern`
+    Separate { directory: PathBuf, pattern: String },
+    /// Every sheet a page of one file
+    Merged(PathBuf),
+}
+
+/// What a batch wrote and which sheets failed
+#[derive(Debug, Default)]
+pub struct BatchReport {
+    pub written: Vec<PathBuf>,
+    /// Sheet name and what went wrong
+    pub failures: Vec<(String, String)>,
+    /// Pages in the merged file
+    pub pages: usize,
+    pub cancelled: bool,
+}
+
+/// The sheets of `model` per storey: each storey's members with everything
+/// not on a storey. Unassigned members print on every sheet.
+pub fn storey_sheets(model: &CadModel) -> Vec<BatchSheet> {
+    model
+        .storeys
+        .storeys
+        .iter()
+        .map(|storey| {
+            let mut sheet = model.clone();
+            sheet.storeys.active = Some(storey.id);
+            sheet
+                .entities
+                .retain(|entity| entity.on_active_storey(&sheet.storeys));
+            BatchSheet {
+                name: storey.name.clone(),
+                model: sheet,
+            }
+        })
+        .collect()
+}
+
+/// File name of a sheet: `pattern` with `{project}` and `{tab}` put in.
+/// Characters file systems reject become `_`, and `.pdf` is added when
+/// the pattern has no extension.
+pub fn file_name(pattern: &str, project: &str, sheet: &str) -> String {
+    let name = pattern
+        .replace("{project}", &sanitize(project))
+        .replace("{tab}", &sanitize(sheet));
+    let name = sanitize(&name);
+    if name.to_lowercase().ends_with(".pdf") {
+        name
+    } else {
+        format!("{}.pdf", name)
+    }
+}
+
+/// File names for every sheet, with " (2)", " (3)"… added to repeats so no
+/// sheet overwrites another
+pub fn file_names(pattern: &str, project: &str, sheets: &[BatchSheet]) -> Vec<String> {
+    let mut used = HashSet::new();
+    sheets
+        .iter()
+        .map(|sheet| {
+            let name = file_name(pattern, project, &sheet.name);
+            let stem = &name[..name.len() - ".pdf".len()];
+            let mut candidate = name.clone();
+            let mut copy = 1;
+            while !used.insert(candidate.to_lowercase()) {
+                copy += 1;
+                candidate = format!("{} ({}).pdf", stem, copy);
+            }
+            candidate
+        })
+        .collect()
+}
+
+fn sanitize(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect()
+}
+
+/// Where the sheets of a running batch go
+enum Writer<'a> {
+    Book(PdfBook, &'a Path),
+    Files(&'a Path, Vec<String>),
+}
+
+/// Print every sheet. `progress` is told how many sheets are done after
+/// each one and returns `false` to stop the batch. Regions picked on a
+/// tab do not carry over, so each sheet prints its whole drawing.
+pub fn export_batch(
+    sheets: &[BatchSheet],
+    project: &str,
+    output: &BatchOutput,
+    settings: &ExportSettings,
+    dim_style: &DimensionStyle,
+    mut progress: impl FnMut(usize) -> bool,
+) -> BatchReport {
+    let settings = ExportSettings {
+        source: ExportSource::ModelBounds,
+        ..settings.clone()
+    };
+    let mut writer = match output {
+        BatchOutput::Merged(path) => Writer::Book(PdfBook::new(&settings), path),
+        BatchOutput::Separate { directory, pattern } => {
+            Writer::Files(directory, file_names(pattern, project, sheets))
+        }
+    };
+
+    let mut report = BatchReport::default();
+    for (index, sheet) in sheets.iter().enumerate() {
+        let result = if sheet.model.entities.is_empty() {
+            Err("nothing to print".to_string())
+        } else {
+            match &mut writer {
+                Writer::Book(book, _) => book
+                    .add_page(&sheet.model, &settings, dim_style)
+                    .map(|()| report.pages += 1)
+                    .map_err(|e| e.to_string()),
+                Writer::Files(directory, names) => {
+                    let path = directory.join(&names[index]);
+                    PdfExporter::export_to_file(&sheet.model, &settings, dim_style, &path)
+                        .map(|()| report.written.push(path))
+                        .map_err(|e| e.to_string())
+                }
+            }
+        };
+        if let Err(error) = result {
+            report.failures.push((sheet.name.clone(), error));
+        }
+        if !progress(index + 1) {
+            report.cancelled = true;
+            return report;
+        }
+    }
+
+    if let Writer::Book(book, path) = writer
+        && report.pages > 0
+    {
+        match book.save(path) {
+            Ok(()) => report.written.push(path.to_path_buf()),
+            Err(e) => report.failures.push((
+                path.display().to_string(),
+                format!("could not write the merged file: {}", e),
+            )),
+        }
+    }
+    report
+}
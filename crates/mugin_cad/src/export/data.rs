@@ -0,0 +1,442 @@
+//! Entity geometry as CSV or JSON, one row (or object) per entity.
+//!
+//! Coordinates are in world space. Rows are built by [`data_rows`] so the
+//! CSV and JSON writers share one column mapping.
+
+use crate::model::{CadModel, Entity, EntityStyle, Shape, Vector2};
+use serde_json::{Value, json};
+use std::collections::HashSet;
+
+/// Output file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DataFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+impl DataFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            DataFormat::Csv => "csv",
+            DataFormat::Json => "json",
+        }
+    }
+}
+
+/// What goes into a data export
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DataExportOptions {
+    pub format: DataFormat,
+    /// Only the selected entities and their children
+    pub selected_only: bool,
+    /// Drop empty containers and parent links, leaving a flat list of geometry
+    pub flatten: bool,
+}
+
+/// CSV header, in column order
+pub const COLUMNS: [&str; 19] = [
+    "id",
+    "parent_id",
+    "type",
+    "layer",
+    "x1",
+    "y1",
+    "x2",
+    "y2",
+    "cx",
+    "cy",
+    "r",
+    "corners",
+    "column_type",
+    "label",
+    "color",
+    "line_style",
+    "stroke_width",
+    "risers",
+    "holes",
+];
+
+/// Type-specific fields of a row
+#[derive(Debug, Clone, PartialEq)]
+pub enum Geometry {
+    None,
+    Segment {
+        start: Vector2,
+        end: Vector2,
+    },
+    Circle {
+        center: Vector2,
+        radius: f32,
+    },
+    Corners(Vec<Vector2>),
+    Text {
+        position: Vector2,
+        text: String,
+    },
+    Column {
+        corners: Vec<Vector2>,
+        column_type: String,
+        label: String,
+    },
+    Beam {
+        start: Vector2,
+        end: Vector2,
+        label: String,
+    },
+    Stair {
+        corners: Vec<Vector2>,
+        risers: u32,
+    },
+    Flooring {
+        corners: Vec<Vector2>,
+        holes: Vec<Vec<Vector2>>,
+    },
+}
+
+/// One exported entity
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataRow {
+    pub id: u64,
+    pub parent_id: Option<u64>,
+    pub type_name: &'static str,
+    pub layer: String,
+    /// Color, line style and width with "by layer" values resolved
+    pub style: EntityStyle,
+    pub geometry: Geometry,
+    /// How many of the following rows are this row's descendants
+    descendants: usize,
+}
+
+/// Rows for every exported entity, parents before their children
+pub fn data_rows(
+    model: &CadModel,
+    selected_ids: &HashSet<u64>,
+    options: &DataExportOptions,
+) -> Vec<DataRow> {
+    let mut rows = Vec::new();
+    for entity in &model.entities {
+        collect(model, entity, None, selected_ids, options, false, &mut rows);
+    }
+    rows
+}
+
+fn collect(
+    model: &CadModel,
+    entity: &Entity,
+    parent_id: Option<u64>,
+    selected_ids: &HashSet<u64>,
+    options: &DataExportOptions,
+    parent_selected: bool,
+    rows: &mut Vec<DataRow>,
+) {
+    let included = !options.selected_only || parent_selected || selected_ids.contains(&entity.id);
+    let keep = included && !(options.flatten && matches!(entity.shape, Shape::None));
+
+    let index = rows.len();
+    if keep {
+        let layer = model.layer_manager.get_layer(entity.layer_id);
+        rows.push(DataRow {
+            id: entity.id,
+            parent_id: if options.flatten { None } else { parent_id },
+            type_name: entity.shape.type_name(),
+            layer: layer.map(|layer| layer.name.clone()).unwrap_or_default(),
+            style: entity.style(layer),
+            geometry: geometry(model, entity),
+            descendants: 0,
+        });
+    }
+
+    // Children of a row that was left out become roots of the export
+    let child_parent = keep.then_some(entity.id);
+    for child in &entity.children {
+        collect(
+            model,
+            child,
+            child_parent,
+            selected_ids,
+            options,
+            included,
+            rows,
+        );
+    }
+    if keep {
+        rows[index].descendants = rows.len() - index - 1;
+    }
+}
+
+fn geometry(model: &CadModel, entity: &Entity) -> Geometry {
+    let world =
+        |p: Vector2| -> Vector2 { entity.world_transform.transform_point2(p.into()).into() };
+    match &entity.shape {
+        Shape::None => Geometry::None,
+        Shape::Line(line) => Geometry::Segment {
+            start: world(line.start),
+            end: world(line.end),
+        },
+        Shape::Circle(circle) => Geometry::Circle {
+            center: world(circle.center),
+            radius: circle.radius,
+        },
+        Shape::Arc(arc) => Geometry::Circle {
+            center: world(arc.center),
+            radius: arc.radius,
+        },
+        Shape::Rectangle(rect) => Geometry::Corners(
+            [
+                rect.min,
+                Vector2::new(rect.max.x, rect.min.y),
+                rect.max,
+                Vector2::new(rect.min.x, rect.max.y),
+            ]
+            .into_iter()
+            .map(world)
+            .collect(),
+        ),
+        Shape::Text(text) => Geometry::Text {
+            position: world(text.position),
+            text: text.text.clone(),
+        },
+        Shape::Column(column) => Geometry::Column {
+            corners: column.get_corners().into_iter().map(world).collect(),
+            column_type: model
+                .definitions
+                .get_column_type(column.column_type_id)
+                .map(|t| t.name.clone())
+                .unwrap_or_default(),
+            label: column.label.clone(),
+        },
+        Shape::Beam(beam) => Geometry::Beam {
+            start: world(beam.start),
+            end: world(beam.end),
+            label: beam.label.clone(),
+        },
+        Shape::Image(image) => Geometry::Corners(image.corners().into_iter().map(world).collect()),
+        Shape::Section(section) => Geometry::Segment {
+            start: world(section.start),
+            end: world(section.end),
+        },
+        Shape::Flooring(floor) => Geometry::Flooring {
+            corners: floor.boundary.iter().copied().map(world).collect(),
+            holes: floor
+                .holes
+                .iter()
+                .map(|hole| hole.iter().copied().map(world).collect())
+                .collect(),
+        },
+        Shape::RevisionCloud(cloud) => {
+            Geometry::Corners(cloud.boundary.iter().copied().map(world).collect())
+        }
+        Shape::Room(room) => Geometry::Corners(room.boundary.iter().copied().map(world).collect()),
+        Shape::Sketch(sketch) => {
+            Geometry::Corners(sketch.points.iter().copied().map(world).collect())
+        }
+        Shape::Stair(stair) => Geometry::Stair {
+            corners: stair.corners().into_iter().map(world).collect(),
+            risers: stair.risers,
+        },
+    }
+}
+
+/// `#rrggbb` of the resolved color
+fn hex_color(style: EntityStyle) -> String {
+    let [r, g, b, _] = style.color.to_array();
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+// ── CSV ─────────────────────────────────────────────────────
+
+/// Field values for one row, in [`COLUMNS`] order
+pub fn csv_fields(row: &DataRow) -> [String; COLUMNS.len()] {
+    let mut fields: [String; COLUMNS.len()] = Default::default();
+    fields[0] = row.id.to_string();
+    fields[1] = row.parent_id.map(|id| id.to_string()).unwrap_or_default();
+    fields[2] = row.type_name.to_string();
+    fields[3] = row.layer.clone();
+    fields[14] = hex_color(row.style);
+    fields[15] = row.style.line_style.label().to_string();
+    fields[16] = row.style.stroke_width.to_string();
+
+    let segment = |fields: &mut [String], start: Vector2, end: Vector2| {
+        fields[4] = start.x.to_string();
+        fields[5] = start.y.to_string();
+        fields[6] = end.x.to_string();
+        fields[7] = end.y.to_string();
+    };
+    let corners = |corners: &[Vector2]| {
+        corners
+            .iter()
+            .map(|p| format!("{} {}", p.x, p.y))
+            .collect::<Vec<_>>()
+            .join(";")
+    };
+
+    match &row.geometry {
+        Geometry::None => {}
+        Geometry::Segment { start, end } => segment(&mut fields, *start, *end),
+        Geometry::Circle { center, radius } => {
+            fields[8] = center.x.to_string();
+            fields[9] = center.y.to_string();
+            fields[10] = radius.to_string();
+        }
+        Geometry::Corners(points) => fields[11] = corners(points),
+        Geometry::Text { position, text } => {
+            fields[4] = position.x.to_string();
+            fields[5] = position.y.to_string();
+            fields[13] = text.clone();
+        }
+        Geometry::Column {
+            corners: points,
+            column_type,
+            label,
+        } => {
+            fields[11] = corners(points);
+            fields[12] = column_type.clone();
+            fields[13] = label.clone();
+        }
+        Geometry::Beam { start, end, label } => {
+            segment(&mut fields, *start, *end);
+            fields[13] = label.clone();
+        }
+        Geometry::Flooring {
+            corners: points,
+            holes,
+        } => {
+            fields[11] = corners(points);
+            fields[18] = holes
+                .iter()
+                .map(|hole| corners(hole))
+                .collect::<Vec<_>>()
+                .join("|");
+        }
+        Geometry::Stair {
+            corners: points,
+            risers,
+        } => {
+            fields[11] = corners(points);
+            fields[17] = risers.to_string();
+        }
+    }
+    fields
+}
+
+/// CSV table with a [`COLUMNS`] header. Corner lists are `x y` pairs
+/// separated by semicolons; slab openings are corner lists separated by
+/// `|`.
+pub fn data_csv(rows: &[DataRow]) -> String {
+    let mut csv = COLUMNS.join(",");
+    csv.push('\n');
+    for row in rows {
+        let fields: Vec<String> = csv_fields(row).iter().map(|f| escape(f)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Quote a field if it contains a delimiter, quote or line break
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+// ── JSON ────────────────────────────────────────────────────
+
+/// JSON object for one row, without children
+pub fn json_object(row: &DataRow) -> Value {
+    let point = |p: Vector2| json!([p.x, p.y]);
+    let points = |ps: &[Vector2]| Value::Array(ps.iter().copied().map(point).collect());
+
+    let mut object = json!({
+        "id": row.id,
+        "parent_id": row.parent_id,
+        "type": row.type_name,
+        "layer": row.layer,
+        "color": hex_color(row.style),
+        "line_style": row.style.line_style.label(),
+        "stroke_width": row.style.stroke_width,
+    });
+    let fields = match &row.geometry {
+        Geometry::None => json!({}),
+        Geometry::Segment { start, end } => json!({
+            "x1": start.x, "y1": start.y, "x2": end.x, "y2": end.y,
+        }),
+        Geometry::Circle { center, radius } => json!({
+            "cx": center.x, "cy": center.y, "r": radius,
+        }),
+        Geometry::Corners(corners) => json!({ "corners": points(corners) }),
+        Geometry::Text { position, text } => json!({
+            "x": position.x, "y": position.y, "text": text,
+        }),
+        Geometry::Column {
+            corners,
+            column_type,
+            label,
+        } => json!({
+            "corners": points(corners),
+            "column_type": column_type,
+            "label": label,
+        }),
+        Geometry::Beam { start, end, label } => json!({
+            "x1": start.x, "y1": start.y, "x2": end.x, "y2": end.y, "label": label,
+        }),
+        Geometry::Flooring { corners, holes } => json!({
+            "corners": points(corners),
+            "holes": Value::Array(holes.iter().map(|hole| points(hole)).collect()),
+        }),
+        Geometry::Stair { corners, risers } => json!({
+            "corners": points(corners),
+            "risers": risers,
+        }),
+    };
+    if let (Value::Object(object), Value::Object(fields)) = (&mut object, fields) {
+        object.extend(fields);
+    }
+    object
+}
+
+/// JSON array of entities. Unless flattened, children nest under their
+/// parent's `children` array.
+pub fn data_json(rows: &[DataRow], flatten: bool) -> String {
+    let value = if flatten {
+        Value::Array(rows.iter().map(json_object).collect())
+    } else {
+        Value::Array(nest(rows))
+    };
+    serde_json::to_string_pretty(&value).unwrap_or_default()
+}
+
+/// Turn a parents-first row list back into a tree of objects
+fn nest(rows: &[DataRow]) -> Vec<Value> {
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < rows.len() {
+        let row = &rows[i];
+        let children = &rows[i + 1..i + 1 + row.descendants];
+        let mut object = json_object(row);
+        if !children.is_empty()
+            && let Value::Object(map) = &mut object
+        {
+            map.insert("children".to_string(), Value::Array(nest(children)));
+        }
+        out.push(object);
+        i += 1 + row.descendants;
+    }
+    out
+}
+
+/// Serialize the model in the chosen format
+pub fn export_data(
+    model: &CadModel,
+    selected_ids: &HashSet<u64>,
+    options: &DataExportOptions,
+) -> String {
+    let rows = data_rows(model, selected_ids, options);
+    match options.format {
+        DataFormat::Csv => data_csv(&rows),
+        DataFormat::Json => data_json(&rows, options.flatten),
+    }
+}
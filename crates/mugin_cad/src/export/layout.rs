@@ -0,0 +1,176 @@
+//! Where the drawing lands on the page, shared by the PDF exporter and
+//! the export preview so both agree.
+//!
+//! Page coordinates are millimetres from the bottom-left corner, Y up.
+
+use super::settings::{ExportSettings, ExportSource, PageOrientation, ScaleType};
+use crate::model::Vector2;
+
+/// Millimetres in one drawing unit; drawings are in centimetres
+pub const MM_PER_UNIT: f32 = 10.0;
+
+/// Preferred length of the scale bar on paper
+const SCALE_BAR_MM: f32 = 50.0;
+
+/// Drawing placed on a page
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageLayout {
+    /// Page width and height, after orientation
+    pub page: Vector2,
+    pub margin: f32,
+    /// Drawing area being printed, in world units
+    pub bounds: (Vector2, Vector2),
+    /// Page millimetres per world unit
+    pub scale: f32,
+    /// Page position of the bottom-left of `bounds`
+    pub offset: Vector2,
+}
+
+/// Graphic scale bar, in page millimetres
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScaleBar {
+    /// Left end of the bar
+    pub origin: Vector2,
+    pub length: f32,
+    pub height: f32,
+    /// Alternately filled parts of the bar
+    pub divisions: usize,
+    /// Real length the bar stands for, e.g. "5 m"
+    pub label: String,
+    /// Scale of the print, e.g. "1:50"
+    pub ratio: String,
+}
+
+impl ExportSettings {
+    /// Page width and height in millimetres, after orientation
+    pub fn page_dimensions(&self) -> (f32, f32) {
+        let (w, h) = self.page_size.dimensions_mm();
+        match self.orientation {
+            PageOrientation::Landscape => (h, w),
+            PageOrientation::Portrait => (w, h),
+        }
+    }
+
+    /// Place the drawing on the page, centered in the printable area.
+    /// `model_bounds` is used when printing all entities.
+    pub fn layout(&self, model_bounds: (Vector2, Vector2)) -> PageLayout {
+        let (w, h) = self.page_dimensions();
+        let bounds = match self.source {
+            ExportSource::ModelBounds => model_bounds,
+            ExportSource::Viewport(min, max) => (min, max),
+        };
+        let size = bounds.1 - bounds.0;
+        let print_w = w - self.margin_mm * 2.0;
+        let print_h = h - self.margin_mm * 2.0;
+
+        let scale = match self.scale_type {
+            ScaleType::FitToPage => (print_w / size.x).min(print_h / size.y),
+            ScaleType::Standard(ratio) | ScaleType::Custom(ratio) => MM_PER_UNIT / ratio,
+        };
+        let offset = Vector2::new(
+            self.margin_mm + (print_w - size.x * scale) / 2.0,
+            self.margin_mm + (print_h - size.y * scale) / 2.0,
+        );
+        PageLayout {
+            page: Vector2::new(w, h),
+            margin: self.margin_mm,
+            bounds,
+            scale,
+            offset,
+        }
+    }
+}
+
+impl PageLayout {
+    /// Page position of world point `p`
+    pub fn to_page(&self, p: Vector2) -> Vector2 {
+        self.offset + (p - self.bounds.0) * self.scale
+    }
+
+    /// N of the 1:N the drawing prints at
+    pub fn ratio(&self) -> f32 {
+        MM_PER_UNIT / self.scale
+    }
+
+    /// Parts of the drawing outside the printable area, as page rectangles
+    /// (min, max). Empty when everything fits.
+    pub fn clipped(&self) -> Vec<(Vector2, Vector2)> {
+        let lo = self.to_page(self.bounds.0);
+        let hi = self.to_page(self.bounds.1);
+        let (p_lo, p_hi) = (
+            Vector2::new(self.margin, self.margin),
+            self.page - Vector2::new(self.margin, self.margin),
+        );
+        // Tolerate rounding when the drawing just fits
+        let eps = 0.01;
+        let mut rects = Vec::new();
+        if lo.x < p_lo.x - eps {
+            rects.push((lo, Vector2::new(p_lo.x, hi.y)));
+        }
+        if hi.x > p_hi.x + eps {
+            rects.push((Vector2::new(p_hi.x, lo.y), hi));
+        }
+        let (x0, x1) = (lo.x.max(p_lo.x), hi.x.min(p_hi.x));
+        if lo.y < p_lo.y - eps {
+            rects.push((Vector2::new(x0, lo.y), Vector2::new(x1, p_lo.y)));
+        }
+        if hi.y > p_hi.y + eps {
+            rects.push((Vector2::new(x0, p_hi.y), Vector2::new(x1, hi.y)));
+        }
+        rects
+    }
+
+    /// A scale bar of a round length in the bottom-right corner of the
+    /// printable area
+    pub fn scale_bar(&self) -> ScaleBar {
+        let real = round_length(SCALE_BAR_MM / self.scale);
+        let length = real * self.scale;
+        let height = 2.0;
+        ScaleBar {
+            origin: Vector2::new(
+                self.page.x - self.margin - length,
+                self.margin + height + 4.0,
+            ),
+            length,
+            height,
+            divisions: 4,
+            label: format_length(real),
+            ratio: format!("1:{}", format_ratio(self.ratio())),
+        }
+    }
+}
+
+/// The largest 1, 2 or 5 times a power of ten not above `max`
+fn round_length(max: f32) -> f32 {
+    if max <= 0.0 || !max.is_finite() {
+        return 1.0;
+    }
+    let power = 10f32.powf(max.log10().floor());
+    [5.0, 2.0, 1.0]
+        .into_iter()
+        .map(|step| step * power)
+        .find(|&length| length <= max * 1.0001)
+        .unwrap_or(power)
+}
+
+/// A world length in centimetres as a label, in metres from 1 m up
+fn format_length(cm: f32) -> String {
+    if cm >= 100.0 {
+        format!("{} m", trim(cm / 100.0))
+    } else {
+        format!("{} cm", trim(cm))
+    }
+}
+
+fn format_ratio(ratio: f32) -> String {
+    if ratio >= 10.0 {
+        format!("{:.0}", ratio)
+    } else {
+        trim(ratio)
+    }
+}
+
+fn trim(value: f32) -> String {
+    let text = format!("{:.2}", value);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
@@ -1,2 +1,8 @@
+pub mod attributes;
+pub mod batch;
+pub mod data;
+pub mod layout;
 pub mod pdf;
+pub mod rooms;
 pub mod settings;
+pub mod thumbnail;
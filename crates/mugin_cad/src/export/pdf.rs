@@ -1,86 +1,187 @@
-use super::settings::{ExportSettings, ExportSource, PageOrientation, ScaleType};
-use crate::model::{CadModel, Shape, Vector2};
+use super::layout::ScaleBar;
+use super::settings::ExportSettings;
+use crate::model::backup::temp_path;
+use crate::model::dimension::{DimensionLayout, DimensionStyle};
+use crate::model::layer::{LayerManager, LineStyle};
+use crate::model::shapes::line::LABEL_UNIT_PX;
+use crate::model::{CadModel, Entity, Geometry, Shape, Vector2};
 use printpdf::*;
 use std::fs::File;
 use std::io::BufWriter;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 pub struct PdfExporter;
 
-impl PdfExporter {
-    pub fn export_to_file(
-        model: &CadModel,
-        settings: &ExportSettings,
-        path: &Path,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        // Setup page size
-        let (mut width_mm, mut height_mm) = settings.page_size.dimensions_mm();
-        if matches!(settings.orientation, PageOrientation::Landscape) {
-            std::mem::swap(&mut width_mm, &mut height_mm);
-        }
+/// A PDF put together one page at a time, every page the same size
+pub struct PdfBook {
+    doc: PdfDocumentReference,
+    /// Page the document was created with, until something is drawn on it
+    first_page: Option<(PdfPageIndex, PdfLayerIndex)>,
+    page_mm: (f32, f32),
+}
 
+impl PdfBook {
+    pub fn new(settings: &ExportSettings) -> Self {
+        let (width_mm, height_mm) = settings.page_dimensions();
         let (doc, page1, layer1) = PdfDocument::new(
             "CAD Export",
             Mm(width_mm as f64),
             Mm(height_mm as f64),
             "CAD Layer",
         );
-        let current_layer = doc.get_page(page1).get_layer(layer1);
-
-        // 1. Calculate Source Bounds
-        let (min_bound, max_bound) = match settings.source {
-            ExportSource::ModelBounds => model.bounds(),
-            ExportSource::Viewport(min, max) => (min, max),
-        };
+        Self {
+            doc,
+            first_page: Some((page1, layer1)),
+            page_mm: (width_mm, height_mm),
+        }
+    }
 
-        let content_width = max_bound.x - min_bound.x;
-        let content_height = max_bound.y - min_bound.y;
+    /// Print `model` on the next page
+    pub fn add_page(
+        &mut self,
+        model: &CadModel,
+        settings: &ExportSettings,
+        dim_style: &DimensionStyle,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (page, layer) = self.first_page.take().unwrap_or_else(|| {
+            let (w, h) = self.page_mm;
+            self.doc.add_page(Mm(w as f64), Mm(h as f64), "CAD Layer")
+        });
+        let layer = self.doc.get_page(page).get_layer(layer);
+        PdfExporter::draw_page(&self.doc, &layer, model, settings, dim_style)
+    }
 
-        // 2. Calculate Scale
-        let margin = settings.margin_mm;
-        let print_width = width_mm - margin * 2.0;
-        let print_height = height_mm - margin * 2.0;
+    pub fn save(self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let mut file = BufWriter::new(File::create(path)?);
+        self.doc.save(&mut file)?;
+        Ok(())
+    }
+}
 
-        let scale = match settings.scale_type {
-            ScaleType::FitToPage => {
-                let scale_x = print_width / content_width;
-                let scale_y = print_height / content_height;
-                scale_x.min(scale_y)
-            }
-            ScaleType::Standard(ratio) => 1.0 / ratio,
-            ScaleType::Custom(s) => 1.0 / s,
-        };
+impl PdfExporter {
+    pub fn export_to_file(
+        model: &CadModel,
+        settings: &ExportSettings,
+        dim_style: &DimensionStyle,
+        path: &Path,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut book = PdfBook::new(settings);
+        book.add_page(model, settings, dim_style)?;
+        book.save(path)
+    }
 
-        // Center content
-        let scaled_w = content_width * scale;
-        let scaled_h = content_height * scale;
+    /// Export through a temporary file beside `path`, renamed over it once
+    /// complete unless `cancel` was raised meanwhile. A cancelled or failed
+    /// export leaves the file already at `path` alone. Returns whether
+    /// `path` was written.
+    pub fn export_replacing(
+        model: &CadModel,
+        settings: &ExportSettings,
+        dim_style: &DimensionStyle,
+        path: &Path,
+        cancel: &AtomicBool,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let temp = temp_path(path);
+        let result = Self::export_to_file(model, settings, dim_style, &temp);
+        if result.is_err() || cancel.load(Ordering::Relaxed) {
+            let _ = std::fs::remove_file(&temp);
+            return result.map(|()| false);
+        }
+        std::fs::rename(&temp, path)?;
+        Ok(true)
+    }
 
-        let offset_x = margin + (print_width - scaled_w) / 2.0;
-        let offset_y = margin + (print_height - scaled_h) / 2.0;
+    fn draw_page(
+        doc: &PdfDocumentReference,
+        current_layer: &PdfLayerReference,
+        model: &CadModel,
+        settings: &ExportSettings,
+        dim_style: &DimensionStyle,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // 1. Place what prints on the page
+        let layout = settings.layout(model.plot_bounds());
+        let scale = layout.scale;
 
         // Transform function: CAD (x,y) -> PDF (mm, mm)
         let transform = |p: Vector2| -> (f64, f64) {
-            let rel_x = p.x - min_bound.x;
-            let rel_y = p.y - min_bound.y;
-            let pdf_x = offset_x + rel_x * scale;
-            let pdf_y = offset_y + rel_y * scale;
-            (pdf_x as f64, pdf_y as f64)
+            let p = layout.to_page(p);
+            (p.x as f64, p.y as f64)
         };
 
         // 3. Draw Entities
-        let outline_color = Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None));
-        current_layer.set_outline_color(outline_color);
-        current_layer.set_outline_thickness(0.5);
+        let weights = &settings.line_weights;
+        Self::set_stroke(current_layer, weights.annotation, LineStyle::Solid);
+
+        // Dimension text size: world height -> mm on paper -> pt
+        let dim_font_pt = (dim_style.text_height * scale) as f64 * 72.0 / 25.4;
+        // Rough Helvetica advance, used to center labels
+        let text_half_width =
+            |text: &str| text.chars().count() as f32 * dim_style.text_height * 0.28;
 
-        for entity in &model.entities {
+        let mut entities = Vec::new();
+        flatten(&model.entities, &model.layer_manager, &mut entities);
+        for entity in entities {
+            let layer = model.layer_manager.get_layer(entity.layer_id);
+            // Shapes of grouped entities are relative to their group
+            let world = |p: Vector2| -> Vector2 {
+                entity.world_transform.transform_point2(p.into()).into()
+            };
+            let color = pdf_color(settings.print_color(entity, layer));
+            current_layer.set_outline_color(color.clone());
+            current_layer.set_fill_color(color);
             match &entity.shape {
+                Shape::Text(text) if text.is_dimension() => {
+                    let (p1, p2) = (world(text.anchor_points[0]), world(text.anchor_points[1]));
+                    let label = text.dimension_label(dim_style, p1.dist(p2));
+                    let half_w = text_half_width(&label);
+
+                    if let Some((offset, label_t)) =
+                        text.dimension_offset(dim_style.text_height * 0.85)
+                        && let Some(layout) =
+                            dim_style.layout(p1, p2, offset, label_t, half_w * 1.1)
+                    {
+                        Self::draw_dimension(current_layer, &layout, dim_style, &transform);
+                    }
+
+                    let font = doc.add_builtin_font(BuiltinFont::Helvetica)?;
+                    let pos = transform(world(text.position) - Vector2::new(half_w, 0.0));
+                    current_layer.use_text(label, dim_font_pt, Mm(pos.0), Mm(pos.1), &font);
+                }
                 Shape::Text(text) => {
-                    let pos = transform(text.position);
+                    if let Some(target) = text.leader_target() {
+                        let point = |p| {
+                            let (x, y) = transform(p);
+                            (Point::new(Mm(x), Mm(y)), false)
+                        };
+                        current_layer.add_shape(Line {
+                            points: vec![point(world(target)), point(world(text.position))],
+                            is_closed: false,
+                            has_fill: false,
+                            has_stroke: true,
+                            is_clipping_path: false,
+                        });
+                    }
+                    let pos = transform(world(text.position));
                     let font = doc.add_builtin_font(BuiltinFont::Helvetica).unwrap();
                     current_layer.use_text(text.text.clone(), 10.0, Mm(pos.0), Mm(pos.1), &font);
                 }
+                Shape::None => {}
                 _ => {
-                    let polyline = entity.as_polyline();
+                    let style = entity.style(layer);
+                    let weight = weights.weight(style.stroke_width, entity.layer_id);
+                    Self::set_stroke(current_layer, weight, style.line_style);
+
+                    // Filled arcs print as the same pie slice the canvas shows
+                    let (polyline, is_closed): (Vec<Vector2>, bool) = match &entity.shape {
+                        Shape::Arc(arc) if arc.filled => {
+                            (arc.sector(24).into_iter().map(world).collect(), true)
+                        }
+                        shape => (
+                            shape.as_polyline().into_iter().map(world).collect(),
+                            shape.is_closed(),
+                        ),
+                    };
                     let points: Vec<(Point, bool)> = polyline
                         .iter()
                         .map(|p| {
@@ -91,20 +192,210 @@ impl PdfExporter {
 
                     let shape = Line {
                         points,
-                        is_closed: entity.is_closed(),
-                        has_fill: entity.is_filled(),
+                        is_closed,
+                        has_fill: entity.shape.is_filled(),
                         has_stroke: true,
                         is_clipping_path: false,
                     };
 
                     current_layer.add_shape(shape);
+                    // Dimensions stay thin and solid
+                    Self::set_stroke(current_layer, weights.annotation, LineStyle::Solid);
+
+                    if let Shape::Line(line) = &entity.shape
+                        && line.show_length
+                    {
+                        // On paper take zoom = 1
+                        let unit = LABEL_UNIT_PX;
+                        let perp = line.dimension_offset(unit);
+                        let label_pos = line.label_position(unit);
+                        let len = line.length();
+                        if len > 0.001 {
+                            let u = (line.end - line.start) / len;
+                            let label_t = (label_pos - (line.start + perp)).dot(u);
+                            if let Some(layout) =
+                                dim_style.layout(line.start, line.end, perp, label_t, 0.0)
+                            {
+                                Self::draw_dimension(current_layer, &layout, dim_style, &transform);
+                            }
+                        }
+
+                        let label = line.label_text(dim_style);
+                        let font = doc.add_builtin_font(BuiltinFont::Helvetica)?;
+                        let pos = transform(label_pos - Vector2::new(text_half_width(&label), 0.0));
+                        current_layer.use_text(label, dim_font_pt, Mm(pos.0), Mm(pos.1), &font);
+                    }
+
+                    if let Shape::RevisionCloud(cloud) = &entity.shape
+                        && let (Some(tag), Some(corners)) = (&cloud.tag, cloud.tag_triangle())
+                    {
+                        let corners = corners.map(world);
+                        current_layer.add_shape(Line {
+                            points: corners
+                                .iter()
+                                .map(|p| {
+                                    let (x, y) = transform(*p);
+                                    (Point::new(Mm(x), Mm(y)), false)
+                                })
+                                .collect(),
+                            is_closed: true,
+                            has_fill: false,
+                            has_stroke: true,
+                            is_clipping_path: false,
+                        });
+                        let center = (corners[0] + corners[1] + corners[2]) / 3.0;
+                        let font = doc.add_builtin_font(BuiltinFont::Helvetica)?;
+                        let pos = transform(center - Vector2::new(text_half_width(tag), 0.0));
+                        current_layer.use_text(
+                            tag.clone(),
+                            dim_font_pt,
+                            Mm(pos.0),
+                            Mm(pos.1),
+                            &font,
+                        );
+                    }
                 }
             }
         }
 
-        let mut file = BufWriter::new(File::create(path)?);
-        doc.save(&mut file)?;
+        if settings.scale_bar {
+            let font = doc.add_builtin_font(BuiltinFont::Helvetica)?;
+            let weight = settings.line_weights.annotation;
+            Self::draw_scale_bar(current_layer, &layout.scale_bar(), weight, &font);
+        }
 
         Ok(())
     }
+
+    /// Outline width in millimetres and dash pattern for the following
+    /// shapes. Dashes scale with the width.
+    fn set_stroke(layer: &PdfLayerReference, width_mm: f32, line_style: LineStyle) {
+        let width_pt = width_mm * 72.0 / 25.4;
+        layer.set_outline_thickness(width_pt as f64);
+
+        // PDF dash lengths are whole points
+        let pattern: Vec<Option<i64>> = line_style
+            .pattern()
+            .iter()
+            .flat_map(|(dash, gap)| [dash, gap])
+            .map(|len| Some(((len * width_pt).round() as i64).max(1)))
+            .collect();
+        let get = |i: usize| pattern.get(i).copied().flatten();
+        layer.set_line_dash_pattern(LineDashPattern::new(
+            0,
+            get(0),
+            get(1),
+            get(2),
+            get(3),
+            get(4),
+            get(5),
+        ));
+    }
+
+    /// Alternating black and white blocks with the length and ratio below
+    fn draw_scale_bar(
+        layer: &PdfLayerReference,
+        bar: &ScaleBar,
+        weight: f32,
+        font: &IndirectFontRef,
+    ) {
+        let black = Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None));
+        layer.set_outline_color(black.clone());
+        layer.set_fill_color(black);
+        Self::set_stroke(layer, weight, LineStyle::Solid);
+
+        let point = |x: f32, y: f32| (Point::new(Mm(x as f64), Mm(y as f64)), false);
+        let step = bar.length / bar.divisions as f32;
+        let (bottom, top) = (bar.origin.y, bar.origin.y + bar.height);
+        for i in 0..bar.divisions {
+            let (left, right) = (
+                bar.origin.x + step * i as f32,
+                bar.origin.x + step * (i + 1) as f32,
+            );
+            layer.add_shape(Line {
+                points: vec![
+                    point(left, bottom),
+                    point(right, bottom),
+                    point(right, top),
+                    point(left, top),
+                ],
+                is_closed: true,
+                has_fill: i % 2 == 0,
+                has_stroke: true,
+                is_clipping_path: false,
+            });
+        }
+
+        let text_y = (bottom - 4.0) as f64;
+        layer.use_text("0", 7.0, Mm(bar.origin.x as f64), Mm(text_y), font);
+        let end_x = bar.origin.x + bar.length - 1.5 * bar.label.len() as f32;
+        layer.use_text(bar.label.clone(), 7.0, Mm(end_x as f64), Mm(text_y), font);
+        let ratio_x = bar.origin.x + bar.length / 2.0 - 0.75 * bar.ratio.len() as f32;
+        layer.use_text(
+            bar.ratio.clone(),
+            7.0,
+            Mm(ratio_x as f64),
+            Mm((top + 1.5) as f64),
+            font,
+        );
+    }
+
+    /// Extension lines, dimension line and arrowheads. Filled arrowheads are
+    /// written as filled polygons so they survive printing.
+    fn draw_dimension(
+        layer: &PdfLayerReference,
+        layout: &DimensionLayout,
+        dim_style: &DimensionStyle,
+        transform: &impl Fn(Vector2) -> (f64, f64),
+    ) {
+        let to_point = |p: Vector2| {
+            let (x, y) = transform(p);
+            (Point::new(Mm(x), Mm(y)), false)
+        };
+
+        let segments = layout
+            .extension_lines
+            .iter()
+            .chain(std::iter::once(&layout.dimension_line));
+        for [a, b] in segments {
+            layer.add_shape(Line {
+                points: vec![to_point(*a), to_point(*b)],
+                is_closed: false,
+                has_fill: false,
+                has_stroke: true,
+                is_clipping_path: false,
+            });
+        }
+
+        let filled = dim_style.arrowhead.is_filled();
+        for outline in &layout.arrows {
+            layer.add_shape(Line {
+                points: outline.iter().map(|p| to_point(*p)).collect(),
+                is_closed: filled,
+                has_fill: filled,
+                has_stroke: !filled,
+                is_clipping_path: false,
+            });
+        }
+    }
+}
+
+/// Every entity of the hierarchy that plots, parents before their children
+fn flatten<'a>(entities: &'a [Entity], layers: &LayerManager, out: &mut Vec<&'a Entity>) {
+    for entity in entities {
+        if entity.plots(layers.get_layer(entity.layer_id)) {
+            out.push(entity);
+            flatten(&entity.children, layers, out);
+        }
+    }
+}
+
+fn pdf_color(color: ecolor::Color32) -> Color {
+    let channel = |c: u8| c as f64 / 255.0;
+    Color::Rgb(Rgb::new(
+        channel(color.r()),
+        channel(color.g()),
+        channel(color.b()),
+        None,
+    ))
 }
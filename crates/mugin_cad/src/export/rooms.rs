@@ -0,0 +1,26 @@
+//! The Rooms report as CSV, one row per room.
+
+use crate::export::attributes::escape;
+use crate::model::CadModel;
+
+/// CSV header, in column order
+pub const COLUMNS: [&str; 4] = ["id", "name", "area_m2", "corners"];
+
+/// CSV of every room with its area in square meters, and a closing total
+pub fn rooms_csv(model: &CadModel) -> String {
+    let mut csv = COLUMNS.join(",");
+    csv.push('\n');
+    let rooms = model.rooms();
+    for (entity, room) in &rooms {
+        csv.push_str(&format!(
+            "{},{},{:.2},{}\n",
+            entity.id,
+            escape(&room.name),
+            room.area_m2(),
+            room.boundary.len()
+        ));
+    }
+    let total: f32 = rooms.iter().map(|(_, room)| room.area_m2()).sum();
+    csv.push_str(&format!(",Total,{:.2},\n", total));
+    csv
+}
@@ -1,5 +1,10 @@
-use crate::model::Vector2;
+use crate::model::color_mode::ColorMode;
+use crate::model::layer::DEFAULT_STROKE_WIDTH;
+use crate::model::layer::Layer;
+use crate::model::{Entity, Vector2};
+use ecolor::Color32;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum PageSize {
@@ -37,13 +42,24 @@ pub enum ExportSource {
     Viewport(Vector2, Vector2), // Min, Max
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ExportSettings {
     pub page_size: PageSize,
     pub orientation: PageOrientation,
     pub scale_type: ScaleType,
     pub source: ExportSource,
     pub margin_mm: f32, // Margin
+    /// Canvas recoloring to print with; `Normal` keeps entity colors
+    #[serde(default)]
+    pub color_mode: ColorMode,
+    /// Colors the print is reduced to
+    #[serde(default)]
+    pub plot_style: PlotStyle,
+    /// Draw a graphic scale bar in the bottom-right corner
+    #[serde(default)]
+    pub scale_bar: bool,
+    #[serde(default)]
+    pub line_weights: LineWeights,
 }
 
 impl Default for ExportSettings {
@@ -54,6 +70,117 @@ impl Default for ExportSettings {
             scale_type: ScaleType::FitToPage,
             source: ExportSource::ModelBounds,
             margin_mm: 10.0,
+            color_mode: ColorMode::Normal,
+            plot_style: PlotStyle::Monochrome,
+            scale_bar: false,
+            line_weights: LineWeights::default(),
         }
     }
 }
+
+impl ExportSettings {
+    /// Color `entity` on `layer` prints in
+    pub fn print_color(&self, entity: &Entity, layer: Option<&Layer>) -> Color32 {
+        let color = self
+            .color_mode
+            .color(&entity.shape, layer)
+            .unwrap_or_else(|| entity.style(layer).color);
+        self.plot_style.apply(color)
+    }
+}
+
+/// Colors a print is reduced to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PlotStyle {
+    /// Entity colors as drawn
+    FullColor,
+    /// Entity colors as shades of gray
+    Grayscale,
+    /// Black lines on white
+    #[default]
+    Monochrome,
+}
+
+impl PlotStyle {
+    pub const ALL: [PlotStyle; 3] = [
+        PlotStyle::FullColor,
+        PlotStyle::Grayscale,
+        PlotStyle::Monochrome,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PlotStyle::FullColor => "Full color",
+            PlotStyle::Grayscale => "Grayscale",
+            PlotStyle::Monochrome => "Monochrome",
+        }
+    }
+
+    /// Printed color of an entity drawn in `color`. White stays white so
+    /// white-filled areas still mask what lies beneath.
+    pub fn apply(self, color: Color32) -> Color32 {
+        let is_white = color.r() >= 250 && color.g() >= 250 && color.b() >= 250;
+        match self {
+            PlotStyle::FullColor => color,
+            _ if is_white => Color32::WHITE,
+            PlotStyle::Grayscale => {
+                let luminance = 0.2126 * color.r() as f32
+                    + 0.7152 * color.g() as f32
+                    + 0.0722 * color.b() as f32;
+                Color32::from_gray(luminance.round() as u8)
+            }
+            PlotStyle::Monochrome => Color32::BLACK,
+        }
+    }
+}
+
+/// How printed line weights are chosen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WeightMode {
+    /// Each entity's stroke width times a factor
+    StrokeWidth,
+    /// A weight per layer
+    PerLayer,
+}
+
+/// Printed line weights, in millimetres
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LineWeights {
+    pub mode: WeightMode,
+    /// Millimetres printed per pixel of stroke width
+    pub factor: f32,
+    /// Weight of each layer by ID under [`WeightMode::PerLayer`]. Layers
+    /// not listed use their stroke width times `factor`.
+    pub layers: BTreeMap<u64, f32>,
+    /// Weight of dimension and extension lines
+    pub annotation: f32,
+}
+
+/// Thinnest line printed, so hairlines stay visible
+pub const MIN_WEIGHT_MM: f32 = 0.05;
+
+impl Default for LineWeights {
+    fn default() -> Self {
+        Self {
+            mode: WeightMode::StrokeWidth,
+            // The default 1.5 px stroke prints as a 0.25 mm pen
+            factor: 0.25 / DEFAULT_STROKE_WIDTH,
+            layers: BTreeMap::new(),
+            annotation: 0.18,
+        }
+    }
+}
+
+impl LineWeights {
+    /// Weight of a stroke `stroke_width` pixels wide on layer `layer_id`
+    pub fn weight(&self, stroke_width: f32, layer_id: u64) -> f32 {
+        let weight = match self.mode {
+            WeightMode::PerLayer => self.layers.get(&layer_id).copied(),
+            WeightMode::StrokeWidth => None,
+        };
+        weight
+            .unwrap_or(stroke_width * self.factor)
+            .max(MIN_WEIGHT_MM)
+    }
+}
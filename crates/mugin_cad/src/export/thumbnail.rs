@@ -0,0 +1,180 @@
+//! Small PNG preview of a drawing, embedded in project files for the
+//! welcome screen's recent list.
+
+use crate::model::axis::AxisOrientation;
+use crate::model::shapes::image::decode_png;
+use crate::model::{CadModel, Entity, Geometry, Vector2};
+use base64::Engine;
+
+pub const THUMBNAIL_WIDTH: u32 = 160;
+pub const THUMBNAIL_HEIGHT: u32 = 120;
+
+const BACKGROUND: [u8; 4] = [20, 20, 20, 255];
+const AXIS_COLOR: [u8; 4] = [90, 90, 90, 255];
+const ENTITY_COLOR: [u8; 4] = [0, 255, 255, 255];
+const MARGIN: f32 = 8.0;
+
+/// Rasterize the model outlines and encode them as a base64 PNG
+pub fn render_thumbnail(model: &CadModel) -> Option<String> {
+    let mut canvas = Raster::new(THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT);
+
+    let (min, max) = thumbnail_bounds(model);
+    let scale = ((THUMBNAIL_WIDTH as f32 - MARGIN * 2.0) / (max.x - min.x))
+        .min((THUMBNAIL_HEIGHT as f32 - MARGIN * 2.0) / (max.y - min.y));
+    let center = (min + max) * 0.5;
+    let to_pixel = |p: Vector2| {
+        (
+            THUMBNAIL_WIDTH as f32 / 2.0 + (p.x - center.x) * scale,
+            // Y up in the model, down in the image
+            THUMBNAIL_HEIGHT as f32 / 2.0 - (p.y - center.y) * scale,
+        )
+    };
+
+    for axis in &model.axis_manager.axes {
+        let (a, b) = axis.get_render_points(min, max);
+        canvas.line(to_pixel(a), to_pixel(b), AXIS_COLOR);
+    }
+
+    for entity in &model.entities {
+        draw_entity(&mut canvas, entity, &to_pixel);
+    }
+
+    let png = canvas.encode_png()?;
+    Some(base64::engine::general_purpose::STANDARD.encode(png))
+}
+
+/// Decode a base64 PNG thumbnail into `(width, height, rgba)`
+pub fn decode_thumbnail(encoded: &str) -> Option<(usize, usize, Vec<u8>)> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .ok()?;
+    decode_png(&bytes)
+}
+
+/// Entity bounds, grown to include the axes so grid-only drawings still show
+fn thumbnail_bounds(model: &CadModel) -> (Vector2, Vector2) {
+    let (mut min, mut max) = model.bounds();
+    if model.entities.is_empty() && !model.axis_manager.axes.is_empty() {
+        min = Vector2::new(f32::MAX, f32::MAX);
+        max = Vector2::new(f32::MIN, f32::MIN);
+    }
+
+    for axis in &model.axis_manager.axes {
+        match axis.orientation {
+            AxisOrientation::Vertical => {
+                min.x = min.x.min(axis.position);
+                max.x = max.x.max(axis.position);
+            }
+            AxisOrientation::Horizontal => {
+                min.y = min.y.min(axis.position);
+                max.y = max.y.max(axis.position);
+            }
+        }
+    }
+
+    // Keep single-direction grids from collapsing to a line
+    if max.x - min.x < 1.0 {
+        max.x = min.x + (max.y - min.y).max(1.0);
+    }
+    if max.y - min.y < 1.0 {
+        max.y = min.y + (max.x - min.x).max(1.0);
+    }
+    (min, max)
+}
+
+fn draw_entity(canvas: &mut Raster, entity: &Entity, to_pixel: &impl Fn(Vector2) -> (f32, f32)) {
+    let points: Vec<(f32, f32)> = entity
+        .shape
+        .as_polyline()
+        .into_iter()
+        .map(|p| to_pixel(entity.world_transform.transform_point2(p.into()).into()))
+        .collect();
+
+    for pair in points.windows(2) {
+        canvas.line(pair[0], pair[1], ENTITY_COLOR);
+    }
+    if entity.shape.is_closed()
+        && let (Some(&first), Some(&last)) = (points.first(), points.last())
+    {
+        canvas.line(last, first, ENTITY_COLOR);
+    }
+
+    for child in &entity.children {
+        draw_entity(canvas, child, to_pixel);
+    }
+}
+
+// ── Raster ──────────────────────────────────────────────────
+
+/// Minimal RGBA pixel buffer with line drawing
+struct Raster {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Raster {
+    fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: BACKGROUND.repeat((width * height) as usize),
+        }
+    }
+
+    fn put(&mut self, x: i32, y: i32, color: [u8; 4]) {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return;
+        }
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        self.pixels[i..i + 4].copy_from_slice(&color);
+    }
+
+    /// Bresenham line, clipped per pixel
+    fn line(&mut self, a: (f32, f32), b: (f32, f32), color: [u8; 4]) {
+        // Skip wildly off-canvas segments instead of walking them
+        let limit = (self.width.max(self.height) * 4) as f32;
+        if [a.0, a.1, b.0, b.1]
+            .iter()
+            .any(|v| !v.is_finite() || v.abs() > limit)
+        {
+            return;
+        }
+
+        let (mut x0, mut y0) = (a.0.round() as i32, a.1.round() as i32);
+        let (x1, y1) = (b.0.round() as i32, b.1.round() as i32);
+        let dx = (x1 - x0).abs();
+        let dy = -(y1 - y0).abs();
+        let sx = if x0 < x1 { 1 } else { -1 };
+        let sy = if y0 < y1 { 1 } else { -1 };
+        let mut err = dx + dy;
+
+        loop {
+            self.put(x0, y0, color);
+            if x0 == x1 && y0 == y1 {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x0 += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y0 += sy;
+            }
+        }
+    }
+
+    fn encode_png(&self) -> Option<Vec<u8>> {
+        let mut bytes = Vec::new();
+        {
+            let mut encoder = png::Encoder::new(&mut bytes, self.width, self.height);
+            encoder.set_color(png::ColorType::Rgba);
+            encoder.set_depth(png::BitDepth::Eight);
+            let mut writer = encoder.write_header().ok()?;
+            writer.write_image_data(&self.pixels).ok()?;
+        }
+        Some(bytes)
+    }
+}
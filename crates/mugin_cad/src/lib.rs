@@ -0,0 +1,15 @@
+//! MuginCAD: the drawing model, commands and exporters, with the egui front
+//! end behind the `gui` feature. Without it the crate builds headless, which
+//! is what the integration tests under `tests/` drive.
+
+// Types are built through `new()`; none of them needs a `Default`
+#![allow(clippy::new_without_default)]
+
+pub mod cli;
+pub mod commands;
+pub mod export;
+pub mod model;
+#[cfg(feature = "gui")]
+pub mod view;
+#[cfg(feature = "gui")]
+pub mod viewmodel;
@@ -1,13 +1,44 @@
-mod commands;
-mod export;
-mod model;
-mod view;
-mod viewmodel;
-
 use eframe::egui;
-use view::CadApp;
+use mugin_cad::cli::{self, CliAction};
+use mugin_cad::view::{self, CadApp};
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let files = match cli::parse(std::env::args().skip(1)) {
+        Ok(CliAction::Gui { files }) => files,
+        Ok(CliAction::ExportPdf {
+            input,
+            output,
+            settings,
+        }) => {
+            return match cli::export_pdf(&input, &output, &settings) {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(e) => {
+                    eprintln!("error: {}", e);
+                    ExitCode::FAILURE
+                }
+            };
+        }
+        Ok(CliAction::Help) => {
+            println!("{}", cli::USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, cli::USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match run_gui(files) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
 
-fn main() -> eframe::Result<()> {
+fn run_gui(files: Vec<std::path::PathBuf>) -> eframe::Result<()> {
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([1200.0, 800.0])
@@ -16,8 +47,8 @@ fn main() -> eframe::Result<()> {
     };
 
     eframe::run_native(
-        "rust_cad",
+        view::APP_ID,
         native_options,
-        Box::new(|cc| Ok(Box::new(CadApp::new(cc)))),
+        Box::new(|cc| Ok(Box::new(CadApp::new(cc, files)))),
     )
 }
@@ -11,7 +11,7 @@ pub enum AxisOrientation {
 }
 
 /// Construction axis (infinite line for architectural grids)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Axis {
     /// Position on the perpendicular axis
     /// For Vertical: X position
@@ -0,0 +1,102 @@
+//! Review flags: a colored marker on entities to come back to.
+
+use crate::model::{CadModel, Entity};
+use ecolor::Color32;
+use serde::{Deserialize, Serialize};
+
+/// One of the few fixed flag colors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FlagColor {
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Blue,
+    Purple,
+}
+
+impl FlagColor {
+    pub const ALL: [FlagColor; 6] = [
+        FlagColor::Red,
+        FlagColor::Orange,
+        FlagColor::Yellow,
+        FlagColor::Green,
+        FlagColor::Blue,
+        FlagColor::Purple,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            FlagColor::Red => "Red",
+            FlagColor::Orange => "Orange",
+            FlagColor::Yellow => "Yellow",
+            FlagColor::Green => "Green",
+            FlagColor::Blue => "Blue",
+            FlagColor::Purple => "Purple",
+        }
+    }
+
+    pub fn color(self) -> Color32 {
+        match self {
+            FlagColor::Red => Color32::from_rgb(230, 60, 60),
+            FlagColor::Orange => Color32::from_rgb(240, 150, 40),
+            FlagColor::Yellow => Color32::from_rgb(235, 210, 50),
+            FlagColor::Green => Color32::from_rgb(70, 190, 90),
+            FlagColor::Blue => Color32::from_rgb(70, 140, 235),
+            FlagColor::Purple => Color32::from_rgb(170, 90, 220),
+        }
+    }
+
+    /// Flag named `name`, ignoring case
+    pub fn parse(name: &str) -> Option<FlagColor> {
+        let name = name.trim();
+        Self::ALL
+            .into_iter()
+            .find(|flag| flag.label().eq_ignore_ascii_case(name))
+    }
+}
+
+impl CadModel {
+    /// Flag the entities `ids`, or clear their flags with `None`.
+    /// Returns how many were found.
+    pub fn set_flag(
+        &mut self,
+        ids: impl IntoIterator<Item = u64>,
+        flag: Option<FlagColor>,
+    ) -> usize {
+        ids.into_iter()
+            .filter(|&id| match self.find_by_id_mut(id) {
+                Some(entity) => {
+                    entity.flag = flag;
+                    true
+                }
+                None => false,
+            })
+            .count()
+    }
+
+    /// IDs of the entities flagged `color`, or flagged at all with `None`,
+    /// at any depth
+    pub fn flagged_ids(&self, color: Option<FlagColor>) -> Vec<u64> {
+        fn collect(entities: &[Entity], color: Option<FlagColor>, ids: &mut Vec<u64>) {
+            for entity in entities {
+                if entity.flag.is_some() && (color.is_none() || entity.flag == color) {
+                    ids.push(entity.id);
+                }
+                collect(&entity.children, color, ids);
+            }
+        }
+        let mut ids = Vec::new();
+        collect(&self.entities, color, &mut ids);
+        ids
+    }
+
+    /// How many entities carry each flag, for flags in use
+    pub fn flag_counts(&self) -> Vec<(FlagColor, usize)> {
+        FlagColor::ALL
+            .into_iter()
+            .map(|flag| (flag, self.flagged_ids(Some(flag)).len()))
+            .filter(|&(_, count)| count > 0)
+            .collect()
+    }
+}
@@ -1,14 +1,78 @@
-use eframe::egui::Color32;
+use ecolor::Color32;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 
-/// A logical layer that groups entities.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Color given to new layers (the classic entity cyan)
+pub const DEFAULT_LAYER_COLOR: Color32 = Color32::from_rgb(0, 255, 255);
+
+/// Stroke width, in screen pixels, given to new layers
+pub const DEFAULT_STROKE_WIDTH: f32 = 1.5;
+
+fn default_stroke_width() -> f32 {
+    DEFAULT_STROKE_WIDTH
+}
+
+fn default_plottable() -> bool {
+    true
+}
+
+/// Dash pattern of an outline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LineStyle {
+    #[default]
+    Solid,
+    Dashed,
+    Dotted,
+    DashDot,
+}
+
+impl LineStyle {
+    pub const ALL: [LineStyle; 4] = [
+        LineStyle::Solid,
+        LineStyle::Dashed,
+        LineStyle::Dotted,
+        LineStyle::DashDot,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            LineStyle::Solid => "Solid",
+            LineStyle::Dashed => "Dashed",
+            LineStyle::Dotted => "Dotted",
+            LineStyle::DashDot => "Dash-dot",
+        }
+    }
+
+    /// Repeating (dash, gap) lengths in multiples of the stroke width;
+    /// empty for a solid line
+    pub fn pattern(self) -> &'static [(f32, f32)] {
+        match self {
+            LineStyle::Solid => &[],
+            LineStyle::Dashed => &[(6.0, 3.0)],
+            LineStyle::Dotted => &[(1.0, 2.0)],
+            LineStyle::DashDot => &[(6.0, 2.0), (1.0, 2.0)],
+        }
+    }
+}
+
+/// A logical layer that groups entities. Its color, line style, stroke
+/// width and printability apply to entities that don't set their own.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Layer {
     pub id: u64,
     pub name: String,
     pub color: Color32,
     pub is_visible: bool,
+    #[serde(default)]
+    pub line_style: LineStyle,
+    /// Stroke width in screen pixels
+    #[serde(default = "default_stroke_width")]
+    pub stroke_width: f32,
+    /// Whether the layer reaches printed sheets; off for construction
+    /// and markup layers
+    #[serde(default = "default_plottable")]
+    pub plottable: bool,
 }
 
 impl Layer {
@@ -18,12 +82,15 @@ impl Layer {
             name,
             color,
             is_visible: true,
+            line_style: LineStyle::Solid,
+            stroke_width: DEFAULT_STROKE_WIDTH,
+            plottable: true,
         }
     }
 }
 
 /// Manages the collection of layers.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LayerManager {
     pub layers: HashMap<u64, Layer>,
     pub active_layer_id: u64,
@@ -34,7 +101,7 @@ impl LayerManager {
     pub fn new() -> Self {
         let mut layers = HashMap::new();
         // Create default layer (ID 0)
-        let default_layer = Layer::new(0, "Default".to_string(), Color32::WHITE);
+        let default_layer = Layer::new(0, "Default".to_string(), DEFAULT_LAYER_COLOR);
         layers.insert(0, default_layer);
 
         Self {
@@ -78,4 +145,150 @@ impl LayerManager {
         list.sort_by_key(|l| l.id);
         list
     }
+
+    /// Layers left off printed sheets, by ID
+    pub fn unplotted_layers(&self) -> Vec<&Layer> {
+        self.get_sorted_layers()
+            .into_iter()
+            .filter(|layer| !layer.plottable)
+            .collect()
+    }
+}
+
+impl Default for LayerManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ── Layer Templates ──────────────────────────────────────
+
+/// One layer of a template
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateLayer {
+    pub name: String,
+    pub color: Color32,
+    #[serde(default = "default_visible")]
+    pub is_visible: bool,
+    #[serde(default)]
+    pub line_style: LineStyle,
+    #[serde(default = "default_stroke_width")]
+    pub stroke_width: f32,
+    #[serde(default = "default_plottable")]
+    pub plottable: bool,
+}
+
+fn default_visible() -> bool {
+    true
+}
+
+/// A named layer set shared between projects as a small JSON file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LayerTemplate {
+    pub layers: Vec<TemplateLayer>,
+}
+
+/// A template layer whose name exists with a different color
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayerConflict {
+    pub layer_id: u64,
+    pub name: String,
+    pub existing: Color32,
+    pub template: Color32,
+}
+
+impl LayerTemplate {
+    /// Template of a project's current layer table
+    pub fn from_layers(manager: &LayerManager) -> Self {
+        Self {
+            layers: manager
+                .get_sorted_layers()
+                .into_iter()
+                .map(|layer| TemplateLayer {
+                    name: layer.name.clone(),
+                    color: layer.color,
+                    is_visible: layer.is_visible,
+                    line_style: layer.line_style,
+                    stroke_width: layer.stroke_width,
+                    plottable: layer.plottable,
+                })
+                .collect(),
+        }
+    }
+
+    /// Read and parse a template file
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+        serde_json::from_str(&content)
+            .map_err(|e| format!("{} is not a valid layer template: {}", path.display(), e))
+    }
+
+    /// Serialize and write the template to a file
+    pub fn write(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Could not serialize layer template: {}", e))?;
+        std::fs::write(path, json).map_err(|e| format!("Could not write {}: {}", path.display(), e))
+    }
+}
+
+impl LayerManager {
+    pub fn find_by_name(&self, name: &str) -> Option<&Layer> {
+        self.get_sorted_layers()
+            .into_iter()
+            .find(|layer| layer.name == name)
+    }
+
+    /// Id of the layer called `name`, adding it with `color` if missing
+    pub fn find_or_add(&mut self, name: &str, color: Color32) -> u64 {
+        match self.find_by_name(name) {
+            Some(layer) => layer.id,
+            None => self.add_layer(name.to_string(), color),
+        }
+    }
+
+    /// Layers that exist under a template name but with another color
+    pub fn template_conflicts(&self, template: &LayerTemplate) -> Vec<LayerConflict> {
+        template
+            .layers
+            .iter()
+            .filter_map(|entry| {
+                let layer = self.find_by_name(&entry.name)?;
+                (layer.color != entry.color).then(|| LayerConflict {
+                    layer_id: layer.id,
+                    name: layer.name.clone(),
+                    existing: layer.color,
+                    template: entry.color,
+                })
+            })
+            .collect()
+    }
+
+    /// Merge a template by name: missing layers are added, and existing
+    /// layers in `recolor` take the template's color. Returns how many
+    /// layers were added.
+    pub fn merge_template(&mut self, template: &LayerTemplate, recolor: &HashSet<u64>) -> usize {
+        let mut added = 0;
+        for entry in &template.layers {
+            match self.find_by_name(&entry.name).map(|layer| layer.id) {
+                Some(id) if recolor.contains(&id) => {
+                    if let Some(layer) = self.layers.get_mut(&id) {
+                        layer.color = entry.color;
+                    }
+                }
+                Some(_) => {}
+                None => {
+                    let id = self.add_layer(entry.name.clone(), entry.color);
+                    if let Some(layer) = self.layers.get_mut(&id) {
+                        layer.is_visible = entry.is_visible;
+                        layer.line_style = entry.line_style;
+                        layer.stroke_width = entry.stroke_width;
+                        layer.plottable = entry.plottable;
+                    }
+                    added += 1;
+                }
+            }
+        }
+        added
+    }
 }
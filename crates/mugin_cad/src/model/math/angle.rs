@@ -0,0 +1,330 @@
+//! Angles as the user reads and types them.
+//!
+//! The model keeps every angle in radians, counter-clockwise from +X. An
+//! `AngleFormat` shows them in degrees, degrees-minutes-seconds or
+//! gradians, measured from East counter-clockwise, from North clockwise,
+//! or as quadrant bearings such as N 45° E.
+//!
+//! A *direction* (a polar angle, an arc's start) is measured from the zero
+//! direction; a *rotation* (turning an entity) only takes the positive
+//! sense. Typed text may name its unit whatever the setting: `45.5`,
+//! `45°30'`, `45d30'15"`, `50g`, `50gon`, `0.79rad`, and for directions
+//! `N 45°30' E`.
+
+use serde::{Deserialize, Serialize};
+use std::f64::consts::{FRAC_PI_2, PI, TAU};
+
+/// Unit angles are shown in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum AngleUnit {
+    /// Decimal degrees, e.g. 45.5°
+    #[default]
+    Degrees,
+    /// Degrees, minutes and seconds, e.g. 45°30'00"
+    DegMinSec,
+    /// Gradians, 400 to the turn, e.g. 50.5 gon
+    Gradians,
+}
+
+impl AngleUnit {
+    pub const ALL: [AngleUnit; 3] = [
+        AngleUnit::Degrees,
+        AngleUnit::DegMinSec,
+        AngleUnit::Gradians,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AngleUnit::Degrees => "Decimal Degrees",
+            AngleUnit::DegMinSec => "Deg/Min/Sec",
+            AngleUnit::Gradians => "Gradians",
+        }
+    }
+
+    /// Radians in one of this unit; minutes and seconds count as degrees
+    fn radians(&self) -> f64 {
+        match self {
+            AngleUnit::Degrees | AngleUnit::DegMinSec => PI / 180.0,
+            AngleUnit::Gradians => PI / 200.0,
+        }
+    }
+}
+
+/// Where angles are measured from and which way they grow
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum AngleDirection {
+    /// 0 at East, counter-clockwise positive
+    #[default]
+    EastCcw,
+    /// 0 at North, clockwise positive (azimuths)
+    NorthCw,
+    /// Quadrant bearings from North or South toward East or West;
+    /// rotations as with `NorthCw`
+    Bearing,
+}
+
+impl AngleDirection {
+    pub const ALL: [AngleDirection; 3] = [
+        AngleDirection::EastCcw,
+        AngleDirection::NorthCw,
+        AngleDirection::Bearing,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AngleDirection::EastCcw => "East, Counter-clockwise",
+            AngleDirection::NorthCw => "North, Clockwise",
+            AngleDirection::Bearing => "Bearing (N 45° E)",
+        }
+    }
+
+    fn clockwise(&self) -> bool {
+        !matches!(self, AngleDirection::EastCcw)
+    }
+}
+
+/// How angles are shown and read back
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AngleFormat {
+    pub unit: AngleUnit,
+    pub direction: AngleDirection,
+    /// Decimal places of the last part shown: degrees, seconds or gradians
+    pub precision: u8,
+}
+
+impl Default for AngleFormat {
+    fn default() -> Self {
+        Self {
+            unit: AngleUnit::Degrees,
+            direction: AngleDirection::EastCcw,
+            precision: 1,
+        }
+    }
+}
+
+impl AngleFormat {
+    /// Text for a direction given counter-clockwise from +X
+    pub fn format_direction(&self, radians: f32) -> String {
+        let measured = self.measured_direction(radians as f64);
+        if self.direction == AngleDirection::Bearing {
+            return self.format_bearing(measured);
+        }
+        self.format_turn(measured)
+    }
+
+    /// Text for a rotation given counter-clockwise, within one turn
+    pub fn format_rotation(&self, radians: f32) -> String {
+        let radians = radians as f64;
+        let measured = if self.direction.clockwise() {
+            -radians
+        } else {
+            radians
+        };
+        self.format_turn(measured.rem_euclid(TAU))
+    }
+
+    /// Direction typed as `text`, counter-clockwise from +X and within one
+    /// turn. Bearings are read whatever the setting.
+    pub fn parse_direction(&self, text: &str) -> Option<f32> {
+        let measured = match parse_bearing(text, self.unit) {
+            Some(azimuth) => FRAC_PI_2 - azimuth,
+            None => {
+                let value = parse_amount(text, self.unit)?;
+                if self.direction.clockwise() {
+                    FRAC_PI_2 - value
+                } else {
+                    value
+                }
+            }
+        };
+        Some(measured.rem_euclid(TAU) as f32)
+    }
+
+    /// Rotation typed as `text`, counter-clockwise
+    pub fn parse_rotation(&self, text: &str) -> Option<f32> {
+        let value = parse_amount(text, self.unit)?;
+        Some(if self.direction.clockwise() {
+            -value
+        } else {
+            value
+        } as f32)
+    }
+
+    /// `radians` from the zero direction in the positive sense, within one
+    /// turn
+    fn measured_direction(&self, radians: f64) -> f64 {
+        if self.direction.clockwise() {
+            (FRAC_PI_2 - radians).rem_euclid(TAU)
+        } else {
+            radians.rem_euclid(TAU)
+        }
+    }
+
+    /// A value within one turn, shown as 0 once it rounds up to a turn
+    fn format_turn(&self, radians: f64) -> String {
+        if TAU - radians < self.step() / 2.0 {
+            return self.format_value(0.0);
+        }
+        self.format_value(radians)
+    }
+
+    /// Radians in one step of the last decimal shown
+    fn step(&self) -> f64 {
+        let step = 10_f64.powi(-(self.precision as i32)) * self.unit.radians();
+        match self.unit {
+            AngleUnit::DegMinSec => step / 3600.0,
+            _ => step,
+        }
+    }
+
+    /// Azimuth clockwise from North as N/S, angle, E/W
+    fn format_bearing(&self, azimuth: f64) -> String {
+        let azimuth = if TAU - azimuth < self.step() / 2.0 {
+            0.0
+        } else {
+            azimuth
+        };
+        let (from, angle, toward) = if azimuth <= FRAC_PI_2 {
+            ('N', azimuth, 'E')
+        } else if azimuth <= PI {
+            ('S', PI - azimuth, 'E')
+        } else if azimuth <= PI + FRAC_PI_2 {
+            ('S', azimuth - PI, 'W')
+        } else {
+            ('N', TAU - azimuth, 'W')
+        };
+        format!("{} {} {}", from, self.format_value(angle), toward)
+    }
+
+    /// Text for a value of at least 0
+    fn format_value(&self, radians: f64) -> String {
+        let precision = self.precision as usize;
+        match self.unit {
+            AngleUnit::Degrees => format!("{:.*}°", precision, radians.to_degrees()),
+            AngleUnit::Gradians => {
+                format!(
+                    "{:.*} gon",
+                    precision,
+                    radians / AngleUnit::Gradians.radians()
+                )
+            }
+            AngleUnit::DegMinSec => {
+                // Whole steps of the last second decimal, so rounding
+                // carries into minutes and degrees
+                let steps_per_second = 10_f64.powi(precision as i32);
+                let steps = (radians.to_degrees() * 3600.0 * steps_per_second).round() as u64;
+                let per_second = steps_per_second as u64;
+                let degrees = steps / (3600 * per_second);
+                let minutes = steps / (60 * per_second) % 60;
+                let seconds = (steps % (60 * per_second)) as f64 / steps_per_second;
+                let width = if precision == 0 { 2 } else { precision + 3 };
+                format!(
+                    "{}°{:02}'{:0width$.precision$}\"",
+                    degrees, minutes, seconds
+                )
+            }
+        }
+    }
+}
+
+/// An amount typed as a number in `unit`, or in the unit it names, in
+/// radians
+pub fn parse_amount(text: &str, unit: AngleUnit) -> Option<f64> {
+    let text = text.trim();
+    let (negative, text) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest.trim_start()),
+        None => (false, text.strip_prefix('+').unwrap_or(text).trim_start()),
+    };
+    let lower = text.to_lowercase();
+    let value = if let Some(number) = lower.strip_suffix("deg") {
+        parse_number(number)?.to_radians()
+    } else if let Some(number) = ["gon", "grad", "g"]
+        .iter()
+        .find_map(|suffix| lower.strip_suffix(suffix))
+    {
+        parse_number(number)? * AngleUnit::Gradians.radians()
+    } else if let Some(number) = ["rad", "r"]
+        .iter()
+        .find_map(|suffix| lower.strip_suffix(suffix))
+    {
+        parse_number(number)?
+    } else if lower.contains(['°', 'd', '\'', '"', '′', '″']) {
+        parse_dms(&lower)?.to_radians()
+    } else {
+        parse_number(&lower)? * unit.radians()
+    };
+    Some(if negative { -value } else { value })
+}
+
+/// A bearing such as "N 45° E" or "s30w", as an azimuth clockwise from
+/// North in radians
+pub fn parse_bearing(text: &str, unit: AngleUnit) -> Option<f64> {
+    let text = text.trim().to_uppercase();
+    let from = text.chars().next().filter(|c| matches!(c, 'N' | 'S'))?;
+    let toward = text.chars().last().filter(|c| matches!(c, 'E' | 'W'))?;
+    let inner = &text[1..text.len() - 1];
+    let angle = parse_amount(inner, unit).filter(|a| (0.0..=FRAC_PI_2 + 1e-9).contains(a))?;
+    Some(match (from, toward) {
+        ('N', 'E') => angle,
+        ('S', 'E') => PI - angle,
+        ('S', 'W') => PI + angle,
+        _ => TAU - angle,
+    })
+}
+
+fn parse_number(text: &str) -> Option<f64> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    text.parse::<f64>()
+        .ok()
+        .filter(|n| n.is_finite() && *n >= 0.0)
+}
+
+/// Degrees from "45°30'15\"", "45d30'15" or "45°30": each part marked
+/// with its unit in order, the last one possibly unmarked
+fn parse_dms(text: &str) -> Option<f64> {
+    // Parts seen so far: 0 none, 1 degrees, 2 minutes, 3 seconds
+    let mut part = 0;
+    let mut total = 0.0;
+    let mut number = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        let next = match c {
+            '°' | 'd' => 1,
+            '\'' | '′' if chars.peek() == Some(&'\'') => {
+                chars.next();
+                3
+            }
+            '\'' | '′' => 2,
+            '"' | '″' => 3,
+            c if c.is_whitespace() => continue,
+            c => {
+                number.push(c);
+                continue;
+            }
+        };
+        if next <= part {
+            return None;
+        }
+        let value = parse_number(&number)?;
+        if next > 1 && value >= 60.0 {
+            return None;
+        }
+        total += value / 60_f64.powi(next - 1);
+        part = next;
+        number.clear();
+    }
+    if !number.is_empty() {
+        // An unmarked last part is the one after the last marked one
+        let next = part + 1;
+        let value = parse_number(&number)?;
+        if part == 0 || next > 3 || value >= 60.0 {
+            return None;
+        }
+        total += value / 60_f64.powi(next - 1);
+    }
+    (part > 0).then_some(total)
+}
@@ -0,0 +1,261 @@
+//! Closest point pairs between entity outlines, for clearance readouts.
+
+use super::segment;
+use super::vector::Vector2;
+use crate::model::shapes::Geometry;
+use crate::model::{Entity, Shape};
+use std::f32::consts::TAU;
+
+/// A piece of an entity's outline in world coordinates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Primitive {
+    /// Straight segment; a point when both ends are equal
+    Segment(Vector2, Vector2),
+    /// Counter-clockwise arc from `start` (radians) over `sweep`; a full
+    /// circle has a sweep of `TAU`
+    Arc {
+        center: Vector2,
+        radius: f32,
+        start: f32,
+        sweep: f32,
+    },
+}
+
+impl Primitive {
+    fn circle(center: Vector2, radius: f32) -> Self {
+        Primitive::Arc {
+            center,
+            radius,
+            start: 0.0,
+            sweep: TAU,
+        }
+    }
+}
+
+fn point_at(center: Vector2, radius: f32, angle: f32) -> Vector2 {
+    center + Vector2::new(angle.cos(), angle.sin()) * radius
+}
+
+fn on_arc(angle: f32, start: f32, sweep: f32) -> bool {
+    sweep >= TAU || (angle - start).rem_euclid(TAU) <= sweep
+}
+
+/// End points of an arc
+fn arc_ends(center: Vector2, radius: f32, start: f32, sweep: f32) -> [Vector2; 2] {
+    [
+        point_at(center, radius, start),
+        point_at(center, radius, start + sweep),
+    ]
+}
+
+/// Closest point to `p` on an arc
+pub fn closest_on_arc(p: Vector2, center: Vector2, radius: f32, start: f32, sweep: f32) -> Vector2 {
+    let d = p - center;
+    if d.length_squared() > 0.0 {
+        let angle = d.y.atan2(d.x);
+        if on_arc(angle, start, sweep) {
+            return point_at(center, radius, angle);
+        }
+    }
+    let [a, b] = arc_ends(center, radius, start, sweep);
+    if p.dist(a) <= p.dist(b) { a } else { b }
+}
+
+/// Closer of the candidate pairs
+fn nearest(pairs: impl IntoIterator<Item = (Vector2, Vector2)>) -> (Vector2, Vector2) {
+    pairs
+        .into_iter()
+        .min_by(|(a1, b1), (a2, b2)| a1.dist(*b1).total_cmp(&a2.dist(*b2)))
+        .expect("at least one candidate pair")
+}
+
+/// Closest points between two segments
+pub fn segment_segment(a1: Vector2, a2: Vector2, b1: Vector2, b2: Vector2) -> (Vector2, Vector2) {
+    if let Some(hit) = segment::intersection(a1, a2, b1, b2) {
+        return (hit, hit);
+    }
+    nearest([
+        (a1, segment::closest_point(a1, b1, b2)),
+        (a2, segment::closest_point(a2, b1, b2)),
+        (segment::closest_point(b1, a1, a2), b1),
+        (segment::closest_point(b2, a1, a2), b2),
+    ])
+}
+
+/// Closest points between a segment and an arc
+pub fn segment_arc(
+    a: Vector2,
+    b: Vector2,
+    center: Vector2,
+    radius: f32,
+    start: f32,
+    sweep: f32,
+) -> (Vector2, Vector2) {
+    if a != b {
+        for hit in segment::circle_intersections(a, b, center, radius) {
+            let d = hit - center;
+            if on_arc(d.y.atan2(d.x), start, sweep) {
+                return (hit, hit);
+            }
+        }
+    }
+
+    let mut pairs = vec![
+        (a, closest_on_arc(a, center, radius, start, sweep)),
+        (b, closest_on_arc(b, center, radius, start, sweep)),
+    ];
+    for end in arc_ends(center, radius, start, sweep) {
+        pairs.push((segment::closest_point(end, a, b), end));
+    }
+    // Foot of the perpendicular from the center, pushed out to the arc
+    let foot = segment::closest_point(center, a, b);
+    let d = foot - center;
+    if d.length_squared() > 0.0 && on_arc(d.y.atan2(d.x), start, sweep) {
+        pairs.push((foot, center + d.normalized() * radius));
+    }
+    nearest(pairs)
+}
+
+/// Intersection points of two circles
+fn circle_circle_intersection(c1: Vector2, r1: f32, c2: Vector2, r2: f32) -> Vec<Vector2> {
+    let d = c1.dist(c2);
+    if d == 0.0 || d > r1 + r2 || d < (r1 - r2).abs() {
+        return Vec::new();
+    }
+    let along = (r1 * r1 - r2 * r2 + d * d) / (2.0 * d);
+    let h = (r1 * r1 - along * along).max(0.0).sqrt();
+    let u = (c2 - c1) / d;
+    let mid = c1 + u * along;
+    let perp = Vector2::new(-u.y, u.x);
+    vec![mid + perp * h, mid - perp * h]
+}
+
+/// Closest points between two arcs, given as `(center, radius, start, sweep)`
+pub fn arc_arc(a: (Vector2, f32, f32, f32), b: (Vector2, f32, f32, f32)) -> (Vector2, Vector2) {
+    let (ca, ra, sa, wa) = a;
+    let (cb, rb, sb, wb) = b;
+    let angle_of = |p: Vector2, c: Vector2| (p.y - c.y).atan2(p.x - c.x);
+
+    for hit in circle_circle_intersection(ca, ra, cb, rb) {
+        if on_arc(angle_of(hit, ca), sa, wa) && on_arc(angle_of(hit, cb), sb, wb) {
+            return (hit, hit);
+        }
+    }
+
+    let mut pairs = Vec::new();
+    for end in arc_ends(ca, ra, sa, wa) {
+        pairs.push((end, closest_on_arc(end, cb, rb, sb, wb)));
+    }
+    for end in arc_ends(cb, rb, sb, wb) {
+        pairs.push((closest_on_arc(end, ca, ra, sa, wa), end));
+    }
+    // Points on the line through both centers
+    let d = cb - ca;
+    if d.length_squared() > 0.0 {
+        let u = d.normalized();
+        for pa in [ca + u * ra, ca - u * ra] {
+            for pb in [cb + u * rb, cb - u * rb] {
+                if on_arc(angle_of(pa, ca), sa, wa) && on_arc(angle_of(pb, cb), sb, wb) {
+                    pairs.push((pa, pb));
+                }
+            }
+        }
+    }
+    nearest(pairs)
+}
+
+/// Closest points between two primitives
+pub fn closest_points(a: &Primitive, b: &Primitive) -> (Vector2, Vector2) {
+    match (*a, *b) {
+        (Primitive::Segment(a1, a2), Primitive::Segment(b1, b2)) => segment_segment(a1, a2, b1, b2),
+        (
+            Primitive::Segment(p, q),
+            Primitive::Arc {
+                center,
+                radius,
+                start,
+                sweep,
+            },
+        ) => segment_arc(p, q, center, radius, start, sweep),
+        (
+            Primitive::Arc {
+                center,
+                radius,
+                start,
+                sweep,
+            },
+            Primitive::Segment(p, q),
+        ) => {
+            let (on_segment, on_arc) = segment_arc(p, q, center, radius, start, sweep);
+            (on_arc, on_segment)
+        }
+        (
+            Primitive::Arc {
+                center: ca,
+                radius: ra,
+                start: sa,
+                sweep: wa,
+            },
+            Primitive::Arc {
+                center: cb,
+                radius: rb,
+                start: sb,
+                sweep: wb,
+            },
+        ) => arc_arc((ca, ra, sa, wa), (cb, rb, sb, wb)),
+    }
+}
+
+/// World-space outline of an entity and its children
+pub fn entity_primitives(entity: &Entity) -> Vec<Primitive> {
+    let mut out = Vec::new();
+    collect_primitives(entity, &mut out);
+    out
+}
+
+fn collect_primitives(entity: &Entity, out: &mut Vec<Primitive>) {
+    let transform = entity.world_transform;
+    let world = |p: Vector2| -> Vector2 { transform.transform_point2(p.into()).into() };
+    match &entity.shape {
+        Shape::None => {}
+        Shape::Circle(circle) => out.push(Primitive::circle(world(circle.center), circle.radius)),
+        Shape::Arc(arc) => {
+            let center = world(arc.center);
+            let mut start = world(arc.start_point());
+            let mut end = world(arc.end_point());
+            // Primitives run CCW; a mirrored transform flips the direction
+            if arc.clockwise != (transform.matrix2.determinant() < 0.0) {
+                std::mem::swap(&mut start, &mut end);
+            }
+            let start_angle = (start.y - center.y).atan2(start.x - center.x);
+            let end_angle = (end.y - center.y).atan2(end.x - center.x);
+            out.push(Primitive::Arc {
+                center,
+                radius: arc.radius,
+                start: start_angle,
+                sweep: (end_angle - start_angle).rem_euclid(TAU),
+            });
+        }
+        shape => {
+            let points: Vec<Vector2> = shape.as_polyline().into_iter().map(world).collect();
+            match points.as_slice() {
+                [] => {}
+                [point] => out.push(Primitive::Segment(*point, *point)),
+                _ => out.extend(points.windows(2).map(|w| Primitive::Segment(w[0], w[1]))),
+            }
+        }
+    }
+    for child in &entity.children {
+        collect_primitives(child, out);
+    }
+}
+
+/// Closest points between the outlines of two entities, `None` if either
+/// has no outline
+pub fn closest_between(a: &Entity, b: &Entity) -> Option<(Vector2, Vector2)> {
+    let a = entity_primitives(a);
+    let b = entity_primitives(b);
+    a.iter()
+        .flat_map(|pa| b.iter().map(move |pb| closest_points(pa, pb)))
+        .min_by(|(a1, b1), (a2, b2)| a1.dist(*b1).total_cmp(&a2.dist(*b2)))
+}
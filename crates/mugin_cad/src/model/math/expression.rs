@@ -0,0 +1,459 @@
+//! Arithmetic expressions over named values, e.g. `(roomWidth - 2*wall)/3`.
+//!
+//! `Expression::parse` reads the text once; `eval` computes it with names
+//! looked up by the caller, so the calculator and anything else taking
+//! typed formulas share one grammar:
+//!
+//! - numbers (`12`, `0.5`, `1e3`) and names (`wall`, `A.width`)
+//! - `+ - * / % ^` with the usual precedence, `^` binding right to left,
+//!   and `−`, `×`, `÷` as typed from a symbol keyboard
+//! - unary minus and parentheses
+//! - functions: `sqrt abs min max round floor ceil hypot ln log exp`, and
+//!   `sin cos tan asin acos atan atan2` in degrees
+//! - the constants `pi` and `e` when the caller does not define them
+//!
+//! Input nested deeper than `MAX_DEPTH` is refused rather than overflowing
+//! the stack.
+
+use std::fmt;
+use std::ops::Range;
+
+/// Deepest an expression may nest, counting parentheses, signs and each
+/// chained operator, so a formula read from a project file cannot exhaust
+/// the stack
+pub const MAX_DEPTH: usize = 256;
+
+/// Why an expression could not be read or computed
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExpressionError {
+    /// Nothing to compute
+    Empty,
+    /// A character or token that does not fit where it is, with its
+    /// character position
+    Unexpected {
+        found: String,
+        at: usize,
+    },
+    /// The text stopped before the expression was complete
+    UnexpectedEnd,
+    /// A name nothing defines
+    UnknownName(String),
+    UnknownFunction(String),
+    /// A function called with the wrong number of arguments
+    WrongArguments {
+        function: String,
+        expected: usize,
+    },
+    /// Division by zero, a root of a negative number and the like
+    NotANumber,
+    /// Nested deeper than `MAX_DEPTH`
+    TooDeep,
+}
+
+impl fmt::Display for ExpressionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("Nothing to calculate."),
+            Self::Unexpected { found, at } => {
+                write!(f, "Unexpected \"{}\" at position {}.", found, at + 1)
+            }
+            Self::UnexpectedEnd => f.write_str("The expression is incomplete."),
+            Self::UnknownName(name) => write!(f, "Unknown name \"{}\".", name),
+            Self::UnknownFunction(name) => write!(f, "Unknown function \"{}\".", name),
+            Self::WrongArguments { function, expected } => {
+                let plural = if *expected == 1 { "" } else { "s" };
+                write!(f, "{} takes {} argument{}.", function, expected, plural)
+            }
+            Self::NotANumber => f.write_str("The result is not a number."),
+            Self::TooDeep => f.write_str("The expression is nested too deeply."),
+        }
+    }
+}
+
+/// A parsed expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+    Number(f64),
+    Name(String),
+    Negate(Box<Expression>),
+    Binary(Operator, Box<Expression>, Box<Expression>),
+    Call(String, Vec<Expression>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+    Power,
+}
+
+impl Operator {
+    /// Binding strength; higher binds tighter
+    fn precedence(&self) -> u8 {
+        match self {
+            Operator::Add | Operator::Subtract => 1,
+            Operator::Multiply | Operator::Divide | Operator::Remainder => 2,
+            Operator::Power => 3,
+        }
+    }
+
+    fn apply(&self, a: f64, b: f64) -> f64 {
+        match self {
+            Operator::Add => a + b,
+            Operator::Subtract => a - b,
+            Operator::Multiply => a * b,
+            Operator::Divide => a / b,
+            Operator::Remainder => a % b,
+            Operator::Power => a.powf(b),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Name(String),
+    Operator(Operator),
+    Open,
+    Close,
+    Comma,
+}
+
+/// Tokens with the characters each was read from
+fn tokenize(text: &str) -> Result<Vec<(Token, Range<usize>)>, ExpressionError> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        let token = match c {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '0'..='9' | '.' => {
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                // An exponent only when digits follow
+                if i < chars.len() && matches!(chars[i], 'e' | 'E') {
+                    let mut j = i + 1;
+                    if j < chars.len() && matches!(chars[j], '+' | '-') {
+                        j += 1;
+                    }
+                    if j < chars.len() && chars[j].is_ascii_digit() {
+                        i = j;
+                        while i < chars.len() && chars[i].is_ascii_digit() {
+                            i += 1;
+                        }
+                    }
+                }
+                let number: String = chars[start..i].iter().collect();
+                let value = number.parse().map_err(|_| ExpressionError::Unexpected {
+                    found: number.clone(),
+                    at: start,
+                })?;
+                tokens.push((Token::Number(value), start..i));
+                continue;
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.')
+                {
+                    i += 1;
+                }
+                tokens.push((Token::Name(chars[start..i].iter().collect()), start..i));
+                continue;
+            }
+            '+' => Token::Operator(Operator::Add),
+            '-' | '−' => Token::Operator(Operator::Subtract),
+            '*' | '×' | '·' => Token::Operator(Operator::Multiply),
+            '/' | '÷' => Token::Operator(Operator::Divide),
+            '%' => Token::Operator(Operator::Remainder),
+            '^' => Token::Operator(Operator::Power),
+            '(' => Token::Open,
+            ')' => Token::Close,
+            ',' => Token::Comma,
+            c => {
+                return Err(ExpressionError::Unexpected {
+                    found: c.to_string(),
+                    at: start,
+                });
+            }
+        };
+        i += 1;
+        tokens.push((token, start..i));
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent over the tokens, one precedence level at a time
+struct Parser {
+    chars: Vec<char>,
+    tokens: Vec<(Token, Range<usize>)>,
+    next: usize,
+    /// How deep the expression being read is nested so far
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next).map(|(token, _)| token)
+    }
+
+    fn unexpected(&self) -> ExpressionError {
+        match self.tokens.get(self.next) {
+            Some((_, span)) => ExpressionError::Unexpected {
+                found: self.chars[span.clone()].iter().collect(),
+                at: span.start,
+            },
+            None => ExpressionError::UnexpectedEnd,
+        }
+    }
+
+    /// One level further in, or `TooDeep` past `MAX_DEPTH`
+    fn deeper(&mut self) -> Result<(), ExpressionError> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            Err(ExpressionError::TooDeep)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Operators binding at least as tight as `min_precedence`. Every
+    /// nested operand is read through here, so this is where the depth is
+    /// counted.
+    fn binary(&mut self, min_precedence: u8) -> Result<Expression, ExpressionError> {
+        let outer = self.depth;
+        let result = self.operators(min_precedence);
+        self.depth = outer;
+        result
+    }
+
+    fn operators(&mut self, min_precedence: u8) -> Result<Expression, ExpressionError> {
+        self.deeper()?;
+        let mut left = self.unary()?;
+        while let Some(&Token::Operator(op)) = self.peek() {
+            if op.precedence() < min_precedence {
+                break;
+            }
+            self.next += 1;
+            // 1+2+3 is (1+2)+3, so each operator nests the left side deeper
+            self.deeper()?;
+            // Power is right-associative: 2^3^2 is 2^(3^2)
+            let next_min = if op == Operator::Power {
+                op.precedence()
+            } else {
+                op.precedence() + 1
+            };
+            let right = self.binary(next_min)?;
+            left = Expression::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expression, ExpressionError> {
+        // A leading plus changes nothing
+        while self.peek() == Some(&Token::Operator(Operator::Add)) {
+            self.next += 1;
+        }
+        match self.peek() {
+            Some(Token::Operator(Operator::Subtract)) => {
+                self.next += 1;
+                // -2^2 is -(2^2)
+                let operand = self.binary(Operator::Power.precedence())?;
+                Ok(Expression::Negate(Box::new(operand)))
+            }
+            _ => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expression, ExpressionError> {
+        let Some((token, _)) = self.tokens.get(self.next).cloned() else {
+            return Err(ExpressionError::UnexpectedEnd);
+        };
+        match token {
+            Token::Number(value) => {
+                self.next += 1;
+                Ok(Expression::Number(value))
+            }
+            Token::Name(name) => {
+                self.next += 1;
+                if self.peek() != Some(&Token::Open) {
+                    return Ok(Expression::Name(name));
+                }
+                self.next += 1;
+                let mut arguments = Vec::new();
+                if self.peek() == Some(&Token::Close) {
+                    self.next += 1;
+                    return Ok(Expression::Call(name, arguments));
+                }
+                loop {
+                    arguments.push(self.binary(1)?);
+                    match self.peek() {
+                        Some(Token::Comma) => self.next += 1,
+                        Some(Token::Close) => {
+                            self.next += 1;
+                            return Ok(Expression::Call(name, arguments));
+                        }
+                        _ => return Err(self.unexpected()),
+                    }
+                }
+            }
+            Token::Open => {
+                self.next += 1;
+                let inner = self.binary(1)?;
+                if self.peek() != Some(&Token::Close) {
+                    return Err(self.unexpected());
+                }
+                self.next += 1;
+                Ok(inner)
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+}
+
+impl Expression {
+    pub fn parse(text: &str) -> Result<Self, ExpressionError> {
+        let tokens = tokenize(text)?;
+        if tokens.is_empty() {
+            return Err(ExpressionError::Empty);
+        }
+        let mut parser = Parser {
+            chars: text.chars().collect(),
+            tokens,
+            next: 0,
+            depth: 0,
+        };
+        let expression = parser.binary(1)?;
+        if parser.next < parser.tokens.len() {
+            return Err(parser.unexpected());
+        }
+        Ok(expression)
+    }
+
+    /// Compute the value, looking names up with `resolve` first and then
+    /// among the constants
+    pub fn eval(&self, resolve: &dyn Fn(&str) -> Option<f64>) -> Result<f64, ExpressionError> {
+        let value = match self {
+            Expression::Number(value) => *value,
+            Expression::Name(name) => resolve(name)
+                .or_else(|| constant(name))
+                .ok_or_else(|| ExpressionError::UnknownName(name.clone()))?,
+            Expression::Negate(operand) => -operand.eval(resolve)?,
+            Expression::Binary(op, a, b) => op.apply(a.eval(resolve)?, b.eval(resolve)?),
+            Expression::Call(function, arguments) => {
+                let values = arguments
+                    .iter()
+                    .map(|argument| argument.eval(resolve))
+                    .collect::<Result<Vec<f64>, _>>()?;
+                call(function, &values)?
+            }
+        };
+        if value.is_finite() {
+            Ok(value)
+        } else {
+            Err(ExpressionError::NotANumber)
+        }
+    }
+
+    /// Every name the expression refers to, functions aside, in order of
+    /// appearance and without repeats
+    pub fn names(&self) -> Vec<&str> {
+        fn collect<'a>(expression: &'a Expression, out: &mut Vec<&'a str>) {
+            match expression {
+                Expression::Number(_) => {}
+                Expression::Name(name) => {
+                    if !out.contains(&name.as_str()) {
+                        out.push(name);
+                    }
+                }
+                Expression::Negate(operand) => collect(operand, out),
+                Expression::Binary(_, a, b) => {
+                    collect(a, out);
+                    collect(b, out);
+                }
+                Expression::Call(_, arguments) => {
+                    for argument in arguments {
+                        collect(argument, out);
+                    }
+                }
+            }
+        }
+        let mut names = Vec::new();
+        collect(self, &mut names);
+        names
+    }
+}
+
+/// Parse and compute `text` in one go
+pub fn evaluate(text: &str, resolve: &dyn Fn(&str) -> Option<f64>) -> Result<f64, ExpressionError> {
+    Expression::parse(text)?.eval(resolve)
+}
+
+fn constant(name: &str) -> Option<f64> {
+    match name.to_lowercase().as_str() {
+        "pi" | "π" => Some(std::f64::consts::PI),
+        "e" => Some(std::f64::consts::E),
+        _ => None,
+    }
+}
+
+fn call(function: &str, args: &[f64]) -> Result<f64, ExpressionError> {
+    let arity = |expected: usize| {
+        if args.len() == expected {
+            Ok(())
+        } else {
+            Err(ExpressionError::WrongArguments {
+                function: function.to_string(),
+                expected,
+            })
+        }
+    };
+    let name = function.to_lowercase();
+    let value = match name.as_str() {
+        "min" | "max" => {
+            if args.is_empty() {
+                arity(1)?;
+            }
+            let pick = if name == "min" { f64::min } else { f64::max };
+            args.iter().copied().reduce(pick).unwrap_or(0.0)
+        }
+        "atan2" | "hypot" => {
+            arity(2)?;
+            if name == "atan2" {
+                args[0].atan2(args[1]).to_degrees()
+            } else {
+                args[0].hypot(args[1])
+            }
+        }
+        _ => {
+            let f: fn(f64) -> f64 = match name.as_str() {
+                "sqrt" => f64::sqrt,
+                "abs" => f64::abs,
+                "round" => f64::round,
+                "floor" => f64::floor,
+                "ceil" => f64::ceil,
+                "ln" => f64::ln,
+                "log" => f64::log10,
+                "exp" => f64::exp,
+                "sin" => |x: f64| x.to_radians().sin(),
+                "cos" => |x: f64| x.to_radians().cos(),
+                "tan" => |x: f64| x.to_radians().tan(),
+                "asin" => |x: f64| x.asin().to_degrees(),
+                "acos" => |x: f64| x.acos().to_degrees(),
+                "atan" => |x: f64| x.atan().to_degrees(),
+                _ => return Err(ExpressionError::UnknownFunction(function.to_string())),
+            };
+            arity(1)?;
+            f(args[0])
+        }
+    };
+    Ok(value)
+}
@@ -306,7 +306,7 @@ fn intersect_ray_entity(
             let t1 = (v2.x * v1.y - v2.y * v1.x) / dot; // Distance along Ray
             let t2 = (dir.x * v1.y - dir.y * v1.x) / dot; // Position on Line (0..1)
 
-            if t1 >= 0.0 && t2 >= 0.0 && t2 <= 1.0 {
+            if t1 >= 0.0 && (0.0..=1.0).contains(&t2) {
                 return Some(origin + dir * t1);
             }
             None
@@ -345,25 +345,8 @@ fn intersect_ray_entity(
 
             for t in candidates {
                 let pos = origin + dir * t;
-                // Calculate angle for checking
                 let angle = (pos.y - arc.center.y).atan2(pos.x - arc.center.x);
-
-                // Normalize angles to [0, 2PI)
-                let normalize = |a: f32| -> f32 {
-                    let m = a % (2.0 * PI);
-                    if m < 0.0 { m + 2.0 * PI } else { m }
-                };
-
-                let start = normalize(arc.start_angle);
-                let end = normalize(arc.end_angle);
-                let angle_norm = normalize(angle);
-
-                let is_inside = if start < end {
-                    angle_norm >= start && angle_norm <= end
-                } else {
-                    // Span crosses 0/2PI
-                    angle_norm >= start || angle_norm <= end
-                };
+                let is_inside = arc.contains_angle(angle);
 
                 if is_inside {
                     return Some(pos);
@@ -375,95 +358,23 @@ fn intersect_ray_entity(
     }
 }
 
-// ─── Geometry Helpers (extracted from trim.rs) ───────────────────────────
+// ─── Geometry Helpers ────────────────────────────────────────────────────
 
-/// Calculate distance from a point to a line segment
-pub fn point_to_line_distance(p: Vector2, a: Vector2, b: Vector2) -> f32 {
-    let ab = b - a;
-    let ap = p - a;
-    let len_sq = ab.x * ab.x + ab.y * ab.y;
-
-    if len_sq == 0.0 {
-        return ((p.x - a.x).powi(2) + (p.y - a.y).powi(2)).sqrt();
+/// Circle through three points as `(center, radius)` (returns None if collinear)
+pub fn circle_through_points(a: Vector2, b: Vector2, c: Vector2) -> Option<(Vector2, f32)> {
+    let d = 2.0 * (a.x * (b.y - c.y) + b.x * (c.y - a.y) + c.x * (a.y - b.y));
+    if d.abs() < 1e-6 {
+        return None;
     }
 
-    let t = ((ap.x * ab.x + ap.y * ab.y) / len_sq).clamp(0.0, 1.0);
-    let closest = Vector2::new(a.x + t * ab.x, a.y + t * ab.y);
-    ((p.x - closest.x).powi(2) + (p.y - closest.y).powi(2)).sqrt()
-}
-
-/// Project point onto line and return parameter t (0 = start, 1 = end)
-pub fn project_point_on_line(p: Vector2, a: Vector2, b: Vector2) -> f32 {
-    let ab = b - a;
-    let ap = p - a;
-    let len_sq = ab.x * ab.x + ab.y * ab.y;
-    if len_sq == 0.0 {
-        return 0.0;
-    }
-    (ap.x * ab.x + ap.y * ab.y) / len_sq
-}
-
-/// Line-line intersection (returns None if parallel or no intersection within segments)
-pub fn line_line_intersection(
-    a1: Vector2,
-    a2: Vector2,
-    b1: Vector2,
-    b2: Vector2,
-) -> Option<Vector2> {
-    let d1 = a2 - a1;
-    let d2 = b2 - b1;
-    let cross = d1.x * d2.y - d1.y * d2.x;
-
-    if cross.abs() < 1e-10 {
-        return None; // Parallel
-    }
-
-    let d = b1 - a1;
-    let t = (d.x * d2.y - d.y * d2.x) / cross;
-    let u = (d.x * d1.y - d.y * d1.x) / cross;
-
-    // Check if intersection is within both line segments
-    if t >= 0.0 && t <= 1.0 && u >= 0.0 && u <= 1.0 {
-        Some(Vector2::new(a1.x + t * d1.x, a1.y + t * d1.y))
-    } else {
-        None
-    }
-}
-
-/// Line-circle intersection (returns 0, 1, or 2 points)
-pub fn line_circle_intersection(
-    p1: Vector2,
-    p2: Vector2,
-    center: Vector2,
-    radius: f32,
-) -> Vec<Vector2> {
-    let d = p2 - p1;
-    let f = p1 - center;
-
-    let a = d.x * d.x + d.y * d.y;
-    let b = 2.0 * (f.x * d.x + f.y * d.y);
-    let c = f.x * f.x + f.y * f.y - radius * radius;
-
-    let discriminant = b * b - 4.0 * a * c;
-
-    if discriminant < 0.0 {
-        return Vec::new();
-    }
-
-    let mut results = Vec::new();
-    let sqrt_disc = discriminant.sqrt();
-
-    let t1 = (-b - sqrt_disc) / (2.0 * a);
-    let t2 = (-b + sqrt_disc) / (2.0 * a);
-
-    if t1 >= 0.0 && t1 <= 1.0 {
-        results.push(Vector2::new(p1.x + t1 * d.x, p1.y + t1 * d.y));
-    }
-    if t2 >= 0.0 && t2 <= 1.0 && (t2 - t1).abs() > 1e-6 {
-        results.push(Vector2::new(p1.x + t2 * d.x, p1.y + t2 * d.y));
-    }
-
-    results
+    let a2 = a.length_squared();
+    let b2 = b.length_squared();
+    let c2 = c.length_squared();
+    let center = Vector2::new(
+        (a2 * (b.y - c.y) + b2 * (c.y - a.y) + c2 * (a.y - b.y)) / d,
+        (a2 * (c.x - b.x) + b2 * (a.x - c.x) + c2 * (b.x - a.x)) / d,
+    );
+    Some((center, center.dist(a)))
 }
 
 /// Calculate centroid of a polygon
@@ -481,6 +392,38 @@ pub fn calculate_centroid(vertices: &[Vector2]) -> Vector2 {
     Vector2::new(cx * inv_len, cy * inv_len)
 }
 
+/// Drop the points of an open path that lie within `tolerance` of the
+/// path through the points kept (Douglas–Peucker). The ends always stay.
+pub fn simplify_path(points: &[Vector2], tolerance: f32) -> Vec<Vector2> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+
+    // Spans still to split, as (first, last) indices
+    let mut spans = vec![(0, points.len() - 1)];
+    while let Some((first, last)) = spans.pop() {
+        let farthest = (first + 1..last)
+            .map(|i| (i, points[i].dist_to_line(points[first], points[last])))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((i, distance)) = farthest
+            && distance > tolerance
+        {
+            keep[i] = true;
+            spans.push((first, i));
+            spans.push((i, last));
+        }
+    }
+
+    points
+        .iter()
+        .zip(keep)
+        .filter_map(|(&p, kept)| kept.then_some(p))
+        .collect()
+}
+
 // ─── Area / Perimeter ────────────────────────────────────────────────────
 
 pub fn calculate_polygon_area(points: &[Vector2]) -> f32 {
@@ -1,2 +1,6 @@
+pub mod angle;
+pub mod closest;
+pub mod expression;
 pub mod geometry;
+pub mod segment;
 pub mod vector;
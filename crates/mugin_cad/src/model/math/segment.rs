@@ -0,0 +1,85 @@
+//! Straight segments from `a` to `b`: where points fall along them, how
+//! far away they are, and where they cross other segments and circles.
+//! Positions along a segment are parameters, 0 at `a` and 1 at `b`.
+
+use super::vector::Vector2;
+
+/// Parameter of the foot of `p` on the infinite line through `a` and `b`.
+/// 0 for a segment of no length.
+pub fn project(p: Vector2, a: Vector2, b: Vector2) -> f32 {
+    let ab = b - a;
+    let len_sq = ab.length_squared();
+    if len_sq == 0.0 {
+        return 0.0;
+    }
+    (p - a).dot(ab) / len_sq
+}
+
+/// Parameter of the point of the segment closest to `p`
+pub fn closest_param(p: Vector2, a: Vector2, b: Vector2) -> f32 {
+    project(p, a, b).clamp(0.0, 1.0)
+}
+
+/// Point of the segment closest to `p`
+pub fn closest_point(p: Vector2, a: Vector2, b: Vector2) -> Vector2 {
+    a.lerp(b, closest_param(p, a, b))
+}
+
+/// Distance from `p` to the segment
+pub fn distance(p: Vector2, a: Vector2, b: Vector2) -> f32 {
+    p.dist(closest_point(p, a, b))
+}
+
+/// Parameters `(t, u)` where the infinite lines through `a1`-`a2` and
+/// `b1`-`b2` cross, `None` if they are parallel
+fn crossing_params(a1: Vector2, a2: Vector2, b1: Vector2, b2: Vector2) -> Option<(f32, f32)> {
+    let (d1, d2) = (a2 - a1, b2 - b1);
+    let cross = d1.cross(d2);
+    if cross.abs() < 1e-10 {
+        return None;
+    }
+    let d = b1 - a1;
+    Some((d.cross(d2) / cross, d.cross(d1) / cross))
+}
+
+/// Where two segments cross, if they do
+pub fn intersection(a1: Vector2, a2: Vector2, b1: Vector2, b2: Vector2) -> Option<Vector2> {
+    let (t, u) = crossing_params(a1, a2, b1, b2)?;
+    ((0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u)).then(|| a1.lerp(a2, t))
+}
+
+/// Where the infinite lines through the segments cross, `None` if they are
+/// parallel
+pub fn line_intersection(a1: Vector2, a2: Vector2, b1: Vector2, b2: Vector2) -> Option<Vector2> {
+    let (t, _) = crossing_params(a1, a2, b1, b2)?;
+    Some(a1.lerp(a2, t))
+}
+
+/// Where the segment crosses the circle: none, one (also when it only
+/// touches) or two points, nearest `a` first
+pub fn circle_intersections(a: Vector2, b: Vector2, center: Vector2, radius: f32) -> Vec<Vector2> {
+    let d = b - a;
+    let f = a - center;
+    let qa = d.length_squared();
+    if qa == 0.0 {
+        return Vec::new();
+    }
+    let qb = 2.0 * f.dot(d);
+    let qc = f.length_squared() - radius * radius;
+    let discriminant = qb * qb - 4.0 * qa * qc;
+    if discriminant < 0.0 {
+        return Vec::new();
+    }
+
+    let root = discriminant.sqrt();
+    let t1 = (-qb - root) / (2.0 * qa);
+    let t2 = (-qb + root) / (2.0 * qa);
+    let mut points = Vec::new();
+    if (0.0..=1.0).contains(&t1) {
+        points.push(a.lerp(b, t1));
+    }
+    if (0.0..=1.0).contains(&t2) && (t2 - t1).abs() > 1e-6 {
+        points.push(a.lerp(b, t2));
+    }
+    points
+}
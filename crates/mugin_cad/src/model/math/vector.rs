@@ -1,3 +1,4 @@
+use super::segment;
 use glam::Vec2;
 use serde::{Deserialize, Serialize};
 use std::ops::{Add, Div, Mul, Neg, Sub};
@@ -50,22 +51,51 @@ impl Vector2 {
         (*self - other).length()
     }
 
+    /// Distance to the segment `start`-`end`
     pub fn dist_to_line(&self, start: Self, end: Self) -> f32 {
-        let length_sq = (end.x - start.x).powi(2) + (end.y - start.y).powi(2);
-        if length_sq == 0.0 {
-            return self.dist(start);
-        }
+        segment::distance(*self, start, end)
+    }
 
-        let t = ((self.x - start.x) * (end.x - start.x) + (self.y - start.y) * (end.y - start.y))
-            / length_sq;
-        let t = t.clamp(0.0, 1.0);
+    /// Z component of the 3D cross product: positive when `other` turns
+    /// counterclockwise from `self`
+    pub fn cross(&self, other: Self) -> f32 {
+        self.x * other.y - self.y * other.x
+    }
 
-        let projection = Vector2::new(
-            start.x + t * (end.x - start.x),
-            start.y + t * (end.y - start.y),
-        );
+    /// Turned a quarter counterclockwise
+    pub fn perp(&self) -> Self {
+        Self::new(-self.y, self.x)
+    }
 
-        self.dist(projection)
+    /// Point `t` of the way from `self` to `other`
+    pub fn lerp(&self, other: Self, t: f32) -> Self {
+        *self + (other - *self) * t
+    }
+
+    /// Turned by `angle` radians counterclockwise about the origin
+    pub fn rotated(&self, angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self::new(self.x * cos - self.y * sin, self.x * sin + self.y * cos)
+    }
+
+    /// Turned by `angle` radians counterclockwise about `center`
+    pub fn rotate_around(&self, center: Self, angle: f32) -> Self {
+        center + (*self - center).rotated(angle)
+    }
+
+    /// Angle in radians that turns `self` onto `other`, counterclockwise
+    /// positive, in `-PI..=PI`
+    pub fn angle_to(&self, other: Self) -> f32 {
+        self.cross(other).atan2(self.dot(other))
+    }
+
+    /// The part of `self` along `other`; zero if `other` is
+    pub fn project_onto(&self, other: Self) -> Self {
+        let len_sq = other.length_squared();
+        if len_sq == 0.0 {
+            return Self::default();
+        }
+        other * (self.dot(other) / len_sq)
     }
 }
 
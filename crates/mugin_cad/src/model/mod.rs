@@ -11,31 +11,53 @@
 //! - `Vector2`: Basic math primitives.
 
 pub mod axis;
+pub mod flag;
 pub mod layer;
 pub mod math;
+pub mod reference;
 pub mod shapes;
 pub mod structure;
 pub mod system;
 pub mod tools;
 
 pub use math::vector;
+pub use system::backup;
+pub use system::color_mode;
 pub use system::config;
+pub use system::dimension;
+pub use system::drafting;
+pub use system::input_history;
 pub use system::project;
+pub use system::recent;
+pub use system::recovery;
+pub use system::template;
 pub use tools::snap;
 pub use tools::undo;
 
-use glam::Affine2;
+use ecolor::Color32;
+use glam::{Affine2, Mat2, Vec2};
+use layer::{DEFAULT_LAYER_COLOR, DEFAULT_STROKE_WIDTH, Layer, LayerManager, LineStyle};
 use serde::{Deserialize, Serialize};
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use tools::changes::ChangeLog;
 
+pub use flag::FlagColor;
 pub use shapes::Geometry;
 pub use shapes::annotation::TextAnnotation;
 pub use shapes::arc::Arc;
 pub use shapes::circle::Circle;
+pub use shapes::image::ImageRef;
 pub use shapes::line::Line;
 pub use shapes::rectangle::Rectangle;
+pub use shapes::revision_cloud::RevisionCloud;
+pub use shapes::room::Room;
+pub use shapes::section::SectionMark;
+pub use shapes::sketch::Sketch;
 pub use structure::beam::BeamData;
 pub use structure::column::ColumnData;
+pub use structure::flooring::Flooring;
+pub use structure::stair::StairData;
 pub use vector::Vector2;
 
 /// Global atomic counter for unique entity IDs.
@@ -45,10 +67,34 @@ fn next_id() -> u64 {
     NEXT_ENTITY_ID.fetch_add(1, Ordering::Relaxed)
 }
 
+/// Global counter for geometry revisions. Sharing one counter means a value
+/// never comes back with different geometry, not even after an undo.
+static NEXT_REVISION: AtomicU32 = AtomicU32::new(1);
+
+fn next_revision() -> u32 {
+    NEXT_REVISION.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Box around `(min, max)` boxes; inverted (MAX, MIN) when there are none
+fn union_bounds(boxes: impl IntoIterator<Item = (Vector2, Vector2)>) -> (Vector2, Vector2) {
+    boxes.into_iter().fold(
+        (
+            Vector2::new(f32::MAX, f32::MAX),
+            Vector2::new(f32::MIN, f32::MIN),
+        ),
+        |(lo, hi), (min, max)| {
+            (
+                Vector2::new(lo.x.min(min.x), lo.y.min(min.y)),
+                Vector2::new(hi.x.max(max.x), hi.y.max(max.y)),
+            )
+        },
+    )
+}
+
 // ─── Shape ──────────────────────────────────────────────────────
 
 /// The geometric primitive of an entity, or `None` for empty containers.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Shape {
     /// Empty container — has no geometry, only used as a hierarchy parent.
     None,
@@ -59,6 +105,13 @@ pub enum Shape {
     Text(TextAnnotation),
     Column(ColumnData),
     Beam(BeamData),
+    Image(ImageRef),
+    Section(SectionMark),
+    Stair(StairData),
+    Flooring(Flooring),
+    RevisionCloud(RevisionCloud),
+    Room(Room),
+    Sketch(Sketch),
 }
 
 impl Shape {
@@ -72,6 +125,73 @@ impl Shape {
             Shape::Text(_) => "Text",
             Shape::Column(_) => "Column",
             Shape::Beam(_) => "Beam",
+            Shape::Image(_) => "Image",
+            Shape::Section(_) => "Section",
+            Shape::Stair(_) => "Stair",
+            Shape::Flooring(_) => "Flooring",
+            Shape::RevisionCloud(_) => "Revision Cloud",
+            Shape::Room(_) => "Room",
+            Shape::Sketch(_) => "Sketch",
+        }
+    }
+
+    /// Scale the geometry about `origin` by a positive `factor`. Column
+    /// sections come from their type, so columns only move.
+    pub fn scale(&mut self, origin: Vector2, factor: f32) {
+        let s = |p: &mut Vector2| *p = origin + (*p - origin) * factor;
+        match self {
+            Shape::None => {}
+            Shape::Line(line) => {
+                s(&mut line.start);
+                s(&mut line.end);
+            }
+            Shape::Circle(circle) => {
+                s(&mut circle.center);
+                circle.radius *= factor;
+            }
+            Shape::Rectangle(rect) => {
+                s(&mut rect.min);
+                s(&mut rect.max);
+            }
+            Shape::Arc(arc) => {
+                s(&mut arc.center);
+                arc.radius *= factor;
+            }
+            Shape::Text(text) => {
+                s(&mut text.position);
+                text.anchor_points.iter_mut().for_each(s);
+            }
+            Shape::Column(column) => s(&mut column.center),
+            Shape::Beam(beam) => {
+                s(&mut beam.start);
+                s(&mut beam.end);
+            }
+            Shape::Image(image) => {
+                s(&mut image.position);
+                image.scale *= factor;
+            }
+            Shape::Section(section) => {
+                s(&mut section.start);
+                s(&mut section.end);
+            }
+            Shape::Stair(stair) => {
+                s(&mut stair.start);
+                stair.width *= factor;
+                stair.tread *= factor;
+            }
+            Shape::Flooring(floor) => {
+                floor.boundary.iter_mut().for_each(s);
+                floor.holes.iter_mut().flatten().for_each(s);
+            }
+            Shape::RevisionCloud(cloud) => {
+                cloud.boundary.iter_mut().for_each(s);
+                cloud.arc_size *= factor;
+            }
+            Shape::Room(room) => {
+                room.boundary.iter_mut().for_each(s);
+                room.area *= factor * factor;
+            }
+            Shape::Sketch(sketch) => sketch.points.iter_mut().for_each(s),
         }
     }
 }
@@ -87,6 +207,13 @@ impl Geometry for Shape {
             Shape::Text(s) => s.hit_test(pos, tolerance),
             Shape::Column(s) => s.hit_test(pos, tolerance),
             Shape::Beam(s) => s.hit_test(pos, tolerance),
+            Shape::Image(s) => s.hit_test(pos, tolerance),
+            Shape::Section(s) => s.hit_test(pos, tolerance),
+            Shape::Stair(s) => s.hit_test(pos, tolerance),
+            Shape::Flooring(s) => s.hit_test(pos, tolerance),
+            Shape::RevisionCloud(s) => s.hit_test(pos, tolerance),
+            Shape::Room(s) => s.hit_test(pos, tolerance),
+            Shape::Sketch(s) => s.hit_test(pos, tolerance),
         }
     }
 
@@ -103,6 +230,13 @@ impl Geometry for Shape {
             Shape::Text(s) => s.bounding_box(),
             Shape::Column(s) => s.bounding_box(),
             Shape::Beam(s) => s.bounding_box(),
+            Shape::Image(s) => s.bounding_box(),
+            Shape::Section(s) => s.bounding_box(),
+            Shape::Stair(s) => s.bounding_box(),
+            Shape::Flooring(s) => s.bounding_box(),
+            Shape::RevisionCloud(s) => s.bounding_box(),
+            Shape::Room(s) => s.bounding_box(),
+            Shape::Sketch(s) => s.bounding_box(),
         }
     }
 
@@ -116,6 +250,13 @@ impl Geometry for Shape {
             Shape::Text(s) => s.as_polyline(),
             Shape::Column(s) => s.as_polyline(),
             Shape::Beam(s) => s.as_polyline(),
+            Shape::Image(s) => s.as_polyline(),
+            Shape::Section(s) => s.as_polyline(),
+            Shape::Stair(s) => s.as_polyline(),
+            Shape::Flooring(s) => s.as_polyline(),
+            Shape::RevisionCloud(s) => s.as_polyline(),
+            Shape::Room(s) => s.as_polyline(),
+            Shape::Sketch(s) => s.as_polyline(),
         }
     }
 
@@ -129,6 +270,13 @@ impl Geometry for Shape {
             Shape::Text(s) => s.is_closed(),
             Shape::Column(s) => s.is_closed(),
             Shape::Beam(s) => s.is_closed(),
+            Shape::Image(s) => s.is_closed(),
+            Shape::Section(s) => s.is_closed(),
+            Shape::Stair(s) => s.is_closed(),
+            Shape::Flooring(s) => s.is_closed(),
+            Shape::RevisionCloud(s) => s.is_closed(),
+            Shape::Room(s) => s.is_closed(),
+            Shape::Sketch(s) => s.is_closed(),
         }
     }
 
@@ -142,12 +290,49 @@ impl Geometry for Shape {
             Shape::Text(s) => s.is_filled(),
             Shape::Column(s) => s.is_filled(),
             Shape::Beam(s) => s.is_filled(),
+            Shape::Image(s) => s.is_filled(),
+            Shape::Section(s) => s.is_filled(),
+            Shape::Stair(s) => s.is_filled(),
+            Shape::Flooring(s) => s.is_filled(),
+            Shape::RevisionCloud(s) => s.is_filled(),
+            Shape::Room(s) => s.is_filled(),
+            Shape::Sketch(s) => s.is_filled(),
+        }
+    }
+    fn snap_points(&self) -> Vec<(snap::SnapPointType, Vector2)> {
+        match self {
+            Shape::None => Vec::new(),
+            Shape::Line(s) => s.snap_points(),
+            Shape::Circle(s) => s.snap_points(),
+            Shape::Rectangle(s) => s.snap_points(),
+            Shape::Arc(s) => s.snap_points(),
+            Shape::Text(s) => s.snap_points(),
+            Shape::Column(s) => s.snap_points(),
+            Shape::Beam(s) => s.snap_points(),
+            Shape::Image(s) => s.snap_points(),
+            Shape::Section(s) => s.snap_points(),
+            Shape::Stair(s) => s.snap_points(),
+            Shape::Flooring(s) => s.snap_points(),
+            Shape::RevisionCloud(s) => s.snap_points(),
+            Shape::Room(s) => s.snap_points(),
+            Shape::Sketch(s) => s.snap_points(),
         }
     }
 }
 
 // ─── Entity ─────────────────────────────────────────────────────
 
+/// Effective look of an entity once "by layer" values are resolved
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EntityStyle {
+    pub color: Color32,
+    pub line_style: LineStyle,
+    /// Stroke width in screen pixels
+    pub stroke_width: f32,
+    /// Whether it reaches printed sheets
+    pub plottable: bool,
+}
+
 /// A node in the scene hierarchy.
 ///
 /// Every entity has a unique `id`, a display `name`, an optional geometric
@@ -158,7 +343,36 @@ pub struct Entity {
     pub name: String,
     pub shape: Shape,
     pub layer_id: u64,
+    /// Explicit color (RGB); `None` draws with the layer color
+    #[serde(default)]
+    pub color: Option<[u8; 3]>,
+    /// Explicit line style; `None` uses the layer's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line_style: Option<LineStyle>,
+    /// Explicit stroke width in screen pixels; `None` uses the layer's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stroke_width: Option<f32>,
+    /// Explicit printability; `None` follows the layer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plottable: Option<bool>,
     pub children: Vec<Entity>,
+    /// User data such as room number or fire rating, keyed by attribute name
+    #[serde(default)]
+    pub attributes: BTreeMap<String, String>,
+    /// Free-text notes
+    #[serde(default)]
+    pub notes: String,
+    /// Library symbol this group was placed from; its children are
+    /// picked and edited as one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    /// Review flag, to find the entity again
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flag: Option<FlagColor>,
+    /// Expressions driving numeric properties, keyed by property name,
+    /// e.g. `width` = `Column_1.width * 2`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub constraints: BTreeMap<String, String>,
 
     /// Local transform relative to parent.
     pub local_transform: Affine2,
@@ -166,6 +380,9 @@ pub struct Entity {
     pub world_transform: Affine2,
     /// Whether the world transform needs recomputation.
     pub is_dirty: bool,
+    /// Geometry revision; changes with the shape or local transform.
+    #[serde(skip, default = "next_revision")]
+    revision: u32,
 }
 
 impl Entity {
@@ -179,10 +396,20 @@ impl Entity {
             name,
             shape,
             layer_id: 0,
+            color: None,
+            line_style: None,
+            stroke_width: None,
+            plottable: None,
             children: Vec::new(),
+            attributes: BTreeMap::new(),
+            notes: String::new(),
+            symbol: None,
+            flag: None,
+            constraints: BTreeMap::new(),
             local_transform: Affine2::IDENTITY,
             world_transform: Affine2::IDENTITY,
             is_dirty: true,
+            revision: next_revision(),
         }
     }
 
@@ -193,10 +420,34 @@ impl Entity {
             name: name.into(),
             shape: Shape::None,
             layer_id: 0,
+            color: None,
+            line_style: None,
+            stroke_width: None,
+            plottable: None,
             children: Vec::new(),
+            attributes: BTreeMap::new(),
+            notes: String::new(),
+            symbol: None,
+            flag: None,
+            constraints: BTreeMap::new(),
             local_transform: Affine2::IDENTITY,
             world_transform: Affine2::IDENTITY,
             is_dirty: true,
+            revision: next_revision(),
+        }
+    }
+
+    /// Deep copy of this entity and its children, each with a fresh ID.
+    pub fn duplicate(&self) -> Self {
+        let mut copy = self.clone();
+        copy.assign_fresh_ids();
+        copy
+    }
+
+    fn assign_fresh_ids(&mut self) {
+        self.id = next_id();
+        for child in &mut self.children {
+            child.assign_fresh_ids();
         }
     }
 
@@ -230,13 +481,85 @@ impl Entity {
         Self::new(Shape::Beam(data))
     }
 
+    pub fn section(mark: SectionMark) -> Self {
+        Self::new(Shape::Section(mark))
+    }
+
+    pub fn stair(data: StairData) -> Self {
+        Self::new(Shape::Stair(data))
+    }
+
+    pub fn flooring(floor: Flooring) -> Self {
+        Self::new(Shape::Flooring(floor))
+    }
+
+    pub fn revision_cloud(cloud: RevisionCloud) -> Self {
+        Self::new(Shape::RevisionCloud(cloud))
+    }
+
+    pub fn sketch(sketch: Sketch) -> Self {
+        Self::new(Shape::Sketch(sketch))
+    }
+
+    /// A room entity named like the room
+    pub fn room(room: Room) -> Self {
+        let name = room.name.clone();
+        let mut entity = Self::new(Shape::Room(room));
+        entity.name = name;
+        entity
+    }
+
+    pub fn image(image: ImageRef) -> Self {
+        let mut entity = Self::new(Shape::Image(image));
+        if let Shape::Image(image) = &entity.shape {
+            entity.name = image.file_name().to_string();
+        }
+        entity
+    }
+
     // ── Queries ─────────────────────────────────────────────
 
     pub fn type_name(&self) -> &str {
         self.shape.type_name()
     }
 
-    pub fn hit_test(&self, pos: Vector2, tolerance: f32) -> bool {
+    /// Color, line style, stroke width and printability after resolving
+    /// "by layer" values against the entity's layer
+    pub fn style(&self, layer: Option<&Layer>) -> EntityStyle {
+        EntityStyle {
+            color: match self.color {
+                Some([r, g, b]) => Color32::from_rgb(r, g, b),
+                None => layer.map_or(DEFAULT_LAYER_COLOR, |l| l.color),
+            },
+            line_style: self
+                .line_style
+                .unwrap_or_else(|| layer.map_or(LineStyle::Solid, |l| l.line_style)),
+            stroke_width: self
+                .stroke_width
+                .unwrap_or_else(|| layer.map_or(DEFAULT_STROKE_WIDTH, |l| l.stroke_width)),
+            plottable: self.plots(layer),
+        }
+    }
+
+    /// Whether the entity reaches printed sheets. Groups have no ink of
+    /// their own, so only their explicit setting counts; an entity that
+    /// doesn't plot leaves its children off too.
+    pub fn plots(&self, layer: Option<&Layer>) -> bool {
+        match (self.plottable, &self.shape) {
+            (Some(plottable), _) => plottable,
+            (None, Shape::None) => true,
+            (None, _) => layer.is_none_or(|l| l.plottable),
+        }
+    }
+
+    /// Whether `pos` hits the entity or one of its children. Beams are
+    /// as wide as their type in `definitions`.
+    pub fn hit_test(
+        &self,
+        pos: Vector2,
+        tolerance: f32,
+        definitions: &StructureDefinitions,
+    ) -> bool {
         // Convert world pos to local pos
         let local_pos: Vector2 = self
             .world_transform
@@ -245,11 +568,34 @@ impl Entity {
             .into();
 
         // Check own shape
-        if self.shape.hit_test(local_pos, tolerance) {
+        let hit = match &self.shape {
+            Shape::Beam(beam) => {
+                beam.hit_test_body(local_pos, tolerance, beam.width_in(definitions))
+            }
+            shape => shape.hit_test(local_pos, tolerance),
+        };
+        if hit {
             return true;
         }
         // Check children
-        self.children.iter().any(|c| c.hit_test(pos, tolerance))
+        self.children
+            .iter()
+            .any(|c| c.hit_test(pos, tolerance, definitions))
+    }
+
+    // ── Revisions ───────────────────────────────────────────
+
+    /// Geometry revision. Caches of derived geometry compare it to tell
+    /// whether the entity changed; a parent's transform is not included.
+    pub fn revision(&self) -> u32 {
+        self.revision
+    }
+
+    /// Edit the shape in place and mark the geometry as changed
+    pub fn modify<R>(&mut self, edit: impl FnOnce(&mut Shape) -> R) -> R {
+        let result = edit(&mut self.shape);
+        self.revision = next_revision();
+        result
     }
 
     // ── Transforms ──────────────────────────────────────────
@@ -257,6 +603,7 @@ impl Entity {
     pub fn translate(&mut self, delta: Vector2) {
         let translation = Affine2::from_translation(delta.into());
         self.local_transform = translation * self.local_transform;
+        self.revision = next_revision();
         self.set_dirty();
     }
 
@@ -264,10 +611,37 @@ impl Entity {
         // To rotate around a pivot in local space: T(pivot) * R(angle) * T(-pivot)
         let t1 = Affine2::from_translation(pivot.into());
         let r = Affine2::from_angle(angle);
-        let t2 = Affine2::from_translation((-Vector2::from(pivot)).into());
+        let t2 = Affine2::from_translation((-pivot).into());
         let rot_at_pivot = t1 * r * t2;
 
         self.local_transform = rot_at_pivot * self.local_transform;
+        self.revision = next_revision();
+        self.set_dirty();
+    }
+
+    /// Mirror across the line through `pivot` along `axis`, e.g. a vertical
+    /// `axis` flips left and right
+    pub fn mirror(&mut self, pivot: Vector2, axis: Vector2) {
+        let angle = 2.0 * axis.y.atan2(axis.x);
+        let (sin, cos) = angle.sin_cos();
+        let reflection = Mat2::from_cols(Vec2::new(cos, sin), Vec2::new(sin, -cos));
+        let t1 = Affine2::from_translation(pivot.into());
+        let m = Affine2::from_mat2(reflection);
+        let t2 = Affine2::from_translation((-pivot).into());
+
+        self.local_transform = t1 * m * t2 * self.local_transform;
+        self.revision = next_revision();
+        self.set_dirty();
+    }
+
+    /// Scale about a world-space `base` point. The geometry is scaled in
+    /// place, so transforms stay rigid; children are scaled the same way.
+    pub fn scale(&mut self, base: Vector2, factor: f32) {
+        let local_base = self.world_transform.inverse().transform_point2(base.into());
+        self.modify(|shape| shape.scale(local_base.into(), factor));
+        for child in &mut self.children {
+            child.scale(base, factor);
+        }
         self.set_dirty();
     }
 
@@ -275,39 +649,61 @@ impl Entity {
 
     /// Returns the axis-aligned bounding box as `(min, max)`.
     pub fn bounding_box(&self) -> (Vector2, Vector2) {
-        let (local_min, local_max) = self.shape.bounding_box();
-
-        let mut world_min = Vector2::new(f32::MAX, f32::MAX);
-        let mut world_max = Vector2::new(f32::MIN, f32::MIN);
-
-        // If shape is None, it won't affect the box expansion if we use MAX/MIN
-        if !matches!(self.shape, Shape::None) {
-            let corners = [
-                Vector2::new(local_min.x, local_min.y),
-                Vector2::new(local_max.x, local_min.y),
-                Vector2::new(local_min.x, local_max.y),
-                Vector2::new(local_max.x, local_max.y),
-            ];
-
-            for corner in corners {
-                let transformed: Vector2 =
-                    self.world_transform.transform_point2(corner.into()).into();
-                world_min.x = world_min.x.min(transformed.x);
-                world_min.y = world_min.y.min(transformed.y);
-                world_max.x = world_max.x.max(transformed.x);
-                world_max.y = world_max.y.max(transformed.y);
-            }
-        }
+        self.bounds(None)
+    }
 
-        for child in &self.children {
-            let (c_min, c_max) = child.bounding_box();
-            world_min.x = world_min.x.min(c_min.x);
-            world_min.y = world_min.y.min(c_min.y);
-            world_max.x = world_max.x.max(c_max.x);
-            world_max.y = world_max.y.max(c_max.y);
+    /// Bounding box with beams as wide as their type in `definitions`
+    pub fn bounding_box_in(&self, definitions: &StructureDefinitions) -> (Vector2, Vector2) {
+        self.bounds(Some(definitions))
+    }
+
+    fn bounds(&self, definitions: Option<&StructureDefinitions>) -> (Vector2, Vector2) {
+        let own = self.shape_bounds(definitions);
+        let children = self.children.iter().map(|child| child.bounds(definitions));
+        union_bounds(own.into_iter().chain(children))
+    }
+
+    /// World bounding box of the parts that plot, `None` if nothing does
+    pub fn plot_bounds(
+        &self,
+        layers: &LayerManager,
+        definitions: &StructureDefinitions,
+    ) -> Option<(Vector2, Vector2)> {
+        if !self.plots(layers.get_layer(self.layer_id)) {
+            return None;
         }
+        let own = self.shape_bounds(Some(definitions));
+        let children = self
+            .children
+            .iter()
+            .filter_map(|child| child.plot_bounds(layers, definitions));
+        let (min, max) = union_bounds(own.into_iter().chain(children));
+        (min.x <= max.x && min.y <= max.y).then_some((min, max))
+    }
 
-        (world_min, world_max)
+    /// World bounding box of the entity's own shape, without children
+    fn shape_bounds(
+        &self,
+        definitions: Option<&StructureDefinitions>,
+    ) -> Option<(Vector2, Vector2)> {
+        // A container has no extent of its own
+        if matches!(self.shape, Shape::None) {
+            return None;
+        }
+        let (local_min, local_max) = match (&self.shape, definitions) {
+            (Shape::Beam(beam), Some(definitions)) => beam.body_bounds(beam.width_in(definitions)),
+            (shape, _) => shape.bounding_box(),
+        };
+        let corners = [
+            Vector2::new(local_min.x, local_min.y),
+            Vector2::new(local_max.x, local_min.y),
+            Vector2::new(local_min.x, local_max.y),
+            Vector2::new(local_max.x, local_max.y),
+        ];
+        Some(union_bounds(corners.into_iter().map(|corner| {
+            let p: Vector2 = self.world_transform.transform_point2(corner.into()).into();
+            (p, p)
+        })))
     }
 
     /// Convert the entity to a polyline (list of points) in world space.
@@ -335,6 +731,40 @@ impl Entity {
         self.shape.is_filled() || self.children.iter().any(|c| c.is_filled())
     }
 
+    /// Whether `other` has the same saved properties and children, ignoring
+    /// the computed world transform and geometry revision
+    pub fn same_content(&self, other: &Entity) -> bool {
+        self.same_own_content(other)
+            && self
+                .children
+                .iter()
+                .zip(&other.children)
+                .all(|(a, b)| a.same_content(b))
+    }
+
+    /// Like `same_content`, but children only count by ID and order
+    pub fn same_own_content(&self, other: &Entity) -> bool {
+        self.id == other.id
+            && self.name == other.name
+            && self.shape == other.shape
+            && self.layer_id == other.layer_id
+            && self.color == other.color
+            && self.line_style == other.line_style
+            && self.stroke_width == other.stroke_width
+            && self.attributes == other.attributes
+            && self.notes == other.notes
+            && self.symbol == other.symbol
+            && self.flag == other.flag
+            && self.constraints == other.constraints
+            && self.local_transform == other.local_transform
+            && self.children.len() == other.children.len()
+            && self
+                .children
+                .iter()
+                .zip(&other.children)
+                .all(|(a, b)| a.id == b.id)
+    }
+
     // ── Hierarchy helpers ───────────────────────────────────
 
     /// Find a descendant entity by id (recursive).
@@ -383,22 +813,37 @@ impl Entity {
     }
 
     /// Pick an entity ID at the given position (recursive).
-    /// Returns the ID of the deepest child that was hit.
+    /// Returns the ID of the deepest child that was hit. Entities `accept`
+    /// turns down are passed over, their children still picked.
     pub fn pick(
         &self,
         pos: Vector2,
         tolerance: f32,
         layer_manager: &crate::model::layer::LayerManager,
+        definitions: &StructureDefinitions,
+        accept: &dyn Fn(&Entity) -> bool,
     ) -> Option<u64> {
+        // Hidden layers are not pickable (mirrors the renderer)
+        if let Some(layer) = layer_manager.get_layer(self.layer_id)
+            && !layer.is_visible
+        {
+            return None;
+        }
+
+        // A placed symbol is picked whole
+        if self.symbol.is_some() {
+            return (accept(self) && self.hit_test(pos, tolerance, definitions)).then_some(self.id);
+        }
+
         // Check children first (render order usually means children are on top)
         for child in self.children.iter().rev() {
-            if let Some(id) = child.pick(pos, tolerance, layer_manager) {
+            if let Some(id) = child.pick(pos, tolerance, layer_manager, definitions, accept) {
                 return Some(id);
             }
         }
 
         // Check self
-        if self.hit_test(pos, tolerance) {
+        if accept(self) && self.hit_test(pos, tolerance, definitions) {
             return Some(self.id);
         }
 
@@ -409,13 +854,32 @@ impl Entity {
 // ─── CadModel ───────────────────────────────────────────────────
 
 use crate::model::structure::definitions::StructureDefinitions;
+use crate::model::structure::storey::StoreyManager;
 
+#[derive(Clone)]
 pub struct CadModel {
     pub entities: Vec<Entity>,
     pub axis_manager: axis::AxisManager,
     pub definitions: StructureDefinitions,
+    pub storeys: StoreyManager,
     pub layer_manager: layer::LayerManager,
     pub export_region: Option<(Vector2, Vector2)>,
+    /// Entity changes, for caches derived from the geometry
+    pub changes: ChangeLog,
+    /// Last number handed out per shape type for names like "Line 12"
+    pub name_counters: HashMap<&'static str, u32>,
+    /// Change revision beam attachments were last resolved at
+    attachments_revision: u64,
+    /// Columns as they were at that revision
+    attached_columns: structure::attachment::ColumnMap,
+    /// Beam junctions found by the last cleanup pass
+    pub beam_joins: structure::junction::BeamJoins,
+    /// Change revision the junctions were last brought up to date at
+    joins_revision: u64,
+    /// Change revision constraints were last evaluated at
+    constraints_revision: u64,
+    /// Constraints the last evaluation could not apply
+    constraint_errors: BTreeMap<(u64, String), tools::constraints::ConstraintError>,
 }
 
 impl CadModel {
@@ -424,15 +888,102 @@ impl CadModel {
             entities: Vec::new(),
             axis_manager: axis::AxisManager::new(),
             definitions: StructureDefinitions::new(),
+            storeys: StoreyManager::new(),
             layer_manager: layer::LayerManager::new(),
             export_region: None,
+            changes: ChangeLog::default(),
+            name_counters: HashMap::new(),
+            attachments_revision: 0,
+            attached_columns: HashMap::new(),
+            beam_joins: Default::default(),
+            joins_revision: 0,
+            constraints_revision: 0,
+            constraint_errors: BTreeMap::new(),
         }
     }
 
-    pub fn add_entity(&mut self, entity: Entity) {
+    /// Add a root entity. One still carrying its generic type name, e.g.
+    /// "Line", is numbered per type instead: "Line 12".
+    pub fn add_entity(&mut self, mut entity: Entity) {
+        let type_name = entity.shape.type_name();
+        if !matches!(entity.shape, Shape::None) && entity.name == type_name {
+            entity.name = self.next_name(type_name);
+        }
+        self.changes.touch(entity.id);
         self.entities.push(entity);
     }
 
+    /// Add an entity drawn in world coordinates as the last child of group
+    /// `parent`, keeping where it is drawn. Without such a group it is
+    /// added at the root.
+    pub fn add_child_entity(&mut self, parent: u64, mut entity: Entity) {
+        let path = self.path_to(parent);
+        if path.is_empty() {
+            self.add_entity(entity);
+            return;
+        }
+        let parent_world = path
+            .iter()
+            .fold(Affine2::IDENTITY, |world, e| world * e.local_transform);
+        let type_name = entity.shape.type_name();
+        if !matches!(entity.shape, Shape::None) && entity.name == type_name {
+            entity.name = self.next_name(type_name);
+        }
+        entity.local_transform = parent_world.inverse() * entity.local_transform;
+        entity.world_transform = parent_world * entity.local_transform;
+        entity.set_dirty();
+        self.changes.touch(entity.id);
+        if let Some(group) = self.find_by_id_mut(parent) {
+            group.children.push(entity);
+        }
+    }
+
+    // ── Names ───────────────────────────────────────────────
+
+    /// Next auto-numbered name for a shape type. The first call for a type
+    /// starts past the highest number already in the drawing.
+    pub fn next_name(&mut self, type_name: &'static str) -> String {
+        let entities = &self.entities;
+        let counter = self.name_counters.entry(type_name).or_insert_with(|| {
+            let prefix = format!("{} ", type_name);
+            let mut names = HashSet::new();
+            Self::collect_names(entities, &mut names);
+            names
+                .iter()
+                .filter_map(|name| name.strip_prefix(&prefix)?.parse::<u32>().ok())
+                .max()
+                .unwrap_or(0)
+        });
+        *counter += 1;
+        format!("{} {}", type_name, counter)
+    }
+
+    /// `base` if no entity uses it yet, otherwise the first free
+    /// "base (copy)", "base (copy 2)", ... A copy of a copy counts on
+    /// from the original name rather than nesting.
+    pub fn unique_name(&self, base: &str) -> String {
+        let mut names = HashSet::new();
+        Self::collect_names(&self.entities, &mut names);
+        if !names.contains(base) {
+            return base.to_string();
+        }
+        let root = strip_copy_suffix(base);
+        (1..)
+            .map(|n| match n {
+                1 => format!("{} (copy)", root),
+                n => format!("{} (copy {})", root, n),
+            })
+            .find(|name| !names.contains(name.as_str()))
+            .unwrap()
+    }
+
+    fn collect_names<'a>(entities: &'a [Entity], names: &mut HashSet<&'a str>) {
+        for entity in entities {
+            names.insert(&entity.name);
+            Self::collect_names(&entity.children, names);
+        }
+    }
+
     /// Update all transforms in the hierarchy.
     pub fn update_hierarchy(&mut self) {
         for entity in &mut self.entities {
@@ -442,13 +993,74 @@ impl CadModel {
 
     /// Find the top-most entity ID under the cursor (recursive).
     pub fn pick_entity_id(&self, pos: Vector2, tolerance: f32) -> Option<u64> {
-        // Iterate reversely (top-most rendered first)
-        for entity in self.entities.iter().rev() {
-            if let Some(id) = entity.pick(pos, tolerance, &self.layer_manager) {
-                return Some(id);
+        self.pick_where(None, pos, tolerance, &|_| true)
+    }
+
+    /// Like `pick_entity_id`, but only among the descendants of `scope`,
+    /// the group being edited
+    pub fn pick_within(&self, scope: Option<u64>, pos: Vector2, tolerance: f32) -> Option<u64> {
+        self.pick_where(scope, pos, tolerance, &|_| true)
+    }
+
+    /// Like `pick_within`, passing over entities `accept` turns down
+    pub fn pick_where(
+        &self,
+        scope: Option<u64>,
+        pos: Vector2,
+        tolerance: f32,
+        accept: &dyn Fn(&Entity) -> bool,
+    ) -> Option<u64> {
+        let pick = |entity: &Entity| {
+            entity.pick(
+                pos,
+                tolerance,
+                &self.layer_manager,
+                &self.definitions,
+                accept,
+            )
+        };
+        match scope.and_then(|id| self.find_by_id(id)) {
+            Some(group) => group.children.iter().rev().find_map(pick),
+            // Iterate reversely (top-most rendered first). Members of
+            // other storeys are ghosted, not editable.
+            None => self
+                .entities
+                .iter()
+                .rev()
+                .filter(|entity| entity.on_active_storey(&self.storeys))
+                .find_map(pick),
+        }
+    }
+
+    /// Entities from the top of the hierarchy down to `id`, which is last.
+    /// Empty if there is no such entity.
+    pub fn path_to(&self, id: u64) -> Vec<&Entity> {
+        fn walk<'a>(entities: &'a [Entity], id: u64, path: &mut Vec<&'a Entity>) -> bool {
+            for entity in entities {
+                path.push(entity);
+                if entity.id == id || walk(&entity.children, id, path) {
+                    return true;
+                }
+                path.pop();
             }
+            false
         }
-        None
+        let mut path = Vec::new();
+        walk(&self.entities, id, &mut path);
+        path
+    }
+
+    /// Topmost locked image under `pos`, which picking passes over
+    pub fn locked_entity_at(&self, pos: Vector2, tolerance: f32) -> Option<u64> {
+        self.entities
+            .iter()
+            .rev()
+            .filter(|entity| entity.on_active_storey(&self.storeys))
+            .find(|entity| {
+                matches!(&entity.shape, Shape::Image(image)
+                    if image.locked && image.covers(pos, tolerance))
+            })
+            .map(|entity| entity.id)
     }
 
     /// Find entity by id across the whole tree.
@@ -461,8 +1073,10 @@ impl CadModel {
         None
     }
 
-    /// Find entity by id (mutable) across the whole tree.
+    /// Find entity by id (mutable) across the whole tree. The entity is
+    /// recorded as changed.
     pub fn find_by_id_mut(&mut self, id: u64) -> Option<&mut Entity> {
+        self.changes.touch(id);
         for entity in &mut self.entities {
             if let Some(found) = entity.find_by_id_mut(id) {
                 return Some(found);
@@ -471,36 +1085,76 @@ impl CadModel {
         None
     }
 
+    /// Shift a line's dimension label or a text annotation by `delta`
+    pub fn move_label(&mut self, id: u64, delta: Vector2) {
+        if let Some(entity) = self.find_by_id_mut(id) {
+            entity.modify(|shape| match shape {
+                Shape::Line(line) => line.label_offset = line.label_offset + delta,
+                Shape::Text(text) => text.position = text.position + delta,
+                _ => {}
+            });
+        }
+    }
+
     /// Compute the bounding box of all entities.
     pub fn bounds(&self) -> (Vector2, Vector2) {
         if self.entities.is_empty() {
             return (Vector2::new(0.0, 0.0), Vector2::new(100.0, 100.0));
         }
+        let boxes = self
+            .entities
+            .iter()
+            .map(|entity| entity.bounding_box_in(&self.definitions));
+        page_extent(union_bounds(boxes))
+    }
 
-        let mut min_b = Vector2::new(f32::MAX, f32::MAX);
-        let mut max_b = Vector2::new(f32::MIN, f32::MIN);
-
-        for entity in &self.entities {
-            let (e_min, e_max) = entity.bounding_box();
-            min_b.x = min_b.x.min(e_min.x);
-            min_b.y = min_b.y.min(e_min.y);
-            max_b.x = max_b.x.max(e_max.x);
-            max_b.y = max_b.y.max(e_max.y);
+    /// Bounding box of what prints, leaving out unplotted layers and
+    /// entities. Falls back like [`Self::bounds`] when nothing prints.
+    pub fn plot_bounds(&self) -> (Vector2, Vector2) {
+        let boxes: Vec<_> = self
+            .entities
+            .iter()
+            .filter_map(|entity| entity.plot_bounds(&self.layer_manager, &self.definitions))
+            .collect();
+        if boxes.is_empty() {
+            return (Vector2::new(0.0, 0.0), Vector2::new(100.0, 100.0));
         }
+        page_extent(union_bounds(boxes))
+    }
 
-        if (max_b.x - min_b.x).abs() < 1.0 {
-            max_b.x = min_b.x + 1.0;
-        }
-        if (max_b.y - min_b.y).abs() < 1.0 {
-            max_b.y = min_b.y + 1.0;
+    /// Copy of the entities that print, for drawing a sheet off the model
+    pub fn plotted_entities(&self) -> Vec<Entity> {
+        fn retain(entities: &mut Vec<Entity>, layers: &LayerManager) {
+            entities.retain(|entity| entity.plots(layers.get_layer(entity.layer_id)));
+            for entity in entities {
+                retain(&mut entity.children, layers);
+            }
         }
+        let mut entities = self.entities.clone();
+        retain(&mut entities, &self.layer_manager);
+        entities
+    }
 
-        (min_b, max_b)
+    /// Bounding box around the given entities, `None` if none has extent
+    pub fn bounds_of(&self, ids: impl IntoIterator<Item = u64>) -> Option<(Vector2, Vector2)> {
+        ids.into_iter()
+            .filter_map(|id| self.find_by_id(id))
+            .map(|entity| entity.bounding_box_in(&self.definitions))
+            .filter(|(min, max)| min.x <= max.x && min.y <= max.y)
+            .reduce(|(a_min, a_max), (b_min, b_max)| {
+                (
+                    Vector2::new(a_min.x.min(b_min.x), a_min.y.min(b_min.y)),
+                    Vector2::new(a_max.x.max(b_max.x), a_max.y.max(b_max.y)),
+                )
+            })
     }
 
     /// Remove entities by a set of IDs (recursive).
     /// Returns the number of entities removed.
     pub fn remove_entities_by_ids(&mut self, ids: &std::collections::HashSet<u64>) -> usize {
+        for &id in ids {
+            self.changes.touch(id);
+        }
         let mut count = 0;
         count += Self::remove_recursive(&mut self.entities, ids);
         count
@@ -551,3 +1205,28 @@ impl CadModel {
         }
     }
 }
+
+/// "Wall A (copy 2)" -> "Wall A"
+/// `bounds` at least one unit across each way, so a page can fit it
+fn page_extent((min, mut max): (Vector2, Vector2)) -> (Vector2, Vector2) {
+    if (max.x - min.x).abs() < 1.0 {
+        max.x = min.x + 1.0;
+    }
+    if (max.y - min.y).abs() < 1.0 {
+        max.y = min.y + 1.0;
+    }
+    (min, max)
+}
+
+fn strip_copy_suffix(name: &str) -> &str {
+    let Some(rest) = name.strip_suffix(')') else {
+        return name;
+    };
+    if let Some(root) = rest.strip_suffix(" (copy") {
+        return root;
+    }
+    match rest.rsplit_once(" (copy ") {
+        Some((root, n)) if n.parse::<u32>().is_ok() => root,
+        _ => name,
+    }
+}
@@ -0,0 +1,79 @@
+//! Background references: another tab's or project file's geometry shown
+//! read-only beneath a tab's own drawing.
+
+use crate::model::project::ProjectData;
+use crate::model::{CadModel, Entity};
+use std::path::PathBuf;
+
+/// Default grey for referenced geometry
+pub const DEFAULT_REFERENCE_TINT: [u8; 3] = [110, 110, 110];
+
+/// Where a reference's geometry comes from
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReferenceSource {
+    /// An open tab, by tab id
+    Tab(u64),
+    /// A saved project file
+    File(PathBuf),
+}
+
+/// A snapshot of another drawing, drawn as an underlay
+#[derive(Clone)]
+pub struct Reference {
+    pub source: ReferenceSource,
+    /// Name shown in the references list
+    pub label: String,
+    pub visible: bool,
+    pub tint: [u8; 3],
+    /// Copy of the source taken at the last refresh, every entity colored
+    /// with `tint`
+    pub snapshot: CadModel,
+    /// Why the last refresh failed, e.g. the tab was closed
+    pub error: Option<String>,
+}
+
+impl Reference {
+    pub fn new(source: ReferenceSource, label: String) -> Self {
+        Self {
+            source,
+            label,
+            visible: true,
+            tint: DEFAULT_REFERENCE_TINT,
+            snapshot: CadModel::new(),
+            error: None,
+        }
+    }
+
+    /// Replace the snapshot with a copy of `model`
+    pub fn set_snapshot(&mut self, model: &CadModel) {
+        self.snapshot = model.clone();
+        self.snapshot.export_region = None;
+        self.error = None;
+        self.apply_tint();
+    }
+
+    /// Read the source file again. Tab references are refreshed by the
+    /// owner of the tabs instead.
+    pub fn reload_file(&mut self) {
+        let ReferenceSource::File(path) = &self.source else {
+            return;
+        };
+        match ProjectData::read(path) {
+            Ok(project) => self.set_snapshot(&project.into_model()),
+            Err(error) => self.error = Some(error),
+        }
+    }
+
+    /// Color every snapshot entity with the current tint
+    pub fn apply_tint(&mut self) {
+        fn tint(entity: &mut Entity, color: [u8; 3]) {
+            entity.color = Some(color);
+            for child in &mut entity.children {
+                tint(child, color);
+            }
+        }
+        for entity in &mut self.snapshot.entities {
+            tint(entity, self.tint);
+        }
+    }
+}
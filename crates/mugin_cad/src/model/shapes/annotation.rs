@@ -1,4 +1,5 @@
 use crate::model::Vector2;
+use crate::model::dimension::DimensionStyle;
 use serde::{Deserialize, Serialize};
 
 /// Type of text annotation
@@ -14,10 +15,12 @@ pub enum AnnotationType {
     Radius,
     /// Perimeter measurement
     Perimeter,
+    /// Text with a leader line to the point it refers to
+    Leader,
 }
 
 /// Text alignment for annotations
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub enum TextAlignment {
     Left,
     #[default]
@@ -26,7 +29,7 @@ pub enum TextAlignment {
 }
 
 /// Style configuration for text annotations
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TextStyle {
     pub font_size: f32,
     pub color: [u8; 3], // RGB
@@ -44,7 +47,7 @@ impl Default for TextStyle {
 }
 
 /// Text annotation entity for labels and measurements
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TextAnnotation {
     /// Position of the text
     pub position: Vector2,
@@ -59,6 +62,9 @@ pub struct TextAnnotation {
     /// Rotation angle in radians
     #[serde(default)]
     pub rotation: f32,
+    /// Text shown on a distance dimension instead of the measured length
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label_override: Option<String>,
 }
 
 impl TextAnnotation {
@@ -71,6 +77,24 @@ impl TextAnnotation {
             style: TextStyle::default(),
             anchor_points: Vec::new(),
             rotation: 0.0,
+            label_override: None,
+        }
+    }
+
+    /// Create text at `position` with a leader line running to `target`
+    pub fn new_leader(target: Vector2, position: Vector2, text: String) -> Self {
+        Self {
+            annotation_type: AnnotationType::Leader,
+            anchor_points: vec![target],
+            ..Self::new_custom(position, text)
+        }
+    }
+
+    /// Point a leader runs to, if the text has one
+    pub fn leader_target(&self) -> Option<Vector2> {
+        match self.annotation_type {
+            AnnotationType::Leader => self.anchor_points.first().copied(),
+            _ => None,
         }
     }
 
@@ -100,6 +124,7 @@ impl TextAnnotation {
             },
             anchor_points: vec![start, end],
             rotation: dy.atan2(dx),
+            label_override: None,
         }
     }
 
@@ -116,6 +141,7 @@ impl TextAnnotation {
             },
             anchor_points: polygon_points,
             rotation: 0.0,
+            label_override: None,
         }
     }
 
@@ -132,7 +158,48 @@ impl TextAnnotation {
             },
             anchor_points: path_points,
             rotation: 0.0,
+            label_override: None,
+        }
+    }
+
+    /// Whether this is a distance dimension, whose label shows the length
+    /// between its first two anchor points
+    pub fn is_dimension(&self) -> bool {
+        self.annotation_type == AnnotationType::Distance && self.anchor_points.len() >= 2
+    }
+
+    /// Label of a distance dimension measuring `length`: the override if
+    /// one is set, else the length formatted with `style`
+    pub fn dimension_label(&self, style: &DimensionStyle, length: f32) -> String {
+        self.label_override
+            .clone()
+            .unwrap_or_else(|| style.format_length(length))
+    }
+
+    /// Perpendicular offset from the measured points to the dimension line and
+    /// the label's distance along them. The line is kept `clearance` away from
+    /// the label so it sits "below" the text.
+    pub fn dimension_offset(&self, clearance: f32) -> Option<(Vector2, f32)> {
+        if self.anchor_points.len() < 2 {
+            return None;
+        }
+        let p1 = self.anchor_points[0];
+        let d = self.anchor_points[1] - p1;
+        let len = d.length();
+        if len < 0.001 {
+            return None;
         }
+        let v = self.position - p1;
+
+        // Distance of the label along the measured points
+        let t = v.dot(d) / len;
+        // Perpendicular vector from line to label
+        let mut perp = v - v.project_onto(d);
+        if perp.length_squared() > 0.0001 {
+            // Move line away from text towards the measured object
+            perp = perp - perp.normalized() * clearance;
+        }
+        Some((perp, t))
     }
 
     /// Hit test for text annotation - simple distance-based check
@@ -151,6 +218,7 @@ impl TextAnnotation {
 }
 
 use super::Geometry;
+use crate::model::snap::SnapPointType;
 
 impl Geometry for TextAnnotation {
     fn hit_test(&self, pos: Vector2, tolerance: f32) -> bool {
@@ -159,7 +227,14 @@ impl Geometry for TextAnnotation {
 
     fn bounding_box(&self) -> (Vector2, Vector2) {
         // Approximate bbox
-        (self.position, self.position)
+        let p = self.position;
+        match self.leader_target() {
+            Some(t) => (
+                Vector2::new(p.x.min(t.x), p.y.min(t.y)),
+                Vector2::new(p.x.max(t.x), p.y.max(t.y)),
+            ),
+            None => (p, p),
+        }
     }
 
     fn as_polyline(&self) -> Vec<Vector2> {
@@ -173,4 +248,15 @@ impl Geometry for TextAnnotation {
     fn is_filled(&self) -> bool {
         true
     }
+
+    /// Insertion point, and the points a measurement label was taken at
+    fn snap_points(&self) -> Vec<(SnapPointType, Vector2)> {
+        let mut points = vec![(SnapPointType::Insertion, self.position)];
+        points.extend(
+            self.anchor_points
+                .iter()
+                .map(|&p| (SnapPointType::Endpoint, p)),
+        );
+        points
+    }
 }
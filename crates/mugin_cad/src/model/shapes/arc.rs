@@ -1,19 +1,21 @@
 use crate::model::Vector2;
 use serde::{Deserialize, Serialize};
+use std::f32::consts::{FRAC_PI_2, TAU};
 
 /// Arc entity - a portion of a circle defined by center, radius, start and end angles
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Arc {
     pub center: Vector2,
     pub radius: f32,
     pub start_angle: f32, // radians
     pub end_angle: f32,   // radians
     pub filled: bool,
+    /// Runs clockwise from `start_angle` to `end_angle`; older files are CCW
+    #[serde(default)]
+    pub clockwise: bool,
 }
 
 impl Arc {
-    /// Create arc from 3 points: center, start point, end point
-
     /// Create arc from 3 points with direction control
     /// If clockwise is true, arc goes CW from start to end, otherwise CCW
     pub fn from_three_points_directed(
@@ -27,132 +29,121 @@ impl Arc {
         let start_angle = (start.y - center.y).atan2(start.x - center.x);
         let end_angle = (end.y - center.y).atan2(end.x - center.x);
 
-        if clockwise {
-            // For CW, swap start and end angles
-            Self {
-                center,
-                radius,
-                start_angle: end_angle,
-                end_angle: start_angle,
-                filled,
-            }
+        Self {
+            center,
+            radius,
+            start_angle,
+            end_angle,
+            filled,
+            clockwise,
+        }
+    }
+
+    /// Signed angle swept from start to end: positive CCW, negative CW
+    pub fn sweep(&self) -> f32 {
+        if self.clockwise {
+            -(self.start_angle - self.end_angle).rem_euclid(TAU)
         } else {
-            Self {
-                center,
-                radius,
-                start_angle,
-                end_angle,
-                filled,
-            }
+            (self.end_angle - self.start_angle).rem_euclid(TAU)
         }
     }
 
-    /// Get start point on the arc
-    pub fn start_point(&self) -> Vector2 {
+    /// Point on the circle at `angle`
+    pub fn point_at(&self, angle: f32) -> Vector2 {
         Vector2::new(
-            self.center.x + self.radius * self.start_angle.cos(),
-            self.center.y + self.radius * self.start_angle.sin(),
+            self.center.x + self.radius * angle.cos(),
+            self.center.y + self.radius * angle.sin(),
         )
     }
 
+    /// Get start point on the arc
+    pub fn start_point(&self) -> Vector2 {
+        self.point_at(self.start_angle)
+    }
+
     /// Get end point on the arc
     pub fn end_point(&self) -> Vector2 {
-        Vector2::new(
-            self.center.x + self.radius * self.end_angle.cos(),
-            self.center.y + self.radius * self.end_angle.sin(),
-        )
+        self.point_at(self.end_angle)
     }
 
-    /// Hit test for arc
-    pub fn hit_test(&self, pos: Vector2, tolerance: f32) -> bool {
-        let dx = pos.x - self.center.x;
-        let dy = pos.y - self.center.y;
-        let dist = (dx * dx + dy * dy).sqrt();
-
-        // Check if near the arc radius
-        if (dist - self.radius).abs() > tolerance {
-            return false;
-        }
+    /// `segments + 1` points from start to end, following the direction
+    pub fn points(&self, segments: usize) -> Vec<Vector2> {
+        let sweep = self.sweep();
+        (0..=segments)
+            .map(|i| self.point_at(self.start_angle + sweep * i as f32 / segments as f32))
+            .collect()
+    }
 
-        // Check if the angle is within the arc
-        let angle = dy.atan2(dx);
-        self.angle_in_range(angle)
+    /// Outline of the pie slice: the center, then the arc from start to end
+    pub fn sector(&self, segments: usize) -> Vec<Vector2> {
+        let mut points = vec![self.center];
+        points.extend(self.points(segments));
+        points
     }
 
     /// Check if angle is within the arc range
-    fn angle_in_range(&self, angle: f32) -> bool {
-        let mut start = self.start_angle;
-        let mut end = self.end_angle;
-        let mut test = angle;
-
-        // Normalize to [0, 2π]
-        let two_pi = std::f32::consts::PI * 2.0;
-        while start < 0.0 {
-            start += two_pi;
-        }
-        while end < 0.0 {
-            end += two_pi;
-        }
-        while test < 0.0 {
-            test += two_pi;
-        }
-        start %= two_pi;
-        end %= two_pi;
-        test %= two_pi;
-
-        if start <= end {
-            test >= start && test <= end
+    pub fn contains_angle(&self, angle: f32) -> bool {
+        let offset = if self.clockwise {
+            self.start_angle - angle
         } else {
-            // Arc crosses 0
-            test >= start || test <= end
-        }
+            angle - self.start_angle
+        };
+        offset.rem_euclid(TAU) <= self.sweep().abs()
     }
 }
 
 use super::Geometry;
+use crate::model::snap::SnapPointType;
 
 impl Geometry for Arc {
     fn hit_test(&self, pos: Vector2, tolerance: f32) -> bool {
-        let dx = pos.x - self.center.x;
-        let dy = pos.y - self.center.y;
-        let dist = (dx * dx + dy * dy).sqrt();
-
-        // Check if near the arc radius
-        if (dist - self.radius).abs() > tolerance {
-            return false;
+        let dist = pos.dist(self.center);
+        let in_sweep = self.contains_angle((pos.y - self.center.y).atan2(pos.x - self.center.x));
+
+        // Outside the sweep the nearest point of the curve is an endpoint
+        let (start, end) = (self.start_point(), self.end_point());
+        if (in_sweep && (dist - self.radius).abs() <= tolerance)
+            || pos.dist(start) <= tolerance
+            || pos.dist(end) <= tolerance
+        {
+            return true;
         }
 
-        // Check if the angle is within the arc
-        let angle = dy.atan2(dx);
-        self.angle_in_range(angle)
+        // Filled arcs render as a pie slice, so the slice and its two
+        // radial edges are pickable too
+        self.filled
+            && ((in_sweep && dist <= self.radius)
+                || pos.dist_to_line(self.center, start) <= tolerance
+                || pos.dist_to_line(self.center, end) <= tolerance)
     }
 
     fn bounding_box(&self) -> (Vector2, Vector2) {
-        // Conservative bbox (entire circle bbox)
-        // Calculating exact arc bbox is complex, this is safe enough for now.
-        (
-            Vector2::new(self.center.x - self.radius, self.center.y - self.radius),
-            Vector2::new(self.center.x + self.radius, self.center.y + self.radius),
-        )
+        // Endpoints plus every axis extreme the arc passes through
+        let mut points = vec![self.start_point(), self.end_point()];
+        if self.filled {
+            points.push(self.center);
+        }
+        points.extend(
+            (0..4)
+                .map(|quadrant| quadrant as f32 * FRAC_PI_2)
+                .filter(|&angle| self.contains_angle(angle))
+                .map(|angle| self.point_at(angle)),
+        );
+        let min = points
+            .iter()
+            .fold(Vector2::new(f32::MAX, f32::MAX), |m, p| {
+                Vector2::new(m.x.min(p.x), m.y.min(p.y))
+            });
+        let max = points
+            .iter()
+            .fold(Vector2::new(f32::MIN, f32::MIN), |m, p| {
+                Vector2::new(m.x.max(p.x), m.y.max(p.y))
+            });
+        (min, max)
     }
 
     fn as_polyline(&self) -> Vec<Vector2> {
-        let segments = 24;
-        let start_angle = self.start_angle;
-        let mut end_angle = self.end_angle;
-        if end_angle < start_angle {
-            end_angle += std::f32::consts::PI * 2.0;
-        }
-        (0..=segments)
-            .map(|i| {
-                let t = i as f32 / segments as f32;
-                let angle = start_angle + t * (end_angle - start_angle);
-                Vector2::new(
-                    self.center.x + self.radius * angle.cos(),
-                    self.center.y + self.radius * angle.sin(),
-                )
-            })
-            .collect()
+        self.points(24)
     }
 
     fn is_closed(&self) -> bool {
@@ -162,4 +153,12 @@ impl Geometry for Arc {
     fn is_filled(&self) -> bool {
         self.filled
     }
+
+    fn snap_points(&self) -> Vec<(SnapPointType, Vector2)> {
+        vec![
+            (SnapPointType::Center, self.center),
+            (SnapPointType::Endpoint, self.start_point()),
+            (SnapPointType::Endpoint, self.end_point()),
+        ]
+    }
 }
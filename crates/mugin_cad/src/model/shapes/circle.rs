@@ -1,8 +1,9 @@
 use super::Geometry;
 use crate::model::Vector2;
+use crate::model::snap::SnapPointType;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Circle {
     pub center: Vector2,
     pub radius: f32,
@@ -25,7 +26,7 @@ impl Geometry for Circle {
         if self.filled {
             d <= self.radius + tolerance
         } else {
-            (d - self.radius).abs() < tolerance
+            (d - self.radius).abs() <= tolerance
         }
     }
 
@@ -56,4 +57,19 @@ impl Geometry for Circle {
     fn is_filled(&self) -> bool {
         self.filled
     }
+
+    /// Center and the four quadrant points
+    fn snap_points(&self) -> Vec<(SnapPointType, Vector2)> {
+        let r = self.radius;
+        let mut points = vec![(SnapPointType::Center, self.center)];
+        for offset in [
+            Vector2::new(r, 0.0),
+            Vector2::new(-r, 0.0),
+            Vector2::new(0.0, r),
+            Vector2::new(0.0, -r),
+        ] {
+            points.push((SnapPointType::Endpoint, self.center + offset));
+        }
+        points
+    }
 }
@@ -0,0 +1,175 @@
+use super::Geometry;
+use crate::model::Vector2;
+use crate::model::snap::SnapPointType;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+/// A raster image placed in the drawing, e.g. a scanned plan to trace over.
+///
+/// The image is referenced by path; its bytes may also be embedded so the
+/// project still shows it when the file moves. Only PNG files are read.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageRef {
+    /// Source file, as chosen when the image was inserted
+    pub path: String,
+    /// Base64 copy of the file, used instead of `path` when present
+    #[serde(default)]
+    pub embedded: Option<String>,
+    /// Lower-left corner
+    pub position: Vector2,
+    /// Size in pixels, kept so a missing file still has an outline
+    pub pixel_size: [u32; 2],
+    /// Drawing units per pixel
+    pub scale: f32,
+    /// Rotation about `position`, in radians
+    pub rotation: f32,
+    /// 0 (invisible) to 1 (opaque)
+    pub opacity: f32,
+    /// Locked images cannot be picked on the canvas
+    #[serde(default)]
+    pub locked: bool,
+}
+
+impl ImageRef {
+    pub fn new(path: String, position: Vector2, pixel_size: [u32; 2]) -> Self {
+        Self {
+            path,
+            embedded: None,
+            position,
+            pixel_size,
+            scale: 1.0,
+            rotation: 0.0,
+            opacity: 0.6,
+            locked: false,
+        }
+    }
+
+    /// Width and height in drawing units
+    pub fn size(&self) -> Vector2 {
+        Vector2::new(
+            self.pixel_size[0] as f32 * self.scale,
+            self.pixel_size[1] as f32 * self.scale,
+        )
+    }
+
+    /// Corners counter-clockwise from `position`
+    pub fn corners(&self) -> [Vector2; 4] {
+        let size = self.size();
+        let (sin, cos) = self.rotation.sin_cos();
+        let place =
+            |x: f32, y: f32| self.position + Vector2::new(x * cos - y * sin, x * sin + y * cos);
+        [
+            place(0.0, 0.0),
+            place(size.x, 0.0),
+            place(size.x, size.y),
+            place(0.0, size.y),
+        ]
+    }
+
+    /// File name shown for the image
+    pub fn file_name(&self) -> &str {
+        std::path::Path::new(&self.path)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or(&self.path)
+    }
+
+    /// The image file's bytes, from the embedded copy or from disk
+    pub fn read_bytes(&self) -> Result<Vec<u8>, String> {
+        match &self.embedded {
+            Some(encoded) => base64::engine::general_purpose::STANDARD
+                .decode(encoded)
+                .map_err(|e| format!("Embedded image is corrupt: {}", e)),
+            None => std::fs::read(&self.path)
+                .map_err(|e| format!("Could not read {}: {}", self.file_name(), e)),
+        }
+    }
+
+    /// Decode the image into `(width, height, rgba)`
+    pub fn load(&self) -> Result<(usize, usize, Vec<u8>), String> {
+        let bytes = self.read_bytes()?;
+        decode_png(&bytes).ok_or_else(|| format!("{} is not a readable PNG", self.file_name()))
+    }
+
+    /// Copy the file into the project so it no longer depends on `path`
+    pub fn embed(&mut self) -> Result<(), String> {
+        let bytes = std::fs::read(&self.path)
+            .map_err(|e| format!("Could not read {}: {}", self.file_name(), e))?;
+        self.embedded = Some(base64::engine::general_purpose::STANDARD.encode(bytes));
+        Ok(())
+    }
+
+    fn to_local(&self, pos: Vector2) -> Vector2 {
+        let d = pos - self.position;
+        let (sin, cos) = self.rotation.sin_cos();
+        Vector2::new(d.x * cos + d.y * sin, -d.x * sin + d.y * cos)
+    }
+
+    /// Whether `pos` is within `tolerance` of the image, locked or not
+    pub fn covers(&self, pos: Vector2, tolerance: f32) -> bool {
+        let local = self.to_local(pos);
+        let size = self.size();
+        local.x >= -tolerance
+            && local.x <= size.x + tolerance
+            && local.y >= -tolerance
+            && local.y <= size.y + tolerance
+    }
+}
+
+/// Decode PNG bytes into `(width, height, rgba)`
+pub fn decode_png(bytes: &[u8]) -> Option<(usize, usize, Vec<u8>)> {
+    let mut decoder = png::Decoder::new(std::io::Cursor::new(bytes));
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::ALPHA);
+    let mut reader = decoder.read_info().ok()?;
+    let mut buffer = vec![0; reader.output_buffer_size()?];
+    let info = reader.next_frame(&mut buffer).ok()?;
+    buffer.truncate(info.buffer_size());
+
+    let rgba = match (info.color_type, info.bit_depth) {
+        (png::ColorType::Rgba, png::BitDepth::Eight) => buffer,
+        (png::ColorType::GrayscaleAlpha, png::BitDepth::Eight) => buffer
+            .chunks_exact(2)
+            .flat_map(|px| [px[0], px[0], px[0], px[1]])
+            .collect(),
+        _ => return None,
+    };
+    Some((info.width as usize, info.height as usize, rgba))
+}
+
+impl Geometry for ImageRef {
+    fn hit_test(&self, pos: Vector2, tolerance: f32) -> bool {
+        !self.locked && self.covers(pos, tolerance)
+    }
+
+    fn bounding_box(&self) -> (Vector2, Vector2) {
+        let corners = self.corners();
+        let mut min = corners[0];
+        let mut max = corners[0];
+        for c in &corners[1..] {
+            min = Vector2::new(min.x.min(c.x), min.y.min(c.y));
+            max = Vector2::new(max.x.max(c.x), max.y.max(c.y));
+        }
+        (min, max)
+    }
+
+    fn as_polyline(&self) -> Vec<Vector2> {
+        let corners = self.corners();
+        vec![corners[0], corners[1], corners[2], corners[3], corners[0]]
+    }
+
+    fn is_closed(&self) -> bool {
+        true
+    }
+
+    fn is_filled(&self) -> bool {
+        false
+    }
+
+    /// Corners only, so tracing snaps to the drawing on top
+    fn snap_points(&self) -> Vec<(SnapPointType, Vector2)> {
+        self.corners()
+            .into_iter()
+            .map(|p| (SnapPointType::Corner, p))
+            .collect()
+    }
+}
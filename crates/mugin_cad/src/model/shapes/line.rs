@@ -1,8 +1,14 @@
 use super::Geometry;
 use crate::model::Vector2;
+use crate::model::dimension::DimensionStyle;
+use crate::model::snap::SnapPointType;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Screen pixels per length label layout unit; the label sits three units
+/// off the line
+pub const LABEL_UNIT_PX: f32 = 5.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Line {
     pub start: Vector2,
     pub end: Vector2,
@@ -12,6 +18,12 @@ pub struct Line {
     /// Offset of the label from the midpoint (for dragging/repositioning)
     #[serde(default)]
     pub label_offset: Vector2,
+    /// Decimal places for the length label (None = dimension style default)
+    #[serde(default)]
+    pub label_precision: Option<u8>,
+    /// Unit suffix for the length label (None = dimension style default)
+    #[serde(default)]
+    pub label_suffix: Option<String>,
 }
 
 impl Line {
@@ -21,50 +33,71 @@ impl Line {
             end,
             show_length: false,
             label_offset: Vector2::new(0.0, 0.0), // Default 0, relative to smart offset
+            label_precision: None,
+            label_suffix: None,
         }
     }
 
     /// Calculate the smart offset vector (visually "up") based on line orientation
-    pub fn calculate_smart_offset(&self, tolerance: f32) -> Vector2 {
-        let dx = self.end.x - self.start.x;
-        let dy = self.end.y - self.start.y;
-        let len = (dx * dx + dy * dy).sqrt();
-
-        if len < 0.001 {
+    pub fn calculate_smart_offset(&self, unit: f32) -> Vector2 {
+        let dir = self.end - self.start;
+        if dir.length() < 0.001 {
             return Vector2::new(0.0, 15.0); // Fallback
         }
 
-        // 15.0 pixels screen distance ~ 3.0 * tolerance world distance
-        let offset_dist = 3.0 * tolerance;
-
-        // Normal check
-        let mut nx = -dy / len;
-        let mut ny = dx / len;
+        // 15.0 pixels screen distance ~ 3.0 * unit world distance
+        let offset_dist = 3.0 * unit;
 
         // Force "Up" (World Y positive)
-        if ny < 0.0 {
-            nx = -nx;
-            ny = -ny;
-        }
+        let normal = dir.normalized().perp();
+        let normal = if normal.y < 0.0 { -normal } else { normal };
+
+        normal * offset_dist
+    }
 
-        Vector2::new(nx * offset_dist, ny * offset_dist)
+    /// Length label text, applying this line's overrides on top of `style`
+    pub fn label_text(&self, style: &DimensionStyle) -> String {
+        style.format_length_with(
+            self.length(),
+            self.label_precision,
+            self.label_suffix.as_deref(),
+        )
+    }
+
+    /// World position of the length label (midpoint + smart offset + user offset)
+    pub fn label_position(&self, unit: f32) -> Vector2 {
+        self.midpoint() + self.calculate_smart_offset(unit) + self.label_offset
+    }
+
+    /// Perpendicular offset from the line to its dimension line, which runs
+    /// through the label
+    pub fn dimension_offset(&self, unit: f32) -> Vector2 {
+        let smart_offset = self.calculate_smart_offset(unit);
+        smart_offset + self.label_offset.project_onto(smart_offset)
     }
 
-    /// Check if a point hits the label specifically
-    pub fn hit_test_label(&self, pos: Vector2, tolerance: f32) -> bool {
+    /// Check if a point hits the label specifically. `unit` is the label
+    /// layout unit in world space, `tolerance` the extra pick margin and
+    /// `style` the dimension style the label is drawn with.
+    pub fn hit_test_label(
+        &self,
+        pos: Vector2,
+        unit: f32,
+        tolerance: f32,
+        style: &DimensionStyle,
+    ) -> bool {
         if !self.show_length {
             return false;
         }
 
-        let smart_offset = self.calculate_smart_offset(tolerance);
+        let smart_offset = self.calculate_smart_offset(unit);
         let mid = self.midpoint();
         // Position = Mid + Smart + UserOffset
         let label_pos = mid + smart_offset + self.label_offset;
 
-        let len = self.length();
-        let text_len = format!("{:.2}", len).len();
-        let box_w = text_len as f32 * 1.4 * tolerance;
-        let box_h = 2.8 * tolerance;
+        let text_len = self.label_text(style).chars().count();
+        let box_w = text_len as f32 * 1.4 * unit;
+        let box_h = 2.8 * unit;
 
         let dx = (pos.x - label_pos.x).abs();
         let dy = (pos.y - label_pos.y).abs();
@@ -74,17 +107,12 @@ impl Line {
 
     /// Get the length of the line
     pub fn length(&self) -> f32 {
-        let dx = self.end.x - self.start.x;
-        let dy = self.end.y - self.start.y;
-        (dx * dx + dy * dy).sqrt()
+        self.start.dist(self.end)
     }
 
     /// Get the midpoint of the line
     pub fn midpoint(&self) -> Vector2 {
-        Vector2::new(
-            (self.start.x + self.end.x) / 2.0,
-            (self.start.y + self.end.y) / 2.0,
-        )
+        self.start.lerp(self.end, 0.5)
     }
 }
 
@@ -95,8 +123,10 @@ impl Geometry for Line {
             return true;
         }
 
-        // label hit test
-        self.hit_test_label(pos, tolerance)
+        // label hit test; the zoom and dimension style are unknown here, so
+        // take the layout unit to be the pick tolerance (they match at the
+        // default settings) and size the label from the default style
+        self.hit_test_label(pos, tolerance, tolerance, &DimensionStyle::default())
     }
 
     fn bounding_box(&self) -> (Vector2, Vector2) {
@@ -117,4 +147,12 @@ impl Geometry for Line {
     fn is_filled(&self) -> bool {
         false
     }
+
+    fn snap_points(&self) -> Vec<(SnapPointType, Vector2)> {
+        vec![
+            (SnapPointType::Endpoint, self.start),
+            (SnapPointType::Endpoint, self.end),
+            (SnapPointType::Midpoint, (self.start + self.end) * 0.5),
+        ]
+    }
 }
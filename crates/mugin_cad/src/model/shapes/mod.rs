@@ -1,14 +1,22 @@
 pub mod annotation;
 pub mod arc;
 pub mod circle;
+pub mod image;
 pub mod line;
 pub mod rectangle;
+pub mod revision_cloud;
+pub mod room;
+pub mod section;
+pub mod sketch;
 
 use crate::model::Vector2;
+use crate::model::snap::SnapPointType;
 
 /// Common geometric operations for all shapes.
 pub trait Geometry {
     /// Check if a point interacts with the shape within a tolerance.
+    /// Outlines hit when the point is within `tolerance` of the drawn
+    /// outline; filled shapes also hit anywhere inside.
     fn hit_test(&self, pos: Vector2, tolerance: f32) -> bool;
 
     /// Get the axis-aligned bounding box (min, max).
@@ -22,4 +30,23 @@ pub trait Geometry {
 
     /// Whether the shape has a fill.
     fn is_filled(&self) -> bool;
+
+    /// Points the cursor snaps to, in the shape's own coordinates. By
+    /// default the outline's corners, or the two ends of an open outline.
+    fn snap_points(&self) -> Vec<(SnapPointType, Vector2)> {
+        let outline = self.as_polyline();
+        if self.is_closed() {
+            outline
+                .into_iter()
+                .map(|p| (SnapPointType::Corner, p))
+                .collect()
+        } else {
+            outline
+                .first()
+                .into_iter()
+                .chain(outline.last().filter(|_| outline.len() > 1))
+                .map(|&p| (SnapPointType::Endpoint, p))
+                .collect()
+        }
+    }
 }
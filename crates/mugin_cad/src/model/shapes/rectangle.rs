@@ -1,8 +1,9 @@
 use super::Geometry;
 use crate::model::Vector2;
+use crate::model::snap::SnapPointType;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Rectangle {
     pub min: Vector2,
     pub max: Vector2,
@@ -17,21 +18,22 @@ impl Rectangle {
 
 impl Geometry for Rectangle {
     fn hit_test(&self, pos: Vector2, tolerance: f32) -> bool {
-        let inside = pos.x >= self.min.x - tolerance
-            && pos.x <= self.max.x + tolerance
-            && pos.y >= self.min.y - tolerance
-            && pos.y <= self.max.y + tolerance;
-
+        // Corners may have been placed in any order
+        let (min, max) = self.bounding_box();
+        let outside_x = (min.x - pos.x).max(pos.x - max.x).max(0.0);
+        let outside_y = (min.y - pos.y).max(pos.y - max.y).max(0.0);
+        if outside_x > 0.0 || outside_y > 0.0 {
+            return outside_x.hypot(outside_y) <= tolerance;
+        }
         if self.filled {
-            inside
-        } else {
-            // Check if near edges
-            let near_x =
-                (pos.x - self.min.x).abs() < tolerance || (pos.x - self.max.x).abs() < tolerance;
-            let near_y =
-                (pos.y - self.min.y).abs() < tolerance || (pos.y - self.max.y).abs() < tolerance;
-            inside && (near_x || near_y)
+            return true;
         }
+        // Inside: distance to the nearest edge
+        let to_edge = (pos.x - min.x)
+            .min(max.x - pos.x)
+            .min(pos.y - min.y)
+            .min(max.y - pos.y);
+        to_edge <= tolerance
     }
 
     fn bounding_box(&self) -> (Vector2, Vector2) {
@@ -58,4 +60,14 @@ impl Geometry for Rectangle {
     fn is_filled(&self) -> bool {
         self.filled
     }
+
+    fn snap_points(&self) -> Vec<(SnapPointType, Vector2)> {
+        vec![
+            (SnapPointType::Corner, self.min),
+            (SnapPointType::Corner, self.max),
+            (SnapPointType::Corner, Vector2::new(self.min.x, self.max.y)),
+            (SnapPointType::Corner, Vector2::new(self.max.x, self.min.y)),
+            (SnapPointType::Center, (self.min + self.max) * 0.5),
+        ]
+    }
 }
@@ -0,0 +1,162 @@
+use super::Geometry;
+use crate::model::Vector2;
+use crate::model::snap::SnapPointType;
+use serde::{Deserialize, Serialize};
+
+/// Straight pieces each bulge is drawn with
+const BULGE_SEGMENTS: usize = 8;
+
+/// Markup outline: a closed boundary drawn as a chain of outward arcs,
+/// optionally tagged with the revision it belongs to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RevisionCloud {
+    /// Corners of the closed boundary, first corner not repeated
+    pub boundary: Vec<Vector2>,
+    /// Chord length of one bulge
+    pub arc_size: f32,
+    /// Revision letter or number shown in a triangle on the cloud
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+}
+
+impl RevisionCloud {
+    pub fn new(boundary: Vec<Vector2>, arc_size: f32) -> Self {
+        Self {
+            boundary,
+            arc_size,
+            tag: None,
+        }
+    }
+
+    /// Chords the bulges span: each boundary edge split into pieces of
+    /// about `arc_size`, each with the unit normal pointing out of the cloud
+    pub fn chords(&self) -> Vec<(Vector2, Vector2, Vector2)> {
+        let n = self.boundary.len();
+        if n < 2 {
+            return Vec::new();
+        }
+        // Counter-clockwise outlines have the outside on the right
+        let twice_area: f32 = (0..n)
+            .map(|i| {
+                let (a, b) = (self.boundary[i], self.boundary[(i + 1) % n]);
+                a.x * b.y - b.x * a.y
+            })
+            .sum();
+        let ccw = twice_area >= 0.0;
+        let step = self.arc_size.max(f32::EPSILON);
+        let mut chords = Vec::new();
+        for i in 0..n {
+            let (a, b) = (self.boundary[i], self.boundary[(i + 1) % n]);
+            let edge = b - a;
+            let length = edge.length();
+            if length <= f32::EPSILON {
+                continue;
+            }
+            let d = edge / length;
+            let outward = if ccw {
+                Vector2::new(d.y, -d.x)
+            } else {
+                Vector2::new(-d.y, d.x)
+            };
+            let pieces = (length / step).round().max(1.0) as usize;
+            for k in 0..pieces {
+                let start = a + edge * (k as f32 / pieces as f32);
+                let end = a + edge * ((k + 1) as f32 / pieces as f32);
+                chords.push((start, end, outward));
+            }
+        }
+        chords
+    }
+
+    /// Corner the revision tag sits on: the top-right one
+    pub fn tag_anchor(&self) -> Option<Vector2> {
+        self.boundary
+            .iter()
+            .copied()
+            .max_by(|a, b| (a.x + a.y).total_cmp(&(b.x + b.y)))
+    }
+
+    /// Triangle around the tag, pointing up, if the cloud has a tag
+    pub fn tag_triangle(&self) -> Option<[Vector2; 3]> {
+        self.tag.as_ref()?;
+        let center = self.tag_anchor()?;
+        // Equilateral, two bulges wide, centered on the corner
+        let side = self.arc_size * 2.0;
+        let height = side * 3f32.sqrt() / 2.0;
+        Some([
+            center + Vector2::new(-side / 2.0, -height / 3.0),
+            center + Vector2::new(side / 2.0, -height / 3.0),
+            center + Vector2::new(0.0, height * 2.0 / 3.0),
+        ])
+    }
+}
+
+/// Points of the arc over `a`→`b` bulging toward `outward`, `b` left out
+fn bulge(a: Vector2, b: Vector2, outward: Vector2, out: &mut Vec<Vector2>) {
+    let length = a.dist(b);
+    // Arc rising a third of its chord, about 135° of a circle
+    let rise = length / 3.0;
+    let radius = (length * length / 4.0 + rise * rise) / (2.0 * rise);
+    let center = (a + b) * 0.5 - outward * (radius - rise);
+    let (ra, rb) = (a - center, b - center);
+    let start = ra.y.atan2(ra.x);
+    let sweep = (ra.x * rb.y - ra.y * rb.x).atan2(ra.dot(rb));
+    for i in 0..BULGE_SEGMENTS {
+        let t = start + sweep * i as f32 / BULGE_SEGMENTS as f32;
+        out.push(center + Vector2::new(t.cos(), t.sin()) * radius);
+    }
+}
+
+impl Geometry for RevisionCloud {
+    fn hit_test(&self, pos: Vector2, tolerance: f32) -> bool {
+        let outline = self.as_polyline();
+        let n = outline.len();
+        let on_outline =
+            (0..n).any(|i| pos.dist_to_line(outline[i], outline[(i + 1) % n]) <= tolerance);
+        let on_tag = self.tag_triangle().is_some_and(|corners| {
+            (0..3).any(|i| pos.dist_to_line(corners[i], corners[(i + 1) % 3]) <= tolerance)
+        });
+        on_outline || on_tag
+    }
+
+    fn bounding_box(&self) -> (Vector2, Vector2) {
+        let outline = self.as_polyline();
+        let tag = self.tag_triangle();
+        outline.iter().chain(tag.iter().flatten()).fold(
+            (
+                Vector2::new(f32::MAX, f32::MAX),
+                Vector2::new(f32::MIN, f32::MIN),
+            ),
+            |(min, max), p| {
+                (
+                    Vector2::new(min.x.min(p.x), min.y.min(p.y)),
+                    Vector2::new(max.x.max(p.x), max.y.max(p.y)),
+                )
+            },
+        )
+    }
+
+    fn as_polyline(&self) -> Vec<Vector2> {
+        let mut points = Vec::new();
+        for (a, b, outward) in self.chords() {
+            bulge(a, b, outward, &mut points);
+        }
+        points
+    }
+
+    fn is_closed(&self) -> bool {
+        true
+    }
+
+    fn is_filled(&self) -> bool {
+        false
+    }
+
+    /// Corners of the boundary rather than every bulge
+    fn snap_points(&self) -> Vec<(SnapPointType, Vector2)> {
+        self.boundary
+            .iter()
+            .map(|&p| (SnapPointType::Corner, p))
+            .collect()
+    }
+}
@@ -0,0 +1,120 @@
+//! Rooms found by the ROOMS command.
+//!
+//! A room is an annotation over a closed loop of lines, rectangles or beams:
+//! its boundary, a name and the area the boundary had when the rooms were
+//! last regenerated. Rooms offer nothing to snap to.
+
+use super::Geometry;
+use crate::model::Vector2;
+use crate::model::math::geometry::calculate_polygon_area;
+use crate::model::snap::SnapPointType;
+use crate::model::structure::flooring::polygon_contains;
+use serde::{Deserialize, Serialize};
+
+/// Label height the hit area is estimated with, as the default dimension
+/// text height
+const LABEL_HEIGHT: f32 = 12.0;
+
+/// A named room and its area
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Room {
+    /// Loop the room was found in, counter-clockwise, first point not repeated
+    pub boundary: Vec<Vector2>,
+    pub name: String,
+    /// Area in square drawing units, as of the last regeneration
+    pub area: f32,
+}
+
+impl Room {
+    pub fn new(boundary: Vec<Vector2>, name: String) -> Self {
+        let area = calculate_polygon_area(&boundary);
+        Self {
+            boundary,
+            name,
+            area,
+        }
+    }
+
+    /// Take a newly found boundary and the area it encloses
+    pub fn regenerate(&mut self, boundary: Vec<Vector2>) {
+        self.area = calculate_polygon_area(&boundary);
+        self.boundary = boundary;
+    }
+
+    /// Area in square meters, drawing units being centimeters
+    pub fn area_m2(&self) -> f32 {
+        self.area / 10_000.0
+    }
+
+    /// Text drawn at the center, e.g. "Room 1 — 14.6 m²"
+    pub fn label(&self) -> String {
+        format!("{} — {:.1} m²", self.name, self.area_m2())
+    }
+
+    /// Centroid of the enclosed area, where the label goes
+    pub fn center(&self) -> Vector2 {
+        let n = self.boundary.len();
+        let mut area2 = 0.0;
+        let mut moment = Vector2::new(0.0, 0.0);
+        for i in 0..n {
+            let (a, b) = (self.boundary[i], self.boundary[(i + 1) % n]);
+            let cross = a.cross(b);
+            area2 += cross;
+            moment = moment + (a + b) * cross;
+        }
+        if area2.abs() <= f32::EPSILON {
+            let sum = self
+                .boundary
+                .iter()
+                .fold(Vector2::new(0.0, 0.0), |sum, p| sum + *p);
+            return sum * (1.0 / n.max(1) as f32);
+        }
+        moment * (1.0 / (3.0 * area2))
+    }
+
+    pub fn contains(&self, pos: Vector2) -> bool {
+        polygon_contains(&self.boundary, pos)
+    }
+}
+
+impl Geometry for Room {
+    /// Only the label: the rest of the room is other geometry's space
+    fn hit_test(&self, pos: Vector2, tolerance: f32) -> bool {
+        let center = self.center();
+        let half_width = self.label().chars().count() as f32 * LABEL_HEIGHT * 0.3;
+        (pos.x - center.x).abs() <= half_width + tolerance
+            && (pos.y - center.y).abs() <= LABEL_HEIGHT * 0.75 + tolerance
+    }
+
+    fn bounding_box(&self) -> (Vector2, Vector2) {
+        self.boundary.iter().fold(
+            (
+                Vector2::new(f32::MAX, f32::MAX),
+                Vector2::new(f32::MIN, f32::MIN),
+            ),
+            |(min, max), p| {
+                (
+                    Vector2::new(min.x.min(p.x), min.y.min(p.y)),
+                    Vector2::new(max.x.max(p.x), max.y.max(p.y)),
+                )
+            },
+        )
+    }
+
+    fn as_polyline(&self) -> Vec<Vector2> {
+        self.boundary.clone()
+    }
+
+    fn is_closed(&self) -> bool {
+        true
+    }
+
+    fn is_filled(&self) -> bool {
+        true
+    }
+
+    /// None: the walls around a room are what drawing snaps to
+    fn snap_points(&self) -> Vec<(SnapPointType, Vector2)> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,83 @@
+use super::Geometry;
+use crate::model::Vector2;
+use crate::model::snap::SnapPointType;
+use serde::{Deserialize, Serialize};
+
+/// A section cut line (A–A) with the side it looks toward
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SectionMark {
+    pub start: Vector2,
+    pub end: Vector2,
+    /// Look toward the right of `start`→`end` instead of the left
+    #[serde(default)]
+    pub flipped: bool,
+    /// Letter shown at both ends, e.g. "A"
+    pub label: String,
+}
+
+impl SectionMark {
+    pub fn new(start: Vector2, end: Vector2, label: impl Into<String>) -> Self {
+        Self {
+            start,
+            end,
+            flipped: false,
+            label: label.into(),
+        }
+    }
+
+    /// Unit vector along the cut, from `start` to `end`
+    pub fn along(&self) -> Vector2 {
+        (self.end - self.start).normalized()
+    }
+
+    /// Unit vector the section looks toward, square to the cut
+    pub fn direction(&self) -> Vector2 {
+        let u = self.along();
+        let left = Vector2::new(-u.y, u.x);
+        if self.flipped { -left } else { left }
+    }
+
+    /// Look toward the side of the cut `pos` is on
+    pub fn look_toward(&mut self, pos: Vector2) {
+        let u = self.along();
+        let left = Vector2::new(-u.y, u.x);
+        self.flipped = (pos - self.start).dot(left) < 0.0;
+    }
+
+    /// Title of the generated view, e.g. "Section A–A"
+    pub fn title(&self) -> String {
+        format!("Section {0}–{0}", self.label)
+    }
+}
+
+impl Geometry for SectionMark {
+    fn hit_test(&self, pos: Vector2, tolerance: f32) -> bool {
+        pos.dist_to_line(self.start, self.end) < tolerance
+    }
+
+    fn bounding_box(&self) -> (Vector2, Vector2) {
+        (
+            Vector2::new(self.start.x.min(self.end.x), self.start.y.min(self.end.y)),
+            Vector2::new(self.start.x.max(self.end.x), self.start.y.max(self.end.y)),
+        )
+    }
+
+    fn as_polyline(&self) -> Vec<Vector2> {
+        vec![self.start, self.end]
+    }
+
+    fn is_closed(&self) -> bool {
+        false
+    }
+
+    fn is_filled(&self) -> bool {
+        false
+    }
+
+    fn snap_points(&self) -> Vec<(SnapPointType, Vector2)> {
+        vec![
+            (SnapPointType::Endpoint, self.start),
+            (SnapPointType::Endpoint, self.end),
+        ]
+    }
+}
@@ -0,0 +1,65 @@
+use super::Geometry;
+use crate::model::Vector2;
+use crate::model::snap::SnapPointType;
+use serde::{Deserialize, Serialize};
+
+/// Freehand markup stroke: the simplified path of a pen or highlighter drag
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Sketch {
+    pub points: Vec<Vector2>,
+    /// Drawn as a wide translucent band instead of a pen line
+    #[serde(default)]
+    pub highlighter: bool,
+}
+
+impl Sketch {
+    pub fn new(points: Vec<Vector2>, highlighter: bool) -> Self {
+        Self {
+            points,
+            highlighter,
+        }
+    }
+}
+
+impl Geometry for Sketch {
+    fn hit_test(&self, pos: Vector2, tolerance: f32) -> bool {
+        match self.points.as_slice() {
+            [point] => pos.dist(*point) <= tolerance,
+            points => points
+                .windows(2)
+                .any(|pair| pos.dist_to_line(pair[0], pair[1]) <= tolerance),
+        }
+    }
+
+    fn bounding_box(&self) -> (Vector2, Vector2) {
+        self.points.iter().fold(
+            (
+                Vector2::new(f32::MAX, f32::MAX),
+                Vector2::new(f32::MIN, f32::MIN),
+            ),
+            |(min, max), p| {
+                (
+                    Vector2::new(min.x.min(p.x), min.y.min(p.y)),
+                    Vector2::new(max.x.max(p.x), max.y.max(p.y)),
+                )
+            },
+        )
+    }
+
+    fn as_polyline(&self) -> Vec<Vector2> {
+        self.points.clone()
+    }
+
+    fn is_closed(&self) -> bool {
+        false
+    }
+
+    fn is_filled(&self) -> bool {
+        false
+    }
+
+    /// Markup never attracts the cursor
+    fn snap_points(&self) -> Vec<(SnapPointType, Vector2)> {
+        Vec::new()
+    }
+}
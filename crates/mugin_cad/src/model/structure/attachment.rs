@@ -0,0 +1,188 @@
+//! Beam ends attached to columns.
+//!
+//! A beam end placed in a column is cut back to the column face and
+//! remembers the column. When the column moves, turns or is resized the end
+//! follows; when the column is deleted, or the end is moved off it, the
+//! attachment is dropped and the beam stays where it is.
+
+use crate::model::structure::beam::{BeamAttachment, BeamData};
+use crate::model::structure::column::ColumnData;
+use crate::model::tools::changes::Changes;
+use crate::model::{CadModel, Entity, Geometry, Shape, Vector2};
+use glam::Affine2;
+use std::collections::HashMap;
+
+/// How far an end may be off its column face and still count as on it
+const ATTACH_TOLERANCE: f32 = 0.5;
+
+/// Columns by entity ID, with their world transforms
+pub(crate) type ColumnMap = HashMap<u64, (ColumnData, Affine2)>;
+
+fn collect_columns(entities: &[Entity], columns: &mut ColumnMap) {
+    for entity in entities {
+        if let Shape::Column(col) = &entity.shape {
+            columns.insert(entity.id, (col.clone(), entity.world_transform));
+        }
+        collect_columns(&entity.children, columns);
+    }
+}
+
+fn transform(m: &Affine2, p: Vector2) -> Vector2 {
+    m.transform_point2(p.into()).into()
+}
+
+fn lerp(a: Vector2, b: Vector2, t: f32) -> Vector2 {
+    a + (b - a) * t
+}
+
+/// Whether world point `p` is inside or on the column
+fn on_column((col, world): &(ColumnData, Affine2), p: Vector2) -> bool {
+    col.hit_test(transform(&world.inverse(), p), ATTACH_TOLERANCE)
+}
+
+/// World position an attachment points at
+fn target((_, world): &(ColumnData, Affine2), attachment: &BeamAttachment) -> Vector2 {
+    transform(world, attachment.point)
+}
+
+/// Cut the world segment `start`-`end` back to the faces of the columns
+/// its ends sit in
+fn trim_to_faces(
+    start: Vector2,
+    end: Vector2,
+    start_column: Option<&(ColumnData, Affine2)>,
+    end_column: Option<&(ColumnData, Affine2)>,
+) -> (Vector2, Vector2) {
+    let span = |(col, world): &(ColumnData, Affine2)| {
+        let inverse = world.inverse();
+        col.segment_span(transform(&inverse, start), transform(&inverse, end))
+    };
+    let mut trimmed = (start, end);
+    if let Some((_, exit)) = start_column.and_then(span)
+        && exit < 1.0
+    {
+        trimmed.0 = lerp(start, end, exit);
+    }
+    if let Some((enter, _)) = end_column.and_then(span)
+        && enter > 0.0
+    {
+        trimmed.1 = lerp(start, end, enter);
+    }
+    trimmed
+}
+
+/// Visit every beam in the tree
+fn for_each_beam(entities: &mut [Entity], visit: &mut impl FnMut(&mut Entity)) {
+    for entity in entities {
+        if matches!(entity.shape, Shape::Beam(_)) {
+            visit(entity);
+        }
+        for_each_beam(&mut entity.children, visit);
+    }
+}
+
+impl CadModel {
+    /// Column entity whose outline contains world point `pos`
+    pub fn column_at(&self, pos: Vector2) -> Option<u64> {
+        let mut columns = ColumnMap::new();
+        collect_columns(&self.entities, &mut columns);
+        let mut ids: Vec<u64> = columns
+            .iter()
+            .filter(|(_, column)| on_column(column, pos))
+            .map(|(id, _)| *id)
+            .collect();
+        // The most recently placed one, when columns overlap
+        ids.sort();
+        ids.pop()
+    }
+
+    /// Attach the ends of a new beam (in world coordinates) that lie in a
+    /// column, cutting them back to the column faces
+    pub fn attach_beam(&self, beam: &mut BeamData) {
+        let mut columns = ColumnMap::new();
+        collect_columns(&self.entities, &mut columns);
+        let attach = |p: Vector2| {
+            let id = self.column_at(p)?;
+            let (_, world) = &columns[&id];
+            Some(BeamAttachment {
+                column_id: id,
+                point: transform(&world.inverse(), p),
+            })
+        };
+        beam.start_attachment = attach(beam.start);
+        beam.end_attachment = attach(beam.end);
+        let column = |a: &Option<BeamAttachment>| a.as_ref().map(|a| &columns[&a.column_id]);
+        (beam.start, beam.end) = trim_to_faces(
+            beam.start,
+            beam.end,
+            column(&beam.start_attachment),
+            column(&beam.end_attachment),
+        );
+    }
+
+    /// Bring attached beam ends up to date with the columns. Ends whose
+    /// column moved follow it; ends whose column is gone, or that were moved
+    /// off an unchanged column, are detached.
+    pub fn resolve_attachments(&mut self) {
+        if self.changes.since(self.attachments_revision) == Changes::None {
+            return;
+        }
+        // Commands report every edit as a change to everything, so columns
+        // are compared with how they were at the last pass instead
+        let mut columns = ColumnMap::new();
+        collect_columns(&self.entities, &mut columns);
+        let previous = std::mem::take(&mut self.attached_columns);
+        let moved = |id: u64| previous.get(&id) != columns.get(&id);
+
+        let mut touched = Vec::new();
+        for_each_beam(&mut self.entities, &mut |entity| {
+            let Shape::Beam(beam) = &entity.shape else {
+                return;
+            };
+            if beam.start_attachment.is_none() && beam.end_attachment.is_none() {
+                return;
+            }
+
+            let world = entity.world_transform;
+            let mut resolved = beam.clone();
+            let mut start = transform(&world, beam.start);
+            let mut end = transform(&world, beam.end);
+            for (attachment, point) in [
+                (&mut resolved.start_attachment, &mut start),
+                (&mut resolved.end_attachment, &mut end),
+            ] {
+                let Some(a) = attachment else {
+                    continue;
+                };
+                match columns.get(&a.column_id) {
+                    // Follow the column around
+                    Some(column) if moved(a.column_id) => *point = target(column, a),
+                    // Otherwise only the beam can have moved; keep the end
+                    // if it is still on the column
+                    Some(column) if on_column(column, *point) => *point = target(column, a),
+                    _ => *attachment = None,
+                }
+            }
+            let column = |a: &Option<BeamAttachment>| a.as_ref().map(|a| &columns[&a.column_id]);
+            (start, end) = trim_to_faces(
+                start,
+                end,
+                column(&resolved.start_attachment),
+                column(&resolved.end_attachment),
+            );
+            let inverse = world.inverse();
+            resolved.start = transform(&inverse, start);
+            resolved.end = transform(&inverse, end);
+
+            if resolved != *beam {
+                entity.modify(|shape| *shape = Shape::Beam(resolved));
+                touched.push(entity.id);
+            }
+        });
+        for id in touched {
+            self.changes.touch(id);
+        }
+        self.attachments_revision = self.changes.revision();
+        self.attached_columns = columns;
+    }
+}
@@ -1,5 +1,9 @@
 use crate::model::Vector2;
 use crate::model::shapes::Geometry;
+use crate::model::snap::SnapPointType;
+use crate::model::structure::definitions::StructureDefinitions;
+use crate::model::structure::flooring::polygon_contains;
+use crate::model::structure::junction::body_corners;
 use serde::{Deserialize, Serialize};
 
 /// Anchor alignment for the beam body relative to its axis.
@@ -10,8 +14,18 @@ pub enum BeamAnchor {
     Bottom,
 }
 
+/// A beam end fixed to a column, so it follows the column around
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BeamAttachment {
+    /// Entity ID of the column
+    pub column_id: u64,
+    /// Where the end was placed, in the column's own coordinates. The end
+    /// itself sits on the column face along the beam toward this point.
+    pub point: Vector2,
+}
+
 /// Data defining an individual structural beam instance.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BeamData {
     /// Start point
     pub start: Vector2,
@@ -24,8 +38,20 @@ pub struct BeamData {
     /// Anchor alignment
     #[serde(default = "default_anchor")]
     pub anchor: BeamAnchor,
+    /// Column the start is attached to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_attachment: Option<BeamAttachment>,
+    /// Column the end is attached to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_attachment: Option<BeamAttachment>,
+    /// Storey the beam belongs to; 0 for none
+    #[serde(default)]
+    pub storey_id: u64,
 }
 
+/// Width assumed for a beam whose type is not known
+pub const DEFAULT_BEAM_WIDTH: f32 = 30.0;
+
 fn default_anchor() -> BeamAnchor {
     BeamAnchor::Center
 }
@@ -44,36 +70,70 @@ impl BeamData {
             beam_type_id,
             label,
             anchor,
+            start_attachment: None,
+            end_attachment: None,
+            storey_id: 0,
         }
     }
 
     pub fn length(&self) -> f32 {
         (self.end - self.start).length()
     }
+
+    /// Body width from the beam's type, or the default if it has none
+    pub fn width_in(&self, definitions: &StructureDefinitions) -> f32 {
+        definitions
+            .get_beam_type(self.beam_type_id)
+            .map_or(DEFAULT_BEAM_WIDTH, |beam_type| beam_type.width)
+    }
+
+    /// Corners of the body as drawn when `width` wide, shifted off the axis
+    /// by the anchor. `None` for a beam of no length.
+    pub fn outline(&self, width: f32) -> Option<[Vector2; 4]> {
+        (self.length() > f32::EPSILON)
+            .then(|| body_corners(self.start, self.end, width, self.anchor))
+    }
+
+    /// Whether `pos` is on the body when `width` wide, or within
+    /// `tolerance` of its outline
+    pub fn hit_test_body(&self, pos: Vector2, tolerance: f32, width: f32) -> bool {
+        let Some(corners) = self.outline(width) else {
+            return pos.dist(self.start) <= tolerance + width / 2.0;
+        };
+        polygon_contains(&corners, pos)
+            || (0..4).any(|i| pos.dist_to_line(corners[i], corners[(i + 1) % 4]) <= tolerance)
+    }
+
+    /// Bounding box of the body when `width` wide
+    pub fn body_bounds(&self, width: f32) -> (Vector2, Vector2) {
+        let corners = self.outline(width).unwrap_or_else(|| {
+            let half = Vector2::new(width / 2.0, width / 2.0);
+            [self.start - half, self.start + half, self.start, self.start]
+        });
+        corners.iter().fold(
+            (
+                Vector2::new(f32::MAX, f32::MAX),
+                Vector2::new(f32::MIN, f32::MIN),
+            ),
+            |(min, max), p| {
+                (
+                    Vector2::new(min.x.min(p.x), min.y.min(p.y)),
+                    Vector2::new(max.x.max(p.x), max.y.max(p.y)),
+                )
+            },
+        )
+    }
 }
 
 impl Geometry for BeamData {
+    /// Hit test at the default width; the model passes the type's width
+    /// through [`BeamData::hit_test_body`]
     fn hit_test(&self, pos: Vector2, tolerance: f32) -> bool {
-        // We don't have width here easily without BeamType,
-        // but for hit test we can use a default or handle it in CadModel.
-        // Actually, Geometry methods should be self-contained if possible.
-        // Let's assume a reasonable hit width for now or pass it if we refactor.
-        // For Beams, we hit test against the line segment with tolerance.
-        let l2 = (self.start - self.end).length_squared();
-        if l2 == 0.0 {
-            return (pos - self.start).length() < tolerance;
-        }
-        let t = ((pos - self.start).dot(self.end - self.start) / l2).clamp(0.0, 1.0);
-        let projection = self.start + (self.end - self.start) * t;
-        (pos - projection).length() < tolerance + 15.0 // Buffer for beam thickness
+        self.hit_test_body(pos, tolerance, DEFAULT_BEAM_WIDTH)
     }
 
     fn bounding_box(&self) -> (Vector2, Vector2) {
-        let min_x = self.start.x.min(self.end.x) - 20.0;
-        let min_y = self.start.y.min(self.end.y) - 20.0;
-        let max_x = self.start.x.max(self.end.x) + 20.0;
-        let max_y = self.start.y.max(self.end.y) + 20.0;
-        (Vector2::new(min_x, min_y), Vector2::new(max_x, max_y))
+        self.body_bounds(DEFAULT_BEAM_WIDTH)
     }
 
     fn as_polyline(&self) -> Vec<Vector2> {
@@ -87,4 +147,12 @@ impl Geometry for BeamData {
     fn is_filled(&self) -> bool {
         false
     }
+
+    fn snap_points(&self) -> Vec<(SnapPointType, Vector2)> {
+        vec![
+            (SnapPointType::Endpoint, self.start),
+            (SnapPointType::Endpoint, self.end),
+            (SnapPointType::Midpoint, (self.start + self.end) * 0.5),
+        ]
+    }
 }
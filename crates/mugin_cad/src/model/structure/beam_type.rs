@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 /// Defines reinforcement parameters for a specific zone along the beam length.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BeamRebarZone {
     /// Diameter of ties in mm.
     pub tie_diameter: f32,
@@ -10,7 +10,7 @@ pub struct BeamRebarZone {
 }
 
 /// Structural template for a Beam, including reinforcement and material data.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BeamType {
     pub id: u64,
     pub name: String,
@@ -1,10 +1,14 @@
 use crate::model::Vector2;
 use crate::model::shapes::Geometry;
+use crate::model::snap::SnapPointType;
+use crate::model::structure::flooring::polygon_contains;
 use serde::{Deserialize, Serialize};
 
-/// Anchor point for the column instance.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// Point of the column that stays put when its size changes. Corners are
+/// named as seen on the canvas before rotation (Y up).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum ColumnAnchor {
+    #[default]
     Center,
     TopLeft,
     TopRight,
@@ -12,8 +16,46 @@ pub enum ColumnAnchor {
     BottomLeft,
 }
 
+impl ColumnAnchor {
+    /// In the order Q cycles through them
+    pub const ALL: [ColumnAnchor; 5] = [
+        ColumnAnchor::Center,
+        ColumnAnchor::TopLeft,
+        ColumnAnchor::TopRight,
+        ColumnAnchor::BottomRight,
+        ColumnAnchor::BottomLeft,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ColumnAnchor::Center => "Center",
+            ColumnAnchor::TopLeft => "Top Left",
+            ColumnAnchor::TopRight => "Top Right",
+            ColumnAnchor::BottomRight => "Bottom Right",
+            ColumnAnchor::BottomLeft => "Bottom Left",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|a| *a == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// Offset from the center of a `width` x `height` column, unrotated
+    pub fn local_offset(self, width: f32, height: f32) -> Vector2 {
+        let (hw, hh) = (width / 2.0, height / 2.0);
+        match self {
+            ColumnAnchor::Center => Vector2::new(0.0, 0.0),
+            ColumnAnchor::TopLeft => Vector2::new(-hw, hh),
+            ColumnAnchor::TopRight => Vector2::new(hw, hh),
+            ColumnAnchor::BottomRight => Vector2::new(hw, -hh),
+            ColumnAnchor::BottomLeft => Vector2::new(-hw, -hh),
+        }
+    }
+}
+
 /// Data defining a structural column.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ColumnData {
     /// Center position of the column.
     pub center: Vector2,
@@ -28,12 +70,11 @@ pub struct ColumnData {
     /// Text label displayed on the column (e.g., "S1").
     pub label: String,
     /// The geometric anchor used for placement and resizing.
-    #[serde(default = "default_anchor")]
+    #[serde(default)]
     pub anchor: ColumnAnchor,
-}
-
-fn default_anchor() -> ColumnAnchor {
-    ColumnAnchor::Center
+    /// Storey the column stands on; 0 for none
+    #[serde(default)]
+    pub storey_id: u64,
 }
 
 impl ColumnData {
@@ -53,23 +94,65 @@ impl ColumnData {
             column_type_id,
             label,
             anchor,
+            storey_id: 0,
         }
     }
 
+    /// Offset of `anchor` from the center, rotated with the column
+    fn anchor_offset(&self, anchor: ColumnAnchor) -> Vector2 {
+        anchor
+            .local_offset(self.width, self.height)
+            .rotated(self.rotation)
+    }
+
+    /// The anchor's position in the drawing
+    pub fn anchor_point(&self) -> Vector2 {
+        self.center + self.anchor_offset(self.anchor)
+    }
+
+    /// Move the column so that `anchor` lands on `point`
+    pub fn place_anchor_at(&mut self, point: Vector2) {
+        self.center = point - self.anchor_offset(self.anchor);
+    }
+
+    /// Change the size, keeping the anchor where it is
+    pub fn resize(&mut self, width: f32, height: f32) {
+        let fixed = self.anchor_point();
+        self.width = width;
+        self.height = height;
+        self.place_anchor_at(fixed);
+    }
+
+    /// Range `(enter, exit)` of `t` in `0..=1` for which `a + (b - a) * t`
+    /// lies inside the column, if the segment reaches it
+    pub fn segment_span(&self, a: Vector2, b: Vector2) -> Option<(f32, f32)> {
+        let to_local = |p: Vector2| (p - self.center).rotated(-self.rotation);
+        let (start, end) = (to_local(a), to_local(b));
+        let (mut enter, mut exit) = (0.0_f32, 1.0_f32);
+        for (from, delta, half) in [
+            (start.x, end.x - start.x, self.width / 2.0),
+            (start.y, end.y - start.y, self.height / 2.0),
+        ] {
+            if delta.abs() < f32::EPSILON {
+                if from.abs() > half {
+                    return None;
+                }
+                continue;
+            }
+            let t0 = (-half - from) / delta;
+            let t1 = (half - from) / delta;
+            enter = enter.max(t0.min(t1));
+            exit = exit.min(t0.max(t1));
+        }
+        (enter <= exit).then_some((enter, exit))
+    }
+
     /// Helper to get corner points (rotated).
     pub fn get_corners(&self) -> [Vector2; 4] {
         let half_w = self.width / 2.0;
         let half_h = self.height / 2.0;
 
-        let cos_a = self.rotation.cos();
-        let sin_a = self.rotation.sin();
-
-        let rotate = |x: f32, y: f32| {
-            Vector2::new(
-                self.center.x + x * cos_a - y * sin_a,
-                self.center.y + x * sin_a + y * cos_a,
-            )
-        };
+        let rotate = |x: f32, y: f32| self.center + Vector2::new(x, y).rotated(self.rotation);
 
         [
             rotate(-half_w, -half_h),
@@ -82,32 +165,11 @@ impl ColumnData {
 
 impl Geometry for ColumnData {
     fn hit_test(&self, pos: Vector2, tolerance: f32) -> bool {
-        // Simple hit test: transform point to local aligned space, check rect
-        let dx = pos.x - self.center.x;
-        let dy = pos.y - self.center.y;
-
-        let cos_a = (-self.rotation).cos();
-        let sin_a = (-self.rotation).sin();
-
-        let local_x = dx * cos_a - dy * sin_a;
-        let local_y = dx * sin_a + dy * cos_a;
-
-        let half_w = self.width / 2.0;
-        let half_h = self.height / 2.0;
-
-        // Check if inside rect
-        let inside = local_x.abs() <= half_w && local_y.abs() <= half_h;
-        if inside {
-            return true;
-        }
-
-        // Check edges (tolerance)
-        let on_edge_x =
-            (local_x.abs() - half_w).abs() < tolerance && local_y.abs() <= half_h + tolerance;
-        let on_edge_y =
-            (local_y.abs() - half_h).abs() < tolerance && local_x.abs() <= half_w + tolerance;
-
-        on_edge_x || on_edge_y
+        // The true footprint, turned with the column, plus a band around
+        // its edges
+        let corners = self.get_corners();
+        polygon_contains(&corners, pos)
+            || (0..4).any(|i| pos.dist_to_line(corners[i], corners[(i + 1) % 4]) <= tolerance)
     }
 
     fn bounding_box(&self) -> (Vector2, Vector2) {
@@ -137,4 +199,16 @@ impl Geometry for ColumnData {
         // Columns are typically filled/hatched
         true
     }
+
+    /// Center, corners and the middle of each side, turned with the column
+    fn snap_points(&self) -> Vec<(SnapPointType, Vector2)> {
+        let corners = self.get_corners();
+        let mut points = vec![(SnapPointType::Center, self.center)];
+        for (i, &corner) in corners.iter().enumerate() {
+            let next = corners[(i + 1) % corners.len()];
+            points.push((SnapPointType::Corner, corner));
+            points.push((SnapPointType::Midpoint, (corner + next) * 0.5));
+        }
+        points
+    }
 }
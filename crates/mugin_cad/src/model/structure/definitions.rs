@@ -32,6 +32,20 @@ impl StructureDefinitions {
         id
     }
 
+    /// Move the ID counter past every ID in use, for tables that were
+    /// filled with explicit IDs
+    pub(crate) fn skip_used_ids(&mut self) {
+        let used = self
+            .materials
+            .keys()
+            .chain(self.column_types.keys())
+            .chain(self.beam_types.keys())
+            .max()
+            .copied()
+            .unwrap_or(0);
+        self.next_id = self.next_id.max(used + 1);
+    }
+
     // --- Materials ---
 
     pub fn add_material(&mut self, mut material: Material) -> u64 {
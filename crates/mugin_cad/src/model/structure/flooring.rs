@@ -0,0 +1,241 @@
+//! Floor slabs.
+//!
+//! A slab is a closed boundary polygon with a floor type number and a
+//! thickness. The floor type picks the slab's tint or hatch on the canvas.
+//! Shaft and stair openings are hole polygons inside the boundary; they are
+//! kept as separate rings rather than cut into the outline.
+
+use crate::model::shapes::Geometry;
+use crate::model::snap::SnapPointType;
+use crate::model::structure::storey::StoreyManager;
+use crate::model::{CadModel, Entity, Shape, Vector2};
+use serde::{Deserialize, Serialize};
+
+/// A floor slab
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Flooring {
+    /// Outline, without repeating the first point
+    pub boundary: Vec<Vector2>,
+    /// Openings inside the outline
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub holes: Vec<Vec<Vector2>>,
+    pub floor_type_id: u64,
+    /// Slab thickness
+    pub thickness: f32,
+    /// Storey the slab belongs to; 0 for none
+    #[serde(default)]
+    pub storey_id: u64,
+}
+
+/// Twice the signed area of a polygon, positive when counter-clockwise
+fn signed_area2(points: &[Vector2]) -> f32 {
+    (0..points.len())
+        .map(|i| {
+            let (a, b) = (points[i], points[(i + 1) % points.len()]);
+            a.x * b.y - b.x * a.y
+        })
+        .sum()
+}
+
+/// Whether `pos` is inside `polygon`, by the even-odd rule
+pub fn polygon_contains(polygon: &[Vector2], pos: Vector2) -> bool {
+    let mut inside = false;
+    for i in 0..polygon.len() {
+        let (a, b) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+        if (a.y > pos.y) != (b.y > pos.y) {
+            let x = a.x + (pos.y - a.y) / (b.y - a.y) * (b.x - a.x);
+            if pos.x < x {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+impl Flooring {
+    pub fn new(boundary: Vec<Vector2>, floor_type_id: u64, thickness: f32) -> Self {
+        Self {
+            boundary,
+            holes: Vec::new(),
+            floor_type_id,
+            thickness,
+            storey_id: 0,
+        }
+    }
+
+    /// Plan area, by the shoelace formula, less the openings
+    pub fn area(&self) -> f32 {
+        let holes: f32 = self.holes.iter().map(|h| signed_area2(h).abs()).sum();
+        (signed_area2(&self.boundary).abs() - holes) / 2.0
+    }
+
+    /// Centroid of the slab's area, openings excluded, where its label goes
+    pub fn centroid(&self) -> Vector2 {
+        // Area-weighted centroids, openings counted negative
+        let mut area = 0.0;
+        let mut moment = Vector2::new(0.0, 0.0);
+        for (i, ring) in self.rings().enumerate() {
+            let sign = if i == 0 { 1.0 } else { -1.0 };
+            let area2 = signed_area2(ring);
+            let mut c = Vector2::new(0.0, 0.0);
+            for j in 0..ring.len() {
+                let (a, b) = (ring[j], ring[(j + 1) % ring.len()]);
+                c = c + (a + b) * (a.x * b.y - b.x * a.y);
+            }
+            // `c` is the centroid times 3·area2; keep the area's sign
+            let weight = sign * area2.signum();
+            area += weight * area2;
+            moment = moment + c * weight;
+        }
+        if area.abs() <= f32::EPSILON {
+            // Degenerate outline: the mean of its points
+            let n = self.boundary.len().max(1) as f32;
+            let sum = self
+                .boundary
+                .iter()
+                .fold(Vector2::new(0.0, 0.0), |sum, p| sum + *p);
+            return sum * (1.0 / n);
+        }
+        moment * (1.0 / (3.0 * area))
+    }
+
+    /// The outline followed by the openings
+    pub fn rings(&self) -> impl Iterator<Item = &Vec<Vector2>> {
+        std::iter::once(&self.boundary).chain(&self.holes)
+    }
+
+    /// Edges of the outline and the openings
+    pub fn edges(&self) -> impl Iterator<Item = (Vector2, Vector2)> + '_ {
+        self.rings().flat_map(|ring| {
+            let n = ring.len();
+            (0..n).map(move |i| (ring[i], ring[(i + 1) % n]))
+        })
+    }
+
+    /// Whether `pos` is on the slab itself, not in an opening
+    pub fn contains(&self, pos: Vector2) -> bool {
+        polygon_contains(&self.boundary, pos)
+            && !self.holes.iter().any(|hole| polygon_contains(hole, pos))
+    }
+
+    /// Whether `hole` fits as a new opening: inside the outline and clear
+    /// of the existing openings
+    pub fn can_cut(&self, hole: &[Vector2]) -> bool {
+        if hole.len() < 3 || signed_area2(hole).abs() <= f32::EPSILON {
+            return false;
+        }
+        hole.iter().all(|p| self.contains(*p))
+            && !self.holes.iter().any(|other| {
+                other.iter().any(|p| polygon_contains(hole, *p)) || segments_cross(hole, other)
+            })
+            && !segments_cross(hole, &self.boundary)
+    }
+}
+
+/// Whether any edge of ring `a` properly crosses an edge of ring `b`
+fn segments_cross(a: &[Vector2], b: &[Vector2]) -> bool {
+    let cross =
+        |o: Vector2, p: Vector2, q: Vector2| (p.x - o.x) * (q.y - o.y) - (p.y - o.y) * (q.x - o.x);
+    let edges = |ring: &[Vector2]| {
+        let n = ring.len();
+        (0..n)
+            .map(move |i| (ring[i], ring[(i + 1) % n]))
+            .collect::<Vec<_>>()
+    };
+    let eb = edges(b);
+    edges(a).into_iter().any(|(p1, p2)| {
+        eb.iter().any(|&(q1, q2)| {
+            let d1 = cross(q1, q2, p1);
+            let d2 = cross(q1, q2, p2);
+            let d3 = cross(p1, p2, q1);
+            let d4 = cross(p1, p2, q2);
+            d1 * d2 < 0.0 && d3 * d4 < 0.0
+        })
+    })
+}
+
+impl Geometry for Flooring {
+    fn hit_test(&self, pos: Vector2, tolerance: f32) -> bool {
+        self.contains(pos)
+            || self
+                .edges()
+                .any(|(a, b)| pos.dist_to_line(a, b) < tolerance)
+    }
+
+    fn bounding_box(&self) -> (Vector2, Vector2) {
+        self.boundary.iter().fold(
+            (
+                Vector2::new(f32::MAX, f32::MAX),
+                Vector2::new(f32::MIN, f32::MIN),
+            ),
+            |(min, max), p| {
+                (
+                    Vector2::new(min.x.min(p.x), min.y.min(p.y)),
+                    Vector2::new(max.x.max(p.x), max.y.max(p.y)),
+                )
+            },
+        )
+    }
+
+    fn as_polyline(&self) -> Vec<Vector2> {
+        self.boundary.clone()
+    }
+
+    fn is_closed(&self) -> bool {
+        true
+    }
+
+    fn is_filled(&self) -> bool {
+        true
+    }
+
+    fn snap_points(&self) -> Vec<(SnapPointType, Vector2)> {
+        self.edges()
+            .flat_map(|(a, b)| {
+                [
+                    (SnapPointType::Corner, a),
+                    (SnapPointType::Midpoint, (a + b) * 0.5),
+                ]
+            })
+            .collect()
+    }
+}
+
+impl CadModel {
+    /// Cut `hole`, in world coordinates, out of the topmost editable slab it
+    /// fits inside. Returns the slab's ID.
+    pub fn cut_opening(&mut self, hole: &[Vector2]) -> Option<u64> {
+        fn find(
+            entities: &[Entity],
+            storeys: &StoreyManager,
+            hole: &[Vector2],
+        ) -> Option<(u64, Vec<Vector2>)> {
+            entities.iter().rev().find_map(|entity| {
+                if let Some(found) = find(&entity.children, storeys, hole) {
+                    return Some(found);
+                }
+                let Shape::Flooring(floor) = &entity.shape else {
+                    return None;
+                };
+                if !entity.on_active_storey(storeys) {
+                    return None;
+                }
+                let inverse = entity.world_transform.inverse();
+                let local: Vec<Vector2> = hole
+                    .iter()
+                    .map(|p| inverse.transform_point2((*p).into()).into())
+                    .collect();
+                floor.can_cut(&local).then_some((entity.id, local))
+            })
+        }
+
+        let (id, local) = find(&self.entities, &self.storeys, hole)?;
+        let entity = self.find_by_id_mut(id)?;
+        entity.modify(|shape| {
+            if let Shape::Flooring(floor) = shape {
+                floor.holes.push(local);
+            }
+        });
+        Some(id)
+    }
+}
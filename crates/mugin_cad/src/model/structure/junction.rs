@@ -0,0 +1,416 @@
+//! Clean-up of beam junctions.
+//!
+//! Where beams meet their outlines overlap, leaving the faces of one drawn
+//! across the body of the other. The cleanup pass finds the L, T and X
+//! junctions between beams of a storey and records per beam how far to draw
+//! its ends past the axis, closing L corners and T stems, and which stretches
+//! of its outline lie inside the other beam and are left out. The beams
+//! themselves are never changed.
+
+use crate::model::structure::beam::{BeamAnchor, BeamData};
+use crate::model::structure::definitions::StructureDefinitions;
+use crate::model::tools::changes::Changes;
+use crate::model::{CadModel, Entity, Shape, Vector2};
+use std::collections::HashMap;
+
+/// How far a beam end may stop short of the other beam and still join it
+pub const JOIN_TOLERANCE: f32 = 1.0;
+
+/// Beams closer to parallel than this (sine of the angle) never join
+const MIN_SINE: f32 = 0.05;
+
+/// How far inside the other beam a face must be to be hidden, so faces that
+/// only touch stay drawn
+const INSIDE_EPSILON: f32 = 1e-3;
+
+/// How two beams meet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JunctionKind {
+    /// Both beams end at the junction
+    L,
+    /// One beam ends on the side of the other
+    T,
+    /// The beams cross
+    X,
+}
+
+/// Two beams meeting
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Junction {
+    /// Entity IDs of the beams. For a T the first one is the stem.
+    pub beams: [u64; 2],
+    pub kind: JunctionKind,
+    /// Where the body axes cross, in world coordinates
+    pub point: Vector2,
+}
+
+/// How a beam is drawn at its junctions
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BeamJoin {
+    /// Beam start the join was worked out for, in the beam's own coordinates
+    pub start: Vector2,
+    /// Beam end the join was worked out for
+    pub end: Vector2,
+    /// How far to draw past the start, as a fraction of the beam length
+    pub start_extension: f32,
+    /// How far to draw past the end, as a fraction of the beam length
+    pub end_extension: f32,
+    /// Hidden stretches of each outline edge, as `(from, to)` fractions
+    /// along it. Edges are those of [`body_corners`] on the extended beam:
+    /// one face, the end, the other face and the start.
+    pub hidden: [Vec<(f32, f32)>; 4],
+}
+
+impl BeamJoin {
+    /// Whether the join still fits the beam, i.e. the beam was not moved
+    /// since it was worked out
+    pub fn matches(&self, beam: &BeamData) -> bool {
+        self.start == beam.start && self.end == beam.end
+    }
+
+    /// Start and end of the beam as drawn, extended into its junctions
+    pub fn extended(&self) -> (Vector2, Vector2) {
+        let axis = self.end - self.start;
+        (
+            self.start - axis * self.start_extension,
+            self.end + axis * self.end_extension,
+        )
+    }
+
+    /// Visible stretches of outline edge `edge`, as fractions along it
+    pub fn visible(&self, edge: usize) -> Vec<(f32, f32)> {
+        let mut visible = Vec::new();
+        let mut from = 0.0;
+        for &(a, b) in &self.hidden[edge] {
+            if a > from {
+                visible.push((from, a));
+            }
+            from = from.max(b);
+        }
+        if from < 1.0 {
+            visible.push((from, 1.0));
+        }
+        visible
+    }
+}
+
+/// Result of the cleanup pass
+#[derive(Debug, Clone, Default)]
+pub struct BeamJoins {
+    pub junctions: Vec<Junction>,
+    /// Joins by beam entity ID
+    pub joins: HashMap<u64, BeamJoin>,
+}
+
+impl BeamJoins {
+    /// Join of beam entity `id`, unless the beam moved since the pass
+    pub fn get(&self, id: u64, beam: &BeamData) -> Option<&BeamJoin> {
+        self.joins.get(&id).filter(|join| join.matches(beam))
+    }
+}
+
+/// Corners of a beam body from `start` to `end`, going along one face, across
+/// the end, back along the other face and across the start. Matches the
+/// order the renderer draws the body in.
+pub fn body_corners(start: Vector2, end: Vector2, width: f32, anchor: BeamAnchor) -> [Vector2; 4] {
+    let dir = (end - start).normalized();
+    // Right of the axis; the renderer's anchor offsets are in screen space,
+    // where Y points down
+    let right = Vector2::new(dir.y, -dir.x);
+    let half = width / 2.0;
+    let offset = match anchor {
+        BeamAnchor::Center => 0.0,
+        BeamAnchor::Top => half,
+        BeamAnchor::Bottom => -half,
+    };
+    [
+        start + right * (offset + half),
+        end + right * (offset + half),
+        end + right * (offset - half),
+        start + right * (offset - half),
+    ]
+}
+
+// ── Detection ──────────────────────────────────────────────
+
+/// A beam in world coordinates
+struct Body {
+    id: u64,
+    storey: u64,
+    beam: BeamData,
+    width: f32,
+    start: Vector2,
+    end: Vector2,
+    /// Middle of the start of the body, which the anchor may shift off the
+    /// axis
+    center: Vector2,
+    dir: Vector2,
+    length: f32,
+}
+
+fn cross(a: Vector2, b: Vector2) -> f32 {
+    a.x * b.y - a.y * b.x
+}
+
+fn collect_bodies(
+    entities: &[Entity],
+    definitions: &StructureDefinitions,
+    include: &impl Fn(u64, &BeamData) -> bool,
+    bodies: &mut Vec<Body>,
+) {
+    for entity in entities {
+        if let Shape::Beam(beam) = &entity.shape
+            && let Some(beam_type) = definitions.beam_types.get(&beam.beam_type_id)
+            && include(entity.id, beam)
+        {
+            let world = entity.world_transform;
+            let start: Vector2 = world.transform_point2(beam.start.into()).into();
+            let end: Vector2 = world.transform_point2(beam.end.into()).into();
+            let length = start.dist(end);
+            if length > f32::EPSILON {
+                let corners = body_corners(start, end, beam_type.width, beam.anchor);
+                bodies.push(Body {
+                    id: entity.id,
+                    storey: beam.storey_id,
+                    beam: beam.clone(),
+                    width: beam_type.width,
+                    start,
+                    end,
+                    center: (corners[0] + corners[3]) / 2.0,
+                    dir: (end - start) / length,
+                    length,
+                });
+            }
+        }
+        collect_bodies(&entity.children, definitions, include, bodies);
+    }
+}
+
+/// Which end of a beam lies at a junction
+#[derive(Clone, Copy, PartialEq)]
+enum Place {
+    Start,
+    End,
+    Middle,
+}
+
+/// Where beam `a` meets beam `b`: the distance along `a`'s body axis to the
+/// crossing, how far `b`'s body reaches either side of it, and which end
+/// of `a` is there. `None` when they do not meet.
+fn meeting(a: &Body, b: &Body, sine: f32) -> Option<(f32, f32, Place)> {
+    let t = cross(b.center - a.center, b.dir) / cross(a.dir, b.dir);
+    let cosine = a.dir.dot(b.dir);
+    // Half the stretch of `a`'s axis covered by `b`, and how much further
+    // `a`'s faces reach along a slanted junction
+    let reach = b.width / 2.0 / sine;
+    let margin = reach + a.width / 2.0 * (cosine / sine).abs() + JOIN_TOLERANCE;
+    if t < -margin || t > a.length + margin {
+        return None;
+    }
+    let end = match (t <= margin, t >= a.length - margin) {
+        (true, true) if t < a.length / 2.0 => Place::Start,
+        (true, true) => Place::End,
+        (true, false) => Place::Start,
+        (false, true) => Place::End,
+        (false, false) => Place::Middle,
+    };
+    Some((t, reach, end))
+}
+
+/// Extension past `end` that takes the axis of a beam of length `length`
+/// to `target`, measured along it from the start
+fn extension(end: Place, target: f32, length: f32) -> f32 {
+    match end {
+        Place::Start => (-target).max(0.0),
+        Place::End => (target - length).max(0.0),
+        Place::Middle => 0.0,
+    }
+}
+
+/// Parameter range of the segment `p`-`q` strictly inside the convex
+/// polygon `corners`
+fn clip_inside(p: Vector2, q: Vector2, corners: &[Vector2; 4]) -> Option<(f32, f32)> {
+    let area: f32 = (0..4)
+        .map(|i| cross(corners[i], corners[(i + 1) % 4]))
+        .sum();
+    let sign = area.signum();
+    let (mut lo, mut hi) = (0.0f32, 1.0f32);
+    for i in 0..4 {
+        let (a, b) = (corners[i], corners[(i + 1) % 4]);
+        let edge = b - a;
+        let length = edge.length();
+        if length <= f32::EPSILON {
+            continue;
+        }
+        let inward = Vector2::new(-edge.y, edge.x) * (sign / length);
+        let f0 = inward.dot(p - a) - INSIDE_EPSILON;
+        let df = inward.dot(q - p);
+        if df.abs() <= f32::EPSILON {
+            if f0 <= 0.0 {
+                return None;
+            }
+        } else if df > 0.0 {
+            lo = lo.max(-f0 / df);
+        } else {
+            hi = hi.min(-f0 / df);
+        }
+    }
+    (hi - lo > 1e-6).then_some((lo, hi))
+}
+
+/// Sort and merge overlapping ranges
+fn merge(ranges: &mut Vec<(f32, f32)>) {
+    ranges.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut merged: Vec<(f32, f32)> = Vec::with_capacity(ranges.len());
+    for &(a, b) in ranges.iter() {
+        match merged.last_mut() {
+            Some(last) if a <= last.1 => last.1 = last.1.max(b),
+            _ => merged.push((a, b)),
+        }
+    }
+    *ranges = merged;
+}
+
+/// Find the junctions between the beams `include` accepts
+fn find_joins(
+    entities: &[Entity],
+    definitions: &StructureDefinitions,
+    include: impl Fn(u64, &BeamData) -> bool,
+) -> BeamJoins {
+    let mut bodies = Vec::new();
+    collect_bodies(entities, definitions, &include, &mut bodies);
+
+    // World length of the extension of each body's (start, end)
+    let mut extensions = vec![(0.0f32, 0.0f32); bodies.len()];
+    let mut junctions = Vec::new();
+    let mut pairs = Vec::new();
+    for i in 0..bodies.len() {
+        for j in i + 1..bodies.len() {
+            let (a, b) = (&bodies[i], &bodies[j]);
+            if a.storey != b.storey {
+                continue;
+            }
+            let sine = cross(a.dir, b.dir).abs();
+            if sine < MIN_SINE {
+                continue;
+            }
+            let (Some((ta, reach_a, end_a)), Some((tb, reach_b, end_b))) =
+                (meeting(a, b, sine), meeting(b, a, sine))
+            else {
+                continue;
+            };
+            let (kind, stem_first) = match (end_a, end_b) {
+                (Place::Middle, Place::Middle) => (JunctionKind::X, true),
+                (_, Place::Middle) => (JunctionKind::T, true),
+                (Place::Middle, _) => (JunctionKind::T, false),
+                _ => (JunctionKind::L, true),
+            };
+            // L corners reach to the outside of the other beam; T stems to
+            // the middle of the beam they end on
+            let (target_a, target_b) = match kind {
+                JunctionKind::L => (
+                    if end_a == Place::Start {
+                        ta - reach_a
+                    } else {
+                        ta + reach_a
+                    },
+                    if end_b == Place::Start {
+                        tb - reach_b
+                    } else {
+                        tb + reach_b
+                    },
+                ),
+                _ => (ta, tb),
+            };
+            for (k, body, end, target) in [(i, a, end_a, target_a), (j, b, end_b, target_b)] {
+                let extension = extension(end, target, body.length);
+                match end {
+                    Place::Start => extensions[k].0 = extensions[k].0.max(extension),
+                    Place::End => extensions[k].1 = extensions[k].1.max(extension),
+                    Place::Middle => {}
+                }
+            }
+            let beams = if stem_first {
+                [a.id, b.id]
+            } else {
+                [b.id, a.id]
+            };
+            junctions.push(Junction {
+                beams,
+                kind,
+                point: a.center + a.dir * ta,
+            });
+            pairs.push((i, j));
+        }
+    }
+
+    // Outlines of the extended bodies
+    let outlines: Vec<[Vector2; 4]> = bodies
+        .iter()
+        .zip(&extensions)
+        .map(|(body, &(before, after))| {
+            body_corners(
+                body.start - body.dir * before,
+                body.end + body.dir * after,
+                body.width,
+                body.beam.anchor,
+            )
+        })
+        .collect();
+
+    let mut joins: HashMap<u64, BeamJoin> = HashMap::new();
+    for &(i, j) in &pairs {
+        for (k, other) in [(i, j), (j, i)] {
+            let body = &bodies[k];
+            let join = joins.entry(body.id).or_insert_with(|| BeamJoin {
+                start: body.beam.start,
+                end: body.beam.end,
+                start_extension: extensions[k].0 / body.length,
+                end_extension: extensions[k].1 / body.length,
+                hidden: Default::default(),
+            });
+            let corners = &outlines[k];
+            for (edge, hidden) in join.hidden.iter_mut().enumerate() {
+                let (p, q) = (corners[edge], corners[(edge + 1) % 4]);
+                if let Some(range) = clip_inside(p, q, &outlines[other]) {
+                    hidden.push(range);
+                }
+            }
+        }
+    }
+    for join in joins.values_mut() {
+        for hidden in &mut join.hidden {
+            merge(hidden);
+        }
+    }
+
+    BeamJoins { junctions, joins }
+}
+
+impl CadModel {
+    /// Find the beam junctions and record how to draw them cleanly. Returns
+    /// the number of junctions found.
+    pub fn cleanup_junctions(&mut self) -> usize {
+        self.beam_joins = find_joins(&self.entities, &self.definitions, |_, _| true);
+        self.joins_revision = self.changes.revision();
+        self.beam_joins.junctions.len()
+    }
+
+    /// Bring the junctions up to date after edits. With `auto` every beam is
+    /// joined; otherwise only beams cleaned up before that have not moved
+    /// since keep their joins.
+    pub fn refresh_junctions(&mut self, auto: bool) {
+        if self.changes.since(self.joins_revision) == Changes::None {
+            return;
+        }
+        if auto {
+            self.cleanup_junctions();
+            return;
+        }
+        let previous = std::mem::take(&mut self.beam_joins.joins);
+        self.beam_joins = find_joins(&self.entities, &self.definitions, |id, beam| {
+            previous.get(&id).is_some_and(|join| join.matches(beam))
+        });
+        self.joins_revision = self.changes.revision();
+    }
+}
@@ -0,0 +1,225 @@
+//! Structural type library shared between projects.
+//!
+//! The library is a plain `StructureDefinitions` stored as JSON in the app's
+//! data directory. Definitions move between it and a project by name: IDs
+//! are local to each side and get remapped on the way.
+
+use crate::model::structure::definitions::StructureDefinitions;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Which table of the definitions an entry lives in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DefinitionKind {
+    Material,
+    ColumnType,
+    BeamType,
+}
+
+impl DefinitionKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            DefinitionKind::Material => "Material",
+            DefinitionKind::ColumnType => "Column type",
+            DefinitionKind::BeamType => "Beam type",
+        }
+    }
+}
+
+/// An incoming definition whose name is taken by a different one
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DefinitionConflict {
+    pub kind: DefinitionKind,
+    pub name: String,
+}
+
+/// Outcome of a merge
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MergeSummary {
+    pub added: usize,
+    pub replaced: usize,
+    pub kept: usize,
+}
+
+/// Read the library; a missing file is an empty library
+pub fn read_library(path: &Path) -> Result<StructureDefinitions, String> {
+    if !path.exists() {
+        return Ok(StructureDefinitions::new());
+    }
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("{} is not a valid structure library: {}", path.display(), e))
+}
+
+/// Write the library, creating its directory if needed
+pub fn write_library(path: &Path, library: &StructureDefinitions) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Could not create {}: {}", dir.display(), e))?;
+    }
+    let json = serde_json::to_string_pretty(library)
+        .map_err(|e| format!("Could not serialize structure library: {}", e))?;
+    std::fs::write(path, json).map_err(|e| format!("Could not write {}: {}", path.display(), e))
+}
+
+/// Sorted IDs of a table, so merges are deterministic
+fn sorted_ids<T>(table: &HashMap<u64, T>) -> Vec<u64> {
+    let mut ids: Vec<u64> = table.keys().copied().collect();
+    ids.sort();
+    ids
+}
+
+fn find_by_name<T>(table: &HashMap<u64, T>, name: &str, name_of: fn(&T) -> &str) -> Option<u64> {
+    sorted_ids(table)
+        .into_iter()
+        .find(|id| name_of(&table[id]) == name)
+}
+
+impl StructureDefinitions {
+    /// Map `incoming` material IDs to ours by name
+    fn material_ids_by_name(&self, incoming: &StructureDefinitions) -> HashMap<u64, u64> {
+        incoming
+            .materials
+            .values()
+            .filter_map(|m| {
+                let id = find_by_name(&self.materials, &m.name, |m| &m.name)?;
+                Some((m.id, id))
+            })
+            .collect()
+    }
+
+    /// Incoming definitions whose names exist here with different contents
+    pub fn name_conflicts(&self, incoming: &StructureDefinitions) -> Vec<DefinitionConflict> {
+        let materials = self.material_ids_by_name(incoming);
+        // Material references that cannot be matched never compare equal
+        let remap = |id: u64| materials.get(&id).copied().unwrap_or(u64::MAX);
+        let mut conflicts = Vec::new();
+
+        for id in sorted_ids(&incoming.materials) {
+            let mut material = incoming.materials[&id].clone();
+            if let Some(existing) = materials.get(&id).map(|own| &self.materials[own]) {
+                material.id = existing.id;
+                if material != *existing {
+                    conflicts.push(DefinitionConflict {
+                        kind: DefinitionKind::Material,
+                        name: material.name,
+                    });
+                }
+            }
+        }
+        for id in sorted_ids(&incoming.column_types) {
+            let mut column = incoming.column_types[&id].clone();
+            if let Some(own) = find_by_name(&self.column_types, &column.name, |c| &c.name) {
+                column.id = own;
+                column.concrete_material_id = remap(column.concrete_material_id);
+                column.long_rebar_material_id = remap(column.long_rebar_material_id);
+                column.tie_material_id = remap(column.tie_material_id);
+                if column != self.column_types[&own] {
+                    conflicts.push(DefinitionConflict {
+                        kind: DefinitionKind::ColumnType,
+                        name: column.name,
+                    });
+                }
+            }
+        }
+        for id in sorted_ids(&incoming.beam_types) {
+            let mut beam = incoming.beam_types[&id].clone();
+            if let Some(own) = find_by_name(&self.beam_types, &beam.name, |b| &b.name) {
+                beam.id = own;
+                beam.concrete_material_id = remap(beam.concrete_material_id);
+                beam.steel_material_id = remap(beam.steel_material_id);
+                if beam != self.beam_types[&own] {
+                    conflicts.push(DefinitionConflict {
+                        kind: DefinitionKind::BeamType,
+                        name: beam.name,
+                    });
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// Merge `incoming` by name. Missing definitions are added under fresh
+    /// IDs; existing ones keep their ID and are overwritten only when listed
+    /// in `replace`. Existing IDs never change, so placed columns and beams
+    /// stay valid, and added types point at our copies of their materials.
+    pub fn merge_by_name(
+        &mut self,
+        incoming: &StructureDefinitions,
+        replace: &HashSet<DefinitionConflict>,
+    ) -> MergeSummary {
+        self.skip_used_ids();
+        let mut summary = MergeSummary::default();
+        let replaces = |kind, name: &str| {
+            replace.contains(&DefinitionConflict {
+                kind,
+                name: name.to_string(),
+            })
+        };
+
+        let mut materials = HashMap::new();
+        for id in sorted_ids(&incoming.materials) {
+            let mut material = incoming.materials[&id].clone();
+            let own = match find_by_name(&self.materials, &material.name, |m| &m.name) {
+                Some(own) if replaces(DefinitionKind::Material, &material.name) => {
+                    material.id = own;
+                    self.materials.insert(own, material);
+                    summary.replaced += 1;
+                    own
+                }
+                Some(own) => {
+                    summary.kept += 1;
+                    own
+                }
+                None => {
+                    material.id = 0;
+                    summary.added += 1;
+                    self.add_material(material)
+                }
+            };
+            materials.insert(id, own);
+        }
+        let remap = |id: u64| materials.get(&id).copied().unwrap_or(0);
+
+        for id in sorted_ids(&incoming.column_types) {
+            let mut column = incoming.column_types[&id].clone();
+            column.concrete_material_id = remap(column.concrete_material_id);
+            column.long_rebar_material_id = remap(column.long_rebar_material_id);
+            column.tie_material_id = remap(column.tie_material_id);
+            match find_by_name(&self.column_types, &column.name, |c| &c.name) {
+                Some(own) if replaces(DefinitionKind::ColumnType, &column.name) => {
+                    column.id = own;
+                    self.column_types.insert(own, column);
+                    summary.replaced += 1;
+                }
+                Some(_) => summary.kept += 1,
+                None => {
+                    column.id = 0;
+                    self.add_column_type(column);
+                    summary.added += 1;
+                }
+            }
+        }
+
+        for id in sorted_ids(&incoming.beam_types) {
+            let mut beam = incoming.beam_types[&id].clone();
+            beam.concrete_material_id = remap(beam.concrete_material_id);
+            beam.steel_material_id = remap(beam.steel_material_id);
+            match find_by_name(&self.beam_types, &beam.name, |b| &b.name) {
+                Some(own) if replaces(DefinitionKind::BeamType, &beam.name) => {
+                    beam.id = own;
+                    self.beam_types.insert(own, beam);
+                    summary.replaced += 1;
+                }
+                Some(_) => summary.kept += 1,
+                None => {
+                    beam.id = 0;
+                    self.add_beam_type(beam);
+                    summary.added += 1;
+                }
+            }
+        }
+        summary
+    }
+}
@@ -10,7 +10,7 @@ pub enum MaterialCategory {
 }
 
 /// Detailed properties for specific material types.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum MaterialProperties {
     /// Concrete properties (e.g., C20, C30)
     Concrete {
@@ -29,7 +29,7 @@ pub enum MaterialProperties {
 }
 
 /// A material definition that can be assigned to structural elements.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Material {
     pub id: u64,
     pub name: String,
@@ -1,6 +1,13 @@
+pub mod attachment;
 pub mod beam;
 pub mod beam_type;
 pub mod column;
 pub mod column_type;
 pub mod definitions;
+pub mod flooring;
+pub mod junction;
+pub mod library;
 pub mod material;
+pub mod section;
+pub mod stair;
+pub mod storey;
@@ -0,0 +1,151 @@
+//! Schematic sections through the structure.
+//!
+//! The section is an elevation seen from the mark's viewing side: distance
+//! along the cut runs across, height runs up from the ground line at zero.
+//! Columns crossed by the cut stand one storey tall, beams crossed by it
+//! show their section under the storey line, and beams lying in the cut
+//! show as bands along it.
+
+use crate::model::shapes::annotation::TextAnnotation;
+use crate::model::{CadModel, Entity, SectionMark, Shape, Vector2};
+use glam::Affine2;
+
+/// Below this sine of the angle between a beam and the cut, the beam is
+/// taken to run along the cut
+const PARALLEL_SINE: f32 = 0.05;
+
+fn cross(a: Vector2, b: Vector2) -> f32 {
+    a.x * b.y - a.y * b.x
+}
+
+fn transform(m: &Affine2, p: Vector2) -> Vector2 {
+    m.transform_point2(p.into()).into()
+}
+
+fn visit(entities: &[Entity], f: &mut impl FnMut(&Entity)) {
+    for entity in entities {
+        f(entity);
+        visit(&entity.children, f);
+    }
+}
+
+fn named(mut entity: Entity, name: &str) -> Entity {
+    if !name.is_empty() {
+        entity.name = name.to_string();
+    }
+    entity
+}
+
+/// Entities of the schematic section through `mark`, whose cut is given in
+/// world coordinates
+pub fn section_entities(model: &CadModel, mark: &SectionMark, storey_height: f32) -> Vec<Entity> {
+    let length = mark.start.dist(mark.end);
+    if length <= f32::EPSILON {
+        return Vec::new();
+    }
+    let cut = mark.end - mark.start;
+    // Seen from the viewing side, `start` is on the left unless flipped
+    let x_at = |t: f32| {
+        if mark.flipped {
+            (1.0 - t) * length
+        } else {
+            t * length
+        }
+    };
+    let band = |t0: f32, t1: f32, bottom: f32, top: f32, filled: bool| {
+        let (x0, x1) = (x_at(t0), x_at(t1));
+        Entity::rectangle(
+            Vector2::new(x0.min(x1), bottom),
+            Vector2::new(x0.max(x1), top),
+            filled,
+        )
+    };
+
+    let mut entities = Vec::new();
+    let mut labels = Vec::new();
+    visit(&model.entities, &mut |entity| match &entity.shape {
+        Shape::Column(col) => {
+            let inverse = entity.world_transform.inverse();
+            let span = col.segment_span(
+                transform(&inverse, mark.start),
+                transform(&inverse, mark.end),
+            );
+            if let Some((enter, exit)) = span
+                && exit > enter
+            {
+                entities.push(named(
+                    band(enter, exit, 0.0, storey_height, false),
+                    &col.label,
+                ));
+                let top = Vector2::new(x_at((enter + exit) / 2.0), storey_height * 1.05);
+                labels.push((top, col.label.clone()));
+            }
+        }
+        Shape::Beam(beam) => {
+            let Some(beam_type) = model.definitions.get_beam_type(beam.beam_type_id) else {
+                return;
+            };
+            let a = transform(&entity.world_transform, beam.start);
+            let b = transform(&entity.world_transform, beam.end);
+            let run = b - a;
+            if run.length() <= f32::EPSILON {
+                return;
+            }
+            let bottom = storey_height - beam_type.height;
+            let sine = cross(cut, run) / (length * run.length());
+
+            if sine.abs() < PARALLEL_SINE {
+                // Along the cut: a band where the beam overlaps it
+                let off_cut = cross(cut, a - mark.start).abs() / length;
+                if off_cut > beam_type.width / 2.0 {
+                    return;
+                }
+                let ta = (a - mark.start).dot(cut) / (length * length);
+                let tb = (b - mark.start).dot(cut) / (length * length);
+                let (t0, t1) = (ta.min(tb).max(0.0), ta.max(tb).min(1.0));
+                if t1 > t0 {
+                    entities.push(named(
+                        band(t0, t1, bottom, storey_height, false),
+                        &beam.label,
+                    ));
+                }
+                return;
+            }
+
+            // Across the cut: the beam's section where the two cross
+            let denom = cross(cut, run);
+            let t = cross(a - mark.start, run) / denom;
+            let s = cross(a - mark.start, cut) / denom;
+            if !(0.0..=1.0).contains(&t) || !(0.0..=1.0).contains(&s) {
+                return;
+            }
+            let half = beam_type.width / sine.abs() / length / 2.0;
+            entities.push(named(
+                band(t - half, t + half, bottom, storey_height, true),
+                &beam.label,
+            ));
+        }
+        _ => {}
+    });
+
+    // Ground line, running a little past the cut
+    let margin = length * 0.05;
+    entities.push(named(
+        Entity::line(
+            Vector2::new(-margin, 0.0),
+            Vector2::new(length + margin, 0.0),
+        ),
+        "Ground",
+    ));
+    for (position, label) in labels {
+        if !label.is_empty() {
+            entities.push(Entity::text(TextAnnotation::new_custom(position, label)));
+        }
+    }
+    let title = Vector2::new(length / 2.0, -storey_height * 0.15);
+    entities.push(Entity::text(TextAnnotation::new_custom(
+        title,
+        mark.title(),
+    )));
+    entities
+}
@@ -0,0 +1,154 @@
+//! Straight stair flights.
+//!
+//! A flight starts at the middle of its first riser and climbs along
+//! `direction`. With `risers` risers there is one tread fewer, so the
+//! flight's run is `(risers - 1) * tread`.
+
+use crate::model::Vector2;
+use crate::model::shapes::Geometry;
+use crate::model::snap::SnapPointType;
+use serde::{Deserialize, Serialize};
+
+/// Highest riser a placed stair may have; the riser count is the smallest
+/// that keeps risers at or under this
+pub const MAX_RISER_HEIGHT: f32 = 17.5;
+
+/// A straight stair flight
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StairData {
+    /// Middle of the first riser
+    pub start: Vector2,
+    /// Unit vector pointing up the flight
+    pub direction: Vector2,
+    /// Flight width
+    pub width: f32,
+    pub risers: u32,
+    /// Going of one tread
+    pub tread: f32,
+    pub riser_height: f32,
+    /// Storey the stair belongs to; 0 for none
+    #[serde(default)]
+    pub storey_id: u64,
+}
+
+impl StairData {
+    pub fn new(
+        start: Vector2,
+        direction: Vector2,
+        width: f32,
+        risers: u32,
+        tread: f32,
+        riser_height: f32,
+    ) -> Self {
+        Self {
+            start,
+            direction: direction.normalized(),
+            width,
+            risers,
+            tread,
+            riser_height,
+            storey_id: 0,
+        }
+    }
+
+    /// A flight climbing `rise` over the run from `start` to `end`, with
+    /// as few risers as [`MAX_RISER_HEIGHT`] allows
+    pub fn spanning(start: Vector2, end: Vector2, width: f32, rise: f32) -> Self {
+        let risers = ((rise / MAX_RISER_HEIGHT).ceil() as u32).max(2);
+        let run = start.dist(end);
+        Self::new(
+            start,
+            end - start,
+            width,
+            risers,
+            run / (risers - 1) as f32,
+            rise / risers as f32,
+        )
+    }
+
+    pub fn treads(&self) -> u32 {
+        self.risers.saturating_sub(1)
+    }
+
+    /// Horizontal length of the flight
+    pub fn total_run(&self) -> f32 {
+        self.treads() as f32 * self.tread
+    }
+
+    /// Height climbed by the flight
+    pub fn total_rise(&self) -> f32 {
+        self.risers as f32 * self.riser_height
+    }
+
+    /// Unit vector to the left of the climbing direction
+    pub fn across(&self) -> Vector2 {
+        Vector2::new(-self.direction.y, self.direction.x)
+    }
+
+    /// Middle of the last riser
+    pub fn end(&self) -> Vector2 {
+        self.start + self.direction * self.total_run()
+    }
+
+    /// Right and left ends of the `i`th riser line, from the first (0) to
+    /// the last
+    pub fn riser_line(&self, i: u32) -> (Vector2, Vector2) {
+        let middle = self.start + self.direction * (i as f32 * self.tread);
+        let half = self.across() * (self.width / 2.0);
+        (middle - half, middle + half)
+    }
+
+    /// Outline corners, counter-clockwise from the right end of the first
+    /// riser
+    pub fn corners(&self) -> [Vector2; 4] {
+        let (first_right, first_left) = self.riser_line(0);
+        let (last_right, last_left) = self.riser_line(self.treads());
+        [first_right, last_right, last_left, first_left]
+    }
+}
+
+impl Geometry for StairData {
+    fn hit_test(&self, pos: Vector2, tolerance: f32) -> bool {
+        let corners = self.corners();
+        (0..4).any(|i| pos.dist_to_line(corners[i], corners[(i + 1) % 4]) < tolerance)
+    }
+
+    fn bounding_box(&self) -> (Vector2, Vector2) {
+        let corners = self.corners();
+        let min = corners
+            .iter()
+            .fold(Vector2::new(f32::MAX, f32::MAX), |m, p| {
+                Vector2::new(m.x.min(p.x), m.y.min(p.y))
+            });
+        let max = corners
+            .iter()
+            .fold(Vector2::new(f32::MIN, f32::MIN), |m, p| {
+                Vector2::new(m.x.max(p.x), m.y.max(p.y))
+            });
+        (min, max)
+    }
+
+    fn as_polyline(&self) -> Vec<Vector2> {
+        self.corners().to_vec()
+    }
+
+    fn is_closed(&self) -> bool {
+        true
+    }
+
+    fn is_filled(&self) -> bool {
+        false
+    }
+
+    /// Corners, and the middle of the first and last step
+    fn snap_points(&self) -> Vec<(SnapPointType, Vector2)> {
+        let mut points: Vec<_> = self
+            .corners()
+            .into_iter()
+            .map(|p| (SnapPointType::Corner, p))
+            .collect();
+        points.push((SnapPointType::Midpoint, self.start));
+        points.push((SnapPointType::Midpoint, self.end()));
+        points
+    }
+}
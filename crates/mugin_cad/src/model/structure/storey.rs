@@ -0,0 +1,194 @@
+//! Building levels.
+//!
+//! Columns and beams carry the ID of the storey they belong to; 0 means
+//! none. While a storey is active, structural members of other storeys are
+//! ghosted and cannot be picked.
+
+use crate::model::structure::beam::BeamAttachment;
+use crate::model::{CadModel, Entity, Shape};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// One level of the building
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Storey {
+    pub id: u64,
+    pub name: String,
+    /// Floor level
+    pub elevation: f32,
+    /// Floor-to-floor height
+    pub height: f32,
+}
+
+/// The building's storeys, lowest first, and the one being worked on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StoreyManager {
+    pub storeys: Vec<Storey>,
+    /// Storey shown and edited; `None` shows every storey
+    pub active: Option<u64>,
+    next_id: u64,
+}
+
+impl Default for StoreyManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StoreyManager {
+    pub fn new() -> Self {
+        Self {
+            storeys: Vec::new(),
+            active: None,
+            next_id: 1,
+        }
+    }
+
+    /// Add a storey on top of the highest one and return its ID
+    pub fn add(&mut self, name: impl Into<String>, height: f32) -> u64 {
+        let elevation = self
+            .storeys
+            .iter()
+            .map(|s| s.elevation + s.height)
+            .fold(0.0, f32::max);
+        self.insert(Storey {
+            id: 0,
+            name: name.into(),
+            elevation,
+            height,
+        })
+    }
+
+    /// Insert a storey under a fresh ID, keeping the list sorted by elevation
+    pub fn insert(&mut self, mut storey: Storey) -> u64 {
+        self.next_id = self
+            .next_id
+            .max(self.storeys.iter().map(|s| s.id + 1).max().unwrap_or(1));
+        storey.id = self.next_id;
+        self.next_id += 1;
+        let id = storey.id;
+        self.storeys.push(storey);
+        self.sort();
+        id
+    }
+
+    /// Keep the list sorted after elevations were edited
+    pub fn sort(&mut self) {
+        self.storeys
+            .sort_by(|a, b| a.elevation.total_cmp(&b.elevation));
+    }
+
+    /// Remove a storey. Members on it become unassigned through
+    /// `CadModel::remove_storey`.
+    fn remove(&mut self, id: u64) {
+        self.storeys.retain(|s| s.id != id);
+        if self.active == Some(id) {
+            self.active = None;
+        }
+    }
+
+    pub fn get(&self, id: u64) -> Option<&Storey> {
+        self.storeys.iter().find(|s| s.id == id)
+    }
+
+    /// Name of a storey, or "—" for unassigned members
+    pub fn name_of(&self, id: u64) -> &str {
+        self.get(id).map_or("—", |s| s.name.as_str())
+    }
+
+    /// ID new members are placed on
+    pub fn active_id(&self) -> u64 {
+        self.active.unwrap_or(0)
+    }
+
+    /// Whether members of `storey_id` are shown normally and can be picked
+    pub fn is_editable(&self, storey_id: u64) -> bool {
+        storey_id == 0 || self.active.is_none_or(|active| active == storey_id)
+    }
+}
+
+impl Shape {
+    /// Storey of a structural member
+    pub fn storey_id(&self) -> Option<u64> {
+        match self {
+            Shape::Column(col) => Some(col.storey_id),
+            Shape::Beam(beam) => Some(beam.storey_id),
+            Shape::Stair(stair) => Some(stair.storey_id),
+            Shape::Flooring(floor) => Some(floor.storey_id),
+            _ => None,
+        }
+    }
+
+    pub fn set_storey_id(&mut self, id: u64) {
+        match self {
+            Shape::Column(col) => col.storey_id = id,
+            Shape::Beam(beam) => beam.storey_id = id,
+            Shape::Stair(stair) => stair.storey_id = id,
+            Shape::Flooring(floor) => floor.storey_id = id,
+            _ => {}
+        }
+    }
+}
+
+impl Entity {
+    /// Whether the entity is on the active storey, or not on any storey
+    pub fn on_active_storey(&self, storeys: &StoreyManager) -> bool {
+        self.shape
+            .storey_id()
+            .is_none_or(|id| storeys.is_editable(id))
+    }
+}
+
+impl CadModel {
+    /// Remove a storey; its members stay in the drawing unassigned
+    pub fn remove_storey(&mut self, id: u64) {
+        self.storeys.remove(id);
+        let mut touched = Vec::new();
+        for entity in &mut self.entities {
+            if entity.shape.storey_id() == Some(id) {
+                entity.modify(|shape| shape.set_storey_id(0));
+                touched.push(entity.id);
+            }
+        }
+        for id in touched {
+            self.changes.touch(id);
+        }
+    }
+
+    /// Duplicate every structural member of storey `from` onto storey `to`,
+    /// with fresh IDs. Beams stay attached to the copies of their columns.
+    /// Returns the number of members copied.
+    pub fn copy_storey(&mut self, from: u64, to: u64) -> usize {
+        let mut copies: Vec<(u64, Entity)> = self
+            .entities
+            .iter()
+            .filter(|e| e.shape.storey_id() == Some(from))
+            .map(|e| (e.id, e.duplicate()))
+            .collect();
+        let new_ids: HashMap<u64, u64> = copies.iter().map(|(old, copy)| (*old, copy.id)).collect();
+        let remap = |attachment: &mut Option<BeamAttachment>| {
+            *attachment = attachment.and_then(|a| {
+                Some(BeamAttachment {
+                    column_id: *new_ids.get(&a.column_id)?,
+                    ..a
+                })
+            });
+        };
+
+        for (_, copy) in &mut copies {
+            // Numbered afresh by `add_entity`
+            copy.name = copy.type_name().to_string();
+            copy.shape.set_storey_id(to);
+            if let Shape::Beam(beam) = &mut copy.shape {
+                remap(&mut beam.start_attachment);
+                remap(&mut beam.end_attachment);
+            }
+        }
+        let count = copies.len();
+        for (_, copy) in copies {
+            self.add_entity(copy);
+        }
+        count
+    }
+}
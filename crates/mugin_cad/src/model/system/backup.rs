@@ -0,0 +1,124 @@
+//! Safe project saves with rotated backups.
+//!
+//! A save writes the new contents to a temporary file next to the project
+//! and renames it over the old one, so a crash mid-save leaves either the
+//! old or the new file intact. The version being replaced is kept as
+//! `<name>.mugin.bak1`, pushing older backups up to `.bak2`, `.bak3`, …
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Backups kept per project unless configured otherwise
+pub const DEFAULT_BACKUP_COUNT: usize = 3;
+
+/// A backup of a project file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Backup {
+    /// 1 for the newest
+    pub index: usize,
+    pub path: PathBuf,
+    /// When the backed-up version was written
+    pub modified: SystemTime,
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Temporary file a new version of `path` is written to before it is
+/// renamed into place, e.g. `plan.mugin.tmp`
+pub fn temp_path(path: &Path) -> PathBuf {
+    with_suffix(path, ".tmp")
+}
+
+/// Path of backup `index` of `path`, e.g. `plan.mugin.bak2`
+pub fn backup_path(path: &Path, index: usize) -> PathBuf {
+    with_suffix(path, &format!(".bak{}", index))
+}
+
+/// Write `contents` to `path` through a temporary file, first keeping the
+/// current file as the newest of `keep` backups. With `keep` 0 the current
+/// file is replaced without a backup.
+pub fn write_with_backups(path: &Path, contents: &[u8], keep: usize) -> std::io::Result<()> {
+    let temp = temp_path(path);
+    let result = fs::write(&temp, contents)
+        .and_then(|()| {
+            if keep > 0 && path.exists() {
+                keep_backup(path, keep)
+            } else {
+                Ok(())
+            }
+        })
+        .and_then(|()| fs::rename(&temp, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
+
+/// Link the current `path` in as backup 1, moving older backups up. The
+/// project file itself stays where it is until the new version replaces it.
+fn keep_backup(path: &Path, keep: usize) -> std::io::Result<()> {
+    // Oldest first, so nothing is overwritten before it has moved on
+    for index in (1..keep).rev() {
+        let older = backup_path(path, index);
+        if older.exists() {
+            fs::rename(&older, backup_path(path, index + 1))?;
+        }
+    }
+
+    let newest = backup_path(path, 1);
+    // Only still there when a single backup is kept
+    let _ = fs::remove_file(&newest);
+    // A hard link keeps the backup's modified time; copy where links are
+    // not supported
+    if fs::hard_link(path, &newest).is_err() {
+        fs::copy(path, &newest)?;
+    }
+    Ok(())
+}
+
+/// Backups of `path` on disk, newest first
+pub fn list_backups(path: &Path) -> Vec<Backup> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let Some(name) = path.file_name() else {
+        return Vec::new();
+    };
+    let prefix = format!("{}.bak", name.to_string_lossy());
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut backups: Vec<Backup> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            let index = name.to_str()?.strip_prefix(&prefix)?.parse().ok()?;
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            Some(Backup {
+                index,
+                path: backup_path(path, index),
+                modified,
+            })
+        })
+        .collect();
+    backups.sort_by_key(|backup| backup.index);
+    backups
+}
+
+/// Rough age for display, e.g. "5 min ago"
+pub fn age_label(age: Duration) -> String {
+    let secs = age.as_secs();
+    match secs {
+        0..60 => "just now".to_string(),
+        60..3600 => format!("{} min ago", secs / 60),
+        3600..86400 => format!("{} h ago", secs / 3600),
+        _ => format!("{} days ago", secs / 86400),
+    }
+}
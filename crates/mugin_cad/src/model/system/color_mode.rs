@@ -0,0 +1,72 @@
+//! Temporary recoloring of the drawing for auditing.
+//!
+//! The mode only changes the colors entities are drawn in; their own colors
+//! are left alone.
+
+use crate::model::Shape;
+use crate::model::layer::{DEFAULT_LAYER_COLOR, Layer};
+use ecolor::Color32;
+use serde::{Deserialize, Serialize};
+
+/// How entities are colored on screen
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ColorMode {
+    /// Each entity's own color
+    #[default]
+    Normal,
+    /// Every entity in its layer's color
+    ByLayer,
+    /// Every shape type in its own hue, see [`TYPE_PALETTE`]
+    ByType,
+}
+
+/// Shape types with their hue under [`ColorMode::ByType`], in legend order
+pub const TYPE_PALETTE: [(&str, Color32); 11] = [
+    ("Line", Color32::from_rgb(230, 90, 80)),
+    ("Circle", Color32::from_rgb(240, 160, 60)),
+    ("Rectangle", Color32::from_rgb(230, 210, 70)),
+    ("Arc", Color32::from_rgb(150, 210, 80)),
+    ("Text", Color32::from_rgb(80, 200, 120)),
+    ("Column", Color32::from_rgb(70, 200, 200)),
+    ("Beam", Color32::from_rgb(80, 150, 240)),
+    ("Image", Color32::from_rgb(140, 110, 230)),
+    ("Section", Color32::from_rgb(200, 100, 220)),
+    ("Stair", Color32::from_rgb(230, 110, 170)),
+    ("Flooring", Color32::from_rgb(170, 140, 100)),
+];
+
+impl ColorMode {
+    pub fn label(self) -> &'static str {
+        match self {
+            ColorMode::Normal => "Normal",
+            ColorMode::ByLayer => "Color by Layer",
+            ColorMode::ByType => "Color by Type",
+        }
+    }
+
+    /// The mode after this one, wrapping around
+    pub fn next(self) -> Self {
+        match self {
+            ColorMode::Normal => ColorMode::ByLayer,
+            ColorMode::ByLayer => ColorMode::ByType,
+            ColorMode::ByType => ColorMode::Normal,
+        }
+    }
+
+    /// Color `shape` on `layer` is drawn in, or `None` to keep its own
+    pub fn color(self, shape: &Shape, layer: Option<&Layer>) -> Option<Color32> {
+        match self {
+            ColorMode::Normal => None,
+            ColorMode::ByLayer => Some(layer.map_or(DEFAULT_LAYER_COLOR, |l| l.color)),
+            ColorMode::ByType => type_color(shape.type_name()),
+        }
+    }
+}
+
+/// Hue of a shape type under [`ColorMode::ByType`]
+pub fn type_color(type_name: &str) -> Option<Color32> {
+    TYPE_PALETTE
+        .iter()
+        .find(|(name, _)| *name == type_name)
+        .map(|(_, color)| *color)
+}
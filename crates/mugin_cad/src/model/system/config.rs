@@ -1,6 +1,10 @@
+use crate::model::backup::DEFAULT_BACKUP_COUNT;
+use crate::model::dimension::DimensionStyle;
+use crate::model::input_history::HistoryScope;
+use crate::model::tools::degenerate::GeometryLimits;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AppConfig {
     /// Snap configuration
     pub snap_config: SnapConfig,
@@ -10,31 +14,154 @@ pub struct AppConfig {
     pub appearance_config: AppearanceConfig,
     /// GUI configuration
     pub gui_config: GuiConfig,
+    /// Dimension and length label style
+    #[serde(default)]
+    pub dimension_style: DimensionStyle,
+    /// Smallest geometry commands and the inspector accept
+    #[serde(default)]
+    pub geometry_limits: GeometryLimits,
+}
+
+// ... existing structs ...
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuiConfig {
+    /// Always show inspector panel regardless of selection
+    pub show_inspector_always: bool,
+    /// Show entity info tooltips when hovering on the canvas
+    #[serde(default = "default_true")]
+    pub show_hover_tooltips: bool,
+    /// Toolbar layout (order and visibility). Empty means the default layout.
+    #[serde(default)]
+    pub toolbar: Vec<ToolbarEntry>,
+    /// Let the view glide briefly after a middle-button pan is released
+    #[serde(default)]
+    pub pan_inertia: bool,
+    /// Whether typed command history is shared or kept per project
+    #[serde(default)]
+    pub history_scope: HistoryScope,
+    /// Side panel sizes and visibility
+    #[serde(default)]
+    pub panels: PanelLayout,
+    /// Show quick edit buttons above the selection on the canvas
+    #[serde(default = "default_true")]
+    pub show_selection_toolbar: bool,
+    /// Key bindings of the quick rotate and flip actions
+    #[serde(default)]
+    pub quick_transform_keys: QuickTransformKeys,
+    /// Quick rotations turn text too instead of keeping it upright
+    #[serde(default)]
+    pub rotate_text: bool,
+    /// Layer template file applied to every new tab. Empty means none.
+    #[serde(default)]
+    pub default_layer_template: String,
+    /// Copy the structure library's types into every new tab
+    #[serde(default)]
+    pub seed_structure_library: bool,
+    /// Previous versions kept as `.bak1`, `.bak2`, … when a project is saved
+    #[serde(default = "default_backup_count")]
+    pub backup_count: usize,
+    /// Redraw every frame instead of only on input and running animations,
+    /// for displays that show artifacts when the app goes idle
+    #[serde(default)]
+    pub continuous_repaint: bool,
+    /// Fit the drawing into view when a project opens instead of going
+    /// back to the view it was saved with
+    #[serde(default)]
+    pub zoom_extents_on_open: bool,
 }
 
-impl Default for AppConfig {
+/// Key bindings of the quick rotate and flip actions, e.g. "Ctrl+Shift+R".
+/// An empty binding disables the shortcut.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuickTransformKeys {
+    pub rotate_cw: String,
+    pub rotate_ccw: String,
+    pub flip_horizontal: String,
+    pub flip_vertical: String,
+}
+
+impl Default for QuickTransformKeys {
     fn default() -> Self {
         Self {
-            snap_config: SnapConfig::default(),
-            grid_config: GridConfig::default(),
-            appearance_config: AppearanceConfig::default(),
-            gui_config: GuiConfig::default(),
+            rotate_cw: "Ctrl+Shift+R".to_string(),
+            rotate_ccw: "Ctrl+Shift+L".to_string(),
+            flip_horizontal: "Ctrl+Shift+H".to_string(),
+            // Ctrl+Shift+V arrives as a paste event on some platforms
+            flip_vertical: "Ctrl+Shift+J".to_string(),
         }
     }
 }
 
-// ... existing structs ...
+/// Tab shown in the left panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LeftPanelTab {
+    #[default]
+    Hierarchy,
+    Layers,
+    References,
+}
 
+/// Side panel sizes and visibility
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PanelLayout {
+    pub show_left: bool,
+    pub left_collapsed: bool,
+    pub left_width: f32,
+    pub left_tab: LeftPanelTab,
+    pub show_right: bool,
+    pub right_collapsed: bool,
+    pub right_width: f32,
+}
+
+impl Default for PanelLayout {
+    fn default() -> Self {
+        Self {
+            show_left: true,
+            left_collapsed: false,
+            left_width: 250.0,
+            left_tab: LeftPanelTab::Hierarchy,
+            show_right: true,
+            right_collapsed: false,
+            right_width: 250.0,
+        }
+    }
+}
+
+/// One toolbar item in the user's layout, keyed by its spec id
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct GuiConfig {
-    /// Always show inspector panel regardless of selection
-    pub show_inspector_always: bool,
+pub struct ToolbarEntry {
+    pub id: String,
+    pub visible: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_backup_count() -> usize {
+    DEFAULT_BACKUP_COUNT
 }
 
 impl Default for GuiConfig {
     fn default() -> Self {
         Self {
             show_inspector_always: true,
+            show_hover_tooltips: true,
+            toolbar: Vec::new(),
+            pan_inertia: false,
+            history_scope: HistoryScope::Global,
+            panels: PanelLayout::default(),
+            show_selection_toolbar: true,
+            quick_transform_keys: QuickTransformKeys::default(),
+            rotate_text: false,
+            default_layer_template: String::new(),
+            seed_structure_library: false,
+            backup_count: DEFAULT_BACKUP_COUNT,
+            continuous_repaint: false,
+            zoom_extents_on_open: false,
         }
     }
 }
@@ -53,8 +180,31 @@ pub struct SnapConfig {
     pub snap_to_center: bool,
     /// Enable snapping to intersection
     pub snap_to_intersection: bool,
+    /// Enable snapping to where two lines would cross if extended
+    #[serde(default)]
+    pub snap_to_apparent_intersection: bool,
     /// Enable snapping to axis lines
     pub snap_to_axis: bool,
+    /// Enable snapping to end points and corners of referenced drawings
+    #[serde(default)]
+    pub snap_to_references: bool,
+    /// How close (in screen pixels) a click must be to pick an entity
+    #[serde(default = "default_pick_tolerance")]
+    pub pick_tolerance: f32,
+    /// Line up moved or copied entities with their neighbours
+    #[serde(default = "default_true")]
+    pub smart_guides: bool,
+}
+
+fn default_pick_tolerance() -> f32 {
+    5.0
+}
+
+impl SnapConfig {
+    /// Pick tolerance in world units at the given zoom
+    pub fn world_pick_tolerance(&self, zoom: f32) -> f32 {
+        self.pick_tolerance / zoom
+    }
 }
 
 impl Default for SnapConfig {
@@ -66,7 +216,11 @@ impl Default for SnapConfig {
             snap_to_midpoint: true,
             snap_to_center: true,
             snap_to_intersection: true,
+            snap_to_apparent_intersection: false,
             snap_to_axis: true,
+            snap_to_references: false,
+            pick_tolerance: default_pick_tolerance(),
+            smart_guides: true,
         }
     }
 }
@@ -100,6 +254,117 @@ pub struct AppearanceConfig {
     pub background_color: [u8; 3],
     /// Selection color (RGB)
     pub selection_color: [u8; 3],
+    /// Zoomed-out simplification thresholds
+    #[serde(default)]
+    pub detail: DetailThresholds,
+    /// Fill of flooring slabs
+    #[serde(default)]
+    pub flooring: FlooringStyle,
+    /// Cursor crosshair and pick box
+    #[serde(default)]
+    pub crosshair: CrosshairStyle,
+}
+
+/// Look of the cursor crosshair
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CrosshairStyle {
+    /// Lines span the whole canvas, ignoring `size_percent`
+    pub full_screen: bool,
+    /// Length of each line as a percentage of the canvas width or height
+    pub size_percent: f32,
+    pub color: [u8; 3],
+    /// 0 (invisible) to 1 (opaque)
+    pub opacity: f32,
+    /// Square at the cursor as large as the pick tolerance
+    pub show_pick_box: bool,
+}
+
+impl Default for CrosshairStyle {
+    fn default() -> Self {
+        Self {
+            full_screen: true,
+            size_percent: 5.0,
+            color: [200, 200, 200],
+            opacity: 0.4,
+            show_pick_box: true,
+        }
+    }
+}
+
+impl CrosshairStyle {
+    /// Half lengths of the horizontal and vertical lines on a canvas of
+    /// the given size
+    pub fn half_extent(&self, width: f32, height: f32) -> (f32, f32) {
+        if self.full_screen {
+            // Reaches both edges from anywhere on the canvas
+            return (width, height);
+        }
+        let fraction = self.size_percent.clamp(0.0, 100.0) / 100.0;
+        (width * fraction / 2.0, height * fraction / 2.0)
+    }
+}
+
+/// How flooring slabs are filled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FloorFill {
+    /// Light tint of the floor type's color
+    #[default]
+    Tint,
+    /// Diagonal lines in the floor type's color
+    Hatch,
+}
+
+/// Fill of flooring slabs, one color per floor type
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FlooringStyle {
+    pub fill: FloorFill,
+    /// Colors of floor types 1, 2, … in turn
+    pub colors: [[u8; 3]; 4],
+}
+
+impl Default for FlooringStyle {
+    fn default() -> Self {
+        Self {
+            fill: FloorFill::Tint,
+            colors: [
+                [120, 160, 220],
+                [220, 170, 110],
+                [130, 200, 140],
+                [200, 130, 190],
+            ],
+        }
+    }
+}
+
+impl FlooringStyle {
+    /// Color of a floor type
+    pub fn color(&self, floor_type_id: u64) -> [u8; 3] {
+        let index = floor_type_id.saturating_sub(1) as usize % self.colors.len();
+        self.colors[index]
+    }
+}
+
+/// On-screen sizes below which detail is dropped when zoomed out
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DetailThresholds {
+    /// Text, length labels and dimensions with a font smaller than this
+    /// many pixels are skipped
+    pub min_text_px: f32,
+    /// Columns and beams narrower than this many pixels are drawn as plain
+    /// filled shapes, without rebar or labels
+    pub min_structure_px: f32,
+}
+
+impl Default for DetailThresholds {
+    fn default() -> Self {
+        Self {
+            min_text_px: 4.0,
+            min_structure_px: 6.0,
+        }
+    }
 }
 
 impl Default for AppearanceConfig {
@@ -107,6 +372,9 @@ impl Default for AppearanceConfig {
         Self {
             background_color: [15, 15, 15],
             selection_color: [255, 215, 0], // Gold
+            detail: DetailThresholds::default(),
+            flooring: FlooringStyle::default(),
+            crosshair: CrosshairStyle::default(),
         }
     }
 }
@@ -0,0 +1,181 @@
+use crate::model::Vector2;
+use crate::model::math::angle::AngleFormat;
+use serde::{Deserialize, Serialize};
+
+/// Terminator drawn at both ends of a dimension line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum ArrowheadType {
+    /// Solid triangle pointing at the extension line
+    #[default]
+    FilledTriangle,
+    /// 45° architectural tick
+    Tick,
+    /// Filled dot
+    Dot,
+}
+
+impl ArrowheadType {
+    pub const ALL: [ArrowheadType; 3] = [
+        ArrowheadType::FilledTriangle,
+        ArrowheadType::Tick,
+        ArrowheadType::Dot,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ArrowheadType::FilledTriangle => "Filled Triangle",
+            ArrowheadType::Tick => "Tick",
+            ArrowheadType::Dot => "Dot",
+        }
+    }
+
+    /// Whether the outline is drawn as a filled polygon (otherwise a stroke)
+    pub fn is_filled(&self) -> bool {
+        !matches!(self, ArrowheadType::Tick)
+    }
+}
+
+/// Style shared by all dimensions and line length labels
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DimensionStyle {
+    /// Label text height in world units
+    pub text_height: f32,
+    /// Arrowhead type
+    pub arrowhead: ArrowheadType,
+    /// Arrowhead size in world units
+    pub arrow_size: f32,
+    /// How far extension lines run past the dimension line (world units)
+    pub ext_overshoot: f32,
+    /// Gap between the measured point and the extension line (world units)
+    pub ext_gap: f32,
+    /// Number of decimal places
+    pub precision: u8,
+    /// Unit suffix appended to the value (e.g. " cm")
+    pub suffix: String,
+    /// How angles are shown and typed
+    pub angles: AngleFormat,
+    /// Distance between stacked rows of dimensions, e.g. AUTODIM's
+    /// chain and overall rows (world units)
+    pub chain_spacing: f32,
+}
+
+impl Default for DimensionStyle {
+    fn default() -> Self {
+        Self {
+            text_height: 12.0,
+            arrowhead: ArrowheadType::FilledTriangle,
+            arrow_size: 6.0,
+            ext_overshoot: 5.0,
+            ext_gap: 2.0,
+            precision: 2,
+            suffix: String::new(),
+            angles: AngleFormat::default(),
+            chain_spacing: 40.0,
+        }
+    }
+}
+
+/// World-space geometry of a linear dimension
+pub struct DimensionLayout {
+    pub extension_lines: [[Vector2; 2]; 2],
+    pub dimension_line: [Vector2; 2],
+    /// Arrowhead outlines at the two ends of the dimension line
+    pub arrows: [Vec<Vector2>; 2],
+}
+
+impl DimensionStyle {
+    /// Format a measured value with the configured precision and suffix
+    pub fn format_length(&self, value: f32) -> String {
+        self.format_length_with(value, None, None)
+    }
+
+    /// Format a measured value, letting per-entity overrides win over the style
+    pub fn format_length_with(
+        &self,
+        value: f32,
+        precision: Option<u8>,
+        suffix: Option<&str>,
+    ) -> String {
+        let precision = precision.unwrap_or(self.precision) as usize;
+        let suffix = suffix.unwrap_or(&self.suffix);
+        format!("{:.*}{}", precision, value, suffix)
+    }
+
+    /// Arrowhead outline with its tip at `tip`, pointing along unit vector `dir`
+    pub fn arrowhead_outline(&self, tip: Vector2, dir: Vector2) -> Vec<Vector2> {
+        let size = self.arrow_size;
+        let normal = dir.perp();
+
+        match self.arrowhead {
+            ArrowheadType::FilledTriangle => {
+                let base = tip - dir * size;
+                vec![
+                    tip,
+                    base + normal * (size * 0.3),
+                    base - normal * (size * 0.3),
+                ]
+            }
+            ArrowheadType::Tick => {
+                let slash = (dir + normal).normalized() * (size * 0.5);
+                vec![tip - slash, tip + slash]
+            }
+            ArrowheadType::Dot => {
+                let radius = size * 0.3;
+                (0..12)
+                    .map(|i| {
+                        let a = i as f32 / 12.0 * std::f32::consts::TAU;
+                        Vector2::new(tip.x + radius * a.cos(), tip.y + radius * a.sin())
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Lay out a dimension measuring `p1`→`p2`, with the dimension line displaced
+    /// by `offset` and stretched to cover the label at `label_t ± label_half_width`
+    /// (distances along `p1`→`p2`). Returns `None` for degenerate input.
+    pub fn layout(
+        &self,
+        p1: Vector2,
+        p2: Vector2,
+        offset: Vector2,
+        label_t: f32,
+        label_half_width: f32,
+    ) -> Option<DimensionLayout> {
+        let d = p2 - p1;
+        let len = d.length();
+        if len < 0.001 {
+            return None;
+        }
+        let u = d / len;
+
+        let dim_p1 = p1 + offset;
+        let dim_p2 = p2 + offset;
+
+        let extension_lines = if offset.length_squared() > 1e-6 {
+            let dir = offset.normalized();
+            // Never let the gap swallow the whole extension line
+            let gap = self.ext_gap.min(offset.length());
+            let overshoot = dir * self.ext_overshoot;
+            [
+                [p1 + dir * gap, dim_p1 + overshoot],
+                [p2 + dir * gap, dim_p2 + overshoot],
+            ]
+        } else {
+            [[dim_p1, dim_p1], [dim_p2, dim_p2]]
+        };
+
+        let t_min = 0.0f32.min(label_t - label_half_width);
+        let t_max = len.max(label_t + label_half_width);
+
+        Some(DimensionLayout {
+            extension_lines,
+            dimension_line: [dim_p1 + u * t_min, dim_p1 + u * t_max],
+            arrows: [
+                self.arrowhead_outline(dim_p1, -u),
+                self.arrowhead_outline(dim_p2, u),
+            ],
+        })
+    }
+}
@@ -0,0 +1,42 @@
+use crate::model::structure::column::ColumnAnchor;
+use serde::{Deserialize, Serialize};
+
+/// Attributes applied to newly created entities. Stored per project.
+///
+/// The current layer is not duplicated here — it is the layer manager's
+/// active layer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DraftingDefaults {
+    /// Entity color (RGB); `None` means "by layer"
+    pub color: Option<[u8; 3]>,
+    /// Create closed shapes (circle, rectangle, arc) filled
+    pub filled: bool,
+    /// Font size for new text annotations
+    pub text_font_size: f32,
+    /// Show length labels on new lines
+    pub show_line_labels: bool,
+    /// Anchor new columns are placed by
+    pub column_anchor: ColumnAnchor,
+    /// Floor-to-floor height, used when drawing sections
+    pub storey_height: f32,
+    /// Clean up beam junctions as beams are placed and edited
+    pub auto_cleanup: bool,
+    /// Drag the ends of lines shared with moved entities along
+    pub connected_move: bool,
+}
+
+impl Default for DraftingDefaults {
+    fn default() -> Self {
+        Self {
+            color: None,
+            filled: false,
+            text_font_size: 14.0,
+            show_line_labels: false,
+            column_anchor: ColumnAnchor::Center,
+            storey_height: 300.0,
+            auto_cleanup: false,
+            connected_move: false,
+        }
+    }
+}
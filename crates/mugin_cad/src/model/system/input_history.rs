@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// How many typed commands are kept on disk
+pub const MAX_INPUT_HISTORY: usize = 500;
+
+/// Where typed commands are remembered across restarts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum HistoryScope {
+    /// One history shared by all projects
+    #[default]
+    Global,
+    /// A history file next to each saved project
+    PerProject,
+}
+
+impl HistoryScope {
+    pub const ALL: [HistoryScope; 2] = [HistoryScope::Global, HistoryScope::PerProject];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            HistoryScope::Global => "Shared by all projects",
+            HistoryScope::PerProject => "Per project",
+        }
+    }
+}
+
+/// Commands typed into the terminal, oldest first
+#[derive(Debug, Clone, Default)]
+pub struct InputHistory {
+    pub entries: Vec<String>,
+}
+
+impl InputHistory {
+    /// Append a command, skipping it if it repeats the last one
+    pub fn push(&mut self, command: &str) {
+        let command = command.trim();
+        if command.is_empty() || self.entries.last().is_some_and(|last| last == command) {
+            return;
+        }
+        self.entries.push(command.to_string());
+        if self.entries.len() > MAX_INPUT_HISTORY {
+            let excess = self.entries.len() - MAX_INPUT_HISTORY;
+            self.entries.drain(..excess);
+        }
+    }
+
+    /// Newest entry at or before `before` containing `query` (case-insensitive).
+    /// Returns its index so a repeated search can continue further back.
+    pub fn search(&self, query: &str, before: usize) -> Option<usize> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        let query = query.to_lowercase();
+        let end = before.min(self.entries.len() - 1);
+        (0..=end)
+            .rev()
+            .find(|&i| self.entries[i].to_lowercase().contains(&query))
+    }
+
+    /// Read a history file: one command per line. Unreadable bytes and
+    /// blank lines are skipped, so a damaged file loses only those lines.
+    pub fn load(path: &Path) -> Self {
+        let mut history = Self::default();
+        if let Ok(bytes) = std::fs::read(path) {
+            for line in String::from_utf8_lossy(&bytes).lines() {
+                if !line.contains('\u{FFFD}') {
+                    history.push(line);
+                }
+            }
+        }
+        history
+    }
+
+    /// Write the history through a temporary file so a crash mid-write
+    /// leaves the previous file intact
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut temp = path.as_os_str().to_owned();
+        temp.push(".tmp");
+        let temp = PathBuf::from(temp);
+
+        let mut content = self.entries.join("\n");
+        content.push('\n');
+        std::fs::write(&temp, content)?;
+        std::fs::rename(&temp, path)
+    }
+}
+
+/// History file kept next to a project, e.g. `plan.mugin.history`
+pub fn project_history_path(project: &Path) -> PathBuf {
+    let mut path = project.as_os_str().to_owned();
+    path.push(".history");
+    PathBuf::from(path)
+}
@@ -1,2 +1,11 @@
+pub mod backup;
+pub mod color_mode;
 pub mod config;
+pub mod dimension;
+pub mod drafting;
+pub mod input_history;
 pub mod project;
+pub mod recent;
+pub mod recovery;
+pub mod symbols;
+pub mod template;
@@ -1,8 +1,14 @@
-use crate::model::Entity;
 use crate::model::axis::Axis;
-use crate::model::config::AppConfig;
+use crate::model::backup::write_with_backups;
+use crate::model::config::{AppConfig, LeftPanelTab};
+use crate::model::drafting::DraftingDefaults;
+use crate::model::layer::LayerManager;
 use crate::model::structure::definitions::StructureDefinitions;
+use crate::model::structure::storey::StoreyManager;
+use crate::model::tools::selection_sets::SelectionSets;
+use crate::model::{CadModel, Entity, Vector2};
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 
 #[derive(Serialize, Deserialize)]
 pub struct ProjectData {
@@ -11,6 +17,52 @@ pub struct ProjectData {
     pub axes: Vec<Axis>,
     pub config: AppConfig,
     pub definitions: StructureDefinitions,
+    /// Layer table; files from before it was saved get the default layer
+    #[serde(default)]
+    pub layers: LayerManager,
+    #[serde(default)]
+    pub drafting: DraftingDefaults,
+    #[serde(default)]
+    pub storeys: StoreyManager,
+    #[serde(default, skip_serializing_if = "SelectionSets::is_empty")]
+    pub selection_sets: SelectionSets,
+    /// Base64 PNG preview shown in the welcome screen's recent list
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thumbnail: Option<String>,
+    /// Where the user left the view; files from before it was saved have none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub view: Option<ViewState>,
+}
+
+/// View and panel state of a tab, restored when its project is opened
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ViewState {
+    /// Pan offset in screen pixels
+    pub offset: Vector2,
+    pub zoom: f32,
+    pub left_tab: LeftPanelTab,
+    /// Layer new entities are drawn on
+    pub active_layer: u64,
+}
+
+impl Default for ViewState {
+    fn default() -> Self {
+        Self {
+            offset: Vector2::new(0.0, 0.0),
+            zoom: 1.0,
+            left_tab: LeftPanelTab::default(),
+            active_layer: 0,
+        }
+    }
+}
+
+/// Just the thumbnail of a project file, to avoid keeping whole projects
+/// around when listing recent files
+#[derive(Deserialize)]
+pub struct ProjectThumbnail {
+    #[serde(default)]
+    pub thumbnail: Option<String>,
 }
 
 impl ProjectData {
@@ -19,6 +71,7 @@ impl ProjectData {
         axes: Vec<Axis>,
         config: AppConfig,
         definitions: StructureDefinitions,
+        drafting: DraftingDefaults,
     ) -> Self {
         Self {
             version: "1.0.0".to_string(),
@@ -26,6 +79,42 @@ impl ProjectData {
             axes,
             config,
             definitions,
+            layers: LayerManager::new(),
+            drafting,
+            storeys: StoreyManager::new(),
+            selection_sets: SelectionSets::default(),
+            thumbnail: None,
+            view: None,
         }
     }
+
+    /// Read and parse a project file
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+        serde_json::from_str(&content)
+            .map_err(|e| format!("{} is not a valid project file: {}", path.display(), e))
+    }
+
+    /// The drawing on its own, with transforms computed, e.g. to show,
+    /// print or compare it
+    pub fn into_model(self) -> CadModel {
+        let mut model = CadModel::new();
+        model.entities = self.entities;
+        model.axis_manager.axes = self.axes;
+        model.layer_manager = self.layers;
+        model.definitions = self.definitions;
+        model.storeys = self.storeys;
+        model.update_hierarchy();
+        model
+    }
+
+    /// Serialize and write the project to a file, keeping up to `backups`
+    /// previous versions of it
+    pub fn write(&self, path: &Path, backups: usize) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Could not serialize project: {}", e))?;
+        write_with_backups(path, json.as_bytes(), backups)
+            .map_err(|e| format!("Could not write {}: {}", path.display(), e))
+    }
 }
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// How many recent projects are remembered
+const MAX_RECENT: usize = 10;
+
+/// A project file opened or saved recently
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentProject {
+    pub path: PathBuf,
+    pub name: String,
+}
+
+/// Most-recently-used project list, newest first. Persisted with the app
+/// state rather than in any project.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecentProjects {
+    pub entries: Vec<RecentProject>,
+}
+
+impl RecentProjects {
+    /// Move `path` to the top of the list, adding it if needed
+    pub fn touch(&mut self, path: &Path) {
+        self.remove(path);
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("Untitled")
+            .to_string();
+        self.entries.insert(
+            0,
+            RecentProject {
+                path: path.to_path_buf(),
+                name,
+            },
+        );
+        self.entries.truncate(MAX_RECENT);
+    }
+
+    pub fn remove(&mut self, path: &Path) {
+        self.entries.retain(|entry| entry.path != path);
+    }
+}
@@ -0,0 +1,118 @@
+//! Recovery files written when the app crashes.
+//!
+//! When a frame panics, every open tab is written to the recovery
+//! directory as `<tab name>-crash.mugin` before the app goes down, and the
+//! panic message and backtrace are appended to `crash.log` there. The next
+//! start offers to reopen the files.
+
+use crate::model::project::ProjectData;
+use std::fs;
+use std::io::Write;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Ending of recovery file names
+pub const CRASH_SUFFIX: &str = "-crash.mugin";
+
+/// Panic messages and backtraces, inside the recovery directory
+pub const CRASH_LOG: &str = "crash.log";
+
+/// A tab saved when the app crashed
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrashFile {
+    pub path: PathBuf,
+    /// Name of the tab it was saved from
+    pub name: String,
+    /// When the crash happened
+    pub modified: SystemTime,
+}
+
+/// Free path for a recovery file of tab `name`: characters that cannot
+/// appear in file names are replaced, and a number is added when another
+/// tab of the same name was saved already
+pub fn crash_file_path(dir: &Path, name: &str) -> PathBuf {
+    let stem: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c => c,
+        })
+        .collect();
+    let stem = if stem.trim().is_empty() {
+        "Untitled".to_string()
+    } else {
+        stem
+    };
+    let mut path = dir.join(format!("{}{}", stem, CRASH_SUFFIX));
+    let mut number = 2;
+    while path.exists() {
+        path = dir.join(format!("{} ({}){}", stem, number, CRASH_SUFFIX));
+        number += 1;
+    }
+    path
+}
+
+/// Write the project `snapshot` builds as a recovery file of tab `name`.
+/// The model may be what panicked, so a snapshot or serialization that
+/// panics too is reported as an error instead of taking the app down.
+pub fn write_crash_file(
+    dir: &Path,
+    name: &str,
+    snapshot: impl FnOnce() -> ProjectData,
+) -> Result<PathBuf, String> {
+    let json = panic::catch_unwind(AssertUnwindSafe(|| serde_json::to_string(&snapshot())))
+        .map_err(|_| format!("Tab \"{}\" could not be read", name))?
+        .map_err(|e| format!("Tab \"{}\" could not be serialized: {}", name, e))?;
+    fs::create_dir_all(dir).map_err(|e| format!("Could not create {}: {}", dir.display(), e))?;
+    let path = crash_file_path(dir, name);
+    fs::write(&path, json).map_err(|e| format!("Could not write {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// Recovery files in `dir`, newest first
+pub fn list_crash_files(dir: &Path) -> Vec<CrashFile> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<CrashFile> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.file_name();
+            let name = file_name.to_str()?.strip_suffix(CRASH_SUFFIX)?.to_string();
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            Some(CrashFile {
+                path: entry.path(),
+                name,
+                modified,
+            })
+        })
+        .collect();
+    files.sort_by_key(|file| std::cmp::Reverse(file.modified));
+    files
+}
+
+/// Append a panic report to the crash log in `dir`
+pub fn log_panic(dir: &Path, report: &str) -> std::io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let mut log = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(CRASH_LOG))?;
+    writeln!(log, "=== Panic at {} s since the Unix epoch ===", seconds)?;
+    writeln!(log, "{}\n", report)
+}
+
+/// Log every panic to the crash log in `dir` with its backtrace, then
+/// report it as before. Only touches the file system.
+pub fn install_panic_hook(dir: PathBuf) {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let backtrace = std::backtrace::Backtrace::force_capture();
+        let _ = log_panic(&dir, &format!("{}\n{}", info, backtrace));
+        previous(info);
+    }));
+}
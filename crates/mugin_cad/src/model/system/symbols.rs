@@ -0,0 +1,273 @@
+//! Drawing symbols: north arrows, section bubbles, level markers and the
+//! like.
+//!
+//! A symbol is a set of shapes drawn around its insertion point at the
+//! origin. Placing one adds a group entity holding copies of them, so the
+//! symbol moves, rotates, scales and exports like any other group. Symbols
+//! imported from drawings are kept as JSON in the app's data directory.
+
+use crate::model::project::ProjectData;
+use crate::model::shapes::annotation::TextAnnotation;
+use crate::model::{Entity, Geometry, Shape, Vector2};
+use glam::Affine2;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Shapes placed together as one symbol
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymbolDefinition {
+    pub name: String,
+    /// Shapes around the insertion point at the origin
+    pub entities: Vec<Entity>,
+}
+
+impl SymbolDefinition {
+    pub fn new(name: impl Into<String>, entities: Vec<Entity>) -> Self {
+        Self {
+            name: name.into(),
+            entities,
+        }
+    }
+
+    /// Where and how a placed copy sits
+    fn placement(position: Vector2, rotation: f32) -> Affine2 {
+        Affine2::from_translation(position.into()) * Affine2::from_angle(rotation)
+    }
+
+    /// Group entity holding a copy of the symbol with its insertion point
+    /// on `position`, turned by `rotation` radians
+    pub fn instance(&self, position: Vector2, rotation: f32) -> Entity {
+        let mut group = Entity::empty(&self.name);
+        group.symbol = Some(self.name.clone());
+        group.children = self.entities.iter().map(Entity::duplicate).collect();
+        group.local_transform = Self::placement(position, rotation);
+        group.set_dirty();
+        group
+    }
+
+    /// Outlines of the symbol placed like `instance` would place it, each
+    /// with whether it is closed. Texts are left out; see `texts`.
+    pub fn outlines(&self, position: Vector2, rotation: f32) -> Vec<(Vec<Vector2>, bool)> {
+        let mut outlines = Vec::new();
+        let placement = Self::placement(position, rotation);
+        for entity in &self.entities {
+            collect_outlines(entity, placement, &mut outlines);
+        }
+        outlines
+    }
+
+    /// Texts of the symbol placed like `instance` would place them
+    pub fn texts(&self, position: Vector2, rotation: f32) -> Vec<(Vector2, &str)> {
+        let placement = Self::placement(position, rotation);
+        self.entities
+            .iter()
+            .filter_map(|entity| match &entity.shape {
+                Shape::Text(text) => {
+                    let transform = placement * entity.local_transform;
+                    Some((
+                        transform.transform_point2(text.position.into()).into(),
+                        text.text.as_str(),
+                    ))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Extent around the insertion point, unturned
+    pub fn bounds(&self) -> (Vector2, Vector2) {
+        let origin = Vector2::new(0.0, 0.0);
+        let outlines = self.outlines(origin, 0.0);
+        let texts = self.texts(origin, 0.0);
+        let points = outlines
+            .iter()
+            .flat_map(|(points, _)| points.iter().copied())
+            .chain(texts.iter().map(|(p, _)| *p));
+        points.fold((origin, origin), |(min, max), p| {
+            (
+                Vector2::new(min.x.min(p.x), min.y.min(p.y)),
+                Vector2::new(max.x.max(p.x), max.y.max(p.y)),
+            )
+        })
+    }
+}
+
+fn collect_outlines(entity: &Entity, parent: Affine2, out: &mut Vec<(Vec<Vector2>, bool)>) {
+    let transform = parent * entity.local_transform;
+    if !matches!(entity.shape, Shape::None | Shape::Text(_)) {
+        let points = entity
+            .shape
+            .as_polyline()
+            .into_iter()
+            .map(|p| transform.transform_point2(p.into()).into())
+            .collect();
+        out.push((points, entity.shape.is_closed()));
+    }
+    for child in &entity.children {
+        collect_outlines(child, transform, out);
+    }
+}
+
+/// Built-in symbols and those imported by the user. An imported symbol
+/// hides a built-in one of the same name.
+#[derive(Debug, Clone)]
+pub struct SymbolLibrary {
+    builtin: Vec<SymbolDefinition>,
+    pub imported: Vec<SymbolDefinition>,
+}
+
+impl Default for SymbolLibrary {
+    fn default() -> Self {
+        Self {
+            builtin: builtin_symbols(),
+            imported: Vec::new(),
+        }
+    }
+}
+
+impl SymbolLibrary {
+    /// Every symbol, built-in ones first
+    pub fn symbols(&self) -> impl Iterator<Item = &SymbolDefinition> {
+        self.builtin
+            .iter()
+            .filter(|symbol| !self.imported.iter().any(|i| i.name == symbol.name))
+            .chain(&self.imported)
+    }
+
+    /// Look a symbol up by name, ignoring case
+    pub fn get(&self, name: &str) -> Option<&SymbolDefinition> {
+        self.symbols()
+            .find(|symbol| symbol.name.eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Add imported symbols, replacing those with the same name
+    pub fn import(&mut self, symbols: Vec<SymbolDefinition>) {
+        for symbol in symbols {
+            self.imported.retain(|s| s.name != symbol.name);
+            self.imported.push(symbol);
+        }
+    }
+}
+
+/// Symbols in a drawing: each group at the top of the hierarchy becomes a
+/// symbol named after it, inserted at the center of its extent. A drawing
+/// without groups becomes a single symbol called `fallback_name`.
+pub fn symbols_from_project(project: &ProjectData, fallback_name: &str) -> Vec<SymbolDefinition> {
+    let groups: Vec<&Entity> = project
+        .entities
+        .iter()
+        .filter(|entity| matches!(entity.shape, Shape::None) && !entity.children.is_empty())
+        .collect();
+    if groups.is_empty() {
+        if project.entities.is_empty() {
+            return Vec::new();
+        }
+        let mut whole = Entity::empty(fallback_name);
+        whole.children = project.entities.clone();
+        return vec![symbol_from_group(whole)];
+    }
+    groups
+        .into_iter()
+        .map(|group| symbol_from_group(group.clone()))
+        .collect()
+}
+
+fn symbol_from_group(mut group: Entity) -> SymbolDefinition {
+    group.set_dirty();
+    group.update_transforms(Affine2::IDENTITY);
+    let (min, max) = group.bounding_box();
+    let center = (min + max) * 0.5;
+    let to_origin = Affine2::from_translation((-center).into()) * group.local_transform;
+    let entities = group
+        .children
+        .into_iter()
+        .map(|mut child| {
+            child.local_transform = to_origin * child.local_transform;
+            child.set_dirty();
+            child
+        })
+        .collect();
+    SymbolDefinition::new(group.name, entities)
+}
+
+/// Read the imported symbols; a missing file has none
+pub fn read_symbols(path: &Path) -> Result<Vec<SymbolDefinition>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("{} is not a valid symbol library: {}", path.display(), e))
+}
+
+/// Write the imported symbols, creating their directory if needed
+pub fn write_symbols(path: &Path, symbols: &[SymbolDefinition]) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Could not create {}: {}", dir.display(), e))?;
+    }
+    let json = serde_json::to_string_pretty(symbols)
+        .map_err(|e| format!("Could not serialize symbols: {}", e))?;
+    std::fs::write(path, json).map_err(|e| format!("Could not write {}: {}", path.display(), e))
+}
+
+// ─── Built-in symbols ───────────────────────────────────────────
+
+fn v(x: f32, y: f32) -> Vector2 {
+    Vector2::new(x, y)
+}
+
+/// Closed outline through `points`
+fn polygon(points: &[Vector2]) -> Vec<Entity> {
+    points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(&a, &b)| Entity::line(a, b))
+        .collect()
+}
+
+fn text(position: Vector2, content: &str) -> Entity {
+    Entity::text(TextAnnotation::new_custom(position, content.to_string()))
+}
+
+fn builtin_symbols() -> Vec<SymbolDefinition> {
+    let mut north = vec![Entity::circle(v(0.0, 0.0), 50.0, false)];
+    north.extend(polygon(&[
+        v(0.0, 60.0),
+        v(-20.0, -40.0),
+        v(0.0, -25.0),
+        v(20.0, -40.0),
+    ]));
+    north.push(text(v(0.0, 78.0), "N"));
+
+    let mut section = vec![
+        Entity::circle(v(0.0, 0.0), 30.0, false),
+        Entity::line(v(-30.0, 0.0), v(30.0, 0.0)),
+    ];
+    section.extend(polygon(&[v(30.0, 12.0), v(48.0, 0.0), v(30.0, -12.0)]));
+    section.push(text(v(0.0, 12.0), "A"));
+    section.push(text(v(0.0, -14.0), "1"));
+
+    // Triangle pointing at the elevation, around a numbered circle
+    let mut elevation = vec![Entity::circle(v(0.0, 0.0), 30.0, false)];
+    elevation.extend(polygon(&[v(-30.0, 0.0), v(0.0, 45.0), v(30.0, 0.0)]));
+    elevation.push(text(v(0.0, -10.0), "1"));
+
+    let mut level = polygon(&[v(0.0, 0.0), v(-15.0, 20.0), v(15.0, 20.0)]);
+    level.push(Entity::line(v(15.0, 20.0), v(90.0, 20.0)));
+    level.push(text(v(50.0, 32.0), "+0.00"));
+
+    let grid = vec![
+        Entity::circle(v(0.0, 0.0), 30.0, false),
+        text(v(0.0, 0.0), "A"),
+    ];
+
+    vec![
+        SymbolDefinition::new("North Arrow", north),
+        SymbolDefinition::new("Section Bubble", section),
+        SymbolDefinition::new("Elevation Marker", elevation),
+        SymbolDefinition::new("Level Marker", level),
+        SymbolDefinition::new("Grid Bubble", grid),
+    ]
+}
@@ -0,0 +1,157 @@
+use crate::model::axis::Axis;
+use crate::model::config::AppConfig;
+use crate::model::drafting::DraftingDefaults;
+use crate::model::layer::LayerManager;
+use crate::model::structure::definitions::StructureDefinitions;
+use crate::model::{CadModel, Entity};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// File extension of saved drawing templates
+pub const TEMPLATE_EXTENSION: &str = "mugintpl";
+
+/// Templates compiled into the binary
+const BUILTIN_TEMPLATES: &[&str] = &[
+    include_str!("templates/structural_grid.mugintpl"),
+    include_str!("templates/floor_plan.mugintpl"),
+];
+
+/// Starting point for a new drawing: everything a drawing brings along
+/// except its file and history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DrawingTemplate {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub entities: Vec<Entity>,
+    pub axes: Vec<Axis>,
+    pub layers: LayerManager,
+    pub definitions: StructureDefinitions,
+    #[serde(default)]
+    pub drafting: DraftingDefaults,
+    /// Settings the template overrides; `None` keeps the user's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<AppConfig>,
+}
+
+impl DrawingTemplate {
+    /// Capture a drawing, with the settings it was drawn under
+    pub fn from_model(
+        name: impl Into<String>,
+        model: &CadModel,
+        config: &AppConfig,
+        drafting: &DraftingDefaults,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            description: String::new(),
+            entities: model.entities.clone(),
+            axes: model.axis_manager.axes.clone(),
+            layers: model.layer_manager.clone(),
+            definitions: model.definitions.clone(),
+            drafting: drafting.clone(),
+            config: Some(config.clone()),
+        }
+    }
+
+    /// Read and parse a template file
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+        serde_json::from_str(&content)
+            .map_err(|e| format!("{} is not a valid template: {}", path.display(), e))
+    }
+
+    /// Serialize and write the template to a file
+    pub fn write(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Could not serialize template: {}", e))?;
+        std::fs::write(path, json).map_err(|e| format!("Could not write {}: {}", path.display(), e))
+    }
+
+    /// Replace the model's contents with the template's. Entities are deep
+    /// copies with fresh IDs, so drawings from one template never share IDs.
+    pub fn apply(&self, model: &mut CadModel) {
+        model.entities = self.entities.iter().map(Entity::duplicate).collect();
+        model.axis_manager.axes = self.axes.clone();
+        model.layer_manager = self.layers.clone();
+        model.definitions = self.definitions.clone();
+        model.changes.touch_all();
+        model.update_hierarchy();
+    }
+}
+
+// ── Template list ───────────────────────────────────────────
+
+/// Where a listed template is loaded from
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplateSource {
+    /// Index into the built-in templates
+    Builtin(usize),
+    File(PathBuf),
+}
+
+/// A template offered in the menus, without its contents
+#[derive(Debug, Clone)]
+pub struct TemplateEntry {
+    pub name: String,
+    pub description: String,
+    pub source: TemplateSource,
+}
+
+impl TemplateEntry {
+    pub fn load(&self) -> Result<DrawingTemplate, String> {
+        match &self.source {
+            TemplateSource::Builtin(index) => serde_json::from_str(BUILTIN_TEMPLATES[*index])
+                .map_err(|e| format!("Built-in template is invalid: {}", e)),
+            TemplateSource::File(path) => DrawingTemplate::read(path),
+        }
+    }
+}
+
+/// Just the header of a template file, for listing
+#[derive(Deserialize)]
+struct TemplateHeader {
+    name: String,
+    #[serde(default)]
+    description: String,
+}
+
+/// The built-in templates, then those saved in `dir` sorted by name.
+/// Files that are not valid templates are left out.
+pub fn available_templates(dir: Option<&Path>) -> Vec<TemplateEntry> {
+    let mut templates: Vec<TemplateEntry> = BUILTIN_TEMPLATES
+        .iter()
+        .enumerate()
+        .filter_map(|(index, json)| {
+            let header: TemplateHeader = serde_json::from_str(json).ok()?;
+            Some(TemplateEntry {
+                name: header.name,
+                description: header.description,
+                source: TemplateSource::Builtin(index),
+            })
+        })
+        .collect();
+
+    let Some(entries) = dir.and_then(|dir| std::fs::read_dir(dir).ok()) else {
+        return templates;
+    };
+    let mut saved: Vec<TemplateEntry> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(TEMPLATE_EXTENSION) {
+                return None;
+            }
+            let content = std::fs::read_to_string(&path).ok()?;
+            let header: TemplateHeader = serde_json::from_str(&content).ok()?;
+            Some(TemplateEntry {
+                name: header.name,
+                description: header.description,
+                source: TemplateSource::File(path),
+            })
+        })
+        .collect();
+    saved.sort_by_key(|entry| entry.name.to_lowercase());
+    templates.extend(saved);
+    templates
+}
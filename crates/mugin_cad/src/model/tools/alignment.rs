@@ -0,0 +1,90 @@
+//! Smart alignment guides: line up a moving bounding box with the edges and
+//! centers of nearby entities.
+
+use crate::model::Vector2;
+
+/// A guide line to draw, in world coordinates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AlignmentGuide {
+    pub from: Vector2,
+    pub to: Vector2,
+}
+
+/// Result of aligning a box: how far to shift it and the guides to show
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Alignment {
+    pub offset: Vector2,
+    pub guides: Vec<AlignmentGuide>,
+}
+
+/// Min, center and max along one axis
+fn stops(min: f32, max: f32) -> [f32; 3] {
+    [min, (min + max) * 0.5, max]
+}
+
+/// Smallest shift (within `threshold`) that puts one of `moving`'s stops on
+/// one of the candidates' stops
+fn best_shift(
+    moving: [f32; 3],
+    candidates: impl Iterator<Item = [f32; 3]>,
+    threshold: f32,
+) -> Option<f32> {
+    let mut best: Option<f32> = None;
+    for target in candidates {
+        for t in target {
+            for m in moving {
+                let shift = t - m;
+                if shift.abs() <= threshold && best.is_none_or(|b| shift.abs() < b.abs()) {
+                    best = Some(shift);
+                }
+            }
+        }
+    }
+    best
+}
+
+/// Align the `moving` box `(min, max)` to `candidates`, snapping each axis
+/// independently when a stop is within `threshold`
+pub fn align_bounds(
+    moving: (Vector2, Vector2),
+    candidates: &[(Vector2, Vector2)],
+    threshold: f32,
+) -> Alignment {
+    let (min, max) = moving;
+    let dx = best_shift(
+        stops(min.x, max.x),
+        candidates.iter().map(|(a, b)| stops(a.x, b.x)),
+        threshold,
+    );
+    let dy = best_shift(
+        stops(min.y, max.y),
+        candidates.iter().map(|(a, b)| stops(a.y, b.y)),
+        threshold,
+    );
+    let offset = Vector2::new(dx.unwrap_or(0.0), dy.unwrap_or(0.0));
+    let (min, max) = (min + offset, max + offset);
+
+    // A guide for every stop that now lines up, spanning both boxes
+    let eps = threshold * 0.01;
+    let mut guides = Vec::new();
+    for (c_min, c_max) in candidates {
+        for x in stops(min.x, max.x) {
+            if dx.is_some() && stops(c_min.x, c_max.x).iter().any(|t| (t - x).abs() <= eps) {
+                guides.push(AlignmentGuide {
+                    from: Vector2::new(x, min.y.min(c_min.y)),
+                    to: Vector2::new(x, max.y.max(c_max.y)),
+                });
+            }
+        }
+        for y in stops(min.y, max.y) {
+            if dy.is_some() && stops(c_min.y, c_max.y).iter().any(|t| (t - y).abs() <= eps) {
+                guides.push(AlignmentGuide {
+                    from: Vector2::new(min.x.min(c_min.x), y),
+                    to: Vector2::new(max.x.max(c_max.x), y),
+                });
+            }
+        }
+    }
+
+    Alignment { offset, guides }
+}
@@ -0,0 +1,107 @@
+//! The calculator palette's working state.
+//!
+//! Lines are expressions, or `name = expression` to keep a value under a
+//! name for later lines. `ans` is the last result.
+
+use crate::model::math::expression::{Expression, ExpressionError, evaluate};
+use std::collections::BTreeMap;
+
+/// Name the last result goes by
+pub const ANSWER: &str = "ans";
+
+/// One evaluated line
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalculatorLine {
+    pub input: String,
+    pub value: f64,
+    /// Name the value was assigned to, if any
+    pub assigned: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Calculator {
+    /// Line being typed
+    pub input: String,
+    /// Evaluated lines, oldest first
+    pub history: Vec<CalculatorLine>,
+    /// Why the last line could not be evaluated
+    pub error: Option<ExpressionError>,
+    variables: BTreeMap<String, f64>,
+    answer: Option<f64>,
+}
+
+impl Calculator {
+    /// Evaluate the typed line and clear it. On error the line stays for
+    /// fixing.
+    pub fn evaluate(&mut self) -> Result<f64, ExpressionError> {
+        let input = self.input.trim().to_string();
+        let (assigned, expression) = match input.split_once('=') {
+            Some((name, expression)) if is_name(name.trim()) => {
+                (Some(name.trim().to_string()), expression)
+            }
+            _ => (None, input.as_str()),
+        };
+        let result = evaluate(expression, &|name| self.value_of(name));
+        self.error = result.as_ref().err().cloned();
+        let value = result?;
+
+        if let Some(name) = &assigned {
+            self.variables.insert(name.clone(), value);
+        }
+        self.answer = Some(value);
+        self.history.push(CalculatorLine {
+            input,
+            value,
+            assigned,
+        });
+        self.input.clear();
+        Ok(value)
+    }
+
+    /// A named value or the last result
+    pub fn value_of(&self, name: &str) -> Option<f64> {
+        if name == ANSWER {
+            return self.answer;
+        }
+        self.variables.get(name).copied()
+    }
+
+    /// The last result
+    pub fn answer(&self) -> Option<f64> {
+        self.answer
+    }
+
+    /// Values kept under a name, by name
+    pub fn variables(&self) -> &BTreeMap<String, f64> {
+        &self.variables
+    }
+
+    /// Add a value at the end of the typed line, e.g. a picked distance
+    pub fn insert(&mut self, value: f64) {
+        let text = format_value(value);
+        let needs_space = self
+            .input
+            .chars()
+            .last()
+            .is_some_and(|c| c.is_alphanumeric() || c == '.' || c == ')');
+        if needs_space {
+            self.input.push(' ');
+        }
+        self.input.push_str(&text);
+    }
+}
+
+/// Whether `text` can be assigned to: a name an expression would read
+fn is_name(text: &str) -> bool {
+    text != ANSWER && matches!(Expression::parse(text), Ok(Expression::Name(_)))
+}
+
+/// `value` without float noise or trailing zeros, e.g. 93.333333 or 120
+pub fn format_value(value: f64) -> String {
+    let text = format!("{:.6}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    match text {
+        "-0" => "0".to_string(),
+        text => text.to_string(),
+    }
+}
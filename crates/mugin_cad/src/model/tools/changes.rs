@@ -0,0 +1,102 @@
+//! Revision counter and change log of a model's entities, so data derived
+//! from the geometry (snap candidates) can be refreshed incrementally.
+
+use std::collections::{HashSet, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Changes kept before readers that fell behind have to rebuild everything
+const LOG_LIMIT: usize = 4096;
+
+/// Revisions are unique across models, so a cache can't mistake a replaced
+/// model for the one it was built from
+static NEXT_REVISION: AtomicU64 = AtomicU64::new(1);
+
+fn next_revision() -> u64 {
+    NEXT_REVISION.fetch_add(1, Ordering::Relaxed)
+}
+
+/// What changed since a given revision
+#[derive(Debug, Clone, PartialEq)]
+pub enum Changes {
+    None,
+    /// Only these entities (any level of the hierarchy) were touched
+    Entities(HashSet<u64>),
+    /// Anything may have changed
+    All,
+}
+
+#[derive(Debug, Clone)]
+pub struct ChangeLog {
+    /// Revision the log started at, or the latest one dropped from it
+    trimmed: u64,
+    revision: u64,
+    /// Recent changes as (revision, entity); `None` stands for everything
+    entries: VecDeque<(u64, Option<u64>)>,
+}
+
+impl Default for ChangeLog {
+    fn default() -> Self {
+        let base = next_revision();
+        Self {
+            trimmed: base,
+            revision: base,
+            entries: VecDeque::new(),
+        }
+    }
+}
+
+impl ChangeLog {
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Record a change to one entity
+    pub fn touch(&mut self, id: u64) {
+        self.push(Some(id));
+    }
+
+    /// Record a change that may affect any entity
+    pub fn touch_all(&mut self) {
+        self.push(None);
+    }
+
+    fn push(&mut self, id: Option<u64>) {
+        self.revision = next_revision();
+        // Repeated edits of one entity (a drag, inspector typing) share an entry
+        if let Some(last) = self.entries.back_mut()
+            && last.1.is_some()
+            && last.1 == id
+        {
+            last.0 = self.revision;
+            return;
+        }
+        self.entries.push_back((self.revision, id));
+        if self.entries.len() > LOG_LIMIT
+            && let Some((rev, _)) = self.entries.pop_front()
+        {
+            self.trimmed = rev;
+        }
+    }
+
+    /// Changes after `revision`, as read by a cache built at that revision
+    pub fn since(&self, revision: u64) -> Changes {
+        if revision == self.revision {
+            return Changes::None;
+        }
+        // Built from another model, or older than the kept log
+        if revision < self.trimmed || revision > self.revision {
+            return Changes::All;
+        }
+        let mut ids = HashSet::new();
+        for &(rev, id) in self.entries.iter().rev() {
+            if rev <= revision {
+                break;
+            }
+            match id {
+                Some(id) => ids.insert(id),
+                None => return Changes::All,
+            };
+        }
+        Changes::Entities(ids)
+    }
+}
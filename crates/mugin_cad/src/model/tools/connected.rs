@@ -0,0 +1,89 @@
+//! Connected moves.
+//!
+//! Lines drawn end to end, such as a room outline, tear apart at the corner
+//! when one of them is moved. In a connected move the ends of other lines
+//! that coincide with an end of the moved entities are dragged along.
+
+use crate::model::tools::spatial::PointIndex;
+use crate::model::{CadModel, Entity, Shape, Vector2};
+use std::collections::HashSet;
+
+/// Line ends closer than this count as shared
+pub const CONNECT_TOLERANCE: f32 = 0.01;
+
+fn transform(entity: &Entity, p: Vector2) -> Vector2 {
+    entity.world_transform.transform_point2(p.into()).into()
+}
+
+/// World positions of the line ends in `entity` and its children
+fn collect_ends(entity: &Entity, ends: &mut Vec<Vector2>) {
+    if let Shape::Line(line) = &entity.shape {
+        ends.push(transform(entity, line.start));
+        ends.push(transform(entity, line.end));
+    }
+    for child in &entity.children {
+        collect_ends(child, ends);
+    }
+}
+
+/// Index the line ends outside the `moved` entities by line ID, with
+/// whether the end is the start
+fn index_ends(entities: &[Entity], moved: &HashSet<u64>, index: &mut PointIndex<(u64, bool)>) {
+    for entity in entities.iter().filter(|e| !moved.contains(&e.id)) {
+        if let Shape::Line(line) = &entity.shape {
+            index.insert(transform(entity, line.start), (entity.id, true));
+            index.insert(transform(entity, line.end), (entity.id, false));
+        }
+        index_ends(&entity.children, moved, index);
+    }
+}
+
+impl CadModel {
+    /// World positions of the line ends in entities `ids` and their children
+    pub fn line_ends(&self, ids: &[u64]) -> Vec<Vector2> {
+        let mut ends = Vec::new();
+        for entity in ids.iter().filter_map(|&id| self.find_by_id(id)) {
+            collect_ends(entity, &mut ends);
+        }
+        ends
+    }
+
+    /// Move by `delta` the ends of lines that lie on one of `ends`, leaving
+    /// out the `moved` entities and their children. Returns how many lines
+    /// were adjusted.
+    pub fn drag_connected(&mut self, ends: &[Vector2], delta: Vector2, moved: &[u64]) -> usize {
+        let moved: HashSet<u64> = moved.iter().copied().collect();
+        let mut index = PointIndex::new(CONNECT_TOLERANCE);
+        index_ends(&self.entities, &moved, &mut index);
+
+        let mut touched: Vec<(u64, bool)> = ends
+            .iter()
+            .flat_map(|&end| index.near(end, CONNECT_TOLERANCE).map(|(_, &hit)| hit))
+            .collect();
+        touched.sort();
+        touched.dedup();
+
+        for &(id, is_start) in &touched {
+            let Some(entity) = self.find_by_id_mut(id) else {
+                continue;
+            };
+            let world = entity.world_transform;
+            entity.modify(|shape| {
+                if let Shape::Line(line) = shape {
+                    let end = if is_start {
+                        &mut line.start
+                    } else {
+                        &mut line.end
+                    };
+                    let moved = world.transform_point2((*end).into()) + glam::Vec2::from(delta);
+                    *end = world.inverse().transform_point2(moved).into();
+                }
+            });
+            self.changes.touch(id);
+        }
+
+        let mut lines: Vec<u64> = touched.into_iter().map(|(id, _)| id).collect();
+        lines.dedup();
+        lines.len()
+    }
+}
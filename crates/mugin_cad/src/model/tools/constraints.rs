@@ -0,0 +1,318 @@
+//! Parametric constraints: numeric properties driven by an expression over
+//! other entities' properties, e.g. a rectangle's `width` kept at
+//! `Column_1.width * 2`.
+//!
+//! Expressions use the calculator's grammar. References are
+//! `<entity>.<property>`, the entity given by its name with characters a
+//! name can't hold written as `_` ("Rectangle 1" is `Rectangle_1`), or by
+//! its ID as `id42`. Constraints are evaluated after every change, sources
+//! before the properties they drive.
+
+use crate::model::math::expression::{Expression, ExpressionError};
+use crate::model::tools::changes::Changes;
+use crate::model::{CadModel, Entity, Shape, Vector2};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+
+/// Smaller differences are not written back, so settled constraints leave
+/// the model alone
+const EPSILON: f32 = 1e-5;
+
+/// A property of an entity: (entity ID, property name)
+type PropertyRef = (u64, String);
+
+/// Why a constraint could not be applied
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstraintError {
+    /// The expression can't be read or computed
+    Expression(ExpressionError),
+    /// No entity goes by this name or ID
+    UnknownEntity(String),
+    /// More than one entity goes by this name
+    AmbiguousName(String),
+    /// A reference, or the constrained property, the shape doesn't have
+    UnknownProperty(String),
+    /// The property depends on itself, directly or through others
+    Cycle,
+    /// Sizes must stay above zero
+    NotPositive(f64),
+}
+
+impl fmt::Display for ConstraintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Expression(error) => error.fmt(f),
+            Self::UnknownEntity(name) => write!(f, "No entity is called \"{}\".", name),
+            Self::AmbiguousName(name) => {
+                write!(
+                    f,
+                    "More than one entity is called \"{}\"; use its ID.",
+                    name
+                )
+            }
+            Self::UnknownProperty(name) => write!(f, "Unknown property \"{}\".", name),
+            Self::Cycle => f.write_str("Circular reference."),
+            Self::NotPositive(value) => write!(f, "The result {} is not above zero.", value),
+        }
+    }
+}
+
+/// Numeric properties a constraint can drive, by shape
+pub fn properties(shape: &Shape) -> &'static [&'static str] {
+    match shape {
+        Shape::Line(_) => &["length"],
+        Shape::Circle(_) | Shape::Arc(_) => &["radius"],
+        Shape::Rectangle(_) => &["width", "height"],
+        Shape::Text(_) => &["size"],
+        _ => &[],
+    }
+}
+
+/// Current value of a property
+pub fn property(shape: &Shape, name: &str) -> Option<f32> {
+    match (shape, name) {
+        (Shape::Line(line), "length") => Some(line.length()),
+        (Shape::Circle(circle), "radius") => Some(circle.radius),
+        (Shape::Arc(arc), "radius") => Some(arc.radius),
+        (Shape::Rectangle(rect), "width") => Some((rect.max.x - rect.min.x).abs()),
+        (Shape::Rectangle(rect), "height") => Some((rect.max.y - rect.min.y).abs()),
+        (Shape::Text(text), "size") => Some(text.style.font_size),
+        _ => None,
+    }
+}
+
+/// Set a property. Lines keep their start and direction, rectangles their
+/// min corner.
+fn set_property(shape: &mut Shape, name: &str, value: f32) {
+    match (shape, name) {
+        (Shape::Line(line), "length") => {
+            let direction = match (line.end - line.start).normalized() {
+                d if d.length() > 0.0 => d,
+                _ => Vector2::new(1.0, 0.0),
+            };
+            line.end = line.start + direction * value;
+        }
+        (Shape::Circle(circle), "radius") => circle.radius = value,
+        (Shape::Arc(arc), "radius") => arc.radius = value,
+        (Shape::Rectangle(rect), "width") => {
+            let sign = if rect.max.x < rect.min.x { -1.0 } else { 1.0 };
+            rect.max.x = rect.min.x + sign * value;
+        }
+        (Shape::Rectangle(rect), "height") => {
+            let sign = if rect.max.y < rect.min.y { -1.0 } else { 1.0 };
+            rect.max.y = rect.min.y + sign * value;
+        }
+        (Shape::Text(text), "size") => text.style.font_size = value,
+        _ => {}
+    }
+}
+
+/// How an entity is referred to by name in an expression: characters other
+/// than letters, digits, `_` and `.` become `_`, and a leading digit gets a
+/// `_` in front
+pub fn reference_name(name: &str) -> String {
+    let mut reference: String = name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '_' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if !reference.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+        reference.insert(0, '_');
+    }
+    reference
+}
+
+/// A constraint ready to evaluate
+struct Pending {
+    expression: Expression,
+    /// The expression's references by the name written
+    references: HashMap<String, PropertyRef>,
+}
+
+impl CadModel {
+    /// Bring constrained properties up to date after edits
+    pub fn apply_constraints(&mut self) {
+        if self.changes.since(self.constraints_revision) == Changes::None {
+            return;
+        }
+        self.constraint_errors = self.evaluate_constraints();
+        self.constraints_revision = self.changes.revision();
+    }
+
+    /// Why the constraint on `property` of entity `id` was not applied at
+    /// the last pass
+    pub fn constraint_error(&self, id: u64, property: &str) -> Option<&ConstraintError> {
+        self.constraint_errors.get(&(id, property.to_string()))
+    }
+
+    /// The entity's broken constraints, one line each, or None when they
+    /// all apply
+    pub fn constraint_warning(&self, id: u64) -> Option<String> {
+        let lines: Vec<String> = self
+            .constraint_errors
+            .range((id, String::new())..)
+            .take_while(|((entity, _), _)| *entity == id)
+            .map(|((_, property), error)| format!("{}: {}", property, error))
+            .collect();
+        (!lines.is_empty()).then(|| lines.join("\n"))
+    }
+
+    /// Evaluate every constraint, sources first. Returns the ones that
+    /// failed.
+    fn evaluate_constraints(&mut self) -> BTreeMap<PropertyRef, ConstraintError> {
+        let mut constrained = Vec::new();
+        let mut names: HashMap<String, Vec<u64>> = HashMap::new();
+        collect(&self.entities, &mut constrained, &mut names);
+        let mut errors = BTreeMap::new();
+        if constrained.is_empty() {
+            return errors;
+        }
+
+        let mut pending = BTreeMap::new();
+        for (id, name, text) in constrained {
+            match self.prepare(id, &name, &text, &names) {
+                Ok(constraint) => {
+                    pending.insert((id, name), constraint);
+                }
+                Err(error) => {
+                    errors.insert((id, name), error);
+                }
+            }
+        }
+
+        // Kahn's algorithm over the constrained properties; the ones never
+        // freed are on a cycle or fed by one
+        let mut waiting: BTreeMap<&PropertyRef, usize> = BTreeMap::new();
+        let mut dependents: HashMap<&PropertyRef, Vec<&PropertyRef>> = HashMap::new();
+        for (target, constraint) in &pending {
+            let mut sources: Vec<&PropertyRef> = constraint
+                .references
+                .values()
+                .filter(|source| pending.contains_key(*source))
+                .collect();
+            sources.sort();
+            sources.dedup();
+            waiting.insert(target, sources.len());
+            for source in sources {
+                dependents.entry(source).or_default().push(target);
+            }
+        }
+        let mut ready: VecDeque<&PropertyRef> = waiting
+            .iter()
+            .filter(|(_, count)| **count == 0)
+            .map(|(target, _)| *target)
+            .collect();
+        let mut order = Vec::new();
+        while let Some(target) = ready.pop_front() {
+            order.push(target.clone());
+            for dependent in dependents.get(target).into_iter().flatten() {
+                let count = waiting.get_mut(dependent).unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    ready.push_back(dependent);
+                }
+            }
+        }
+        for (target, count) in &waiting {
+            if *count > 0 {
+                errors.insert((*target).clone(), ConstraintError::Cycle);
+            }
+        }
+
+        for target in order {
+            let constraint = &pending[&target];
+            let value = constraint.expression.eval(&|name| {
+                let (id, name) = constraint.references.get(name)?;
+                self.find_by_id(*id)
+                    .and_then(|entity| property(&entity.shape, name))
+                    .map(f64::from)
+            });
+            let value = match value {
+                Ok(value) if value > 0.0 => value as f32,
+                Ok(value) => {
+                    errors.insert(target, ConstraintError::NotPositive(value));
+                    continue;
+                }
+                Err(error) => {
+                    errors.insert(target, ConstraintError::Expression(error));
+                    continue;
+                }
+            };
+            let (id, name) = &target;
+            let current = self
+                .find_by_id(*id)
+                .and_then(|entity| property(&entity.shape, name));
+            if current.is_some_and(|current| (current - value).abs() > EPSILON)
+                && let Some(entity) = self.find_by_id_mut(*id)
+            {
+                entity.modify(|shape| set_property(shape, name, value));
+            }
+        }
+        errors
+    }
+
+    /// Parse a constraint and resolve its references
+    fn prepare(
+        &self,
+        id: u64,
+        name: &str,
+        text: &str,
+        names: &HashMap<String, Vec<u64>>,
+    ) -> Result<Pending, ConstraintError> {
+        let entity = self.find_by_id(id).unwrap();
+        if property(&entity.shape, name).is_none() {
+            return Err(ConstraintError::UnknownProperty(name.to_string()));
+        }
+        let expression = Expression::parse(text).map_err(ConstraintError::Expression)?;
+        let mut references = HashMap::new();
+        for written in expression.names() {
+            // Plain names are constants, or unknown when evaluated
+            let Some((entity, name)) = written.rsplit_once('.') else {
+                continue;
+            };
+            let source = match names.get(entity).map(Vec::as_slice) {
+                Some([source]) => *source,
+                Some(_) => return Err(ConstraintError::AmbiguousName(entity.to_string())),
+                None => entity
+                    .strip_prefix("id")
+                    .and_then(|digits| digits.parse().ok())
+                    .filter(|source| self.find_by_id(*source).is_some())
+                    .ok_or_else(|| ConstraintError::UnknownEntity(entity.to_string()))?,
+            };
+            let shape = &self.find_by_id(source).unwrap().shape;
+            if property(shape, name).is_none() {
+                return Err(ConstraintError::UnknownProperty(written.to_string()));
+            }
+            references.insert(written.to_string(), (source, name.to_string()));
+        }
+        Ok(Pending {
+            expression,
+            references,
+        })
+    }
+}
+
+/// Every constraint as (entity, property, expression), and entity IDs by
+/// reference name
+fn collect(
+    entities: &[Entity],
+    constrained: &mut Vec<(u64, String, String)>,
+    names: &mut HashMap<String, Vec<u64>>,
+) {
+    for entity in entities {
+        for (property, expression) in &entity.constraints {
+            constrained.push((entity.id, property.clone(), expression.clone()));
+        }
+        names
+            .entry(reference_name(&entity.name))
+            .or_default()
+            .push(entity.id);
+        collect(&entity.children, constrained, names);
+    }
+}
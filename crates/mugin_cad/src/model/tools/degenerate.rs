@@ -0,0 +1,122 @@
+//! Tiny and degenerate geometry.
+//!
+//! A slip of the mouse makes zero-length lines, zero-radius circles and
+//! hairline rectangles, which later break offsets, area math and exports.
+//! Commands and the inspector refuse shapes below the configured limits,
+//! and AUDIT finds and removes the ones already in the drawing.
+
+use crate::model::{CadModel, Entity, Shape};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Smallest geometry new shapes may have, in drawing units
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GeometryLimits {
+    /// Shortest line, arc or rectangle side
+    pub min_length: f32,
+    /// Smallest circle or arc radius
+    pub min_radius: f32,
+}
+
+impl Default for GeometryLimits {
+    fn default() -> Self {
+        Self {
+            min_length: 0.01,
+            min_radius: 0.01,
+        }
+    }
+}
+
+/// A measure of a shape that fell below its limit
+#[derive(Debug, Clone, PartialEq)]
+pub struct Degenerate {
+    /// What was measured, e.g. "Line length"
+    pub what: &'static str,
+    pub value: f32,
+    pub minimum: f32,
+}
+
+impl std::fmt::Display for Degenerate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} below minimum {}",
+            self.what,
+            significant(self.value),
+            significant(self.minimum)
+        )
+    }
+}
+
+/// `value` with enough decimals to show its first significant digit
+fn significant(value: f32) -> String {
+    if value == 0.0 {
+        return "0".to_string();
+    }
+    let decimals = (-value.abs().log10().floor()).clamp(2.0, 8.0) as usize;
+    format!("{:.*}", decimals, value)
+}
+
+impl GeometryLimits {
+    /// The first measure of `shape` below its limit, if any. Only shapes
+    /// drawn from picked points are checked.
+    pub fn check(&self, shape: &Shape) -> Option<Degenerate> {
+        let below = |what, value: f32, minimum: f32| {
+            (value < minimum).then_some(Degenerate {
+                what,
+                value,
+                minimum,
+            })
+        };
+        match shape {
+            Shape::Line(line) => below("Line length", line.start.dist(line.end), self.min_length),
+            Shape::Circle(circle) => below("Circle radius", circle.radius, self.min_radius),
+            Shape::Arc(arc) => below("Arc radius", arc.radius, self.min_radius).or_else(|| {
+                below(
+                    "Arc length",
+                    arc.radius * arc.sweep().abs(),
+                    self.min_length,
+                )
+            }),
+            Shape::Rectangle(rect) => {
+                let size = rect.max - rect.min;
+                below("Rectangle width", size.x.abs(), self.min_length)
+                    .or_else(|| below("Rectangle height", size.y.abs(), self.min_length))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl CadModel {
+    /// Entities anywhere in the tree whose shape is below `limits`, with
+    /// what is wrong with each
+    pub fn degenerate_entities(&self, limits: &GeometryLimits) -> Vec<(u64, Degenerate)> {
+        fn collect(entities: &[Entity], limits: &GeometryLimits, out: &mut Vec<(u64, Degenerate)>) {
+            for entity in entities {
+                if let Some(problem) = limits.check(&entity.shape) {
+                    out.push((entity.id, problem));
+                }
+                collect(&entity.children, limits, out);
+            }
+        }
+        let mut found = Vec::new();
+        collect(&self.entities, limits, &mut found);
+        found
+    }
+
+    /// Remove every entity below `limits`. Containers holding one keep
+    /// their other children. Returns the number removed.
+    pub fn remove_degenerate(&mut self, limits: &GeometryLimits) -> usize {
+        let ids: HashSet<u64> = self
+            .degenerate_entities(limits)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        if ids.is_empty() {
+            return 0;
+        }
+        self.remove_entities_by_ids(&ids)
+    }
+}
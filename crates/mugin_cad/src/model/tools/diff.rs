@@ -0,0 +1,243 @@
+//! Differences between two versions of a drawing, matched by entity and
+//! layer ID: what the change journal records and Compare with File lists.
+//!
+//! Entities are compared one by one at every level of the hierarchy; a
+//! group counts as modified when its own properties or the IDs of its
+//! children change, not when a child is edited. Payloads carry the entity
+//! without its children, which have entries of their own.
+
+use crate::model::axis::Axis;
+use crate::model::layer::{Layer, LineStyle};
+use crate::model::{CadModel, Entity, Shape};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Overlay color of entities added since the compared file
+pub const ADDED_COLOR: [u8; 3] = [60, 200, 90];
+/// Overlay color of entities only the compared file has
+pub const REMOVED_COLOR: [u8; 3] = [230, 60, 60];
+/// Overlay color of modified entities; the file's version is dashed
+pub const MODIFIED_COLOR: [u8; 3] = [240, 200, 40];
+
+/// One difference between two versions of a drawing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Change {
+    EntityAdded {
+        entity: Box<Entity>,
+    },
+    EntityRemoved {
+        entity: Box<Entity>,
+    },
+    EntityModified {
+        before: Box<Entity>,
+        after: Box<Entity>,
+    },
+    LayerAdded {
+        layer: Layer,
+    },
+    LayerRemoved {
+        layer: Layer,
+    },
+    LayerModified {
+        before: Layer,
+        after: Layer,
+    },
+    /// The construction axes, which have no IDs, as a whole
+    AxesChanged {
+        before: Vec<Axis>,
+        after: Vec<Axis>,
+    },
+}
+
+impl Change {
+    /// ID of the entity added, removed or modified
+    pub fn entity_id(&self) -> Option<u64> {
+        match self {
+            Change::EntityAdded { entity } | Change::EntityRemoved { entity } => Some(entity.id),
+            Change::EntityModified { after, .. } => Some(after.id),
+            _ => None,
+        }
+    }
+
+    /// One line for lists, e.g. "Modified Line 3"
+    pub fn describe(&self) -> String {
+        match self {
+            Change::EntityAdded { entity } => format!("Added {}", entity.name),
+            Change::EntityRemoved { entity } => format!("Removed {}", entity.name),
+            Change::EntityModified { before, after } if before.name != after.name => {
+                format!("Modified {} (was {})", after.name, before.name)
+            }
+            Change::EntityModified { after, .. } => format!("Modified {}", after.name),
+            Change::LayerAdded { layer } => format!("Added layer {}", layer.name),
+            Change::LayerRemoved { layer } => format!("Removed layer {}", layer.name),
+            Change::LayerModified { after, .. } => format!("Modified layer {}", after.name),
+            Change::AxesChanged { before, after } => {
+                format!("Axes changed ({} → {})", before.len(), after.len())
+            }
+        }
+    }
+}
+
+/// The entity without its children
+fn own(entity: &Entity) -> Box<Entity> {
+    let mut copy = Box::new(entity.clone());
+    copy.children.clear();
+    copy
+}
+
+/// Every entity of the tree by ID, and the IDs in drawing order
+fn flatten(entities: &[Entity]) -> (HashMap<u64, &Entity>, Vec<u64>) {
+    fn walk<'a>(entities: &'a [Entity], map: &mut HashMap<u64, &'a Entity>, order: &mut Vec<u64>) {
+        for entity in entities {
+            map.insert(entity.id, entity);
+            order.push(entity.id);
+            walk(&entity.children, map, order);
+        }
+    }
+    let mut map = HashMap::new();
+    let mut order = Vec::new();
+    walk(entities, &mut map, &mut order);
+    (map, order)
+}
+
+/// What changed from `before` to `after`: entities added and modified in
+/// `after`'s drawing order, then those removed in `before`'s, then layers
+/// by ID, then the axes
+pub fn diff(before: &CadModel, after: &CadModel) -> Vec<Change> {
+    let mut changes = entity_changes(&before.entities, &after.entities);
+    changes.extend(layer_changes(before, after));
+    if before.axis_manager.axes != after.axis_manager.axes {
+        changes.push(Change::AxesChanged {
+            before: before.axis_manager.axes.clone(),
+            after: after.axis_manager.axes.clone(),
+        });
+    }
+    changes
+}
+
+/// Entity differences between two trees
+fn entity_changes(before: &[Entity], after: &[Entity]) -> Vec<Change> {
+    let (old, old_order) = flatten(before);
+    let (new, new_order) = flatten(after);
+    let mut changes = Vec::new();
+    for id in &new_order {
+        let entity = new[id];
+        match old.get(id) {
+            None => changes.push(Change::EntityAdded {
+                entity: own(entity),
+            }),
+            Some(previous) if !previous.same_own_content(entity) => {
+                changes.push(Change::EntityModified {
+                    before: own(previous),
+                    after: own(entity),
+                })
+            }
+            Some(_) => {}
+        }
+    }
+    for id in old_order.iter().filter(|id| !new.contains_key(id)) {
+        changes.push(Change::EntityRemoved {
+            entity: own(old[id]),
+        });
+    }
+    changes
+}
+
+/// Layer differences, by layer ID
+fn layer_changes(before: &CadModel, after: &CadModel) -> Vec<Change> {
+    let old = &before.layer_manager.layers;
+    let new = &after.layer_manager.layers;
+    let mut changes = Vec::new();
+    for layer in after.layer_manager.get_sorted_layers() {
+        match old.get(&layer.id) {
+            None => changes.push(Change::LayerAdded {
+                layer: layer.clone(),
+            }),
+            Some(previous) if previous != layer => changes.push(Change::LayerModified {
+                before: previous.clone(),
+                after: layer.clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+    for layer in before.layer_manager.get_sorted_layers() {
+        if !new.contains_key(&layer.id) {
+            changes.push(Change::LayerRemoved {
+                layer: layer.clone(),
+            });
+        }
+    }
+    changes
+}
+
+/// The drawing compared with another version of it, e.g. a saved file
+#[derive(Clone)]
+pub struct Comparison {
+    /// Name of the other version, shown in the compare window
+    pub label: String,
+    /// Entity and axis changes from the other version to the drawing.
+    /// Project files don't carry layers, so those are left out.
+    pub changes: Vec<Change>,
+    /// The changed entities in world coordinates, each colored by what
+    /// happened to it
+    pub overlay: CadModel,
+    pub visible: bool,
+}
+
+impl Comparison {
+    /// Compare `current` with `other`. Both need their transforms computed.
+    pub fn new(label: String, other: &CadModel, current: &CadModel) -> Self {
+        let mut changes = entity_changes(&other.entities, &current.entities);
+        if other.axis_manager.axes != current.axis_manager.axes {
+            changes.push(Change::AxesChanged {
+                before: other.axis_manager.axes.clone(),
+                after: current.axis_manager.axes.clone(),
+            });
+        }
+
+        let mut overlay = CadModel::new();
+        let mut place = |entity: &Entity, color: [u8; 3], dashed: bool| {
+            // Groups have nothing of their own to draw
+            if matches!(entity.shape, Shape::None) {
+                return;
+            }
+            let mut copy = own(entity);
+            copy.local_transform = entity.world_transform;
+            copy.color = Some(color);
+            if dashed {
+                copy.line_style = Some(LineStyle::Dashed);
+            }
+            overlay.entities.push(*copy);
+        };
+        for change in &changes {
+            match change {
+                Change::EntityAdded { entity } => place(entity, ADDED_COLOR, false),
+                Change::EntityRemoved { entity } => place(entity, REMOVED_COLOR, false),
+                Change::EntityModified { before, after } => {
+                    place(before, MODIFIED_COLOR, true);
+                    place(after, MODIFIED_COLOR, false);
+                }
+                _ => {}
+            }
+        }
+        overlay.update_hierarchy();
+
+        Self {
+            label,
+            changes,
+            overlay,
+            visible: true,
+        }
+    }
+
+    /// Number of entities (added, removed, modified)
+    pub fn counts(&self) -> (usize, usize, usize) {
+        let count = |pick: fn(&Change) -> bool| self.changes.iter().filter(|c| pick(c)).count();
+        (
+            count(|c| matches!(c, Change::EntityAdded { .. })),
+            count(|c| matches!(c, Change::EntityRemoved { .. })),
+            count(|c| matches!(c, Change::EntityModified { .. })),
+        )
+    }
+}
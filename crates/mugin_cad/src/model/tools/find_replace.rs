@@ -0,0 +1,185 @@
+//! Find and replace across entity names, text annotations and structural
+//! labels. Matching is plain substring search, never a pattern.
+
+use crate::model::{CadModel, Entity, Shape};
+use std::collections::HashSet;
+
+/// Which text of an entity a match was found in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FindField {
+    Name,
+    /// Contents of a text annotation
+    Text,
+    /// Label of a column or beam
+    Label,
+}
+
+impl FindField {
+    pub fn label(self) -> &'static str {
+        match self {
+            FindField::Name => "Name",
+            FindField::Text => "Text",
+            FindField::Label => "Label",
+        }
+    }
+}
+
+/// Entities searched
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FindScope {
+    /// Selected entities and their children
+    Selection,
+    /// Entities on one layer
+    Layer(u64),
+    #[default]
+    Project,
+}
+
+/// One field that would change
+#[derive(Debug, Clone, PartialEq)]
+pub struct FindMatch {
+    pub entity_id: u64,
+    pub field: FindField,
+    pub before: String,
+    pub after: String,
+}
+
+/// Length in bytes of `find` matched at the start of `text`, if it matches
+fn match_len(text: &str, find: &str, case_sensitive: bool) -> Option<usize> {
+    if case_sensitive {
+        return text.starts_with(find).then_some(find.len());
+    }
+    let mut chars = text.char_indices();
+    for f in find.chars() {
+        let (_, t) = chars.next()?;
+        if !t.to_lowercase().eq(f.to_lowercase()) {
+            return None;
+        }
+    }
+    Some(chars.next().map_or(text.len(), |(i, _)| i))
+}
+
+/// Replace every non-overlapping occurrence of `find` in `text`, left to
+/// right. Returns `None` when nothing matches or `find` is empty.
+pub fn replace_text(text: &str, find: &str, replace: &str, case_sensitive: bool) -> Option<String> {
+    if find.is_empty() {
+        return None;
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut found = false;
+    let mut rest = text;
+    while !rest.is_empty() {
+        if let Some(len) = match_len(rest, find, case_sensitive) {
+            out.push_str(replace);
+            rest = &rest[len..];
+            found = true;
+        } else {
+            let c = rest.chars().next().expect("rest is not empty");
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    found.then_some(out)
+}
+
+/// The searchable texts of an entity
+fn fields(entity: &Entity) -> Vec<(FindField, &str)> {
+    let mut fields = vec![(FindField::Name, entity.name.as_str())];
+    match &entity.shape {
+        Shape::Text(text) => fields.push((FindField::Text, text.text.as_str())),
+        Shape::Column(column) => fields.push((FindField::Label, column.label.as_str())),
+        Shape::Beam(beam) => fields.push((FindField::Label, beam.label.as_str())),
+        _ => {}
+    }
+    fields
+}
+
+/// Write `value` into one of the entity's texts. Returns false if the
+/// entity has no such field.
+fn set_field(entity: &mut Entity, field: FindField, value: &str) -> bool {
+    if field == FindField::Name {
+        entity.name = value.to_string();
+        return true;
+    }
+    let has_field = matches!(
+        (field, &entity.shape),
+        (FindField::Text, Shape::Text(_)) | (FindField::Label, Shape::Column(_) | Shape::Beam(_))
+    );
+    if has_field {
+        entity.modify(|shape| match shape {
+            Shape::Text(text) => text.text = value.to_string(),
+            Shape::Column(column) => column.label = value.to_string(),
+            Shape::Beam(beam) => beam.label = value.to_string(),
+            _ => {}
+        });
+    }
+    has_field
+}
+
+/// Every field in `scope` that replacing `find` with `replace` would change
+pub fn find_matches(
+    model: &CadModel,
+    scope: FindScope,
+    selected: &HashSet<u64>,
+    find: &str,
+    replace: &str,
+    case_sensitive: bool,
+) -> Vec<FindMatch> {
+    fn visit(
+        entity: &Entity,
+        scope: FindScope,
+        selected: &HashSet<u64>,
+        inside_selection: bool,
+        search: (&str, &str, bool),
+        out: &mut Vec<FindMatch>,
+    ) {
+        let inside_selection = inside_selection || selected.contains(&entity.id);
+        let in_scope = match scope {
+            FindScope::Selection => inside_selection,
+            FindScope::Layer(layer_id) => entity.layer_id == layer_id,
+            FindScope::Project => true,
+        };
+        if in_scope {
+            let (find, replace, case_sensitive) = search;
+            for (field, before) in fields(entity) {
+                if let Some(after) = replace_text(before, find, replace, case_sensitive) {
+                    out.push(FindMatch {
+                        entity_id: entity.id,
+                        field,
+                        before: before.to_string(),
+                        after,
+                    });
+                }
+            }
+        }
+        for child in &entity.children {
+            visit(child, scope, selected, inside_selection, search, out);
+        }
+    }
+
+    let mut out = Vec::new();
+    for entity in &model.entities {
+        visit(
+            entity,
+            scope,
+            selected,
+            false,
+            (find, replace, case_sensitive),
+            &mut out,
+        );
+    }
+    out
+}
+
+/// Write the matches' replacement texts. Returns how many fields changed.
+pub fn apply_matches(model: &mut CadModel, matches: &[FindMatch]) -> usize {
+    let mut count = 0;
+    for m in matches {
+        if let Some(entity) = model.find_by_id_mut(m.entity_id)
+            && set_field(entity, m.field, &m.after)
+        {
+            count += 1;
+        }
+    }
+    count
+}
@@ -0,0 +1,101 @@
+//! Fuzzy matching of typed text against names, for the command palette.
+//!
+//! The query's characters must appear in order in the name, in any case.
+//! Matches that start words, run together and start early score higher,
+//! so "rc" finds "Revision Cloud" before "Rectangle".
+
+/// Score for each matched character
+const MATCH: i32 = 1;
+/// Bonus for a character that starts a word
+const WORD_START: i32 = 8;
+/// Bonus for a character right after the previous match
+const CONSECUTIVE: i32 = 6;
+/// Penalty for skipping ahead between two matches
+const GAP: i32 = 1;
+/// Most the name's leading characters before the first match cost
+const MAX_LEADING: i32 = 3;
+
+/// How well a query matched a name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    pub score: i32,
+    /// Character indices of the name that matched, for highlighting
+    pub positions: Vec<usize>,
+}
+
+/// Match `query` against `text`, `None` if its characters don't all
+/// appear in order. Spaces in the query are ignored; an empty query
+/// matches everything with a score of 0.
+pub fn fuzzy_match(query: &str, text: &str) -> Option<FuzzyMatch> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(fold)
+        .collect();
+    let text: Vec<char> = text.chars().collect();
+    if query.is_empty() {
+        return Some(FuzzyMatch {
+            score: 0,
+            positions: Vec::new(),
+        });
+    }
+    let folded: Vec<char> = text.iter().copied().map(fold).collect();
+    let bonus: Vec<i32> = (0..text.len())
+        .map(|j| MATCH + if word_start(&text, j) { WORD_START } else { 0 })
+        .collect();
+
+    // best[i][j]: best score with query[i] on text[j], and where query[i - 1] went
+    let (n, m) = (query.len(), text.len());
+    let mut best = vec![vec![None::<(i32, usize)>; m]; n];
+    for j in 0..m {
+        if folded[j] == query[0] {
+            best[0][j] = Some((bonus[j] - (j as i32).min(MAX_LEADING), 0));
+        }
+    }
+    for i in 1..n {
+        // Best placement of query[i - 1] strictly before j - 1
+        let mut earlier: Option<(i32, usize)> = None;
+        for j in 1..m {
+            if j >= 2
+                && let Some((score, _)) = best[i - 1][j - 2]
+                && earlier.is_none_or(|(s, _)| score > s)
+            {
+                earlier = Some((score, j - 2));
+            }
+            if folded[j] != query[i] {
+                continue;
+            }
+            let adjacent = best[i - 1][j - 1].map(|(s, _)| (s + CONSECUTIVE, j - 1));
+            let skipped = earlier.map(|(s, k)| (s - GAP, k));
+            best[i][j] = match (adjacent, skipped) {
+                (Some(a), Some(s)) => Some(if a.0 >= s.0 { a } else { s }),
+                (a, s) => a.or(s),
+            }
+            .map(|(score, from)| (score + bonus[j], from));
+        }
+    }
+
+    let (mut j, score) = (0..m)
+        .filter_map(|j| best[n - 1][j].map(|(score, _)| (j, score)))
+        .max_by_key(|&(j, score)| (score, std::cmp::Reverse(j)))?;
+    let mut positions = vec![0; n];
+    for i in (0..n).rev() {
+        positions[i] = j;
+        j = best[i][j].map_or(0, |(_, from)| from);
+    }
+    Some(FuzzyMatch { score, positions })
+}
+
+fn fold(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// Whether `text[j]` begins a word: after a separator, or a capital
+/// after a lowercase letter
+fn word_start(text: &[char], j: usize) -> bool {
+    let Some(&before) = j.checked_sub(1).and_then(|k| text.get(k)) else {
+        return true;
+    };
+    let current = text[j];
+    !before.is_alphanumeric() || (before.is_lowercase() && current.is_uppercase())
+}
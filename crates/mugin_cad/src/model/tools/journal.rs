@@ -0,0 +1,94 @@
+//! Append-only journal of a drawing's changes.
+//!
+//! The journal keeps a copy of the entities, layers and axes as of its last
+//! entry. Recording diffs the model against that copy, so every mutation
+//! is caught whichever code path made it, and a run of edits between two
+//! recordings (a drag, an undo) becomes one entry per entity touched.
+
+use crate::model::CadModel;
+use crate::model::tools::diff::{Change, diff};
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A recorded change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Position in the journal, counting from 1
+    pub sequence: u64,
+    /// When it was recorded, in seconds since the Unix epoch
+    pub time: u64,
+    #[serde(flatten)]
+    pub change: Change,
+}
+
+#[derive(Clone)]
+pub struct Journal {
+    entries: Vec<JournalEntry>,
+    /// Entities, layers and axes as of the last entry
+    baseline: CadModel,
+    /// Change revision of the model at the last recording
+    revision: u64,
+}
+
+impl Journal {
+    /// Start an empty journal from the model as it is now
+    pub fn new(model: &CadModel) -> Self {
+        Self {
+            entries: Vec::new(),
+            baseline: snapshot(model),
+            revision: model.changes.revision(),
+        }
+    }
+
+    /// Append what changed since the last recording. Returns the number of
+    /// entries added.
+    pub fn record(&mut self, model: &CadModel) -> usize {
+        let untouched = model.changes.revision() == self.revision
+            && model.layer_manager.layers == self.baseline.layer_manager.layers
+            && model.axis_manager.axes == self.baseline.axis_manager.axes;
+        if untouched {
+            return 0;
+        }
+        self.revision = model.changes.revision();
+        let changes = diff(&self.baseline, model);
+        if changes.is_empty() {
+            return 0;
+        }
+
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        let count = changes.len();
+        for change in changes {
+            self.entries.push(JournalEntry {
+                sequence: self.entries.len() as u64 + 1,
+                time,
+                change,
+            });
+        }
+        self.baseline = snapshot(model);
+        count
+    }
+
+    pub fn entries(&self) -> &[JournalEntry] {
+        &self.entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The entries as a JSON array, oldest first
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(&self.entries).map_err(|e| e.to_string())
+    }
+}
+
+/// Copy of what the journal compares
+fn snapshot(model: &CadModel) -> CadModel {
+    let mut copy = CadModel::new();
+    copy.entities = model.entities.clone();
+    copy.layer_manager = model.layer_manager.clone();
+    copy.axis_manager = model.axis_manager.clone();
+    copy
+}
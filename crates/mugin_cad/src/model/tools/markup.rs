@@ -0,0 +1,85 @@
+//! Markup: review notes drawn over the drawing with revision clouds and
+//! freehand sketches, kept on their own layer.
+
+use crate::model::layer::LayerManager;
+use crate::model::math::geometry::simplify_path;
+use crate::model::{CadModel, Entity, Shape, Sketch, Vector2};
+use ecolor::Color32;
+use std::collections::HashSet;
+
+/// Layer markup goes on, added unplotted the first time it is needed
+pub const MARKUP_LAYER: &str = "Markup";
+const MARKUP_COLOR: Color32 = Color32::from_rgb(230, 40, 40);
+
+/// ID of the markup layer. Markup is for review, so a new one stays off
+/// printed sheets until turned on.
+pub fn markup_layer(layers: &mut LayerManager) -> u64 {
+    if let Some(layer) = layers.find_by_name(MARKUP_LAYER) {
+        return layer.id;
+    }
+    let id = layers.add_layer(MARKUP_LAYER.to_string(), MARKUP_COLOR);
+    if let Some(layer) = layers.layers.get_mut(&id) {
+        layer.plottable = false;
+    }
+    id
+}
+
+impl CadModel {
+    /// Every sketch in the drawing, children included, in drawing order
+    pub fn sketches(&self) -> Vec<(&Entity, &Sketch)> {
+        fn collect<'a>(entity: &'a Entity, out: &mut Vec<(&'a Entity, &'a Sketch)>) {
+            if let Shape::Sketch(sketch) = &entity.shape {
+                out.push((entity, sketch));
+            }
+            for child in &entity.children {
+                collect(child, out);
+            }
+        }
+        let mut sketches = Vec::new();
+        for entity in &self.entities {
+            collect(entity, &mut sketches);
+        }
+        sketches
+    }
+
+    /// Topmost sketch under `pos` on a visible layer, whatever the
+    /// selection would pick there
+    pub fn sketch_at(&self, pos: Vector2, tolerance: f32) -> Option<u64> {
+        self.pick_where(None, pos, tolerance, &|entity| {
+            matches!(entity.shape, Shape::Sketch(_))
+        })
+    }
+
+    /// Add a sketch along a freehand `stroke` on the markup layer, dropping
+    /// points within `tolerance` of the simplified path. Returns its ID, or
+    /// None for a stroke that never left its first point.
+    pub fn add_sketch(
+        &mut self,
+        stroke: &[Vector2],
+        tolerance: f32,
+        highlighter: bool,
+    ) -> Option<u64> {
+        let first = *stroke.first()?;
+        if stroke.iter().all(|p| p.dist(first) <= tolerance) {
+            return None;
+        }
+        let mut entity = Entity::sketch(Sketch::new(simplify_path(stroke, tolerance), highlighter));
+        entity.layer_id = markup_layer(&mut self.layer_manager);
+        let id = entity.id;
+        self.add_entity(entity);
+        Some(id)
+    }
+
+    /// Remove every sketch. Returns the number removed.
+    pub fn clear_sketches(&mut self) -> usize {
+        let ids: HashSet<u64> = self
+            .sketches()
+            .into_iter()
+            .map(|(entity, _)| entity.id)
+            .collect();
+        if ids.is_empty() {
+            return 0;
+        }
+        self.remove_entities_by_ids(&ids)
+    }
+}
@@ -1,2 +1,17 @@
+pub mod alignment;
+pub mod calculator;
+pub mod changes;
+pub mod connected;
+pub mod constraints;
+pub mod degenerate;
+pub mod diff;
+pub mod find_replace;
+pub mod fuzzy;
+pub mod journal;
+pub mod markup;
+pub mod rooms;
+pub mod saved;
+pub mod selection_sets;
 pub mod snap;
+pub mod spatial;
 pub mod undo;
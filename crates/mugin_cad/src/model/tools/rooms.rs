@@ -0,0 +1,285 @@
+//! Room detection.
+//!
+//! The walls of a plan are its lines, rectangle edges and beam center
+//! lines. They are split where they cross and where one ends on another,
+//! wall ends closer than the gap tolerance are joined, and every bounded
+//! face of the resulting planar graph is a room.
+
+use crate::model::math::segment;
+use crate::model::structure::flooring::polygon_contains;
+use crate::model::tools::spatial::PointIndex;
+use crate::model::{CadModel, Entity, Room, Shape, Vector2};
+use std::collections::BTreeSet;
+
+/// Gap between wall ends that still closes a room, in drawing units
+pub const ROOM_GAP_TOLERANCE: f32 = 5.0;
+
+/// Sine of the angle below which a corner counts as straight
+const STRAIGHT: f32 = 1e-4;
+
+/// Closed loops formed by `walls`, each counter-clockwise with its first
+/// corner not repeated. Ends within `tolerance` of each other or of
+/// another wall count as touching; walls leading nowhere are ignored.
+pub fn find_loops(walls: &[(Vector2, Vector2)], tolerance: f32) -> Vec<Vec<Vector2>> {
+    let walls: Vec<(Vector2, Vector2)> = walls
+        .iter()
+        .copied()
+        .filter(|(a, b)| a.dist(*b) > f32::EPSILON)
+        .collect();
+
+    // Where each wall is cut, as parameters along it
+    let mut cuts: Vec<Vec<f32>> = vec![vec![0.0, 1.0]; walls.len()];
+    for (i, &(a, b)) in walls.iter().enumerate() {
+        for (j, &(c, d)) in walls.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+            if j > i
+                && let Some(p) = segment::intersection(a, b, c, d)
+            {
+                cuts[i].push(segment::project(p, a, b));
+                cuts[j].push(segment::project(p, c, d));
+            }
+            // An end stopping on this wall, or just short of it
+            for end in [c, d] {
+                if segment::distance(end, a, b) <= tolerance {
+                    cuts[i].push(segment::closest_param(end, a, b));
+                }
+            }
+        }
+    }
+
+    // Graph of the wall pieces, with nearby points merged into one node
+    let mut nodes: Vec<Vector2> = Vec::new();
+    let mut index = PointIndex::new(tolerance);
+    let mut node_at = |p: Vector2| -> usize {
+        if let Some((_, &id)) = index.near(p, tolerance).next() {
+            return id;
+        }
+        nodes.push(p);
+        index.insert(p, nodes.len() - 1);
+        nodes.len() - 1
+    };
+    let mut edges = BTreeSet::new();
+    for (&(a, b), cuts) in walls.iter().zip(&mut cuts) {
+        cuts.sort_by(f32::total_cmp);
+        let ids: Vec<usize> = cuts.iter().map(|&t| node_at(a.lerp(b, t))).collect();
+        for pair in ids.windows(2) {
+            if pair[0] != pair[1] {
+                edges.insert((pair[0].min(pair[1]), pair[0].max(pair[1])));
+            }
+        }
+    }
+
+    let mut adjacent: Vec<Vec<usize>> = vec![Vec::new(); nodes.len()];
+    for &(u, v) in &edges {
+        adjacent[u].push(v);
+        adjacent[v].push(u);
+    }
+    prune_dead_ends(&mut adjacent);
+
+    // Neighbors counter-clockwise by direction
+    for (u, around) in adjacent.iter_mut().enumerate() {
+        let angle = |v: &usize| {
+            let d = nodes[*v] - nodes[u];
+            d.y.atan2(d.x)
+        };
+        around.sort_by(|a, b| angle(a).total_cmp(&angle(b)));
+    }
+
+    // Walk every face keeping it on the left: from u→v, go on along the
+    // edge at v that comes clockwise right after the way back to u
+    let mut walked = BTreeSet::new();
+    let mut loops = Vec::new();
+    for (u, around) in adjacent.iter().enumerate() {
+        for &v in around {
+            if walked.contains(&(u, v)) {
+                continue;
+            }
+            let mut face = Vec::new();
+            let (mut from, mut to) = (u, v);
+            while walked.insert((from, to)) {
+                face.push(nodes[from]);
+                let around = &adjacent[to];
+                let back = around.iter().position(|&w| w == from).unwrap_or(0);
+                let next = around[(back + around.len() - 1) % around.len()];
+                (from, to) = (to, next);
+            }
+            let face = drop_straight_corners(face);
+            // The outside of each group of walls runs clockwise
+            if face.len() >= 3 && signed_area(&face) > tolerance * tolerance {
+                loops.push(face);
+            }
+        }
+    }
+    loops
+}
+
+/// Remove edges with an end nothing else meets, until none are left
+fn prune_dead_ends(adjacent: &mut [Vec<usize>]) {
+    let mut ends: Vec<usize> = (0..adjacent.len())
+        .filter(|&u| adjacent[u].len() == 1)
+        .collect();
+    while let Some(u) = ends.pop() {
+        let Some(v) = adjacent[u].pop() else {
+            continue;
+        };
+        adjacent[v].retain(|&w| w != u);
+        if adjacent[v].len() == 1 {
+            ends.push(v);
+        }
+    }
+}
+
+/// The loop without corners where it runs straight on
+fn drop_straight_corners(mut points: Vec<Vector2>) -> Vec<Vector2> {
+    let mut i = 0;
+    while points.len() >= 3 && i < points.len() {
+        let n = points.len();
+        let (prev, here, next) = (points[(i + n - 1) % n], points[i], points[(i + 1) % n]);
+        let (a, b) = (here - prev, next - here);
+        let turn = a.cross(b) / (a.length() * b.length()).max(f32::EPSILON);
+        if turn.abs() <= STRAIGHT && a.dot(b) > 0.0 {
+            points.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+    points
+}
+
+/// Area enclosed by a loop, positive when counter-clockwise
+fn signed_area(points: &[Vector2]) -> f32 {
+    let n = points.len();
+    (0..n)
+        .map(|i| points[i].cross(points[(i + 1) % n]))
+        .sum::<f32>()
+        / 2.0
+}
+
+/// Rooms changed by `CadModel::regenerate_rooms`
+#[derive(Debug, Default)]
+pub struct RoomsUpdate {
+    /// Rooms found again, with their boundary and area brought up to date
+    pub updated: usize,
+    /// Loops no room was in yet, as new rooms to add
+    pub added: Vec<Room>,
+    /// Rooms whose loop is gone; they are left as they were
+    pub unmatched: usize,
+}
+
+fn world(entity: &Entity, p: Vector2) -> Vector2 {
+    entity.world_transform.transform_point2(p.into()).into()
+}
+
+fn local(entity: &Entity, p: Vector2) -> Vector2 {
+    entity
+        .world_transform
+        .inverse()
+        .transform_point2(p.into())
+        .into()
+}
+
+impl CadModel {
+    /// Every room in the drawing, children included, in drawing order
+    pub fn rooms(&self) -> Vec<(&Entity, &Room)> {
+        fn collect<'a>(entity: &'a Entity, out: &mut Vec<(&'a Entity, &'a Room)>) {
+            if let Shape::Room(room) = &entity.shape {
+                out.push((entity, room));
+            }
+            for child in &entity.children {
+                collect(child, out);
+            }
+        }
+        let mut rooms = Vec::new();
+        for entity in &self.entities {
+            collect(entity, &mut rooms);
+        }
+        rooms
+    }
+
+    /// Walls of the active storey on visible layers, in world coordinates
+    pub fn room_walls(&self) -> Vec<(Vector2, Vector2)> {
+        fn collect(model: &CadModel, entity: &Entity, out: &mut Vec<(Vector2, Vector2)>) {
+            if model
+                .layer_manager
+                .get_layer(entity.layer_id)
+                .is_some_and(|layer| !layer.is_visible)
+            {
+                return;
+            }
+            let w = |p| world(entity, p);
+            match &entity.shape {
+                Shape::Line(line) => out.push((w(line.start), w(line.end))),
+                Shape::Beam(beam) => out.push((w(beam.start), w(beam.end))),
+                Shape::Rectangle(rect) => {
+                    let corners = [
+                        rect.min,
+                        Vector2::new(rect.max.x, rect.min.y),
+                        rect.max,
+                        Vector2::new(rect.min.x, rect.max.y),
+                    ]
+                    .map(w);
+                    for i in 0..4 {
+                        out.push((corners[i], corners[(i + 1) % 4]));
+                    }
+                }
+                _ => {}
+            }
+            for child in &entity.children {
+                collect(model, child, out);
+            }
+        }
+        let mut walls = Vec::new();
+        for entity in self
+            .entities
+            .iter()
+            .filter(|e| e.on_active_storey(&self.storeys))
+        {
+            collect(self, entity, &mut walls);
+        }
+        walls
+    }
+
+    /// Find the rooms of the plan again. A room whose label lies in a loop
+    /// takes that loop's boundary and area; loops without a room come back
+    /// as new, numbered rooms for the caller to add.
+    pub fn regenerate_rooms(&mut self, tolerance: f32) -> RoomsUpdate {
+        let loops = find_loops(&self.room_walls(), tolerance);
+
+        // Existing rooms by id, with their label position in the world
+        let mut rooms: Vec<(u64, Vector2)> = self
+            .rooms()
+            .into_iter()
+            .map(|(entity, room)| (entity.id, world(entity, room.center())))
+            .collect();
+
+        let mut update = RoomsUpdate::default();
+        let mut fresh = Vec::new();
+        for boundary in loops {
+            let found = rooms
+                .iter()
+                .position(|&(_, center)| polygon_contains(&boundary, center));
+            let Some(found) = found else {
+                fresh.push(boundary);
+                continue;
+            };
+            let (id, _) = rooms.swap_remove(found);
+            if let Some(entity) = self.find_by_id_mut(id) {
+                let boundary = boundary.iter().map(|&p| local(entity, p)).collect();
+                entity.modify(|shape| {
+                    if let Shape::Room(room) = shape {
+                        room.regenerate(boundary);
+                    }
+                });
+                update.updated += 1;
+            }
+        }
+        update.unmatched = rooms.len();
+        update.added = fresh
+            .into_iter()
+            .map(|boundary| Room::new(boundary, self.next_name("Room")))
+            .collect();
+        update
+    }
+}
@@ -0,0 +1,68 @@
+//! Entities changed since the drawing was last saved.
+//!
+//! Saving records each entity's geometry revision. An entity whose revision
+//! differs, or that was not there at all, has unsaved changes. Undoing back
+//! to the saved state restores the old revisions, so it counts as saved.
+
+use crate::model::Entity;
+use std::collections::{HashMap, HashSet};
+
+/// Geometry revisions of the entities as last saved, by ID
+#[derive(Debug, Clone, Default)]
+pub struct SavedRevisions {
+    revisions: HashMap<u64, u32>,
+}
+
+/// How much of the drawing differs from the saved file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UnsavedCount {
+    /// Entities created or modified
+    pub changed: usize,
+    /// Saved entities no longer in the drawing
+    pub deleted: usize,
+}
+
+impl UnsavedCount {
+    pub fn is_empty(&self) -> bool {
+        self.changed == 0 && self.deleted == 0
+    }
+}
+
+impl SavedRevisions {
+    /// Record the revisions of `entities` and all their children
+    pub fn capture(entities: &[Entity]) -> Self {
+        let mut revisions = HashMap::new();
+        let mut stack: Vec<&Entity> = entities.iter().collect();
+        while let Some(entity) = stack.pop() {
+            revisions.insert(entity.id, entity.revision());
+            stack.extend(&entity.children);
+        }
+        Self { revisions }
+    }
+
+    /// Whether `entity` was created or modified since the save. Its
+    /// children are not considered.
+    pub fn is_changed(&self, entity: &Entity) -> bool {
+        self.revisions.get(&entity.id) != Some(&entity.revision())
+    }
+
+    /// Count the changes in `entities` and their children
+    pub fn unsaved(&self, entities: &[Entity]) -> UnsavedCount {
+        let mut count = UnsavedCount::default();
+        let mut present = HashSet::new();
+        let mut stack: Vec<&Entity> = entities.iter().collect();
+        while let Some(entity) = stack.pop() {
+            present.insert(entity.id);
+            if self.is_changed(entity) {
+                count.changed += 1;
+            }
+            stack.extend(&entity.children);
+        }
+        count.deleted = self
+            .revisions
+            .keys()
+            .filter(|id| !present.contains(id))
+            .count();
+        count
+    }
+}
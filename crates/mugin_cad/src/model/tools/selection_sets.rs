@@ -0,0 +1,58 @@
+//! Named selection sets.
+//!
+//! A set stores entity IDs by name so a selection such as "all shear walls"
+//! can be recalled later. Entities deleted since the set was saved are
+//! skipped when it is recalled.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+/// Saved selections by name, listed alphabetically
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SelectionSets {
+    sets: BTreeMap<String, BTreeSet<u64>>,
+}
+
+impl SelectionSets {
+    pub fn is_empty(&self) -> bool {
+        self.sets.is_empty()
+    }
+
+    /// Names and IDs of the sets
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &BTreeSet<u64>)> {
+        self.sets.iter().map(|(name, ids)| (name.as_str(), ids))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&BTreeSet<u64>> {
+        self.sets.get(name)
+    }
+
+    /// Store `ids` under `name`, replacing a set of that name. Returns
+    /// whether one was replaced.
+    pub fn save(&mut self, name: &str, ids: &HashSet<u64>) -> bool {
+        self.sets
+            .insert(name.to_string(), ids.iter().copied().collect())
+            .is_some()
+    }
+
+    /// Rename a set. Fails if there is no set `from`, or `to` is empty or
+    /// already taken.
+    pub fn rename(&mut self, from: &str, to: &str) -> bool {
+        let to = to.trim();
+        if to.is_empty() || self.sets.contains_key(to) {
+            return false;
+        }
+        match self.sets.remove(from) {
+            Some(ids) => {
+                self.sets.insert(to.to_string(), ids);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.sets.remove(name).is_some()
+    }
+}
@@ -1,22 +1,31 @@
-use crate::model::{CadModel, Entity, Shape, Vector2};
+use crate::model::math::segment;
+use crate::model::{CadModel, Entity, Geometry, Shape, Vector2};
+use std::collections::HashSet;
 
 /// Types of snap points
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum SnapPointType {
     /// Endpoint of a line
     Endpoint,
-    /// Center of a circle or rectangle
+    /// Center of a circle, rectangle or column
     Center,
+    /// Insertion point of a text
+    Insertion,
     /// Corner of a rectangle
     Corner,
-    /// Intersection between two entities
+    /// Intersection between two entities, or between the command preview
+    /// and an entity
     Intersection,
+    /// Where two lines would cross if extended
+    ApparentIntersection,
     /// Midpoint of a line
     Midpoint,
     /// Point on an axis line
     AxisLine,
     /// Grid intersection point
     Grid,
+    /// Temporary tracking point, or a point lined up with one or two
+    Tracking,
 }
 
 /// A snap point with its position and type
@@ -24,6 +33,8 @@ pub enum SnapPointType {
 pub struct SnapPoint {
     pub position: Vector2,
     pub point_type: SnapPointType,
+    /// Entities the point was found on; two for an intersection
+    pub sources: [Option<u64>; 2],
 }
 
 impl SnapPoint {
@@ -31,49 +42,252 @@ impl SnapPoint {
         Self {
             position,
             point_type,
+            sources: [None, None],
         }
     }
+
+    /// Record the entity the point belongs to
+    pub fn on_entity(mut self, id: u64) -> Self {
+        self.sources = [Some(id), None];
+        self
+    }
+
+    /// Record the two entities the point lies on
+    pub fn on_entities(mut self, a: u64, b: u64) -> Self {
+        self.sources = [Some(a), Some(b)];
+        self
+    }
+
+    /// Ids of the source entities
+    pub fn source_ids(&self) -> impl Iterator<Item = u64> + '_ {
+        self.sources.iter().flatten().copied()
+    }
 }
 
 use crate::model::config::AppConfig;
+use crate::model::tools::changes::Changes;
+use std::collections::HashMap;
+
+/// Axis-aligned box around some points
+type Bounds = (Vector2, Vector2);
+
+fn bounds_of(points: impl IntoIterator<Item = Vector2>) -> Option<Bounds> {
+    points.into_iter().fold(None, |acc, p| match acc {
+        None => Some((p, p)),
+        Some((min, max)) => Some((
+            Vector2::new(min.x.min(p.x), min.y.min(p.y)),
+            Vector2::new(max.x.max(p.x), max.y.max(p.y)),
+        )),
+    })
+}
+
+/// Whether `pos` is within `tolerance` of the box along both axes
+fn near(bounds: Option<Bounds>, pos: Vector2, tolerance: f32) -> bool {
+    bounds.is_some_and(|(min, max)| {
+        pos.x >= min.x - tolerance
+            && pos.x <= max.x + tolerance
+            && pos.y >= min.y - tolerance
+            && pos.y <= max.y + tolerance
+    })
+}
+
+/// Snap data of one top-level entity, kept until it changes
+struct CachedEntity {
+    /// Snap points of the entity and its children
+    points: Vec<SnapPoint>,
+    point_bounds: Option<Bounds>,
+    /// Box around the shape, for shapes that can intersect others
+    shape_bounds: Option<Bounds>,
+    /// Ids and geometry revisions of the entity and all its descendants
+    revisions: Vec<(u64, u32)>,
+}
+
+/// Per-entity snap candidates, refreshed incrementally from the model's
+/// change log
+#[derive(Default)]
+struct SnapCache {
+    /// Model revision the cache matches
+    revision: Option<u64>,
+    entities: HashMap<u64, CachedEntity>,
+    /// Top-level entity each cached id belongs to
+    roots: HashMap<u64, u64>,
+}
+
+impl SnapCache {
+    /// Bring the cache up to date with `model`
+    fn sync(&mut self, system: &SnapSystem, model: &CadModel) {
+        let changes = match self.revision {
+            Some(revision) => model.changes.since(revision),
+            None => Changes::All,
+        };
+        match changes {
+            Changes::None => {}
+            Changes::All => {
+                self.entities.clear();
+                self.roots.clear();
+                for entity in &model.entities {
+                    self.insert(system, entity);
+                }
+            }
+            Changes::Entities(ids) => {
+                let mut stale: HashSet<u64> = HashSet::new();
+                for id in ids {
+                    match self.roots.get(&id) {
+                        Some(&root) => {
+                            stale.insert(root);
+                        }
+                        // New entity: find the top-level entity holding it
+                        None => stale.extend(
+                            model
+                                .entities
+                                .iter()
+                                .find(|entity| entity.find_by_id(id).is_some())
+                                .map(|entity| entity.id),
+                        ),
+                    }
+                }
+                for root in stale {
+                    let entity = model.entities.iter().find(|e| e.id == root);
+                    // Touched without a geometry change, e.g. a recolor
+                    if let (Some(entity), Some(cached)) = (entity, self.entities.get(&root))
+                        && revisions(entity) == cached.revisions
+                    {
+                        continue;
+                    }
+                    if let Some(old) = self.entities.remove(&root) {
+                        for (id, _) in old.revisions {
+                            self.roots.remove(&id);
+                        }
+                    }
+                    if let Some(entity) = entity {
+                        self.insert(system, entity);
+                    }
+                }
+            }
+        }
+        self.revision = Some(model.changes.revision());
+    }
+
+    fn insert(&mut self, system: &SnapSystem, entity: &Entity) {
+        let points: Vec<SnapPoint> = system
+            .get_entity_snap_points(entity)
+            .into_iter()
+            .map(|point| point.on_entity(entity.id))
+            .collect();
+        let revisions = revisions(entity);
+        for &(id, _) in &revisions {
+            self.roots.insert(id, entity.id);
+        }
+        self.entities.insert(
+            entity.id,
+            CachedEntity {
+                point_bounds: bounds_of(points.iter().map(|point| point.position)),
+                points,
+                shape_bounds: intersection_bounds(&entity.shape),
+                revisions,
+            },
+        );
+    }
+}
 
-/// Snap system that finds snap points from entities
-pub struct SnapSystem;
+fn revisions(entity: &Entity) -> Vec<(u64, u32)> {
+    fn collect(entity: &Entity, out: &mut Vec<(u64, u32)>) {
+        out.push((entity.id, entity.revision()));
+        for child in &entity.children {
+            collect(child, out);
+        }
+    }
+    let mut out = Vec::new();
+    collect(entity, &mut out);
+    out
+}
+
+/// Box around the shapes `find_intersections` handles
+fn intersection_bounds(shape: &Shape) -> Option<Bounds> {
+    match shape {
+        Shape::Line(line) => bounds_of([line.start, line.end]),
+        Shape::Circle(circle) => {
+            let r = Vector2::new(circle.radius, circle.radius);
+            Some((circle.center - r, circle.center + r))
+        }
+        Shape::Rectangle(rect) => bounds_of([rect.min, rect.max]),
+        _ => None,
+    }
+}
+
+/// Distance from `pos` to the infinite line through `a` and `b`
+fn line_distance(pos: Vector2, a: Vector2, b: Vector2) -> Option<f32> {
+    let dir = b - a;
+    let len = dir.length();
+    (len > 1e-6).then(|| (dir.x * (pos.y - a.y) - dir.y * (pos.x - a.x)).abs() / len)
+}
+
+/// Snap system that finds snap points from entities. Per-entity candidates
+/// are cached between queries.
+pub struct SnapSystem {
+    cache: SnapCache,
+}
 
 impl SnapSystem {
     pub fn new() -> Self {
-        Self
+        Self {
+            cache: SnapCache::default(),
+        }
     }
 
-    /// Find the nearest snap point to a position
+    /// Find the nearest snap point to a position. `preview` is the active
+    /// command's rubber band (last point, cursor), whose crossings with the
+    /// model are snap candidates too.
     pub fn find_nearest(
-        &self,
+        &mut self,
         pos: Vector2,
         model: &CadModel,
         config: &AppConfig,
+        preview: Option<(Vector2, Vector2)>,
     ) -> Option<SnapPoint> {
         let mut nearest: Option<(SnapPoint, f32)> = None;
         let tolerance = config.snap_config.tolerance;
 
+        let mut cache = std::mem::take(&mut self.cache);
+        cache.sync(self, model);
+
         // 1. Entity Snaps
         for entity in &model.entities {
-            for snap_point in self.get_entity_snap_points(entity) {
+            let Some(cached) = cache.entities.get(&entity.id) else {
+                continue;
+            };
+            if !near(cached.point_bounds, pos, tolerance) {
+                continue;
+            }
+            for snap_point in &cached.points {
                 let dist = pos.dist(snap_point.position);
-                if dist <= tolerance {
-                    if nearest.is_none() || dist < nearest.unwrap().1 {
-                        nearest = Some((snap_point, dist));
-                    }
+                if dist <= tolerance && (nearest.is_none() || dist < nearest.unwrap().1) {
+                    nearest = Some((*snap_point, dist));
                 }
             }
         }
 
+        // Only shapes whose box reaches the cursor can cross within reach
+        let candidates: Vec<&Entity> = model
+            .entities
+            .iter()
+            .filter(|entity| {
+                cache
+                    .entities
+                    .get(&entity.id)
+                    .is_some_and(|cached| near(cached.shape_bounds, pos, tolerance))
+            })
+            .collect();
+        self.cache = cache;
+
         // 2. Intersection Snaps
-        for (i, entity_a) in model.entities.iter().enumerate() {
-            for entity_b in model.entities.iter().skip(i + 1) {
+        for (i, entity_a) in candidates.iter().enumerate() {
+            for entity_b in candidates.iter().skip(i + 1) {
                 for intersection in self.find_intersections(entity_a, entity_b) {
                     let dist = pos.dist(intersection);
                     if dist <= tolerance {
-                        let snap_point = SnapPoint::new(intersection, SnapPointType::Intersection);
+                        let snap_point = SnapPoint::new(intersection, SnapPointType::Intersection)
+                            .on_entities(entity_a.id, entity_b.id);
                         if nearest.is_none() || dist < nearest.unwrap().1 {
                             nearest = Some((snap_point, dist));
                         }
@@ -82,6 +296,59 @@ impl SnapSystem {
             }
         }
 
+        // 2b. Preview Intersections: where the line being drawn would cross
+        // the model. The rubber band is stretched past the cursor so a
+        // crossing just ahead of it is found too.
+        if config.snap_config.snap_to_intersection
+            && let Some((from, to)) = preview
+            && from.dist(to) > 1e-6
+        {
+            let reach = to + (to - from).normalized() * tolerance;
+            let band = Entity::line(from, reach);
+            for entity in &candidates {
+                for intersection in self.find_intersections(&band, entity) {
+                    let dist = pos.dist(intersection);
+                    if dist <= tolerance && (nearest.is_none() || dist < nearest.unwrap().1) {
+                        let snap_point = SnapPoint::new(intersection, SnapPointType::Intersection)
+                            .on_entity(entity.id);
+                        nearest = Some((snap_point, dist));
+                    }
+                }
+            }
+        }
+
+        // 2c. Apparent Intersections: lines that would cross if extended.
+        // Both extensions pass within reach of a crossing near the cursor.
+        if config.snap_config.snap_to_apparent_intersection {
+            let lines: Vec<(u64, Vector2, Vector2)> = model
+                .entities
+                .iter()
+                .filter_map(|entity| match &entity.shape {
+                    Shape::Line(line) => Some((entity.id, line.start, line.end)),
+                    _ => None,
+                })
+                .filter(|&(_, a, b)| line_distance(pos, a, b).is_some_and(|d| d <= tolerance))
+                .collect();
+            for (i, &(a, a1, a2)) in lines.iter().enumerate() {
+                for &(b, b1, b2) in lines.iter().skip(i + 1) {
+                    let Some(intersection) = segment::line_intersection(a1, a2, b1, b2) else {
+                        continue;
+                    };
+                    // Real crossings are already offered as Intersection
+                    if segment::intersection(a1, a2, b1, b2).is_some() {
+                        continue;
+                    }
+                    let dist = pos.dist(intersection);
+                    if dist <= tolerance && (nearest.is_none() || dist < nearest.unwrap().1) {
+                        let snap_point =
+                            SnapPoint::new(intersection, SnapPointType::ApparentIntersection)
+                                .on_entities(a, b);
+                        nearest = Some((snap_point, dist));
+                    }
+                }
+            }
+        }
+
         // 3. Axis Snaps (Akslar)
         // Check intersections between axes (Axis Intersections)
         for (i, axis_a) in model.axis_manager.axes.iter().enumerate() {
@@ -155,102 +422,69 @@ impl SnapSystem {
         nearest.map(|(sp, _)| sp)
     }
 
-    /// Get all snap points from an entity
-    fn get_entity_snap_points(&self, entity: &Entity) -> Vec<SnapPoint> {
-        let mut points = Vec::new();
-
-        match &entity.shape {
-            Shape::Line(line) => {
-                // Endpoints
-                points.push(SnapPoint::new(line.start, SnapPointType::Endpoint));
-                points.push(SnapPoint::new(line.end, SnapPointType::Endpoint));
-                // Midpoint
-                let mid = Vector2::new(
-                    (line.start.x + line.end.x) / 2.0,
-                    (line.start.y + line.end.y) / 2.0,
-                );
-                points.push(SnapPoint::new(mid, SnapPointType::Midpoint));
-            }
-            Shape::Circle(circle) => {
-                // Center
-                points.push(SnapPoint::new(circle.center, SnapPointType::Center));
-                // Quadrant points (N, S, E, W)
-                points.push(SnapPoint::new(
-                    Vector2::new(circle.center.x + circle.radius, circle.center.y),
-                    SnapPointType::Endpoint,
-                ));
-                points.push(SnapPoint::new(
-                    Vector2::new(circle.center.x - circle.radius, circle.center.y),
-                    SnapPointType::Endpoint,
-                ));
-                points.push(SnapPoint::new(
-                    Vector2::new(circle.center.x, circle.center.y + circle.radius),
-                    SnapPointType::Endpoint,
-                ));
-                points.push(SnapPoint::new(
-                    Vector2::new(circle.center.x, circle.center.y - circle.radius),
-                    SnapPointType::Endpoint,
-                ));
-            }
-            Shape::Rectangle(rect) => {
-                // Four corners
-                points.push(SnapPoint::new(rect.min, SnapPointType::Corner));
-                points.push(SnapPoint::new(rect.max, SnapPointType::Corner));
-                points.push(SnapPoint::new(
-                    Vector2::new(rect.min.x, rect.max.y),
-                    SnapPointType::Corner,
-                ));
-                points.push(SnapPoint::new(
-                    Vector2::new(rect.max.x, rect.min.y),
-                    SnapPointType::Corner,
-                ));
-                // Center
-                let center = Vector2::new(
-                    (rect.min.x + rect.max.x) / 2.0,
-                    (rect.min.y + rect.max.y) / 2.0,
-                );
-                points.push(SnapPoint::new(center, SnapPointType::Center));
-            }
-            Shape::Arc(arc) => {
-                // Center
-                points.push(SnapPoint::new(arc.center, SnapPointType::Center));
-                // Start and end points
-                points.push(SnapPoint::new(arc.start_point(), SnapPointType::Endpoint));
-                points.push(SnapPoint::new(arc.end_point(), SnapPointType::Endpoint));
-            }
-            Shape::Text(text) => {
-                // Text position
-                points.push(SnapPoint::new(text.position, SnapPointType::Center));
-                // Anchor points for measurements
-                for pt in &text.anchor_points {
-                    points.push(SnapPoint::new(*pt, SnapPointType::Endpoint));
-                }
-            }
-            Shape::Column(col) => {
-                // Center
-                points.push(SnapPoint::new(col.center, SnapPointType::Center));
-                // Corners
-                let corners = col.get_corners();
-                for p in corners {
-                    points.push(SnapPoint::new(p, SnapPointType::Corner));
-                }
-            }
-            Shape::Beam(beam) => {
-                // Endpoints
-                points.push(SnapPoint::new(beam.start, SnapPointType::Endpoint));
-                points.push(SnapPoint::new(beam.end, SnapPointType::Endpoint));
-                // Midpoint
-                let mid = (beam.start + beam.end) * 0.5;
-                points.push(SnapPoint::new(mid, SnapPointType::Midpoint));
-            }
-            Shape::None => {}
-        }
+    /// Nearest end point or corner of referenced geometry, which offers no
+    /// other snaps
+    pub fn find_nearest_reference(
+        &self,
+        pos: Vector2,
+        entities: &[Entity],
+        tolerance: f32,
+    ) -> Option<SnapPoint> {
+        entities
+            .iter()
+            .flat_map(|entity| self.get_entity_snap_points(entity))
+            .filter(|snap| {
+                matches!(
+                    snap.point_type,
+                    SnapPointType::Endpoint | SnapPointType::Corner
+                )
+            })
+            .map(|snap| (snap, pos.dist(snap.position)))
+            .filter(|(_, dist)| *dist <= tolerance)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(snap, _)| snap)
+    }
+
+    /// Nearest point given by the temporary tracking points: one of them
+    /// or the x of one with the y of another, else the cursor lined up
+    /// horizontally or vertically with one
+    pub fn find_nearest_tracking(
+        &self,
+        pos: Vector2,
+        tracking: &[Vector2],
+        tolerance: f32,
+    ) -> Option<SnapPoint> {
+        let nearest = |points: Vec<Vector2>| {
+            points
+                .into_iter()
+                .map(|point| (point, pos.dist(point)))
+                .filter(|(_, dist)| *dist <= tolerance)
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(point, _)| SnapPoint::new(point, SnapPointType::Tracking))
+        };
+        // Pairing a point with itself gives the point
+        let exact = tracking
+            .iter()
+            .flat_map(|a| tracking.iter().map(|b| Vector2::new(a.x, b.y)))
+            .collect();
+        let aligned = tracking
+            .iter()
+            .flat_map(|a| [Vector2::new(a.x, pos.y), Vector2::new(pos.x, a.y)])
+            .collect();
+        nearest(exact).or_else(|| nearest(aligned))
+    }
 
-        // Also process children recursively
+    /// Get all snap points from an entity and its children
+    fn get_entity_snap_points(&self, entity: &Entity) -> Vec<SnapPoint> {
+        let mut points: Vec<SnapPoint> = entity
+            .shape
+            .snap_points()
+            .into_iter()
+            .map(|(point_type, position)| SnapPoint::new(position, point_type))
+            .collect();
         for child in &entity.children {
             points.extend(self.get_entity_snap_points(child));
         }
-
         points
     }
 
@@ -260,13 +494,13 @@ impl SnapSystem {
 
         match (&a.shape, &b.shape) {
             (Shape::Line(l1), Shape::Line(l2)) => {
-                if let Some(pt) = self.line_line_intersection(l1.start, l1.end, l2.start, l2.end) {
+                if let Some(pt) = segment::intersection(l1.start, l1.end, l2.start, l2.end) {
                     intersections.push(pt);
                 }
             }
             (Shape::Line(line), Shape::Circle(circle))
             | (Shape::Circle(circle), Shape::Line(line)) => {
-                intersections.extend(self.line_circle_intersection(
+                intersections.extend(segment::circle_intersections(
                     line.start,
                     line.end,
                     circle.center,
@@ -283,7 +517,7 @@ impl SnapSystem {
                     (Vector2::new(rect.min.x, rect.max.y), rect.min),
                 ];
                 for (c1, c2) in corners {
-                    if let Some(pt) = self.line_line_intersection(line.start, line.end, c1, c2) {
+                    if let Some(pt) = segment::intersection(line.start, line.end, c1, c2) {
                         intersections.push(pt);
                     }
                 }
@@ -301,67 +535,6 @@ impl SnapSystem {
         intersections
     }
 
-    /// Line-line intersection
-    fn line_line_intersection(
-        &self,
-        p1: Vector2,
-        p2: Vector2,
-        p3: Vector2,
-        p4: Vector2,
-    ) -> Option<Vector2> {
-        let d = (p1.x - p2.x) * (p3.y - p4.y) - (p1.y - p2.y) * (p3.x - p4.x);
-        if d.abs() < 1e-10 {
-            return None; // Parallel
-        }
-
-        let t = ((p1.x - p3.x) * (p3.y - p4.y) - (p1.y - p3.y) * (p3.x - p4.x)) / d;
-        let u = -((p1.x - p2.x) * (p1.y - p3.y) - (p1.y - p2.y) * (p1.x - p3.x)) / d;
-
-        // Check if intersection is within both line segments
-        if t >= 0.0 && t <= 1.0 && u >= 0.0 && u <= 1.0 {
-            Some(Vector2::new(
-                p1.x + t * (p2.x - p1.x),
-                p1.y + t * (p2.y - p1.y),
-            ))
-        } else {
-            None
-        }
-    }
-
-    /// Line-circle intersection
-    fn line_circle_intersection(
-        &self,
-        p1: Vector2,
-        p2: Vector2,
-        center: Vector2,
-        radius: f32,
-    ) -> Vec<Vector2> {
-        let mut result = Vec::new();
-
-        let dx = p2.x - p1.x;
-        let dy = p2.y - p1.y;
-        let fx = p1.x - center.x;
-        let fy = p1.y - center.y;
-
-        let a = dx * dx + dy * dy;
-        let b = 2.0 * (fx * dx + fy * dy);
-        let c = fx * fx + fy * fy - radius * radius;
-
-        let discriminant = b * b - 4.0 * a * c;
-
-        if discriminant >= 0.0 {
-            let sqrt_disc = discriminant.sqrt();
-
-            for t in [(-b - sqrt_disc) / (2.0 * a), (-b + sqrt_disc) / (2.0 * a)] {
-                if t >= 0.0 && t <= 1.0 {
-                    result.push(Vector2::new(p1.x + t * dx, p1.y + t * dy));
-                }
-            }
-        }
-
-        result
-    }
-
     /// Circle-circle intersection
     fn circle_circle_intersection(
         &self,
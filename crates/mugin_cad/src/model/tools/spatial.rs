@@ -0,0 +1,45 @@
+//! Spatial index for finding points near a location.
+
+use crate::model::Vector2;
+use std::collections::HashMap;
+
+/// Points bucketed on a uniform grid, each carrying a value
+pub struct PointIndex<T> {
+    cell: f32,
+    cells: HashMap<(i32, i32), Vec<(Vector2, T)>>,
+}
+
+impl<T> PointIndex<T> {
+    /// An empty index with square cells of side `cell`. Searches are fastest
+    /// with cells about as large as the search radius.
+    pub fn new(cell: f32) -> Self {
+        Self {
+            cell: cell.max(f32::EPSILON),
+            cells: HashMap::new(),
+        }
+    }
+
+    fn key(&self, p: Vector2) -> (i32, i32) {
+        (
+            (p.x / self.cell).floor() as i32,
+            (p.y / self.cell).floor() as i32,
+        )
+    }
+
+    pub fn insert(&mut self, p: Vector2, value: T) {
+        let key = self.key(p);
+        self.cells.entry(key).or_default().push((p, value));
+    }
+
+    /// Points within `radius` of `p`, with their values
+    pub fn near(&self, p: Vector2, radius: f32) -> impl Iterator<Item = (Vector2, &T)> {
+        let (x0, y0) = self.key(p - Vector2::new(radius, radius));
+        let (x1, y1) = self.key(p + Vector2::new(radius, radius));
+        (x0..=x1)
+            .flat_map(move |x| (y0..=y1).map(move |y| (x, y)))
+            .filter_map(|key| self.cells.get(&key))
+            .flatten()
+            .filter(move |(q, _)| q.dist(p) <= radius)
+            .map(|(q, value)| (*q, value))
+    }
+}
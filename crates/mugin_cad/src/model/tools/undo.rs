@@ -1,11 +1,27 @@
 use crate::model::Entity;
+use std::collections::HashSet;
+
+/// Entities plus the selection at the time, so undo restores both
+pub struct Snapshot {
+    pub entities: Vec<Entity>,
+    pub selection: HashSet<u64>,
+}
+
+impl Snapshot {
+    fn new(entities: &[Entity], selection: &HashSet<u64>) -> Self {
+        Self {
+            entities: entities.to_vec(),
+            selection: selection.clone(),
+        }
+    }
+}
 
 /// Manages undo/redo history using state snapshots
 pub struct UndoManager {
     /// Stack of previous states (most recent at the end)
-    undo_stack: Vec<Vec<Entity>>,
+    undo_stack: Vec<Snapshot>,
     /// Stack of undone states for redo
-    redo_stack: Vec<Vec<Entity>>,
+    redo_stack: Vec<Snapshot>,
     /// Maximum number of undo levels
     max_levels: usize,
 }
@@ -20,12 +36,12 @@ impl UndoManager {
     }
 
     /// Save current state before making changes
-    pub fn save_state(&mut self, entities: &[Entity]) {
+    pub fn save_state(&mut self, entities: &[Entity], selection: &HashSet<u64>) {
         // Clear redo stack when new action is performed
         self.redo_stack.clear();
 
         // Save current state
-        self.undo_stack.push(entities.to_vec());
+        self.undo_stack.push(Snapshot::new(entities, selection));
 
         // Limit stack size
         if self.undo_stack.len() > self.max_levels {
@@ -34,10 +50,15 @@ impl UndoManager {
     }
 
     /// Undo: restore previous state and return it
-    pub fn undo(&mut self, current_entities: &[Entity]) -> Option<Vec<Entity>> {
+    pub fn undo(
+        &mut self,
+        current_entities: &[Entity],
+        current_selection: &HashSet<u64>,
+    ) -> Option<Snapshot> {
         if let Some(previous_state) = self.undo_stack.pop() {
             // Save current state for redo
-            self.redo_stack.push(current_entities.to_vec());
+            self.redo_stack
+                .push(Snapshot::new(current_entities, current_selection));
             Some(previous_state)
         } else {
             None
@@ -45,10 +66,15 @@ impl UndoManager {
     }
 
     /// Redo: restore undone state
-    pub fn redo(&mut self, current_entities: &[Entity]) -> Option<Vec<Entity>> {
+    pub fn redo(
+        &mut self,
+        current_entities: &[Entity],
+        current_selection: &HashSet<u64>,
+    ) -> Option<Snapshot> {
         if let Some(redo_state) = self.redo_stack.pop() {
             // Save current state for undo
-            self.undo_stack.push(current_entities.to_vec());
+            self.undo_stack
+                .push(Snapshot::new(current_entities, current_selection));
             Some(redo_state)
         } else {
             None
@@ -1,10 +1,17 @@
-use crate::commands::InputModifiers;
+use crate::commands::{InputModifiers, preview};
 // use crate::model::snap::SnapPointType;
-use crate::model::Vector2;
+use crate::model::color_mode::{ColorMode, TYPE_PALETTE};
+use crate::model::{CadModel, Entity, Sketch, Vector2};
 use crate::view::rendering::context::DrawContext;
-use crate::view::rendering::renderer;
-use crate::viewmodel::CadViewModel;
+use crate::view::rendering::renderable::Renderable;
+use crate::view::rendering::renderer::{self, Highlight};
+use crate::view::ui::toolbar::quick_tooltip;
+use crate::viewmodel::{
+    CadViewModel, CalculatorPick, CanvasCursor, HANDLE_SIZE, MarkupTool, QuickTransform,
+    region_corners,
+};
 use eframe::egui;
+use std::collections::HashSet;
 
 pub fn render_canvas(ui: &mut egui::Ui, vm: &mut CadViewModel) {
     let (response, painter) =
@@ -21,29 +28,51 @@ pub fn render_canvas(ui: &mut egui::Ui, vm: &mut CadViewModel) {
     let modifiers = ui.input(|i| InputModifiers {
         shift: i.modifiers.shift,
         ctrl: i.modifiers.ctrl,
+        alt: i.modifiers.alt,
     });
 
     // Update global modifier state
     vm.set_modifiers(modifiers);
 
+    // Right-click cancels a running command; only an idle canvas gets a menu
+    let command_was_active = vm.active_tab().executor.is_active();
+
     let middle_pressed = ui.input(|i| i.pointer.middle_down());
+    let middle_double_clicked =
+        ui.input(|i| i.pointer.button_double_clicked(egui::PointerButton::Middle));
     let scroll_delta = ui.input(|i| i.raw_scroll_delta.y);
 
     let hover_pos = response.hover_pos();
+    let pointer_moved = ui.input(|i| i.pointer.delta() != egui::Vec2::ZERO);
     let dragged = response.dragged();
     let drag_started = response.drag_started();
     let drag_stopped = response.drag_stopped();
     let clicked = response.clicked();
+    let double_clicked = response.double_clicked();
     let secondary_clicked = response.secondary_clicked();
-    let r_pressed = ui.input(|i| i.key_pressed(egui::Key::R));
 
     // Viewport manipulation (Directly on active tab)
+    vm.active_tab_mut().viewport.screen_size = Vector2::new(rect.width(), rect.height());
+
+    // Middle double-click — zoom extents, same as the ZE command. A fit
+    // asked for before the canvas had a size happens now.
+    if (middle_double_clicked && hover_pos.is_some()) || vm.active_tab().fit_pending {
+        vm.zoom_extents();
+    }
+
     let is_panning;
     let viewport_zoom;
     let viewport_offset;
 
     {
+        let inertia = vm.config.gui_config.pan_inertia;
         let tab = vm.active_tab_mut();
+
+        // A command left pending in this tab shows its preview again once
+        // the cursor moves over the canvas
+        if tab.preview_paused && hover_pos.is_some() && pointer_moved {
+            tab.preview_paused = false;
+        }
         if let Some(mouse_pos) = hover_pos {
             let screen_pos = Vector2::new(mouse_pos.x, mouse_pos.y);
             if middle_pressed && !tab.viewport.is_panning {
@@ -51,18 +80,18 @@ pub fn render_canvas(ui: &mut egui::Ui, vm: &mut CadViewModel) {
             } else if middle_pressed && tab.viewport.is_panning {
                 tab.viewport.update_pan(screen_pos);
             } else if !middle_pressed && tab.viewport.is_panning {
-                tab.viewport.end_pan();
+                tab.viewport.end_pan(inertia);
             }
-        } else if !middle_pressed {
-            tab.viewport.end_pan();
+        } else if !middle_pressed && tab.viewport.is_panning {
+            tab.viewport.end_pan(inertia);
         }
 
-        if scroll_delta != 0.0 {
-            if let Some(mouse_pos) = hover_pos {
-                let screen_pos = Vector2::new(mouse_pos.x, mouse_pos.y);
-                let zoom_delta = scroll_delta / 50.0;
-                tab.viewport.zoom_at(screen_pos, screen_center, zoom_delta);
-            }
+        if scroll_delta != 0.0
+            && let Some(mouse_pos) = hover_pos
+        {
+            let screen_pos = Vector2::new(mouse_pos.x, mouse_pos.y);
+            let zoom_delta = scroll_delta / 50.0;
+            tab.viewport.zoom_at(screen_pos, screen_center, zoom_delta);
         }
 
         is_panning = tab.viewport.is_panning;
@@ -71,34 +100,43 @@ pub fn render_canvas(ui: &mut egui::Ui, vm: &mut CadViewModel) {
     }
 
     // Create DrawContext (needs immutable props)
+    let dim_style = vm.config.dimension_style.clone();
     let ctx = DrawContext {
         painter: &painter,
         zoom: viewport_zoom,
         offset: viewport_offset,
         screen_center,
         transform: glam::Affine2::IDENTITY,
+        dim_style: &dim_style,
+        color: crate::view::rendering::context::DEFAULT_ENTITY_COLOR,
+        emphasized: false,
+        line_style: crate::model::layer::LineStyle::Solid,
+        stroke_width: crate::model::layer::DEFAULT_STROKE_WIDTH,
+        detail: vm.config.appearance_config.detail,
+        flooring: vm.config.appearance_config.flooring,
     };
 
     // Handle Input logic (Calls methods on VM)
-    if let Some(mouse_pos) = hover_pos {
-        if !is_panning {
-            let cad_pos = ctx.to_cad(mouse_pos);
-            vm.update_snap(cad_pos, modifiers);
-        }
+    if let Some(mouse_pos) = hover_pos
+        && !is_panning
+    {
+        vm.update_cursor(ctx.to_cad(mouse_pos), modifiers);
+    } else {
+        vm.active_tab_mut().cursor = None;
     }
 
     if !is_panning {
-        if drag_started {
-            if let Some(mouse_pos) = response.interact_pointer_pos() {
-                let cad_pos = ctx.to_cad(mouse_pos);
-                vm.handle_drag_start(cad_pos, modifiers);
-            }
+        // Start where the button went down, not where the drag was detected
+        let press_pos = ui
+            .input(|i| i.pointer.press_origin())
+            .or(response.interact_pointer_pos());
+        if drag_started && let Some(mouse_pos) = press_pos {
+            let cad_pos = ctx.to_cad(mouse_pos);
+            vm.handle_drag_start(cad_pos, modifiers);
         }
-        if dragged {
-            if let Some(mouse_pos) = response.interact_pointer_pos() {
-                let cad_pos = ctx.to_cad(mouse_pos);
-                vm.handle_drag_update(cad_pos);
-            }
+        if dragged && let Some(mouse_pos) = response.interact_pointer_pos() {
+            let cad_pos = ctx.to_cad(mouse_pos);
+            vm.handle_drag_update(cad_pos, modifiers);
         }
         if drag_stopped {
             vm.handle_drag_end(modifiers);
@@ -111,29 +149,14 @@ pub fn render_canvas(ui: &mut egui::Ui, vm: &mut CadViewModel) {
         } else if secondary_clicked {
             vm.cancel_command();
         }
-
-        if r_pressed && !modifiers.ctrl && !modifiers.shift {
-            vm.active_tab_mut().executor.toggle_arc_direction();
+        // After the clicks' selection: fit, step into a group or edit
+        if double_clicked && let Some(mouse_pos) = response.interact_pointer_pos() {
+            vm.handle_double_click(ctx.to_cad(mouse_pos), modifiers);
         }
 
-        let q_pressed = ui.input(|i| i.key_pressed(egui::Key::Q));
-        let e_pressed = ui.input(|i| i.key_pressed(egui::Key::E));
-
-        if q_pressed && !modifiers.ctrl && !modifiers.shift {
-            vm.active_tab_mut().executor.cycle_placement_anchor();
-        }
-
-        if e_pressed && !modifiers.ctrl && !modifiers.shift {
-            if !vm.active_tab_mut().executor.rotate_placement() {
-                // Fallback for E to be "Rotate Command" alias if no active command consuming it?
-                // No, Rotate Command is 'R' usually or 'E'.
-                // If we preserve 'E' as alias for "Rotate Command" when "Place Column" is not active...
-                // The global shortcut handler or `shortcuts.rs` might handle it, OR we handle it here.
-                // Currently 'E' alias acts via text command "e".
-                // BUT wait, `process_input` handles text. Key press is different.
-                // If we want 'E' to trigger rotate command when idle, we should check it.
-                // But `shortcuts::handle` might do it.
-            }
+        // Ctrl+T over a snap point drops or removes a tracking point
+        if modifiers.ctrl && hover_pos.is_some() && ui.input(|i| i.key_pressed(egui::Key::T)) {
+            vm.toggle_tracking_point();
         }
     }
 
@@ -144,6 +167,13 @@ pub fn render_canvas(ui: &mut egui::Ui, vm: &mut CadViewModel) {
 
     // Update hierarchy transforms before rendering entities
     vm.active_tab_mut().model.update_hierarchy();
+    // Constrained properties follow the ones they refer to
+    vm.active_tab_mut().model.apply_constraints();
+    // Beam ends follow the columns they are attached to
+    vm.active_tab_mut().model.resolve_attachments();
+    let tab = vm.active_tab_mut();
+    tab.model
+        .refresh_junctions(tab.executor.defaults.auto_cleanup);
 
     // We can now borrow tab for rendering
     let tab = vm.active_tab();
@@ -187,15 +217,50 @@ pub fn render_canvas(ui: &mut egui::Ui, vm: &mut CadViewModel) {
     }
 
     // Hover detection - access tab
-    let mut hovered_entity_id = None;
-    if let Some(mouse_pos) = hover_pos {
-        if !is_panning {
-            let cad_mouse = ctx.to_cad(mouse_pos);
-            let tolerance = 5.0 / viewport_zoom;
-            hovered_entity_id = tab.model.pick_entity_id(cad_mouse, tolerance);
-        }
+    // Picks at the snapped position, same as a click would
+    let hovered_entity_id = tab.cursor.and_then(|cursor| {
+        tab.selection_manager.pick(
+            &tab.model,
+            cursor.snapped,
+            vm.config.snap_config.world_pick_tolerance(viewport_zoom),
+        )
+    });
+
+    // Pointer shape for the running command or what lies under it; handle
+    // grips below override it
+    if let Some(pos) = hover_pos {
+        let icon = match vm.canvas_cursor(hovered_entity_id) {
+            _ if is_panning => egui::CursorIcon::Grabbing,
+            CanvasCursor::Idle => egui::CursorIcon::Default,
+            CanvasCursor::Pick => egui::CursorIcon::PointingHand,
+            CanvasCursor::Locked => egui::CursorIcon::NotAllowed,
+            CanvasCursor::Point => egui::CursorIcon::Crosshair,
+            CanvasCursor::Move => egui::CursorIcon::Move,
+            CanvasCursor::Rotate => {
+                // egui has no rotation cursor; mark the crosshair instead
+                painter.text(
+                    pos + egui::vec2(10.0, -10.0),
+                    egui::Align2::LEFT_BOTTOM,
+                    "↻",
+                    egui::FontId::proportional(16.0),
+                    ui.visuals().strong_text_color(),
+                );
+                egui::CursorIcon::Crosshair
+            }
+            CanvasCursor::Drag => egui::CursorIcon::Grabbing,
+        };
+        ui.ctx().set_cursor_icon(icon);
     }
 
+    // Hover tooltip (suppressed while panning, dragging or running a command)
+    let tooltip_id = hovered_entity_id.filter(|_| {
+        vm.config.gui_config.show_hover_tooltips
+            && !is_panning
+            && !dragged
+            && !tab.executor.is_active()
+    });
+    crate::view::ui::hover_tooltip::render_hover_tooltip(ui, tab, &dim_style, tooltip_id);
+
     // Selection Box
     if let (Some(start), Some(current)) = (
         tab.selection_manager.selection_rect_start,
@@ -215,85 +280,307 @@ pub fn render_canvas(ui: &mut egui::Ui, vm: &mut CadViewModel) {
         painter.rect_stroke(rect_screen, 0.0, stroke);
     }
 
-    // Entities
-    // Entities
+    // References: other drawings beneath this one, never selected or hovered
+    let no_selection = std::collections::HashSet::new();
+    for reference in tab.references.iter().filter(|r| r.visible) {
+        renderer::render_entities(
+            &ctx,
+            &reference.snapshot.definitions,
+            &reference.snapshot.entities,
+            Highlight {
+                selected: &no_selection,
+                hovered: None,
+                emphasized: &no_selection,
+                active_storey: None,
+                joins: None,
+                color_mode: ColorMode::Normal,
+                unsaved: None,
+                carried: &no_selection,
+                editing: None,
+            },
+            &reference.snapshot.layer_manager,
+        );
+    }
+
+    // What MOVE, ROTATE, SCALE or MIRROR carries: ghosted where it started and
+    // drawn transformed at the cursor
+    let mut carried = HashSet::new();
+    let mut carried_copies = Vec::new();
+    if let Some(cursor) = tab.cursor.filter(|_| !tab.preview_paused)
+        && let Some(preview) = tab.executor.transform_preview(cursor.constrained)
+    {
+        for entity in preview
+            .entity_ids
+            .iter()
+            .filter_map(|&id| tab.model.find_by_id(id))
+        {
+            if !preview.copy {
+                collect_tree_ids(entity, &mut carried);
+            }
+            let parent = entity.world_transform * entity.local_transform.inverse();
+            let mut copy = entity.clone();
+            preview.transform.apply(&mut copy);
+            copy.update_transforms(parent);
+            carried_copies.push(copy);
+        }
+    }
+
+    // Inside group editing, everything outside the group is ghosted
+    let editing = tab
+        .selection_manager
+        .scope
+        .and_then(|id| tab.model.find_by_id(id))
+        .map(|group| {
+            let mut ids = HashSet::new();
+            collect_tree_ids(group, &mut ids);
+            ids
+        });
+
+    // Entities, with the ones the current snap lies on emphasized
+    let snap_sources: std::collections::HashSet<u64> = tab
+        .current_snap
+        .iter()
+        .flat_map(|snap| snap.source_ids())
+        .collect();
     renderer::render_entities(
         &ctx,
         &tab.model.definitions,
         &tab.model.entities,
-        &tab.selection_manager.selected_ids,
-        hovered_entity_id,
+        Highlight {
+            selected: &tab.selection_manager.selected_ids,
+            hovered: hovered_entity_id,
+            emphasized: &snap_sources,
+            active_storey: tab.model.storeys.active,
+            joins: Some(&tab.model.beam_joins),
+            color_mode: vm.color_mode,
+            unsaved: vm.highlight_unsaved.then_some(&tab.saved_revisions),
+            carried: &carried,
+            editing: editing.as_ref(),
+        },
         &tab.model.layer_manager,
     );
+    renderer::render_entities(
+        &ctx,
+        &tab.model.definitions,
+        &carried_copies,
+        Highlight {
+            selected: &no_selection,
+            hovered: None,
+            emphasized: &no_selection,
+            active_storey: tab.model.storeys.active,
+            joins: None,
+            color_mode: vm.color_mode,
+            unsaved: None,
+            carried: &no_selection,
+            editing: None,
+        },
+        &tab.model.layer_manager,
+    );
+    // Compare with File: what changed since the file, over the drawing
+    if let Some(comparison) = tab.comparison.as_ref().filter(|c| c.visible) {
+        renderer::render_entities(
+            &ctx,
+            &tab.model.definitions,
+            &comparison.overlay.entities,
+            Highlight {
+                selected: &no_selection,
+                hovered: None,
+                emphasized: &no_selection,
+                active_storey: None,
+                joins: None,
+                color_mode: ColorMode::Normal,
+                unsaved: None,
+                carried: &no_selection,
+                editing: None,
+            },
+            &tab.model.layer_manager,
+        );
+    }
+    if vm.color_mode == ColorMode::ByType {
+        draw_type_legend(&painter, rect, &tab.model);
+    }
+
+    // Export region being set up, with grips to adjust it
+    if vm.export_window.open
+        && let Some((min, max)) = tab.model.export_region
+    {
+        draw_export_region(&ctx, min, max, hover_pos);
+        if let Some(pos) = hover_pos
+            && vm.export_region_grip_at(ctx.to_cad(pos)).is_some()
+        {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::Grab);
+        }
+    }
+
+    // Selection box with scale and rotation handles
+    if !tab.executor.is_active() && tab.selection_manager.selection_rect_start.is_none() {
+        crate::view::rendering::selection_box::render_selection_box(&ctx, tab, hover_pos);
+        if let Some(pos) = hover_pos
+            && tab.handle_drag.is_none()
+            && tab.handle_at(ctx.to_cad(pos)).is_some()
+        {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::Grab);
+        }
+    }
 
-    // Cursor and Preview
-    if let Some(mouse_pos) = hover_pos {
-        if !is_panning {
-            let raw_cad = ctx.to_cad(mouse_pos);
-            let effective_cad = vm.get_effective_position(raw_cad); // Call VM method
+    // Tracking points of the running command
+    let tracking_snap = tab
+        .current_snap
+        .filter(|snap| snap.point_type == crate::model::snap::SnapPointType::Tracking)
+        .map(|snap| snap.position);
+    draw_tracking_points(&ctx, &tab.executor.tracking_points, tracking_snap);
+
+    // Cursor and Preview, both at the resolved cursor a click would commit
+    if let Some(cursor) = tab.cursor {
+        let crosshair = &vm.config.appearance_config.crosshair;
+        let [r, g, b] = crosshair.color;
+        let cross_stroke = egui::Stroke::new(
+            0.5,
+            egui::Color32::from_rgb(r, g, b).gamma_multiply(crosshair.opacity.clamp(0.0, 1.0)),
+        );
+        let effective_screen = ctx.to_screen(cursor.constrained);
+        let (half_w, half_h) = crosshair.half_extent(rect.width(), rect.height());
+        painter.line_segment(
+            [
+                effective_screen - egui::vec2(half_w, 0.0),
+                effective_screen + egui::vec2(half_w, 0.0),
+            ],
+            cross_stroke,
+        );
+        painter.line_segment(
+            [
+                effective_screen - egui::vec2(0.0, half_h),
+                effective_screen + egui::vec2(0.0, half_h),
+            ],
+            cross_stroke,
+        );
 
-            let cross_stroke = egui::Stroke::new(
-                0.5,
-                egui::Color32::from_rgba_unmultiplied(200, 200, 200, 100),
+        // Pick box: what a click here can hit
+        if crosshair.show_pick_box {
+            let pick_box = egui::Rect::from_center_size(
+                ctx.to_screen(cursor.snapped),
+                egui::Vec2::splat(vm.config.snap_config.pick_tolerance * 2.0),
             );
-            let effective_screen = ctx.to_screen(effective_cad);
-            painter.line_segment(
-                [
-                    egui::pos2(rect.min.x, effective_screen.y),
-                    egui::pos2(rect.max.x, effective_screen.y),
+            painter.rect_stroke(pick_box, 0.0, cross_stroke);
+        }
+
+        if let Some(snap) = &tab.current_snap {
+            let snap_screen = ctx.to_screen(snap.position);
+            let snap_color = match snap.point_type {
+                crate::model::snap::SnapPointType::Endpoint => egui::Color32::GREEN,
+                crate::model::snap::SnapPointType::Center => egui::Color32::YELLOW,
+                crate::model::snap::SnapPointType::Insertion => egui::Color32::KHAKI,
+                crate::model::snap::SnapPointType::Corner => egui::Color32::LIGHT_GREEN,
+                crate::model::snap::SnapPointType::Intersection => egui::Color32::RED,
+                crate::model::snap::SnapPointType::ApparentIntersection => {
+                    egui::Color32::from_rgb(255, 140, 140)
+                }
+                crate::model::snap::SnapPointType::Midpoint => egui::Color32::LIGHT_BLUE,
+                crate::model::snap::SnapPointType::AxisLine => egui::Color32::from_rgb(255, 128, 0),
+                crate::model::snap::SnapPointType::Grid => egui::Color32::from_rgb(200, 200, 200),
+                crate::model::snap::SnapPointType::Tracking => TRACKING_COLOR,
+            };
+            let size = 8.0;
+            painter.add(egui::Shape::convex_polygon(
+                vec![
+                    egui::pos2(snap_screen.x, snap_screen.y - size),
+                    egui::pos2(snap_screen.x + size, snap_screen.y),
+                    egui::pos2(snap_screen.x, snap_screen.y + size),
+                    egui::pos2(snap_screen.x - size, snap_screen.y),
                 ],
-                cross_stroke,
+                snap_color.linear_multiply(0.3),
+                egui::Stroke::new(2.0, snap_color),
+            ));
+        }
+
+        if !tab.preview_paused
+            && let Some((cmd, points)) = tab.executor.get_preview_points()
+        {
+            cmd.draw_preview(&ctx, points, cursor.constrained);
+        }
+        if let Some(CalculatorPick::Distance { first: Some(first) }) = vm.calculator_pick {
+            preview::draw_line_to_cursor(&ctx, first, cursor.snapped);
+        }
+        // The markup stroke being drawn, as it will be placed
+        if vm.markup.stroke.len() >= 2 {
+            let highlighter = vm.markup.tool == MarkupTool::Highlighter;
+            Sketch::new(vm.markup.stroke.clone(), highlighter).render(
+                &ctx,
+                &tab.model.definitions,
+                false,
+                false,
             );
+        }
+
+        // Smart alignment guides
+        let guide_stroke = egui::Stroke::new(1.0, egui::Color32::from_rgb(255, 0, 255));
+        for guide in &tab.alignment_guides {
             painter.line_segment(
-                [
-                    egui::pos2(effective_screen.x, rect.min.y),
-                    egui::pos2(effective_screen.x, rect.max.y),
-                ],
-                cross_stroke,
+                [ctx.to_screen(guide.from), ctx.to_screen(guide.to)],
+                guide_stroke,
             );
+        }
 
-            // Re-borrow active tab just for snap/preview data?
-            // We called vm.get_effective_position which uses active_tab().
-            // So we need to use 'tab' for rendering again. 'tab' is immutable borrow.
-            let tab = vm.active_tab();
-
-            if let Some(snap) = &tab.current_snap {
-                let snap_screen = ctx.to_screen(snap.position);
-                let snap_color = match snap.point_type {
-                    crate::model::snap::SnapPointType::Endpoint => egui::Color32::GREEN,
-                    crate::model::snap::SnapPointType::Center => egui::Color32::YELLOW,
-                    crate::model::snap::SnapPointType::Corner => egui::Color32::LIGHT_GREEN,
-                    crate::model::snap::SnapPointType::Intersection => egui::Color32::RED,
-                    crate::model::snap::SnapPointType::Midpoint => egui::Color32::LIGHT_BLUE,
-                    crate::model::snap::SnapPointType::AxisLine => {
-                        egui::Color32::from_rgb(255, 128, 0)
-                    }
-                    crate::model::snap::SnapPointType::Grid => {
-                        egui::Color32::from_rgb(200, 200, 200)
-                    }
-                };
-                let size = 8.0;
-                painter.add(egui::Shape::convex_polygon(
-                    vec![
-                        egui::pos2(snap_screen.x, snap_screen.y - size),
-                        egui::pos2(snap_screen.x + size, snap_screen.y),
-                        egui::pos2(snap_screen.x, snap_screen.y + size),
-                        egui::pos2(snap_screen.x - size, snap_screen.y),
-                    ],
-                    snap_color.linear_multiply(0.3),
-                    egui::Stroke::new(2.0, snap_color),
-                ));
-            }
+        // Coordinate readout
+        painter.text(
+            egui::pos2(rect.max.x - 10.0, rect.max.y - 10.0),
+            egui::Align2::RIGHT_BOTTOM,
+            format!(
+                "X: {:.2}  Y: {:.2}",
+                cursor.constrained.x, cursor.constrained.y
+            ),
+            egui::FontId::monospace(12.0),
+            egui::Color32::from_rgba_unmultiplied(200, 200, 200, 180),
+        );
+    }
+
+    // Selection toolbar (hidden while panning, dragging or running a command)
+    let show_selection_toolbar = vm.config.gui_config.show_selection_toolbar
+        && !is_panning
+        && !dragged
+        && tab.handle_drag.is_none()
+        && !tab.executor.is_active()
+        && !tab.selection_manager.selected_ids.is_empty();
+    if show_selection_toolbar {
+        crate::view::ui::selection_toolbar::render_selection_toolbar(ui, vm, &ctx, rect);
+    }
 
-            if let Some((cmd, points)) = tab.executor.get_preview_points() {
-                let current_cad = if let Some(&last_point) = points.last() {
-                    cmd.constrain_point(effective_cad, Some(last_point), modifiers)
-                } else {
-                    effective_cad
-                };
-                cmd.draw_preview(&ctx, points, current_cad);
+    // Dynamic input next to the cursor
+    if !is_panning {
+        crate::view::ui::dynamic_input::render_dynamic_input(ui, vm, &ctx);
+    }
+
+    // Annotation being retyped in place, and the path of an edited group
+    crate::view::ui::text_edit::render_text_edit(ui, vm, &ctx);
+    crate::view::ui::breadcrumb::render_group_breadcrumb(ui, vm, rect);
+
+    // Context menu with the quick transforms, flags and group editing
+    if !command_was_active && !vm.active_tab().selection_manager.selected_ids.is_empty() {
+        let keys = vm.config.gui_config.quick_transform_keys.clone();
+        let selection = &vm.active_tab().selection_manager;
+        let group = selection
+            .last_interacted_id
+            .or_else(|| selection.selected_ids.iter().next().copied())
+            .and_then(|id| vm.enclosing_group(id));
+        response.context_menu(|ui| {
+            for transform in QuickTransform::ALL {
+                if ui.button(quick_tooltip(transform, &keys)).clicked() {
+                    vm.apply_quick_transform(transform);
+                    ui.close_menu();
+                }
             }
-        }
+            ui.separator();
+            if let Some(flag) = crate::view::hierarchy::flag_menu(ui) {
+                vm.flag_selection(flag);
+            }
+            if let Some(group) = group {
+                ui.separator();
+                if ui.button("Enter Group").clicked() {
+                    vm.enter_group(group);
+                    ui.close_menu();
+                }
+            }
+        });
     }
 
     // Pan indicator
@@ -318,3 +605,144 @@ pub fn render_canvas(ui: &mut egui::Ui, vm: &mut CadViewModel) {
         egui::Color32::from_rgba_unmultiplied(200, 200, 200, 180),
     );
 }
+
+/// R, Q and E act on the running command (reverse an arc, cycle the
+/// placement anchor, rotate the placement) while the mouse is drawing.
+/// Call before the terminal is shown: a key used here does not reach it,
+/// and after a typed point the letters go to the terminal as options.
+pub fn capture_placement_keys(ctx: &egui::Context, vm: &mut CadViewModel) {
+    let Some(tab) = vm.tabs.get(vm.active_tab_index) else {
+        return;
+    };
+    if tab.cursor.is_none()
+        || tab.preview_paused
+        || !vm.command_input.is_empty()
+        || ctx.input(|i| i.modifiers.ctrl || i.modifiers.shift || i.modifiers.alt)
+    {
+        return;
+    }
+    let executor = &mut vm.active_tab_mut().executor;
+    let used = if ctx.input(|i| i.key_pressed(egui::Key::R)) {
+        executor.toggle_arc_direction()
+    } else if ctx.input(|i| i.key_pressed(egui::Key::Q)) {
+        executor.cycle_placement_anchor()
+    } else if ctx.input(|i| i.key_pressed(egui::Key::E)) {
+        executor.rotate_placement()
+    } else {
+        false
+    };
+    if used {
+        ctx.input_mut(|i| i.events.retain(|e| !matches!(e, egui::Event::Text(_))));
+    }
+}
+
+/// Hues of the shape types in the drawing, in the canvas's lower left corner
+fn draw_type_legend(painter: &egui::Painter, rect: egui::Rect, model: &CadModel) {
+    fn collect(entities: &[Entity], types: &mut HashSet<&'static str>) {
+        for entity in entities {
+            types.insert(entity.shape.type_name());
+            collect(&entity.children, types);
+        }
+    }
+    let mut present = HashSet::new();
+    collect(&model.entities, &mut present);
+    let rows: Vec<_> = TYPE_PALETTE
+        .iter()
+        .filter(|(name, _)| present.contains(name))
+        .collect();
+    if rows.is_empty() {
+        return;
+    }
+
+    const ROW: f32 = 16.0;
+    let size = egui::vec2(110.0, rows.len() as f32 * ROW + 8.0);
+    let frame = egui::Rect::from_min_size(
+        egui::pos2(rect.min.x + 10.0, rect.max.y - 10.0 - size.y),
+        size,
+    );
+    painter.rect_filled(frame, 4.0, egui::Color32::from_black_alpha(170));
+    for (i, (name, color)) in rows.into_iter().enumerate() {
+        let y = frame.min.y + 4.0 + ROW * (i as f32 + 0.5);
+        let swatch =
+            egui::Rect::from_center_size(egui::pos2(frame.min.x + 12.0, y), egui::vec2(10.0, 10.0));
+        painter.rect_filled(swatch, 2.0, *color);
+        painter.text(
+            egui::pos2(frame.min.x + 24.0, y),
+            egui::Align2::LEFT_CENTER,
+            *name,
+            egui::FontId::proportional(12.0),
+            egui::Color32::from_gray(220),
+        );
+    }
+}
+
+/// Ids of an entity and everything grouped under it
+fn collect_tree_ids(entity: &Entity, out: &mut HashSet<u64>) {
+    out.insert(entity.id);
+    for child in &entity.children {
+        collect_tree_ids(child, out);
+    }
+}
+
+/// Color of tracking points and the lines tracked from them
+const TRACKING_COLOR: egui::Color32 = egui::Color32::from_rgb(120, 255, 200);
+
+/// Tracking points as small crosses, with dashed lines from those the
+/// snapped cursor lines up with
+fn draw_tracking_points(ctx: &DrawContext, points: &[Vector2], snap: Option<Vector2>) {
+    let stroke = egui::Stroke::new(1.0, TRACKING_COLOR);
+    let arm = 5.0;
+    for &point in points {
+        let at = ctx.to_screen(point);
+        ctx.painter.line_segment(
+            [at - egui::vec2(arm, 0.0), at + egui::vec2(arm, 0.0)],
+            stroke,
+        );
+        ctx.painter.line_segment(
+            [at - egui::vec2(0.0, arm), at + egui::vec2(0.0, arm)],
+            stroke,
+        );
+
+        if let Some(snap) = snap
+            && snap.dist(point) > 1e-4
+            && ((snap.x - point.x).abs() < 1e-4 || (snap.y - point.y).abs() < 1e-4)
+        {
+            ctx.painter.extend(egui::Shape::dashed_line(
+                &[at, ctx.to_screen(snap)],
+                stroke,
+                6.0,
+                4.0,
+            ));
+        }
+    }
+}
+
+/// Dashed outline of the export region, labeled, with a grip per corner
+fn draw_export_region(ctx: &DrawContext, min: Vector2, max: Vector2, hovered: Option<egui::Pos2>) {
+    const REGION_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 170, 60);
+    let stroke = egui::Stroke::new(1.0, REGION_COLOR);
+    let corners = region_corners(min, max).map(|c| ctx.to_screen(c));
+    let outline = [corners[0], corners[1], corners[2], corners[3], corners[0]];
+    ctx.painter
+        .extend(egui::Shape::dashed_line(&outline, stroke, 8.0, 4.0));
+    ctx.painter.text(
+        corners[3] + egui::vec2(0.0, -4.0),
+        egui::Align2::LEFT_BOTTOM,
+        format!("Export Region {:.0} x {:.0}", max.x - min.x, max.y - min.y),
+        egui::FontId::proportional(12.0),
+        REGION_COLOR,
+    );
+
+    for at in corners {
+        let is_hovered = hovered.is_some_and(|p| {
+            (p.x - at.x).abs() <= HANDLE_SIZE && (p.y - at.y).abs() <= HANDLE_SIZE
+        });
+        let fill = if is_hovered {
+            REGION_COLOR
+        } else {
+            egui::Color32::from_gray(20)
+        };
+        let square = egui::Rect::from_center_size(at, egui::Vec2::splat(HANDLE_SIZE));
+        ctx.painter.rect(square, 0.0, fill, stroke);
+    }
+}
@@ -12,24 +12,111 @@ pub use ui::terminal;
 pub use ui::toolbar;
 pub use ui::topmenu;
 
-use crate::viewmodel::CadViewModel;
+use crate::model::recovery::{self, CrashFile};
+use crate::viewmodel::{CadViewModel, LeftPanelTab, LibraryTransfer};
 use eframe::egui;
 use mugin_widgets::panel;
+use mugin_widgets::window::{Modal, ModalResponse};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::sync::Arc;
 
 pub struct CadApp {
     pub view_model: CadViewModel,
 }
 
+/// eframe application id, also naming the app's data directory
+pub const APP_ID: &str = "rust_cad";
+
+/// Shared command history file inside the app's data directory
+const COMMAND_HISTORY_FILE: &str = "command_history.txt";
+
+/// Saved drawing templates, inside the app's data directory
+const TEMPLATES_DIR: &str = "templates";
+
+/// Shared structural type library inside the app's data directory
+const STRUCTURE_LIBRARY_FILE: &str = "structure_library.json";
+
+/// Symbols imported from drawings, inside the app's data directory
+const SYMBOL_LIBRARY_FILE: &str = "symbols.json";
+
+/// Tabs saved by a crash and the crash log, inside the app's data directory
+const RECOVERY_DIR: &str = "recovery";
+
+/// Storage key for the recent projects list
+const RECENT_PROJECTS_KEY: &str = "recent_projects";
+
+/// Storage key for the side panel layout
+const PANEL_LAYOUT_KEY: &str = "panel_layout";
+
+/// Egui ids of the side panels
+pub const LEFT_PANEL_ID: &str = "side_panel";
+pub const RIGHT_PANEL_ID: &str = "inspector";
+
 impl CadApp {
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        Self {
-            view_model: CadViewModel::new(),
+    /// Create the app, opening `files` (from the command line) in tabs
+    pub fn new(cc: &eframe::CreationContext<'_>, files: Vec<PathBuf>) -> Self {
+        let mut view_model = CadViewModel::new();
+        if let Some(storage) = cc.storage
+            && let Some(recent) = eframe::get_value(storage, RECENT_PROJECTS_KEY)
+        {
+            view_model.recent_projects = recent;
         }
+        if let Some(storage) = cc.storage
+            && let Some(panels) = eframe::get_value(storage, PANEL_LAYOUT_KEY)
+        {
+            view_model.config.gui_config.panels = panels;
+        }
+        view_model.global_history_path =
+            eframe::storage_dir(APP_ID).map(|dir| dir.join(COMMAND_HISTORY_FILE));
+        view_model.templates_dir = eframe::storage_dir(APP_ID).map(|dir| dir.join(TEMPLATES_DIR));
+        view_model.refresh_templates();
+        view_model.structure_library_path =
+            eframe::storage_dir(APP_ID).map(|dir| dir.join(STRUCTURE_LIBRARY_FILE));
+        view_model.symbol_library_path =
+            eframe::storage_dir(APP_ID).map(|dir| dir.join(SYMBOL_LIBRARY_FILE));
+        view_model.load_symbols();
+        view_model.recovery_dir = eframe::storage_dir(APP_ID).map(|dir| dir.join(RECOVERY_DIR));
+        if let Some(dir) = view_model.recovery_dir.clone() {
+            recovery::install_panic_hook(dir);
+        }
+        view_model.load_crash_files();
+        for path in files {
+            view_model.open_project(&path);
+        }
+        Self { view_model }
     }
 }
 
 impl eframe::App for CadApp {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(
+            storage,
+            RECENT_PROJECTS_KEY,
+            &self.view_model.recent_projects,
+        );
+        eframe::set_value(
+            storage,
+            PANEL_LAYOUT_KEY,
+            &self.view_model.config.gui_config.panels,
+        );
+        self.view_model.flush_input_history(true);
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // The panic hook has logged the panic by the time it gets here;
+        // save what the user was working on before the app goes down
+        let frame = panic::catch_unwind(AssertUnwindSafe(|| self.update_frame(ctx)));
+        if let Err(payload) = frame {
+            let saved = self.view_model.save_crash_files();
+            eprintln!("Saved {} tab(s) for recovery", saved);
+            panic::resume_unwind(payload);
+        }
+    }
+}
+
+impl CadApp {
+    fn update_frame(&mut self, ctx: &egui::Context) {
         // Dark theme from config
         let mut visuals = egui::Visuals::dark();
         let bg_color = self.view_model.config.appearance_config.background_color;
@@ -37,6 +124,23 @@ impl eframe::App for CadApp {
             egui::Color32::from_rgb(bg_color[0], bg_color[1], bg_color[2]);
         ctx.set_visuals(visuals);
 
+        // Pick up finished background saves, loads and exports
+        self.view_model.poll_background();
+        // Journal what the last frame changed
+        self.view_model.record_journal();
+        self.view_model.sync_input_history();
+        self.view_model.collect_command_output();
+
+        // Frames are drawn on input; when idle, only running animations
+        // and pending work ask for more. Progress, toasts and tooltips
+        // schedule their own.
+        let dt = ctx.input(|i| i.stable_dt);
+        if self.view_model.tick(dt) || self.view_model.config.gui_config.continuous_repaint {
+            ctx.request_repaint();
+        } else if let Some(delay) = self.view_model.history_flush_due_in() {
+            ctx.request_repaint_after(delay);
+        }
+
         // Reset per-frame focus flags
         self.view_model.inspector_renaming = false;
         self.view_model.hierarchy_renaming = false;
@@ -48,6 +152,12 @@ impl eframe::App for CadApp {
             .active_tab_mut()
             .executor
             .set_active_types(col_type, beam_type);
+        let dim_style = self.view_model.config.dimension_style.clone();
+        self.view_model.active_tab_mut().executor.dimension_style = dim_style;
+        let limits = self.view_model.config.geometry_limits.clone();
+        self.view_model.active_tab_mut().executor.limits = limits;
+        let symbols = Arc::clone(&self.view_model.symbols);
+        self.view_model.active_tab_mut().executor.symbols = symbols;
 
         // Render Settings Window if open
         if self.view_model.show_settings_window {
@@ -69,18 +179,106 @@ impl eframe::App for CadApp {
             ui::structure::beams::render_beam_manager(ctx, &mut self.view_model);
         }
 
+        // Render Storey Manager
+        if self.view_model.storey_manager_open {
+            ui::structure::storeys::render_storey_manager(ctx, &mut self.view_model);
+        }
+
         // Render Export Window if open
+        self.view_model.update_export_region_pick();
         {
+            let busy = self.view_model.is_busy();
             let CadViewModel {
                 export_window,
                 tabs,
                 active_tab_index,
+                color_mode,
                 ..
             } = &mut self.view_model;
             if *active_tab_index < tabs.len() {
                 let model = &tabs[*active_tab_index].model;
-                export_window.show(ctx, model);
+                if let Some(path) = export_window.show(ctx, model, busy, *color_mode) {
+                    let settings = export_window.settings.clone();
+                    self.view_model.start_pdf_export(settings, path);
+                }
+            }
+        }
+
+        // Render Batch Export Window if open
+        if self.view_model.batch_export_window.open {
+            let vm = &mut self.view_model;
+            let tabs: Vec<(u64, String)> = vm
+                .tabs
+                .iter()
+                .map(|tab| (tab.id, tab.name.clone()))
+                .collect();
+            let storeys: Vec<(u64, String)> = vm
+                .active_tab()
+                .model
+                .storeys
+                .storeys
+                .iter()
+                .map(|storey| (storey.id, storey.name.clone()))
+                .collect();
+            let project = vm.active_tab().name.clone();
+            let busy = vm.is_busy();
+            let settings = &vm.export_window.settings;
+            if let Some(request) = vm
+                .batch_export_window
+                .show(ctx, &tabs, &storeys, &project, settings, busy)
+            {
+                vm.start_batch_export(request);
             }
+            if std::mem::take(&mut vm.batch_export_window.edit_settings) {
+                vm.export_window.open = true;
+            }
+        }
+
+        // Render Data Export Window if open
+        if self.view_model.data_export_window.open {
+            let tab = self.view_model.active_tab();
+            let default_name = tab.name.clone();
+            let has_selection = !tab.selection_manager.selected_ids.is_empty();
+            if let Some(path) =
+                self.view_model
+                    .data_export_window
+                    .show(ctx, &default_name, has_selection)
+            {
+                let options = self.view_model.data_export_window.options.clone();
+                self.view_model.export_data(&options, path);
+            }
+        }
+
+        // Render Rooms report if open
+        if self.view_model.rooms_report_window.open {
+            ui::rooms_report::render_rooms_report(ctx, &mut self.view_model);
+        }
+
+        if self.view_model.calculator_window.open {
+            ui::calculator::render_calculator(ctx, &mut self.view_model);
+        }
+
+        if self.view_model.markup_window.open {
+            ui::markup::render_markup(ctx, &mut self.view_model);
+        }
+
+        if self.view_model.compare_window.open {
+            ui::compare::render_compare(ctx, &mut self.view_model);
+        }
+
+        // Render Find & Replace Window if open
+        if self.view_model.find_replace_window.open {
+            ui::find_replace::render_find_replace_window(ctx, &mut self.view_model);
+        }
+
+        // Command palette, on top of everything and taking the keyboard
+        if self.view_model.command_palette.open {
+            ui::command_palette::render_command_palette(ctx, &mut self.view_model);
+        } else {
+            // While drawing with the mouse, numbers typed during a command go
+            // to the fields at the cursor and R/Q/E to the command itself
+            ui::dynamic_input::capture_typing(ctx, &self.view_model);
+            canvas::capture_placement_keys(ctx, &mut self.view_model);
         }
 
         // Top Menu
@@ -102,46 +300,69 @@ impl eframe::App for CadApp {
         // Left Toolbar Panel
         toolbar::render_toolbar(ctx, &mut self.view_model);
 
-        // Left Panel (Hierarchy & Layers)
-        panel::left_panel("side_panel", 250.0, ctx, |ui| {
-            ui.horizontal(|ui| {
-                ui.selectable_value(
-                    &mut self.view_model.active_left_panel_tab,
-                    crate::viewmodel::LeftPanelTab::Hierarchy,
-                    "Hierarchy",
-                );
-                ui.selectable_value(
-                    &mut self.view_model.active_left_panel_tab,
-                    crate::viewmodel::LeftPanelTab::Layers,
-                    "Layers",
-                );
-            });
-            ui.separator();
+        // Left Panel (Hierarchy, Layers & References)
+        let mut panels = self.view_model.config.gui_config.panels.clone();
+        if panels.show_left {
+            panel::left_panel_collapsible(
+                LEFT_PANEL_ID,
+                "Explorer",
+                &mut panels.left_width,
+                &mut panels.left_collapsed,
+                ctx,
+                |ui| {
+                    ui.horizontal(|ui| {
+                        ui.selectable_value(
+                            &mut panels.left_tab,
+                            LeftPanelTab::Hierarchy,
+                            "Hierarchy",
+                        );
+                        ui.selectable_value(&mut panels.left_tab, LeftPanelTab::Layers, "Layers");
+                        ui.selectable_value(
+                            &mut panels.left_tab,
+                            LeftPanelTab::References,
+                            "References",
+                        );
+                    });
+                    ui.separator();
 
-            match self.view_model.active_left_panel_tab {
-                crate::viewmodel::LeftPanelTab::Hierarchy => {
-                    hierarchy::render_hierarchy(ui, &mut self.view_model);
-                }
-                crate::viewmodel::LeftPanelTab::Layers => {
-                    ui::layer_panel::render_layer_panel(ui, &mut self.view_model);
-                }
-            }
-        });
+                    match panels.left_tab {
+                        LeftPanelTab::Hierarchy => {
+                            hierarchy::render_hierarchy(ui, &mut self.view_model);
+                        }
+                        LeftPanelTab::Layers => {
+                            ui::layer_panel::render_layer_panel(ui, &mut self.view_model);
+                        }
+                        LeftPanelTab::References => {
+                            ui::reference_panel::render_reference_panel(ui, &mut self.view_model);
+                        }
+                    }
+                },
+            );
+        }
 
         // Inspector Panel Logic
-        let show_inspector = self.view_model.config.gui_config.show_inspector_always
-            || !self
-                .view_model
-                .active_tab()
-                .selection_manager
-                .selected_ids
-                .is_empty();
+        let show_inspector = panels.show_right
+            && (self.view_model.config.gui_config.show_inspector_always
+                || !self
+                    .view_model
+                    .active_tab()
+                    .selection_manager
+                    .selected_ids
+                    .is_empty());
 
         if show_inspector {
-            panel::right_panel("inspector", 250.0, ctx, |ui| {
-                inspector::render_inspector(ui, &mut self.view_model);
-            });
+            panel::right_panel_collapsible(
+                RIGHT_PANEL_ID,
+                "Inspector",
+                &mut panels.right_width,
+                &mut panels.right_collapsed,
+                ctx,
+                |ui| {
+                    inspector::render_inspector(ui, &mut self.view_model);
+                },
+            );
         }
+        self.view_model.config.gui_config.panels = panels;
 
         egui::TopBottomPanel::bottom("terminal")
             .resizable(true)
@@ -160,30 +381,213 @@ impl eframe::App for CadApp {
         egui::CentralPanel::default()
             .frame(egui::Frame::none().fill(egui::Color32::from_rgb(15, 15, 15)))
             .show(ctx, |ui| {
-                canvas::render_canvas(ui, &mut self.view_model);
+                if self.view_model.show_welcome {
+                    ui::welcome::render_welcome(ui, &mut self.view_model);
+                } else {
+                    canvas::render_canvas(ui, &mut self.view_model);
+                }
             });
 
+        ui::progress::render_background_progress(ctx, &mut self.view_model);
+        ui::toast::render_toasts(ctx, &mut self.view_model);
+
         // Layer Change Prompt Modal
-        if self.view_model.layer_change_prompt.is_some() {
-            egui::Window::new("Layer Change")
-                .collapsible(false)
-                .resizable(false)
-                .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
-                .show(ctx, |ui| {
+        let mut prompt_open = self.view_model.layer_change_prompt.is_some();
+        let response =
+            Modal::new("Layer Change")
+                .enter_accepts(false)
+                .show(ctx, &mut prompt_open, |ui, _| {
                     ui.label("Apply layer change to all children entities as well?");
                     ui.add_space(10.0);
                     ui.horizontal(|ui| {
                         if ui.button("Yes (Recursive)").clicked() {
-                            self.view_model.apply_layer_change(true);
+                            ModalResponse::Accepted(true)
+                        } else if ui.button("No (Only Parent)").clicked() {
+                            ModalResponse::Accepted(false)
+                        } else if ui.button("Cancel").clicked() {
+                            ModalResponse::Closed
+                        } else {
+                            ModalResponse::Pending
                         }
-                        if ui.button("No (Only Parent)").clicked() {
-                            self.view_model.apply_layer_change(false);
+                    })
+                    .inner
+                });
+        match response {
+            ModalResponse::Pending => {}
+            ModalResponse::Closed => self.view_model.cancel_layer_change(),
+            ModalResponse::Accepted(recursive) => self.view_model.apply_layer_change(recursive),
+        }
+
+        // Layer Template Conflict Modal
+        let mut import_open = self.view_model.layer_import_prompt.is_some();
+        let conflict = self
+            .view_model
+            .layer_import_prompt
+            .as_ref()
+            .and_then(|pending| pending.conflicts.first().cloned());
+        let apply_all_id = egui::Id::new("layer_import_apply_all");
+        let response = Modal::new("Layer Template").enter_accepts(false).show(
+            ctx,
+            &mut import_open,
+            |ui, _| {
+                let Some(conflict) = conflict else {
+                    return ModalResponse::Closed;
+                };
+                ui.label(format!(
+                    "Layer \"{}\" already exists with a different color.",
+                    conflict.name
+                ));
+                ui.add_space(6.0);
+                ui.horizontal(|ui| {
+                    ui.label("Existing:");
+                    ui.colored_label(conflict.existing, "⬛");
+                    ui.label("Template:");
+                    ui.colored_label(conflict.template, "⬛");
+                });
+                ui.add_space(6.0);
+                let mut apply_all = ui.data(|d| d.get_temp(apply_all_id).unwrap_or(false));
+                ui.checkbox(&mut apply_all, "Apply to all conflicts");
+                ui.data_mut(|d| d.insert_temp(apply_all_id, apply_all));
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Keep Existing").clicked() {
+                        ModalResponse::Accepted((false, apply_all))
+                    } else if ui.button("Use Template").clicked() {
+                        ModalResponse::Accepted((true, apply_all))
+                    } else if ui.button("Cancel").clicked() {
+                        ModalResponse::Closed
+                    } else {
+                        ModalResponse::Pending
+                    }
+                })
+                .inner
+            },
+        );
+        match response {
+            ModalResponse::Pending => {}
+            ModalResponse::Closed => {
+                ctx.data_mut(|d| d.remove::<bool>(apply_all_id));
+                self.view_model.cancel_layer_import();
+            }
+            ModalResponse::Accepted((use_template, apply_all)) => {
+                if apply_all {
+                    ctx.data_mut(|d| d.remove::<bool>(apply_all_id));
+                }
+                self.view_model
+                    .resolve_layer_conflict(use_template, apply_all);
+            }
+        }
+
+        // Structure Library Conflict Modal
+        let mut merge_open = self.view_model.library_merge_prompt.is_some();
+        let pending = self
+            .view_model
+            .library_merge_prompt
+            .as_ref()
+            .and_then(|pending| {
+                let conflict = pending.conflicts.first()?.clone();
+                Some((pending.transfer, conflict))
+            });
+        let apply_all_id = egui::Id::new("library_merge_apply_all");
+        let response = Modal::new("Structure Library").enter_accepts(false).show(
+            ctx,
+            &mut merge_open,
+            |ui, _| {
+                let Some((transfer, conflict)) = pending else {
+                    return ModalResponse::Closed;
+                };
+                let (existing, incoming) = match transfer {
+                    LibraryTransfer::Import => ("project", "Library"),
+                    LibraryTransfer::Export => ("library", "Project"),
+                };
+                ui.label(format!(
+                    "{} \"{}\" already exists in the {} with different properties.",
+                    conflict.kind.label(),
+                    conflict.name,
+                    existing
+                ));
+                ui.add_space(6.0);
+                let mut apply_all = ui.data(|d| d.get_temp(apply_all_id).unwrap_or(false));
+                ui.checkbox(&mut apply_all, "Apply to all conflicts");
+                ui.data_mut(|d| d.insert_temp(apply_all_id, apply_all));
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Keep Existing").clicked() {
+                        ModalResponse::Accepted((false, apply_all))
+                    } else if ui.button(format!("Use {}", incoming)).clicked() {
+                        ModalResponse::Accepted((true, apply_all))
+                    } else if ui.button("Cancel").clicked() {
+                        ModalResponse::Closed
+                    } else {
+                        ModalResponse::Pending
+                    }
+                })
+                .inner
+            },
+        );
+        match response {
+            ModalResponse::Pending => {}
+            ModalResponse::Closed => {
+                ctx.data_mut(|d| d.remove::<bool>(apply_all_id));
+                self.view_model.cancel_library_merge();
+            }
+            ModalResponse::Accepted((use_incoming, apply_all)) => {
+                if apply_all {
+                    ctx.data_mut(|d| d.remove::<bool>(apply_all_id));
+                }
+                self.view_model
+                    .resolve_library_conflict(use_incoming, apply_all);
+            }
+        }
+
+        // Crash Recovery Modal
+        let mut recovery_open = !self.view_model.crash_files.is_empty();
+        let files = self.view_model.crash_files.clone();
+        let response = Modal::new("Recover Drawings").enter_accepts(false).show(
+            ctx,
+            &mut recovery_open,
+            |ui, _| {
+                ui.label("MuginCAD closed unexpectedly. These drawings were saved:");
+                ui.add_space(6.0);
+                let mut choice = ModalResponse::Pending;
+                for file in &files {
+                    ui.horizontal(|ui| {
+                        ui.label(&file.name);
+                        if ui.button("Open").clicked() {
+                            choice = ModalResponse::Accepted((Some(file.clone()), true));
                         }
-                        if ui.button("Cancel").clicked() {
-                            self.view_model.cancel_layer_change();
+                        if ui.button("Discard").clicked() {
+                            choice = ModalResponse::Accepted((Some(file.clone()), false));
                         }
                     });
+                }
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Open All").clicked() {
+                        choice = ModalResponse::Accepted((None, true));
+                    } else if ui.button("Discard All").clicked() {
+                        choice = ModalResponse::Accepted((None, false));
+                    } else if ui.button("Later").clicked() {
+                        choice = ModalResponse::Closed;
+                    }
                 });
+                choice
+            },
+        );
+        match response {
+            ModalResponse::Pending => {}
+            // Asked again at the next start
+            ModalResponse::Closed => self.view_model.crash_files.clear(),
+            ModalResponse::Accepted((file, open)) => {
+                let chosen: Vec<CrashFile> = file.map_or(files, |file| vec![file]);
+                for file in &chosen {
+                    if open {
+                        self.view_model.open_crash_file(file);
+                    } else {
+                        self.view_model.discard_crash_file(file);
+                    }
+                }
+            }
         }
     }
 }
@@ -1,8 +1,14 @@
 use crate::model::Vector2;
+use crate::model::config::{DetailThresholds, FlooringStyle};
+use crate::model::dimension::DimensionStyle;
+use crate::model::layer::LineStyle;
 use glam::Affine2;
 // use crate::view::viewport::Viewport;
 use eframe::egui;
 
+/// Color for entities with neither an explicit nor a layer color
+pub const DEFAULT_ENTITY_COLOR: egui::Color32 = crate::model::layer::DEFAULT_LAYER_COLOR;
+
 /// Context object passed to rendering functions
 pub struct DrawContext<'a> {
     pub painter: &'a egui::Painter,
@@ -11,6 +17,19 @@ pub struct DrawContext<'a> {
     pub offset: Vector2,
     pub screen_center: Vector2,
     pub transform: Affine2,
+    pub dim_style: &'a DimensionStyle,
+    /// Base color of the entity being drawn (its own color or its layer's)
+    pub color: egui::Color32,
+    /// Draw the entity emphasized, e.g. as the source of the current snap
+    pub emphasized: bool,
+    /// Dash pattern of the entity's outline
+    pub line_style: LineStyle,
+    /// Outline width in screen pixels
+    pub stroke_width: f32,
+    /// Sizes below which text and structural detail are simplified
+    pub detail: DetailThresholds,
+    /// Fill of flooring slabs
+    pub flooring: FlooringStyle,
 }
 
 impl<'a> DrawContext<'a> {
@@ -27,6 +46,56 @@ impl<'a> DrawContext<'a> {
         )
     }
 
+    /// Whether text `world_height` tall is big enough on screen to draw
+    pub fn text_visible(&self, world_height: f32) -> bool {
+        world_height * self.zoom >= self.detail.min_text_px
+    }
+
+    /// Whether a structural member `world_width` wide gets full detail
+    pub fn structure_detailed(&self, world_width: f32) -> bool {
+        world_width * self.zoom >= self.detail.min_structure_px
+    }
+
+    /// Stroke a screen-space path in the entity's line style
+    pub fn stroke_path(&self, points: &[egui::Pos2], closed: bool, stroke: egui::Stroke) {
+        let mut path = points.to_vec();
+        if closed && let Some(&first) = points.first() {
+            path.push(first);
+        }
+        let pattern = self.line_style.pattern();
+        if pattern.is_empty() {
+            self.painter.add(egui::Shape::line(path, stroke));
+            return;
+        }
+        let (dashes, gaps): (Vec<f32>, Vec<f32>) = pattern
+            .iter()
+            .map(|(dash, gap)| (dash * stroke.width, gap * stroke.width))
+            .unzip();
+        self.painter.extend(egui::Shape::dashed_line_with_offset(
+            &path, stroke, &dashes, &gaps, 0.0,
+        ));
+    }
+
+    /// Fill a screen-space polygon that is star-shaped around `center`,
+    /// e.g. a pie slice wider than 180°
+    pub fn fill_fan(&self, center: egui::Pos2, rim: &[egui::Pos2], color: egui::Color32) {
+        self.painter.add(fan_mesh(center, rim, color));
+    }
+
+    /// Fill screen-space rings by the even-odd rule, so a ring inside
+    /// another cuts a hole
+    pub fn fill_rings(&self, rings: &[Vec<egui::Pos2>], color: egui::Color32) {
+        self.painter.add(even_odd_mesh(rings, color));
+    }
+
+    /// Hatch screen-space rings by the even-odd rule with 45° lines
+    /// `spacing` pixels apart
+    pub fn hatch_rings(&self, rings: &[Vec<egui::Pos2>], spacing: f32, stroke: egui::Stroke) {
+        for segment in hatch_segments(rings, spacing) {
+            self.painter.line_segment(segment, stroke);
+        }
+    }
+
     pub fn to_cad(&self, screen_pos: egui::Pos2) -> Vector2 {
         let zoom = self.zoom;
         let offset = self.offset;
@@ -37,3 +106,91 @@ impl<'a> DrawContext<'a> {
         )
     }
 }
+
+/// Triangle fan from `center` through consecutive `rim` points. Unlike
+/// `Shape::convex_polygon` this stays correct for non-convex outlines
+/// as long as every rim point is visible from the center.
+pub fn fan_mesh(center: egui::Pos2, rim: &[egui::Pos2], color: egui::Color32) -> egui::Mesh {
+    let mut mesh = egui::Mesh::default();
+    mesh.colored_vertex(center, color);
+    for &point in rim {
+        mesh.colored_vertex(point, color);
+    }
+    for i in 1..rim.len() as u32 {
+        mesh.add_triangle(0, i, i + 1);
+    }
+    mesh
+}
+
+/// Edges of all rings, closing each one
+fn ring_edges(rings: &[Vec<egui::Pos2>]) -> impl Iterator<Item = (egui::Pos2, egui::Pos2)> + '_ {
+    rings
+        .iter()
+        .flat_map(|ring| (0..ring.len()).map(move |i| (ring[i], ring[(i + 1) % ring.len()])))
+}
+
+/// Trapezoids between consecutive vertex heights, filling the area inside
+/// an odd number of rings. Works for any outline, holes included.
+pub fn even_odd_mesh(rings: &[Vec<egui::Pos2>], color: egui::Color32) -> egui::Mesh {
+    let mut mesh = egui::Mesh::default();
+    let mut ys: Vec<f32> = rings.iter().flatten().map(|p| p.y).collect();
+    ys.sort_by(f32::total_cmp);
+    ys.dedup();
+
+    for band in ys.windows(2) {
+        let (y0, y1) = (band[0], band[1]);
+        let mid = (y0 + y1) / 2.0;
+        let x_at =
+            |a: egui::Pos2, b: egui::Pos2, y: f32| a.x + (y - a.y) / (b.y - a.y) * (b.x - a.x);
+        let mut crossings: Vec<(f32, f32, f32)> = ring_edges(rings)
+            .filter(|(a, b)| (a.y < mid) != (b.y < mid))
+            .map(|(a, b)| (x_at(a, b, mid), x_at(a, b, y0), x_at(a, b, y1)))
+            .collect();
+        crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+        for pair in crossings.chunks_exact(2) {
+            let (left, right) = (pair[0], pair[1]);
+            let base = mesh.vertices.len() as u32;
+            mesh.colored_vertex(egui::pos2(left.1, y0), color);
+            mesh.colored_vertex(egui::pos2(right.1, y0), color);
+            mesh.colored_vertex(egui::pos2(right.2, y1), color);
+            mesh.colored_vertex(egui::pos2(left.2, y1), color);
+            mesh.add_triangle(base, base + 1, base + 2);
+            mesh.add_triangle(base, base + 2, base + 3);
+        }
+    }
+    mesh
+}
+
+/// 45° hatch lines `spacing` apart, clipped to the area inside an odd
+/// number of rings
+pub fn hatch_segments(rings: &[Vec<egui::Pos2>], spacing: f32) -> Vec<[egui::Pos2; 2]> {
+    // Lines of constant x + y, which run at 45° on screen
+    let key = |p: egui::Pos2| p.x + p.y;
+    let (lo, hi) = rings
+        .iter()
+        .flatten()
+        .fold((f32::MAX, f32::MIN), |(lo, hi), p| {
+            (lo.min(key(*p)), hi.max(key(*p)))
+        });
+    if spacing <= 0.0 || lo > hi {
+        return Vec::new();
+    }
+
+    let mut segments = Vec::new();
+    let mut c = (lo / spacing).ceil() * spacing;
+    while c <= hi {
+        let mut hits: Vec<egui::Pos2> = ring_edges(rings)
+            .filter(|(a, b)| (key(*a) < c) != (key(*b) < c))
+            .map(|(a, b)| {
+                let t = (c - key(a)) / (key(b) - key(a));
+                a + (b - a) * t
+            })
+            .collect();
+        hits.sort_by(|a, b| a.x.total_cmp(&b.x));
+        for pair in hits.chunks_exact(2) {
+            segments.push([pair[0], pair[1]]);
+        }
+        c += spacing;
+    }
+    segments
+}
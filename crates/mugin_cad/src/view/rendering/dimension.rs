@@ -0,0 +1,62 @@
+use crate::model::dimension::DimensionLayout;
+use crate::view::rendering::context::DrawContext;
+use eframe::egui;
+
+/// Font for dimension text, scaled from the style's world-unit text height
+pub fn dimension_font(ctx: &DrawContext) -> egui::FontId {
+    egui::FontId::proportional((ctx.dim_style.text_height * ctx.zoom).clamp(6.0, 96.0))
+}
+
+/// Draw extension lines, the dimension line and both arrowheads
+pub fn draw_dimension_layout(ctx: &DrawContext, layout: &DimensionLayout, color: egui::Color32) {
+    let stroke = egui::Stroke::new(1.0, color);
+
+    for [a, b] in layout.extension_lines {
+        ctx.painter
+            .line_segment([ctx.to_screen(a), ctx.to_screen(b)], stroke);
+    }
+
+    let [a, b] = layout.dimension_line;
+    ctx.painter
+        .line_segment([ctx.to_screen(a), ctx.to_screen(b)], stroke);
+
+    for outline in &layout.arrows {
+        let points: Vec<egui::Pos2> = outline.iter().map(|p| ctx.to_screen(*p)).collect();
+        if ctx.dim_style.arrowhead.is_filled() {
+            ctx.painter.add(egui::Shape::convex_polygon(
+                points,
+                color,
+                egui::Stroke::NONE,
+            ));
+        } else if points.len() >= 2 {
+            ctx.painter
+                .line_segment([points[0], points[1]], egui::Stroke::new(1.5, color));
+        }
+    }
+}
+
+/// Draw a galley centered on `screen_pos` and rotated by `angle` (screen radians)
+pub fn draw_rotated_galley(
+    ctx: &DrawContext,
+    screen_pos: egui::Pos2,
+    galley: std::sync::Arc<egui::Galley>,
+    angle: f32,
+    color: egui::Color32,
+) {
+    let size = galley.size();
+    let half_w = size.x / 2.0;
+    let half_h = size.y / 2.0;
+    let (sin_a, cos_a) = angle.sin_cos();
+    let rot_x = half_w * cos_a - half_h * sin_a;
+    let rot_y = half_w * sin_a + half_h * cos_a;
+
+    ctx.painter.add(egui::epaint::TextShape {
+        pos: egui::pos2(screen_pos.x - rot_x, screen_pos.y - rot_y),
+        galley,
+        underline: egui::Stroke::NONE,
+        fallback_color: color,
+        override_text_color: Some(color),
+        opacity_factor: 1.0,
+        angle,
+    });
+}
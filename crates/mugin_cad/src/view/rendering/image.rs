@@ -0,0 +1,122 @@
+use crate::model::ImageRef;
+use crate::model::structure::definitions::StructureDefinitions;
+use crate::view::rendering::context::DrawContext;
+use crate::view::rendering::renderable::Renderable;
+use eframe::egui;
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+/// Decoded image textures, keyed by image source. `None` marks a source
+/// that could not be read, so it is not retried every frame.
+#[derive(Clone, Default)]
+struct TextureCache(HashMap<u64, Option<egui::TextureHandle>>);
+
+fn cache_id() -> egui::Id {
+    egui::Id::new("image_textures")
+}
+
+fn source_key(image: &ImageRef) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    image.path.hash(&mut hasher);
+    image.embedded.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// The image's texture, loaded on first use
+fn texture(ctx: &egui::Context, image: &ImageRef) -> Option<egui::TextureHandle> {
+    let key = source_key(image);
+    if let Some(cached) = ctx.data(|d| {
+        d.get_temp::<TextureCache>(cache_id())
+            .and_then(|cache| cache.0.get(&key).cloned())
+    }) {
+        return cached;
+    }
+
+    let handle = image.load().ok().map(|(width, height, rgba)| {
+        let pixels = egui::ColorImage::from_rgba_unmultiplied([width, height], &rgba);
+        ctx.load_texture(
+            format!("image:{}", image.path),
+            pixels,
+            egui::TextureOptions::LINEAR,
+        )
+    });
+    ctx.data_mut(|d| {
+        d.get_temp_mut_or_default::<TextureCache>(cache_id())
+            .0
+            .insert(key, handle.clone());
+    });
+    handle
+}
+
+/// Drop the cached texture so the image is read again on the next frame
+pub fn reload(ctx: &egui::Context, image: &ImageRef) {
+    let key = source_key(image);
+    ctx.data_mut(|d| {
+        d.get_temp_mut_or_default::<TextureCache>(cache_id())
+            .0
+            .remove(&key);
+    });
+}
+
+impl Renderable for ImageRef {
+    fn render(
+        &self,
+        ctx: &DrawContext,
+        _definitions: &StructureDefinitions,
+        is_selected: bool,
+        is_hovered: bool,
+    ) {
+        let corners = self.corners().map(|c| ctx.to_screen(c));
+
+        match texture(ctx.painter.ctx(), self) {
+            Some(texture) => {
+                let tint = egui::Color32::WHITE.gamma_multiply(self.opacity.clamp(0.0, 1.0));
+                let mut mesh = egui::Mesh::with_texture(texture.id());
+                // Pixel rows run top-down while the drawing's Y runs up
+                let uvs = [
+                    egui::pos2(0.0, 1.0),
+                    egui::pos2(1.0, 1.0),
+                    egui::pos2(1.0, 0.0),
+                    egui::pos2(0.0, 0.0),
+                ];
+                for (pos, uv) in corners.into_iter().zip(uvs) {
+                    mesh.vertices.push(egui::epaint::Vertex {
+                        pos,
+                        uv,
+                        color: tint,
+                    });
+                }
+                mesh.add_triangle(0, 1, 2);
+                mesh.add_triangle(0, 2, 3);
+                ctx.painter.add(mesh);
+            }
+            None => {
+                // Missing or unreadable file: keep the footprint visible
+                let stroke = egui::Stroke::new(1.0, egui::Color32::from_gray(110));
+                ctx.painter.line_segment([corners[0], corners[2]], stroke);
+                ctx.painter.line_segment([corners[1], corners[3]], stroke);
+                ctx.painter.text(
+                    corners[3] + egui::vec2(4.0, 4.0),
+                    egui::Align2::LEFT_TOP,
+                    format!("Missing image: {}", self.file_name()),
+                    egui::FontId::proportional(12.0),
+                    egui::Color32::from_gray(160),
+                );
+            }
+        }
+
+        let outline = if is_selected {
+            Some(egui::Stroke::new(2.0, egui::Color32::GOLD))
+        } else if is_hovered {
+            Some(egui::Stroke::new(1.0, egui::Color32::WHITE))
+        } else if self.locked {
+            None
+        } else {
+            Some(egui::Stroke::new(1.0, egui::Color32::from_gray(110)))
+        };
+        if let Some(stroke) = outline {
+            ctx.painter
+                .add(egui::Shape::closed_line(corners.to_vec(), stroke));
+        }
+    }
+}
@@ -1,6 +1,9 @@
 pub mod context;
+pub mod dimension;
 pub mod grid;
+pub mod image;
 pub mod renderable;
 
 pub mod renderer;
+pub mod selection_box;
 pub mod structure;
@@ -1,12 +1,26 @@
 use crate::model::Vector2;
+use crate::model::config::FloorFill;
+use crate::model::layer::LineStyle;
 use crate::model::shapes::{
-    annotation::TextAnnotation, arc::Arc, circle::Circle, line::Line, rectangle::Rectangle,
+    annotation::TextAnnotation,
+    arc::Arc,
+    circle::Circle,
+    line::{LABEL_UNIT_PX, Line},
+    rectangle::Rectangle,
+    section::SectionMark,
+};
+use crate::model::{
+    BeamData, Entity, Flooring, Geometry, RevisionCloud, Room, Shape, Sketch, StairData,
 };
-use crate::model::{BeamData, Entity, Shape};
 use crate::view::rendering::context::DrawContext;
+use crate::view::rendering::dimension::{
+    dimension_font, draw_dimension_layout, draw_rotated_galley,
+};
+use crate::view::rendering::renderer::{GHOST_OPACITY, Highlight, RenderPass, UNSAVED_COLOR};
 use eframe::egui;
 
 use crate::model::structure::definitions::StructureDefinitions;
+use crate::model::structure::junction::BeamJoin;
 
 /// Trait for entities that can be rendered on the canvas
 pub trait Renderable {
@@ -19,13 +33,18 @@ pub trait Renderable {
     );
 }
 
-fn get_base_style(is_selected: bool, is_hovered: bool) -> (egui::Color32, f32) {
+fn get_base_style(ctx: &DrawContext, is_selected: bool, is_hovered: bool) -> (egui::Color32, f32) {
     if is_selected {
-        (egui::Color32::GOLD, 2.5)
+        (egui::Color32::GOLD, ctx.stroke_width + 1.0)
     } else if is_hovered {
-        (egui::Color32::WHITE, 1.5)
+        (egui::Color32::WHITE, ctx.stroke_width)
+    } else if ctx.emphasized {
+        (
+            ctx.color.lerp_to_gamma(egui::Color32::WHITE, 0.35),
+            ctx.stroke_width + 1.0,
+        )
     } else {
-        (egui::Color32::from_rgb(0, 255, 255), 1.5)
+        (ctx.color, ctx.stroke_width)
     }
 }
 
@@ -37,71 +56,30 @@ impl Renderable for Line {
         is_selected: bool,
         is_hovered: bool,
     ) {
-        let (color, stroke_width) = get_base_style(is_selected, is_hovered);
+        let (color, stroke_width) = get_base_style(ctx, is_selected, is_hovered);
 
-        ctx.painter.line_segment(
-            [ctx.to_screen(self.start), ctx.to_screen(self.end)],
+        ctx.stroke_path(
+            &[ctx.to_screen(self.start), ctx.to_screen(self.end)],
+            false,
             egui::Stroke::new(stroke_width, color),
         );
 
-        if self.show_length {
-            let viewport_zoom = ctx.zoom;
-            let tolerance = 5.0 / viewport_zoom;
-            let smart_offset = self.calculate_smart_offset(tolerance);
-            let world_label_pos = self.midpoint() + smart_offset + self.label_offset;
-
-            // Dimension Lines logic
-            let dx = self.end.x - self.start.x;
-            let dy = self.end.y - self.start.y;
-            let len = (dx * dx + dy * dy).sqrt();
+        // Length labels too small to read are skipped along with their dimension lines
+        if self.show_length && ctx.text_visible(ctx.dim_style.text_height) {
+            let unit = LABEL_UNIT_PX / ctx.zoom;
+            let world_label_pos = self.label_position(unit);
 
+            let perp_vec = self.dimension_offset(unit);
+            let len = self.length();
             if len > 0.001 {
-                let smart_len = (smart_offset.x.powi(2) + smart_offset.y.powi(2)).sqrt();
-                let (perp_offset_x, perp_offset_y) = if smart_len > 0.001 {
-                    let nx = smart_offset.x / smart_len;
-                    let ny = smart_offset.y / smart_len;
-                    let user_proj = self.label_offset.x * nx + self.label_offset.y * ny;
-                    let total_perp = smart_len + user_proj;
-                    (nx * total_perp, ny * total_perp)
-                } else {
-                    (smart_offset.x, smart_offset.y)
-                };
-                let perp_vec = Vector2::new(perp_offset_x, perp_offset_y);
-
-                let ext_start_pos = self.start + perp_vec;
-                let ext_end_pos = self.end + perp_vec;
-
-                let s_line_start = ctx.to_screen(self.start);
-                let s_line_end = ctx.to_screen(self.end);
-                let s_ext_start = ctx.to_screen(ext_start_pos);
-                let s_ext_end = ctx.to_screen(ext_end_pos);
-
-                let dim_color = egui::Color32::from_rgb(150, 150, 150);
-                let dim_stroke = egui::Stroke::new(1.0, dim_color);
-
-                ctx.painter
-                    .line_segment([s_line_start, s_ext_start], dim_stroke);
-                ctx.painter
-                    .line_segment([s_line_end, s_ext_end], dim_stroke);
-
-                let tx = dx / len;
-                let ty = dy / len;
-                let label_rel_x = world_label_pos.x - ext_start_pos.x;
-                let label_rel_y = world_label_pos.y - ext_start_pos.y;
-                let label_proj_t = label_rel_x * tx + label_rel_y * ty;
-
-                let t_min = 0.0f32.min(label_proj_t);
-                let t_max = len.max(label_proj_t);
-
-                let dim_start_world =
-                    Vector2::new(ext_start_pos.x + tx * t_min, ext_start_pos.y + ty * t_min);
-                let dim_end_world =
-                    Vector2::new(ext_start_pos.x + tx * t_max, ext_start_pos.y + ty * t_max);
-
-                ctx.painter.line_segment(
-                    [ctx.to_screen(dim_start_world), ctx.to_screen(dim_end_world)],
-                    dim_stroke,
-                );
+                let u = (self.end - self.start) / len;
+                let label_t = (world_label_pos - (self.start + perp_vec)).dot(u);
+                if let Some(layout) = ctx
+                    .dim_style
+                    .layout(self.start, self.end, perp_vec, label_t, 0.0)
+                {
+                    draw_dimension_layout(ctx, &layout, egui::Color32::from_rgb(150, 150, 150));
+                }
             }
 
             // Text
@@ -118,37 +96,17 @@ impl Renderable for Line {
                 screen_angle
             };
 
-            let length_text = format!("{:.2}", self.length());
+            let length_text = self.label_text(ctx.dim_style);
             let label_color = if is_selected {
                 egui::Color32::GOLD
             } else {
                 egui::Color32::from_rgb(255, 200, 100)
             };
 
-            let font_id = egui::FontId::proportional(12.0);
             let galley = ctx
                 .painter
-                .layout_no_wrap(length_text, font_id, label_color);
-            let text_size = galley.size();
-
-            let half_w = text_size.x / 2.0;
-            let half_h = text_size.y / 2.0;
-            let cos_a = adjusted_angle.cos();
-            let sin_a = adjusted_angle.sin();
-            let rot_x = half_w * cos_a - half_h * sin_a;
-            let rot_y = half_w * sin_a + half_h * cos_a;
-
-            let final_pos = egui::pos2(label_pos.x - rot_x, label_pos.y - rot_y);
-
-            ctx.painter.add(egui::epaint::TextShape {
-                pos: final_pos,
-                galley,
-                underline: egui::Stroke::NONE,
-                fallback_color: label_color,
-                override_text_color: Some(label_color),
-                opacity_factor: 1.0,
-                angle: adjusted_angle,
-            });
+                .layout_no_wrap(length_text, dimension_font(ctx), label_color);
+            draw_rotated_galley(ctx, label_pos, galley, adjusted_angle, label_color);
         }
     }
 }
@@ -161,7 +119,7 @@ impl Renderable for Circle {
         is_selected: bool,
         is_hovered: bool,
     ) {
-        let (color, stroke_width) = get_base_style(is_selected, is_hovered);
+        let (color, stroke_width) = get_base_style(ctx, is_selected, is_hovered);
 
         let screen_radius = self.radius * ctx.zoom;
         if self.filled {
@@ -171,11 +129,22 @@ impl Renderable for Circle {
                 color.linear_multiply(0.3),
             );
         }
-        ctx.painter.circle_stroke(
-            ctx.to_screen(self.center),
-            screen_radius,
-            egui::Stroke::new(stroke_width, color),
-        );
+        let stroke = egui::Stroke::new(stroke_width, color);
+        if ctx.line_style == LineStyle::Solid {
+            ctx.painter
+                .circle_stroke(ctx.to_screen(self.center), screen_radius, stroke);
+        } else {
+            let segments = 64;
+            let points: Vec<egui::Pos2> = (0..segments)
+                .map(|i| {
+                    let angle = std::f32::consts::TAU * i as f32 / segments as f32;
+                    ctx.to_screen(
+                        self.center + Vector2::new(angle.cos(), angle.sin()) * self.radius,
+                    )
+                })
+                .collect();
+            ctx.stroke_path(&points, true, stroke);
+        }
     }
 }
 
@@ -187,9 +156,10 @@ impl Renderable for Rectangle {
         is_selected: bool,
         is_hovered: bool,
     ) {
-        let (color, stroke_width) = get_base_style(is_selected, is_hovered);
+        let (color, stroke_width) = get_base_style(ctx, is_selected, is_hovered);
 
-        let rect_screen = egui::Rect::from_min_max(
+        // Corners can swap on screen once the entity is rotated or mirrored
+        let rect_screen = egui::Rect::from_two_pos(
             ctx.to_screen(Vector2::new(self.min.x, self.max.y)),
             ctx.to_screen(Vector2::new(self.max.x, self.min.y)),
         );
@@ -197,8 +167,16 @@ impl Renderable for Rectangle {
             ctx.painter
                 .rect_filled(rect_screen, 0.0, color.linear_multiply(0.3));
         }
-        ctx.painter
-            .rect_stroke(rect_screen, 0.0, egui::Stroke::new(stroke_width, color));
+        ctx.stroke_path(
+            &[
+                rect_screen.left_top(),
+                rect_screen.right_top(),
+                rect_screen.right_bottom(),
+                rect_screen.left_bottom(),
+            ],
+            true,
+            egui::Stroke::new(stroke_width, color),
+        );
     }
 }
 
@@ -210,41 +188,23 @@ impl Renderable for Arc {
         is_selected: bool,
         is_hovered: bool,
     ) {
-        let (color, stroke_width) = get_base_style(is_selected, is_hovered);
+        let (color, stroke_width) = get_base_style(ctx, is_selected, is_hovered);
 
-        let segments = 32;
-        let mut angle_range = self.end_angle - self.start_angle;
-        if angle_range < 0.0 {
-            angle_range += std::f32::consts::PI * 2.0;
-        }
-        let angle_step = angle_range / segments as f32;
-
-        let mut points = Vec::with_capacity(segments + 1);
-        for i in 0..=segments {
-            let angle = self.start_angle + angle_step * i as f32;
-            let pt = Vector2::new(
-                self.center.x + self.radius * angle.cos(),
-                self.center.y + self.radius * angle.sin(),
-            );
-            points.push(ctx.to_screen(pt));
-        }
+        let points: Vec<egui::Pos2> = self
+            .points(32)
+            .into_iter()
+            .map(|pt| ctx.to_screen(pt))
+            .collect();
 
         if self.filled {
-            let mut fill_points = vec![ctx.to_screen(self.center)];
-            fill_points.extend(points.iter().cloned());
-            ctx.painter.add(egui::Shape::convex_polygon(
-                fill_points,
+            ctx.fill_fan(
+                ctx.to_screen(self.center),
+                &points,
                 color.linear_multiply(0.3),
-                egui::Stroke::NONE,
-            ));
-        }
-
-        for i in 0..points.len() - 1 {
-            ctx.painter.line_segment(
-                [points[i], points[i + 1]],
-                egui::Stroke::new(stroke_width, color),
             );
         }
+
+        ctx.stroke_path(&points, false, egui::Stroke::new(stroke_width, color));
     }
 }
 
@@ -267,95 +227,38 @@ impl Renderable for TextAnnotation {
             text_color
         };
 
-        // Calculate text size first
-        let font_size = self.style.font_size * ctx.zoom;
-        let font_id = egui::FontId::proportional(font_size.max(8.0).min(48.0));
-        let galley = ctx
-            .painter
-            .layout_no_wrap(self.text.clone(), font_id, final_color);
-        let text_size = galley.size();
-
-        if self.anchor_points.len() >= 2
-            && matches!(
-                self.annotation_type,
-                crate::model::shapes::annotation::AnnotationType::Distance
-            )
-        {
+        if self.is_dimension() {
+            if !ctx.text_visible(ctx.dim_style.text_height) {
+                return;
+            }
             let p1 = self.anchor_points[0];
             let p2 = self.anchor_points[1];
-            let label_pos = self.position;
-
-            let d = p2 - p1;
-            let len = d.length();
-            if len > 0.001 {
-                let u = d / len;
-                let v = label_pos - p1;
-
-                // Project v onto u to find parallel component t
-                let t = v.dot(u);
-                // Perpendicular vector from line to label
-                let mut perp = v - u * t;
-
-                // Add margin between text and line (line should be "below" text)
-                if perp.length_squared() > 0.0001 {
-                    let dir = perp.normalized();
-                    let margin = 3.0 / ctx.zoom;
-                    let half_h_world = (text_size.y / 2.0) / ctx.zoom;
-                    // Move line away from text towards the object properties
-                    perp = perp - dir * (half_h_world + margin);
-                }
-
-                // Points on the dimension line (original anchors projection)
-                let dim_p1_proj = p1 + perp;
-                let dim_p2_proj = p2 + perp;
-
-                let dim_color = final_color.linear_multiply(0.6);
-                let dim_stroke = egui::Stroke::new(1.0, dim_color);
-
-                // Extension lines
-                let perp_len_sq = perp.length_squared();
-                if perp_len_sq > 0.0001 {
-                    let perp_dir = perp.normalized();
-                    let zoom_factor = 1.0 / ctx.zoom;
-                    let overshoot = perp_dir * (5.0 * zoom_factor);
-                    let gap = perp_dir * (2.0 * zoom_factor);
-
-                    let ext1_start = p1 + gap;
-                    let ext1_end = dim_p1_proj + overshoot;
-
-                    let ext2_start = p2 + gap;
-                    let ext2_end = dim_p2_proj + overshoot;
+            let text = self.dimension_label(ctx.dim_style, p1.dist(p2));
+            let galley = ctx
+                .painter
+                .layout_no_wrap(text, dimension_font(ctx), final_color);
+            let text_size = galley.size();
 
-                    ctx.painter.line_segment(
-                        [ctx.to_screen(ext1_start), ctx.to_screen(ext1_end)],
-                        dim_stroke,
-                    );
-                    ctx.painter.line_segment(
-                        [ctx.to_screen(ext2_start), ctx.to_screen(ext2_end)],
-                        dim_stroke,
-                    );
+            // Keep the dimension line clear of the text (half height + margin)
+            let clearance = (text_size.y / 2.0 + 3.0) / ctx.zoom;
+            if let Some((offset, label_t)) = self.dimension_offset(clearance) {
+                // text_size is in screen pixels, convert to world units (10% padding)
+                let half_w_world = (text_size.x / 2.0) / ctx.zoom * 1.1;
+                if let Some(layout) = ctx.dim_style.layout(p1, p2, offset, label_t, half_w_world) {
+                    draw_dimension_layout(ctx, &layout, final_color.linear_multiply(0.6));
                 }
+            }
 
-                // Draw main dimension line with extension to cover text
-                // text_size is in screen pixels, convert to world units
-                let half_w_world = (text_size.x / 2.0) / ctx.zoom * 1.1; // 10% padding
-
-                let line_start_t = 0.0f32.min(t - half_w_world);
-                let line_end_t = len.max(t + half_w_world);
-
-                let line_p1 = dim_p1_proj + u * line_start_t;
-                let line_p2 = dim_p1_proj + u * line_end_t;
+            let text_pos = ctx.to_screen(self.position);
+            draw_rotated_galley(ctx, text_pos, galley, -self.rotation, final_color);
+            return;
+        }
 
-                ctx.painter
-                    .line_segment([ctx.to_screen(line_p1), ctx.to_screen(line_p2)], dim_stroke);
+        if !ctx.text_visible(self.style.font_size) {
+            return;
+        }
 
-                // Draw ticks at original intersections
-                ctx.painter
-                    .circle_filled(ctx.to_screen(dim_p1_proj), 2.0, dim_color);
-                ctx.painter
-                    .circle_filled(ctx.to_screen(dim_p2_proj), 2.0, dim_color);
-            }
-        } else if self.anchor_points.len() >= 2 {
+        if self.anchor_points.len() >= 2 {
             let start = ctx.to_screen(self.anchor_points[0]);
             let end = ctx.to_screen(self.anchor_points[1]);
             ctx.painter.line_segment(
@@ -366,26 +269,305 @@ impl Renderable for TextAnnotation {
             ctx.painter.circle_filled(end, 3.0, final_color);
         }
 
+        let font_size = self.style.font_size * ctx.zoom;
+        let font_id = egui::FontId::proportional(font_size.clamp(8.0, 48.0));
+        let galley = ctx
+            .painter
+            .layout_no_wrap(self.text.clone(), font_id, final_color);
         let text_pos = ctx.to_screen(self.position);
-        let angle = -self.rotation;
-        let half_w = text_size.x / 2.0;
-        let half_h = text_size.y / 2.0;
-        let cos_a = angle.cos();
-        let sin_a = angle.sin();
-        let rot_x = half_w * cos_a - half_h * sin_a;
-        let rot_y = half_w * sin_a + half_h * cos_a;
-
-        let final_pos = egui::pos2(text_pos.x - rot_x, text_pos.y - rot_y);
-
-        ctx.painter.add(egui::epaint::TextShape {
-            pos: final_pos,
-            galley,
-            underline: egui::Stroke::NONE,
-            fallback_color: final_color,
-            override_text_color: Some(final_color),
-            opacity_factor: 1.0,
-            angle,
-        });
+        if let Some(target) = self.leader_target() {
+            // Run the leader from the edge of the text to the target
+            let target = ctx.to_screen(target);
+            let half = galley.size() / 2.0 + egui::vec2(2.0, 2.0);
+            let d = target - text_pos;
+            let t = (half.x / d.x.abs()).min(half.y / d.y.abs());
+            if t < 1.0 {
+                ctx.painter.line_segment(
+                    [text_pos + d * t, target],
+                    egui::Stroke::new(1.0, final_color),
+                );
+                ctx.painter.circle_filled(target, 2.5, final_color);
+            }
+        }
+        draw_rotated_galley(ctx, text_pos, galley, -self.rotation, final_color);
+    }
+}
+
+impl Renderable for SectionMark {
+    fn render(
+        &self,
+        ctx: &DrawContext,
+        _definitions: &StructureDefinitions,
+        is_selected: bool,
+        is_hovered: bool,
+    ) {
+        let (color, stroke_width) = get_base_style(ctx, is_selected, is_hovered);
+        let start = ctx.to_screen(self.start);
+        let end = ctx.to_screen(self.end);
+        if start.distance(end) < 1.0 {
+            return;
+        }
+        ctx.stroke_path(&[start, end], false, egui::Stroke::new(1.0, color));
+
+        // Screen direction of a world vector, through the entity transform
+        let screen_dir = |v| (ctx.to_screen(self.start + v) - start).normalized();
+        let along = (end - start).normalized();
+        let look = screen_dir(self.direction());
+        let heavy = egui::Stroke::new(stroke_width + 2.5, color);
+        let arrow = egui::Stroke::new(stroke_width, color);
+        let font = egui::FontId::proportional(14.0);
+
+        for (point, inward) in [(start, along), (end, -along)] {
+            // Heavy tick running into the cut
+            ctx.painter
+                .line_segment([point, point + inward * 14.0], heavy);
+
+            // Arrow showing the viewing direction
+            let tip = point + look * 18.0;
+            ctx.painter.line_segment([point, tip], arrow);
+            let side = egui::vec2(-look.y, look.x) * 4.0;
+            ctx.painter.add(egui::Shape::convex_polygon(
+                vec![tip, tip - look * 8.0 + side, tip - look * 8.0 - side],
+                color,
+                egui::Stroke::NONE,
+            ));
+
+            ctx.painter.text(
+                point - inward * 10.0 + look * 14.0,
+                egui::Align2::CENTER_CENTER,
+                &self.label,
+                font.clone(),
+                color,
+            );
+        }
+    }
+}
+
+impl Renderable for RevisionCloud {
+    fn render(
+        &self,
+        ctx: &DrawContext,
+        _definitions: &StructureDefinitions,
+        is_selected: bool,
+        is_hovered: bool,
+    ) {
+        let (color, stroke_width) = get_base_style(ctx, is_selected, is_hovered);
+        let stroke = egui::Stroke::new(stroke_width, color);
+        let outline: Vec<egui::Pos2> = self
+            .as_polyline()
+            .into_iter()
+            .map(|p| ctx.to_screen(p))
+            .collect();
+        if outline.len() >= 2 {
+            ctx.stroke_path(&outline, true, stroke);
+        }
+
+        if let (Some(tag), Some(corners)) = (&self.tag, self.tag_triangle()) {
+            let corners: Vec<egui::Pos2> = corners.iter().map(|p| ctx.to_screen(*p)).collect();
+            let center = egui::pos2(
+                (corners[0].x + corners[1].x + corners[2].x) / 3.0,
+                (corners[0].y + corners[1].y + corners[2].y) / 3.0,
+            );
+            let size = corners[0].distance(corners[1]);
+            ctx.painter.add(egui::Shape::convex_polygon(
+                corners,
+                ctx.painter.ctx().style().visuals.extreme_bg_color,
+                stroke,
+            ));
+            ctx.painter.text(
+                center,
+                egui::Align2::CENTER_CENTER,
+                tag,
+                egui::FontId::proportional((size * 0.4).clamp(6.0, 48.0)),
+                color,
+            );
+        }
+    }
+}
+
+/// Screen width of a highlighter stroke
+const HIGHLIGHTER_WIDTH: f32 = 14.0;
+/// Opacity of a highlighter stroke, so the drawing shows through
+const HIGHLIGHTER_OPACITY: f32 = 0.35;
+
+impl Renderable for Sketch {
+    fn render(
+        &self,
+        ctx: &DrawContext,
+        _definitions: &StructureDefinitions,
+        is_selected: bool,
+        is_hovered: bool,
+    ) {
+        let (color, stroke_width) = get_base_style(ctx, is_selected, is_hovered);
+        let path: Vec<egui::Pos2> = self.points.iter().map(|p| ctx.to_screen(*p)).collect();
+        if self.highlighter {
+            let band = egui::Stroke::new(
+                HIGHLIGHTER_WIDTH,
+                color.linear_multiply(HIGHLIGHTER_OPACITY),
+            );
+            match path.as_slice() {
+                [point] => ctx
+                    .painter
+                    .circle_filled(*point, band.width / 2.0, band.color),
+                _ => ctx.painter.add(egui::Shape::line(path, band)),
+            };
+        } else if path.len() >= 2 {
+            ctx.painter.add(egui::Shape::line(
+                path,
+                egui::Stroke::new(stroke_width, color),
+            ));
+        }
+    }
+}
+
+/// Pixels between flooring hatch lines
+const FLOOR_HATCH_SPACING: f32 = 10.0;
+
+impl Renderable for Flooring {
+    fn render(
+        &self,
+        ctx: &DrawContext,
+        _definitions: &StructureDefinitions,
+        is_selected: bool,
+        is_hovered: bool,
+    ) {
+        if self.boundary.len() < 3 {
+            return;
+        }
+        let (color, stroke_width) = get_base_style(ctx, is_selected, is_hovered);
+        let rings: Vec<Vec<egui::Pos2>> = self
+            .rings()
+            .map(|ring| ring.iter().map(|p| ctx.to_screen(*p)).collect())
+            .collect();
+
+        let [r, g, b] = ctx.flooring.color(self.floor_type_id);
+        let type_color = egui::Color32::from_rgb(r, g, b);
+        match ctx.flooring.fill {
+            FloorFill::Tint => ctx.fill_rings(&rings, type_color.linear_multiply(0.18)),
+            FloorFill::Hatch => ctx.hatch_rings(
+                &rings,
+                FLOOR_HATCH_SPACING,
+                egui::Stroke::new(1.0, type_color.linear_multiply(0.6)),
+            ),
+        }
+        for ring in &rings {
+            ctx.stroke_path(ring, true, egui::Stroke::new(stroke_width, color));
+        }
+
+        // Label at the centroid, if legible and it fits inside the slab
+        if !ctx.text_visible(ctx.dim_style.text_height) {
+            return;
+        }
+        let text = format!(
+            "F{} · {} · {:.2} m²",
+            self.floor_type_id,
+            ctx.dim_style.format_length(self.thickness),
+            self.area() / 10_000.0
+        );
+        let font = egui::FontId::proportional(ctx.dim_style.text_height * ctx.zoom);
+        let galley = ctx.painter.layout_no_wrap(text, font, color);
+        let (min, max) = self.bounding_box();
+        let size = egui::vec2((max.x - min.x) * ctx.zoom, (max.y - min.y) * ctx.zoom);
+        if galley.size().x > size.x || galley.size().y > size.y {
+            return;
+        }
+        let center = ctx.to_screen(self.centroid());
+        ctx.painter
+            .galley(center - galley.size() / 2.0, galley, color);
+    }
+}
+
+impl Renderable for Room {
+    fn render(
+        &self,
+        ctx: &DrawContext,
+        _definitions: &StructureDefinitions,
+        is_selected: bool,
+        is_hovered: bool,
+    ) {
+        if self.boundary.len() < 3 {
+            return;
+        }
+        let (color, stroke_width) = get_base_style(ctx, is_selected, is_hovered);
+        let ring: Vec<egui::Pos2> = self.boundary.iter().map(|p| ctx.to_screen(*p)).collect();
+        ctx.fill_rings(std::slice::from_ref(&ring), color.linear_multiply(0.08));
+        // The walls draw the outline; a room shows its own only when picked
+        if is_selected || is_hovered {
+            ctx.stroke_path(&ring, true, egui::Stroke::new(stroke_width, color));
+        }
+
+        if !ctx.text_visible(ctx.dim_style.text_height) {
+            return;
+        }
+        ctx.painter.text(
+            ctx.to_screen(self.center()),
+            egui::Align2::CENTER_CENTER,
+            self.label(),
+            egui::FontId::proportional(ctx.dim_style.text_height * ctx.zoom),
+            color,
+        );
+    }
+}
+
+impl Renderable for StairData {
+    fn render(
+        &self,
+        ctx: &DrawContext,
+        _definitions: &StructureDefinitions,
+        is_selected: bool,
+        is_hovered: bool,
+    ) {
+        let (color, stroke_width) = get_base_style(ctx, is_selected, is_hovered);
+        let stroke = egui::Stroke::new(stroke_width, color);
+        let corners: Vec<egui::Pos2> = self.corners().iter().map(|p| ctx.to_screen(*p)).collect();
+        ctx.stroke_path(&corners, true, stroke);
+
+        // Tread lines
+        let thin = egui::Stroke::new(1.0, color);
+        for i in 1..self.treads() {
+            let (a, b) = self.riser_line(i);
+            ctx.painter
+                .line_segment([ctx.to_screen(a), ctx.to_screen(b)], thin);
+        }
+
+        // Break line across the flight where the plan cuts it, as a zigzag
+        let run = self.total_run();
+        let half = self.across() * (self.width / 2.0);
+        let cut = self.start + self.direction * (run * 0.6);
+        let jog = self.direction * (self.tread * 0.5);
+        let zigzag = [
+            cut - half - jog,
+            cut - half * 0.15 - jog,
+            cut + half * 0.05 + jog,
+            cut - half * 0.05 - jog,
+            cut + half * 0.15 + jog,
+            cut + half + jog,
+        ];
+        let zigzag: Vec<egui::Pos2> = zigzag.iter().map(|p| ctx.to_screen(*p)).collect();
+        ctx.stroke_path(&zigzag, false, stroke);
+
+        // Walking line up the flight, ending in an arrow
+        let from = ctx.to_screen(self.start);
+        let to = ctx.to_screen(self.end());
+        if from.distance(to) < 1.0 {
+            return;
+        }
+        ctx.painter.line_segment([from, to], thin);
+        let look = (to - from).normalized();
+        let side = egui::vec2(-look.y, look.x) * 4.0;
+        ctx.painter.add(egui::Shape::convex_polygon(
+            vec![to, to - look * 10.0 + side, to - look * 10.0 - side],
+            color,
+            egui::Stroke::NONE,
+        ));
+        ctx.painter.circle_filled(from, 3.0, color);
+        ctx.painter.text(
+            from - look * 10.0,
+            egui::Align2::CENTER_CENTER,
+            "UP",
+            egui::FontId::proportional(12.0),
+            color,
+        );
     }
 }
 
@@ -399,7 +581,7 @@ impl Renderable for ColumnData {
         is_selected: bool,
         is_hovered: bool,
     ) {
-        let (base_color, stroke_width) = get_base_style(is_selected, is_hovered);
+        let (base_color, stroke_width) = get_base_style(ctx, is_selected, is_hovered);
 
         // Use a distinct color for columns if not selected/hovered
         let color = if !is_selected && !is_hovered {
@@ -411,6 +593,19 @@ impl Renderable for ColumnData {
         let corners = self.get_corners();
         let screen_points: Vec<egui::Pos2> = corners.iter().map(|p| ctx.to_screen(*p)).collect();
 
+        // Zoomed out: a plain filled shape
+        if !ctx.structure_detailed(self.width.min(self.height)) {
+            ctx.painter.add(egui::Shape::convex_polygon(
+                screen_points,
+                color,
+                egui::Stroke::NONE,
+            ));
+            if is_selected {
+                draw_anchor_marker(ctx, self.anchor_point());
+            }
+            return;
+        }
+
         // 1. Draw Body
         ctx.painter.add(egui::Shape::convex_polygon(
             screen_points.clone(),
@@ -441,6 +636,23 @@ impl Renderable for ColumnData {
 
             ctx.painter.galley(text_pos, galley, text_color);
         }
+
+        if is_selected {
+            draw_anchor_marker(ctx, self.anchor_point());
+        }
+    }
+}
+
+/// Small crosshair ring on the point a column resizes around
+fn draw_anchor_marker(ctx: &DrawContext, point: Vector2) {
+    const RADIUS: f32 = 5.0;
+    let center = ctx.to_screen(point);
+    let stroke = egui::Stroke::new(1.5, egui::Color32::from_rgb(255, 140, 0));
+    ctx.painter.circle_stroke(center, RADIUS, stroke);
+    for (dx, dy) in [(1.0, 0.0), (0.0, 1.0)] {
+        let arm = egui::vec2(dx, dy) * (RADIUS + 3.0);
+        ctx.painter
+            .line_segment([center - arm, center + arm], stroke);
     }
 }
 
@@ -451,48 +663,81 @@ impl Renderable for BeamData {
         definitions: &StructureDefinitions,
         is_selected: bool,
         is_hovered: bool,
+    ) {
+        self.render_joined(ctx, definitions, is_selected, is_hovered, None);
+    }
+}
+
+impl BeamData {
+    /// Render the beam, cleaned up at its junctions when `join` is given
+    pub fn render_joined(
+        &self,
+        ctx: &DrawContext,
+        definitions: &StructureDefinitions,
+        is_selected: bool,
+        is_hovered: bool,
+        join: Option<&BeamJoin>,
     ) {
         let p1 = ctx.to_screen(self.start);
         let p2 = ctx.to_screen(self.end);
+        let (body_start, body_end) = match join {
+            Some(join) => {
+                let (start, end) = join.extended();
+                (ctx.to_screen(start), ctx.to_screen(end))
+            }
+            None => (p1, p2),
+        };
 
         if let Some(beam_type) = definitions.beam_types.get(&self.beam_type_id) {
-            crate::view::rendering::structure::draw_beam(
-                ctx.painter,
-                p1,
-                p2,
-                ctx.zoom,
-                beam_type,
-                1.0,
-                self.anchor,
-            );
-
-            // 2. Draw Label
-            if !self.label.is_empty() {
-                let center_cad = (self.start + self.end) / 2.0;
-                let center_screen = ctx.to_screen(center_cad);
-                let font_id = egui::FontId::proportional(13.0);
-
-                let text_color = if is_selected {
-                    egui::Color32::BLACK
-                } else {
-                    egui::Color32::from_rgb(220, 220, 220)
-                };
-
-                let galley = ctx
-                    .painter
-                    .layout_no_wrap(self.label.clone(), font_id, text_color);
+            if ctx.structure_detailed(beam_type.width) {
+                crate::view::rendering::structure::draw_joined_beam(
+                    ctx.painter,
+                    [body_start, body_end],
+                    ctx.zoom,
+                    beam_type,
+                    1.0,
+                    self.anchor,
+                    join,
+                );
 
-                let text_rect = galley.rect;
-                let text_pos = center_screen - (text_rect.size() / 2.0);
+                // 2. Draw Label
+                if !self.label.is_empty() {
+                    let center_cad = (self.start + self.end) / 2.0;
+                    let center_screen = ctx.to_screen(center_cad);
+                    let font_id = egui::FontId::proportional(13.0);
+
+                    let text_color = if is_selected {
+                        egui::Color32::BLACK
+                    } else {
+                        egui::Color32::from_rgb(220, 220, 220)
+                    };
+
+                    let galley =
+                        ctx.painter
+                            .layout_no_wrap(self.label.clone(), font_id, text_color);
+
+                    let text_rect = galley.rect;
+                    let text_pos = center_screen - (text_rect.size() / 2.0);
+
+                    // Draw small background for legibility if needed
+                    ctx.painter.rect_filled(
+                        text_rect.translate(text_pos.to_vec2()).expand(2.0),
+                        2.0,
+                        egui::Color32::from_black_alpha(150),
+                    );
 
-                // Draw small background for legibility if needed
-                ctx.painter.rect_filled(
-                    text_rect.translate(text_pos.to_vec2()).expand(2.0),
-                    2.0,
-                    egui::Color32::from_black_alpha(150),
+                    ctx.painter.galley(text_pos, galley, text_color);
+                }
+            } else {
+                // Zoomed out: a plain filled shape, no rebar or label
+                crate::view::rendering::structure::draw_beam_simple(
+                    ctx.painter,
+                    body_start,
+                    body_end,
+                    ctx.zoom,
+                    beam_type,
+                    self.anchor,
                 );
-
-                ctx.painter.galley(text_pos, galley, text_color);
             }
 
             // Selection/Hover highlight
@@ -534,6 +779,13 @@ impl Renderable for Entity {
             Shape::Text(e) => e.render(ctx, definitions, is_selected, is_hovered),
             Shape::Column(e) => e.render(ctx, definitions, is_selected, is_hovered),
             Shape::Beam(e) => e.render(ctx, definitions, is_selected, is_hovered),
+            Shape::Image(e) => e.render(ctx, definitions, is_selected, is_hovered),
+            Shape::Section(e) => e.render(ctx, definitions, is_selected, is_hovered),
+            Shape::Stair(e) => e.render(ctx, definitions, is_selected, is_hovered),
+            Shape::Flooring(e) => e.render(ctx, definitions, is_selected, is_hovered),
+            Shape::RevisionCloud(e) => e.render(ctx, definitions, is_selected, is_hovered),
+            Shape::Room(e) => e.render(ctx, definitions, is_selected, is_hovered),
+            Shape::Sketch(e) => e.render(ctx, definitions, is_selected, is_hovered),
             Shape::None => {}
         }
         // Basic render propagates selection (legacy behavior)
@@ -549,30 +801,65 @@ impl Entity {
         &self,
         ctx: &DrawContext,
         definitions: &StructureDefinitions,
-        selected_ids: &std::collections::HashSet<u64>,
-        hovered_id: Option<u64>,
+        highlight: Highlight,
         layer_manager: &crate::model::layer::LayerManager,
-        is_column_pass: bool,
+        pass: RenderPass,
     ) {
         // LAYER VISIBILITY CHECK
-        if let Some(layer) = layer_manager.get_layer(self.layer_id) {
-            if !layer.is_visible {
-                return;
-            }
+        let layer = layer_manager.get_layer(self.layer_id);
+        if layer.is_some_and(|l| !l.is_visible) {
+            return;
         }
 
-        let is_self_selected = selected_ids.contains(&self.id);
-        let is_self_hovered = hovered_id == Some(self.id);
+        let is_self_selected = highlight.selected.contains(&self.id);
+        let is_self_hovered = highlight.hovered == Some(self.id);
 
-        let is_column = matches!(self.shape, Shape::Column(_));
+        let own_pass = match self.shape {
+            Shape::Image(_) => RenderPass::Images(self.layer_id),
+            Shape::Column(_) => RenderPass::Columns,
+            _ => RenderPass::Shapes,
+        };
 
-        if is_column == is_column_pass {
+        if own_pass == pass {
+            let style = self.style(layer);
+            let recolor = if highlight
+                .unsaved
+                .is_some_and(|saved| saved.is_changed(self))
+            {
+                Some(UNSAVED_COLOR)
+            } else {
+                highlight.color_mode.color(&self.shape, layer)
+            };
+            let carried = highlight.carried.contains(&self.id);
+            let ghosted = carried
+                || highlight
+                    .editing
+                    .is_some_and(|scope| !scope.contains(&self.id))
+                || match (self.shape.storey_id(), highlight.active_storey) {
+                    (Some(storey), Some(active)) => storey != 0 && storey != active,
+                    _ => false,
+                };
+            let mut painter = ctx.painter.clone();
+            if ghosted {
+                painter.multiply_opacity(GHOST_OPACITY);
+            }
             let local_ctx = DrawContext {
-                painter: ctx.painter,
+                painter: &painter,
                 zoom: ctx.zoom,
                 offset: ctx.offset,
                 screen_center: ctx.screen_center,
                 transform: self.world_transform,
+                dim_style: ctx.dim_style,
+                color: recolor.unwrap_or(style.color),
+                emphasized: highlight.emphasized.contains(&self.id),
+                line_style: if carried {
+                    LineStyle::Dashed
+                } else {
+                    style.line_style
+                },
+                stroke_width: style.stroke_width,
+                detail: ctx.detail,
+                flooring: ctx.flooring,
             };
 
             match &self.shape {
@@ -594,22 +881,58 @@ impl Entity {
                 Shape::Column(e) => {
                     e.render(&local_ctx, definitions, is_self_selected, is_self_hovered)
                 }
-                Shape::Beam(e) => {
+                Shape::Beam(e) => e.render_joined(
+                    &local_ctx,
+                    definitions,
+                    is_self_selected,
+                    is_self_hovered,
+                    highlight.joins.and_then(|joins| joins.get(self.id, e)),
+                ),
+                Shape::Image(e) => {
+                    e.render(&local_ctx, definitions, is_self_selected, is_self_hovered)
+                }
+                Shape::Section(e) => {
+                    e.render(&local_ctx, definitions, is_self_selected, is_self_hovered)
+                }
+                Shape::Stair(e) => {
+                    e.render(&local_ctx, definitions, is_self_selected, is_self_hovered)
+                }
+                Shape::Flooring(e) => {
+                    e.render(&local_ctx, definitions, is_self_selected, is_self_hovered)
+                }
+                Shape::RevisionCloud(e) => {
+                    e.render(&local_ctx, definitions, is_self_selected, is_self_hovered)
+                }
+                Shape::Room(e) => {
+                    e.render(&local_ctx, definitions, is_self_selected, is_self_hovered)
+                }
+                Shape::Sketch(e) => {
                     e.render(&local_ctx, definitions, is_self_selected, is_self_hovered)
                 }
                 Shape::None => {}
             }
+
+            // Members draw in fixed colors; outline them in the override
+            if let Some(color) = recolor
+                && matches!(self.shape, Shape::Column(_) | Shape::Beam(_))
+            {
+                let points: Vec<egui::Pos2> = self
+                    .shape
+                    .as_polyline()
+                    .into_iter()
+                    .map(|p| local_ctx.to_screen(p))
+                    .collect();
+                let stroke = egui::Stroke::new(2.0, color);
+                if self.shape.is_closed() {
+                    painter.add(egui::Shape::closed_line(points, stroke));
+                } else {
+                    painter.add(egui::Shape::line(points, stroke));
+                }
+            }
         }
 
         for child in &self.children {
-            child.render_recursive(
-                ctx,
-                definitions,
-                selected_ids,
-                hovered_id,
-                layer_manager,
-                is_column_pass,
-            );
+            child.render_recursive(ctx, definitions, highlight, layer_manager, pass);
         }
     }
 }
@@ -1,35 +1,74 @@
 use crate::model::Entity;
+use crate::model::color_mode::ColorMode;
 use crate::model::structure::definitions::StructureDefinitions;
+use crate::model::structure::junction::BeamJoins;
+use crate::model::tools::saved::SavedRevisions;
 use crate::view::rendering::context::DrawContext;
+use eframe::egui;
+use std::collections::HashSet;
+
+/// What one walk over the entity tree draws
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderPass {
+    /// Image underlays on the given layer
+    Images(u64),
+    /// Everything except images and columns
+    Shapes,
+    /// Columns, drawn on top
+    Columns,
+}
+
+/// Entities drawn with a highlight
+#[derive(Clone, Copy)]
+pub struct Highlight<'a> {
+    pub selected: &'a HashSet<u64>,
+    pub hovered: Option<u64>,
+    /// Drawn thicker and brighter, e.g. the entities the current snap
+    /// point lies on
+    pub emphasized: &'a HashSet<u64>,
+    /// Storey being worked on; structural members of other storeys are
+    /// drawn ghosted
+    pub active_storey: Option<u64>,
+    /// Beam junctions to draw cleaned up
+    pub joins: Option<&'a BeamJoins>,
+    /// Temporary recoloring; entity colors are left alone
+    pub color_mode: ColorMode,
+    /// Revisions as saved; entities changed since are drawn tinted
+    pub unsaved: Option<&'a SavedRevisions>,
+    /// Entities a command is carrying, drawn as a faint dashed ghost where
+    /// they started
+    pub carried: &'a HashSet<u64>,
+    /// The edited group and everything in it, during group editing; the
+    /// rest of the drawing is drawn ghosted
+    pub editing: Option<&'a HashSet<u64>>,
+}
+
+/// Opacity of members on storeys other than the active one
+pub const GHOST_OPACITY: f32 = 0.25;
+
+/// Tint of entities changed since the last save
+pub const UNSAVED_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 120, 200);
 
 pub fn render_entities(
     ctx: &DrawContext,
     definitions: &StructureDefinitions,
     entities: &[Entity],
-    selected_ids: &std::collections::HashSet<u64>,
-    hovered_entity_id: Option<u64>,
+    highlight: Highlight,
     layer_manager: &crate::model::layer::LayerManager,
 ) {
-    // Priority 1: Everything except Columns
-    for entity in entities {
-        entity.render_recursive(
-            ctx,
-            definitions,
-            selected_ids,
-            hovered_entity_id,
-            layer_manager,
-            false, // Not column pass
-        );
-    }
-    // Priority 2: Columns on top
-    for entity in entities {
-        entity.render_recursive(
-            ctx,
-            definitions,
-            selected_ids,
-            hovered_entity_id,
-            layer_manager,
-            true, // Column pass
-        );
+    // Priority 1: Images beneath everything, in layer order
+    let passes = layer_manager
+        .get_sorted_layers()
+        .into_iter()
+        .map(|layer| RenderPass::Images(layer.id))
+        .collect::<Vec<_>>();
+    // Priority 2: Everything except Columns, then 3: Columns on top
+    for pass in passes
+        .into_iter()
+        .chain([RenderPass::Shapes, RenderPass::Columns])
+    {
+        for entity in entities {
+            entity.render_recursive(ctx, definitions, highlight, layer_manager, pass);
+        }
     }
 }
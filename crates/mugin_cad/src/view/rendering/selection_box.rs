@@ -0,0 +1,50 @@
+//! Dashed box around the selection with its scale and rotation handles.
+
+use crate::view::rendering::context::DrawContext;
+use crate::viewmodel::tab::ProjectTab;
+use crate::viewmodel::{HANDLE_SIZE, SelectionHandle, selection_handles};
+use eframe::egui;
+
+const BOX_COLOR: egui::Color32 = egui::Color32::from_rgb(100, 160, 255);
+
+/// Draw the selection box of `tab`, highlighting the handle at `hovered`
+pub fn render_selection_box(ctx: &DrawContext, tab: &ProjectTab, hovered: Option<egui::Pos2>) {
+    let Some((min, max)) = tab.selection_bounds() else {
+        return;
+    };
+    let stroke = egui::Stroke::new(1.0, BOX_COLOR);
+    let rect = egui::Rect::from_two_pos(ctx.to_screen(min), ctx.to_screen(max));
+    let outline = [
+        rect.left_top(),
+        rect.right_top(),
+        rect.right_bottom(),
+        rect.left_bottom(),
+        rect.left_top(),
+    ];
+    ctx.painter
+        .extend(egui::Shape::dashed_line(&outline, stroke, 6.0, 4.0));
+
+    for (handle, at) in selection_handles(min, max, ctx.zoom) {
+        let at = ctx.to_screen(at);
+        let is_hovered = hovered.is_some_and(|p| {
+            (p.x - at.x).abs() <= HANDLE_SIZE && (p.y - at.y).abs() <= HANDLE_SIZE
+        });
+        let fill = if is_hovered {
+            BOX_COLOR
+        } else {
+            egui::Color32::from_gray(20)
+        };
+        match handle {
+            SelectionHandle::Scale { .. } => {
+                let square = egui::Rect::from_center_size(at, egui::Vec2::splat(HANDLE_SIZE));
+                ctx.painter.rect(square, 0.0, fill, stroke);
+            }
+            SelectionHandle::Rotate { .. } => {
+                ctx.painter
+                    .line_segment([egui::pos2(at.x, rect.top()), at], stroke);
+                ctx.painter
+                    .circle(at, HANDLE_SIZE / 2.0 + 1.0, fill, stroke);
+            }
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use crate::model::structure::beam::BeamAnchor;
 use crate::model::structure::beam_type::BeamType;
 use crate::model::structure::column_type::ColumnType;
+use crate::model::structure::junction::BeamJoin;
 use eframe::egui;
 
 /// Draws a structural column using the given painter and transform.
@@ -139,52 +140,130 @@ pub fn draw_column(
     }
 }
 
-pub fn draw_beam(
+/// Screen-space corners of a beam body
+fn beam_corners(
+    start: egui::Pos2,
+    end: egui::Pos2,
+    perp: egui::Vec2,
+    offset_px: f32,
+    half_w: f32,
+) -> [egui::Pos2; 4] {
+    [
+        start + perp * (offset_px + half_w),
+        end + perp * (offset_px + half_w),
+        end + perp * (offset_px - half_w),
+        start + perp * (offset_px - half_w),
+    ]
+}
+
+/// Perpendicular of the beam axis and the body offset for its anchor,
+/// `None` for a beam too short to draw
+fn beam_frame(
+    start: egui::Pos2,
+    end: egui::Pos2,
+    half_w: f32,
+    anchor: BeamAnchor,
+) -> Option<(egui::Vec2, f32)> {
+    let dir = end - start;
+    let len_px = dir.length();
+    if len_px < 0.1 {
+        return None;
+    }
+    let dir = dir / len_px;
+    let offset_px = match anchor {
+        BeamAnchor::Center => 0.0,
+        BeamAnchor::Top => half_w,
+        BeamAnchor::Bottom => -half_w,
+    };
+    Some((egui::Vec2::new(-dir.y, dir.x), offset_px))
+}
+
+/// Draws a beam as a plain filled band, for views zoomed out too far to
+/// show its rebar.
+pub fn draw_beam_simple(
     painter: &egui::Painter,
     start: egui::Pos2,
     end: egui::Pos2,
     scale: f32,
     beam_type: &BeamType,
-    alpha_mul: f32,
     anchor: BeamAnchor,
 ) {
-    let dx = end.x - start.x;
-    let dy = end.y - start.y;
-    let len_px = (dx * dx + dy * dy).sqrt();
-
-    if len_px < 0.1 {
+    // At least a pixel wide so the beam stays visible
+    let half_w = (beam_type.width * scale / 2.0).max(0.5);
+    let Some((perp, offset_px)) = beam_frame(start, end, half_w, anchor) else {
         return;
-    }
+    };
+    painter.add(egui::Shape::convex_polygon(
+        beam_corners(start, end, perp, offset_px, half_w).to_vec(),
+        egui::Color32::from_rgb(180, 180, 190).linear_multiply(0.6),
+        egui::Stroke::NONE,
+    ));
+}
 
-    let dir = egui::Vec2::new(dx / len_px, dy / len_px);
-    let perp = egui::Vec2::new(-dir.y, dir.x);
+pub fn draw_beam(
+    painter: &egui::Painter,
+    start: egui::Pos2,
+    end: egui::Pos2,
+    scale: f32,
+    beam_type: &BeamType,
+    alpha_mul: f32,
+    anchor: BeamAnchor,
+) {
+    draw_joined_beam(
+        painter,
+        [start, end],
+        scale,
+        beam_type,
+        alpha_mul,
+        anchor,
+        None,
+    );
+}
 
+/// Draws a beam whose outline is left out where `join` hides it inside the
+/// beams it meets. `ends` are those of the extended beam.
+pub fn draw_joined_beam(
+    painter: &egui::Painter,
+    [start, end]: [egui::Pos2; 2],
+    scale: f32,
+    beam_type: &BeamType,
+    alpha_mul: f32,
+    anchor: BeamAnchor,
+    join: Option<&BeamJoin>,
+) {
     let width_px = beam_type.width * scale;
     let half_w = width_px / 2.0;
 
-    let offset_px = match anchor {
-        BeamAnchor::Center => 0.0,
-        BeamAnchor::Top => half_w,
-        BeamAnchor::Bottom => -half_w,
+    let Some((perp, offset_px)) = beam_frame(start, end, half_w, anchor) else {
+        return;
     };
 
     // Corners of the beam body
-    let points = [
-        start + perp * (offset_px + half_w),
-        end + perp * (offset_px + half_w),
-        end + perp * (offset_px - half_w),
-        start + perp * (offset_px - half_w),
-    ];
+    let points = beam_corners(start, end, perp, offset_px, half_w);
 
     let concrete_color = egui::Color32::from_rgb(180, 180, 190);
     let fill = concrete_color.linear_multiply(0.2 * alpha_mul);
     let stroke = concrete_color.linear_multiply(alpha_mul);
 
-    painter.add(egui::Shape::convex_polygon(
-        points.to_vec(),
-        fill,
-        egui::Stroke::new(1.5, stroke),
-    ));
+    let outline = egui::Stroke::new(1.5, stroke);
+    match join {
+        None => {
+            painter.add(egui::Shape::convex_polygon(points.to_vec(), fill, outline));
+        }
+        Some(join) => {
+            painter.add(egui::Shape::convex_polygon(
+                points.to_vec(),
+                fill,
+                egui::Stroke::NONE,
+            ));
+            for edge in 0..4 {
+                let (a, b) = (points[edge], points[(edge + 1) % 4]);
+                for (from, to) in join.visible(edge) {
+                    painter.line_segment([a.lerp(b, from), a.lerp(b, to)], outline);
+                }
+            }
+        }
+    }
 
     // --- REBAR VISUALIZATION ---
     if alpha_mul > 0.1 {
@@ -1,42 +1,122 @@
-use crate::viewmodel::CadViewModel;
+use crate::model::Vector2;
+use crate::viewmodel::{CadViewModel, QuickTransform, find_action};
 use eframe::egui;
 
+/// Key binding label for a command, if the action registry gives it one.
+/// Other commands are reached through their typed aliases.
+pub fn key_binding_for(command: &str) -> Option<&'static str> {
+    find_action(command).and_then(|spec| spec.keys)
+}
+
+/// Parse a binding such as "Ctrl+Shift+R". Returns `None` for an empty or
+/// unknown binding.
+pub fn parse_shortcut(text: &str) -> Option<egui::KeyboardShortcut> {
+    let mut modifiers = egui::Modifiers::NONE;
+    let mut key = None;
+    for part in text.split('+').map(str::trim) {
+        match part.to_lowercase().as_str() {
+            "ctrl" | "control" => modifiers = modifiers | egui::Modifiers::CTRL,
+            "shift" => modifiers = modifiers | egui::Modifiers::SHIFT,
+            "alt" => modifiers = modifiers | egui::Modifiers::ALT,
+            _ if key.is_none() => key = Some(egui::Key::from_name(part)?),
+            _ => return None,
+        }
+    }
+    Some(egui::KeyboardShortcut::new(modifiers, key?))
+}
+
 /// Handle all global keyboard shortcuts.
 ///
 /// Called once per frame from the main update loop.
 pub fn handle(ctx: &egui::Context, vm: &mut CadViewModel) {
-    // If we are renaming or any text edit has focus, skip global shortcuts
+    // Ctrl+P — command palette, also while typing in the terminal
+    if vm.tab_renaming_index.is_none()
+        && !mugin_widgets::window::is_modal_open(ctx)
+        && let Some(spec) = find_action("command_palette")
+        && let Some(shortcut) = spec.keys.and_then(parse_shortcut)
+        && ctx.input_mut(|i| i.consume_shortcut(&shortcut))
+    {
+        vm.run_action(spec);
+    }
+
+    // If we are renaming, a modal is up or any text edit has focus, skip
+    // global shortcuts
     if vm.tab_renaming_index.is_some()
         || vm.hierarchy_renaming
         || vm.inspector_renaming
+        || mugin_widgets::window::is_modal_open(ctx)
         || ctx.memory(|m| m.focused().is_some())
     {
         return;
     }
 
-    // Escape — cancel active command, clear selection, or clear input
+    // Escape — cancel one level at a time: input, drag, step, command, selection
     if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
-        if !vm.command_input.is_empty() {
-            vm.command_input.clear();
-        } else if vm.active_tab().executor.is_active() {
-            vm.cancel_command();
-        } else if !vm.active_tab().selection_manager.selected_ids.is_empty() {
-            vm.active_tab_mut().selection_manager.selected_ids.clear();
-        }
+        vm.escape();
+    }
+
+    // Ctrl+Tab / Ctrl+Shift+Tab — cycle tabs
+    if ctx.input_mut(|i| {
+        i.consume_key(
+            egui::Modifiers::CTRL | egui::Modifiers::SHIFT,
+            egui::Key::Tab,
+        )
+    }) {
+        vm.cycle_tab(false);
+    } else if ctx.input_mut(|i| i.consume_key(egui::Modifiers::CTRL, egui::Key::Tab)) {
+        vm.cycle_tab(true);
     }
 
     // End — reset viewport
-    if ctx.input(|i| i.key_pressed(egui::Key::End)) {
-        if let Some(tab) = vm.tabs.get_mut(vm.active_tab_index) {
-            tab.viewport.reset();
-        }
+    if ctx.input(|i| i.key_pressed(egui::Key::End))
+        && let Some(tab) = vm.tabs.get_mut(vm.active_tab_index)
+    {
+        tab.viewport.reset();
     }
 
     // Delete — delete selected entities
-    if ctx.input(|i| i.key_pressed(egui::Key::Delete)) {
-        if !vm.active_tab().selection_manager.selected_ids.is_empty() {
-            vm.command_input = "delete".to_string();
-            vm.process_command();
+    if ctx.input(|i| i.key_pressed(egui::Key::Delete))
+        && !vm.active_tab().selection_manager.selected_ids.is_empty()
+    {
+        vm.command_input = "delete".to_string();
+        vm.process_command();
+    }
+
+    // Arrow keys — nudge the selection one grid unit (Shift 10×, Alt 0.1×)
+    if !vm.active_tab().selection_manager.selected_ids.is_empty() {
+        let (direction, modifiers) = ctx.input(|i| {
+            let mut direction = Vector2::new(0.0, 0.0);
+            for (key, step) in [
+                (egui::Key::ArrowLeft, Vector2::new(-1.0, 0.0)),
+                (egui::Key::ArrowRight, Vector2::new(1.0, 0.0)),
+                (egui::Key::ArrowUp, Vector2::new(0.0, 1.0)),
+                (egui::Key::ArrowDown, Vector2::new(0.0, -1.0)),
+            ] {
+                if i.key_pressed(key) {
+                    direction = direction + step;
+                }
+            }
+            (direction, i.modifiers)
+        });
+        if (direction.x != 0.0 || direction.y != 0.0) && !modifiers.command {
+            let factor = if modifiers.shift {
+                10.0
+            } else if modifiers.alt {
+                0.1
+            } else {
+                1.0
+            };
+            vm.nudge_selection(direction * factor);
+        }
+    }
+
+    // Quick rotate / flip — user configurable
+    for transform in QuickTransform::ALL {
+        let binding = transform.shortcut(&vm.config.gui_config.quick_transform_keys);
+        if let Some(shortcut) = parse_shortcut(binding)
+            && ctx.input_mut(|i| i.consume_shortcut(&shortcut))
+        {
+            vm.apply_quick_transform(transform);
         }
     }
 
@@ -44,8 +124,7 @@ pub fn handle(ctx: &egui::Context, vm: &mut CadViewModel) {
     if ctx.input(|i| i.modifiers.ctrl && i.key_pressed(egui::Key::C)) {
         let tab = vm.active_tab_mut();
         if !tab.selection_manager.selected_ids.is_empty() && !tab.executor.is_active() {
-            let ids = tab.selection_manager.selected_ids.clone();
-            tab.executor.start_command("copy", &mut tab.model, &ids);
+            tab.start_command("copy");
         }
     }
 
@@ -53,8 +132,7 @@ pub fn handle(ctx: &egui::Context, vm: &mut CadViewModel) {
     if ctx.input(|i| i.modifiers.ctrl && i.key_pressed(egui::Key::X)) {
         let tab = vm.active_tab_mut();
         if !tab.selection_manager.selected_ids.is_empty() && !tab.executor.is_active() {
-            let ids = tab.selection_manager.selected_ids.clone();
-            tab.executor.start_command("cut", &mut tab.model, &ids);
+            tab.start_command("cut");
         }
     }
 }
@@ -0,0 +1,46 @@
+//! Path of the group being edited, shown across the top of the canvas.
+
+use crate::viewmodel::CadViewModel;
+use eframe::egui;
+
+/// Show "Drawing ▸ Group ▸ Subgroup" while a group is being edited.
+/// Clicking "Drawing" leaves group editing; clicking a group above the
+/// edited one steps out to it.
+pub fn render_group_breadcrumb(ui: &egui::Ui, vm: &mut CadViewModel, canvas: egui::Rect) {
+    let path = vm.group_path();
+    let Some((edited, _)) = path.last().cloned() else {
+        return;
+    };
+
+    let mut clicked = None;
+    egui::Area::new(egui::Id::new("group_breadcrumb"))
+        .order(egui::Order::Foreground)
+        .pivot(egui::Align2::CENTER_TOP)
+        .fixed_pos(egui::pos2(canvas.center().x, canvas.top() + 8.0))
+        .show(ui.ctx(), |ui| {
+            egui::Frame::none()
+                .fill(egui::Color32::from_rgba_unmultiplied(40, 90, 160, 200))
+                .rounding(4.0)
+                .inner_margin(egui::Margin::symmetric(8.0, 2.0))
+                .show(ui, |ui| {
+                    ui.visuals_mut().override_text_color = Some(egui::Color32::WHITE);
+                    ui.horizontal(|ui| {
+                        if ui.add(egui::Button::new("Drawing").frame(false)).clicked() {
+                            clicked = Some(None);
+                        }
+                        for (id, name) in &path {
+                            ui.label("▸");
+                            if *id == edited {
+                                ui.strong(name);
+                            } else if ui.add(egui::Button::new(name).frame(false)).clicked() {
+                                clicked = Some(Some(*id));
+                            }
+                        }
+                    });
+                });
+        });
+
+    if let Some(scope) = clicked {
+        vm.set_group_scope(scope);
+    }
+}
@@ -0,0 +1,146 @@
+use crate::model::tools::calculator::format_value;
+use crate::viewmodel::{CadViewModel, CalculatorPick};
+use eframe::egui;
+
+/// Tools ▸ Calculator window state
+#[derive(Default)]
+pub struct CalculatorWindow {
+    pub open: bool,
+}
+
+/// Calculator action picked in the window
+enum CalculatorAction {
+    Evaluate,
+    Pick(CalculatorPick),
+    CancelPick,
+    Send,
+}
+
+pub fn render_calculator(ctx: &egui::Context, vm: &mut CadViewModel) {
+    let mut open = vm.calculator_window.open;
+    let mut action = None;
+    let picking = vm.calculator_pick;
+    let command = vm.active_tab().executor.active_command_name();
+
+    egui::Window::new("Calculator")
+        .open(&mut open)
+        .default_width(280.0)
+        .show(ctx, |ui| {
+            let calculator = &mut vm.calculator;
+            egui::ScrollArea::vertical()
+                .max_height(160.0)
+                .stick_to_bottom(true)
+                .show(ui, |ui| {
+                    let mut reuse = None;
+                    for line in &calculator.history {
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new(&line.input).weak().monospace());
+                            // Clicking a result reuses it in the next line
+                            let value = format_value(line.value);
+                            if ui
+                                .link(egui::RichText::new(format!("= {}", value)).monospace())
+                                .on_hover_text("Insert into the expression")
+                                .clicked()
+                            {
+                                reuse = Some(line.value);
+                            }
+                        });
+                    }
+                    if let Some(value) = reuse {
+                        calculator.insert(value);
+                    }
+                });
+
+            let field = ui.add(
+                egui::TextEdit::singleline(&mut calculator.input)
+                    .id_salt("calculator_input")
+                    .hint_text("(roomWidth - 2*wall)/3")
+                    .desired_width(f32::INFINITY)
+                    .font(egui::TextStyle::Monospace),
+            );
+            if field.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                action = Some(CalculatorAction::Evaluate);
+                field.request_focus();
+            }
+            if let Some(error) = &calculator.error {
+                ui.colored_label(ui.visuals().error_fg_color, error.to_string());
+            }
+
+            ui.horizontal(|ui| {
+                let answer = calculator.answer();
+                ui.strong(answer.map_or("= —".to_string(), |a| format!("= {}", format_value(a))));
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button("=").clicked() {
+                        action = Some(CalculatorAction::Evaluate);
+                    }
+                });
+            });
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                let picks = [
+                    (CalculatorPick::Distance { first: None }, "Pick Distance"),
+                    (CalculatorPick::X, "Pick X"),
+                    (CalculatorPick::Y, "Pick Y"),
+                ];
+                for (pick, label) in picks {
+                    // A distance pick stays lit after its first point
+                    let active = picking.is_some_and(|current| {
+                        std::mem::discriminant(&current) == std::mem::discriminant(&pick)
+                    });
+                    if ui.selectable_label(active, label).clicked() {
+                        action = Some(if active {
+                            CalculatorAction::CancelPick
+                        } else {
+                            CalculatorAction::Pick(pick)
+                        });
+                    }
+                }
+            });
+
+            let send_label = match command {
+                Some(name) => format!("Send to {}", name),
+                None => "Send to Command".to_string(),
+            };
+            if ui
+                .add_enabled(
+                    command.is_some() && calculator.answer().is_some(),
+                    egui::Button::new(send_label),
+                )
+                .on_hover_text("Enter the result as the active command's next input")
+                .clicked()
+            {
+                action = Some(CalculatorAction::Send);
+            }
+
+            if !calculator.variables().is_empty() {
+                ui.collapsing("Names", |ui| {
+                    egui::Grid::new("calculator_names")
+                        .num_columns(2)
+                        .show(ui, |ui| {
+                            for (name, value) in calculator.variables() {
+                                ui.monospace(name);
+                                ui.monospace(format_value(*value));
+                                ui.end_row();
+                            }
+                        });
+                });
+            }
+        });
+
+    match action {
+        Some(CalculatorAction::Evaluate) => {
+            let _ = vm.calculator.evaluate();
+        }
+        Some(CalculatorAction::Pick(pick)) => vm.start_calculator_pick(pick),
+        Some(CalculatorAction::CancelPick) => {
+            vm.cancel_calculator_pick();
+        }
+        Some(CalculatorAction::Send) => vm.send_calculator_answer(),
+        None => {}
+    }
+    if !open {
+        vm.cancel_calculator_pick();
+    }
+    vm.calculator_window.open = open;
+}
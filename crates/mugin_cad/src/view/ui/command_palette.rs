@@ -0,0 +1,147 @@
+use crate::viewmodel::{ActionMatch, ActionSpec, CadViewModel};
+use eframe::egui;
+
+/// Ctrl+P overlay searching the action registry
+#[derive(Default)]
+pub struct CommandPalette {
+    pub open: bool,
+    pub query: String,
+    /// Highlighted row
+    pub selected: usize,
+}
+
+impl CommandPalette {
+    /// Open with an empty query
+    pub fn opened() -> Self {
+        Self {
+            open: true,
+            ..Default::default()
+        }
+    }
+}
+
+pub fn render_command_palette(ctx: &egui::Context, vm: &mut CadViewModel) {
+    // Taken before the query field and the terminal see them
+    let (up, down, enter, escape) = ctx.input_mut(|i| {
+        (
+            i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+            i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+            i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+            i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+        )
+    });
+
+    let mut palette = std::mem::take(&mut vm.command_palette);
+    let mut run: Option<&'static ActionSpec> = None;
+
+    let response = egui::Window::new("Command Palette")
+        .title_bar(false)
+        .resizable(false)
+        .collapsible(false)
+        .order(egui::Order::Foreground)
+        .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 60.0))
+        .fixed_size(egui::vec2(460.0, 0.0))
+        .show(ctx, |ui| {
+            let field = ui.add(
+                egui::TextEdit::singleline(&mut palette.query)
+                    .id_salt("command_palette_query")
+                    .hint_text("Search commands, actions and settings")
+                    .desired_width(f32::INFINITY),
+            );
+            field.request_focus();
+            if field.changed() {
+                palette.selected = 0;
+            }
+
+            let matches = vm.search_actions(&palette.query);
+            let last = matches.len().saturating_sub(1);
+            if down {
+                palette.selected = (palette.selected + 1).min(last);
+            }
+            if up {
+                palette.selected = palette.selected.saturating_sub(1);
+            }
+            palette.selected = palette.selected.min(last);
+            if enter {
+                run = matches
+                    .get(palette.selected)
+                    .map(|found| found.spec)
+                    .filter(|spec| vm.action_enabled(spec));
+            }
+
+            ui.separator();
+            egui::ScrollArea::vertical()
+                .max_height(320.0)
+                .show(ui, |ui| {
+                    if matches.is_empty() {
+                        ui.weak("No matching actions");
+                    }
+                    for (index, found) in matches.iter().enumerate() {
+                        let enabled = vm.action_enabled(found.spec);
+                        let shortcut = vm.action_shortcut(found.spec);
+                        let row =
+                            palette_row(ui, found, index == palette.selected, enabled, shortcut);
+                        if index == palette.selected && (up || down) {
+                            row.scroll_to_me(None);
+                        }
+                        if row.clicked() {
+                            run = Some(found.spec);
+                        }
+                    }
+                });
+        });
+
+    let clicked_away = response.is_some_and(|r| r.response.clicked_elsewhere());
+    palette.open = !(escape || clicked_away || run.is_some());
+    vm.command_palette = palette;
+    if let Some(spec) = run {
+        vm.run_action(spec);
+    }
+}
+
+/// Label with the matched letters picked out, its category and shortcut
+fn palette_row(
+    ui: &mut egui::Ui,
+    found: &ActionMatch,
+    selected: bool,
+    enabled: bool,
+    shortcut: Option<String>,
+) -> egui::Response {
+    let font = egui::TextStyle::Body.resolve(ui.style());
+    let (normal, strong) = if enabled {
+        (ui.visuals().text_color(), ui.visuals().strong_text_color())
+    } else {
+        let weak = ui.visuals().weak_text_color();
+        (weak, weak)
+    };
+    let mut job = egui::text::LayoutJob::default();
+    for (index, c) in found.spec.label.chars().enumerate() {
+        let matched = found.matched.positions.contains(&index);
+        job.append(
+            &c.to_string(),
+            0.0,
+            egui::TextFormat {
+                font_id: font.clone(),
+                color: if matched { strong } else { normal },
+                underline: if matched {
+                    egui::Stroke::new(1.0, strong)
+                } else {
+                    egui::Stroke::NONE
+                },
+                ..Default::default()
+            },
+        );
+    }
+
+    ui.horizontal(|ui| {
+        let row = ui.add_enabled(enabled, egui::SelectableLabel::new(selected, job));
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            if let Some(shortcut) = shortcut {
+                ui.label(egui::RichText::new(shortcut).monospace().weak());
+            }
+            ui.weak(found.spec.category.label());
+        });
+        row
+    })
+    .inner
+}
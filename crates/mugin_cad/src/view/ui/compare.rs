@@ -0,0 +1,97 @@
+use crate::model::tools::diff::{ADDED_COLOR, Change, MODIFIED_COLOR, REMOVED_COLOR};
+use crate::viewmodel::CadViewModel;
+use eframe::egui;
+
+/// Project ▸ Compare with File window state
+#[derive(Default)]
+pub struct CompareWindow {
+    pub open: bool,
+}
+
+/// Action picked in the window
+enum CompareAction {
+    Select(u64),
+    Close,
+}
+
+fn color([r, g, b]: [u8; 3]) -> egui::Color32 {
+    egui::Color32::from_rgb(r, g, b)
+}
+
+pub fn render_compare(ctx: &egui::Context, vm: &mut CadViewModel) {
+    let mut open = vm.compare_window.open;
+    let mut action = None;
+
+    let tab = vm.active_tab_mut();
+    let Some(comparison) = tab.comparison.as_mut() else {
+        vm.compare_window.open = false;
+        return;
+    };
+    let (added, removed, modified) = comparison.counts();
+
+    egui::Window::new("Compare with File")
+        .open(&mut open)
+        .min_width(280.0)
+        .show(ctx, |ui| {
+            ui.label(format!("Drawing compared with {}", comparison.label));
+            ui.horizontal(|ui| {
+                ui.colored_label(color(ADDED_COLOR), format!("{} added", added));
+                ui.colored_label(color(REMOVED_COLOR), format!("{} removed", removed));
+                ui.colored_label(color(MODIFIED_COLOR), format!("{} modified", modified));
+            });
+            ui.checkbox(&mut comparison.visible, "Show overlay")
+                .on_hover_text("Added in green, removed in red, modified in yellow with the file's version dashed");
+            ui.separator();
+
+            if comparison.changes.is_empty() {
+                ui.label(egui::RichText::new("No differences").weak());
+            }
+            egui::ScrollArea::vertical()
+                .max_height(320.0)
+                .show(ui, |ui| {
+                    for change in &comparison.changes {
+                        let tint = match change {
+                            Change::EntityAdded { .. } => ADDED_COLOR,
+                            Change::EntityRemoved { .. } => REMOVED_COLOR,
+                            _ => MODIFIED_COLOR,
+                        };
+                        let text = egui::RichText::new(change.describe()).color(color(tint));
+                        match change {
+                            // Only entities still in the drawing can be selected
+                            Change::EntityAdded { entity }
+                            | Change::EntityModified { after: entity, .. } => {
+                                if ui.link(text).clicked() {
+                                    action = Some(CompareAction::Select(entity.id));
+                                }
+                            }
+                            _ => {
+                                ui.label(text);
+                            }
+                        }
+                    }
+                });
+
+            ui.separator();
+            if ui.button("Close Comparison").clicked() {
+                action = Some(CompareAction::Close);
+            }
+        });
+
+    match action {
+        Some(CompareAction::Select(id)) => {
+            let selection = &mut vm.active_tab_mut().selection_manager;
+            selection.clear();
+            selection.selected_ids.insert(id);
+            selection.last_interacted_id = Some(id);
+        }
+        Some(CompareAction::Close) => {
+            vm.close_comparison();
+            return;
+        }
+        None => {}
+    }
+    // Closing the window ends the comparison
+    if !open {
+        vm.close_comparison();
+    }
+}
@@ -0,0 +1,223 @@
+//! Dynamic input: fields next to the cursor that show the active command's
+//! live values (e.g. LINE length and angle) and take typed ones.
+
+use crate::commands::DynamicInput;
+use crate::model::Vector2;
+use crate::model::math::angle::AngleFormat;
+use crate::view::rendering::context::DrawContext;
+use crate::viewmodel::CadViewModel;
+use eframe::egui;
+
+/// Screen offset of the fields from the cursor
+const CURSOR_OFFSET: egui::Vec2 = egui::vec2(16.0, 16.0);
+
+/// Typed field texts while the user is entering values
+#[derive(Clone)]
+struct Editing {
+    /// Command run and point count the texts belong to
+    step: (u64, usize),
+    texts: Vec<String>,
+    field: usize,
+}
+
+/// Where the fields were last shown, kept while the pointer is away
+#[derive(Clone, Copy)]
+struct Anchor {
+    screen: egui::Pos2,
+    cursor: Vector2,
+}
+
+fn state_id() -> egui::Id {
+    egui::Id::new("dynamic_input")
+}
+
+fn anchor_id() -> egui::Id {
+    state_id().with("anchor")
+}
+
+/// The active command's dynamic input and the step it belongs to
+fn current(vm: &CadViewModel) -> Option<(DynamicInput, (u64, usize))> {
+    let tab = vm.tabs.get(vm.active_tab_index)?;
+    if tab.preview_paused {
+        return None;
+    }
+    let run = tab.executor.active_run()?;
+    let (_, points) = tab.executor.get_preview_points()?;
+    Some((tab.executor.dynamic_input()?, (run, points.len())))
+}
+
+/// Whether values are being typed into the fields
+pub fn is_editing(ctx: &egui::Context) -> bool {
+    ctx.data(|d| d.get_temp::<Editing>(state_id()).is_some())
+}
+
+/// Start editing when a number is typed while the active command offers
+/// dynamic input. Call before the terminal is shown so the keystroke does
+/// not reach it.
+pub fn capture_typing(ctx: &egui::Context, vm: &CadViewModel) {
+    if is_editing(ctx) || !vm.command_input.is_empty() {
+        return;
+    }
+    let Some((input, step)) = current(vm) else {
+        return;
+    };
+    let typed = ctx.input(|i| {
+        i.events.iter().find_map(|event| match event {
+            egui::Event::Text(text)
+                if text.starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '.') =>
+            {
+                Some(text.clone())
+            }
+            _ => None,
+        })
+    });
+    let Some(text) = typed else {
+        return;
+    };
+
+    ctx.input_mut(|i| i.events.retain(|e| !matches!(e, egui::Event::Text(_))));
+    let mut texts = vec![String::new(); input.labels().len()];
+    texts[0] = text;
+    ctx.data_mut(|d| {
+        d.insert_temp(
+            state_id(),
+            Editing {
+                step,
+                texts,
+                field: 0,
+            },
+        )
+    });
+}
+
+/// Show the fields next to the cursor. Enter commits the typed values
+/// through the terminal input path; Tab moves to the next field.
+pub fn render_dynamic_input(ui: &egui::Ui, vm: &mut CadViewModel, ctx: &DrawContext) {
+    let ectx = ui.ctx().clone();
+    let current = current(vm);
+
+    // Typed values belong to one step of one command run
+    let mut editing = ectx.data(|d| d.get_temp::<Editing>(state_id()));
+    if editing
+        .as_ref()
+        .is_some_and(|e| current.is_none_or(|(_, step)| step != e.step))
+    {
+        editing = None;
+        ectx.data_mut(|d| d.remove::<Editing>(state_id()));
+    }
+    let Some((input, _)) = current else {
+        return;
+    };
+
+    let anchor = match vm.active_tab().cursor {
+        Some(cursor) => {
+            let anchor = Anchor {
+                screen: ctx.to_screen(cursor.constrained) + CURSOR_OFFSET,
+                cursor: cursor.constrained,
+            };
+            ectx.data_mut(|d| d.insert_temp(anchor_id(), anchor));
+            anchor
+        }
+        None => match ectx.data(|d| d.get_temp::<Anchor>(anchor_id())) {
+            Some(anchor) if editing.is_some() => anchor,
+            _ => return,
+        },
+    };
+    let live = input.values(anchor.cursor);
+    let labels = input.labels();
+    let angles = vm.config.dimension_style.angles;
+
+    let mut commit = None;
+    if let Some(state) = &mut editing {
+        let (tab, back_tab, enter, escape) = ectx.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Tab),
+                i.consume_key(egui::Modifiers::SHIFT, egui::Key::Tab),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+            )
+        });
+        if tab {
+            state.field = (state.field + 1) % labels.len();
+        } else if back_tab {
+            state.field = (state.field + labels.len() - 1) % labels.len();
+        }
+        if escape {
+            editing = None;
+        } else if enter {
+            commit = Some(input_text(&input, &state.texts, &live, &angles));
+            editing = None;
+        }
+    }
+
+    egui::Area::new(state_id().with("area"))
+        .order(egui::Order::Foreground)
+        .fixed_pos(anchor.screen)
+        .interactable(editing.is_some())
+        .show(&ectx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    for (i, label) in labels.iter().enumerate() {
+                        ui.label(*label);
+                        let shown = input.field_text(i, live[i], &angles);
+                        match &mut editing {
+                            Some(state) => {
+                                let field = ui.add(
+                                    egui::TextEdit::singleline(&mut state.texts[i])
+                                        .id(state_id().with(i))
+                                        .hint_text(shown)
+                                        .desired_width(64.0),
+                                );
+                                if i == state.field && !field.has_focus() {
+                                    field.request_focus();
+                                }
+                            }
+                            None => {
+                                ui.label(egui::RichText::new(shown).monospace());
+                            }
+                        }
+                    }
+                });
+            });
+        });
+
+    match editing {
+        Some(state) => ectx.data_mut(|d| d.insert_temp(state_id(), state)),
+        None => {
+            ectx.data_mut(|d| d.remove::<Editing>(state_id()));
+            if let Some(focused) = ectx.memory(|m| m.focused())
+                && (0..labels.len()).any(|i| state_id().with(i) == focused)
+            {
+                ectx.memory_mut(|m| m.surrender_focus(focused));
+            }
+        }
+    }
+
+    if let Some(text) = commit {
+        vm.command_input = text;
+        vm.process_command();
+    }
+}
+
+/// Terminal input for the typed texts; empty fields take the live value.
+/// Text that is not a value of its field (e.g. "10,20") is passed on as
+/// typed.
+fn input_text(
+    input: &DynamicInput,
+    texts: &[String],
+    live: &[f32],
+    angles: &AngleFormat,
+) -> String {
+    let mut values = Vec::with_capacity(texts.len());
+    for (i, (text, &live)) in texts.iter().zip(live).enumerate() {
+        let text = text.trim();
+        if text.is_empty() {
+            values.push(live);
+        } else if let Some(value) = input.parse_field(i, text, angles) {
+            values.push(value);
+        } else {
+            return texts[0].trim().to_string();
+        }
+    }
+    input.terminal_input(&values)
+}
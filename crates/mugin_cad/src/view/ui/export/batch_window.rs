@@ -0,0 +1,217 @@
+use crate::export::batch::{BatchOutput, DEFAULT_PATTERN, file_name};
+use crate::export::settings::{ExportSettings, PageOrientation, PageSize, ScaleType};
+use eframe::egui;
+use std::collections::HashSet;
+
+/// What a batch prints a sheet for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BatchSource {
+    /// Each checked tab
+    #[default]
+    Tabs,
+    /// Each checked storey of the active tab
+    Storeys,
+}
+
+/// A batch the user asked for
+pub struct BatchRequest {
+    pub source: BatchSource,
+    /// Tab or storey IDs, in list order
+    pub ids: Vec<u64>,
+    /// Put in for `{project}`
+    pub project: String,
+    pub output: BatchOutput,
+}
+
+/// Checklist of tabs or storeys printed together with the Export PDF
+/// window's settings
+pub struct BatchExportWindow {
+    pub open: bool,
+    pub source: BatchSource,
+    /// Tabs and storeys left unchecked; new ones start checked
+    pub skipped: HashSet<u64>,
+    /// One multi-page file instead of a file per sheet
+    pub merged: bool,
+    pub pattern: String,
+    /// Set when the user asks to edit the shared page settings
+    pub edit_settings: bool,
+}
+
+impl Default for BatchExportWindow {
+    fn default() -> Self {
+        Self {
+            open: false,
+            source: BatchSource::Tabs,
+            skipped: HashSet::new(),
+            merged: false,
+            pattern: DEFAULT_PATTERN.to_string(),
+            edit_settings: false,
+        }
+    }
+}
+
+impl BatchExportWindow {
+    /// Show the window. `tabs` and `storeys` are the IDs and names to pick
+    /// from and `project` is the active tab's name. Returns the batch once
+    /// the user picks where it goes.
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        tabs: &[(u64, String)],
+        storeys: &[(u64, String)],
+        project: &str,
+        settings: &ExportSettings,
+        busy: bool,
+    ) -> Option<BatchRequest> {
+        if !self.open {
+            return None;
+        }
+        if storeys.is_empty() {
+            self.source = BatchSource::Tabs;
+        }
+
+        let mut request = None;
+        let mut close_window = false;
+        let mut open = self.open;
+        egui::Window::new("Batch Export")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut self.source, BatchSource::Tabs, "Open tabs");
+                    ui.add_enabled_ui(!storeys.is_empty(), |ui| {
+                        ui.radio_value(&mut self.source, BatchSource::Storeys, "Storeys")
+                            .on_disabled_hover_text("This drawing has no storeys");
+                    });
+                });
+                let items = match self.source {
+                    BatchSource::Tabs => tabs,
+                    BatchSource::Storeys => storeys,
+                };
+
+                ui.group(|ui| {
+                    ui.horizontal(|ui| {
+                        if ui.small_button("All").clicked() {
+                            for (id, _) in items {
+                                self.skipped.remove(id);
+                            }
+                        }
+                        if ui.small_button("None").clicked() {
+                            self.skipped.extend(items.iter().map(|(id, _)| *id));
+                        }
+                    });
+                    egui::ScrollArea::vertical()
+                        .max_height(180.0)
+                        .show(ui, |ui| {
+                            for (id, name) in items {
+                                let mut checked = !self.skipped.contains(id);
+                                if ui.checkbox(&mut checked, name).changed() {
+                                    if checked {
+                                        self.skipped.remove(id);
+                                    } else {
+                                        self.skipped.insert(*id);
+                                    }
+                                }
+                            }
+                        });
+                });
+
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    ui.label(settings_summary(settings));
+                    if ui.button("Page Settings...").clicked() {
+                        self.edit_settings = true;
+                    }
+                });
+
+                ui.add_space(4.0);
+                ui.group(|ui| {
+                    ui.radio_value(&mut self.merged, false, "A file per sheet");
+                    ui.add_enabled_ui(!self.merged, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("File name");
+                            ui.text_edit_singleline(&mut self.pattern)
+                                .on_hover_text("{project}: this drawing, {tab}: the tab or storey");
+                        });
+                        if let Some((_, first)) = items.first() {
+                            ui.weak(format!("e.g. {}", file_name(&self.pattern, project, first)));
+                        }
+                    });
+                    ui.radio_value(&mut self.merged, true, "One multi-page PDF");
+                });
+
+                let ids: Vec<u64> = items
+                    .iter()
+                    .map(|(id, _)| *id)
+                    .filter(|id| !self.skipped.contains(id))
+                    .collect();
+
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    let label = format!("Export {} sheets...", ids.len());
+                    if ui
+                        .add_enabled(!busy && !ids.is_empty(), egui::Button::new(label))
+                        .clicked()
+                        && let Some(output) = self.pick_output(project)
+                    {
+                        request = Some(BatchRequest {
+                            source: self.source,
+                            ids,
+                            project: project.to_string(),
+                            output,
+                        });
+                        close_window = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        close_window = true;
+                    }
+                });
+            });
+
+        self.open = open && !close_window;
+        request
+    }
+
+    /// Ask for the merged file or the folder the sheets go in
+    fn pick_output(&self, project: &str) -> Option<BatchOutput> {
+        if self.merged {
+            rfd::FileDialog::new()
+                .add_filter("PDF", &["pdf"])
+                .set_file_name(format!("{}.pdf", project))
+                .save_file()
+                .map(BatchOutput::Merged)
+        } else {
+            rfd::FileDialog::new()
+                .pick_folder()
+                .map(|directory| BatchOutput::Separate {
+                    directory,
+                    pattern: self.pattern.clone(),
+                })
+        }
+    }
+}
+
+/// Page size, orientation and scale in a few words
+fn settings_summary(settings: &ExportSettings) -> String {
+    let page = match settings.page_size {
+        PageSize::A4 => "A4".to_string(),
+        PageSize::A3 => "A3".to_string(),
+        PageSize::Custom(w, h) => format!("{:.0}×{:.0} mm", w, h),
+    };
+    let orientation = match settings.orientation {
+        PageOrientation::Portrait => "portrait",
+        PageOrientation::Landscape => "landscape",
+    };
+    let scale = match settings.scale_type {
+        ScaleType::FitToPage => "fit to page".to_string(),
+        ScaleType::Standard(ratio) | ScaleType::Custom(ratio) => format!("1:{}", ratio),
+    };
+    format!(
+        "{} {}, {}, {}",
+        page,
+        orientation,
+        scale,
+        settings.plot_style.label().to_lowercase()
+    )
+}
@@ -0,0 +1,67 @@
+use crate::export::data::{DataExportOptions, DataFormat};
+use eframe::egui;
+use mugin_widgets::window::{Modal, ModalResponse};
+use std::path::PathBuf;
+
+/// Options dialog for exporting entity geometry as CSV or JSON
+#[derive(Default)]
+pub struct DataExportWindow {
+    pub open: bool,
+    pub options: DataExportOptions,
+}
+
+impl DataExportWindow {
+    /// Show the dialog. Returns the chosen output path when the user asks
+    /// to export. `has_selection` enables the "selected only" option.
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        default_name: &str,
+        has_selection: bool,
+    ) -> Option<PathBuf> {
+        let options = &mut self.options;
+        let response =
+            Modal::new("Export Data")
+                .enter_accepts(false)
+                .show(ctx, &mut self.open, |ui, _| {
+                    ui.group(|ui| {
+                        ui.label("Format");
+                        ui.radio_value(&mut options.format, DataFormat::Csv, "CSV");
+                        ui.radio_value(&mut options.format, DataFormat::Json, "JSON");
+                    });
+
+                    ui.add_space(4.0);
+                    if !has_selection {
+                        options.selected_only = false;
+                    }
+                    ui.add_enabled(
+                        has_selection,
+                        egui::Checkbox::new(&mut options.selected_only, "Selected only"),
+                    );
+                    ui.checkbox(&mut options.flatten, "Flatten hierarchy")
+                        .on_hover_text("Leave out groups and parent links");
+
+                    ui.add_space(8.0);
+                    ui.horizontal(|ui| {
+                        let extension = options.format.extension();
+                        if ui.button("Export...").clicked()
+                            && let Some(path) = rfd::FileDialog::new()
+                                .add_filter(extension.to_uppercase(), &[extension])
+                                .set_file_name(format!("{}.{}", default_name, extension))
+                                .save_file()
+                        {
+                            return ModalResponse::Accepted(path);
+                        }
+                        if ui.button("Cancel").clicked() {
+                            return ModalResponse::Closed;
+                        }
+                        ModalResponse::Pending
+                    })
+                    .inner
+                });
+        match response {
+            ModalResponse::Accepted(path) => Some(path),
+            _ => None,
+        }
+    }
+}
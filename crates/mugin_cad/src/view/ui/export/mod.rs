@@ -1 +1,3 @@
+pub mod batch_window;
+pub mod data_window;
 pub mod window;
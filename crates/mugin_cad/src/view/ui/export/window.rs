@@ -1,32 +1,102 @@
-use crate::export::pdf::PdfExporter;
-use crate::export::settings::{ExportSettings, ExportSource, PageOrientation, PageSize, ScaleType};
-use crate::model::{CadModel, Shape, Vector2};
+use crate::export::layout::ScaleBar;
+use crate::export::settings::{
+    ExportSettings, ExportSource, LineWeights, MIN_WEIGHT_MM, PageOrientation, PageSize, PlotStyle,
+    ScaleType, WeightMode,
+};
+use crate::model::color_mode::ColorMode;
+use crate::model::layer::LayerManager;
+use crate::model::{CadModel, Entity, Shape, Vector2};
+use crate::view::rendering::context::fan_mesh;
 use eframe::egui;
+use std::path::PathBuf;
+use std::sync::mpsc;
+
+/// What a preview was built from; a new preview is built when this changes
+#[derive(Clone, PartialEq)]
+struct PreviewKey {
+    settings: ExportSettings,
+    entity_count: usize,
+    bounds: (Vector2, Vector2),
+    /// Layers left off the sheet
+    unplotted: Vec<u64>,
+}
+
+/// How much thicker lines are drawn in the preview than to scale
+const PREVIEW_WEIGHT_GAIN: f32 = 4.0;
+
+/// Custom scale offered when switching to it
+const DEFAULT_CUSTOM_RATIO: f32 = 200.0;
+const MAX_CUSTOM_RATIO: f32 = 100_000.0;
+
+/// A line of the preview as it prints
+struct PreviewSegment {
+    points: [Vector2; 2],
+    /// Printed weight in millimetres
+    weight: f32,
+    color: egui::Color32,
+}
+
+/// Preview geometry in page millimetres (Y up), built on a worker thread
+#[derive(Default)]
+struct PreviewData {
+    segments: Vec<PreviewSegment>,
+    /// Filled pie slices as a center and rim
+    fans: Vec<(Vector2, Vec<Vector2>, egui::Color32)>,
+    texts: Vec<(Vector2, String, egui::Color32)>,
+    /// Parts of the drawing off the printable area
+    clipped: Vec<(Vector2, Vector2)>,
+    scale_bar: Option<ScaleBar>,
+}
+
+/// Picking the export region on the canvas, with the window hidden
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegionPick {
+    /// Asked for from the window; the command is not running yet
+    Requested,
+    /// The region command is running
+    Picking,
+}
 
+#[derive(Default)]
 pub struct ExportWindow {
     pub open: bool,
     pub settings: ExportSettings,
-}
-
-impl Default for ExportWindow {
-    fn default() -> Self {
-        Self {
-            open: false,
-            settings: ExportSettings::default(),
-        }
-    }
+    /// Print in the canvas's color mode instead of black
+    pub use_view_colors: bool,
+    pub region_pick: Option<RegionPick>,
+    preview: Option<(PreviewKey, PreviewData)>,
+    pending_preview: Option<(PreviewKey, mpsc::Receiver<PreviewData>)>,
 }
 
 impl ExportWindow {
-    pub fn show(&mut self, ctx: &egui::Context, model: &CadModel) {
+    /// Show the window. Returns the chosen output path when the user asks
+    /// to export; the export itself runs in the background. `busy` disables
+    /// exporting while another file operation is in flight. `color_mode`
+    /// is the canvas's, printed only when opted in.
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        model: &CadModel,
+        busy: bool,
+        color_mode: ColorMode,
+    ) -> Option<PathBuf> {
         if !self.open {
-            return;
+            return None;
         }
+        self.settings.color_mode = if self.use_view_colors {
+            color_mode
+        } else {
+            ColorMode::Normal
+        };
+
+        self.update_preview(ctx, model);
 
         let mut close_window = false;
+        let mut export_path = None;
 
+        let mut open = self.open;
         egui::Window::new("Export PDF")
-            .open(&mut self.open)
+            .open(&mut open)
             .resize(|r| r.fixed_size([800.0, 600.0]))
             .show(ctx, |ui| {
                 ui.horizontal(|ui| {
@@ -34,8 +104,9 @@ impl ExportWindow {
                     ui.vertical(|ui| {
                         ui.heading("Preview");
                         egui::Frame::canvas(ui.style()).show(ui, |ui| {
-                            ExportWindow::draw_preview(ui, &self.settings, model);
+                            self.draw_preview(ui, model.entities.is_empty());
                         });
+                        unplotted_note(ui, model);
                     });
 
                     ui.separator();
@@ -43,128 +114,237 @@ impl ExportWindow {
                     // Right Panel: Settings
                     ui.vertical(|ui| {
                         ui.heading("Settings");
-
-                        // Page Size
-                        ui.group(|ui| {
-                            ui.label("Page Size");
-                            ui.radio_value(&mut self.settings.page_size, PageSize::A4, "A4");
-                            ui.radio_value(&mut self.settings.page_size, PageSize::A3, "A3");
-                        });
-
-                        ui.separator();
-
-                        // Orientation
-                        ui.group(|ui| {
-                            ui.label("Orientation");
-                            ui.radio_value(
-                                &mut self.settings.orientation,
-                                PageOrientation::Portrait,
-                                "Portrait",
-                            );
-                            ui.radio_value(
-                                &mut self.settings.orientation,
-                                PageOrientation::Landscape,
-                                "Landscape",
-                            );
-                        });
-
-                        ui.separator();
-
-                        // Scale
-                        ui.group(|ui| {
-                            ui.label("Scale");
-                            ui.radio_value(
-                                &mut self.settings.scale_type,
-                                ScaleType::FitToPage,
-                                "Fit to Page",
-                            );
-                            ui.radio_value(
-                                &mut self.settings.scale_type,
-                                ScaleType::Standard(50.0),
-                                "1:50",
-                            );
-                            ui.radio_value(
-                                &mut self.settings.scale_type,
-                                ScaleType::Standard(100.0),
-                                "1:100",
-                            );
-                        });
-
-                        ui.separator();
-
-                        // Source
-                        ui.group(|ui| {
-                            ui.label("Source");
-                            ui.radio_value(
-                                &mut self.settings.source,
-                                ExportSource::ModelBounds,
-                                "All Entities",
-                            );
-
-                            let is_viewport =
-                                matches!(self.settings.source, ExportSource::Viewport(_, _));
-                            let viewport_val = if is_viewport {
-                                self.settings.source
-                            } else {
-                                ExportSource::Viewport(
-                                    Vector2::new(0.0, 0.0),
-                                    Vector2::new(100.0, 100.0),
-                                )
-                            };
-
-                            if ui
-                                .radio_value(&mut self.settings.source, viewport_val, "Region")
-                                .clicked()
-                            {
-                                // If clicked, try to sync
-                            }
-
-                            if matches!(self.settings.source, ExportSource::Viewport(_, _)) {
-                                if let Some((min, max)) = model.export_region {
-                                    self.settings.source = ExportSource::Viewport(min, max);
-                                    ui.label(format!(
-                                        "Selected: {:.0},{:.0} - {:.0},{:.0}",
-                                        min.x, min.y, max.x, max.y
-                                    ));
+                        egui::ScrollArea::vertical().show(ui, |ui| {
+                            // Page Size
+                            ui.group(|ui| {
+                                ui.label("Page Size");
+                                ui.radio_value(&mut self.settings.page_size, PageSize::A4, "A4");
+                                ui.radio_value(&mut self.settings.page_size, PageSize::A3, "A3");
+                            });
+
+                            ui.separator();
+
+                            // Orientation
+                            ui.group(|ui| {
+                                ui.label("Orientation");
+                                ui.radio_value(
+                                    &mut self.settings.orientation,
+                                    PageOrientation::Portrait,
+                                    "Portrait",
+                                );
+                                ui.radio_value(
+                                    &mut self.settings.orientation,
+                                    PageOrientation::Landscape,
+                                    "Landscape",
+                                );
+                            });
+
+                            ui.separator();
+
+                            // Scale
+                            ui.group(|ui| {
+                                ui.label("Scale");
+                                ui.radio_value(
+                                    &mut self.settings.scale_type,
+                                    ScaleType::FitToPage,
+                                    "Fit to Page",
+                                );
+                                ui.radio_value(
+                                    &mut self.settings.scale_type,
+                                    ScaleType::Standard(50.0),
+                                    "1:50",
+                                );
+                                ui.radio_value(
+                                    &mut self.settings.scale_type,
+                                    ScaleType::Standard(100.0),
+                                    "1:100",
+                                );
+                                ui.horizontal(|ui| {
+                                    let mut ratio = match self.settings.scale_type {
+                                        ScaleType::Custom(ratio) => ratio,
+                                        _ => DEFAULT_CUSTOM_RATIO,
+                                    };
+                                    let is_custom =
+                                        matches!(self.settings.scale_type, ScaleType::Custom(_));
+                                    if ui.radio(is_custom, "Custom").clicked() {
+                                        self.settings.scale_type = ScaleType::Custom(ratio);
+                                    }
+                                    let response = ui.add_enabled(
+                                        is_custom,
+                                        egui::DragValue::new(&mut ratio)
+                                            .prefix("1:")
+                                            .range(1.0..=MAX_CUSTOM_RATIO)
+                                            .speed(1.0),
+                                    );
+                                    if response.changed() && ratio.is_finite() {
+                                        self.settings.scale_type =
+                                            ScaleType::Custom(ratio.clamp(1.0, MAX_CUSTOM_RATIO));
+                                    }
+                                });
+                                ui.checkbox(&mut self.settings.scale_bar, "Scale bar");
+                            });
+
+                            ui.separator();
+
+                            // Source
+                            ui.group(|ui| {
+                                ui.label("Source");
+                                ui.radio_value(
+                                    &mut self.settings.source,
+                                    ExportSource::ModelBounds,
+                                    "All Entities",
+                                );
+
+                                let is_viewport =
+                                    matches!(self.settings.source, ExportSource::Viewport(_, _));
+                                let viewport_val = if is_viewport {
+                                    self.settings.source
                                 } else {
-                                    ui.label("No region selected.");
-                                    ui.label("Run 'Actions > Select Export Region' first.");
+                                    ExportSource::Viewport(
+                                        Vector2::new(0.0, 0.0),
+                                        Vector2::new(100.0, 100.0),
+                                    )
+                                };
+
+                                if ui
+                                    .radio_value(&mut self.settings.source, viewport_val, "Region")
+                                    .clicked()
+                                {
+                                    // If clicked, try to sync
                                 }
-                            }
-                        });
-
-                        ui.add_space(20.0);
 
-                        // Actions
-                        ui.horizontal(|ui| {
-                            if ui.button("Export...").clicked() {
-                                if let Some(path) = rfd::FileDialog::new()
-                                    .add_filter("PDF", &["pdf"])
-                                    .save_file()
-                                {
-                                    if let Err(e) =
-                                        PdfExporter::export_to_file(model, &self.settings, &path)
-                                    {
-                                        eprintln!("Export failed: {}", e);
+                                if matches!(self.settings.source, ExportSource::Viewport(_, _)) {
+                                    if let Some((min, max)) = model.export_region {
+                                        self.settings.source = ExportSource::Viewport(min, max);
+                                        ui.label(format!(
+                                            "Selected: {:.0},{:.0} - {:.0},{:.0}",
+                                            min.x, min.y, max.x, max.y
+                                        ));
                                     } else {
-                                        close_window = true;
+                                        ui.label("No region selected.");
                                     }
                                 }
-                            }
-                            if ui.button("Cancel").clicked() {
-                                close_window = true;
-                            }
+                                if ui
+                                    .button("Pick Region")
+                                    .on_hover_text("Click two corners on the canvas")
+                                    .clicked()
+                                {
+                                    self.region_pick = Some(RegionPick::Requested);
+                                    close_window = true;
+                                }
+                            });
+
+                            ui.separator();
+
+                            // Colors
+                            ui.group(|ui| {
+                                ui.label("Colors");
+                                ui.horizontal(|ui| {
+                                    for style in PlotStyle::ALL {
+                                        ui.radio_value(
+                                            &mut self.settings.plot_style,
+                                            style,
+                                            style.label(),
+                                        );
+                                    }
+                                });
+                                let colored = self.settings.plot_style != PlotStyle::Monochrome;
+                                ui.add_enabled_ui(
+                                    colored && color_mode != ColorMode::Normal,
+                                    |ui| {
+                                        ui.checkbox(
+                                            &mut self.use_view_colors,
+                                            format!("Use view colors ({})", color_mode.label()),
+                                        )
+                                        .on_disabled_hover_text(
+                                            "Pick a color mode in the View menu and print in color",
+                                        );
+                                    },
+                                );
+                            });
+
+                            ui.separator();
+
+                            // Line weights
+                            ui.group(|ui| {
+                                ui.label("Line Weights");
+                                line_weight_settings(ui, &mut self.settings.line_weights, model);
+                            });
+
+                            ui.add_space(20.0);
+
+                            // Actions
+                            ui.horizontal(|ui| {
+                                if ui
+                                    .add_enabled(!busy, egui::Button::new("Export..."))
+                                    .clicked()
+                                    && let Some(path) = rfd::FileDialog::new()
+                                        .add_filter("PDF", &["pdf"])
+                                        .save_file()
+                                {
+                                    export_path = Some(path);
+                                    close_window = true;
+                                }
+                                if ui.button("Cancel").clicked() {
+                                    close_window = true;
+                                }
+                            });
                         });
                     });
                 });
             });
 
-        if close_window {
-            self.open = false;
+        self.open = open && !close_window;
+        export_path
+    }
+
+    /// Start a worker for the preview when the settings or model changed,
+    /// and pick up its result when ready. The old preview stays up meanwhile.
+    fn update_preview(&mut self, ctx: &egui::Context, model: &CadModel) {
+        if let Some((key, receiver)) = &self.pending_preview {
+            match receiver.try_recv() {
+                Ok(data) => {
+                    self.preview = Some((key.clone(), data));
+                    self.pending_preview = None;
+                }
+                Err(mpsc::TryRecvError::Empty) => ctx.request_repaint(),
+                Err(mpsc::TryRecvError::Disconnected) => self.pending_preview = None,
+            }
         }
+
+        let key = PreviewKey {
+            settings: self.settings.clone(),
+            entity_count: model.entities.len(),
+            bounds: model.plot_bounds(),
+            unplotted: model
+                .layer_manager
+                .unplotted_layers()
+                .iter()
+                .map(|layer| layer.id)
+                .collect(),
+        };
+        let up_to_date = self.preview.as_ref().is_some_and(|(k, _)| *k == key)
+            || self
+                .pending_preview
+                .as_ref()
+                .is_some_and(|(k, _)| *k == key);
+        if up_to_date {
+            return;
+        }
+
+        // Clone the entities so the worker never touches the live model
+        let entities = model.plotted_entities();
+        let layers = model.layer_manager.clone();
+        let worker_key = key.clone();
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            let _ = sender.send(build_preview(&worker_key, &entities, &layers));
+        });
+        self.pending_preview = Some((key, receiver));
+        ctx.request_repaint();
     }
 
-    fn draw_preview(ui: &mut egui::Ui, settings: &ExportSettings, model: &CadModel) {
+    fn draw_preview(&self, ui: &mut egui::Ui, model_is_empty: bool) {
         let (rect, _response) =
             ui.allocate_exact_size(egui::vec2(400.0, 500.0), egui::Sense::hover());
 
@@ -173,13 +353,7 @@ impl ExportWindow {
         painter.rect_filled(rect, 0.0, egui::Color32::from_gray(50));
 
         // Draw Page representation
-        let (page_w_mm, page_h_mm) = settings.page_size.dimensions_mm();
-        let (w_mm, h_mm) = if matches!(settings.orientation, PageOrientation::Landscape) {
-            (page_h_mm, page_w_mm)
-        } else {
-            (page_w_mm, page_h_mm)
-        };
-
+        let (w_mm, h_mm) = self.settings.page_dimensions();
         let preview_scale = (rect.width() / w_mm).min(rect.height() / h_mm) * 0.9;
         let display_w = w_mm * preview_scale;
         let display_h = h_mm * preview_scale;
@@ -188,77 +362,214 @@ impl ExportWindow {
             egui::Rect::from_center_size(rect.center(), egui::vec2(display_w, display_h));
         painter.rect_filled(page_rect, 0.0, egui::Color32::WHITE);
 
-        // Calculate bounds
-        let (min_bound, max_bound) = match settings.source {
-            ExportSource::ModelBounds => model.bounds(),
-            ExportSource::Viewport(min, max) => (min, max),
+        if model_is_empty {
+            ui.label("No entities to preview.");
+            return;
+        }
+
+        let Some((_, data)) = &self.preview else {
+            ui.put(
+                egui::Rect::from_center_size(page_rect.center(), egui::vec2(24.0, 24.0)),
+                egui::Spinner::new(),
+            );
+            return;
         };
 
-        let content_width = max_bound.x - min_bound.x;
-        let content_height = max_bound.y - min_bound.y;
+        let to_screen = |p: Vector2| {
+            egui::pos2(
+                page_rect.min.x + p.x * preview_scale,
+                page_rect.max.y - p.y * preview_scale, // Y-up flip
+            )
+        };
 
-        let margin_mm = settings.margin_mm;
-        let print_w = w_mm - margin_mm * 2.0;
-        let print_h = h_mm - margin_mm * 2.0;
+        for (center, rim, color) in &data.fans {
+            let rim: Vec<egui::Pos2> = rim.iter().map(|p| to_screen(*p)).collect();
+            painter.add(fan_mesh(to_screen(*center), &rim, *color));
+        }
 
-        let scale = match settings.scale_type {
-            ScaleType::FitToPage => {
-                let s_x = print_w / content_width;
-                let s_y = print_h / content_height;
-                s_x.min(s_y)
-            }
-            ScaleType::Standard(r) => 1.0 / r,
-            ScaleType::Custom(s) => 1.0 / s,
-        };
+        for (pos, text, color) in &data.texts {
+            painter.text(
+                to_screen(*pos),
+                egui::Align2::CENTER_CENTER,
+                text,
+                egui::FontId::proportional(10.0),
+                *color,
+            );
+        }
 
-        let pdf_content_w = content_width * scale;
-        let pdf_content_h = content_height * scale;
-        let pdf_off_x = margin_mm + (print_w - pdf_content_w) / 2.0;
-        let pdf_off_y = margin_mm + (print_h - pdf_content_h) / 2.0;
-
-        let transform_point = |p: Vector2| -> egui::Pos2 {
-            let rel_x = p.x - min_bound.x;
-            let rel_y = p.y - min_bound.y;
-            let pdf_x = pdf_off_x + rel_x * scale;
-            let pdf_y = pdf_off_y + rel_y * scale;
-            let screen_x = page_rect.min.x + pdf_x * preview_scale;
-            let screen_y = page_rect.max.y - pdf_y * preview_scale; // Y-up flip
-            egui::pos2(screen_x, screen_y)
-        };
+        // Weights are exaggerated so the difference shows at this size
+        painter.extend(data.segments.iter().map(|segment| {
+            let width = (segment.weight * preview_scale * PREVIEW_WEIGHT_GAIN).max(0.5);
+            let stroke = egui::Stroke::new(width, segment.color);
+            let [a, b] = segment.points;
+            egui::Shape::line_segment([to_screen(a), to_screen(b)], stroke)
+        }));
 
-        if model.entities.is_empty() {
-            ui.label("No entities to preview.");
-            return;
+        if let Some(bar) = &data.scale_bar {
+            draw_scale_bar(&painter, bar, to_screen);
         }
 
-        // Draw entities using polyline conversion
-        let stroke = egui::Stroke::new(1.0, egui::Color32::BLACK);
-        let mut shapes = Vec::new();
-
-        for entity in &model.entities {
-            match &entity.shape {
-                Shape::Text(text) => {
-                    let p = transform_point(text.position);
-                    painter.text(
-                        p,
-                        egui::Align2::CENTER_CENTER,
-                        &text.text,
-                        egui::FontId::proportional(10.0),
-                        egui::Color32::BLACK,
-                    );
-                }
-                _ => {
-                    let polyline = entity.as_polyline();
-                    let screen_pts: Vec<egui::Pos2> =
-                        polyline.iter().map(|p| transform_point(*p)).collect();
-
-                    for pair in screen_pts.windows(2) {
-                        shapes.push(egui::Shape::line_segment([pair[0], pair[1]], stroke));
-                    }
-                }
+        // Content off the printable area, shaded on the page
+        if !data.clipped.is_empty() {
+            let page_painter = painter.with_clip_rect(page_rect);
+            let shade = egui::Color32::from_rgba_unmultiplied(220, 40, 40, 60);
+            for (min, max) in &data.clipped {
+                let rect = egui::Rect::from_two_pos(to_screen(*min), to_screen(*max));
+                page_painter.rect_filled(rect, 0.0, shade);
             }
+            painter.text(
+                egui::pos2(rect.center().x, rect.bottom() - 8.0),
+                egui::Align2::CENTER_BOTTOM,
+                "⚠ The drawing does not fit the page at this scale",
+                egui::FontId::proportional(13.0),
+                egui::Color32::from_rgb(255, 110, 110),
+            );
         }
+    }
+}
+
+/// "3 layers excluded from plot", naming them on hover
+fn unplotted_note(ui: &mut egui::Ui, model: &CadModel) {
+    let layers = model.layer_manager.unplotted_layers();
+    if layers.is_empty() {
+        return;
+    }
+    let plural = if layers.len() == 1 { "" } else { "s" };
+    let names: Vec<&str> = layers.iter().map(|layer| layer.name.as_str()).collect();
+    ui.label(
+        egui::RichText::new(format!(
+            "🖶 {} layer{} excluded from plot",
+            layers.len(),
+            plural
+        ))
+        .weak(),
+    )
+    .on_hover_text(names.join(", "));
+}
+
+fn weight_mm(value: &mut f32) -> egui::DragValue<'_> {
+    egui::DragValue::new(value)
+        .suffix(" mm")
+        .range(MIN_WEIGHT_MM..=5.0)
+        .speed(0.01)
+        .fixed_decimals(2)
+}
+
+/// Stroke width factor or a grid of per-layer weights, plus the weight of
+/// annotations
+fn line_weight_settings(ui: &mut egui::Ui, weights: &mut LineWeights, model: &CadModel) {
+    ui.horizontal(|ui| {
+        ui.radio_value(&mut weights.mode, WeightMode::StrokeWidth, "Stroke width ×");
+        ui.add(
+            egui::DragValue::new(&mut weights.factor)
+                .suffix(" mm/px")
+                .range(0.01..=2.0)
+                .speed(0.005)
+                .fixed_decimals(3),
+        );
+    });
+    ui.radio_value(&mut weights.mode, WeightMode::PerLayer, "Per layer");
+    if weights.mode == WeightMode::PerLayer {
+        egui::ScrollArea::vertical()
+            .max_height(110.0)
+            .show(ui, |ui| {
+                egui::Grid::new("export_layer_weights")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for layer in model.layer_manager.get_sorted_layers() {
+                            let fallback = layer.stroke_width * weights.factor;
+                            let mut weight =
+                                weights.layers.get(&layer.id).copied().unwrap_or(fallback);
+                            ui.label(&layer.name);
+                            if ui.add(weight_mm(&mut weight)).changed() {
+                                weights.layers.insert(layer.id, weight);
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+    ui.horizontal(|ui| {
+        ui.label("Dimensions");
+        ui.add(weight_mm(&mut weights.annotation));
+    });
+}
 
-        painter.extend(shapes);
+/// Lay out the drawing on the page the same way the PDF exporter does
+fn build_preview(key: &PreviewKey, entities: &[Entity], layers: &LayerManager) -> PreviewData {
+    let layout = key.settings.layout(key.bounds);
+    let to_page = |p: Vector2| layout.to_page(p);
+    let weights = &key.settings.line_weights;
+
+    // Convert entities using polylines
+    let mut data = PreviewData::default();
+    for entity in entities {
+        let layer = layers.get_layer(entity.layer_id);
+        let style = entity.style(layer);
+        let weight = weights.weight(style.stroke_width, entity.layer_id);
+        let color = key.settings.print_color(entity, layer);
+        let segment = |pair: &[Vector2]| PreviewSegment {
+            points: [pair[0], pair[1]],
+            weight,
+            color,
+        };
+        match &entity.shape {
+            Shape::Text(text) => {
+                data.texts
+                    .push((to_page(text.position), text.text.clone(), color))
+            }
+            Shape::Arc(arc) if arc.filled => {
+                let world = |p: Vector2| -> Vector2 {
+                    to_page(entity.world_transform.transform_point2(p.into()).into())
+                };
+                let rim: Vec<Vector2> = arc.points(24).into_iter().map(world).collect();
+                data.segments.extend(rim.windows(2).map(segment));
+                data.fans.push((world(arc.center), rim, color));
+            }
+            _ => {
+                let points: Vec<Vector2> = entity.as_polyline().into_iter().map(to_page).collect();
+                data.segments.extend(points.windows(2).map(segment));
+            }
+        }
+    }
+    data.clipped = layout.clipped();
+    data.scale_bar = key.settings.scale_bar.then(|| layout.scale_bar());
+    data
+}
+
+/// Scale bar on the preview page, matching the printed one
+fn draw_scale_bar(
+    painter: &egui::Painter,
+    bar: &ScaleBar,
+    to_screen: impl Fn(Vector2) -> egui::Pos2,
+) {
+    let stroke = egui::Stroke::new(1.0, egui::Color32::BLACK);
+    let step = bar.length / bar.divisions as f32;
+    for i in 0..bar.divisions {
+        let min = bar.origin + Vector2::new(step * i as f32, 0.0);
+        let max = min + Vector2::new(step, bar.height);
+        let rect = egui::Rect::from_two_pos(to_screen(min), to_screen(max));
+        let fill = if i % 2 == 0 {
+            egui::Color32::BLACK
+        } else {
+            egui::Color32::WHITE
+        };
+        painter.rect(rect, 0.0, fill, stroke);
     }
+    let font = egui::FontId::proportional(9.0);
+    painter.text(
+        to_screen(bar.origin + Vector2::new(bar.length, 0.0)),
+        egui::Align2::RIGHT_TOP,
+        &bar.label,
+        font.clone(),
+        egui::Color32::BLACK,
+    );
+    painter.text(
+        to_screen(bar.origin + Vector2::new(bar.length / 2.0, bar.height)),
+        egui::Align2::CENTER_BOTTOM,
+        &bar.ratio,
+        font,
+        egui::Color32::BLACK,
+    );
 }
@@ -0,0 +1,141 @@
+use crate::model::tools::find_replace::{FindField, FindMatch, FindScope, find_matches};
+use crate::viewmodel::CadViewModel;
+use eframe::egui;
+use std::collections::HashSet;
+
+/// Edit ▸ Find & Replace window state
+#[derive(Default)]
+pub struct FindReplaceWindow {
+    pub open: bool,
+    pub find: String,
+    pub replace: String,
+    pub case_sensitive: bool,
+    pub scope: FindScope,
+    /// Matches the user unticked in the preview
+    excluded: HashSet<(u64, FindField)>,
+}
+
+pub fn render_find_replace_window(ctx: &egui::Context, vm: &mut CadViewModel) {
+    let mut window = std::mem::take(&mut vm.find_replace_window);
+    let mut open = window.open;
+    let mut run = None;
+
+    let tab = vm.active_tab();
+    // A layer scope whose layer went away falls back to the whole project
+    if let FindScope::Layer(id) = window.scope
+        && tab.model.layer_manager.get_layer(id).is_none()
+    {
+        window.scope = FindScope::Project;
+    }
+    let matches = find_matches(
+        &tab.model,
+        window.scope,
+        &tab.selection_manager.selected_ids,
+        &window.find,
+        &window.replace,
+        window.case_sensitive,
+    );
+
+    egui::Window::new("Find & Replace")
+        .open(&mut open)
+        .min_width(360.0)
+        .show(ctx, |ui| {
+            egui::Grid::new("find_replace_fields")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label("Find:");
+                    ui.text_edit_singleline(&mut window.find);
+                    ui.end_row();
+
+                    ui.label("Replace:");
+                    ui.text_edit_singleline(&mut window.replace);
+                    ui.end_row();
+
+                    ui.label("In:");
+                    let layers = tab.model.layer_manager.get_sorted_layers();
+                    let scope_text = match window.scope {
+                        FindScope::Selection => "Selection".to_string(),
+                        FindScope::Layer(id) => layers
+                            .iter()
+                            .find(|layer| layer.id == id)
+                            .map(|layer| format!("Layer: {}", layer.name))
+                            .unwrap_or_default(),
+                        FindScope::Project => "Whole project".to_string(),
+                    };
+                    egui::ComboBox::from_id_salt("find_replace_scope")
+                        .selected_text(scope_text)
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(
+                                &mut window.scope,
+                                FindScope::Selection,
+                                "Selection",
+                            );
+                            for layer in &layers {
+                                ui.selectable_value(
+                                    &mut window.scope,
+                                    FindScope::Layer(layer.id),
+                                    format!("Layer: {}", layer.name),
+                                );
+                            }
+                            ui.selectable_value(
+                                &mut window.scope,
+                                FindScope::Project,
+                                "Whole project",
+                            );
+                        });
+                    ui.end_row();
+                });
+            ui.checkbox(&mut window.case_sensitive, "Match case");
+
+            ui.separator();
+
+            // Preview
+            if matches.is_empty() {
+                let hint = if window.find.is_empty() {
+                    "Type something to find"
+                } else {
+                    "No matches"
+                };
+                ui.label(egui::RichText::new(hint).weak());
+            }
+            egui::ScrollArea::vertical()
+                .max_height(240.0)
+                .show(ui, |ui| {
+                    for m in &matches {
+                        let key = (m.entity_id, m.field);
+                        let mut included = !window.excluded.contains(&key);
+                        let text = format!("{}: {} → {}", m.field.label(), m.before, m.after);
+                        if ui.checkbox(&mut included, text).changed() {
+                            if included {
+                                window.excluded.remove(&key);
+                            } else {
+                                window.excluded.insert(key);
+                            }
+                        }
+                    }
+                });
+
+            ui.separator();
+            let chosen: Vec<FindMatch> = matches
+                .iter()
+                .filter(|m| !window.excluded.contains(&(m.entity_id, m.field)))
+                .cloned()
+                .collect();
+            if ui
+                .add_enabled(
+                    !chosen.is_empty(),
+                    egui::Button::new(format!("Replace ({})", chosen.len())),
+                )
+                .clicked()
+            {
+                run = Some(chosen);
+            }
+        });
+
+    if let Some(chosen) = run {
+        vm.replace_matches(&chosen);
+        window.excluded.clear();
+    }
+    window.open = open;
+    vm.find_replace_window = window;
+}
@@ -3,7 +3,7 @@
 //! Converts the `CadModel` entity list into `TreeNode`s and uses the
 //! reusable `mugin_widgets::hierarchy` widget to display them.
 
-use crate::model::{Entity, Shape};
+use crate::model::{CadModel, Entity, FlagColor, Shape};
 use crate::viewmodel::CadViewModel;
 use eframe::egui;
 use mugin_widgets::hierarchy::TreeNode;
@@ -34,7 +34,11 @@ pub fn render_hierarchy(ui: &mut egui::Ui, vm: &mut CadViewModel) {
             // If empty, let's just make nodes empty.
             (Vec::new(), HashSet::new(), false)
         } else {
-            let nodes: Vec<TreeNode> = entities.iter().map(entity_to_node).collect();
+            let flagged_only = vm.hierarchy_flagged_only;
+            let nodes: Vec<TreeNode> = entities
+                .iter()
+                .filter_map(|entity| entity_to_node(&tab.model, entity, flagged_only))
+                .collect();
             let sel = tab.selection_manager.selected_ids.clone();
             let has_sel = !sel.is_empty();
             (nodes, sel, has_sel)
@@ -45,7 +49,8 @@ pub fn render_hierarchy(ui: &mut egui::Ui, vm: &mut CadViewModel) {
     ui.horizontal(|ui| {
         if ui.button("📁 New Group").clicked() {
             let tab = &mut vm.tabs[vm.active_tab_index];
-            tab.model.add_entity(Entity::empty("New Group"));
+            let name = tab.model.next_name("Group");
+            tab.model.add_entity(Entity::empty(name));
         }
 
         ui.add_enabled_ui(has_selection, |ui| {
@@ -54,15 +59,33 @@ pub fn render_hierarchy(ui: &mut egui::Ui, vm: &mut CadViewModel) {
                 let ids = tab.selection_manager.selected_ids.clone();
                 if !ids.is_empty() {
                     tab.model.remove_entities_by_ids(&ids);
-                    tab.selection_manager.selected_ids.clear();
+                    tab.selection_manager.clear();
                 }
             }
         });
+
+        ui.toggle_value(&mut vm.hierarchy_flagged_only, "⚑ Flagged")
+            .on_hover_text("Show only flagged entities (SELECTFLAG <color> selects them)");
     });
     ui.separator();
 
     // 3. Render tree
-    let response = mugin_widgets::hierarchy::tree_view(ui, &nodes, &selected_ids_set);
+    let mut flag_action = None;
+    let response = mugin_widgets::hierarchy::tree_view(ui, &nodes, &selected_ids_set, |ui, id| {
+        if let Some(flag) = flag_menu(ui) {
+            flag_action = Some((id, flag));
+        }
+    });
+
+    // Flag the row, or the whole selection when the row is part of it
+    if let Some((id, flag)) = flag_action {
+        let ids: Vec<u64> = if selected_ids_set.contains(&id) {
+            selected_ids_set.iter().copied().collect()
+        } else {
+            vec![id]
+        };
+        vm.flag_entities(&ids, flag);
+    }
 
     // Update hierarchy_renaming flag so terminal doesn't steal focus
     vm.hierarchy_renaming = response.is_renaming;
@@ -89,11 +112,11 @@ pub fn render_hierarchy(ui: &mut egui::Ui, vm: &mut CadViewModel) {
                     let (min, max) = (start_idx.min(end_idx), start_idx.max(end_idx));
 
                     if !modifiers.ctrl && !modifiers.command {
-                        tab.selection_manager.selected_ids.clear();
+                        tab.selection_manager.clear();
                     }
 
-                    for i in min..=max {
-                        tab.selection_manager.selected_ids.insert(visible_ids[i]);
+                    for id in &visible_ids[min..=max] {
+                        tab.selection_manager.selected_ids.insert(*id);
                     }
 
                     // Do not update last_interacted_id on shift-click generally, to allow extending range from same anchor?
@@ -101,7 +124,7 @@ pub fn render_hierarchy(ui: &mut egui::Ui, vm: &mut CadViewModel) {
                 }
             } else {
                 // No previous interaction, treat as single select
-                tab.selection_manager.selected_ids.clear();
+                tab.selection_manager.clear();
                 tab.selection_manager.selected_ids.insert(clicked_id);
                 tab.selection_manager.last_interacted_id = Some(clicked_id);
             }
@@ -113,17 +136,21 @@ pub fn render_hierarchy(ui: &mut egui::Ui, vm: &mut CadViewModel) {
                 tab.selection_manager.last_interacted_id = Some(clicked_id);
             }
         } else {
-            tab.selection_manager.selected_ids.clear();
+            tab.selection_manager.clear();
             tab.selection_manager.selected_ids.insert(clicked_id);
             tab.selection_manager.last_interacted_id = Some(clicked_id);
         }
     }
 
-    // Handle renames — update entity name
+    // Handle renames — update entity name, at any depth; blank names are
+    // ignored
     if let Some((renamed_id, new_name)) = response.renamed {
         let tab = &mut vm.tabs[vm.active_tab_index];
-        if let Some(entity) = tab.model.entities.iter_mut().find(|e| e.id == renamed_id) {
-            entity.name = new_name;
+        let new_name = new_name.trim();
+        if !new_name.is_empty()
+            && let Some(entity) = tab.model.find_by_id_mut(renamed_id)
+        {
+            entity.name = new_name.to_string();
         }
     }
 
@@ -149,6 +176,7 @@ pub fn render_hierarchy(ui: &mut egui::Ui, vm: &mut CadViewModel) {
         let top_level_ids = tab.model.get_top_level_selected_ids(&ids_set);
 
         // Remove them all first
+        tab.model.changes.touch_all();
         let mut moved_entities = Vec::new();
         for id in &top_level_ids {
             if let Some(e) = remove_entity_by_id(&mut tab.model.entities, *id) {
@@ -173,23 +201,54 @@ pub fn render_hierarchy(ui: &mut egui::Ui, vm: &mut CadViewModel) {
         }
 
         // Keep selection?
-        // tab.selection_manager.selected_ids.clear();
+        // tab.selection_manager.clear();
     }
 }
 
 // ─── Helpers ─────────────────────────────────────────────────────────────
 
-/// Convert an `Entity` into a `TreeNode` for the hierarchy widget.
-fn entity_to_node(entity: &Entity) -> TreeNode {
+/// "Flag" submenu: the flag picked, `Some(None)` to clear it
+pub fn flag_menu(ui: &mut egui::Ui) -> Option<Option<FlagColor>> {
+    let mut picked = None;
+    ui.menu_button("Flag", |ui| {
+        for flag in FlagColor::ALL {
+            let label = egui::RichText::new(format!("● {}", flag.label())).color(flag.color());
+            if ui.button(label).clicked() {
+                picked = Some(Some(flag));
+                ui.close_menu();
+            }
+        }
+        ui.separator();
+        if ui.button("Clear Flag").clicked() {
+            picked = Some(None);
+            ui.close_menu();
+        }
+    });
+    picked
+}
+
+/// Convert an `Entity` into a `TreeNode` for the hierarchy widget. With
+/// `flagged_only`, unflagged entities holding no flagged ones are left out.
+/// Broken constraints show as a warning badge.
+fn entity_to_node(model: &CadModel, entity: &Entity, flagged_only: bool) -> Option<TreeNode> {
     let icon = shape_icon(&entity.shape);
-    let children = entity.children.iter().map(entity_to_node).collect();
+    let children: Vec<TreeNode> = entity
+        .children
+        .iter()
+        .filter_map(|child| entity_to_node(model, child, flagged_only))
+        .collect();
+    if flagged_only && entity.flag.is_none() && children.is_empty() {
+        return None;
+    }
 
-    TreeNode {
+    Some(TreeNode {
         id: entity.id,
         label: entity.name.clone(),
         icon,
+        marker: entity.flag.map(FlagColor::color),
+        warning: model.constraint_warning(entity.id),
         children,
-    }
+    })
 }
 
 /// Map a `Shape` variant to a display icon.
@@ -203,6 +262,13 @@ fn shape_icon(shape: &Shape) -> &'static str {
         Shape::Text(_) => "🔤",
         Shape::Column(_) => "🏛",
         Shape::Beam(_) => "▬", // Horizontal bar icon for Beam
+        Shape::Image(_) => "🖼",
+        Shape::Section(_) => "✂",
+        Shape::Stair(_) => "🪜",
+        Shape::Flooring(_) => "▦",
+        Shape::RevisionCloud(_) => "☁",
+        Shape::Room(_) => "⌂",
+        Shape::Sketch(_) => "✏",
     }
 }
 
@@ -0,0 +1,147 @@
+//! Canvas hover tooltip — shows entity details after the pointer rests on it.
+
+use crate::model::dimension::DimensionStyle;
+use crate::model::structure::definitions::StructureDefinitions;
+use crate::model::{Entity, Shape};
+use crate::viewmodel::tab::ProjectTab;
+use eframe::egui;
+
+/// Seconds the pointer must rest on the same entity before the tooltip shows
+const HOVER_DELAY: f64 = 0.5;
+
+/// Show the hover tooltip for `hovered_id`. Pass `None` to reset the timer
+/// (nothing hovered, or tooltips suppressed).
+pub fn render_hover_tooltip(
+    ui: &egui::Ui,
+    tab: &ProjectTab,
+    dim_style: &DimensionStyle,
+    hovered_id: Option<u64>,
+) {
+    let state_id = egui::Id::new("canvas_hover_tooltip");
+    let now = ui.input(|i| i.time);
+
+    let Some(id) = hovered_id else {
+        ui.data_mut(|d| d.remove::<(u64, f64)>(state_id));
+        return;
+    };
+
+    // Restart the timer only when the hovered entity changes, so moving
+    // along the same entity keeps the tooltip steady.
+    let since = match ui.data(|d| d.get_temp::<(u64, f64)>(state_id)) {
+        Some((prev_id, since)) if prev_id == id => since,
+        _ => {
+            ui.data_mut(|d| d.insert_temp(state_id, (id, now)));
+            now
+        }
+    };
+
+    let remaining = HOVER_DELAY - (now - since);
+    if remaining > 0.0 {
+        ui.ctx()
+            .request_repaint_after(std::time::Duration::from_secs_f64(remaining));
+        return;
+    }
+
+    let Some(entity) = tab.model.find_by_id(id) else {
+        return;
+    };
+
+    let layer_name = tab
+        .model
+        .layer_manager
+        .get_layer(entity.layer_id)
+        .map(|l| l.name.as_str())
+        .unwrap_or("Unknown");
+
+    egui::show_tooltip_at_pointer(ui.ctx(), ui.layer_id(), state_id.with(id), |ui| {
+        ui.label(egui::RichText::new(&entity.name).strong());
+        ui.label(format!("{} (ID: {})", entity.type_name(), entity.id));
+        ui.label(format!("Layer: {}", layer_name));
+        if let Some(metric) = entity_metric(entity, &tab.model.definitions, dim_style) {
+            ui.label(metric);
+        }
+    });
+}
+
+/// The most relevant measurement for an entity, formatted with the dimension style
+fn entity_metric(
+    entity: &Entity,
+    definitions: &StructureDefinitions,
+    style: &DimensionStyle,
+) -> Option<String> {
+    match &entity.shape {
+        Shape::Line(line) => Some(format!("Length: {}", line.label_text(style))),
+        Shape::Circle(circle) => Some(format!("Radius: {}", style.format_length(circle.radius))),
+        Shape::Rectangle(rect) => Some(format!(
+            "Size: {} × {}",
+            style.format_length((rect.max.x - rect.min.x).abs()),
+            style.format_length((rect.max.y - rect.min.y).abs())
+        )),
+        Shape::Arc(arc) => Some(format!("Radius: {}", style.format_length(arc.radius))),
+        Shape::Text(text) => Some(format!("Text: {}", text.text)),
+        Shape::Column(col) => {
+            let type_name = definitions
+                .get_column_type(col.column_type_id)
+                .map(|t| t.name.as_str())
+                .unwrap_or("Unknown Type");
+            Some(format!(
+                "{} — {}: {} × {}",
+                col.label,
+                type_name,
+                style.format_length(col.width),
+                style.format_length(col.height)
+            ))
+        }
+        Shape::Beam(beam) => {
+            let type_name = definitions
+                .get_beam_type(beam.beam_type_id)
+                .map(|t| t.name.as_str())
+                .unwrap_or("Unknown Type");
+            Some(format!(
+                "{} — {}: {}",
+                beam.label,
+                type_name,
+                style.format_length(beam.length())
+            ))
+        }
+        Shape::Image(image) => {
+            let size = image.size();
+            Some(format!(
+                "{}: {} × {}",
+                image.file_name(),
+                style.format_length(size.x),
+                style.format_length(size.y)
+            ))
+        }
+        Shape::Section(section) => Some(format!(
+            "{}: {}",
+            section.title(),
+            style.format_length(section.start.dist(section.end))
+        )),
+        Shape::Flooring(floor) => Some(format!(
+            "Flooring F{}: {} thick, {:.2} m²",
+            floor.floor_type_id,
+            style.format_length(floor.thickness),
+            floor.area() / 10_000.0
+        )),
+        Shape::Stair(stair) => Some(format!(
+            "Stair: {} risers, run {}",
+            stair.risers,
+            style.format_length(stair.total_run())
+        )),
+        Shape::RevisionCloud(cloud) => Some(match &cloud.tag {
+            Some(tag) => format!("Revision cloud, revision {}", tag),
+            None => "Revision cloud".to_string(),
+        }),
+        Shape::Room(room) => Some(format!("Area: {:.2} m²", room.area_m2())),
+        Shape::Sketch(sketch) => Some(
+            if sketch.highlighter {
+                "Highlighter markup"
+            } else {
+                "Pen markup"
+            }
+            .to_string(),
+        ),
+        Shape::None => None,
+    }
+}
@@ -1,12 +1,22 @@
-use crate::model::Shape;
+use crate::model::layer::LineStyle;
+use crate::model::math::angle::AngleFormat;
 use crate::model::shapes::{
     annotation::TextAnnotation, arc::Arc, circle::Circle, line::Line, rectangle::Rectangle,
+    section::SectionMark,
 };
 use crate::model::structure::beam::BeamData;
 use crate::model::structure::column::ColumnData;
+use crate::model::structure::storey::StoreyManager;
+use crate::model::tools::constraints;
+use crate::model::{
+    CadModel, Entity, EntityStyle, Flooring, ImageRef, RevisionCloud, Room, Shape, Sketch,
+    StairData,
+};
+use crate::view::ui::structure::anchor;
 use crate::viewmodel::CadViewModel;
 use eframe::egui;
-use mugin_widgets::properties;
+use mugin_widgets::properties::{self, Point2Options};
+use std::collections::{BTreeMap, HashSet};
 
 pub fn render_selection_status(ui: &mut egui::Ui, vm: &mut CadViewModel) {
     if vm.tabs.is_empty() {
@@ -14,6 +24,9 @@ pub fn render_selection_status(ui: &mut egui::Ui, vm: &mut CadViewModel) {
         return;
     }
     let tab = vm.active_tab();
+    let mut restore: Option<HashSet<u64>> = None;
+    let mut set_action = None;
+    let mut select_flag = None;
     ui.horizontal(|ui| {
         ui.label(egui::RichText::new("Selection:").strong());
         if tab.selection_manager.selected_ids.len() == 1 {
@@ -38,13 +51,135 @@ pub fn render_selection_status(ui: &mut egui::Ui, vm: &mut CadViewModel) {
         } else {
             ui.label(egui::RichText::new("None").weak());
         }
+
+        // Recent selections, most recent first
+        let recent: Vec<&HashSet<u64>> = tab.selection_manager.history().collect();
+        ui.add_enabled_ui(!recent.is_empty(), |ui| {
+            ui.menu_button("Recent ⏷", |ui| {
+                for ids in recent {
+                    if ui.button(selection_summary(&tab.model, ids)).clicked() {
+                        restore = Some(ids.clone());
+                        ui.close_menu();
+                    }
+                }
+            })
+            .response
+            .on_hover_text("Reselect a recent selection (type P for the previous one)");
+        });
+
+        let sets: Vec<(String, usize)> = tab
+            .selection_sets
+            .iter()
+            .map(|(name, ids)| (name.to_string(), ids.len()))
+            .collect();
+        ui.add_enabled_ui(!sets.is_empty(), |ui| {
+            ui.menu_button("Sets ⏷", |ui| {
+                selection_sets_menu(ui, &sets, &mut set_action)
+            })
+            .response
+            .on_hover_text("Saved selections (SELSAVE <name> saves the current one)");
+        });
+
+        // Flagged entities per color
+        let flags = tab.model.flag_counts();
+        let total: usize = flags.iter().map(|(_, count)| count).sum();
+        ui.add_enabled_ui(total > 0, |ui| {
+            ui.menu_button(format!("⚑ {} ⏷", total), |ui| {
+                for (flag, count) in &flags {
+                    let label = egui::RichText::new(format!("● {} ({})", flag.label(), count))
+                        .color(flag.color());
+                    if ui.button(label).clicked() {
+                        select_flag = Some(flag.label());
+                        ui.close_menu();
+                    }
+                }
+                ui.separator();
+                if ui.button(format!("All flagged ({})", total)).clicked() {
+                    select_flag = Some("all");
+                    ui.close_menu();
+                }
+            })
+            .response
+            .on_hover_text("Flagged entities (SELECTFLAG <color> selects them)");
+        });
+    });
+
+    if let Some(ids) = restore {
+        vm.restore_selection(&ids);
+    }
+    if let Some(name) = select_flag {
+        vm.select_flagged(name);
+    }
+    match set_action {
+        Some(SetAction::Load(name)) => vm.load_selection_set(&name),
+        Some(SetAction::Rename(from, to)) => vm.rename_selection_set(&from, &to),
+        Some(SetAction::Remove(name)) => vm.remove_selection_set(&name),
+        None => {}
+    }
+}
+
+/// What was done in the selection sets menu
+enum SetAction {
+    Load(String),
+    Rename(String, String),
+    Remove(String),
+}
+
+/// Saved selection sets with their sizes, each with rename and delete
+fn selection_sets_menu(
+    ui: &mut egui::Ui,
+    sets: &[(String, usize)],
+    action: &mut Option<SetAction>,
+) {
+    // Set being renamed and the name typed so far
+    let rename_id = egui::Id::new("selection_set_rename");
+    let mut renaming: Option<(String, String)> = ui.data(|d| d.get_temp(rename_id));
+    for (name, count) in sets {
+        ui.horizontal(|ui| match &mut renaming {
+            Some((from, typed)) if from == name => {
+                let response = ui.text_edit_singleline(typed);
+                if !response.has_focus() && !response.lost_focus() {
+                    response.request_focus();
+                }
+                if response.lost_focus() {
+                    if ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                        *action = Some(SetAction::Rename(from.clone(), typed.clone()));
+                    }
+                    renaming = None;
+                }
+            }
+            _ => {
+                if ui.button(format!("{} ({})", name, count)).clicked() {
+                    *action = Some(SetAction::Load(name.clone()));
+                    ui.close_menu();
+                }
+                if ui.small_button("✏").on_hover_text("Rename").clicked() {
+                    renaming = Some((name.clone(), name.clone()));
+                }
+                if ui.small_button("🗑").on_hover_text("Delete").clicked() {
+                    *action = Some(SetAction::Remove(name.clone()));
+                }
+            }
+        });
+    }
+    ui.data_mut(|d| match renaming {
+        Some(renaming) => d.insert_temp(rename_id, renaming),
+        None => d.remove::<(String, String)>(rename_id),
     });
 }
 
+/// Short description of a recorded selection, e.g. "Line #12" or "3 items"
+fn selection_summary(model: &CadModel, ids: &HashSet<u64>) -> String {
+    let alive: Vec<&Entity> = ids.iter().filter_map(|&id| model.find_by_id(id)).collect();
+    match alive.as_slice() {
+        [] => format!("{} items (deleted)", ids.len()),
+        [entity] => format!("{} #{}", entity.type_name(), entity.id),
+        entities => format!("{} items", entities.len()),
+    }
+}
+
 pub fn render_inspector(ui: &mut egui::Ui, vm: &mut CadViewModel) {
-    ui.heading("Inspector");
-    ui.separator();
-    ui.add_space(10.0);
+    ui.add_space(5.0);
 
     if vm.tabs.is_empty() {
         ui.label("No active project");
@@ -145,8 +280,16 @@ pub fn render_inspector(ui: &mut egui::Ui, vm: &mut CadViewModel) {
             let mut is_renaming = false;
             let mut delete_id = None;
             let mut delete_selection = false;
+            let mut calibrate = false;
+            let mut generate_section = false;
 
             let mut pending_layer_change = None;
+            // The entity as it was before an edit this frame
+            let mut edited = None;
+            let reveal = std::mem::take(&mut vm.reveal_in_inspector);
+            let angles = vm.config.dimension_style.angles;
+            let limits = vm.config.geometry_limits.clone();
+            let mut degenerate = None;
 
             {
                 let tab = vm.active_tab_mut();
@@ -163,8 +306,29 @@ pub fn render_inspector(ui: &mut egui::Ui, vm: &mut CadViewModel) {
                         .map(|l| (l.id, l.name.clone()))
                         .collect::<Vec<_>>();
                     let definitions = tab.model.definitions.clone();
+                    let storeys = tab.model.storeys.clone();
+                    // Values an override starts from when "By Layer" is turned off
+                    let effective = tab.model.find_by_id(id).map(|entity| {
+                        entity.style(tab.model.layer_manager.get_layer(entity.layer_id))
+                    });
+                    let constraint_errors: BTreeMap<String, String> = tab
+                        .model
+                        .find_by_id(id)
+                        .map(|entity| {
+                            entity
+                                .constraints
+                                .keys()
+                                .filter_map(|property| {
+                                    let error = tab.model.constraint_error(id, property)?;
+                                    Some((property.clone(), error.to_string()))
+                                })
+                                .collect()
+                        })
+                        .unwrap_or_default();
 
                     if let Some(entity) = tab.model.find_by_id_mut(id) {
+                        let before = entity.clone();
+
                         // Entity name
                         let response = properties::text_input(ui, "Name:", &mut entity.name);
                         if response.has_focus() || response.clicked() {
@@ -202,6 +366,9 @@ pub fn render_inspector(ui: &mut egui::Ui, vm: &mut CadViewModel) {
                                 }
                             }
                         });
+                        if let Some(effective) = effective {
+                            inspect_style(ui, entity, &effective);
+                        }
                         ui.add_space(3.0);
                         ui.label(
                             egui::RichText::new(entity.type_name())
@@ -210,16 +377,50 @@ pub fn render_inspector(ui: &mut egui::Ui, vm: &mut CadViewModel) {
                         );
                         ui.add_space(5.0);
 
-                        match &mut entity.shape {
+                        // Double-clicked on the canvas: bring the properties into view
+                        if reveal {
+                            ui.scroll_to_cursor(Some(egui::Align::TOP));
+                        }
+
+                        // Edit a copy so only real edits bump the revision
+                        let mut shape = entity.shape.clone();
+                        match &mut shape {
                             Shape::Line(line) => inspect_line(ui, line),
                             Shape::Circle(circle) => inspect_circle(ui, circle),
                             Shape::Rectangle(rect) => inspect_rectangle(ui, rect),
-                            Shape::Arc(arc) => inspect_arc(ui, arc),
-                            Shape::Text(text) => inspect_text(ui, text),
-                            Shape::Column(col) => inspect_column(ui, col, &definitions),
-                            Shape::Beam(beam) => inspect_beam(ui, beam, &definitions),
+                            Shape::Arc(arc) => inspect_arc(ui, arc, &angles),
+                            Shape::Text(text) => inspect_text(ui, text, &angles),
+                            Shape::Column(col) => {
+                                inspect_column(ui, col, &definitions, &storeys, &angles)
+                            }
+                            Shape::Beam(beam) => inspect_beam(ui, beam, &definitions, &storeys),
+                            Shape::Image(image) => calibrate = inspect_image(ui, image, &angles),
+                            Shape::Stair(stair) => inspect_stair(ui, stair, &storeys, &angles),
+                            Shape::Flooring(floor) => inspect_flooring(ui, floor, &storeys),
+                            Shape::RevisionCloud(cloud) => inspect_revision_cloud(ui, cloud),
+                            Shape::Room(room) => inspect_room(ui, room),
+                            Shape::Sketch(sketch) => inspect_sketch(ui, sketch),
+                            Shape::Section(section) => {
+                                generate_section = inspect_section(ui, section)
+                            }
                             Shape::None => {}
                         }
+                        // Values below the limits are not applied
+                        if shape != entity.shape {
+                            match limits.check(&shape) {
+                                Some(problem) if limits.check(&entity.shape).is_none() => {
+                                    degenerate = Some(problem);
+                                }
+                                _ => entity.modify(|current| *current = shape),
+                            }
+                        }
+
+                        if inspect_constraints(ui, entity, &constraint_errors) {
+                            is_renaming = true;
+                        }
+                        if inspect_attributes(ui, entity) {
+                            is_renaming = true;
+                        }
 
                         if !entity.children.is_empty() {
                             properties::section(ui, "Children", |ui| {
@@ -229,6 +430,9 @@ pub fn render_inspector(ui: &mut egui::Ui, vm: &mut CadViewModel) {
                                 }
                             });
                         }
+                        if !entity.same_content(&before) {
+                            edited = Some(before);
+                        }
 
                         ui.add_space(20.0);
                         if ui
@@ -313,11 +517,11 @@ pub fn render_inspector(ui: &mut egui::Ui, vm: &mut CadViewModel) {
                                 // Check if any have children
                                 let mut any_has_children = false;
                                 for id in &ids {
-                                    if let Some(e) = tab.model.find_by_id(*id) {
-                                        if !e.children.is_empty() {
-                                            any_has_children = true;
-                                            break;
-                                        }
+                                    if let Some(e) = tab.model.find_by_id(*id)
+                                        && !e.children.is_empty()
+                                    {
+                                        any_has_children = true;
+                                        break;
                                     }
                                 }
 
@@ -363,6 +567,17 @@ pub fn render_inspector(ui: &mut egui::Ui, vm: &mut CadViewModel) {
             }
 
             vm.inspector_renaming = is_renaming;
+            if let Some(problem) = degenerate {
+                vm.active_tab_mut().executor.status_message = format!("{} — ignored", problem);
+            }
+
+            // A drag or a typed value becomes one undo step once it is done
+            if let Some(before) = edited {
+                vm.begin_property_edit(before);
+            }
+            if !editing_in(ui) {
+                vm.end_property_edit();
+            }
 
             // Handle deferred deletion
             if let Some(id_to_delete) = delete_id {
@@ -377,25 +592,309 @@ pub fn render_inspector(ui: &mut egui::Ui, vm: &mut CadViewModel) {
                 let ids = tab.selection_manager.selected_ids.clone();
                 if !ids.is_empty() {
                     tab.model.remove_entities_by_ids(&ids);
-                    tab.selection_manager.selected_ids.clear();
+                    tab.selection_manager.clear();
                 }
             }
             if let Some(change) = pending_layer_change {
                 vm.layer_change_prompt = Some(change);
             }
+            if calibrate {
+                vm.active_tab_mut().start_command("calibrate");
+            }
+            if generate_section {
+                vm.generate_section();
+            }
         });
 }
 
+/// Whether a value is being dragged or a field of this panel has keyboard
+/// focus
+fn editing_in(ui: &egui::Ui) -> bool {
+    let ctx = ui.ctx();
+    // Drags count anywhere, as color pickers drag in a popup
+    ctx.dragged_id().is_some()
+        || ctx
+            .memory(|m| m.focused())
+            .and_then(|id| ctx.read_response(id))
+            .is_some_and(|focused| ui.min_rect().contains_rect(focused.rect))
+}
+
+/// Numeric properties with an fx button each, to drive them by an
+/// expression. Returns true while an expression field has focus.
+fn inspect_constraints(
+    ui: &mut egui::Ui,
+    entity: &mut Entity,
+    errors: &BTreeMap<String, String>,
+) -> bool {
+    let supported = constraints::properties(&entity.shape);
+    if supported.is_empty() {
+        return false;
+    }
+    let mut editing = false;
+
+    properties::collapsible_section(ui, "Constraints", |ui| {
+        ui.label(
+            egui::RichText::new(format!(
+                "Refer to this entity as {}.<property>",
+                constraints::reference_name(&entity.name)
+            ))
+            .small()
+            .weak(),
+        );
+        for &property in supported {
+            let value = constraints::property(&entity.shape, property).unwrap_or_default();
+            // Expression being typed, kept in temp data until committed
+            let key = ui.id().with("constraint").with(entity.id).with(property);
+            let mut draft = ui.ctx().data(|d| d.get_temp::<String>(key));
+            let open = draft.is_some() || entity.constraints.contains_key(property);
+
+            let mut opened = false;
+            ui.horizontal(|ui| {
+                ui.label(format!("{}:", property));
+                ui.label(format!("{:.2}", value));
+                if ui
+                    .selectable_label(open, "fx")
+                    .on_hover_text("Drive this value by an expression")
+                    .clicked()
+                {
+                    if open {
+                        entity.constraints.remove(property);
+                        draft = None;
+                    } else {
+                        draft = Some(String::new());
+                        opened = true;
+                    }
+                }
+            });
+
+            let shown = draft
+                .clone()
+                .or_else(|| entity.constraints.get(property).cloned());
+            if let Some(mut text) = shown {
+                let edit = ui.add(
+                    egui::TextEdit::singleline(&mut text)
+                        .hint_text("e.g. Rectangle_1.width / 2")
+                        .desired_width(f32::INFINITY),
+                );
+                if opened {
+                    edit.request_focus();
+                }
+                editing |= edit.has_focus();
+                if edit.lost_focus() {
+                    let text = text.trim();
+                    if text.is_empty() {
+                        entity.constraints.remove(property);
+                    } else {
+                        entity
+                            .constraints
+                            .insert(property.to_string(), text.to_string());
+                    }
+                    draft = None;
+                } else if edit.has_focus() || opened {
+                    draft = Some(text);
+                }
+                if let Some(error) = errors.get(property).filter(|_| draft.is_none()) {
+                    ui.label(
+                        egui::RichText::new(format!("⚠ {}", error))
+                            .small()
+                            .color(egui::Color32::from_rgb(230, 170, 40)),
+                    );
+                }
+            }
+
+            ui.ctx().data_mut(|d| match draft {
+                Some(draft) => d.insert_temp(key, draft),
+                None => d.remove::<String>(key),
+            });
+        }
+    });
+    editing
+}
+
+/// Key/value attribute rows and free-text notes. Returns true while one
+/// of its text fields has focus.
+fn inspect_attributes(ui: &mut egui::Ui, entity: &mut Entity) -> bool {
+    let mut editing = false;
+    let mut remove = None;
+
+    properties::collapsible_section(ui, "Attributes", |ui| {
+        egui::Grid::new("entity_attributes")
+            .num_columns(3)
+            .striped(true)
+            .show(ui, |ui| {
+                for (key, value) in entity.attributes.iter_mut() {
+                    ui.label(key);
+                    let edit = ui.add(egui::TextEdit::singleline(value).desired_width(120.0));
+                    editing |= edit.has_focus();
+                    if ui.small_button("✖").on_hover_text("Remove").clicked() {
+                        remove = Some(key.clone());
+                    }
+                    ui.end_row();
+                }
+            });
+
+        // New row: name and value are kept in temp data until added
+        let id = ui.id().with("new_attribute").with(entity.id);
+        let (mut key, mut value) = ui
+            .ctx()
+            .data(|d| d.get_temp::<(String, String)>(id))
+            .unwrap_or_default();
+        let added = ui
+            .horizontal(|ui| {
+                let key_edit = ui.add(
+                    egui::TextEdit::singleline(&mut key)
+                        .hint_text("Name")
+                        .desired_width(90.0),
+                );
+                let value_edit = ui.add(
+                    egui::TextEdit::singleline(&mut value)
+                        .hint_text("Value")
+                        .desired_width(90.0),
+                );
+                editing |= key_edit.has_focus() || value_edit.has_focus();
+                let submitted =
+                    value_edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                let valid = !key.trim().is_empty();
+                ui.add_enabled(valid, egui::Button::new("+ Add"))
+                    .on_hover_text("Add the attribute, replacing one with the same name")
+                    .clicked()
+                    || (submitted && valid)
+            })
+            .inner;
+        if added {
+            entity
+                .attributes
+                .insert(key.trim().to_string(), std::mem::take(&mut value));
+            key.clear();
+        }
+        ui.ctx().data_mut(|d| d.insert_temp(id, (key, value)));
+
+        ui.add_space(5.0);
+        ui.label("Notes:");
+        let notes = ui.add(
+            egui::TextEdit::multiline(&mut entity.notes)
+                .desired_rows(3)
+                .desired_width(f32::INFINITY),
+        );
+        editing |= notes.has_focus();
+    });
+
+    if let Some(key) = remove {
+        entity.attributes.remove(&key);
+    }
+    editing
+}
+
+/// Color, line style, stroke width and printability, each either "by
+/// layer" or overridden
+fn inspect_style(ui: &mut egui::Ui, entity: &mut Entity, effective: &EntityStyle) {
+    ui.horizontal(|ui| {
+        let mut by_layer = entity.color.is_none();
+        ui.checkbox(&mut by_layer, "Color By Layer");
+        match (by_layer, entity.color.as_mut()) {
+            (false, Some(color)) => {
+                ui.color_edit_button_srgb(color);
+            }
+            (false, None) => {
+                let [r, g, b, _] = effective.color.to_array();
+                entity.color = Some([r, g, b]);
+            }
+            (true, _) => entity.color = None,
+        }
+    });
+    ui.horizontal(|ui| {
+        let mut by_layer = entity.line_style.is_none();
+        ui.checkbox(&mut by_layer, "Line Style By Layer");
+        match (by_layer, entity.line_style.as_mut()) {
+            (false, Some(style)) => {
+                egui::ComboBox::from_id_salt("entity_line_style")
+                    .selected_text(style.label())
+                    .show_ui(ui, |ui| {
+                        for option in LineStyle::ALL {
+                            ui.selectable_value(style, option, option.label());
+                        }
+                    });
+            }
+            (false, None) => entity.line_style = Some(effective.line_style),
+            (true, _) => entity.line_style = None,
+        }
+    });
+    ui.horizontal(|ui| {
+        let mut by_layer = entity.stroke_width.is_none();
+        ui.checkbox(&mut by_layer, "Width By Layer");
+        match (by_layer, entity.stroke_width.as_mut()) {
+            (false, Some(width)) => {
+                ui.add(
+                    egui::DragValue::new(width)
+                        .range(0.5..=10.0)
+                        .speed(0.1)
+                        .suffix(" px"),
+                );
+            }
+            (false, None) => entity.stroke_width = Some(effective.stroke_width),
+            (true, _) => entity.stroke_width = None,
+        }
+    });
+    ui.horizontal(|ui| {
+        let mut by_layer = entity.plottable.is_none();
+        ui.checkbox(&mut by_layer, "Plot By Layer");
+        match (by_layer, entity.plottable.as_mut()) {
+            (false, Some(plottable)) => {
+                ui.checkbox(plottable, "Plot");
+            }
+            (false, None) => entity.plottable = Some(effective.plottable),
+            (true, _) => entity.plottable = None,
+        }
+    });
+}
+
 fn inspect_line(ui: &mut egui::Ui, line: &mut Line) {
-    properties::point2(ui, "Start Point", &mut line.start.x, &mut line.start.y);
+    let options = Point2Options {
+        origin: [line.end.x, line.end.y],
+        ..Default::default()
+    };
+    properties::point2_ex(
+        ui,
+        "Start Point",
+        &mut line.start.x,
+        &mut line.start.y,
+        options,
+    );
     ui.add_space(5.0);
-    properties::point2(ui, "End Point", &mut line.end.x, &mut line.end.y);
+    let options = Point2Options {
+        origin: [line.start.x, line.start.y],
+        ..Default::default()
+    };
+    properties::point2_ex(ui, "End Point", &mut line.end.x, &mut line.end.y, options);
     ui.add_space(5.0);
 
     properties::display_float(ui, "Length:", line.length(), 2);
     properties::toggle(ui, "Show Length Label", &mut line.show_length);
 
     if line.show_length {
+        ui.horizontal(|ui| {
+            let mut override_precision = line.label_precision.is_some();
+            ui.checkbox(&mut override_precision, "Precision:");
+            match (override_precision, line.label_precision.as_mut()) {
+                (true, Some(precision)) => {
+                    ui.add(egui::DragValue::new(precision).range(0..=6));
+                }
+                (true, None) => line.label_precision = Some(2),
+                (false, _) => line.label_precision = None,
+            }
+        });
+        ui.horizontal(|ui| {
+            let mut override_suffix = line.label_suffix.is_some();
+            ui.checkbox(&mut override_suffix, "Suffix:");
+            match (override_suffix, line.label_suffix.as_mut()) {
+                (true, Some(suffix)) => {
+                    ui.add(egui::TextEdit::singleline(suffix).desired_width(60.0));
+                }
+                (true, None) => line.label_suffix = Some(String::new()),
+                (false, _) => line.label_suffix = None,
+            }
+        });
+
         properties::point2_speed(
             ui,
             "Label Offset",
@@ -414,35 +913,252 @@ fn inspect_circle(ui: &mut egui::Ui, circle: &mut Circle) {
 }
 
 fn inspect_rectangle(ui: &mut egui::Ui, rect: &mut Rectangle) {
-    properties::point2(ui, "Min Corner", &mut rect.min.x, &mut rect.min.y);
+    let options = Point2Options {
+        origin: [rect.max.x, rect.max.y],
+        lockable: true,
+        ..Default::default()
+    };
+    properties::point2_ex(ui, "Min Corner", &mut rect.min.x, &mut rect.min.y, options);
     ui.add_space(5.0);
-    properties::point2(ui, "Max Corner", &mut rect.max.x, &mut rect.max.y);
+    let options = Point2Options {
+        origin: [rect.min.x, rect.min.y],
+        lockable: true,
+        ..Default::default()
+    };
+    properties::point2_ex(ui, "Max Corner", &mut rect.max.x, &mut rect.max.y, options);
     properties::toggle(ui, "Filled", &mut rect.filled);
 }
 
-fn inspect_arc(ui: &mut egui::Ui, arc: &mut Arc) {
+/// Angle field for a direction, in the configured angle format
+fn direction_field(
+    ui: &mut egui::Ui,
+    label: &str,
+    radians: &mut f32,
+    angles: &AngleFormat,
+) -> egui::Response {
+    properties::angle_text(
+        ui,
+        label,
+        radians,
+        |r| angles.format_direction(r),
+        |text| angles.parse_direction(text),
+    )
+}
+
+/// Angle field for a rotation, in the configured angle format
+fn rotation_field(ui: &mut egui::Ui, label: &str, radians: &mut f32, angles: &AngleFormat) {
+    properties::angle_text(
+        ui,
+        label,
+        radians,
+        |r| angles.format_rotation(r),
+        |text| angles.parse_rotation(text),
+    );
+}
+
+fn inspect_arc(ui: &mut egui::Ui, arc: &mut Arc, angles: &AngleFormat) {
     properties::point2(ui, "Center", &mut arc.center.x, &mut arc.center.y);
     ui.add_space(5.0);
     properties::float_range(ui, "Radius:", &mut arc.radius, 0.1, 0.0..=f32::INFINITY);
-    properties::float_value(ui, "Start Angle:", &mut arc.start_angle, 0.01);
-    properties::float_value(ui, "End Angle:", &mut arc.end_angle, 0.01);
+    direction_field(ui, "Start Angle:", &mut arc.start_angle, angles);
+    direction_field(ui, "End Angle:", &mut arc.end_angle, angles);
+    properties::toggle(ui, "Clockwise", &mut arc.clockwise);
     properties::toggle(ui, "Filled", &mut arc.filled);
 }
 
-fn inspect_text(ui: &mut egui::Ui, text: &mut TextAnnotation) {
+fn inspect_text(ui: &mut egui::Ui, text: &mut TextAnnotation, angles: &AngleFormat) {
     properties::point2(ui, "Position", &mut text.position.x, &mut text.position.y);
     ui.add_space(5.0);
 
     properties::text_input(ui, "Text:", &mut text.text);
 
     properties::float_range(ui, "Font Size:", &mut text.style.font_size, 0.5, 6.0..=72.0);
-    properties::angle_degrees(ui, "Rotation:", &mut text.rotation);
+    rotation_field(ui, "Rotation:", &mut text.rotation, angles);
+}
+
+/// Cut line and viewing side. Returns true when Generate Section is clicked.
+fn inspect_section(ui: &mut egui::Ui, section: &mut SectionMark) -> bool {
+    properties::point2(
+        ui,
+        "Start Point",
+        &mut section.start.x,
+        &mut section.start.y,
+    );
+    ui.add_space(5.0);
+    properties::point2(ui, "End Point", &mut section.end.x, &mut section.end.y);
+    ui.add_space(5.0);
+    properties::text_input(ui, "Label:", &mut section.label);
+    properties::toggle(ui, "Flip direction", &mut section.flipped);
+    ui.add_space(5.0);
+    ui.button("Generate Section")
+        .on_hover_text("Open a schematic section through this cut in a new tab")
+        .clicked()
+}
+
+/// Image placement and source. Returns true when Calibrate is clicked.
+fn inspect_image(ui: &mut egui::Ui, image: &mut ImageRef, angles: &AngleFormat) -> bool {
+    properties::point2(ui, "Position", &mut image.position.x, &mut image.position.y);
+    ui.add_space(5.0);
+    properties::float_range(ui, "Scale:", &mut image.scale, 0.001, 1e-4..=f32::INFINITY);
+    rotation_field(ui, "Rotation:", &mut image.rotation, angles);
+    ui.horizontal(|ui| {
+        ui.label("Opacity:");
+        ui.add(egui::Slider::new(&mut image.opacity, 0.0..=1.0));
+    });
+    properties::toggle(ui, "Locked", &mut image.locked);
+
+    let mut calibrate = false;
+    properties::section(ui, "Source", |ui| {
+        ui.label(egui::RichText::new(&image.path).small().weak());
+        let on_disk = std::path::Path::new(&image.path).exists();
+        if image.embedded.is_some() {
+            ui.label("Embedded in project");
+        } else if !on_disk {
+            ui.colored_label(egui::Color32::LIGHT_RED, "File not found");
+        }
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    image.embedded.is_none() && on_disk,
+                    egui::Button::new("Embed"),
+                )
+                .on_hover_text("Store the image inside the project file")
+                .clicked()
+            {
+                let _ = image.embed();
+            }
+            if ui
+                .button("Reload")
+                .on_hover_text("Read the image file again")
+                .clicked()
+            {
+                crate::view::rendering::image::reload(ui.ctx(), image);
+            }
+            calibrate = ui
+                .button("Calibrate")
+                .on_hover_text("Pick two points and enter their real distance")
+                .clicked();
+        });
+    });
+    calibrate
+}
+
+/// Storey a structural member belongs to
+fn storey_combo(ui: &mut egui::Ui, id: &str, storey_id: &mut u64, storeys: &StoreyManager) {
+    if storeys.storeys.is_empty() {
+        return;
+    }
+    ui.horizontal(|ui| {
+        ui.label("Storey:");
+        egui::ComboBox::from_id_salt(id)
+            .selected_text(storeys.name_of(*storey_id))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(storey_id, 0, "—");
+                for storey in &storeys.storeys {
+                    ui.selectable_value(storey_id, storey.id, &storey.name);
+                }
+            });
+    });
+}
+
+fn inspect_flooring(ui: &mut egui::Ui, floor: &mut Flooring, storeys: &StoreyManager) {
+    ui.heading("Flooring Properties");
+    ui.add_space(5.0);
+
+    properties::section(ui, "Slab", |ui| {
+        ui.horizontal(|ui| {
+            ui.label("Floor Type:");
+            ui.add(egui::DragValue::new(&mut floor.floor_type_id).range(1..=99));
+        });
+        properties::float_range(ui, "Thickness:", &mut floor.thickness, 1.0, 1.0..=f32::MAX);
+        storey_combo(ui, "flooring_storey", &mut floor.storey_id, storeys);
+    });
+    ui.add_space(5.0);
+
+    properties::section(ui, "Totals", |ui| {
+        properties::display_value(ui, "Area:", &format!("{:.2} m²", floor.area() / 10_000.0));
+        properties::display_value(ui, "Corners:", &floor.boundary.len().to_string());
+        properties::display_value(ui, "Openings:", &floor.holes.len().to_string());
+        if !floor.holes.is_empty() && ui.button("Remove Openings").clicked() {
+            floor.holes.clear();
+        }
+    });
+}
+
+fn inspect_revision_cloud(ui: &mut egui::Ui, cloud: &mut RevisionCloud) {
+    ui.heading("Revision Cloud");
+    ui.add_space(5.0);
+    properties::float_range(ui, "Arc Size:", &mut cloud.arc_size, 1.0, 1.0..=f32::MAX);
+    let mut tagged = cloud.tag.is_some();
+    if ui.checkbox(&mut tagged, "Revision tag").changed() {
+        cloud.tag = tagged.then(|| "1".to_string());
+    }
+    if let Some(tag) = &mut cloud.tag {
+        properties::text_input(ui, "Revision:", tag);
+    }
+    properties::display_value(ui, "Corners:", &cloud.boundary.len().to_string());
+}
+
+fn inspect_sketch(ui: &mut egui::Ui, sketch: &mut Sketch) {
+    ui.heading("Sketch");
+    ui.add_space(5.0);
+    ui.checkbox(&mut sketch.highlighter, "Highlighter");
+    properties::display_value(ui, "Points:", &sketch.points.len().to_string());
+}
+
+fn inspect_room(ui: &mut egui::Ui, room: &mut Room) {
+    ui.heading("Room");
+    ui.add_space(5.0);
+    properties::text_input(ui, "Name:", &mut room.name);
+    properties::display_value(ui, "Area:", &format!("{:.2} m²", room.area_m2()));
+    properties::display_value(ui, "Corners:", &room.boundary.len().to_string());
+}
+
+fn inspect_stair(
+    ui: &mut egui::Ui,
+    stair: &mut StairData,
+    storeys: &StoreyManager,
+    angles: &AngleFormat,
+) {
+    ui.heading("Stair Properties");
+    ui.add_space(5.0);
+
+    properties::section(ui, "Flight", |ui| {
+        properties::point2(ui, "Start", &mut stair.start.x, &mut stair.start.y);
+        ui.add_space(5.0);
+        let mut angle = stair.direction.y.atan2(stair.direction.x);
+        if direction_field(ui, "Direction:", &mut angle, angles).changed() {
+            stair.direction = crate::model::Vector2::new(angle.cos(), angle.sin());
+        }
+        properties::float_range(ui, "Width:", &mut stair.width, 1.0, 1.0..=f32::MAX);
+        ui.horizontal(|ui| {
+            ui.label("Risers:");
+            ui.add(egui::DragValue::new(&mut stair.risers).range(2..=200));
+        });
+        properties::float_range(ui, "Tread:", &mut stair.tread, 1.0, 1.0..=f32::MAX);
+        properties::float_range(
+            ui,
+            "Riser Height:",
+            &mut stair.riser_height,
+            1.0,
+            1.0..=f32::MAX,
+        );
+        storey_combo(ui, "stair_storey", &mut stair.storey_id, storeys);
+    });
+    ui.add_space(5.0);
+
+    properties::section(ui, "Totals", |ui| {
+        properties::display_float(ui, "Total Run:", stair.total_run(), 2);
+        properties::display_float(ui, "Total Rise:", stair.total_rise(), 2);
+    });
 }
 
 fn inspect_column(
     ui: &mut egui::Ui,
     col: &mut ColumnData,
     definitions: &crate::model::structure::definitions::StructureDefinitions,
+    storeys: &StoreyManager,
+    angles: &AngleFormat,
 ) {
     ui.heading("Column Properties");
     ui.add_space(5.0);
@@ -457,6 +1173,7 @@ fn inspect_column(
             .unwrap_or("Unknown Type");
 
         ui.label(format!("Type: {} (ID: {})", type_name, col.column_type_id));
+        storey_combo(ui, "column_storey", &mut col.storey_id, storeys);
     });
     ui.add_space(5.0);
 
@@ -468,7 +1185,9 @@ fn inspect_column(
         ));
         ui.label(format!("Width: {:.2} cm", col.width));
         ui.label(format!("Height: {:.2} cm", col.height));
-        ui.label(format!("Rotation: {:.2}°", col.rotation.to_degrees()));
+        rotation_field(ui, "Rotation:", &mut col.rotation, angles);
+        // Only the pivot of later resizes changes; the column stays put
+        anchor::anchor_grid(ui, "column_anchor", &mut col.anchor);
     });
     ui.add_space(5.0);
 
@@ -521,6 +1240,7 @@ fn inspect_beam(
     ui: &mut egui::Ui,
     beam: &mut BeamData,
     definitions: &crate::model::structure::definitions::StructureDefinitions,
+    storeys: &StoreyManager,
 ) {
     ui.heading("Beam Properties");
     ui.add_space(5.0);
@@ -538,6 +1258,7 @@ fn inspect_beam(
             "Beam Type: {} (ID: {})",
             type_name, beam.beam_type_id
         ));
+        storey_combo(ui, "beam_storey", &mut beam.storey_id, storeys);
     });
     ui.add_space(5.0);
 
@@ -548,6 +1269,14 @@ fn inspect_beam(
         properties::point2(ui, "End Point", &mut beam.end.x, &mut beam.end.y);
         ui.add_space(5.0);
         properties::display_float(ui, "Length:", beam.length(), 2);
+        for (end, attachment) in [
+            ("Start", &beam.start_attachment),
+            ("End", &beam.end_attachment),
+        ] {
+            if let Some(attachment) = attachment {
+                ui.label(format!("{} on column #{}", end, attachment.column_id));
+            }
+        }
     });
     ui.add_space(5.0);
 
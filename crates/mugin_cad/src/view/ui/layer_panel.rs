@@ -1,3 +1,4 @@
+use crate::model::layer::LineStyle;
 use crate::viewmodel::CadViewModel;
 use eframe::egui;
 
@@ -6,17 +7,39 @@ pub fn render_layer_panel(ui: &mut egui::Ui, vm: &mut CadViewModel) {
     let layer_manager = &mut tab.model.layer_manager;
     let next_id = layer_manager.layers.keys().max().unwrap_or(&0) + 1; // Simple next_id logic for UI if needed locally, but manager handles it.
 
+    let mut import = false;
+    let mut export = false;
+
     ui.vertical(|ui| {
         // Toolbar
         ui.horizontal(|ui| {
             if ui.button("➕ New Layer").clicked() {
-                layer_manager.add_layer(format!("Layer {}", next_id), egui::Color32::WHITE);
+                layer_manager.add_layer(
+                    format!("Layer {}", next_id),
+                    crate::model::layer::DEFAULT_LAYER_COLOR,
+                );
             }
             if ui.button("🗑 Delete").clicked() {
                 let active = layer_manager.active_layer_id;
                 layer_manager.remove_layer(active);
             }
         });
+        ui.horizontal(|ui| {
+            if ui
+                .button("📥 Import")
+                .on_hover_text("Merge a layer template into this project")
+                .clicked()
+            {
+                import = true;
+            }
+            if ui
+                .button("📤 Export")
+                .on_hover_text("Save these layers as a template")
+                .clicked()
+            {
+                export = true;
+            }
+        });
 
         ui.separator();
 
@@ -41,9 +64,42 @@ pub fn render_layer_panel(ui: &mut egui::Ui, vm: &mut CadViewModel) {
                         layer.is_visible = !layer.is_visible;
                     }
 
+                    // Printability Toggle
+                    let icon = egui::RichText::new("🖶");
+                    let (icon, hover) = if layer.plottable {
+                        (icon, "Prints; click to leave off sheets")
+                    } else {
+                        (
+                            icon.weak().strikethrough(),
+                            "Left off sheets; click to print",
+                        )
+                    };
+                    if ui.button(icon).on_hover_text(hover).clicked() {
+                        layer.plottable = !layer.plottable;
+                    }
+
                     // Color Swatch
                     ui.color_edit_button_srgba(&mut layer.color);
 
+                    // Line Style
+                    egui::ComboBox::from_id_salt(("layer_line_style", layer.id))
+                        .width(70.0)
+                        .selected_text(layer.line_style.label())
+                        .show_ui(ui, |ui| {
+                            for style in LineStyle::ALL {
+                                ui.selectable_value(&mut layer.line_style, style, style.label());
+                            }
+                        });
+
+                    // Stroke Width
+                    ui.add(
+                        egui::DragValue::new(&mut layer.stroke_width)
+                            .range(0.5..=10.0)
+                            .speed(0.1)
+                            .suffix(" px"),
+                    )
+                    .on_hover_text("Stroke width");
+
                     // Name
                     let response = ui.text_edit_singleline(&mut layer.name);
                     if response.has_focus() || response.clicked() {
@@ -58,4 +114,12 @@ pub fn render_layer_panel(ui: &mut egui::Ui, vm: &mut CadViewModel) {
             layer_manager.set_active_layer(next_active);
         }
     });
+
+    // File dialogs need the whole view model
+    if import {
+        vm.import_layer_template();
+    }
+    if export {
+        vm.export_layer_template();
+    }
 }
@@ -0,0 +1,103 @@
+use crate::viewmodel::{CadViewModel, MarkupTool};
+use eframe::egui;
+
+/// Tools ▸ Markup window state
+#[derive(Default)]
+pub struct MarkupWindow {
+    pub open: bool,
+}
+
+/// Markup action picked in the window
+enum MarkupAction {
+    Toggle,
+    Tool(MarkupTool),
+    Select(u64),
+    Remove(u64),
+    Clear,
+}
+
+pub fn render_markup(ctx: &egui::Context, vm: &mut CadViewModel) {
+    let mut open = vm.markup_window.open;
+    let mut action = None;
+
+    let sketches: Vec<(u64, String, bool)> = vm
+        .active_tab()
+        .model
+        .sketches()
+        .into_iter()
+        .map(|(entity, sketch)| (entity.id, entity.name.clone(), sketch.highlighter))
+        .collect();
+    let mode = vm.markup.clone();
+    let mut select_markup = vm.active_tab().selection_manager.select_markup;
+
+    egui::Window::new("Markup")
+        .open(&mut open)
+        .min_width(240.0)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if ui.selectable_label(mode.active, "Markup Mode").clicked() {
+                    action = Some(MarkupAction::Toggle);
+                }
+                ui.separator();
+                for tool in [MarkupTool::Pen, MarkupTool::Highlighter, MarkupTool::Eraser] {
+                    let current = mode.active && mode.tool == tool;
+                    if ui.selectable_label(current, tool.label()).clicked() {
+                        action = Some(MarkupAction::Tool(tool));
+                    }
+                }
+            });
+            ui.checkbox(&mut select_markup, "Selectable")
+                .on_hover_text("Let clicks and selection windows pick sketches");
+            ui.separator();
+
+            if sketches.is_empty() {
+                ui.label(egui::RichText::new("No sketches yet").weak());
+            }
+            egui::ScrollArea::vertical()
+                .max_height(320.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("markup_sketches")
+                        .num_columns(3)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for (id, name, highlighter) in &sketches {
+                                if ui.link(name).clicked() {
+                                    action = Some(MarkupAction::Select(*id));
+                                }
+                                ui.label(if *highlighter { "Highlighter" } else { "Pen" });
+                                if ui.small_button("🗑").on_hover_text("Remove").clicked() {
+                                    action = Some(MarkupAction::Remove(*id));
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.strong(format!("{} sketches", sketches.len()));
+                if ui
+                    .add_enabled(!sketches.is_empty(), egui::Button::new("Clear All"))
+                    .clicked()
+                {
+                    action = Some(MarkupAction::Clear);
+                }
+            });
+        });
+
+    vm.active_tab_mut().selection_manager.select_markup = select_markup;
+    match action {
+        Some(MarkupAction::Toggle) => vm.toggle_markup(),
+        Some(MarkupAction::Tool(tool)) => vm.set_markup_tool(tool),
+        Some(MarkupAction::Select(id)) => {
+            let selection = &mut vm.active_tab_mut().selection_manager;
+            selection.clear();
+            selection.selected_ids.insert(id);
+            selection.last_interacted_id = Some(id);
+        }
+        Some(MarkupAction::Remove(id)) => vm.remove_sketch(id),
+        Some(MarkupAction::Clear) => vm.clear_markup(),
+        None => {}
+    }
+    vm.markup_window.open = open;
+}
@@ -1,10 +1,25 @@
+pub mod breadcrumb;
+pub mod calculator;
+pub mod command_palette;
+pub mod compare;
+pub mod dynamic_input;
 pub mod export;
+pub mod find_replace;
 pub mod hierarchy;
+pub mod hover_tooltip;
 pub mod inspector;
 pub mod layer_panel;
+pub mod markup;
+pub mod progress;
+pub mod reference_panel;
+pub mod rooms_report;
+pub mod selection_toolbar;
 pub mod settings;
 pub mod structure;
 pub mod tab_bar;
 pub mod terminal;
+pub mod text_edit;
+pub mod toast;
 pub mod toolbar;
 pub mod topmenu;
+pub mod welcome;
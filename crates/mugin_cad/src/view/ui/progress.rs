@@ -0,0 +1,46 @@
+//! Spinner shown while a save, load or export runs in the background.
+
+use crate::viewmodel::CadViewModel;
+use eframe::egui;
+
+pub fn render_background_progress(ctx: &egui::Context, vm: &mut CadViewModel) {
+    let Some(job) = &vm.background else {
+        return;
+    };
+
+    let mut cancel = false;
+    egui::Window::new("Working")
+        .title_bar(false)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label(format!("{}…", job.label));
+            });
+            if let Some((done, total)) = job.progress() {
+                ui.add(
+                    egui::ProgressBar::new(done as f32 / total.max(1) as f32)
+                        .text(format!("{} / {}", done, total))
+                        .desired_width(200.0),
+                );
+            }
+            ui.label(
+                egui::RichText::new(format!("{:.0} s", job.started.elapsed().as_secs_f32()))
+                    .weak()
+                    .size(12.0),
+            );
+            if job.cancellable {
+                ui.add_space(4.0);
+                cancel = ui.button("Cancel").clicked();
+            }
+        });
+
+    if cancel {
+        vm.cancel_background();
+    }
+
+    // Keep polling the worker while nothing else triggers a repaint
+    ctx.request_repaint_after(std::time::Duration::from_millis(100));
+}
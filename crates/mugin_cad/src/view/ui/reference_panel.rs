@@ -0,0 +1,87 @@
+use crate::viewmodel::CadViewModel;
+use eframe::egui;
+
+pub fn render_reference_panel(ui: &mut egui::Ui, vm: &mut CadViewModel) {
+    let active_id = vm.active_tab().id;
+    let other_tabs: Vec<(u64, String)> = vm
+        .tabs
+        .iter()
+        .filter(|tab| tab.id != active_id)
+        .map(|tab| (tab.id, tab.name.clone()))
+        .collect();
+
+    // Toolbar
+    ui.horizontal(|ui| {
+        ui.add_enabled_ui(!other_tabs.is_empty(), |ui| {
+            ui.menu_button("➕ Tab", |ui| {
+                for (id, name) in &other_tabs {
+                    if ui.button(name).clicked() {
+                        vm.add_tab_reference(*id);
+                        ui.close_menu();
+                    }
+                }
+            })
+            .response
+            .on_hover_text("Reference another open tab");
+        });
+        if ui
+            .button("➕ File...")
+            .on_hover_text("Reference a saved project")
+            .clicked()
+        {
+            vm.add_file_reference();
+        }
+        if ui
+            .button("⟳ Reload")
+            .on_hover_text("Update references from their sources")
+            .clicked()
+        {
+            vm.reload_references();
+        }
+    });
+
+    ui.separator();
+
+    let references = &mut vm.active_tab_mut().references;
+    if references.is_empty() {
+        ui.label(
+            egui::RichText::new("Show another drawing beneath this one")
+                .small()
+                .weak(),
+        );
+        return;
+    }
+
+    // Reference List
+    let mut remove = None;
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        for (index, reference) in references.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                // Visibility Toggle
+                let icon = if reference.visible { "👁" } else { "🚫" };
+                if ui.button(icon).clicked() {
+                    reference.visible = !reference.visible;
+                }
+
+                // Tint
+                if ui.color_edit_button_srgb(&mut reference.tint).changed() {
+                    reference.apply_tint();
+                }
+
+                let label = ui.label(&reference.label);
+                if let Some(error) = &reference.error {
+                    label.on_hover_text(error);
+                    ui.colored_label(egui::Color32::LIGHT_RED, "⚠");
+                }
+
+                if ui.small_button("✖").on_hover_text("Remove").clicked() {
+                    remove = Some(index);
+                }
+            });
+        }
+    });
+
+    if let Some(index) = remove {
+        references.remove(index);
+    }
+}
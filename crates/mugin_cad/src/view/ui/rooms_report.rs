@@ -0,0 +1,81 @@
+use crate::viewmodel::CadViewModel;
+use eframe::egui;
+
+/// Reports ▸ Rooms window state
+#[derive(Default)]
+pub struct RoomsReportWindow {
+    pub open: bool,
+}
+
+/// Rooms action picked in the window
+enum RoomsAction {
+    Regenerate,
+    Export,
+    Select(u64),
+}
+
+pub fn render_rooms_report(ctx: &egui::Context, vm: &mut CadViewModel) {
+    let mut open = vm.rooms_report_window.open;
+    let mut action = None;
+
+    let rooms: Vec<(u64, String, f32)> = vm
+        .active_tab()
+        .model
+        .rooms()
+        .into_iter()
+        .map(|(entity, room)| (entity.id, room.name.clone(), room.area_m2()))
+        .collect();
+
+    egui::Window::new("Rooms")
+        .open(&mut open)
+        .min_width(260.0)
+        .show(ctx, |ui| {
+            if rooms.is_empty() {
+                ui.label(egui::RichText::new("No rooms yet; Regenerate finds them").weak());
+            }
+            egui::ScrollArea::vertical()
+                .max_height(320.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("rooms_report")
+                        .num_columns(2)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for (id, name, area) in &rooms {
+                                if ui.link(name).clicked() {
+                                    action = Some(RoomsAction::Select(*id));
+                                }
+                                ui.label(format!("{:.2} m²", area));
+                                ui.end_row();
+                            }
+                        });
+                });
+
+            ui.separator();
+            let total: f32 = rooms.iter().map(|(_, _, area)| area).sum();
+            ui.strong(format!("{} rooms, {:.2} m²", rooms.len(), total));
+            ui.horizontal(|ui| {
+                if ui.button("Regenerate").clicked() {
+                    action = Some(RoomsAction::Regenerate);
+                }
+                if ui
+                    .add_enabled(!rooms.is_empty(), egui::Button::new("Export CSV..."))
+                    .clicked()
+                {
+                    action = Some(RoomsAction::Export);
+                }
+            });
+        });
+
+    match action {
+        Some(RoomsAction::Regenerate) => vm.regenerate_rooms(),
+        Some(RoomsAction::Export) => vm.export_rooms(),
+        Some(RoomsAction::Select(id)) => {
+            let selection = &mut vm.active_tab_mut().selection_manager;
+            selection.clear();
+            selection.selected_ids.insert(id);
+            selection.last_interacted_id = Some(id);
+        }
+        None => {}
+    }
+    vm.rooms_report_window.open = open;
+}
@@ -0,0 +1,155 @@
+//! Floating toolbar above the selection for quick edits on the canvas.
+
+use crate::model::Vector2;
+use crate::view::rendering::context::DrawContext;
+use crate::viewmodel::{CadViewModel, PendingLayerChange};
+use eframe::egui;
+
+/// Screen gap between the selection's top edge and the toolbar
+const TOOLBAR_GAP: f32 = 10.0;
+
+/// Room kept above the toolbar's bottom edge so it never leaves the canvas
+const TOOLBAR_HEIGHT: f32 = 36.0;
+
+/// Show the toolbar above the selected entities, kept inside `canvas`
+pub fn render_selection_toolbar(
+    ui: &egui::Ui,
+    vm: &mut CadViewModel,
+    ctx: &DrawContext,
+    canvas: egui::Rect,
+) {
+    let tab = vm.active_tab();
+    let Some((min, max)) = tab.selection_bounds() else {
+        return;
+    };
+
+    // Anchor above the top edge, kept inside the canvas
+    let top_left = ctx.to_screen(Vector2::new(min.x, max.y));
+    let top_right = ctx.to_screen(Vector2::new(max.x, max.y));
+    let anchor = egui::pos2(
+        ((top_left.x + top_right.x) / 2.0).clamp(canvas.left(), canvas.right()),
+        (top_left.y - TOOLBAR_GAP).clamp(canvas.top() + TOOLBAR_HEIGHT, canvas.bottom()),
+    );
+
+    // Layer and color shared by the whole selection, if any
+    let selected: Vec<u64> = tab.selection_manager.selected_ids.iter().copied().collect();
+    let entities: Vec<_> = selected
+        .iter()
+        .filter_map(|&id| tab.model.find_by_id(id))
+        .collect();
+    let layer_ids: Vec<u64> = entities.iter().map(|e| e.layer_id).collect();
+    let common_layer = layer_ids
+        .first()
+        .copied()
+        .filter(|first| layer_ids.iter().all(|id| id == first));
+    let has_children = entities.iter().any(|e| !e.children.is_empty());
+    let mut color = entities
+        .first()
+        .and_then(|e| {
+            e.color.or_else(|| {
+                tab.model
+                    .layer_manager
+                    .get_layer(e.layer_id)
+                    .map(|l| [l.color.r(), l.color.g(), l.color.b()])
+            })
+        })
+        .unwrap_or([255, 255, 255]);
+    let layers: Vec<(u64, String)> = tab
+        .model
+        .layer_manager
+        .get_sorted_layers()
+        .into_iter()
+        .map(|l| (l.id, l.name.clone()))
+        .collect();
+
+    let mut command = None;
+    let mut delete = false;
+    let mut new_layer = None;
+    let mut new_color = None;
+    let color_undo_id = egui::Id::new("selection_toolbar_color_undo");
+
+    egui::Area::new(egui::Id::new("selection_toolbar"))
+        .order(egui::Order::Foreground)
+        .fixed_pos(anchor)
+        .pivot(egui::Align2::CENTER_BOTTOM)
+        .show(ui.ctx(), |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("✥").on_hover_text("Move").clicked() {
+                        command = Some("move");
+                    }
+                    if ui.button("⟲").on_hover_text("Rotate").clicked() {
+                        command = Some("rotate");
+                    }
+                    if ui.button("🗑").on_hover_text("Delete").clicked() {
+                        delete = true;
+                    }
+
+                    let layer_text = common_layer
+                        .and_then(|id| layers.iter().find(|(l, _)| *l == id))
+                        .map_or("(mixed)", |(_, name)| name.as_str());
+                    egui::ComboBox::from_id_salt("selection_toolbar_layer")
+                        .selected_text(layer_text)
+                        .width(100.0)
+                        .show_ui(ui, |ui| {
+                            for (id, name) in &layers {
+                                if ui
+                                    .selectable_label(common_layer == Some(*id), name)
+                                    .clicked()
+                                {
+                                    new_layer = Some(*id);
+                                }
+                            }
+                        });
+
+                    if ui
+                        .color_edit_button_srgb(&mut color)
+                        .on_hover_text("Color")
+                        .changed()
+                    {
+                        new_color = Some(color);
+                    }
+                });
+            });
+        });
+
+    if let Some(name) = command {
+        vm.active_tab_mut().start_command(name);
+    }
+    if delete {
+        vm.delete_selected();
+        return;
+    }
+    if let Some(layer_id) = new_layer {
+        if has_children {
+            vm.layer_change_prompt = Some(PendingLayerChange {
+                entity_ids: selected.clone(),
+                new_layer_id: layer_id,
+            });
+        } else {
+            vm.save_undo_state();
+            let tab = vm.active_tab_mut();
+            for &id in &selected {
+                if let Some(entity) = tab.model.find_by_id_mut(id) {
+                    entity.layer_id = layer_id;
+                }
+            }
+        }
+    }
+
+    // One undo step per color picker session
+    if let Some(color) = new_color {
+        if !ui.data(|d| d.get_temp::<bool>(color_undo_id).unwrap_or(false)) {
+            vm.save_undo_state();
+            ui.data_mut(|d| d.insert_temp(color_undo_id, true));
+        }
+        let tab = vm.active_tab_mut();
+        for &id in &selected {
+            if let Some(entity) = tab.model.find_by_id_mut(id) {
+                entity.color = Some(color);
+            }
+        }
+    } else if !ui.ctx().memory(|m| m.any_popup_open()) {
+        ui.data_mut(|d| d.remove::<bool>(color_undo_id));
+    }
+}
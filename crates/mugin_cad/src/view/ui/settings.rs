@@ -1,9 +1,23 @@
-use crate::viewmodel::CadViewModel;
+use crate::model::Vector2;
+use crate::model::config::{FloorFill, ToolbarEntry};
+use crate::model::dimension::{ArrowheadType, DimensionStyle};
+use crate::model::input_history::HistoryScope;
+use crate::model::math::angle::{AngleDirection, AngleFormat, AngleUnit};
+use crate::view::rendering::context::DrawContext;
+use crate::view::rendering::dimension::{
+    dimension_font, draw_dimension_layout, draw_rotated_galley,
+};
+use crate::view::shortcuts;
+use crate::view::ui::structure::anchor;
+use crate::view::ui::toolbar;
+use crate::viewmodel::tab::ProjectTab;
+use crate::viewmodel::{ACTIONS, AppAction, CadViewModel, SettingsPage};
 use eframe::egui;
 use mugin_widgets::properties;
 
 pub fn render_settings_window(ctx: &egui::Context, vm: &mut CadViewModel) {
     let mut open = vm.show_settings_window;
+    let reveal = vm.settings_page.take();
 
     egui::Window::new("Settings")
         .open(&mut open)
@@ -15,7 +29,7 @@ pub fn render_settings_window(ctx: &egui::Context, vm: &mut CadViewModel) {
 
             egui::ScrollArea::vertical().show(ui, |ui| {
                 // ── Snap Configuration ───────────────────────
-                properties::collapsible_section(ui, "Snap Configuration", |ui| {
+                section(ui, SettingsPage::Snap, reveal, |ui| {
                     properties::float_range(
                         ui,
                         "Snap Tolerance:",
@@ -23,6 +37,13 @@ pub fn render_settings_window(ctx: &egui::Context, vm: &mut CadViewModel) {
                         0.5,
                         1.0..=50.0,
                     );
+                    properties::float_range(
+                        ui,
+                        "Pick Tolerance (px):",
+                        &mut vm.config.snap_config.pick_tolerance,
+                        0.5,
+                        1.0..=20.0,
+                    );
                     properties::toggle(ui, "Snap to Grid", &mut vm.config.snap_config.snap_to_grid);
                     properties::toggle(
                         ui,
@@ -44,13 +65,28 @@ pub fn render_settings_window(ctx: &egui::Context, vm: &mut CadViewModel) {
                         "Snap to Intersection",
                         &mut vm.config.snap_config.snap_to_intersection,
                     );
+                    properties::toggle(
+                        ui,
+                        "Snap to Apparent Intersection",
+                        &mut vm.config.snap_config.snap_to_apparent_intersection,
+                    );
                     properties::toggle(ui, "Snap to Axis", &mut vm.config.snap_config.snap_to_axis);
+                    properties::toggle(
+                        ui,
+                        "Smart Guides (hold Alt to disable)",
+                        &mut vm.config.snap_config.smart_guides,
+                    );
+                    properties::toggle(
+                        ui,
+                        "Snap to References",
+                        &mut vm.config.snap_config.snap_to_references,
+                    );
                 });
 
                 ui.add_space(10.0);
 
                 // ── Grid Configuration ───────────────────────
-                properties::collapsible_section(ui, "Grid Configuration", |ui| {
+                section(ui, SettingsPage::Grid, reveal, |ui| {
                     properties::float_range(
                         ui,
                         "Grid Size:",
@@ -66,7 +102,7 @@ pub fn render_settings_window(ctx: &egui::Context, vm: &mut CadViewModel) {
                 ui.add_space(10.0);
 
                 // ── Appearance Configuration ─────────────────
-                properties::collapsible_section(ui, "Appearance Configuration", |ui| {
+                section(ui, SettingsPage::Appearance, reveal, |ui| {
                     properties::color_rgb(
                         ui,
                         "Background Color:",
@@ -77,12 +113,100 @@ pub fn render_settings_window(ctx: &egui::Context, vm: &mut CadViewModel) {
                         "Selection Color:",
                         &mut vm.config.appearance_config.selection_color,
                     );
+                    let detail = &mut vm.config.appearance_config.detail;
+                    properties::float_range(
+                        ui,
+                        "Hide Text Below (px):",
+                        &mut detail.min_text_px,
+                        0.1,
+                        0.0..=20.0,
+                    );
+                    properties::float_range(
+                        ui,
+                        "Simplify Structure Below (px):",
+                        &mut detail.min_structure_px,
+                        0.1,
+                        0.0..=40.0,
+                    );
+                    let flooring = &mut vm.config.appearance_config.flooring;
+                    ui.horizontal(|ui| {
+                        ui.label("Flooring Fill:");
+                        ui.selectable_value(&mut flooring.fill, FloorFill::Tint, "Tint");
+                        ui.selectable_value(&mut flooring.fill, FloorFill::Hatch, "Hatch");
+                    });
+                    for (i, color) in flooring.colors.iter_mut().enumerate() {
+                        properties::color_rgb(ui, &format!("Floor Type {}:", i + 1), color);
+                    }
+                    let crosshair = &mut vm.config.appearance_config.crosshair;
+                    properties::toggle(ui, "Full-Screen Crosshair", &mut crosshair.full_screen);
+                    if !crosshair.full_screen {
+                        properties::float_range(
+                            ui,
+                            "Crosshair Size (%):",
+                            &mut crosshair.size_percent,
+                            0.5,
+                            1.0..=100.0,
+                        );
+                    }
+                    properties::color_rgb(ui, "Crosshair Color:", &mut crosshair.color);
+                    properties::float_range(
+                        ui,
+                        "Crosshair Opacity:",
+                        &mut crosshair.opacity,
+                        0.01,
+                        0.05..=1.0,
+                    );
+                    properties::toggle(ui, "Show Pick Box", &mut crosshair.show_pick_box);
+                });
+
+                ui.add_space(10.0);
+
+                // ── Drafting Defaults ────────────────────────
+                if !vm.tabs.is_empty() {
+                    section(ui, SettingsPage::Drafting, reveal, |ui| {
+                        render_drafting_defaults(ui, vm.active_tab_mut());
+                    });
+
+                    ui.add_space(10.0);
+                }
+
+                // ── Dimensions ───────────────────────────────
+                section(ui, SettingsPage::Dimensions, reveal, |ui| {
+                    render_dimension_settings(ui, &mut vm.config.dimension_style);
+                });
+
+                ui.add_space(10.0);
+
+                // ── Geometry Limits ──────────────────────────
+                section(ui, SettingsPage::Geometry, reveal, |ui| {
+                    let limits = &mut vm.config.geometry_limits;
+                    properties::float_range(
+                        ui,
+                        "Min Length:",
+                        &mut limits.min_length,
+                        0.001,
+                        0.0..=100.0,
+                    );
+                    properties::float_range(
+                        ui,
+                        "Min Radius:",
+                        &mut limits.min_radius,
+                        0.001,
+                        0.0..=100.0,
+                    );
+                    ui.label(
+                        egui::RichText::new(
+                            "Smaller lines, arcs, circles and rectangles are refused. AUDIT FIX removes existing ones.",
+                        )
+                        .weak()
+                        .size(12.0),
+                    );
                 });
 
                 ui.add_space(10.0);
 
                 // ── GUI Configuration ────────────────────────
-                properties::collapsible_section(ui, "GUI Configuration", |ui| {
+                section(ui, SettingsPage::Gui, reveal, |ui| {
                     properties::toggle(
                         ui,
                         "Always Show Inspector",
@@ -95,9 +219,369 @@ pub fn render_settings_window(ctx: &egui::Context, vm: &mut CadViewModel) {
                         .weak()
                         .size(12.0),
                     );
+                    properties::toggle(
+                        ui,
+                        "Show hover tooltips",
+                        &mut vm.config.gui_config.show_hover_tooltips,
+                    );
+                    properties::toggle(
+                        ui,
+                        "Show selection toolbar",
+                        &mut vm.config.gui_config.show_selection_toolbar,
+                    );
+                    properties::toggle(ui, "Pan inertia", &mut vm.config.gui_config.pan_inertia);
+                    properties::toggle(
+                        ui,
+                        "Zoom extents on open",
+                        &mut vm.config.gui_config.zoom_extents_on_open,
+                    );
+                    ui.label(
+                        egui::RichText::new(
+                            "Fit opened drawings into view instead of restoring the view they were saved with.",
+                        )
+                        .weak()
+                        .size(12.0),
+                    );
+                    properties::toggle(
+                        ui,
+                        "Repaint continuously",
+                        &mut vm.config.gui_config.continuous_repaint,
+                    );
+                    ui.label(
+                        egui::RichText::new(
+                            "Redraws even when idle. Uses more power; turn on only if the canvas shows artifacts.",
+                        )
+                        .weak()
+                        .size(12.0),
+                    );
+                    properties::text_input(
+                        ui,
+                        "Default layer template:",
+                        &mut vm.config.gui_config.default_layer_template,
+                    )
+                    .on_hover_text("JSON layer template applied to every new tab");
+                    properties::toggle(
+                        ui,
+                        "Seed new tabs from structure library",
+                        &mut vm.config.gui_config.seed_structure_library,
+                    );
+                    ui.horizontal(|ui| {
+                        ui.label("Backups kept on save:");
+                        ui.add(
+                            egui::DragValue::new(&mut vm.config.gui_config.backup_count)
+                                .range(0..=9),
+                        );
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Command history:");
+                        let scope = &mut vm.config.gui_config.history_scope;
+                        egui::ComboBox::from_id_salt("history_scope")
+                            .selected_text(scope.label())
+                            .show_ui(ui, |ui| {
+                                for kind in HistoryScope::ALL {
+                                    ui.selectable_value(scope, kind, kind.label());
+                                }
+                            });
+                    });
+                });
+
+                ui.add_space(10.0);
+
+                // ── Keyboard Shortcuts ───────────────────────
+                section(ui, SettingsPage::Keyboard, reveal, |ui| {
+                    render_keyboard_settings(ui, vm);
+                });
+
+                ui.add_space(10.0);
+
+                // ── Quick Transforms ─────────────────────────
+                section(ui, SettingsPage::QuickTransforms, reveal, |ui| {
+                    properties::toggle(
+                        ui,
+                        "Rotate text with the selection",
+                        &mut vm.config.gui_config.rotate_text,
+                    );
+                });
+
+                ui.add_space(10.0);
+
+                // ── Toolbar ──────────────────────────────────
+                section(ui, SettingsPage::Toolbar, reveal, |ui| {
+                    render_toolbar_settings(ui, &mut vm.config.gui_config.toolbar);
                 });
             });
         });
 
     vm.show_settings_window = open;
 }
+
+/// Settings section for `page`, opened and scrolled to when it is `reveal`
+fn section(
+    ui: &mut egui::Ui,
+    page: SettingsPage,
+    reveal: Option<SettingsPage>,
+    body: impl FnOnce(&mut egui::Ui),
+) {
+    properties::revealable_section(ui, page.label(), reveal == Some(page), body);
+}
+
+/// Key bindings from the action registry. Quick transforms are rebound
+/// here; the others are fixed and listed for reference.
+fn render_keyboard_settings(ui: &mut egui::Ui, vm: &mut CadViewModel) {
+    egui::Grid::new("keyboard_shortcuts")
+        .num_columns(2)
+        .striped(true)
+        .show(ui, |ui| {
+            for spec in ACTIONS {
+                match spec.action {
+                    AppAction::Quick(transform) => {
+                        ui.label(spec.label);
+                        let keys = &mut vm.config.gui_config.quick_transform_keys;
+                        let binding = transform.shortcut_mut(keys);
+                        ui.horizontal(|ui| {
+                            ui.add(egui::TextEdit::singleline(binding).desired_width(100.0));
+                            if !binding.is_empty() && shortcuts::parse_shortcut(binding).is_none() {
+                                ui.colored_label(egui::Color32::LIGHT_RED, "Unknown key binding");
+                            }
+                        });
+                        ui.end_row();
+                    }
+                    _ => {
+                        if let Some(keys) = vm.action_shortcut(spec) {
+                            ui.label(spec.label);
+                            ui.monospace(keys);
+                            ui.end_row();
+                        }
+                    }
+                }
+            }
+        });
+}
+
+/// Show/hide and reorder toolbar items. Writes the full layout back only
+/// when something changes, so untouched configs keep following the default.
+fn render_toolbar_settings(ui: &mut egui::Ui, saved: &mut Vec<ToolbarEntry>) {
+    let mut layout = toolbar::toolbar_layout(saved);
+    let mut changed = false;
+    let mut swap = None;
+    let last = layout.len().saturating_sub(1);
+
+    for (index, entry) in layout.iter_mut().enumerate() {
+        let Some(spec) = toolbar::find_spec(&entry.id) else {
+            continue;
+        };
+        ui.horizontal(|ui| {
+            changed |= ui.checkbox(&mut entry.visible, spec.label()).changed();
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui
+                    .add_enabled(index < last, egui::Button::new("⏷"))
+                    .clicked()
+                {
+                    swap = Some((index, index + 1));
+                }
+                if ui.add_enabled(index > 0, egui::Button::new("⏶")).clicked() {
+                    swap = Some((index - 1, index));
+                }
+            });
+        });
+    }
+
+    if let Some((a, b)) = swap {
+        layout.swap(a, b);
+        changed = true;
+    }
+    if changed {
+        *saved = layout;
+    }
+
+    ui.add_space(4.0);
+    if ui.button("Reset to Default").clicked() {
+        saved.clear();
+    }
+}
+
+fn render_dimension_settings(ui: &mut egui::Ui, style: &mut DimensionStyle) {
+    properties::float_range(ui, "Text Height:", &mut style.text_height, 0.5, 0.5..=500.0);
+
+    ui.horizontal(|ui| {
+        ui.label("Arrowhead:");
+        egui::ComboBox::from_id_salt("dim_arrowhead")
+            .selected_text(style.arrowhead.label())
+            .show_ui(ui, |ui| {
+                for kind in ArrowheadType::ALL {
+                    ui.selectable_value(&mut style.arrowhead, kind, kind.label());
+                }
+            });
+    });
+    properties::float_range(ui, "Arrow Size:", &mut style.arrow_size, 0.2, 0.0..=200.0);
+    properties::float_range(
+        ui,
+        "Extension Overshoot:",
+        &mut style.ext_overshoot,
+        0.2,
+        0.0..=200.0,
+    );
+    properties::float_range(ui, "Extension Gap:", &mut style.ext_gap, 0.2, 0.0..=200.0);
+    properties::float_range(
+        ui,
+        "Row Spacing:",
+        &mut style.chain_spacing,
+        0.5,
+        1.0..=1000.0,
+    );
+
+    ui.horizontal(|ui| {
+        ui.label("Precision:");
+        ui.add(egui::DragValue::new(&mut style.precision).range(0..=6));
+    });
+    properties::text_input(ui, "Suffix:", &mut style.suffix);
+
+    ui.add_space(5.0);
+    draw_dimension_preview(ui, style);
+
+    ui.add_space(5.0);
+    render_angle_settings(ui, &mut style.angles);
+}
+
+fn render_angle_settings(ui: &mut egui::Ui, angles: &mut AngleFormat) {
+    ui.horizontal(|ui| {
+        ui.label("Angle Unit:");
+        egui::ComboBox::from_id_salt("angle_unit")
+            .selected_text(angles.unit.label())
+            .show_ui(ui, |ui| {
+                for unit in AngleUnit::ALL {
+                    ui.selectable_value(&mut angles.unit, unit, unit.label());
+                }
+            });
+    });
+    ui.horizontal(|ui| {
+        ui.label("Angles From:");
+        egui::ComboBox::from_id_salt("angle_direction")
+            .selected_text(angles.direction.label())
+            .show_ui(ui, |ui| {
+                for direction in AngleDirection::ALL {
+                    ui.selectable_value(&mut angles.direction, direction, direction.label());
+                }
+            });
+    });
+    ui.horizontal(|ui| {
+        ui.label("Angle Precision:");
+        ui.add(egui::DragValue::new(&mut angles.precision).range(0..=4));
+    });
+    ui.label(
+        egui::RichText::new(format!(
+            "e.g. {}",
+            angles.format_direction(std::f32::consts::FRAC_PI_6)
+        ))
+        .weak(),
+    );
+}
+
+/// Live preview strip showing a sample dimension in the current style
+fn draw_dimension_preview(ui: &mut egui::Ui, style: &DimensionStyle) {
+    let (response, painter) =
+        ui.allocate_painter(egui::vec2(ui.available_width(), 70.0), egui::Sense::hover());
+    let rect = response.rect;
+    painter.rect_filled(rect, 4.0, egui::Color32::from_gray(30));
+
+    // Zoom so the sample text is always legible, whatever the world height
+    let zoom = 14.0 / style.text_height.max(0.01);
+    let ctx = DrawContext {
+        painter: &painter,
+        zoom,
+        offset: Vector2::new(0.0, 0.0),
+        screen_center: Vector2::new(rect.center().x, rect.center().y),
+        transform: glam::Affine2::IDENTITY,
+        dim_style: style,
+        color: crate::view::rendering::context::DEFAULT_ENTITY_COLOR,
+        emphasized: false,
+        line_style: crate::model::layer::LineStyle::Solid,
+        stroke_width: crate::model::layer::DEFAULT_STROKE_WIDTH,
+        detail: Default::default(),
+        flooring: Default::default(),
+    };
+
+    let half = ((rect.width() / 2.0 - 30.0) / zoom).max(1.0);
+    let p1 = Vector2::new(-half, -20.0 / zoom);
+    let p2 = Vector2::new(half, -20.0 / zoom);
+    let offset = Vector2::new(0.0, 28.0 / zoom);
+
+    let color = egui::Color32::from_rgb(255, 200, 100);
+    let galley = painter.layout_no_wrap(
+        style.format_length(p1.dist(p2)),
+        dimension_font(&ctx),
+        color,
+    );
+    let half_w_world = (galley.size().x / 2.0) / zoom * 1.1;
+
+    if let Some(layout) = style.layout(p1, p2, offset, half, half_w_world) {
+        draw_dimension_layout(&ctx, &layout, color.linear_multiply(0.6));
+    }
+    let label_pos = ctx.to_screen(p1 + offset + Vector2::new(half, 0.0));
+    draw_rotated_galley(
+        &ctx,
+        egui::pos2(label_pos.x, label_pos.y - galley.size().y / 2.0 - 3.0),
+        galley,
+        0.0,
+        color,
+    );
+}
+
+fn render_drafting_defaults(ui: &mut egui::Ui, tab: &mut ProjectTab) {
+    let layer_manager = &mut tab.model.layer_manager;
+    let current_name = layer_manager
+        .get_layer(layer_manager.active_layer_id)
+        .map(|l| l.name.clone())
+        .unwrap_or_else(|| "Unknown".to_string());
+    let layers: Vec<(u64, String)> = layer_manager
+        .get_sorted_layers()
+        .into_iter()
+        .map(|l| (l.id, l.name.clone()))
+        .collect();
+
+    ui.horizontal(|ui| {
+        ui.label("Current Layer:");
+        let mut active = layer_manager.active_layer_id;
+        egui::ComboBox::from_id_salt("drafting_layer")
+            .selected_text(current_name)
+            .show_ui(ui, |ui| {
+                for (id, name) in &layers {
+                    ui.selectable_value(&mut active, *id, name);
+                }
+            });
+        layer_manager.set_active_layer(active);
+    });
+
+    let defaults = &mut tab.executor.defaults;
+
+    let mut by_layer = defaults.color.is_none();
+    properties::toggle(ui, "Color By Layer", &mut by_layer);
+    match (by_layer, defaults.color.as_mut()) {
+        (false, Some(color)) => properties::color_rgb(ui, "Entity Color:", color),
+        (false, None) => defaults.color = Some([0, 255, 255]),
+        (true, _) => defaults.color = None,
+    }
+
+    properties::toggle(ui, "Fill Closed Shapes", &mut defaults.filled);
+    properties::float_range(
+        ui,
+        "Text Font Size:",
+        &mut defaults.text_font_size,
+        0.5,
+        6.0..=72.0,
+    );
+    properties::toggle(
+        ui,
+        "Show Line Length Labels",
+        &mut defaults.show_line_labels,
+    );
+    anchor::anchor_grid(ui, "drafting_column_anchor", &mut defaults.column_anchor);
+    properties::float_range(
+        ui,
+        "Storey Height:",
+        &mut defaults.storey_height,
+        1.0,
+        1.0..=f32::INFINITY,
+    );
+    properties::toggle(ui, "Clean Up Beam Junctions", &mut defaults.auto_cleanup);
+}
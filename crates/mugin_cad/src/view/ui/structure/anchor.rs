@@ -0,0 +1,51 @@
+use crate::model::structure::column::ColumnAnchor;
+use eframe::egui;
+
+/// Anchors by grid cell, laid out as they sit on the column
+const GRID: [[Option<ColumnAnchor>; 3]; 3] = [
+    [
+        Some(ColumnAnchor::TopLeft),
+        None,
+        Some(ColumnAnchor::TopRight),
+    ],
+    [None, Some(ColumnAnchor::Center), None],
+    [
+        Some(ColumnAnchor::BottomLeft),
+        None,
+        Some(ColumnAnchor::BottomRight),
+    ],
+];
+
+/// 3x3 grid of radio points picking the column anchor. Returns true when
+/// the anchor changed.
+pub fn anchor_grid(ui: &mut egui::Ui, id_salt: &str, anchor: &mut ColumnAnchor) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label("Anchor:");
+        egui::Frame::group(ui.style()).show(ui, |ui| {
+            egui::Grid::new(id_salt).spacing([2.0, 2.0]).show(ui, |ui| {
+                for row in GRID {
+                    for cell in row {
+                        match cell {
+                            Some(option) => {
+                                let response = ui
+                                    .radio(*anchor == option, "")
+                                    .on_hover_text(option.label());
+                                if response.clicked() && *anchor != option {
+                                    *anchor = option;
+                                    changed = true;
+                                }
+                            }
+                            None => {
+                                ui.label("");
+                            }
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+        ui.label(egui::RichText::new(anchor.label()).weak());
+    });
+    changed
+}
@@ -1,7 +1,9 @@
 use crate::model::structure::beam_type::{BeamRebarZone, BeamType};
 use crate::model::structure::material::MaterialProperties;
+use crate::view::ui::structure::library;
 use crate::viewmodel::CadViewModel;
 use eframe::egui;
+use mugin_widgets::window::{Modal, ModalResponse};
 use mugin_widgets::{card, window};
 
 pub fn render_beam_manager(ctx: &egui::Context, vm: &mut CadViewModel) {
@@ -15,6 +17,7 @@ pub fn render_beam_manager(ctx: &egui::Context, vm: &mut CadViewModel) {
 }
 
 fn render_beam_ui(ctx: &egui::Context, ui: &mut egui::Ui, vm: &mut CadViewModel) {
+    library::render_library_bar(ui, vm);
     {
         let tab = vm.active_tab_mut();
         let definitions = &mut tab.model.definitions;
@@ -67,46 +70,46 @@ fn render_beam_ui(ctx: &egui::Context, ui: &mut egui::Ui, vm: &mut CadViewModel)
         });
 
         // Handle Create Popup
-        let mut should_close = false;
-        let mut final_beam = None;
-
-        let closed = window::modal("Create New Beam", ctx, &mut show_create, |ui| {
-            let new_beam_id = egui::Id::new("new_beam_state");
-            let mut new_beam = ui
-                .data(|d| d.get_temp::<BeamType>(new_beam_id))
-                .unwrap_or_else(|| BeamType::new(0, "Err", 25.0, 50.0, 0, 0));
-
-            ui.set_min_width(820.0);
-            ui.heading("Define New Beam");
-            ui.separator();
-
-            render_beam_details_form(ui, &mut new_beam, &concrete_options, &steel_options, true);
-
-            ui.data_mut(|d| d.insert_temp(new_beam_id, new_beam.clone()));
-
-            ui.separator();
-            ui.horizontal(|ui| {
-                if ui.button("Create").clicked() {
-                    final_beam = Some(new_beam);
-                    return true;
-                }
-                if ui.button("Cancel").clicked() {
-                    return true;
-                }
-                false
-            })
-            .inner
-        });
-
-        if closed {
-            should_close = true;
-        }
+        let response = Modal::new("Create New Beam")
+            .min_size([820.0, 0.0])
+            .enter_accepts(false)
+            .show(ctx, &mut show_create, |ui, _| {
+                let new_beam_id = egui::Id::new("new_beam_state");
+                let mut new_beam = ui
+                    .data(|d| d.get_temp::<BeamType>(new_beam_id))
+                    .unwrap_or_else(|| BeamType::new(0, "Err", 25.0, 50.0, 0, 0));
+
+                ui.heading("Define New Beam");
+                ui.separator();
+
+                render_beam_details_form(
+                    ui,
+                    &mut new_beam,
+                    &concrete_options,
+                    &steel_options,
+                    true,
+                );
+
+                ui.data_mut(|d| d.insert_temp(new_beam_id, new_beam.clone()));
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Create").clicked() {
+                        return ModalResponse::Accepted(new_beam);
+                    }
+                    if ui.button("Cancel").clicked() {
+                        return ModalResponse::Closed;
+                    }
+                    ModalResponse::Pending
+                })
+                .inner
+            });
 
-        if should_close {
+        if !show_create {
             ui.data_mut(|d| d.insert_temp(create_popup_id, false));
-            if let Some(beam) = final_beam {
-                definitions.add_beam_type(beam);
-            }
+        }
+        if let ModalResponse::Accepted(beam) = response {
+            definitions.add_beam_type(beam);
         }
     }
 }
@@ -1,7 +1,9 @@
 use crate::model::structure::column_type::ColumnType;
 use crate::model::structure::material::MaterialProperties;
+use crate::view::ui::structure::library;
 use crate::viewmodel::CadViewModel;
 use eframe::egui;
+use mugin_widgets::window::{Modal, ModalResponse};
 use mugin_widgets::{card, window};
 
 pub fn render_column_manager(ctx: &egui::Context, vm: &mut CadViewModel) {
@@ -17,6 +19,7 @@ pub fn render_column_manager(ctx: &egui::Context, vm: &mut CadViewModel) {
 
 fn render_column_ui(ctx: &egui::Context, ui: &mut egui::Ui, vm: &mut CadViewModel) {
     let mut resize_events = Vec::new();
+    library::render_library_bar(ui, vm);
 
     {
         let tab = vm.active_tab_mut();
@@ -80,7 +83,7 @@ fn render_column_ui(ctx: &egui::Context, ui: &mut egui::Ui, vm: &mut CadViewMode
                     render_column_card(ui, col, &concrete_options, &steel_options);
 
                     if (col.width - old_w).abs() > 0.001 || (col.depth - old_d).abs() > 0.001 {
-                        resize_events.push((id, old_w, old_d, col.width, col.depth));
+                        resize_events.push((id, col.width, col.depth));
                     }
 
                     ui.add_space(8.0);
@@ -89,106 +92,67 @@ fn render_column_ui(ctx: &egui::Context, ui: &mut egui::Ui, vm: &mut CadViewMode
         });
 
         // Handle Create Popup
-        let mut should_close = false;
-        let mut final_col = None;
-
-        let closed = window::modal("Create New Column", ctx, &mut show_create, |ui| {
-            let new_col_id = egui::Id::new("new_col_state");
-            let mut new_col = ui
-                .data(|d| d.get_temp::<ColumnType>(new_col_id))
-                .unwrap_or_else(|| ColumnType::new(0, "Err", 30.0, 30.0, 0, 0, 0));
-
-            ui.set_min_width(820.0); // Fits inside 850 window
-            ui.heading("Define New Column");
-            ui.separator();
-
-            render_details_form(ui, &mut new_col, &concrete_options, &steel_options, true);
-
-            ui.data_mut(|d| d.insert_temp(new_col_id, new_col.clone()));
-
-            ui.separator();
-            ui.horizontal(|ui| {
-                if ui.button("Create").clicked() {
-                    final_col = Some(new_col);
-                    return true;
-                }
-                if ui.button("Cancel").clicked() {
-                    return true;
-                }
-                false
-            })
-            .inner
-        });
-
-        if closed {
-            should_close = true;
-        }
+        let response = Modal::new("Create New Column")
+            .min_size([820.0, 0.0])
+            .enter_accepts(false)
+            .show(ctx, &mut show_create, |ui, _| {
+                let new_col_id = egui::Id::new("new_col_state");
+                let mut new_col = ui
+                    .data(|d| d.get_temp::<ColumnType>(new_col_id))
+                    .unwrap_or_else(|| ColumnType::new(0, "Err", 30.0, 30.0, 0, 0, 0));
+
+                ui.heading("Define New Column");
+                ui.separator();
+
+                render_details_form(ui, &mut new_col, &concrete_options, &steel_options, true);
+
+                ui.data_mut(|d| d.insert_temp(new_col_id, new_col.clone()));
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Create").clicked() {
+                        return ModalResponse::Accepted(new_col);
+                    }
+                    if ui.button("Cancel").clicked() {
+                        return ModalResponse::Closed;
+                    }
+                    ModalResponse::Pending
+                })
+                .inner
+            });
 
-        if should_close {
+        if !show_create {
             ui.data_mut(|d| d.insert_temp(create_popup_id, false));
-            if let Some(col) = final_col {
-                definitions.add_column_type(col);
-            }
+        }
+        if let ModalResponse::Accepted(col) = response {
+            definitions.add_column_type(col);
         }
     } // End of borrow scope for tab/definitions
 
     // Process Resizes
     if !resize_events.is_empty() {
         let tab = vm.active_tab_mut();
-        for (id, old_w, old_d, new_w, new_d) in resize_events {
-            apply_column_resize(&mut tab.model, id, old_w, old_d, new_w, new_d);
+        for (id, new_w, new_d) in resize_events {
+            apply_column_resize(&mut tab.model, id, new_w, new_d);
         }
     }
 }
 
-fn apply_column_resize(
-    model: &mut crate::model::CadModel,
-    type_id: u64,
-    old_w: f32,
-    old_d: f32,
-    new_w: f32,
-    new_d: f32,
-) {
+fn apply_column_resize(model: &mut crate::model::CadModel, type_id: u64, new_w: f32, new_d: f32) {
     // Iterate over all entities, find columns with this type, and update them
     use crate::model::Shape;
-    use crate::model::structure::column::ColumnAnchor;
 
+    model.changes.touch_all();
     for entity in model.entities.iter_mut() {
-        if let Shape::Column(col) = &mut entity.shape {
-            if col.column_type_id == type_id {
-                // Update dimensions
-                col.width = new_w;
-                col.height = new_d;
-
-                // Rotated delta calculation to keep anchor fixed
-                // Global_Pos = Center + Rotated(Local_Pos)
-                // New_Center = Center_Old + Rot(Local_Old - Local_New)
-
-                let (sin, cos) = col.rotation.sin_cos();
-                let rotate =
-                    |x: f32, y: f32| -> (f32, f32) { (x * cos - y * sin, x * sin + y * cos) };
-
-                let get_local_anchor = |w: f32, h: f32, anchor: ColumnAnchor| -> (f32, f32) {
-                    let hw = w / 2.0;
-                    let hh = h / 2.0;
-                    match anchor {
-                        ColumnAnchor::Center => (0.0, 0.0),
-                        ColumnAnchor::TopLeft => (-hw, -hh),
-                        ColumnAnchor::TopRight => (hw, -hh),
-                        ColumnAnchor::BottomRight => (hw, hh),
-                        ColumnAnchor::BottomLeft => (-hw, hh),
-                    }
-                };
-
-                let (ax_old, ay_old) = get_local_anchor(old_w, old_d, col.anchor);
-                let (ax_new, ay_new) = get_local_anchor(new_w, new_d, col.anchor);
-
-                let (dx_rot, dy_rot) = rotate(ax_old - ax_new, ay_old - ay_new);
-
-                col.center.x += dx_rot;
-                col.center.y += dy_rot;
-            }
+        if !matches!(&entity.shape, Shape::Column(col) if col.column_type_id == type_id) {
+            continue;
         }
+        entity.modify(|shape| {
+            if let Shape::Column(col) = shape {
+                // Each column pivots around its own anchor
+                col.resize(new_w, new_d);
+            }
+        });
     }
 }
 
@@ -0,0 +1,23 @@
+use crate::viewmodel::CadViewModel;
+use eframe::egui;
+
+/// Import / export buttons for the shared structure library, shown at the
+/// top of each structure manager window
+pub fn render_library_bar(ui: &mut egui::Ui, vm: &mut CadViewModel) {
+    ui.horizontal(|ui| {
+        if ui
+            .button("📥 Import from Library")
+            .on_hover_text("Copy the shared library's materials and types into this project")
+            .clicked()
+        {
+            vm.import_structure_library();
+        }
+        if ui
+            .button("📤 Export to Library")
+            .on_hover_text("Copy this project's materials and types into the shared library")
+            .clicked()
+        {
+            vm.export_structure_library();
+        }
+    });
+}
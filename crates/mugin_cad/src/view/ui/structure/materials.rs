@@ -1,4 +1,5 @@
 use crate::model::structure::material::{Material, MaterialProperties};
+use crate::view::ui::structure::library;
 use crate::viewmodel::CadViewModel;
 use eframe::egui;
 use mugin_widgets::window;
@@ -21,6 +22,8 @@ pub fn render_materials_manager(ctx: &egui::Context, vm: &mut CadViewModel) {
 }
 
 fn render_materials_tab(ui: &mut egui::Ui, vm: &mut CadViewModel) {
+    library::render_library_bar(ui, vm);
+
     let tab = vm.active_tab_mut();
     let definitions = &mut tab.model.definitions;
 
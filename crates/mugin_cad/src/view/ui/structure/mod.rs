@@ -1,4 +1,7 @@
+pub mod anchor;
 pub mod beams;
 pub mod columns;
+pub mod library;
 pub mod materials;
 pub mod quick_access;
+pub mod storeys;
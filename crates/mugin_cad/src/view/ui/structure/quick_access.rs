@@ -6,6 +6,15 @@ pub fn render_quick_access(ui: &mut egui::Ui, vm: &mut CadViewModel) {
         .inner_margin(egui::Margin::symmetric(20.0, 8.0)) // Add margins
         .show(ui, |ui| {
             ui.horizontal(|ui| {
+                // Storeys Section
+                render_section(ui, "Storey", |ui| {
+                    render_storey_selector(ui, vm);
+                });
+
+                ui.add_space(15.0);
+                ui.separator();
+                ui.add_space(15.0);
+
                 // Columns Section
                 render_section(ui, "Columns", |ui| {
                     render_column_selector(ui, vm);
@@ -26,7 +35,12 @@ pub fn render_quick_access(ui: &mut egui::Ui, vm: &mut CadViewModel) {
 
                 // Slabs Section
                 render_section(ui, "Slabs", |ui| {
-                    ui.add_enabled(false, egui::Label::new("Coming Soon"));
+                    if ui.button("Draw").clicked() {
+                        vm.active_tab_mut().start_command("flooring");
+                    }
+                    if ui.button("Opening").clicked() {
+                        vm.active_tab_mut().start_command("opening");
+                    }
                 });
             });
         });
@@ -76,10 +90,10 @@ fn render_column_selector(ui: &mut egui::Ui, vm: &mut CadViewModel) {
     };
 
     // 2. Determine active ID logic (local calculation)
-    if active_id.is_none() {
-        if let Some(first) = col_types.first() {
-            active_id = Some(first.0);
-        }
+    if active_id.is_none()
+        && let Some(first) = col_types.first()
+    {
+        active_id = Some(first.0);
     }
 
     // 3. Update VM state if needed (Mutable borrow of VM)
@@ -113,13 +127,12 @@ fn render_column_selector(ui: &mut egui::Ui, vm: &mut CadViewModel) {
 
     if ui.button("Place").clicked() {
         let tab = vm.active_tab_mut();
-        if !tab.executor.start_command(
+        // The executor reports why it could not start
+        let _ = tab.executor.start_command(
             "place_column",
             &mut tab.model,
             &tab.selection_manager.selected_ids.clone(),
-        ) {
-            tab.executor.status_message = "Could not start place_column command".to_string();
-        }
+        );
     }
 }
 
@@ -144,10 +157,10 @@ fn render_beam_selector(ui: &mut egui::Ui, vm: &mut CadViewModel) {
     };
 
     // 2. Logic
-    if active_id.is_none() {
-        if let Some(first) = beam_types.first() {
-            active_id = Some(first.0);
-        }
+    if active_id.is_none()
+        && let Some(first) = beam_types.first()
+    {
+        active_id = Some(first.0);
     }
 
     if vm.active_beam_type_id != active_id {
@@ -180,12 +193,38 @@ fn render_beam_selector(ui: &mut egui::Ui, vm: &mut CadViewModel) {
 
     if ui.button("Place").clicked() {
         let tab = vm.active_tab_mut();
-        if !tab.executor.start_command(
+        // The executor reports why it could not start
+        let _ = tab.executor.start_command(
             "place_beam",
             &mut tab.model,
             &tab.selection_manager.selected_ids.clone(),
-        ) {
-            tab.executor.status_message = "Could not start place_beam command".to_string();
-        }
+        );
+    }
+}
+
+fn render_storey_selector(ui: &mut egui::Ui, vm: &mut CadViewModel) {
+    if ui.button("⚙").on_hover_text("Manage Storeys").clicked() {
+        vm.storey_manager_open = true;
     }
+
+    let storeys = &mut vm.active_tab_mut().model.storeys;
+    let current_name = match storeys.active {
+        Some(id) => storeys.name_of(id).to_string(),
+        None => "All storeys".to_string(),
+    };
+    let options: Vec<(u64, String)> = storeys
+        .storeys
+        .iter()
+        .map(|s| (s.id, s.name.clone()))
+        .collect();
+
+    egui::ComboBox::from_id_salt("quick_storey")
+        .width(120.0)
+        .selected_text(current_name)
+        .show_ui(ui, |ui| {
+            ui.selectable_value(&mut storeys.active, None, "All storeys");
+            for (id, name) in options {
+                ui.selectable_value(&mut storeys.active, Some(id), name);
+            }
+        });
 }
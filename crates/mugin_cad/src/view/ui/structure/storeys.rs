@@ -0,0 +1,113 @@
+use crate::viewmodel::CadViewModel;
+use eframe::egui;
+use mugin_widgets::window;
+
+pub fn render_storey_manager(ctx: &egui::Context, vm: &mut CadViewModel) {
+    let mut open = vm.storey_manager_open;
+
+    window::window("Storeys", ctx, &mut open, [460.0, 360.0], true, |ui| {
+        render_storey_ui(ui, vm);
+    });
+
+    vm.storey_manager_open = open;
+}
+
+fn storey_combo(ui: &mut egui::Ui, id: &str, options: &[(u64, String)], selected: &mut u64) {
+    let current = options
+        .iter()
+        .find(|(i, _)| i == selected)
+        .map(|(_, n)| n.as_str())
+        .unwrap_or("Select...");
+    egui::ComboBox::from_id_salt(id)
+        .width(110.0)
+        .selected_text(current)
+        .show_ui(ui, |ui| {
+            for (i, name) in options {
+                ui.selectable_value(selected, *i, name);
+            }
+        });
+}
+
+fn render_storey_ui(ui: &mut egui::Ui, vm: &mut CadViewModel) {
+    let mut remove = None;
+    let options: Vec<(u64, String)>;
+    {
+        let storeys = &mut vm.active_tab_mut().model.storeys;
+
+        if ui.button("➕ New Storey").clicked() {
+            let name = format!("Storey {}", storeys.storeys.len() + 1);
+            let height = storeys.storeys.last().map_or(300.0, |s| s.height);
+            storeys.add(name, height);
+        }
+        ui.separator();
+
+        let mut reorder = false;
+        egui::Grid::new("storey_grid")
+            .num_columns(4)
+            .spacing([8.0, 6.0])
+            .show(ui, |ui| {
+                ui.label(egui::RichText::new("Name").strong());
+                ui.label(egui::RichText::new("Elevation").strong());
+                ui.label(egui::RichText::new("Height").strong());
+                ui.end_row();
+
+                // Highest storey on top, as in a building
+                for storey in storeys.storeys.iter_mut().rev() {
+                    ui.add(egui::TextEdit::singleline(&mut storey.name).desired_width(120.0));
+                    reorder |= ui
+                        .add(egui::DragValue::new(&mut storey.elevation).suffix("cm"))
+                        .changed();
+                    ui.add(
+                        egui::DragValue::new(&mut storey.height)
+                            .range(1.0..=f32::MAX)
+                            .suffix("cm"),
+                    );
+                    if ui.button("🗑").on_hover_text("Remove storey").clicked() {
+                        remove = Some(storey.id);
+                    }
+                    ui.end_row();
+                }
+            });
+        if reorder {
+            storeys.sort();
+        }
+
+        options = storeys
+            .storeys
+            .iter()
+            .map(|s| (s.id, s.name.clone()))
+            .collect();
+    }
+
+    if let Some(id) = remove {
+        vm.remove_storey(id);
+    }
+
+    if options.len() < 2 {
+        return;
+    }
+    ui.separator();
+
+    // Source and target of the copy, kept between frames
+    let state_id = egui::Id::new("storey_copy_state");
+    let (mut from, mut to) = ui
+        .data(|d| d.get_temp::<(u64, u64)>(state_id))
+        .unwrap_or((options[0].0, options[1].0));
+
+    let mut copy = false;
+    ui.horizontal(|ui| {
+        ui.label("Copy storey");
+        storey_combo(ui, "storey_copy_from", &options, &mut from);
+        ui.label("to");
+        storey_combo(ui, "storey_copy_to", &options, &mut to);
+        copy = ui
+            .add_enabled(from != to, egui::Button::new("Copy"))
+            .on_hover_text("Duplicate every column and beam onto the other storey")
+            .clicked();
+    });
+    ui.data_mut(|d| d.insert_temp(state_id, (from, to)));
+
+    if copy {
+        vm.copy_storey(from, to);
+    }
+}
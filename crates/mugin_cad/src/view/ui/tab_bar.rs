@@ -1,28 +1,70 @@
-use crate::viewmodel::CadViewModel;
+use crate::viewmodel::{CadViewModel, CloseChoice};
 use eframe::egui;
+use mugin_widgets::window::{Modal, ModalResponse};
 
 pub fn render_tab_bar(ui: &mut egui::Ui, vm: &mut CadViewModel) {
     ui.horizontal(|ui| {
         let mut action = None;
         let active_tab_index = vm.active_tab_index;
+        let tab_count = vm.tabs.len();
 
         for (i, tab) in vm.tabs.iter_mut().enumerate() {
             let is_active = i == active_tab_index;
-            let display_name = if tab.is_dirty {
+            let mut display_name = if tab.is_dirty {
                 format!("* {}", tab.name)
             } else {
                 tab.name.clone()
             };
+            // Marks a tab with a command still waiting for input
+            let pending = tab.pending_command();
+            if pending.is_some() {
+                display_name.push_str(" ⏵");
+            }
 
-            ui.group(|ui| {
+            let group = ui.group(|ui| {
                 ui.style_mut().spacing.item_spacing.x = 2.0;
 
-                let response = ui.selectable_label(is_active, display_name);
+                // Dragging the label reorders tabs; the payload is the source index
+                let response = ui
+                    .dnd_drag_source(egui::Id::new("project_tab").with(i), i, |ui| {
+                        ui.selectable_label(is_active, display_name)
+                    })
+                    .inner;
+                let response = match pending {
+                    Some(command) => response.on_hover_text(format!("{} in progress", command)),
+                    None => response,
+                };
                 if response.clicked() {
                     action = Some(TabAction::Switch(i));
                 }
+                if response.middle_clicked() {
+                    action = Some(TabAction::Close(i));
+                }
 
                 response.context_menu(|ui| {
+                    if ui.button("Close").clicked() {
+                        action = Some(TabAction::Close(i));
+                        ui.close_menu();
+                    }
+                    if ui
+                        .add_enabled(tab_count > 1, egui::Button::new("Close Others"))
+                        .clicked()
+                    {
+                        action = Some(TabAction::CloseOthers(i));
+                        ui.close_menu();
+                    }
+                    if ui
+                        .add_enabled(i + 1 < tab_count, egui::Button::new("Close to the Right"))
+                        .clicked()
+                    {
+                        action = Some(TabAction::CloseRight(i));
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    if ui.button("Duplicate").clicked() {
+                        action = Some(TabAction::Duplicate(i));
+                        ui.close_menu();
+                    }
                     if ui.button("Rename Project").clicked() {
                         action = Some(TabAction::Rename(i));
                         ui.close_menu();
@@ -33,6 +75,25 @@ pub fn render_tab_bar(ui: &mut egui::Ui, vm: &mut CadViewModel) {
                     action = Some(TabAction::Close(i));
                 }
             });
+
+            // Drop target: highlight while hovering, move on release
+            if let Some(from) = group.response.dnd_hover_payload::<usize>()
+                && *from != i
+            {
+                let x = if *from < i {
+                    group.response.rect.right()
+                } else {
+                    group.response.rect.left()
+                };
+                ui.painter().vline(
+                    x,
+                    group.response.rect.y_range(),
+                    egui::Stroke::new(2.0, ui.visuals().selection.stroke.color),
+                );
+            }
+            if let Some(from) = group.response.dnd_release_payload::<usize>() {
+                action = Some(TabAction::Move(*from, i));
+            }
         }
 
         if ui.button("+").clicked() {
@@ -41,28 +102,40 @@ pub fn render_tab_bar(ui: &mut egui::Ui, vm: &mut CadViewModel) {
 
         if let Some(act) = action {
             match act {
-                TabAction::Switch(i) => vm.active_tab_index = i,
-                TabAction::Close(i) => vm.close_tab(i),
+                TabAction::Switch(i) => {
+                    vm.switch_tab(i);
+                    vm.show_welcome = false;
+                }
+                TabAction::Close(i) => vm.request_close_tabs(vec![i]),
+                TabAction::CloseOthers(i) => {
+                    vm.request_close_tabs((0..tab_count).filter(|&j| j != i).collect())
+                }
+                TabAction::CloseRight(i) => vm.request_close_tabs((i + 1..tab_count).collect()),
+                TabAction::Duplicate(i) => vm.duplicate_tab(i),
+                TabAction::Move(from, to) => vm.move_tab(from, to),
                 TabAction::New => vm.new_tab(),
                 TabAction::Rename(i) => vm.tab_renaming_index = Some(i),
             }
         }
     });
 
+    render_unsaved_prompt(ui.ctx(), vm);
+
     // ── Rename Dialog ────────────────────────────────────────
     let mut close_rename = false;
     if let Some(idx) = vm.tab_renaming_index {
         if let Some(tab) = vm.tabs.get_mut(idx) {
             let mut open = true;
-            mugin_widgets::window::modal("Rename Project", ui.ctx(), &mut open, |ui| {
+            Modal::new("Rename Project").show(ui.ctx(), &mut open, |ui, enter| {
                 ui.label("Enter new name:");
                 let response = ui.text_edit_singleline(&mut tab.name);
                 response.request_focus();
 
-                let enter_pressed = ui.input(|i| i.key_pressed(egui::Key::Enter));
-                let ok_clicked = ui.button("OK").clicked();
-
-                enter_pressed || ok_clicked
+                if ui.button("OK").clicked() || enter {
+                    ModalResponse::Accepted(())
+                } else {
+                    ModalResponse::Pending
+                }
             });
 
             if !open {
@@ -78,9 +151,56 @@ pub fn render_tab_bar(ui: &mut egui::Ui, vm: &mut CadViewModel) {
     }
 }
 
+/// Ask what to do with unsaved changes for the first tab queued to close
+fn render_unsaved_prompt(ctx: &egui::Context, vm: &mut CadViewModel) {
+    // Wait for an in-flight save (e.g. "Save" on the previous tab) to finish
+    if vm.background.is_some() {
+        return;
+    }
+    let Some(&id) = vm.pending_tab_close.first() else {
+        return;
+    };
+    let Some(tab) = vm.tab_index(id).map(|i| &vm.tabs[i]) else {
+        vm.pending_tab_close.remove(0);
+        return;
+    };
+
+    let message = format!("\"{}\" has unsaved changes.", tab.name);
+    let mut open = true;
+    let response =
+        Modal::new("Unsaved Changes")
+            .enter_accepts(false)
+            .show(ctx, &mut open, |ui, _| {
+                ui.label(message);
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Save").clicked() {
+                        ModalResponse::Accepted(CloseChoice::Save)
+                    } else if ui.button("Discard").clicked() {
+                        ModalResponse::Accepted(CloseChoice::Discard)
+                    } else if ui.button("Cancel").clicked() {
+                        ModalResponse::Closed
+                    } else {
+                        ModalResponse::Pending
+                    }
+                })
+                .inner
+            });
+
+    match response {
+        ModalResponse::Pending => {}
+        ModalResponse::Closed => vm.resolve_pending_close(CloseChoice::Cancel),
+        ModalResponse::Accepted(choice) => vm.resolve_pending_close(choice),
+    }
+}
+
 enum TabAction {
     Switch(usize),
     Close(usize),
+    CloseOthers(usize),
+    CloseRight(usize),
+    Duplicate(usize),
+    Move(usize, usize),
     New,
     Rename(usize),
 }
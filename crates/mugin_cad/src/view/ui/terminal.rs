@@ -1,7 +1,12 @@
+use crate::commands::output::{LineKind, TerminalLine};
+use crate::view::rendering::renderer::UNSAVED_COLOR;
 use crate::viewmodel::CadViewModel;
 use eframe::egui;
 
 pub fn render_terminal(ui: &mut egui::Ui, vm: &mut CadViewModel) {
+    let mut copy_all = false;
+    let mut save_log = false;
+
     ui.with_layout(egui::Layout::bottom_up(egui::Align::LEFT), |ui| {
         ui.style_mut().visuals.override_text_color = Some(egui::Color32::from_rgb(200, 200, 200));
 
@@ -18,17 +23,65 @@ pub fn render_terminal(ui: &mut egui::Ui, vm: &mut CadViewModel) {
                         .range(1.0..=1000.0),
                 );
             }
+
+            // Changes since the last save, while they are highlighted
+            if vm.highlight_unsaved
+                && let Some(tab) = vm.tabs.get(vm.active_tab_index)
+            {
+                let count = tab.saved_revisions.unsaved(&tab.model.entities);
+                ui.separator();
+                let text = if count.deleted == 0 {
+                    format!("{} unsaved changes", count.changed)
+                } else {
+                    format!(
+                        "{} unsaved changes, {} deleted",
+                        count.changed, count.deleted
+                    )
+                };
+                ui.label(egui::RichText::new(text).color(UNSAVED_COLOR));
+            }
+
+            // Clearance between exactly two selected entities
+            if let Some(pair) = vm
+                .tabs
+                .get(vm.active_tab_index)
+                .and_then(|tab| tab.selected_pair_distance())
+            {
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    let delta = pair.delta();
+                    let text = format!(
+                        "Gap {:.2}   Centers {:.2}   ΔX {:.2}   ΔY {:.2}",
+                        pair.gap(),
+                        pair.center_distance(),
+                        delta.x.abs(),
+                        delta.y.abs()
+                    );
+                    if ui
+                        .link(egui::RichText::new(text).monospace())
+                        .on_hover_text("Add a distance annotation")
+                        .clicked()
+                    {
+                        vm.annotate_pair_distance();
+                    }
+                });
+            }
         });
 
         ui.separator();
 
         // 2. Input Bar
         ui.horizontal(|ui| {
+            if vm.history_search.is_some() {
+                render_history_search(ui, vm);
+                return;
+            }
+
             ui.label(
                 egui::RichText::new(vm.status_message())
                     .strong()
                     .color(egui::Color32::LIGHT_BLUE),
             );
+            render_option_chips(ui, vm);
 
             let text_edit = egui::TextEdit::singleline(&mut vm.command_input)
                 .desired_width(f32::INFINITY)
@@ -46,18 +99,38 @@ pub fn render_terminal(ui: &mut egui::Ui, vm: &mut CadViewModel) {
                 || vm.hierarchy_renaming
                 || vm.inspector_renaming
                 || vm.materials_manager_open
-                || vm.column_manager_open;
+                || vm.column_manager_open
+                || vm.storey_manager_open
+                || vm.find_replace_window.open
+                || vm.command_palette.open
+                || mugin_widgets::window::is_modal_open(ui.ctx())
+                || crate::view::ui::dynamic_input::is_editing(ui.ctx());
 
             // Only request focus if we don't have it and nothing else needs it
-            if !text_edit_elsewhere {
-                if !response.has_focus()
-                    && !response.lost_focus()
-                    && ui.memory(|m| m.focused().is_none())
-                {
-                    // Check if we should auto-focus (e.g. not interacting with other widgets)
-                    // For now, we keep it aggressive but respect the flags
-                    response.request_focus();
-                }
+            if !text_edit_elsewhere
+                && !response.has_focus()
+                && !response.lost_focus()
+                && ui.memory(|m| m.focused().is_none())
+            {
+                // Check if we should auto-focus (e.g. not interacting with other widgets)
+                // For now, we keep it aggressive but respect the flags
+                response.request_focus();
+            }
+
+            // Global shortcuts skip frames where this input has focus, so
+            // staged Escape is handled here while typing
+            if (response.has_focus() || response.lost_focus())
+                && ui.input(|i| i.key_pressed(egui::Key::Escape))
+            {
+                vm.escape();
+            }
+
+            // Ctrl+R — reverse search through typed commands
+            if response.has_focus()
+                && ui.input_mut(|i| i.consume_key(egui::Modifiers::CTRL, egui::Key::R))
+            {
+                vm.history_search_next();
+                return;
             }
 
             // Handle Arrow keys for history navigation
@@ -70,7 +143,12 @@ pub fn render_terminal(ui: &mut egui::Ui, vm: &mut CadViewModel) {
 
             // Handle Enter key
             if ui.input(|i| i.key_pressed(egui::Key::Enter)) {
-                vm.process_command();
+                vm.submit_command_input();
+                // Enter drops focus; take it back so the next step can be
+                // typed straight away
+                if !text_edit_elsewhere {
+                    response.request_focus();
+                }
             }
         });
 
@@ -84,9 +162,101 @@ pub fn render_terminal(ui: &mut egui::Ui, vm: &mut CadViewModel) {
                 ui.with_layout(egui::Layout::top_down(egui::Align::LEFT), |ui| {
                     ui.set_width(ui.available_width());
                     for line in &vm.command_history {
-                        ui.label(egui::RichText::new(line).monospace());
+                        let response =
+                            ui.add(egui::Label::new(line_text(line)).sense(egui::Sense::click()));
+                        response.context_menu(|ui| {
+                            if ui.button("Copy line").clicked() {
+                                ui.ctx().copy_text(line.display());
+                                ui.close_menu();
+                            }
+                            if ui.button("Copy all").clicked() {
+                                copy_all = true;
+                                ui.close_menu();
+                            }
+                            if ui.button("Save log...").clicked() {
+                                save_log = true;
+                                ui.close_menu();
+                            }
+                        });
                     }
                 });
             });
     });
+
+    if copy_all {
+        ui.ctx().copy_text(vm.log_text());
+    }
+    if save_log {
+        vm.save_log();
+    }
+}
+
+/// The active command's options as `[Label]` chips; clicking one is the
+/// same as typing its key
+fn render_option_chips(ui: &mut egui::Ui, vm: &mut CadViewModel) {
+    let options = vm.active_tab().executor.active_options().to_vec();
+    for option in options {
+        let chip = egui::Button::new(
+            egui::RichText::new(format!("[{}]", option.label))
+                .monospace()
+                .color(egui::Color32::LIGHT_BLUE),
+        )
+        .small();
+        if ui
+            .add(chip)
+            .on_hover_text(format!("Type {}", option.key.to_uppercase()))
+            .clicked()
+        {
+            vm.command_input = option.key.to_string();
+            vm.process_command();
+        }
+    }
+}
+
+/// Styled text for a log line: warnings yellow, errors red, results green
+fn line_text(line: &TerminalLine) -> egui::RichText {
+    let text = egui::RichText::new(line.display()).monospace();
+    match line.kind {
+        LineKind::Input => text.color(egui::Color32::WHITE),
+        LineKind::Info => text,
+        LineKind::Warning => text.color(egui::Color32::YELLOW),
+        LineKind::Error => text.color(egui::Color32::LIGHT_RED),
+        LineKind::Result => text.color(egui::Color32::LIGHT_GREEN).strong(),
+    }
+}
+
+/// Input bar while Ctrl+R search is running: the query on the left, the
+/// best match shown inline. Enter takes the match, Escape gives up.
+fn render_history_search(ui: &mut egui::Ui, vm: &mut CadViewModel) {
+    ui.label(
+        egui::RichText::new("(reverse-search)")
+            .strong()
+            .color(egui::Color32::LIGHT_BLUE),
+    );
+
+    let Some(search) = &mut vm.history_search else {
+        return;
+    };
+    let response = ui.add(
+        egui::TextEdit::singleline(&mut search.query)
+            .desired_width(160.0)
+            .font(egui::TextStyle::Monospace),
+    );
+    response.request_focus();
+
+    match vm.history_search_match() {
+        Some(matched) => ui.label(egui::RichText::new(matched).monospace().weak()),
+        None => ui.label(egui::RichText::new("no match").italics().weak()),
+    };
+
+    if response.changed() {
+        vm.update_history_search();
+    }
+    if ui.input_mut(|i| i.consume_key(egui::Modifiers::CTRL, egui::Key::R)) {
+        vm.history_search_next();
+    } else if ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+        vm.accept_history_search();
+    } else if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+        vm.cancel_history_search();
+    }
 }
@@ -0,0 +1,59 @@
+//! Annotation text retyped where it is drawn, opened by double-clicking it.
+
+use crate::model::{Shape, Vector2};
+use crate::view::rendering::context::DrawContext;
+use crate::viewmodel::CadViewModel;
+use eframe::egui;
+
+/// Show the field over the annotation being retyped. Enter or clicking
+/// elsewhere applies the text; Escape drops it.
+pub fn render_text_edit(ui: &egui::Ui, vm: &mut CadViewModel, ctx: &DrawContext) {
+    let tab = vm.active_tab();
+    let Some(edit) = &tab.text_edit else {
+        return;
+    };
+    let Some(entity) = tab.model.find_by_id(edit.entity_id) else {
+        vm.cancel_text_edit();
+        return;
+    };
+    let Shape::Text(text) = &entity.shape else {
+        return;
+    };
+    let position: Vector2 = entity
+        .world_transform
+        .transform_point2(text.position.into())
+        .into();
+    let mut typed = edit.text.clone();
+
+    let id = egui::Id::new("canvas_text_edit").with(edit.entity_id);
+    let field = egui::Area::new(id)
+        .order(egui::Order::Foreground)
+        .pivot(egui::Align2::CENTER_CENTER)
+        .fixed_pos(ctx.to_screen(position))
+        .show(ui.ctx(), |ui| {
+            egui::Frame::popup(ui.style())
+                .show(ui, |ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut typed)
+                            .id(id.with("field"))
+                            .desired_width(160.0),
+                    )
+                })
+                .inner
+        })
+        .inner;
+
+    if !field.has_focus() && !field.lost_focus() {
+        field.request_focus();
+    }
+    if let Some(edit) = &mut vm.active_tab_mut().text_edit {
+        edit.text = typed;
+    }
+    if field.lost_focus() {
+        if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+            vm.cancel_text_edit();
+        } else {
+            vm.commit_text_edit();
+        }
+    }
+}
@@ -0,0 +1,37 @@
+//! Transient notifications stacked in the bottom-right of the canvas.
+
+use crate::viewmodel::CadViewModel;
+use eframe::egui;
+
+/// Seconds a toast stays on screen
+const TOAST_DURATION: f64 = 4.0;
+
+pub fn render_toasts(ctx: &egui::Context, vm: &mut CadViewModel) {
+    if vm.toasts.is_empty() {
+        return;
+    }
+
+    let now = ctx.input(|i| i.time);
+    for toast in &mut vm.toasts {
+        toast.shown_at.get_or_insert(now);
+    }
+    vm.toasts
+        .retain(|toast| now - toast.shown_at.unwrap_or(now) < TOAST_DURATION);
+    if vm.toasts.is_empty() {
+        return;
+    }
+
+    egui::Area::new(egui::Id::new("toasts"))
+        .order(egui::Order::Foreground)
+        .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-16.0, -220.0))
+        .interactable(false)
+        .show(ctx, |ui| {
+            for toast in &vm.toasts {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.colored_label(egui::Color32::from_rgb(255, 200, 80), &toast.message);
+                });
+            }
+        });
+
+    ctx.request_repaint_after(std::time::Duration::from_millis(250));
+}
@@ -1,9 +1,210 @@
-use crate::viewmodel::CadViewModel;
+use crate::commands::executor::CommandExecutor;
+use crate::model::config::{QuickTransformKeys, ToolbarEntry};
+use crate::model::system::symbols::{SymbolDefinition, SymbolLibrary};
+use crate::viewmodel::{ActionSpec, CadViewModel, QuickTransform, find_action};
 use eframe::egui;
 use mugin_widgets::toolbar;
+use std::sync::Arc;
+
+// ─── Toolbar Spec ────────────────────────────────────────────────────────
+
+/// A single command button. Its label and whether it needs a selection
+/// come from the action registry.
+pub struct ToolSpec {
+    /// Registry name passed to `start_command`
+    pub command: &'static str,
+    /// Option typed right after the command starts, with the label shown
+    /// for it, e.g. CIRCLE's "2p"
+    pub option: Option<(&'static str, &'static str)>,
+    pub icon: &'static str,
+}
+
+impl ToolSpec {
+    fn action(&self) -> Option<&'static ActionSpec> {
+        find_action(self.command)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self.option {
+            Some((_, label)) => label,
+            None => self.action().map_or(self.command, |action| action.label),
+        }
+    }
+
+    /// Disabled while nothing is selected
+    pub fn needs_selection(&self) -> bool {
+        self.action().is_some_and(|action| action.needs_selection)
+    }
+}
+
+/// One item of the left toolbar
+pub enum ToolbarSpec {
+    Tool(ToolSpec),
+    /// Group of related tools; the button face shows the last one used
+    Flyout {
+        id: &'static str,
+        label: &'static str,
+        tools: &'static [ToolSpec],
+    },
+    /// Group of quick transforms that act on the selection right away
+    Quick {
+        id: &'static str,
+        label: &'static str,
+        actions: &'static [QuickTransform],
+    },
+    /// Palette of the symbol library; the face places the last one picked
+    Symbols {
+        id: &'static str,
+        label: &'static str,
+    },
+    Separator(&'static str),
+}
+
+impl ToolbarSpec {
+    /// Stable id used to persist the user's layout
+    pub fn id(&self) -> &'static str {
+        match self {
+            ToolbarSpec::Tool(tool) => tool.command,
+            ToolbarSpec::Flyout { id, .. } => id,
+            ToolbarSpec::Quick { id, .. } => id,
+            ToolbarSpec::Symbols { id, .. } => id,
+            ToolbarSpec::Separator(id) => id,
+        }
+    }
+
+    /// Name shown in the settings page
+    pub fn label(&self) -> &'static str {
+        match self {
+            ToolbarSpec::Tool(tool) => tool.label(),
+            ToolbarSpec::Flyout { label, .. } => label,
+            ToolbarSpec::Quick { label, .. } => label,
+            ToolbarSpec::Symbols { label, .. } => label,
+            ToolbarSpec::Separator(_) => "── Separator ──",
+        }
+    }
+}
+
+const fn tool(command: &'static str, icon: &'static str) -> ToolSpec {
+    ToolSpec {
+        command,
+        option: None,
+        icon,
+    }
+}
+
+/// A command started in one of its modes
+const fn mode(
+    command: &'static str,
+    option: &'static str,
+    label: &'static str,
+    icon: &'static str,
+) -> ToolSpec {
+    ToolSpec {
+        command,
+        option: Some((option, label)),
+        icon,
+    }
+}
+
+/// Default toolbar layout
+pub const TOOLBAR: &[ToolbarSpec] = &[
+    ToolbarSpec::Flyout {
+        id: "transform",
+        label: "Modify",
+        tools: &[
+            tool("move", "M"),
+            tool("rotate", "R"),
+            tool("scale", "S"),
+            tool("mirror", "Mir"),
+        ],
+    },
+    ToolbarSpec::Quick {
+        id: "quick_transform",
+        label: "Rotate 90° / Flip",
+        actions: &QuickTransform::ALL,
+    },
+    ToolbarSpec::Flyout {
+        id: "clipboard",
+        label: "Clipboard",
+        tools: &[tool("copy", "C"), tool("cut", "X")],
+    },
+    ToolbarSpec::Separator("separator_shapes"),
+    ToolbarSpec::Tool(tool("line", "/")),
+    ToolbarSpec::Flyout {
+        id: "circle",
+        label: "Circle",
+        tools: &[
+            tool("circle", "O"),
+            mode("circle", "2p", "Circle 2P", "O2"),
+            mode("circle", "3p", "Circle 3P", "O3"),
+            mode("circle", "ttr", "Circle TTR", "Ot"),
+        ],
+    },
+    ToolbarSpec::Flyout {
+        id: "shapes",
+        label: "Shapes",
+        tools: &[tool("rect", "[]"), tool("arc", "(")],
+    },
+    ToolbarSpec::Separator("separator_construction"),
+    ToolbarSpec::Tool(tool("axis", "+")),
+    ToolbarSpec::Tool(tool("trim", "T")),
+    ToolbarSpec::Tool(tool("offset", "||")),
+    ToolbarSpec::Tool(tool("text", "Txt")),
+    ToolbarSpec::Tool(tool("revcloud", "Rev")),
+    ToolbarSpec::Symbols {
+        id: "symbols",
+        label: "Symbols",
+    },
+    ToolbarSpec::Flyout {
+        id: "measure",
+        label: "Measure",
+        tools: &[
+            tool("measure", "Dim"),
+            tool("autodim", "ADim"),
+            tool("area", "Area"),
+            tool("perim", "Perim"),
+            tool("distance", "Dist"),
+            tool("label", "Lbl"),
+        ],
+    },
+];
+
+/// Merge the user's saved layout with the spec: known entries keep their
+/// saved order and visibility, new spec items are appended visible.
+pub fn toolbar_layout(saved: &[ToolbarEntry]) -> Vec<ToolbarEntry> {
+    let mut layout: Vec<ToolbarEntry> = saved
+        .iter()
+        .filter(|entry| TOOLBAR.iter().any(|spec| spec.id() == entry.id))
+        .cloned()
+        .collect();
+
+    for spec in TOOLBAR {
+        if !layout.iter().any(|entry| entry.id == spec.id()) {
+            layout.push(ToolbarEntry {
+                id: spec.id().to_string(),
+                visible: true,
+            });
+        }
+    }
+
+    layout
+}
+
+/// Look up a toolbar item by id
+pub fn find_spec(id: &str) -> Option<&'static ToolbarSpec> {
+    TOOLBAR.iter().find(|spec| spec.id() == id)
+}
+
+// ─── Rendering ───────────────────────────────────────────────────────────
 
 /// Render the left toolbar with icon buttons for commands
 pub fn render_toolbar(ctx: &egui::Context, vm: &mut CadViewModel) {
+    let layout = toolbar_layout(&vm.config.gui_config.toolbar);
+    let keys = vm.config.gui_config.quick_transform_keys.clone();
+    let symbols = Arc::clone(&vm.symbols);
+    let mut quick = None;
+    let mut symbol_pick = None;
+
     egui::SidePanel::left("toolbar")
         .resizable(false)
         .default_width(48.0)
@@ -20,81 +221,253 @@ pub fn render_toolbar(ctx: &egui::Context, vm: &mut CadViewModel) {
 
             let tab = vm.active_tab_mut();
             let has_sel = !tab.selection_manager.selected_ids.is_empty();
+            let mut start: Option<&'static ToolSpec> = None;
 
             ui.vertical_centered(|ui| {
-                // ── Transform ────────────────────────────────────
-                if toolbar::tool_button(ui, "M", "Move (W)", has_sel) {
-                    let ids = tab.selection_manager.selected_ids.clone();
-                    tab.executor.start_command("move", &mut tab.model, &ids);
-                }
-                if toolbar::tool_button(ui, "R", "Rotate (E)", has_sel) {
-                    let ids = tab.selection_manager.selected_ids.clone();
-                    tab.executor.start_command("rotate", &mut tab.model, &ids);
+                for spec in layout
+                    .iter()
+                    .filter(|entry| entry.visible)
+                    .filter_map(|entry| find_spec(&entry.id))
+                {
+                    match spec {
+                        ToolbarSpec::Tool(tool) => {
+                            if tool_button(ui, tool, &tab.executor, has_sel) {
+                                start = Some(tool);
+                            }
+                        }
+                        ToolbarSpec::Flyout { id, tools, .. } => {
+                            render_flyout(ui, id, tools, &tab.executor, has_sel, &mut start);
+                        }
+                        ToolbarSpec::Quick { id, actions, .. } => {
+                            render_quick_flyout(ui, id, actions, &keys, has_sel, &mut quick);
+                        }
+                        ToolbarSpec::Symbols { id, .. } => {
+                            render_symbol_flyout(ui, id, &symbols, &mut symbol_pick);
+                        }
+                        ToolbarSpec::Separator(_) => toolbar::separator(ui),
+                    }
                 }
+            });
 
-                ui.add_space(4.0);
+            if let Some(tool) = start
+                && tab.start_command(tool.command)
+                && let Some((option, _)) = tool.option
+            {
+                tab.executor.process_input(
+                    option,
+                    &mut tab.model,
+                    &tab.selection_manager.selected_ids,
+                );
+            }
+        });
 
-                // ── Clipboard ────────────────────────────────────
-                if toolbar::tool_button(ui, "C", "Copy (Ctrl+C)", has_sel) {
-                    let ids = tab.selection_manager.selected_ids.clone();
-                    tab.executor.start_command("copy", &mut tab.model, &ids);
-                }
-                if toolbar::tool_button(ui, "X", "Cut (Ctrl+X)", has_sel) {
-                    let ids = tab.selection_manager.selected_ids.clone();
-                    tab.executor.start_command("cut", &mut tab.model, &ids);
-                }
+    if let Some(transform) = quick {
+        vm.apply_quick_transform(transform);
+    }
+    match symbol_pick {
+        Some(SymbolPick::Place(name)) => vm.place_symbol(&name),
+        Some(SymbolPick::Import) => vm.import_symbols(),
+        None => {}
+    }
+}
 
-                toolbar::separator(ui);
+/// Tooltip text: label plus its shortcut, e.g. "Move (W)". A shortcut
+/// starts the command in its default mode, so modes show none.
+fn tool_tooltip(tool: &ToolSpec, executor: &CommandExecutor) -> String {
+    match executor
+        .shortcut_for(tool.command)
+        .filter(|_| tool.option.is_none())
+    {
+        Some(shortcut) => format!("{} ({})", tool.label(), shortcut),
+        None => tool.label().to_string(),
+    }
+}
 
-                // ── Shapes ───────────────────────────────────────
-                if toolbar::tool_button(ui, "/", "Line (L)", true) {
-                    let ids = tab.selection_manager.selected_ids.clone();
-                    tab.executor.start_command("line", &mut tab.model, &ids);
-                }
-                if toolbar::tool_button(ui, "O", "Circle (C)", true) {
-                    let ids = tab.selection_manager.selected_ids.clone();
-                    tab.executor.start_command("circle", &mut tab.model, &ids);
-                }
-                if toolbar::tool_button(ui, "[]", "Rectangle", true) {
-                    let ids = tab.selection_manager.selected_ids.clone();
-                    tab.executor.start_command("rect", &mut tab.model, &ids);
-                }
-                if toolbar::tool_button(ui, "(", "Arc", true) {
-                    let ids = tab.selection_manager.selected_ids.clone();
-                    tab.executor.start_command("arc", &mut tab.model, &ids);
-                }
+fn tool_button(
+    ui: &mut egui::Ui,
+    tool: &ToolSpec,
+    executor: &CommandExecutor,
+    has_sel: bool,
+) -> bool {
+    let tooltip = tool_tooltip(tool, executor);
+    toolbar::tool_button(ui, tool.icon, &tooltip, has_sel || !tool.needs_selection())
+}
 
-                toolbar::separator(ui);
+fn render_flyout(
+    ui: &mut egui::Ui,
+    id: &str,
+    tools: &'static [ToolSpec],
+    executor: &CommandExecutor,
+    has_sel: bool,
+    start: &mut Option<&'static ToolSpec>,
+) {
+    let Some(first) = tools.first() else {
+        return;
+    };
 
-                // ── Construction ─────────────────────────────────
-                if toolbar::tool_button(ui, "+", "Axis (A)", true) {
-                    let ids = tab.selection_manager.selected_ids.clone();
-                    tab.executor.start_command("axis", &mut tab.model, &ids);
-                }
-                if toolbar::tool_button(ui, "T", "Trim (T)", true) {
-                    let ids = tab.selection_manager.selected_ids.clone();
-                    tab.executor.start_command("trim", &mut tab.model, &ids);
-                }
-                if toolbar::tool_button(ui, "||", "Offset (O)", has_sel) {
-                    let ids = tab.selection_manager.selected_ids.clone();
-                    tab.executor.start_command("offset", &mut tab.model, &ids);
-                }
-                if toolbar::tool_button(ui, "Txt", "Text Annotation", true) {
-                    let ids = tab.selection_manager.selected_ids.clone();
-                    tab.executor.start_command("text", &mut tab.model, &ids);
-                }
-                if toolbar::tool_button(ui, "Dim", "Measure Distance", true) {
-                    let ids = tab.selection_manager.selected_ids.clone();
-                    tab.executor.start_command("measure", &mut tab.model, &ids);
-                }
-                if toolbar::tool_button(ui, "Area", "Measure Closed Area", true) {
-                    let ids = tab.selection_manager.selected_ids.clone();
-                    tab.executor.start_command("area", &mut tab.model, &ids);
+    // The face remembers the last tool picked from the flyout
+    let face_id = egui::Id::new("toolbar_flyout").with(id);
+    let face_index = ui.data(|d| d.get_temp::<usize>(face_id)).unwrap_or(0);
+    let face = tools.get(face_index).unwrap_or(first);
+
+    let tooltip = tool_tooltip(face, executor);
+    let mut picked = None;
+    let clicked = toolbar::flyout_button(
+        ui,
+        face.icon,
+        &tooltip,
+        has_sel || !face.needs_selection(),
+        |ui| {
+            for (index, tool) in tools.iter().enumerate() {
+                if tool_button(ui, tool, executor, has_sel) {
+                    picked = Some(index);
                 }
-                if toolbar::tool_button(ui, "Perim", "Measure Perimeter", true) {
-                    let ids = tab.selection_manager.selected_ids.clone();
-                    tab.executor.start_command("perim", &mut tab.model, &ids);
+            }
+        },
+    );
+
+    if let Some(index) = picked {
+        ui.data_mut(|d| d.insert_temp(face_id, index));
+        *start = Some(&tools[index]);
+    } else if clicked {
+        *start = Some(face);
+    }
+}
+
+/// Tooltip text for a quick transform, with its key binding if set
+pub fn quick_tooltip(transform: QuickTransform, keys: &QuickTransformKeys) -> String {
+    match transform.shortcut(keys) {
+        "" => transform.label().to_string(),
+        shortcut => format!("{} ({})", transform.label(), shortcut),
+    }
+}
+
+fn render_quick_flyout(
+    ui: &mut egui::Ui,
+    id: &str,
+    actions: &'static [QuickTransform],
+    keys: &QuickTransformKeys,
+    has_sel: bool,
+    picked: &mut Option<QuickTransform>,
+) {
+    let Some(&first) = actions.first() else {
+        return;
+    };
+
+    // Same last-used face as the command flyouts
+    let face_id = egui::Id::new("toolbar_flyout").with(id);
+    let face_index = ui.data(|d| d.get_temp::<usize>(face_id)).unwrap_or(0);
+    let face = actions.get(face_index).copied().unwrap_or(first);
+
+    let mut chosen = None;
+    let clicked =
+        toolbar::flyout_button(ui, face.icon(), &quick_tooltip(face, keys), has_sel, |ui| {
+            for (index, &action) in actions.iter().enumerate() {
+                if toolbar::tool_button(ui, action.icon(), &quick_tooltip(action, keys), has_sel) {
+                    chosen = Some(index);
                 }
-            });
+            }
         });
+
+    if let Some(index) = chosen {
+        ui.data_mut(|d| d.insert_temp(face_id, index));
+        *picked = Some(actions[index]);
+    } else if clicked {
+        *picked = Some(face);
+    }
+}
+
+/// What was picked from the symbol palette
+enum SymbolPick {
+    Place(String),
+    Import,
+}
+
+fn render_symbol_flyout(
+    ui: &mut egui::Ui,
+    id: &str,
+    symbols: &SymbolLibrary,
+    picked: &mut Option<SymbolPick>,
+) {
+    // The face places the symbol picked last
+    let face_id = egui::Id::new("toolbar_flyout").with(id);
+    let face = ui
+        .data(|d| d.get_temp::<String>(face_id))
+        .filter(|name| symbols.get(name).is_some())
+        .or_else(|| symbols.symbols().next().map(|s| s.name.clone()));
+    let tooltip = match &face {
+        Some(name) => format!("Symbol: {}", name),
+        None => "Symbols".to_string(),
+    };
+
+    let mut chosen = None;
+    let clicked = toolbar::flyout_button(ui, "Sym", &tooltip, face.is_some(), |ui| {
+        ui.set_min_width(160.0);
+        for symbol in symbols.symbols() {
+            if symbol_button(ui, symbol) {
+                chosen = Some(SymbolPick::Place(symbol.name.clone()));
+            }
+        }
+        ui.separator();
+        if ui.button("Import from Drawing...").clicked() {
+            chosen = Some(SymbolPick::Import);
+        }
+    });
+
+    if let Some(SymbolPick::Place(name)) = &chosen {
+        ui.data_mut(|d| d.insert_temp(face_id, name.clone()));
+    }
+    if chosen.is_some() {
+        *picked = chosen;
+    } else if clicked && let Some(name) = face {
+        *picked = Some(SymbolPick::Place(name));
+    }
+}
+
+/// Palette row: a thumbnail of the symbol and its name
+fn symbol_button(ui: &mut egui::Ui, symbol: &SymbolDefinition) -> bool {
+    let size = egui::vec2(ui.available_width().max(160.0), 40.0);
+    let (rect, response) = ui.allocate_exact_size(size, egui::Sense::click());
+    let visuals = ui.style().interact(&response);
+    if response.hovered() {
+        ui.painter().rect_filled(rect, 2.0, visuals.bg_fill);
+    }
+
+    // Fit the symbol into a square on the left; screen Y points down
+    let thumb = egui::Rect::from_min_size(rect.min, egui::vec2(40.0, 40.0)).shrink(4.0);
+    let (min, max) = symbol.bounds();
+    let extent = (max.x - min.x).max(max.y - min.y).max(1e-3);
+    let scale = thumb.width() / extent;
+    let center = (min + max) * 0.5;
+    let to_screen = |p: crate::model::Vector2| {
+        thumb.center() + egui::vec2(p.x - center.x, center.y - p.y) * scale
+    };
+    let stroke = egui::Stroke::new(1.0, visuals.fg_stroke.color);
+    let origin = crate::model::Vector2::new(0.0, 0.0);
+    for (points, closed) in symbol.outlines(origin, 0.0) {
+        let points: Vec<egui::Pos2> = points.into_iter().map(to_screen).collect();
+        if closed {
+            ui.painter().add(egui::Shape::closed_line(points, stroke));
+        } else {
+            ui.painter().add(egui::Shape::line(points, stroke));
+        }
+    }
+    for (position, text) in symbol.texts(origin, 0.0) {
+        ui.painter().text(
+            to_screen(position),
+            egui::Align2::CENTER_CENTER,
+            text,
+            egui::FontId::proportional(8.0),
+            stroke.color,
+        );
+    }
+
+    ui.painter().text(
+        egui::pos2(thumb.max.x + 8.0, rect.center().y),
+        egui::Align2::LEFT_CENTER,
+        &symbol.name,
+        egui::FontId::proportional(13.0),
+        visuals.text_color(),
+    );
+    response.clicked()
 }
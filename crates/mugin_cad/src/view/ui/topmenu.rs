@@ -1,6 +1,11 @@
-use crate::viewmodel::CadViewModel;
+use crate::model::backup::{age_label, list_backups};
+use crate::model::config::PanelLayout;
+use crate::view::ui::toolbar::quick_tooltip;
+use crate::view::{LEFT_PANEL_ID, RIGHT_PANEL_ID};
+use crate::viewmodel::{CadViewModel, QuickTransform};
 use eframe::egui;
-use mugin_widgets::toolbar;
+use mugin_widgets::{panel, toolbar};
+use std::time::SystemTime;
 
 /// Render the top menu bar
 pub fn render_top_menu(ctx: &egui::Context, vm: &mut CadViewModel) {
@@ -11,25 +16,87 @@ pub fn render_top_menu(ctx: &egui::Context, vm: &mut CadViewModel) {
             // ── Project Menu ─────────────────────────────────
             ui.menu_button("Project", |ui| {
                 ui.set_min_width(120.0);
+                // File operations run in the background, one at a time
+                let idle = !vm.is_busy();
 
                 if toolbar::menu_action(ui, "New") {
                     vm.new_tab();
                 }
-                if toolbar::menu_action(ui, "Save") {
+                ui.menu_button("New from Template", |ui| {
+                    let mut chosen = None;
+                    for template in &vm.templates {
+                        let mut response = ui.button(&template.name);
+                        if !template.description.is_empty() {
+                            response = response.on_hover_text(&template.description);
+                        }
+                        if response.clicked() {
+                            chosen = Some(template.clone());
+                        }
+                    }
+                    if let Some(template) = chosen {
+                        vm.new_from_template(&template);
+                        ui.close_menu();
+                    }
+                });
+                if toolbar::menu_item(ui, "Save", idle) {
                     vm.save_project();
                 }
-                if toolbar::menu_action(ui, "Load") {
+                if toolbar::menu_item(ui, "Load", idle) {
                     vm.load_project();
                 }
+                let saved_path = vm.active_tab().file_path.clone();
+                ui.add_enabled_ui(saved_path.is_some(), |ui| {
+                    ui.menu_button("Restore from Backup...", |ui| {
+                        let backups = saved_path.as_deref().map(list_backups).unwrap_or_default();
+                        if backups.is_empty() {
+                            ui.weak("No backups of this file");
+                        }
+                        let now = SystemTime::now();
+                        for backup in backups {
+                            let age = now.duration_since(backup.modified).unwrap_or_default();
+                            let label = format!("Backup {} ({})", backup.index, age_label(age));
+                            if ui
+                                .button(label)
+                                .on_hover_text(backup.path.display().to_string())
+                                .clicked()
+                            {
+                                vm.open_backup(&backup);
+                                ui.close_menu();
+                            }
+                        }
+                    });
+                });
+                if toolbar::menu_action(ui, "Save as Template...") {
+                    vm.save_as_template();
+                }
+                if toolbar::menu_action(ui, "Insert Image...") {
+                    vm.insert_image();
+                    ui.close_menu();
+                }
+                if toolbar::menu_action(ui, "Compare with File...") {
+                    vm.compare_with_file();
+                    ui.close_menu();
+                }
 
                 ui.separator();
 
-                if toolbar::menu_action(ui, "Export PDF...") {
+                if toolbar::menu_item(ui, "Export PDF...", idle) {
                     vm.export_window.open = true;
                 }
+                if toolbar::menu_item(ui, "Batch Export...", idle) {
+                    vm.batch_export_window.open = true;
+                }
+                if toolbar::menu_action(ui, "Export Data...") {
+                    vm.data_export_window.open = true;
+                    ui.close_menu();
+                }
+                if toolbar::menu_action(ui, "Export Change Journal...") {
+                    vm.export_journal();
+                    ui.close_menu();
+                }
                 if toolbar::menu_action(ui, "Select Export Region") {
                     let tab = vm.active_tab_mut();
-                    tab.executor.start_command(
+                    let _ = tab.executor.start_command(
                         "select_region",
                         &mut tab.model,
                         &std::collections::HashSet::new(),
@@ -37,6 +104,26 @@ pub fn render_top_menu(ctx: &egui::Context, vm: &mut CadViewModel) {
                 }
             });
 
+            // ── Edit Menu ────────────────────────────────────
+            ui.menu_button("Edit", |ui| {
+                ui.set_min_width(120.0);
+                if toolbar::menu_action(ui, "Undo") {
+                    vm.undo();
+                    ui.close_menu();
+                }
+                if toolbar::menu_action(ui, "Redo") {
+                    vm.redo();
+                    ui.close_menu();
+                }
+
+                ui.separator();
+
+                if toolbar::menu_action(ui, "Find & Replace...") {
+                    vm.find_replace_window.open = true;
+                    ui.close_menu();
+                }
+            });
+
             // ── Structure Menu ───────────────────────────────
             ui.menu_button("Structure", |ui| {
                 ui.set_min_width(120.0);
@@ -52,6 +139,27 @@ pub fn render_top_menu(ctx: &egui::Context, vm: &mut CadViewModel) {
                     vm.beam_manager_open = true;
                     ui.close_menu();
                 }
+                if toolbar::menu_action(ui, "Storeys...") {
+                    vm.storey_manager_open = true;
+                    ui.close_menu();
+                }
+            });
+
+            // ── Reports Menu ─────────────────────────────────
+            ui.menu_button("Reports", |ui| {
+                ui.set_min_width(120.0);
+                if toolbar::menu_action(ui, "Export Attributes...") {
+                    vm.export_attributes();
+                    ui.close_menu();
+                }
+                if toolbar::menu_action(ui, "Rooms...") {
+                    vm.rooms_report_window.open = true;
+                    ui.close_menu();
+                }
+                if toolbar::menu_action(ui, "Generate Section") {
+                    vm.generate_section();
+                    ui.close_menu();
+                }
             });
 
             // ── Actions Menu ─────────────────────────────────
@@ -63,97 +171,155 @@ pub fn render_top_menu(ctx: &egui::Context, vm: &mut CadViewModel) {
                     return;
                 }
 
+                let keys = vm.config.gui_config.quick_transform_keys.clone();
+                let mut quick = None;
                 let tab = vm.active_tab_mut();
                 let has_sel = !tab.selection_manager.selected_ids.is_empty();
 
                 // Shapes
                 toolbar::menu_section(ui, "Shapes");
                 if toolbar::menu_action(ui, "Line (L)") {
-                    let ids = tab.selection_manager.selected_ids.clone();
-                    tab.executor.start_command("line", &mut tab.model, &ids);
+                    tab.start_command("line");
                 }
                 if toolbar::menu_action(ui, "Circle (C)") {
-                    let ids = tab.selection_manager.selected_ids.clone();
-                    tab.executor.start_command("circle", &mut tab.model, &ids);
+                    tab.start_command("circle");
                 }
                 if toolbar::menu_action(ui, "Rectangle") {
-                    let ids = tab.selection_manager.selected_ids.clone();
-                    tab.executor.start_command("rect", &mut tab.model, &ids);
+                    tab.start_command("rect");
                 }
                 if toolbar::menu_action(ui, "Arc") {
-                    let ids = tab.selection_manager.selected_ids.clone();
-                    tab.executor.start_command("arc", &mut tab.model, &ids);
+                    tab.start_command("arc");
                 }
 
                 // Structural
                 toolbar::menu_section(ui, "Structural");
                 if toolbar::menu_action(ui, "Place Column") {
-                    let ids = tab.selection_manager.selected_ids.clone();
-                    tab.executor
-                        .start_command("place_column", &mut tab.model, &ids);
+                    tab.start_command("place_column");
                 }
                 if toolbar::menu_action(ui, "Place Beam") {
-                    let ids = tab.selection_manager.selected_ids.clone();
-                    tab.executor
-                        .start_command("place_beam", &mut tab.model, &ids);
+                    tab.start_command("place_beam");
+                }
+                if toolbar::menu_action(ui, "Flooring") {
+                    tab.start_command("flooring");
+                }
+                if toolbar::menu_action(ui, "Slab Opening") {
+                    tab.start_command("opening");
+                }
+                if toolbar::menu_action(ui, "Stair") {
+                    tab.start_command("stair");
+                }
+                if toolbar::menu_action(ui, "Clean Up Junctions") {
+                    let count = tab.model.cleanup_junctions();
+                    tab.executor.status_message = format!("CLEANUP: {} junctions", count);
                 }
 
                 // Transform
                 toolbar::menu_section(ui, "Transform");
                 if toolbar::menu_item(ui, "Move (W)", has_sel) {
-                    let ids = tab.selection_manager.selected_ids.clone();
-                    tab.executor.start_command("move", &mut tab.model, &ids);
+                    tab.start_command("move");
                 }
                 if toolbar::menu_item(ui, "Rotate (E)", has_sel) {
-                    let ids = tab.selection_manager.selected_ids.clone();
-                    tab.executor.start_command("rotate", &mut tab.model, &ids);
+                    tab.start_command("rotate");
+                }
+                if toolbar::menu_item(ui, "Scale (SC)", has_sel) {
+                    tab.start_command("scale");
+                }
+                for transform in QuickTransform::ALL {
+                    if toolbar::menu_item(ui, &quick_tooltip(transform, &keys), has_sel) {
+                        quick = Some(transform);
+                    }
                 }
 
                 // Clipboard
                 toolbar::menu_section(ui, "Clipboard");
                 if toolbar::menu_item(ui, "Copy (Ctrl+C)", has_sel) {
-                    let ids = tab.selection_manager.selected_ids.clone();
-                    tab.executor.start_command("copy", &mut tab.model, &ids);
+                    tab.start_command("copy");
                 }
                 if toolbar::menu_item(ui, "Cut (Ctrl+X)", has_sel) {
-                    let ids = tab.selection_manager.selected_ids.clone();
-                    tab.executor.start_command("cut", &mut tab.model, &ids);
+                    tab.start_command("cut");
                 }
 
                 // Construction
                 toolbar::menu_section(ui, "Construction");
                 if toolbar::menu_action(ui, "Axis (A)") {
-                    let ids = tab.selection_manager.selected_ids.clone();
-                    tab.executor.start_command("axis", &mut tab.model, &ids);
+                    tab.start_command("axis");
                 }
                 if toolbar::menu_action(ui, "Trim (T)") {
-                    let ids = tab.selection_manager.selected_ids.clone();
-                    tab.executor.start_command("trim", &mut tab.model, &ids);
+                    tab.start_command("trim");
                 }
                 if toolbar::menu_item(ui, "Offset (O)", has_sel) {
-                    let ids = tab.selection_manager.selected_ids.clone();
-                    tab.executor.start_command("offset", &mut tab.model, &ids);
+                    tab.start_command("offset");
                 }
 
                 // Annotation
                 toolbar::menu_section(ui, "Annotation");
                 if toolbar::menu_action(ui, "Text") {
-                    let ids = tab.selection_manager.selected_ids.clone();
-                    tab.executor.start_command("text", &mut tab.model, &ids);
+                    tab.start_command("text");
                 }
                 if toolbar::menu_action(ui, "Distance") {
-                    let ids = tab.selection_manager.selected_ids.clone();
-                    tab.executor.start_command("distance", &mut tab.model, &ids);
+                    tab.start_command("distance");
+                }
+                if toolbar::menu_action(ui, "Section Mark") {
+                    tab.start_command("section");
+                }
+                if toolbar::menu_action(ui, "Revision Cloud") {
+                    tab.start_command("revcloud");
+                }
+                if toolbar::menu_action(ui, "Symbol") {
+                    tab.executor.active_symbol = None;
+                    tab.start_command("symbol");
+                }
+                let import_symbols = toolbar::menu_action(ui, "Import Symbols...");
+
+                if let Some(transform) = quick {
+                    vm.apply_quick_transform(transform);
+                }
+                if import_symbols {
+                    vm.import_symbols();
+                }
+            });
+
+            // ── View Menu ────────────────────────────────────
+            ui.menu_button("View", |ui| {
+                ui.set_min_width(120.0);
+                let panels = &mut vm.config.gui_config.panels;
+                ui.checkbox(&mut panels.show_left, "Explorer Panel");
+                ui.checkbox(&mut panels.show_right, "Inspector Panel");
+                ui.separator();
+                if toolbar::menu_action(ui, &format!("Colors: {}", vm.color_mode.label())) {
+                    vm.color_mode = vm.color_mode.next();
+                }
+                ui.checkbox(&mut vm.highlight_unsaved, "Highlight Unsaved Changes");
+                ui.separator();
+                if toolbar::menu_action(ui, "Reset Layout") {
+                    *panels = PanelLayout::default();
+                    panel::reset_panel(ui.ctx(), LEFT_PANEL_ID);
+                    panel::reset_panel(ui.ctx(), RIGHT_PANEL_ID);
+                    ui.close_menu();
                 }
             });
 
             // ── Tools Menu ───────────────────────────────────
             ui.menu_button("Tools", |ui| {
                 ui.set_min_width(120.0);
+                if toolbar::menu_action(ui, "Calculator") {
+                    vm.open_calculator();
+                }
+                if toolbar::menu_action(ui, "Markup") {
+                    vm.markup_window.open = true;
+                }
                 if toolbar::menu_action(ui, "Settings") {
                     vm.show_settings_window = true;
                 }
             });
+
+            // ── Help Menu ────────────────────────────────────
+            ui.menu_button("Help", |ui| {
+                ui.set_min_width(120.0);
+                if toolbar::menu_action(ui, "Welcome") {
+                    vm.show_welcome = true;
+                }
+            });
         });
     });
 }
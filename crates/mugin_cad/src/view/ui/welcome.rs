@@ -0,0 +1,129 @@
+//! Start view shown in place of the canvas when no project is open.
+
+use crate::export::thumbnail::decode_thumbnail;
+use crate::model::project::ProjectThumbnail;
+use crate::viewmodel::CadViewModel;
+use eframe::egui;
+use std::path::{Path, PathBuf};
+
+const THUMBNAIL_SIZE: egui::Vec2 = egui::vec2(160.0, 120.0);
+
+pub fn render_welcome(ui: &mut egui::Ui, vm: &mut CadViewModel) {
+    let mut open_recent: Option<PathBuf> = None;
+
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        ui.add_space(24.0);
+        ui.vertical_centered(|ui| {
+            ui.heading("MuginCAD");
+            ui.label(egui::RichText::new("The Modern Rust CAD").weak());
+        });
+        ui.add_space(16.0);
+
+        ui.horizontal(|ui| {
+            ui.add_space(24.0);
+            if ui.button("New Drawing").clicked() {
+                vm.show_welcome = false;
+            }
+            if ui
+                .add_enabled(!vm.is_busy(), egui::Button::new("Open..."))
+                .clicked()
+            {
+                vm.load_project();
+            }
+        });
+
+        ui.add_space(16.0);
+        section_heading(ui, "Templates");
+        ui.horizontal_wrapped(|ui| {
+            ui.add_space(24.0);
+            let mut chosen = None;
+            for template in &vm.templates {
+                let mut response = ui.add_sized([200.0, 48.0], egui::Button::new(&template.name));
+                if !template.description.is_empty() {
+                    response = response.on_hover_text(&template.description);
+                }
+                if response.clicked() {
+                    chosen = Some(template.clone());
+                }
+            }
+            if let Some(template) = chosen {
+                vm.new_from_template(&template);
+            }
+        });
+
+        ui.add_space(16.0);
+        section_heading(ui, "Recent Projects");
+        if vm.recent_projects.entries.is_empty() {
+            ui.horizontal(|ui| {
+                ui.add_space(24.0);
+                ui.label(egui::RichText::new("No recent projects").weak());
+            });
+            return;
+        }
+
+        ui.horizontal_wrapped(|ui| {
+            ui.add_space(24.0);
+            for entry in &vm.recent_projects.entries {
+                let clicked = ui
+                    .vertical(|ui| {
+                        ui.set_width(THUMBNAIL_SIZE.x);
+                        let response = match thumbnail_texture(ui.ctx(), &entry.path) {
+                            Some(texture) => ui.add(
+                                egui::ImageButton::new((texture.id(), THUMBNAIL_SIZE)).frame(true),
+                            ),
+                            None => ui.add_sized(THUMBNAIL_SIZE, egui::Button::new("No preview")),
+                        };
+                        ui.label(&entry.name);
+                        response
+                            .on_hover_text(entry.path.display().to_string())
+                            .clicked()
+                    })
+                    .inner;
+                if clicked && vm.background.is_none() {
+                    open_recent = Some(entry.path.clone());
+                }
+            }
+        });
+    });
+
+    if let Some(path) = open_recent {
+        vm.open_recent(&path);
+    }
+}
+
+fn section_heading(ui: &mut egui::Ui, title: &str) {
+    ui.horizontal(|ui| {
+        ui.add_space(24.0);
+        ui.label(egui::RichText::new(title).strong().size(16.0));
+    });
+    ui.separator();
+}
+
+// ── Thumbnail cache ─────────────────────────────────────────
+
+/// Load (once per file version) and cache the thumbnail embedded in a project file
+fn thumbnail_texture(ctx: &egui::Context, path: &Path) -> Option<egui::TextureHandle> {
+    // Keyed by modification time so a re-saved project shows its new preview
+    let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    let id = egui::Id::new("welcome_thumbnail").with(path).with(modified);
+    if let Some(cached) = ctx.data(|d| d.get_temp::<Option<egui::TextureHandle>>(id)) {
+        return cached;
+    }
+
+    let texture = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<ProjectThumbnail>(&content).ok())
+        .and_then(|project| project.thumbnail)
+        .and_then(|encoded| decode_thumbnail(&encoded))
+        .map(|(width, height, rgba)| {
+            let image = egui::ColorImage::from_rgba_unmultiplied([width, height], &rgba);
+            ctx.load_texture(
+                format!("thumbnail:{}", path.display()),
+                image,
+                egui::TextureOptions::LINEAR,
+            )
+        });
+
+    ctx.data_mut(|d| d.insert_temp(id, texture.clone()));
+    texture
+}
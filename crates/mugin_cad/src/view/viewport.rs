@@ -1,5 +1,13 @@
 use crate::model::Vector2;
 
+/// Time constant of the pan inertia decay; the glide is over in ~200 ms
+const INERTIA_DECAY: f32 = 0.2 / 3.0;
+/// Glide speed (screen pixels per second) below which inertia stops
+const INERTIA_MIN_SPEED: f32 = 5.0;
+/// Zoom limits shared by wheel zoom and zoom extents
+const MIN_ZOOM: f32 = 0.1;
+const MAX_ZOOM: f32 = 10.0;
+
 /// Camera/viewport settings for the canvas
 #[derive(Debug, Clone)]
 pub struct Viewport {
@@ -11,6 +19,13 @@ pub struct Viewport {
     pub is_panning: bool,
     /// Last mouse position during pan
     pub pan_start: Option<Vector2>,
+    /// Pan speed in screen pixels per second, measured while panning and
+    /// used for the glide after release
+    pub pan_velocity: Vector2,
+    /// Pan movement since the last `tick`
+    pan_delta: Vector2,
+    /// Size of the canvas in screen pixels, updated every frame
+    pub screen_size: Vector2,
 }
 
 impl Default for Viewport {
@@ -20,6 +35,9 @@ impl Default for Viewport {
             zoom: 1.0,
             is_panning: false,
             pan_start: None,
+            pan_velocity: Vector2::new(0.0, 0.0),
+            pan_delta: Vector2::new(0.0, 0.0),
+            screen_size: Vector2::new(0.0, 0.0),
         }
     }
 }
@@ -33,23 +51,55 @@ impl Viewport {
     pub fn start_pan(&mut self, screen_pos: Vector2) {
         self.is_panning = true;
         self.pan_start = Some(screen_pos);
+        self.pan_velocity = Vector2::new(0.0, 0.0);
+        self.pan_delta = Vector2::new(0.0, 0.0);
     }
 
     /// Update pan during drag
     pub fn update_pan(&mut self, screen_pos: Vector2) {
-        if self.is_panning {
-            if let Some(start) = self.pan_start {
-                let delta = screen_pos - start;
-                self.offset = self.offset + delta;
-                self.pan_start = Some(screen_pos);
-            }
+        if self.is_panning
+            && let Some(start) = self.pan_start
+        {
+            let delta = screen_pos - start;
+            self.offset = self.offset + delta;
+            self.pan_delta = self.pan_delta + delta;
+            self.pan_start = Some(screen_pos);
         }
     }
 
-    /// End panning
-    pub fn end_pan(&mut self) {
+    /// End panning. With `inertia` the view keeps gliding at the release
+    /// speed (see `tick`); otherwise it stops dead.
+    pub fn end_pan(&mut self, inertia: bool) {
         self.is_panning = false;
         self.pan_start = None;
+        if !inertia {
+            self.pan_velocity = Vector2::new(0.0, 0.0);
+        }
+    }
+
+    /// Advance per-frame state by `dt` seconds: measure pan speed while
+    /// panning, apply and decay inertia after release.
+    /// Returns true while the view is still gliding.
+    pub fn tick(&mut self, dt: f32) -> bool {
+        if dt <= 0.0 {
+            return false;
+        }
+
+        if self.is_panning {
+            // Smooth over a few frames so one jittery frame doesn't dominate
+            let frame_velocity = self.pan_delta / dt;
+            self.pan_velocity = (self.pan_velocity + frame_velocity) * 0.5;
+            self.pan_delta = Vector2::new(0.0, 0.0);
+            return false;
+        }
+
+        if self.pan_velocity.length() < INERTIA_MIN_SPEED {
+            self.pan_velocity = Vector2::new(0.0, 0.0);
+            return false;
+        }
+        self.offset = self.offset + self.pan_velocity * dt;
+        self.pan_velocity = self.pan_velocity * (-dt / INERTIA_DECAY).exp();
+        true
     }
 
     /// Convert screen position to CAD coordinates
@@ -68,10 +118,46 @@ impl Viewport {
         )
     }
 
+    /// World-space corners `(min, max)` of the visible area
+    pub fn visible_bounds(&self) -> (Vector2, Vector2) {
+        let center = self.screen_size * 0.5;
+        let a = self.screen_to_cad(Vector2::new(0.0, 0.0), center);
+        let b = self.screen_to_cad(self.screen_size, center);
+        (
+            Vector2::new(a.x.min(b.x), a.y.min(b.y)),
+            Vector2::new(a.x.max(b.x), a.y.max(b.y)),
+        )
+    }
+
     /// Reset viewport to default
     pub fn reset(&mut self) {
         self.offset = Vector2::new(0.0, 0.0);
         self.zoom = 1.0;
+        self.pan_velocity = Vector2::new(0.0, 0.0);
+    }
+
+    /// Jump to a saved pan and zoom, keeping the zoom within limits
+    pub fn restore(&mut self, offset: Vector2, zoom: f32) {
+        self.offset = offset;
+        self.zoom = if zoom.is_finite() {
+            zoom.clamp(MIN_ZOOM, MAX_ZOOM)
+        } else {
+            1.0
+        };
+        self.pan_velocity = Vector2::new(0.0, 0.0);
+    }
+
+    /// Fit the CAD rectangle `min`..`max` into the canvas with a small margin
+    pub fn zoom_to_bounds(&mut self, min: Vector2, max: Vector2) {
+        let size = max - min;
+        let zoom_x = self.screen_size.x / size.x.max(f32::EPSILON);
+        let zoom_y = self.screen_size.y / size.y.max(f32::EPSILON);
+        self.zoom = (zoom_x.min(zoom_y) * 0.9).clamp(MIN_ZOOM, MAX_ZOOM);
+
+        // Put the centre of the bounds at the centre of the canvas
+        let center = (min + max) * 0.5;
+        self.offset = Vector2::new(-center.x * self.zoom, center.y * self.zoom);
+        self.pan_velocity = Vector2::new(0.0, 0.0);
     }
 
     /// Zoom at a specific screen position (for mouse-centered zoom)
@@ -80,7 +166,7 @@ impl Viewport {
         // Calculate zoom factor
         let zoom_speed = 0.1;
         let zoom_factor = 1.0 + delta * zoom_speed;
-        let new_zoom = (self.zoom * zoom_factor).clamp(0.1, 10.0); // 10% to 1000%
+        let new_zoom = (self.zoom * zoom_factor).clamp(MIN_ZOOM, MAX_ZOOM); // 10% to 1000%
 
         if (new_zoom - self.zoom).abs() < 0.001 {
             return;
@@ -0,0 +1,531 @@
+//! Every command, menu action and settings page in one table. The
+//! command palette searches it, the toolbar takes its labels from it and
+//! the settings window lists its key bindings.
+
+use crate::model::tools::fuzzy::{FuzzyMatch, fuzzy_match};
+use crate::view::ui::command_palette::CommandPalette;
+use crate::viewmodel::{CadViewModel, MarkupTool, QuickTransform};
+use ActionCategory::*;
+
+/// Where an action is listed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionCategory {
+    Draw,
+    Modify,
+    Annotate,
+    Structure,
+    Measure,
+    Project,
+    Edit,
+    View,
+    Tools,
+    Settings,
+}
+
+impl ActionCategory {
+    pub fn label(self) -> &'static str {
+        match self {
+            ActionCategory::Draw => "Draw",
+            ActionCategory::Modify => "Modify",
+            ActionCategory::Annotate => "Annotate",
+            ActionCategory::Structure => "Structure",
+            ActionCategory::Measure => "Measure",
+            ActionCategory::Project => "Project",
+            ActionCategory::Edit => "Edit",
+            ActionCategory::View => "View",
+            ActionCategory::Tools => "Tools",
+            ActionCategory::Settings => "Settings",
+        }
+    }
+}
+
+/// A section of the settings window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsPage {
+    Snap,
+    Grid,
+    Appearance,
+    Drafting,
+    Dimensions,
+    Geometry,
+    Gui,
+    Keyboard,
+    QuickTransforms,
+    Toolbar,
+}
+
+impl SettingsPage {
+    pub const fn label(self) -> &'static str {
+        match self {
+            SettingsPage::Snap => "Snap Configuration",
+            SettingsPage::Grid => "Grid Configuration",
+            SettingsPage::Appearance => "Appearance Configuration",
+            SettingsPage::Drafting => "Drafting Defaults",
+            SettingsPage::Dimensions => "Dimensions",
+            SettingsPage::Geometry => "Geometry Limits",
+            SettingsPage::Gui => "GUI Configuration",
+            SettingsPage::Keyboard => "Keyboard Shortcuts",
+            SettingsPage::QuickTransforms => "Quick Transforms",
+            SettingsPage::Toolbar => "Toolbar",
+        }
+    }
+}
+
+/// What running an action does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppAction {
+    /// Typed into the terminal, exactly as if entered by hand
+    Terminal(&'static str),
+    Quick(QuickTransform),
+    /// Open the settings window at a section
+    Settings(SettingsPage),
+    NewTab,
+    Save,
+    Load,
+    SaveAsTemplate,
+    InsertImage,
+    CompareWithFile,
+    ExportPdf,
+    BatchExport,
+    ExportData,
+    ExportJournal,
+    FindReplace,
+    Materials,
+    ColumnTypes,
+    BeamTypes,
+    Storeys,
+    ExportAttributes,
+    RoomsReport,
+    GenerateSection,
+    ImportSymbols,
+    Calculator,
+    ToggleMarkup,
+    Markup(MarkupTool),
+    MarkupPanel,
+    CommandPalette,
+    CycleColors,
+    ToggleExplorer,
+    ToggleInspector,
+    Welcome,
+}
+
+/// One entry of the registry
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ActionSpec {
+    /// Stable id; a command's is its terminal name
+    pub id: &'static str,
+    pub label: &'static str,
+    pub category: ActionCategory,
+    pub action: AppAction,
+    /// Disabled while nothing is selected
+    pub needs_selection: bool,
+    /// Fixed key binding or typed alias shown beside the action. Other
+    /// commands show the executor's shortest alias.
+    pub keys: Option<&'static str>,
+}
+
+const fn command(
+    id: &'static str,
+    label: &'static str,
+    category: ActionCategory,
+    needs_selection: bool,
+) -> ActionSpec {
+    ActionSpec {
+        id,
+        label,
+        category,
+        action: AppAction::Terminal(id),
+        needs_selection,
+        keys: None,
+    }
+}
+
+const fn action(
+    id: &'static str,
+    label: &'static str,
+    category: ActionCategory,
+    action: AppAction,
+) -> ActionSpec {
+    ActionSpec {
+        id,
+        label,
+        category,
+        action,
+        needs_selection: false,
+        keys: None,
+    }
+}
+
+const fn quick(id: &'static str, transform: QuickTransform) -> ActionSpec {
+    ActionSpec {
+        id,
+        label: transform.label(),
+        category: ActionCategory::Modify,
+        action: AppAction::Quick(transform),
+        needs_selection: true,
+        keys: None,
+    }
+}
+
+const fn settings(id: &'static str, page: SettingsPage) -> ActionSpec {
+    action(
+        id,
+        page.label(),
+        ActionCategory::Settings,
+        AppAction::Settings(page),
+    )
+}
+
+const fn keys(spec: ActionSpec, keys: &'static str) -> ActionSpec {
+    ActionSpec {
+        keys: Some(keys),
+        ..spec
+    }
+}
+
+/// Every action, in the order the palette lists them before anything is typed
+pub const ACTIONS: &[ActionSpec] = &[
+    // Terminal commands
+    command("line", "Line", Draw, false),
+    command("circle", "Circle", Draw, false),
+    command("rect", "Rectangle", Draw, false),
+    command("arc", "Arc", Draw, false),
+    command("axis", "Axis", Draw, false),
+    command("move", "Move", Modify, true),
+    command("rotate", "Rotate", Modify, true),
+    command("scale", "Scale", Modify, true),
+    command("mirror", "Mirror", Modify, true),
+    command("calibrate", "Calibrate Scale", Modify, true),
+    keys(command("copy", "Copy", Modify, true), "Ctrl+C"),
+    keys(command("cut", "Cut", Modify, true), "Ctrl+X"),
+    command("trim", "Trim", Modify, false),
+    command("offset", "Offset", Modify, true),
+    keys(command("delete", "Delete", Modify, true), "Delete"),
+    quick("rotate_cw", QuickTransform::RotateCw),
+    quick("rotate_ccw", QuickTransform::RotateCcw),
+    quick("flip_horizontal", QuickTransform::FlipHorizontal),
+    quick("flip_vertical", QuickTransform::FlipVertical),
+    command("text", "Text Annotation", Annotate, false),
+    command("section", "Section Mark", Annotate, false),
+    command("revcloud", "Revision Cloud", Annotate, false),
+    command("symbol", "Symbol", Annotate, false),
+    command("place_column", "Place Column", Structure, false),
+    command("place_beam", "Place Beam", Structure, false),
+    command("flooring", "Flooring", Structure, false),
+    command("opening", "Slab Opening", Structure, false),
+    command("stair", "Stair", Structure, false),
+    command("cleanup", "Clean Up Junctions", Structure, false),
+    command("rooms", "Detect Rooms", Structure, false),
+    command("measure", "Measure Distance", Measure, false),
+    command("autodim", "Auto Dimension", Measure, false),
+    command("area", "Measure Closed Area", Measure, false),
+    command("perim", "Measure Perimeter", Measure, false),
+    command("distance", "Distance", Measure, false),
+    command("label", "Label Last Measurement", Measure, false),
+    command("fill", "Toggle Shade Mode", Draw, false),
+    // Edit
+    keys(command("undo", "Undo", Edit, false), "U"),
+    command("redo", "Redo", Edit, false),
+    command("audit", "Audit Degenerate Geometry", Edit, false),
+    action(
+        "audit_fix",
+        "Remove Degenerate Geometry",
+        Edit,
+        AppAction::Terminal("audit fix"),
+    ),
+    keys(command("previous", "Select Previous", Edit, false), "P"),
+    action(
+        "find_replace",
+        "Find & Replace...",
+        Edit,
+        AppAction::FindReplace,
+    ),
+    // Project
+    action("new", "New Project", Project, AppAction::NewTab),
+    action("save", "Save", Project, AppAction::Save),
+    action("load", "Load", Project, AppAction::Load),
+    action(
+        "save_as_template",
+        "Save as Template...",
+        Project,
+        AppAction::SaveAsTemplate,
+    ),
+    action(
+        "insert_image",
+        "Insert Image...",
+        Project,
+        AppAction::InsertImage,
+    ),
+    action(
+        "compare_file",
+        "Compare with File...",
+        Project,
+        AppAction::CompareWithFile,
+    ),
+    action("export_pdf", "Export PDF...", Project, AppAction::ExportPdf),
+    action(
+        "batch_export",
+        "Batch Export...",
+        Project,
+        AppAction::BatchExport,
+    ),
+    action(
+        "export_data",
+        "Export Data...",
+        Project,
+        AppAction::ExportData,
+    ),
+    action(
+        "export_journal",
+        "Export Change Journal...",
+        Project,
+        AppAction::ExportJournal,
+    ),
+    command("select_region", "Select Export Region", Project, false),
+    action(
+        "export_attributes",
+        "Export Attributes...",
+        Project,
+        AppAction::ExportAttributes,
+    ),
+    action(
+        "import_symbols",
+        "Import Symbols...",
+        Annotate,
+        AppAction::ImportSymbols,
+    ),
+    // Structure windows and reports
+    action("materials", "Materials...", Structure, AppAction::Materials),
+    action(
+        "column_types",
+        "Column Types...",
+        Structure,
+        AppAction::ColumnTypes,
+    ),
+    action(
+        "beam_types",
+        "Beam Types...",
+        Structure,
+        AppAction::BeamTypes,
+    ),
+    action("storeys", "Storeys...", Structure, AppAction::Storeys),
+    action(
+        "rooms_report",
+        "Rooms Report...",
+        Structure,
+        AppAction::RoomsReport,
+    ),
+    action(
+        "generate_section",
+        "Generate Section",
+        Structure,
+        AppAction::GenerateSection,
+    ),
+    // View
+    keys(command("ze", "Zoom Extents", View, false), "ZE"),
+    action("cycle_colors", "Cycle Colors", View, AppAction::CycleColors),
+    action(
+        "toggle_explorer",
+        "Toggle Explorer Panel",
+        View,
+        AppAction::ToggleExplorer,
+    ),
+    action(
+        "toggle_inspector",
+        "Toggle Inspector Panel",
+        View,
+        AppAction::ToggleInspector,
+    ),
+    action("welcome", "Welcome", View, AppAction::Welcome),
+    // Tools
+    action("calc", "Calculator", Tools, AppAction::Calculator),
+    action(
+        "markup",
+        "Toggle Markup Mode",
+        Tools,
+        AppAction::ToggleMarkup,
+    ),
+    action(
+        "markup_pen",
+        "Markup Pen",
+        Tools,
+        AppAction::Markup(MarkupTool::Pen),
+    ),
+    action(
+        "markup_highlighter",
+        "Markup Highlighter",
+        Tools,
+        AppAction::Markup(MarkupTool::Highlighter),
+    ),
+    action(
+        "markup_eraser",
+        "Markup Eraser",
+        Tools,
+        AppAction::Markup(MarkupTool::Eraser),
+    ),
+    action("markup_panel", "Markup...", Tools, AppAction::MarkupPanel),
+    keys(
+        action(
+            "command_palette",
+            "Command Palette",
+            Tools,
+            AppAction::CommandPalette,
+        ),
+        "Ctrl+P",
+    ),
+    // Settings pages
+    settings("settings_snap", SettingsPage::Snap),
+    settings("settings_grid", SettingsPage::Grid),
+    settings("settings_appearance", SettingsPage::Appearance),
+    settings("settings_drafting", SettingsPage::Drafting),
+    settings("settings_dimensions", SettingsPage::Dimensions),
+    settings("settings_geometry", SettingsPage::Geometry),
+    settings("settings_gui", SettingsPage::Gui),
+    settings("settings_keyboard", SettingsPage::Keyboard),
+    settings("settings_quick_transforms", SettingsPage::QuickTransforms),
+    settings("settings_toolbar", SettingsPage::Toolbar),
+];
+
+/// Look up an action by id
+pub fn find_action(id: &str) -> Option<&'static ActionSpec> {
+    ACTIONS.iter().find(|spec| spec.id == id)
+}
+
+/// An action found by the palette
+#[derive(Debug, Clone)]
+pub struct ActionMatch {
+    pub spec: &'static ActionSpec,
+    /// Match against the label; positions are for highlighting it
+    pub matched: FuzzyMatch,
+}
+
+impl CadViewModel {
+    /// Actions matching `query`, best first. Commands are also found by
+    /// their terminal name, e.g. "revcloud".
+    pub fn search_actions(&self, query: &str) -> Vec<ActionMatch> {
+        let mut matches: Vec<ActionMatch> = ACTIONS
+            .iter()
+            .filter_map(|spec| {
+                let by_label = fuzzy_match(query, spec.label);
+                let by_name = match spec.action {
+                    AppAction::Terminal(name) => fuzzy_match(query, name),
+                    _ => None,
+                };
+                let matched = match (by_label, by_name) {
+                    (Some(label), Some(name)) if name.score > label.score => FuzzyMatch {
+                        score: name.score,
+                        positions: Vec::new(),
+                    },
+                    (Some(label), _) => label,
+                    (None, Some(name)) => FuzzyMatch {
+                        score: name.score,
+                        positions: Vec::new(),
+                    },
+                    (None, None) => return None,
+                };
+                Some(ActionMatch { spec, matched })
+            })
+            .collect();
+        // Stable, so equal scores keep the registry order
+        matches.sort_by_key(|found| std::cmp::Reverse(found.matched.score));
+        matches
+    }
+
+    /// Key binding or typed alias shown next to an action
+    pub fn action_shortcut(&self, spec: &ActionSpec) -> Option<String> {
+        match spec.action {
+            AppAction::Quick(transform) => {
+                let binding = transform.shortcut(&self.config.gui_config.quick_transform_keys);
+                (!binding.is_empty()).then(|| binding.to_string())
+            }
+            _ if spec.keys.is_some() => spec.keys.map(str::to_string),
+            AppAction::Terminal(name) => self
+                .tabs
+                .get(self.active_tab_index)
+                .and_then(|tab| tab.executor.shortcut_for(name)),
+            _ => None,
+        }
+    }
+
+    /// Whether the action can run right now
+    pub fn action_enabled(&self, spec: &ActionSpec) -> bool {
+        let Some(tab) = self.tabs.get(self.active_tab_index) else {
+            return matches!(
+                spec.action,
+                AppAction::NewTab
+                    | AppAction::Load
+                    | AppAction::Settings(_)
+                    | AppAction::CommandPalette
+                    | AppAction::Welcome
+            );
+        };
+        if spec.needs_selection && tab.selection_manager.selected_ids.is_empty() {
+            return false;
+        }
+        match spec.action {
+            AppAction::Save | AppAction::Load | AppAction::ExportPdf | AppAction::BatchExport => {
+                !self.is_busy()
+            }
+            _ => true,
+        }
+    }
+
+    /// Run an action from the palette or a binding. Does nothing when it
+    /// can't run right now.
+    pub fn run_action(&mut self, spec: &ActionSpec) {
+        if !self.action_enabled(spec) {
+            return;
+        }
+        match spec.action {
+            AppAction::Terminal(name) => {
+                if name == "symbol" {
+                    self.active_tab_mut().executor.active_symbol = None;
+                }
+                self.command_input = name.to_string();
+                self.process_command();
+            }
+            AppAction::Quick(transform) => self.apply_quick_transform(transform),
+            AppAction::Settings(page) => {
+                self.show_settings_window = true;
+                self.settings_page = Some(page);
+            }
+            AppAction::NewTab => self.new_tab(),
+            AppAction::Save => self.save_project(),
+            AppAction::Load => self.load_project(),
+            AppAction::SaveAsTemplate => self.save_as_template(),
+            AppAction::InsertImage => self.insert_image(),
+            AppAction::CompareWithFile => self.compare_with_file(),
+            AppAction::ExportPdf => self.export_window.open = true,
+            AppAction::BatchExport => self.batch_export_window.open = true,
+            AppAction::ExportData => self.data_export_window.open = true,
+            AppAction::ExportJournal => self.export_journal(),
+            AppAction::FindReplace => self.find_replace_window.open = true,
+            AppAction::Materials => self.materials_manager_open = true,
+            AppAction::ColumnTypes => self.column_manager_open = true,
+            AppAction::BeamTypes => self.beam_manager_open = true,
+            AppAction::Storeys => self.storey_manager_open = true,
+            AppAction::ExportAttributes => self.export_attributes(),
+            AppAction::RoomsReport => self.rooms_report_window.open = true,
+            AppAction::GenerateSection => self.generate_section(),
+            AppAction::ImportSymbols => self.import_symbols(),
+            AppAction::Calculator => self.open_calculator(),
+            AppAction::ToggleMarkup => self.toggle_markup(),
+            AppAction::Markup(tool) => self.set_markup_tool(tool),
+            AppAction::MarkupPanel => self.markup_window.open = true,
+            AppAction::CommandPalette if self.command_palette.open => {
+                self.command_palette.open = false;
+            }
+            AppAction::CommandPalette => self.command_palette = CommandPalette::opened(),
+            AppAction::CycleColors => self.color_mode = self.color_mode.next(),
+            AppAction::ToggleExplorer => {
+                let panels = &mut self.config.gui_config.panels;
+                panels.show_left = !panels.show_left;
+            }
+            AppAction::ToggleInspector => {
+                let panels = &mut self.config.gui_config.panels;
+                panels.show_right = !panels.show_right;
+            }
+            AppAction::Welcome => self.show_welcome = true,
+        }
+    }
+}
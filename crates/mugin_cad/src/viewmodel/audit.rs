@@ -0,0 +1,54 @@
+//! AUDIT: degenerate geometry already in the drawing.
+
+use crate::commands::output::TerminalLine;
+use crate::viewmodel::CadViewModel;
+
+/// Most offending entities AUDIT lists by name
+const LISTED: usize = 10;
+
+impl CadViewModel {
+    /// AUDIT: report the entities below the geometry limits. With `fix`
+    /// they are removed as one undo step.
+    pub fn audit_geometry(&mut self, fix: bool) {
+        let limits = self.config.geometry_limits.clone();
+        let found = self.active_tab().model.degenerate_entities(&limits);
+        if found.is_empty() {
+            let (tab, history) = self.active_tab_mut_and_history();
+            let line = TerminalLine::info("AUDIT: no degenerate geometry");
+            tab.executor.status_message = line.text.clone();
+            history.push(line);
+            return;
+        }
+
+        if fix {
+            self.save_undo_state();
+            let (tab, history) = self.active_tab_mut_and_history();
+            let removed = tab.model.remove_degenerate(&limits);
+            tab.selection_manager.prune(&tab.model);
+            let line =
+                TerminalLine::info(format!("AUDIT: removed {} degenerate entities", removed));
+            tab.executor.status_message = line.text.clone();
+            history.push(line);
+            return;
+        }
+
+        let (tab, history) = self.active_tab_mut_and_history();
+        let message = format!("AUDIT: {} degenerate entities", found.len());
+        history.push(TerminalLine::warning(&message));
+        for (id, problem) in found.iter().take(LISTED) {
+            let name = tab
+                .model
+                .find_by_id(*id)
+                .map_or_else(String::new, |entity| entity.name.clone());
+            history.push(TerminalLine::warning(format!("  {}: {}", name, problem)));
+        }
+        if found.len() > LISTED {
+            history.push(TerminalLine::warning(format!(
+                "  ... and {} more",
+                found.len() - LISTED
+            )));
+        }
+        history.push(TerminalLine::info("Type AUDIT FIX to remove them"));
+        tab.executor.status_message = message;
+    }
+}
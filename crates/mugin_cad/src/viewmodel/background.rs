@@ -0,0 +1,331 @@
+//! Save, load and PDF export run on a worker thread so large projects do
+//! not freeze the UI. The view polls for the outcome once per frame.
+
+use crate::commands::output::TerminalLine;
+use crate::export::batch::{BatchOutput, BatchReport, BatchSheet, export_batch, storey_sheets};
+use crate::export::pdf::PdfExporter;
+use crate::export::settings::ExportSettings;
+use crate::export::thumbnail::render_thumbnail;
+use crate::model::CadModel;
+use crate::model::project::ProjectData;
+use crate::model::tools::saved::SavedRevisions;
+use crate::view::ui::export::batch_window::{BatchRequest, BatchSource};
+use crate::viewmodel::CadViewModel;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::time::Instant;
+
+/// Result reported by a worker
+pub enum JobOutcome {
+    Saved {
+        tab_id: u64,
+        path: PathBuf,
+        /// Revisions of the entities as written
+        revisions: SavedRevisions,
+        /// Change revision of the model when the snapshot was taken
+        model_revision: u64,
+    },
+    Loaded {
+        path: PathBuf,
+        project: Box<ProjectData>,
+    },
+    LoadFailed {
+        path: PathBuf,
+        error: String,
+    },
+    Exported {
+        path: PathBuf,
+    },
+    BatchExported(BatchReport),
+    Failed(String),
+}
+
+/// A running worker and the channel its outcome arrives on
+pub struct BackgroundJob {
+    /// What is running, e.g. "Saving plan.mugin"
+    pub label: String,
+    pub started: Instant,
+    /// Whether the user may abandon the job
+    pub cancellable: bool,
+    cancel: Arc<AtomicBool>,
+    receiver: mpsc::Receiver<JobOutcome>,
+    /// Steps done and the total, for jobs made of several steps
+    steps: Option<(Arc<AtomicUsize>, usize)>,
+}
+
+impl BackgroundJob {
+    /// Run `work` on a new thread. It receives the cancel flag to check.
+    fn spawn(
+        label: String,
+        cancellable: bool,
+        work: impl FnOnce(&AtomicBool) -> JobOutcome + Send + 'static,
+    ) -> Self {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel();
+        let flag = cancel.clone();
+        std::thread::spawn(move || {
+            // The receiver is gone if the job was cancelled; nothing to report then
+            let _ = sender.send(work(&flag));
+        });
+
+        Self {
+            label,
+            started: Instant::now(),
+            cancellable,
+            cancel,
+            receiver,
+            steps: None,
+        }
+    }
+
+    /// Steps done and the total, if the job reports them
+    pub fn progress(&self) -> Option<(usize, usize)> {
+        self.steps
+            .as_ref()
+            .map(|(done, total)| (done.load(Ordering::Relaxed), *total))
+    }
+}
+
+impl CadViewModel {
+    /// Whether a save, load or export is in flight
+    pub fn is_busy(&self) -> bool {
+        self.background.is_some()
+    }
+
+    /// Abandon the running job. The worker notices the flag and cleans up.
+    pub fn cancel_background(&mut self) {
+        if let Some(job) = self.background.take() {
+            job.cancel.store(true, Ordering::Relaxed);
+            self.notify(format!("{} cancelled", job.label));
+        }
+    }
+
+    /// Pick up a finished job's outcome. Called once per frame.
+    pub fn poll_background(&mut self) {
+        let Some(job) = &self.background else {
+            return;
+        };
+        let outcome = match job.receiver.try_recv() {
+            Ok(outcome) => outcome,
+            Err(mpsc::TryRecvError::Empty) => return,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                JobOutcome::Failed(format!("{} failed unexpectedly", job.label))
+            }
+        };
+        self.background = None;
+
+        match outcome {
+            JobOutcome::Saved {
+                tab_id,
+                path,
+                revisions,
+                model_revision,
+            } => self.finish_save(tab_id, path, revisions, model_revision),
+            JobOutcome::Loaded { path, project } => self.apply_project(&path, *project),
+            JobOutcome::LoadFailed { path, error } => {
+                self.recent_projects.remove(&path);
+                self.command_history.push(TerminalLine::error(&error));
+                self.notify(error);
+            }
+            JobOutcome::Exported { path } => {
+                self.command_history
+                    .push(TerminalLine::info(format!("Exported PDF to {:?}", path)));
+                self.notify(format!("Exported {}", path.display()));
+            }
+            JobOutcome::BatchExported(report) => self.finish_batch_export(report),
+            JobOutcome::Failed(error) => {
+                if self.close_after_save.take().is_some() {
+                    self.pending_tab_close.clear();
+                }
+                self.command_history.push(TerminalLine::error(&error));
+                self.notify(error);
+            }
+        }
+    }
+
+    fn finish_save(
+        &mut self,
+        tab_id: u64,
+        path: PathBuf,
+        revisions: SavedRevisions,
+        model_revision: u64,
+    ) {
+        if let Some(index) = self.tab_index(tab_id) {
+            let tab = &mut self.tabs[index];
+            tab.file_path = Some(path.clone());
+            if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+                tab.name = name.to_string();
+            }
+            // Edits made while the file was written keep the tab unsaved
+            if tab.model.changes.revision() == model_revision {
+                tab.is_dirty = false;
+                tab.saved_revisions = revisions;
+            }
+        }
+        self.recent_projects.touch(&path);
+        self.command_history
+            .push(TerminalLine::info(format!("Saved project to {:?}", path)));
+        self.notify(format!("Saved {}", path.display()));
+
+        if self.close_after_save == Some(tab_id) {
+            self.close_after_save = None;
+            if let Some(index) = self.tab_index(tab_id) {
+                self.close_tab(index);
+            }
+            self.pending_tab_close.retain(|&id| id != tab_id);
+        }
+    }
+
+    /// Log what a batch wrote, then every sheet that failed
+    fn finish_batch_export(&mut self, report: BatchReport) {
+        for path in &report.written {
+            self.command_history
+                .push(TerminalLine::info(format!("Exported PDF to {:?}", path)));
+        }
+        for (sheet, error) in &report.failures {
+            self.command_history
+                .push(TerminalLine::error(format!("{}: {}", sheet, error)));
+        }
+        let printed = report.pages.max(report.written.len());
+        if report.failures.is_empty() {
+            self.notify(format!("Exported {} sheets", printed));
+        } else {
+            self.notify(format!(
+                "Batch export: {} exported, {} failed",
+                printed,
+                report.failures.len()
+            ));
+        }
+    }
+
+    /// Write a project snapshot (already cloned off the model) on a worker
+    pub(super) fn start_save(&mut self, tab_id: u64, mut project: ProjectData, path: PathBuf) {
+        let label = format!("Saving {}", file_label(&path));
+        // Edits made while the file is written stay unsaved
+        let revisions = SavedRevisions::capture(&project.entities);
+        let model_revision = self
+            .tab_index(tab_id)
+            .map_or(0, |index| self.tabs[index].model.changes.revision());
+        self.background = Some(BackgroundJob::spawn(label, false, move |_| {
+            let mut model = CadModel::new();
+            model.entities = std::mem::take(&mut project.entities);
+            model.axis_manager.axes = project.axes.clone();
+            model.layer_manager = project.layers.clone();
+            model.update_hierarchy();
+            project.thumbnail = render_thumbnail(&model);
+            project.entities = model.entities;
+
+            let backups = project.config.gui_config.backup_count;
+            match project.write(&path, backups) {
+                Ok(()) => JobOutcome::Saved {
+                    tab_id,
+                    path,
+                    revisions,
+                    model_revision,
+                },
+                Err(error) => JobOutcome::Failed(error),
+            }
+        }));
+    }
+
+    /// Read and parse a project file on a worker
+    pub fn start_load(&mut self, path: PathBuf) {
+        if self.is_busy() {
+            return;
+        }
+        let label = format!("Loading {}", file_label(&path));
+        self.background = Some(BackgroundJob::spawn(
+            label,
+            false,
+            move |_| match ProjectData::read(&path) {
+                Ok(project) => JobOutcome::Loaded {
+                    path,
+                    project: Box::new(project),
+                },
+                Err(error) => JobOutcome::LoadFailed { path, error },
+            },
+        ));
+    }
+
+    /// Export the active tab to PDF on a worker
+    pub fn start_pdf_export(&mut self, settings: ExportSettings, path: PathBuf) {
+        if self.is_busy() {
+            return;
+        }
+        let model = self.active_tab().model.clone();
+        let dim_style = self.config.dimension_style.clone();
+        let label = format!("Exporting {}", file_label(&path));
+
+        self.background = Some(BackgroundJob::spawn(label, true, move |cancel| {
+            // Abandoned while running: the previous PDF at the path stays
+            match PdfExporter::export_replacing(&model, &settings, &dim_style, &path, cancel) {
+                Ok(true) => JobOutcome::Exported { path },
+                Ok(false) => JobOutcome::Failed("Export cancelled".to_string()),
+                Err(e) => JobOutcome::Failed(format!("Export failed: {}", e)),
+            }
+        }));
+    }
+
+    /// Print the checked tabs or storeys with the Export PDF settings on a
+    /// worker, reporting each finished sheet to the progress dialog
+    pub fn start_batch_export(&mut self, request: BatchRequest) {
+        if self.is_busy() {
+            return;
+        }
+        let sheets: Vec<BatchSheet> = match request.source {
+            BatchSource::Tabs => request
+                .ids
+                .iter()
+                .filter_map(|&id| self.tab_index(id))
+                .map(|index| BatchSheet {
+                    name: self.tabs[index].name.clone(),
+                    model: self.tabs[index].model.clone(),
+                })
+                .collect(),
+            BatchSource::Storeys => {
+                let model = &self.active_tab().model;
+                model
+                    .storeys
+                    .storeys
+                    .iter()
+                    .zip(storey_sheets(model))
+                    .filter(|(storey, _)| request.ids.contains(&storey.id))
+                    .map(|(_, sheet)| sheet)
+                    .collect()
+            }
+        };
+        if sheets.is_empty() {
+            return;
+        }
+        let BatchRequest {
+            project, output, ..
+        } = request;
+        let settings = self.export_window.settings.clone();
+        let dim_style = self.config.dimension_style.clone();
+        let total = sheets.len();
+        let label = match &output {
+            BatchOutput::Merged(path) => format!("Exporting {}", file_label(path)),
+            BatchOutput::Separate { .. } => format!("Exporting {} sheets", total),
+        };
+
+        let done = Arc::new(AtomicUsize::new(0));
+        let counter = done.clone();
+        let mut job = BackgroundJob::spawn(label, true, move |cancel| {
+            let report = export_batch(&sheets, &project, &output, &settings, &dim_style, |n| {
+                counter.store(n, Ordering::Relaxed);
+                !cancel.load(Ordering::Relaxed)
+            });
+            JobOutcome::BatchExported(report)
+        });
+        job.steps = Some((done, total));
+        self.background = Some(job);
+    }
+}
+
+fn file_label(path: &std::path::Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
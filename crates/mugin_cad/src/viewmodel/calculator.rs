@@ -0,0 +1,94 @@
+//! Calculator palette: values picked from the drawing and results sent to
+//! the active command.
+
+use crate::commands::DynamicInput;
+use crate::model::Vector2;
+use crate::model::tools::calculator::format_value;
+use crate::viewmodel::CadViewModel;
+
+/// Value the calculator is waiting to pick from the canvas. Clicks go to
+/// the pick instead of the active command or the selection.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CalculatorPick {
+    /// Distance between two points, once the first is picked
+    Distance { first: Option<Vector2> },
+    /// X of a point
+    X,
+    /// Y of a point
+    Y,
+}
+
+impl CalculatorPick {
+    fn prompt(&self) -> &'static str {
+        match self {
+            CalculatorPick::Distance { first: None } => "Calculator: pick the first point",
+            CalculatorPick::Distance { first: Some(_) } => "Calculator: pick the second point",
+            CalculatorPick::X => "Calculator: pick a point for its X",
+            CalculatorPick::Y => "Calculator: pick a point for its Y",
+        }
+    }
+}
+
+impl CadViewModel {
+    pub fn open_calculator(&mut self) {
+        self.calculator_window.open = true;
+    }
+
+    /// Take the next canvas clicks as a value for the calculator
+    pub fn start_calculator_pick(&mut self, pick: CalculatorPick) {
+        self.active_tab_mut().executor.status_message = pick.prompt().to_string();
+        self.calculator_pick = Some(pick);
+    }
+
+    /// Stop picking. Returns false if nothing was being picked.
+    pub fn cancel_calculator_pick(&mut self) -> bool {
+        if self.calculator_pick.take().is_none() {
+            return false;
+        }
+        self.active_tab_mut().executor.status_message = "Calculator pick cancelled".to_string();
+        true
+    }
+
+    /// Use a clicked point for the pending pick, adding the value to the
+    /// calculator input once complete. Returns false if nothing was being
+    /// picked, so the click is handled as usual.
+    pub fn calculator_pick_point(&mut self, pos: Vector2) -> bool {
+        let Some(pick) = self.calculator_pick else {
+            return false;
+        };
+        let value = match pick {
+            CalculatorPick::Distance { first: None } => {
+                self.start_calculator_pick(CalculatorPick::Distance { first: Some(pos) });
+                return true;
+            }
+            CalculatorPick::Distance { first: Some(first) } => first.dist(pos),
+            CalculatorPick::X => pos.x,
+            CalculatorPick::Y => pos.y,
+        } as f64;
+        self.calculator_pick = None;
+        self.calculator.insert(value);
+        self.active_tab_mut().executor.status_message =
+            format!("Calculator: {} picked", format_value(value));
+        true
+    }
+
+    /// Type the last result into the terminal as input for the active
+    /// command. A command taking a length and angle gets a point that far
+    /// toward where the pointer last was; others get the number, e.g. as
+    /// a radius.
+    pub fn send_calculator_answer(&mut self) {
+        let Some(value) = self.calculator.answer() else {
+            return;
+        };
+        let tab = self.active_tab();
+        self.command_input = match (tab.executor.dynamic_input(), tab.last_cursor) {
+            (Some(input @ DynamicInput::Polar { .. }), Some(toward)) => {
+                let mut values = input.values(toward);
+                values[0] = value as f32;
+                input.terminal_input(&values)
+            }
+            _ => format_value(value),
+        };
+        self.process_command();
+    }
+}
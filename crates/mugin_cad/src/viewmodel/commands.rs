@@ -1,5 +1,33 @@
+use crate::commands::output::TerminalLine;
 use crate::viewmodel::CadViewModel;
 
+/// What a press of Escape did. Each press undoes one level, innermost first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EscapeStage {
+    /// Left the Ctrl+R history search
+    CancelSearch,
+    /// Cleared the half-typed terminal input
+    ClearInput,
+    /// Stopped picking a value for the calculator
+    CancelPick,
+    /// Left markup mode
+    ExitMarkup,
+    /// Aborted a drag selection or label drag
+    AbortDrag,
+    /// Dismissed the delete confirmation prompt
+    CancelPrompt,
+    /// Dropped the points picked so far; the command stays active
+    CancelStep,
+    /// Ended the active command
+    CancelCommand,
+    /// Deselected everything
+    ClearSelection,
+    /// Stepped out of the group being edited
+    ExitGroup,
+    /// Nothing left to cancel
+    Idle,
+}
+
 impl CadViewModel {
     /// Process command input from terminal
     pub fn process_command(&mut self) {
@@ -16,11 +44,18 @@ impl CadViewModel {
         // Reset history navigation
         self.history_nav_index = None;
 
-        self.command_history.push(format!("> {}", input_text));
+        self.command_history
+            .push(TerminalLine::input(input_text.clone()));
+        self.record_input(&input_text);
 
         // Handle special commands
         let clean = input_text.trim().to_lowercase();
 
+        // Option keys of the active command (e.g. "u" for Undo in LINE)
+        // and text it is waiting for take precedence over the shortcuts below
+        let executor = &self.active_tab().executor;
+        let is_option = executor.is_option(&clean) || executor.takes_text();
+
         if self.active_tab_mut().pending_delete_confirmation {
             match clean.as_str() {
                 "y" | "yes" => {
@@ -30,7 +65,7 @@ impl CadViewModel {
                 "n" | "no" => {
                     let (tab, history) = self.active_tab_mut_and_history();
                     tab.executor.status_message = "Delete cancelled".to_string();
-                    history.push("Cancelled.".to_string());
+                    history.push(TerminalLine::info("Cancelled."));
                     tab.pending_delete_confirmation = false;
                 }
                 _ => {
@@ -42,7 +77,32 @@ impl CadViewModel {
             return;
         }
 
+        // Commands taking a name, e.g. "selsave walls"
+        let (command, name) = input_text.split_once(' ').unwrap_or((&input_text, ""));
+        if !is_option {
+            match command.to_lowercase().as_str() {
+                "selsave" => {
+                    self.save_selection_set(name);
+                    return;
+                }
+                "selload" => {
+                    self.load_selection_set(name);
+                    return;
+                }
+                "selectflag" => {
+                    self.select_flagged(name);
+                    return;
+                }
+                "audit" => {
+                    self.audit_geometry(name.trim().eq_ignore_ascii_case("fix"));
+                    return;
+                }
+                _ => {}
+            }
+        }
+
         match clean.as_str() {
+            _ if is_option => {}
             "u" | "undo" => {
                 self.undo();
                 return;
@@ -51,20 +111,50 @@ impl CadViewModel {
                 self.redo();
                 return;
             }
+            "ze" | "zoom extents" => {
+                self.zoom_extents();
+                return;
+            }
+            "p" | "previous" => {
+                self.select_previous();
+                return;
+            }
             "fill" | "shade" => {
                 let (tab, history) = self.active_tab_mut_and_history();
                 let mode = tab.executor.toggle_filled();
                 let mode_str = if mode { "ON" } else { "OFF" };
                 tab.executor.status_message = format!("SHADE mode: {}", mode_str);
-                history.push(format!("Shade mode is now {}", mode_str));
+                history.push(TerminalLine::info(format!(
+                    "Shade mode is now {}",
+                    mode_str
+                )));
+                return;
+            }
+            "cleanup" => {
+                let (tab, history) = self.active_tab_mut_and_history();
+                let count = tab.model.cleanup_junctions();
+                tab.executor.status_message = format!("CLEANUP: {} junctions", count);
+                history.push(TerminalLine::info(format!(
+                    "Cleaned up {} beam junctions",
+                    count
+                )));
+                return;
+            }
+            "rooms" => {
+                self.regenerate_rooms();
+                return;
+            }
+            "calc" => {
+                self.open_calculator();
                 return;
             }
             "clear" => {
                 self.save_undo_state();
                 let (tab, history) = self.active_tab_mut_and_history();
                 tab.model.entities.clear();
+                tab.model.changes.touch_all();
                 history.clear();
-                tab.selection_manager.selected_ids.clear();
+                tab.selection_manager.clear();
                 tab.executor.cancel();
                 return;
             }
@@ -74,7 +164,9 @@ impl CadViewModel {
                     tab.pending_delete_confirmation = true;
                     tab.executor.status_message =
                         "Are you sure you want to delete? (Y/N)".to_string();
-                    history.push("Are you sure you want to delete? (Y/N)".to_string());
+                    history.push(TerminalLine::warning(
+                        "Are you sure you want to delete? (Y/N)",
+                    ));
                 } else {
                     tab.executor.status_message = "Nothing selected to delete".to_string();
                 }
@@ -83,16 +175,127 @@ impl CadViewModel {
             _ => {}
         }
 
-        // Save state before command execution
-        self.save_undo_state();
+        // Save state before command execution (once per command run)
+        self.save_command_undo_state();
 
         // Process with command executor
         let tab = self.active_tab_mut();
+        tab.executor.group = tab.selection_manager.scope;
         tab.executor.process_input(
             &input_text,
             &mut tab.model,
             &tab.selection_manager.selected_ids,
         );
+        tab.selection_manager.prune(&tab.model);
+        tab.record_consumed_selection();
+        self.collect_command_output();
+    }
+
+    /// Run what was typed in the terminal and confirmed with Enter. A
+    /// command left running hides its preview and dynamic input until the
+    /// pointer moves, so the next typed values stay in the terminal.
+    pub fn submit_command_input(&mut self) {
+        self.process_command();
+        if let Some(tab) = self.tabs.get_mut(self.active_tab_index)
+            && tab.executor.is_active()
+        {
+            tab.preview_paused = true;
+        }
+    }
+
+    /// Handle one press of Escape, cancelling the innermost pending state
+    pub fn escape(&mut self) -> EscapeStage {
+        if self.history_search.is_some() {
+            self.cancel_history_search();
+            return EscapeStage::CancelSearch;
+        }
+        if !self.command_input.is_empty() {
+            self.command_input.clear();
+            self.history_nav_index = None;
+            return EscapeStage::ClearInput;
+        }
+
+        if self.tabs.is_empty() {
+            return EscapeStage::Idle;
+        }
+        if self.cancel_calculator_pick() {
+            return EscapeStage::CancelPick;
+        }
+        // A command run while marking up is cancelled first
+        if self.markup_drawing() && self.exit_markup() {
+            return EscapeStage::ExitMarkup;
+        }
+        if self.cancel_handle_drag() {
+            return EscapeStage::AbortDrag;
+        }
+        let tab = self.active_tab_mut();
+        if let Some(id) = tab.dragging_label_id.take() {
+            if let (Some(origin), Some(last)) = (tab.drag_origin.take(), tab.drag_last_pos.take()) {
+                tab.model.move_label(id, origin - last);
+            }
+            tab.executor.status_message = "Label drag cancelled".to_string();
+            EscapeStage::AbortDrag
+        } else if tab.selection_manager.selection_rect_start.is_some() {
+            tab.selection_manager.selection_rect_start = None;
+            tab.selection_manager.selection_rect_current = None;
+            tab.executor.status_message = "Selection box cancelled".to_string();
+            EscapeStage::AbortDrag
+        } else if tab.pending_delete_confirmation {
+            tab.pending_delete_confirmation = false;
+            tab.executor.status_message = "Delete cancelled".to_string();
+            EscapeStage::CancelPrompt
+        } else if tab.executor.cancel_step() {
+            EscapeStage::CancelStep
+        } else if let Some(name) = tab.executor.active_command_name() {
+            tab.executor.cancel();
+            tab.executor.status_message = format!("{} cancelled", name);
+            EscapeStage::CancelCommand
+        } else if !tab.selection_manager.is_empty() {
+            tab.selection_manager.clear();
+            tab.executor.status_message = "Selection cleared (P to reselect)".to_string();
+            EscapeStage::ClearSelection
+        } else if self.exit_group() {
+            EscapeStage::ExitGroup
+        } else {
+            EscapeStage::Idle
+        }
+    }
+
+    // ── Terminal log ───────────────────────────────────────────
+
+    /// Move lines written by commands into the terminal log
+    pub fn collect_command_output(&mut self) {
+        for tab in &mut self.tabs {
+            self.command_history.append(&mut tab.executor.output);
+        }
+    }
+
+    /// The whole log as plain text, one line per entry
+    pub fn log_text(&self) -> String {
+        self.command_history
+            .iter()
+            .map(TerminalLine::display)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Write the log to a text file chosen by the user
+    pub fn save_log(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Text", &["txt", "log"])
+            .set_file_name("mugin_log.txt")
+            .save_file()
+        else {
+            return;
+        };
+        match std::fs::write(&path, self.log_text() + "\n") {
+            Ok(()) => self.notify(format!("Saved log to {}", path.display())),
+            Err(e) => {
+                let message = format!("Could not save log: {}", e);
+                self.command_history.push(TerminalLine::error(&message));
+                self.notify(message);
+            }
+        }
     }
 
     /// Cancel current command (right-click or Escape)
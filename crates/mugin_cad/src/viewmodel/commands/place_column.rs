@@ -126,7 +126,7 @@ impl Command for CmdPlaceColumn {
         );
         col_data.rotation = self.rotation;
 
-        ctx.model.add_entity(Entity::column(col_data));
+        ctx.place(Entity::column(col_data));
 
         PointResult::Complete
     }
@@ -0,0 +1,187 @@
+//! Double-click editing on the canvas: stepping into groups and retyping
+//! text where it is drawn.
+
+use crate::commands::InputModifiers;
+use crate::model::{Shape, Vector2};
+use crate::viewmodel::CadViewModel;
+
+/// Annotation text being retyped on the canvas
+#[derive(Debug, Clone, PartialEq)]
+pub struct CanvasTextEdit {
+    pub entity_id: u64,
+    pub text: String,
+}
+
+impl CadViewModel {
+    /// Double-click on the canvas: empty space fits the drawing, a group
+    /// is stepped into, and anything else is selected with its editor
+    /// opened, text in place and the rest in the inspector
+    pub fn handle_double_click(&mut self, pos: Vector2, modifiers: InputModifiers) {
+        if self.tabs.is_empty() || self.active_tab().executor.is_active() {
+            return;
+        }
+        let snapped = self.resolve_cursor(pos, modifiers).snapped;
+        let tolerance = self
+            .config
+            .snap_config
+            .world_pick_tolerance(self.active_tab().viewport.zoom);
+        let tab = self.active_tab();
+        let Some(id) = tab.selection_manager.pick(&tab.model, snapped, tolerance) else {
+            self.zoom_extents();
+            return;
+        };
+
+        if let Some(group) = self.enclosing_group(id) {
+            self.enter_group(group);
+            return;
+        }
+
+        let is_text = matches!(
+            self.active_tab().model.find_by_id(id).map(|e| &e.shape),
+            Some(Shape::Text(_))
+        );
+        let tab = self.active_tab_mut();
+        if !(tab.selection_manager.selected_ids.len() == 1
+            && tab.selection_manager.selected_ids.contains(&id))
+        {
+            tab.selection_manager.clear();
+            tab.selection_manager.selected_ids.insert(id);
+        }
+        tab.selection_manager.last_interacted_id = Some(id);
+        if is_text {
+            self.begin_text_edit(id);
+        } else {
+            let panels = &mut self.config.gui_config.panels;
+            panels.show_right = true;
+            panels.right_collapsed = false;
+            self.reveal_in_inspector = true;
+        }
+    }
+
+    // ── Group editing ──────────────────────────────────────────
+
+    /// The outermost group holding `id`, or `id` itself if it is one,
+    /// below the group being edited
+    pub fn enclosing_group(&self, id: u64) -> Option<u64> {
+        let tab = self.active_tab();
+        let path = tab.model.path_to(id);
+        let inside = tab
+            .selection_manager
+            .scope
+            .and_then(|scope| path.iter().position(|entity| entity.id == scope))
+            .map_or(0, |i| i + 1);
+        path[inside..]
+            .iter()
+            .find(|entity| !entity.children.is_empty())
+            .map(|group| group.id)
+    }
+
+    /// Limit picking to the children of group `id` until `exit_group`
+    pub fn enter_group(&mut self, id: u64) {
+        if self.active_tab().model.find_by_id(id).is_some() {
+            self.set_group_scope(Some(id));
+        }
+    }
+
+    /// Step out of the edited group into the one holding it, or the whole
+    /// drawing. Returns false if no group was being edited.
+    pub fn exit_group(&mut self) -> bool {
+        let tab = self.active_tab();
+        let Some(scope) = tab.selection_manager.scope else {
+            return false;
+        };
+        let path = tab.model.path_to(scope);
+        let parent = path.len().checked_sub(2).map(|i| path[i].id);
+        self.set_group_scope(parent);
+        true
+    }
+
+    /// Edit group `scope`, or the whole drawing with `None`. Clears the
+    /// selection, which may lie outside the new scope.
+    pub fn set_group_scope(&mut self, scope: Option<u64>) {
+        let tab = self.active_tab_mut();
+        let name = scope
+            .and_then(|id| tab.model.find_by_id(id))
+            .map(|group| group.name.clone());
+        tab.executor.status_message = match &name {
+            Some(name) => format!("Editing group \"{}\" (Escape to leave)", name),
+            None => "Left group editing".to_string(),
+        };
+        tab.selection_manager.scope = name.and(scope);
+        tab.selection_manager.clear();
+    }
+
+    /// Ids and names of the edited group and the groups holding it,
+    /// outermost first; empty outside group editing
+    pub fn group_path(&self) -> Vec<(u64, String)> {
+        let tab = self.active_tab();
+        tab.selection_manager
+            .scope
+            .map(|scope| {
+                tab.model
+                    .path_to(scope)
+                    .into_iter()
+                    .map(|entity| (entity.id, entity.name.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    // ── Text in place ──────────────────────────────────────────
+
+    /// Start retyping annotation `id` on the canvas. A dimension starts
+    /// from the label it shows.
+    pub fn begin_text_edit(&mut self, id: u64) {
+        let style = self.config.dimension_style.clone();
+        let tab = self.active_tab_mut();
+        let Some(Shape::Text(text)) = tab.model.find_by_id(id).map(|e| &e.shape) else {
+            return;
+        };
+        let current = if text.is_dimension() {
+            text.dimension_label(&style, text.anchor_points[0].dist(text.anchor_points[1]))
+        } else {
+            text.text.clone()
+        };
+        tab.text_edit = Some(CanvasTextEdit {
+            entity_id: id,
+            text: current,
+        });
+    }
+
+    /// Apply the retyped text as one undo step. A dimension label left
+    /// empty or typed as its measured length follows the length again.
+    pub fn commit_text_edit(&mut self) {
+        let style = self.config.dimension_style.clone();
+        let Some(edit) = self.active_tab_mut().text_edit.take() else {
+            return;
+        };
+        let tab = self.active_tab();
+        let Some(Shape::Text(current)) = tab.model.find_by_id(edit.entity_id).map(|e| &e.shape)
+        else {
+            return;
+        };
+
+        let mut text = current.clone();
+        if text.is_dimension() {
+            let measured = style.format_length(text.anchor_points[0].dist(text.anchor_points[1]));
+            let typed = edit.text.trim();
+            text.label_override =
+                (!typed.is_empty() && typed != measured).then(|| typed.to_string());
+        } else {
+            text.text = edit.text;
+        }
+        if text == *current {
+            return;
+        }
+
+        self.save_undo_state();
+        if let Some(entity) = self.active_tab_mut().model.find_by_id_mut(edit.entity_id) {
+            entity.modify(|shape| *shape = Shape::Text(text));
+        }
+    }
+
+    /// Drop the retyped text
+    pub fn cancel_text_edit(&mut self) {
+        self.active_tab_mut().text_edit = None;
+    }
+}
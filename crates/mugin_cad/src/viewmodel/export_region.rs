@@ -0,0 +1,80 @@
+//! Picking and adjusting the PDF export region on the canvas.
+
+use crate::commands::InputModifiers;
+use crate::export::settings::ExportSource;
+use crate::model::Vector2;
+use crate::view::ui::export::window::RegionPick;
+use crate::viewmodel::{CadViewModel, HANDLE_SIZE};
+
+/// Corners of the box from `min` to `max`, counter-clockwise from `min`
+pub fn region_corners(min: Vector2, max: Vector2) -> [Vector2; 4] {
+    [
+        min,
+        Vector2::new(max.x, min.y),
+        max,
+        Vector2::new(min.x, max.y),
+    ]
+}
+
+impl CadViewModel {
+    /// Start a requested region pick, and once it ends reopen the export
+    /// window with the region chosen. Called once per frame.
+    pub fn update_export_region_pick(&mut self) {
+        match self.export_window.region_pick {
+            Some(RegionPick::Requested) => {
+                let started = self.active_tab_mut().start_command("select_region");
+                self.export_window.region_pick = started.then_some(RegionPick::Picking);
+                self.export_window.open = !started;
+            }
+            Some(RegionPick::Picking) if !self.active_tab().executor.is_active() => {
+                self.export_window.region_pick = None;
+                self.export_window.open = true;
+                if let Some((min, max)) = self.active_tab().model.export_region {
+                    self.export_window.settings.source = ExportSource::Viewport(min, max);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The export region corner under `pos`, while the export window shows
+    pub fn export_region_grip_at(&self, pos: Vector2) -> Option<usize> {
+        let tab = self.active_tab();
+        if !self.export_window.open || tab.executor.is_active() {
+            return None;
+        }
+        let (min, max) = tab.model.export_region?;
+        let tolerance = HANDLE_SIZE / tab.viewport.zoom;
+        region_corners(min, max)
+            .iter()
+            .position(|c| (c.x - pos.x).abs() <= tolerance && (c.y - pos.y).abs() <= tolerance)
+    }
+
+    /// Begin dragging the export region corner under `pos`. Returns false
+    /// if there is none.
+    pub fn start_export_region_drag(&mut self, pos: Vector2) -> bool {
+        let Some(corner) = self.export_region_grip_at(pos) else {
+            return false;
+        };
+        let tab = self.active_tab_mut();
+        let Some((min, max)) = tab.model.export_region else {
+            return false;
+        };
+        tab.export_region_drag = Some(region_corners(min, max)[(corner + 2) % 4]);
+        true
+    }
+
+    /// Move the dragged corner to `pos`, keeping the opposite one fixed
+    pub fn update_export_region_drag(&mut self, pos: Vector2, modifiers: InputModifiers) {
+        let pos = self.resolve_cursor(pos, modifiers).snapped;
+        let tab = self.active_tab_mut();
+        let Some(fixed) = tab.export_region_drag else {
+            return;
+        };
+        let min = Vector2::new(fixed.x.min(pos.x), fixed.y.min(pos.y));
+        let max = Vector2::new(fixed.x.max(pos.x), fixed.y.max(pos.y));
+        tab.model.export_region = Some((min, max));
+        tab.executor.status_message =
+            format!("Export region: {:.0} x {:.0}", max.x - min.x, max.y - min.y);
+    }
+}
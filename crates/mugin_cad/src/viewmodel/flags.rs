@@ -0,0 +1,72 @@
+//! Review flags: flagging entities and selecting them again.
+
+use crate::commands::output::TerminalLine;
+use crate::model::FlagColor;
+use crate::viewmodel::CadViewModel;
+use std::collections::HashSet;
+
+impl CadViewModel {
+    /// Flag the selected entities, or clear their flags with `None`
+    pub fn flag_selection(&mut self, flag: Option<FlagColor>) {
+        let ids: Vec<u64> = self
+            .active_tab()
+            .selection_manager
+            .selected_ids
+            .iter()
+            .copied()
+            .collect();
+        self.flag_entities(&ids, flag);
+    }
+
+    /// Flag the entities `ids`, or clear their flags with `None`
+    pub fn flag_entities(&mut self, ids: &[u64], flag: Option<FlagColor>) {
+        if ids.is_empty() {
+            return;
+        }
+        self.save_undo_state();
+        let (tab, history) = self.active_tab_mut_and_history();
+        let count = tab.model.set_flag(ids.iter().copied(), flag);
+        let line = match flag {
+            Some(flag) => TerminalLine::info(format!("Flagged {} items {}", count, flag.label())),
+            None => TerminalLine::info(format!("Cleared the flags of {} items", count)),
+        };
+        tab.executor.status_message = line.text.clone();
+        history.push(line);
+    }
+
+    /// SELECTFLAG: select the entities flagged `name`, or every flagged
+    /// entity when `name` is empty or "all"
+    pub fn select_flagged(&mut self, name: &str) {
+        let name = name.trim();
+        let color = if name.is_empty() || name.eq_ignore_ascii_case("all") {
+            None
+        } else {
+            match FlagColor::parse(name) {
+                Some(color) => Some(color),
+                None => {
+                    let colors: Vec<&str> = FlagColor::ALL.iter().map(|f| f.label()).collect();
+                    let line = TerminalLine::error(format!(
+                        "Unknown flag \"{}\". Use {} or all",
+                        name,
+                        colors.join(", ")
+                    ));
+                    let (tab, history) = self.active_tab_mut_and_history();
+                    tab.executor.status_message = line.text.clone();
+                    history.push(line);
+                    return;
+                }
+            }
+        };
+        let which = color.map_or("flagged".to_string(), |c| format!("flagged {}", c.label()));
+        let (tab, history) = self.active_tab_mut_and_history();
+        let ids: HashSet<u64> = tab.model.flagged_ids(color).into_iter().collect();
+        let count = tab.selection_manager.restore(&ids, &tab.model);
+        let line = if count == 0 {
+            TerminalLine::warning(format!("No entities are {}", which))
+        } else {
+            TerminalLine::info(format!("Selected {} items {}", count, which))
+        };
+        tab.executor.status_message = line.text.clone();
+        history.push(line);
+    }
+}
@@ -0,0 +1,189 @@
+//! Scale and rotation handles on the selection's bounding box.
+
+use crate::commands::InputModifiers;
+use crate::model::{Entity, Vector2};
+use crate::viewmodel::CadViewModel;
+use crate::viewmodel::tab::ProjectTab;
+
+/// Handle size and pick tolerance, in screen pixels
+pub const HANDLE_SIZE: f32 = 6.0;
+
+/// Screen distance of the rotation handle above the box
+const ROTATE_HANDLE_OFFSET: f32 = 25.0;
+
+/// Shift snaps handle rotation to this step
+const ROTATE_SNAP: f32 = std::f32::consts::PI / 12.0;
+
+/// A grip on the selection box
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SelectionHandle {
+    /// Scales about the opposite corner or edge, given as `pivot`
+    Scale { pivot: Vector2 },
+    /// Rotates about the box center
+    Rotate { center: Vector2 },
+}
+
+/// A handle drag in progress
+pub struct HandleDrag {
+    pub handle: SelectionHandle,
+    /// Where the drag began
+    start: Vector2,
+    /// Entities before the drag, restored each update and on cancel
+    originals: Vec<Entity>,
+    /// Whole drawing before the drag, for the undo step
+    before: Vec<Entity>,
+}
+
+/// The selection's box with its handles and their world positions.
+/// `zoom` places the rotation handle a fixed screen distance above.
+pub fn selection_handles(min: Vector2, max: Vector2, zoom: f32) -> Vec<(SelectionHandle, Vector2)> {
+    let center = Vector2::new((min.x + max.x) / 2.0, (min.y + max.y) / 2.0);
+    let corners = [
+        Vector2::new(min.x, min.y),
+        Vector2::new(max.x, min.y),
+        Vector2::new(max.x, max.y),
+        Vector2::new(min.x, max.y),
+    ];
+
+    let mut handles = Vec::with_capacity(9);
+    for (i, &corner) in corners.iter().enumerate() {
+        let opposite = corners[(i + 2) % 4];
+        handles.push((SelectionHandle::Scale { pivot: opposite }, corner));
+
+        // Edge from this corner to the next, pivoting on the opposite edge
+        let next = corners[(i + 1) % 4];
+        let mid = Vector2::new((corner.x + next.x) / 2.0, (corner.y + next.y) / 2.0);
+        let opposite_mid = Vector2::new(2.0 * center.x - mid.x, 2.0 * center.y - mid.y);
+        handles.push((
+            SelectionHandle::Scale {
+                pivot: opposite_mid,
+            },
+            mid,
+        ));
+    }
+    handles.push((
+        SelectionHandle::Rotate { center },
+        Vector2::new(center.x, max.y + ROTATE_HANDLE_OFFSET / zoom),
+    ));
+    handles
+}
+
+impl ProjectTab {
+    /// World bounds of the selected entities
+    pub fn selection_bounds(&self) -> Option<(Vector2, Vector2)> {
+        self.model
+            .bounds_of(self.selection_manager.selected_ids.iter().copied())
+    }
+
+    /// The handle under `pos`, if the selection box is showing
+    pub fn handle_at(&self, pos: Vector2) -> Option<(SelectionHandle, Vector2)> {
+        if self.executor.is_active() {
+            return None;
+        }
+        let (min, max) = self.selection_bounds()?;
+        let zoom = self.viewport.zoom;
+        let tolerance = HANDLE_SIZE / zoom;
+        selection_handles(min, max, zoom)
+            .into_iter()
+            .find(|(_, at)| (at.x - pos.x).abs() <= tolerance && (at.y - pos.y).abs() <= tolerance)
+    }
+}
+
+impl CadViewModel {
+    /// Begin dragging the handle under `pos`. Returns false if there is none.
+    pub fn start_handle_drag(&mut self, pos: Vector2) -> bool {
+        let tab = self.active_tab_mut();
+        let Some((handle, at)) = tab.handle_at(pos) else {
+            return false;
+        };
+        let originals = tab
+            .model
+            .get_top_level_selected_ids(&tab.selection_manager.selected_ids)
+            .into_iter()
+            .filter_map(|id| tab.model.find_by_id(id).cloned())
+            .collect();
+        tab.handle_drag = Some(HandleDrag {
+            handle,
+            start: at,
+            originals,
+            before: tab.model.entities.clone(),
+        });
+        true
+    }
+
+    /// Reapply the dragged handle's transform for the pointer at `pos`
+    pub fn update_handle_drag(&mut self, pos: Vector2, modifiers: InputModifiers) {
+        let tab = self.active_tab_mut();
+        let Some(drag) = &tab.handle_drag else {
+            return;
+        };
+        restore(&mut tab.model, &drag.originals);
+
+        match drag.handle {
+            SelectionHandle::Scale { pivot } => {
+                // Project the pointer onto the line from pivot to handle
+                let from = drag.start - pivot;
+                let length_sq = from.x * from.x + from.y * from.y;
+                if length_sq < 1e-12 {
+                    return;
+                }
+                let to = pos - pivot;
+                let factor = ((to.x * from.x + to.y * from.y) / length_sq).max(0.01);
+                for entity in &drag.originals {
+                    if let Some(e) = tab.model.find_by_id_mut(entity.id) {
+                        e.scale(pivot, factor);
+                    }
+                }
+                tab.executor.status_message = format!("Scale: {:.3}", factor);
+            }
+            SelectionHandle::Rotate { center } => {
+                let angle_of = |p: Vector2| (p.y - center.y).atan2(p.x - center.x);
+                let mut angle = angle_of(pos) - angle_of(drag.start);
+                if modifiers.shift {
+                    angle = (angle / ROTATE_SNAP).round() * ROTATE_SNAP;
+                }
+                for entity in &drag.originals {
+                    if let Some(e) = tab.model.find_by_id_mut(entity.id) {
+                        e.rotate(center, angle);
+                    }
+                }
+                let angles = &tab.executor.dimension_style.angles;
+                tab.executor.status_message = format!("Rotate: {}", angles.format_rotation(angle));
+            }
+        }
+        tab.model.update_hierarchy();
+    }
+
+    /// Finish the handle drag as one undo step
+    pub fn end_handle_drag(&mut self) {
+        let tab = self.active_tab_mut();
+        let Some(drag) = tab.handle_drag.take() else {
+            return;
+        };
+        tab.undo_manager
+            .save_state(&drag.before, &tab.selection_manager.selected_ids);
+        tab.is_dirty = true;
+        tab.undo_run = None;
+        tab.last_nudge = None;
+    }
+
+    /// Put the dragged entities back. Returns false if no handle was dragged.
+    pub fn cancel_handle_drag(&mut self) -> bool {
+        let tab = self.active_tab_mut();
+        let Some(drag) = tab.handle_drag.take() else {
+            return false;
+        };
+        restore(&mut tab.model, &drag.originals);
+        tab.model.update_hierarchy();
+        tab.executor.status_message = "Transform cancelled".to_string();
+        true
+    }
+}
+
+fn restore(model: &mut crate::model::CadModel, originals: &[Entity]) {
+    for original in originals {
+        if let Some(entity) = model.find_by_id_mut(original.id) {
+            *entity = original.clone();
+        }
+    }
+}
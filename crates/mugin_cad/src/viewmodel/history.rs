@@ -1,19 +1,103 @@
+use crate::commands::output::TerminalLine;
+use crate::model::Entity;
+use crate::model::input_history::{HistoryScope, InputHistory, project_history_path};
 use crate::viewmodel::CadViewModel;
+use std::time::{Duration, Instant};
+
+/// Batch history writes: at most one per this interval while typing
+const HISTORY_SAVE_DELAY: Duration = Duration::from_secs(2);
+
+/// State of a Ctrl+R search through typed commands
+pub struct HistorySearch {
+    pub query: String,
+    /// Index of the shown match in `InputHistory::entries`
+    pub match_index: Option<usize>,
+    /// Input typed before the search started, restored on cancel
+    saved_input: String,
+}
 
 impl CadViewModel {
     /// Save current state for undo
     pub fn save_undo_state(&mut self) {
         let tab = self.active_tab_mut();
-        tab.undo_manager.save_state(&tab.model.entities);
+        tab.undo_manager
+            .save_state(&tab.model.entities, &tab.selection_manager.selected_ids);
+        tab.is_dirty = true;
+        // Any other edit ends the running command's undo step
+        tab.undo_run = None;
+        tab.last_nudge = None;
+    }
+
+    /// Save state before an input to the active command. All inputs of
+    /// one command run share the snapshot taken before the first, so a
+    /// LINE chain is a single undo step. Does nothing with no command.
+    pub fn save_command_undo_state(&mut self) {
+        let tab = self.active_tab_mut();
+        let Some(run) = tab.executor.active_run() else {
+            return;
+        };
+        if tab.undo_run == Some(run) {
+            tab.is_dirty = true;
+            return;
+        }
+        self.save_undo_state();
+        self.active_tab_mut().undo_run = Some(run);
+    }
+
+    /// Note an inspector change to `before.id`, which looked like `before`
+    /// until now. Changes up to `end_property_edit` make one undo step, so
+    /// a dragged value is not recorded once per frame.
+    pub fn begin_property_edit(&mut self, before: Entity) {
+        let tab = self.active_tab();
+        match &tab.property_edit {
+            Some(open) if open.id == before.id => return,
+            Some(_) => self.end_property_edit(),
+            None => {}
+        }
+        self.active_tab_mut().property_edit = Some(before);
+    }
+
+    /// Finish the inspector edit, saving an undo step if the entity ended
+    /// up different from where it started
+    pub fn end_property_edit(&mut self) {
+        let tab = self.active_tab_mut();
+        let Some(before) = tab.property_edit.take() else {
+            return;
+        };
+        let Some(current) = tab.model.find_by_id_mut(before.id) else {
+            return;
+        };
+        if current.same_content(&before) {
+            return;
+        }
+        // Snapshot with the entity put back as it was, then reapply the edit
+        let after = std::mem::replace(current, before);
+        self.save_undo_state();
+        let tab = self.active_tab_mut();
+        if let Some(current) = tab.model.find_by_id_mut(after.id) {
+            *current = after;
+        }
     }
 
     /// Perform undo
     pub fn undo(&mut self) -> bool {
+        self.end_property_edit();
         let (tab, history) = self.active_tab_mut_and_history();
-        if let Some(previous_state) = tab.undo_manager.undo(&tab.model.entities) {
-            tab.model.entities = previous_state;
-            tab.selection_manager.selected_ids.clear();
-            history.push("Undo".to_string());
+        tab.undo_run = None;
+        tab.last_nudge = None;
+        if let Some(previous_state) = tab
+            .undo_manager
+            .undo(&tab.model.entities, &tab.selection_manager.selected_ids)
+        {
+            tab.model.entities = previous_state.entities;
+            tab.model.changes.touch_all();
+            tab.is_dirty = true;
+            // Bring back the selection recorded with the snapshot, e.g. the
+            // entities an undone delete removed
+            tab.selection_manager.clear();
+            tab.selection_manager
+                .restore(&previous_state.selection, &tab.model);
+            history.push(TerminalLine::info("Undo"));
             tab.executor.status_message = "Undo".to_string();
             true
         } else {
@@ -24,11 +108,23 @@ impl CadViewModel {
 
     /// Perform redo
     pub fn redo(&mut self) -> bool {
+        self.end_property_edit();
         let (tab, history) = self.active_tab_mut_and_history();
-        if let Some(redo_state) = tab.undo_manager.redo(&tab.model.entities) {
-            tab.model.entities = redo_state;
-            tab.selection_manager.selected_ids.clear();
-            history.push("Redo".to_string());
+        tab.undo_run = None;
+        tab.last_nudge = None;
+        if let Some(redo_state) = tab
+            .undo_manager
+            .redo(&tab.model.entities, &tab.selection_manager.selected_ids)
+        {
+            tab.model.entities = redo_state.entities;
+            tab.model.changes.touch_all();
+            tab.is_dirty = true;
+            // Bring back the selection recorded with the snapshot, e.g. the
+            // entities an undone delete removed
+            tab.selection_manager.clear();
+            tab.selection_manager
+                .restore(&redo_state.selection, &tab.model);
+            history.push(TerminalLine::info("Redo"));
             tab.executor.status_message = "Redo".to_string();
             true
         } else {
@@ -37,40 +133,146 @@ impl CadViewModel {
         }
     }
 
-    /// Navigate history up (older commands)
-    pub fn history_up(&mut self) {
-        if self.command_history.is_empty() {
+    // ── Typed command history ──────────────────────────────────
+
+    /// Remember a typed command; written to disk shortly afterwards
+    pub fn record_input(&mut self, command: &str) {
+        self.input_history.push(command);
+        self.input_history_dirty.get_or_insert_with(Instant::now);
+    }
+
+    /// Load the history file matching the current scope and active tab,
+    /// writing out the previous one first. Called once per frame.
+    pub fn sync_input_history(&mut self) {
+        let wanted = match self.config.gui_config.history_scope {
+            HistoryScope::PerProject => self
+                .tabs
+                .get(self.active_tab_index)
+                .and_then(|tab| tab.file_path.as_deref())
+                .map(project_history_path)
+                .or_else(|| self.global_history_path.clone()),
+            HistoryScope::Global => self.global_history_path.clone(),
+        };
+        if wanted == self.input_history_path {
+            self.flush_input_history(false);
             return;
         }
 
-        let start_index = self.history_nav_index.unwrap_or(self.command_history.len());
+        self.flush_input_history(true);
+        self.input_history = wanted
+            .as_deref()
+            .map(InputHistory::load)
+            .unwrap_or_default();
+        self.input_history_path = wanted;
+        self.history_nav_index = None;
+        self.history_search = None;
+    }
 
-        // Find previous user command (starts with "> ")
-        for i in (0..start_index).rev() {
-            if self.command_history[i].starts_with("> ") {
-                self.history_nav_index = Some(i);
-                // remove "> " prefix
-                self.command_input = self.command_history[i][2..].to_string();
-                return;
-            }
+    /// Write the history if it changed, at most once per `HISTORY_SAVE_DELAY`
+    /// unless `force` is set
+    pub fn flush_input_history(&mut self, force: bool) {
+        let Some(changed) = self.input_history_dirty else {
+            return;
+        };
+        if !force && changed.elapsed() < HISTORY_SAVE_DELAY {
+            return;
+        }
+        self.input_history_dirty = None;
+        if let Some(path) = &self.input_history_path
+            && let Err(e) = self.input_history.save(path)
+        {
+            self.notify(format!("Could not save command history: {}", e));
+        }
+    }
+
+    /// Time left until unsaved command history is due to be written
+    pub fn history_flush_due_in(&self) -> Option<Duration> {
+        self.input_history_dirty
+            .map(|changed| HISTORY_SAVE_DELAY.saturating_sub(changed.elapsed()))
+    }
+
+    /// Navigate history up (older commands), skipping repeats of the
+    /// command already shown
+    pub fn history_up(&mut self) {
+        let entries = &self.input_history.entries;
+        let start = self.history_nav_index.unwrap_or(entries.len());
+        let current = self.command_input.trim();
+
+        if let Some(i) = (0..start).rev().find(|&i| entries[i] != current) {
+            self.history_nav_index = Some(i);
+            self.command_input = entries[i].clone();
         }
     }
 
     /// Navigate history down (newer commands)
     pub fn history_down(&mut self) {
-        if let Some(current_index) = self.history_nav_index {
-            // Find next user command
-            for i in (current_index + 1)..self.command_history.len() {
-                if self.command_history[i].starts_with("> ") {
-                    self.history_nav_index = Some(i);
-                    self.command_input = self.command_history[i][2..].to_string();
-                    return;
+        let Some(index) = self.history_nav_index else {
+            return;
+        };
+        let entries = &self.input_history.entries;
+        let current = self.command_input.trim();
+
+        match (index + 1..entries.len()).find(|&i| entries[i] != current) {
+            Some(i) => {
+                self.history_nav_index = Some(i);
+                self.command_input = entries[i].clone();
+            }
+            None => {
+                // Back at "now"
+                self.history_nav_index = None;
+                self.command_input.clear();
+            }
+        }
+    }
+
+    // ── Reverse search (Ctrl+R) ────────────────────────────────
+
+    /// Start a reverse search, or jump to the next older match if one is running
+    pub fn history_search_next(&mut self) {
+        match &mut self.history_search {
+            Some(search) => {
+                if let Some(current) = search.match_index
+                    && current > 0
+                    && let Some(older) = self.input_history.search(&search.query, current - 1)
+                {
+                    search.match_index = Some(older);
                 }
             }
+            None => {
+                self.history_search = Some(HistorySearch {
+                    query: String::new(),
+                    match_index: self.input_history.entries.len().checked_sub(1),
+                    saved_input: std::mem::take(&mut self.command_input),
+                });
+            }
+        }
+    }
+
+    /// Re-run the search after the query was edited
+    pub fn update_history_search(&mut self) {
+        if let Some(search) = &mut self.history_search {
+            search.match_index = self.input_history.search(&search.query, usize::MAX);
+        }
+    }
+
+    /// Best match for the running search
+    pub fn history_search_match(&self) -> Option<&str> {
+        let index = self.history_search.as_ref()?.match_index?;
+        self.input_history.entries.get(index).map(String::as_str)
+    }
+
+    /// Put the match into the input and end the search
+    pub fn accept_history_search(&mut self) {
+        let matched = self.history_search_match().map(str::to_string);
+        if let Some(search) = self.history_search.take() {
+            self.command_input = matched.unwrap_or(search.saved_input);
+        }
+    }
 
-            // If no newer command found, clear input (we are back at "now")
-            self.history_nav_index = None;
-            self.command_input.clear();
+    /// End the search, restoring what was typed before it started
+    pub fn cancel_history_search(&mut self) {
+        if let Some(search) = self.history_search.take() {
+            self.command_input = search.saved_input;
         }
     }
 }
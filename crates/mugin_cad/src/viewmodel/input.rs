@@ -1,36 +1,110 @@
 use crate::commands::InputModifiers;
+use crate::commands::output::TerminalLine;
 use crate::model::shapes::Geometry;
+use crate::model::shapes::line::LABEL_UNIT_PX;
+use crate::model::tools::find_replace::{FindMatch, apply_matches};
 use crate::model::{Shape, Vector2};
 use crate::viewmodel::CadViewModel;
+use std::collections::HashSet;
+
+/// Pointer shape over the canvas, telling what a click or drag will do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CanvasCursor {
+    /// Nothing under the pointer
+    Idle,
+    /// An entity a click would select
+    Pick,
+    /// A locked entity, which cannot be picked
+    Locked,
+    /// A command is waiting for a point
+    Point,
+    /// MOVE, COPY or CUT carries the selection
+    Move,
+    /// ROTATE turns the selection
+    Rotate,
+    /// A handle, label or export region is being dragged
+    Drag,
+}
 
 impl CadViewModel {
+    /// Pointer shape for the active tab, with `hovered` the entity under
+    /// the cursor as picked for highlighting
+    pub fn canvas_cursor(&self, hovered: Option<u64>) -> CanvasCursor {
+        let tab = self.active_tab();
+        if tab.handle_drag.is_some()
+            || tab.dragging_label_id.is_some()
+            || tab.export_region_drag.is_some()
+        {
+            return CanvasCursor::Drag;
+        }
+        if self.calculator_pick.is_some() || self.markup_drawing() {
+            return CanvasCursor::Point;
+        }
+        if tab.executor.is_active() {
+            return match tab.executor.active_command_name() {
+                Some("MOVE" | "COPY" | "CUT") => CanvasCursor::Move,
+                Some("ROTATE") => CanvasCursor::Rotate,
+                _ => CanvasCursor::Point,
+            };
+        }
+        if hovered.is_some() {
+            return CanvasCursor::Pick;
+        }
+        let tolerance = self
+            .config
+            .snap_config
+            .world_pick_tolerance(tab.viewport.zoom);
+        match tab.cursor {
+            Some(cursor)
+                if tab
+                    .model
+                    .locked_entity_at(cursor.snapped, tolerance)
+                    .is_some() =>
+            {
+                CanvasCursor::Locked
+            }
+            _ => CanvasCursor::Idle,
+        }
+    }
+
     /// Handle a click on the canvas (mouse down/up without drag)
     pub fn handle_click(&mut self, pos: Vector2, modifiers: InputModifiers) {
-        let effective_pos = self.get_effective_position(pos);
+        let cursor = self.resolve_cursor(pos, modifiers);
+        let effective_pos = cursor.constrained;
+        if self.calculator_pick_point(cursor.snapped) || self.markup_click(pos) {
+            return;
+        }
 
         // Check if executor is active without holding mutable borrow too long
         let is_active = self.active_tab().executor.is_active();
 
         if is_active {
-            // Save state before modifying
-            self.save_undo_state();
+            // Save state before modifying (once per command run)
+            self.save_command_undo_state();
 
             let tab = self.active_tab_mut();
+            tab.executor.group = tab.selection_manager.scope;
             tab.executor.push_point(
                 effective_pos,
                 &mut tab.model,
                 &tab.selection_manager.selected_ids,
             );
-            self.command_history.push(format!(
+            tab.selection_manager.prune(&tab.model);
+            self.command_history.push(TerminalLine::info(format!(
                 "Point: {:.2}, {:.2}",
                 effective_pos.x, effective_pos.y
-            ));
+            )));
+            self.collect_command_output();
         } else {
             // Delegate to SelectionManager
+            let tolerance = self
+                .config
+                .snap_config
+                .world_pick_tolerance(self.active_tab().viewport.zoom);
             let tab = self.active_tab_mut();
             let msg = tab.selection_manager.handle_click_selection(
-                pos,
-                5.0 / tab.viewport.zoom,
+                cursor.snapped,
+                tolerance,
                 &tab.model,
                 modifiers.shift,
                 modifiers.ctrl,
@@ -40,43 +114,54 @@ impl CadViewModel {
     }
 
     pub fn handle_drag_start(&mut self, pos: Vector2, modifiers: InputModifiers) {
+        // A calculator pick takes clicks only
+        if self.calculator_pick.is_some() {
+            return;
+        }
+        // Markup strokes follow the raw pointer, without snapping
+        if self.markup_drag_start(pos) {
+            return;
+        }
+        // Handles are picked at the raw pointer, so snaps cannot steal them
+        if self.start_export_region_drag(pos) || self.start_handle_drag(pos) {
+            return;
+        }
+        let pos = self.resolve_cursor(pos, modifiers).snapped;
+        let tolerance = self
+            .config
+            .snap_config
+            .world_pick_tolerance(self.active_tab().viewport.zoom);
+        let dim_style = self.config.dimension_style.clone();
         let tab = self.active_tab_mut();
         // Reset drag state
-        tab.dragging_label_index = None;
+        tab.dragging_label_id = None;
         tab.drag_last_pos = None;
+        tab.drag_origin = None;
 
         if !tab.executor.is_active() {
             // Check for label dragging first
-            let tolerance = 5.0 / tab.viewport.zoom;
-            // Iterate all entities to find Lines with labels
-            // We need to find index first to avoid multiple borrow issues inside loop
-            let mut label_drag_index = None;
-
-            for (i, entity) in tab.model.entities.iter().enumerate().rev() {
-                match &entity.shape {
-                    Shape::Line(line) => {
-                        if line.hit_test_label(pos, tolerance) {
-                            label_drag_index = Some(i);
-                            break;
-                        }
-                    }
-                    Shape::Text(text) => {
-                        if text.hit_test(pos, tolerance) {
-                            label_drag_index = Some(i);
-                            break;
-                        }
-                    }
-                    _ => {}
-                }
-            }
+            let unit = LABEL_UNIT_PX / tab.viewport.zoom;
+            // Iterate all entities to find Lines with labels. Track the
+            // entity by ID so the drag survives entities being added or removed.
+            let label_drag_id = tab
+                .model
+                .entities
+                .iter()
+                .rev()
+                .find(|entity| match &entity.shape {
+                    Shape::Line(line) => line.hit_test_label(pos, unit, tolerance, &dim_style),
+                    Shape::Text(text) => text.hit_test(pos, tolerance),
+                    _ => false,
+                })
+                .map(|entity| entity.id);
 
-            if let Some(i) = label_drag_index {
-                tab.dragging_label_index = Some(i);
+            if let Some(entity_id) = label_drag_id {
+                tab.dragging_label_id = Some(entity_id);
                 tab.drag_last_pos = Some(pos);
+                tab.drag_origin = Some(pos);
                 tab.executor.status_message = "Dragging label...".to_string();
 
                 // Also select the line if not selected
-                let entity_id = tab.model.entities[i].id;
                 if !tab.selection_manager.selected_ids.contains(&entity_id) {
                     if !modifiers.shift && !modifiers.ctrl {
                         tab.selection_manager.clear();
@@ -96,25 +181,23 @@ impl CadViewModel {
         }
     }
 
-    pub fn handle_drag_update(&mut self, pos: Vector2) {
+    pub fn handle_drag_update(&mut self, pos: Vector2, modifiers: InputModifiers) {
+        if self.markup_drag_update(pos) {
+            return;
+        }
+        if self.active_tab().export_region_drag.is_some() {
+            self.update_export_region_drag(pos, modifiers);
+            return;
+        }
+        if self.active_tab().handle_drag.is_some() {
+            self.update_handle_drag(pos, modifiers);
+            return;
+        }
+        let pos = self.resolve_cursor(pos, modifiers).snapped;
         let tab = self.active_tab_mut();
-        if let Some(idx) = tab.dragging_label_index {
+        if let Some(id) = tab.dragging_label_id {
             if let Some(last_pos) = tab.drag_last_pos {
-                let delta = pos - last_pos;
-
-                // Update the specific entity
-                if let Some(entity) = tab.model.entities.get_mut(idx) {
-                    match &mut entity.shape {
-                        Shape::Line(line) => {
-                            line.label_offset = line.label_offset + delta;
-                        }
-                        Shape::Text(text) => {
-                            text.position = text.position + delta;
-                        }
-                        _ => {}
-                    }
-                }
-
+                tab.model.move_label(id, pos - last_pos);
                 tab.drag_last_pos = Some(pos);
             }
         } else {
@@ -123,7 +206,14 @@ impl CadViewModel {
     }
 
     pub fn handle_drag_end(&mut self, _modifiers: InputModifiers) {
+        if self.markup_drag_end() {
+            return;
+        }
+        self.end_handle_drag();
         let tab = self.active_tab_mut();
+        tab.export_region_drag = None;
+        tab.dragging_label_id = None;
+        tab.drag_origin = None;
         if tab.selection_manager.selection_rect_start.is_some() {
             let msg = tab.selection_manager.end_selection_rect(&tab.model);
             tab.executor.status_message = msg;
@@ -139,10 +229,42 @@ impl CadViewModel {
             let (msg, count) = tab.selection_manager.delete_selected(&mut tab.model);
             tab.executor.status_message = msg;
             self.command_history
-                .push(format!("Deleted {} items", count));
+                .push(TerminalLine::info(format!("Deleted {} items", count)));
         } else {
             self.active_tab_mut().executor.status_message =
                 "Nothing selected to delete".to_string();
         }
     }
+
+    /// Apply find-and-replace matches as one undo step
+    pub fn replace_matches(&mut self, matches: &[FindMatch]) {
+        self.save_undo_state();
+        let (tab, history) = self.active_tab_mut_and_history();
+        let count = apply_matches(&mut tab.model, matches);
+        tab.executor.status_message = format!("Replaced {} occurrences", count);
+        history.push(TerminalLine::info(&tab.executor.status_message));
+    }
+
+    /// Reselect what was selected before the last clearing action
+    pub fn select_previous(&mut self) {
+        let (tab, history) = self.active_tab_mut_and_history();
+        let count = tab.selection_manager.select_previous(&tab.model);
+        tab.executor.status_message = if count > 0 {
+            format!("Selected {} items", count)
+        } else {
+            "No previous selection".to_string()
+        };
+        history.push(TerminalLine::info(&tab.executor.status_message));
+    }
+
+    /// Reselect an entry of the recent selection list
+    pub fn restore_selection(&mut self, ids: &HashSet<u64>) {
+        let tab = self.active_tab_mut();
+        let count = tab.selection_manager.restore(ids, &tab.model);
+        tab.executor.status_message = if count > 0 {
+            format!("Selected {} items", count)
+        } else {
+            "Those entities no longer exist".to_string()
+        };
+    }
 }
@@ -0,0 +1,106 @@
+//! Change journal of each tab, and comparing a tab with a project file.
+
+use crate::commands::output::TerminalLine;
+use crate::model::project::ProjectData;
+use crate::model::tools::diff::Comparison;
+use crate::viewmodel::CadViewModel;
+use std::path::Path;
+
+impl CadViewModel {
+    /// Record the active tab's changes in its journal. Edits still under
+    /// way (a handle or label drag, an inspector edit, canvas text) are
+    /// recorded once they are done.
+    pub fn record_journal(&mut self) {
+        if self.tabs.is_empty() {
+            return;
+        }
+        let tab = self.active_tab_mut();
+        let editing = tab.handle_drag.is_some()
+            || tab.dragging_label_id.is_some()
+            || tab.property_edit.is_some()
+            || tab.text_edit.is_some();
+        if !editing {
+            tab.journal.record(&tab.model);
+        }
+    }
+
+    /// Write the active tab's journal as JSON to a file chosen by the user
+    pub fn export_journal(&mut self) {
+        self.record_journal();
+        let default_name = format!("{}_journal.json", self.active_tab().name);
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("JSON", &["json"])
+            .set_file_name(&default_name)
+            .save_file()
+        else {
+            return;
+        };
+        let result = self
+            .active_tab()
+            .journal
+            .to_json()
+            .and_then(|json| std::fs::write(&path, json).map_err(|e| e.to_string()));
+        match result {
+            Ok(()) => {
+                let count = self.active_tab().journal.entries().len();
+                self.command_history.push(TerminalLine::info(format!(
+                    "Exported {} journal entries to {:?}",
+                    count, path
+                )));
+                self.notify(format!("Exported {}", path.display()));
+            }
+            Err(e) => {
+                let message = format!("Could not export the journal: {}", e);
+                self.command_history.push(TerminalLine::error(&message));
+                self.notify(message);
+            }
+        }
+    }
+
+    /// Pick a project file and compare the active tab with it
+    pub fn compare_with_file(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("MuginCAD Project", &["mugin"])
+            .pick_file()
+        else {
+            return;
+        };
+        self.compare_with_path(&path);
+    }
+
+    /// Compare the active tab with the project at `path` and open the
+    /// compare window. Returns false (after a toast) if it can't be read.
+    pub fn compare_with_path(&mut self, path: &Path) -> bool {
+        let other = match ProjectData::read(path) {
+            Ok(project) => project.into_model(),
+            Err(error) => {
+                self.command_history.push(TerminalLine::error(&error));
+                self.notify(error);
+                return false;
+            }
+        };
+        let label = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+        let tab = self.active_tab_mut();
+        tab.model.update_hierarchy();
+        let comparison = Comparison::new(label, &other, &tab.model);
+        let (added, removed, modified) = comparison.counts();
+        let line = TerminalLine::info(format!(
+            "Compared with {}: {} added, {} removed, {} modified",
+            comparison.label, added, removed, modified
+        ));
+        tab.executor.status_message = line.text.clone();
+        tab.comparison = Some(comparison);
+        self.command_history.push(line);
+        self.compare_window.open = true;
+        true
+    }
+
+    /// Drop the active tab's comparison and its overlay
+    pub fn close_comparison(&mut self) {
+        self.active_tab_mut().comparison = None;
+        self.compare_window.open = false;
+    }
+}
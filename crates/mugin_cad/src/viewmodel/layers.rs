@@ -0,0 +1,144 @@
+//! Layer template import and export.
+
+use crate::commands::output::TerminalLine;
+use crate::model::layer::{LayerConflict, LayerTemplate};
+use crate::viewmodel::CadViewModel;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// A template import waiting on the user to settle color conflicts
+pub struct PendingLayerImport {
+    pub template: LayerTemplate,
+    /// Conflicts still to decide; the first one is being asked about
+    pub conflicts: Vec<LayerConflict>,
+    /// Existing layers that take the template's color
+    pub recolor: HashSet<u64>,
+}
+
+impl CadViewModel {
+    /// Write the active tab's layer table to a template file chosen by the user
+    pub fn export_layer_template(&mut self) {
+        let Some(mut path) = rfd::FileDialog::new()
+            .add_filter("Layer Template", &["json"])
+            .set_file_name("layers.json")
+            .save_file()
+        else {
+            return;
+        };
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            path.set_extension("json");
+        }
+        let template = LayerTemplate::from_layers(&self.active_tab().model.layer_manager);
+        match template.write(&path) {
+            Ok(()) => self.command_history.push(TerminalLine::info(format!(
+                "Exported {} layers to {:?}",
+                template.layers.len(),
+                path
+            ))),
+            Err(error) => {
+                self.command_history.push(TerminalLine::error(&error));
+                self.notify(error);
+            }
+        }
+    }
+
+    /// Pick a template file and merge it into the active tab's layers,
+    /// asking about layers whose colors differ
+    pub fn import_layer_template(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Layer Template", &["json"])
+            .pick_file()
+        else {
+            return;
+        };
+        let template = match LayerTemplate::read(&path) {
+            Ok(template) => template,
+            Err(error) => {
+                self.command_history.push(TerminalLine::error(&error));
+                self.notify(error);
+                return;
+            }
+        };
+        let conflicts = self
+            .active_tab()
+            .model
+            .layer_manager
+            .template_conflicts(&template);
+        let pending = PendingLayerImport {
+            template,
+            conflicts,
+            recolor: HashSet::new(),
+        };
+        if pending.conflicts.is_empty() {
+            self.finish_layer_import(pending);
+        } else {
+            self.layer_import_prompt = Some(pending);
+        }
+    }
+
+    /// Settle the conflict being asked about, or every remaining one
+    pub fn resolve_layer_conflict(&mut self, use_template: bool, apply_to_all: bool) {
+        let Some(mut pending) = self.layer_import_prompt.take() else {
+            return;
+        };
+        let count = if apply_to_all {
+            pending.conflicts.len()
+        } else {
+            1.min(pending.conflicts.len())
+        };
+        for conflict in pending.conflicts.drain(..count) {
+            if use_template {
+                pending.recolor.insert(conflict.layer_id);
+            }
+        }
+        if pending.conflicts.is_empty() {
+            self.finish_layer_import(pending);
+        } else {
+            self.layer_import_prompt = Some(pending);
+        }
+    }
+
+    /// Drop a template import without touching the layers
+    pub fn cancel_layer_import(&mut self) {
+        self.layer_import_prompt = None;
+    }
+
+    fn finish_layer_import(&mut self, pending: PendingLayerImport) {
+        let (tab, history) = self.active_tab_mut_and_history();
+        let added = tab
+            .model
+            .layer_manager
+            .merge_template(&pending.template, &pending.recolor);
+        if added > 0 || !pending.recolor.is_empty() {
+            tab.is_dirty = true;
+        }
+        history.push(TerminalLine::info(format!(
+            "Layer template: {} added, {} recolored",
+            added,
+            pending.recolor.len()
+        )));
+    }
+
+    /// Merge the configured default template into the active tab. The tab
+    /// is new, so the template's colors win.
+    pub(super) fn apply_default_layer_template(&mut self) {
+        let path = self.config.gui_config.default_layer_template.trim();
+        if path.is_empty() {
+            return;
+        }
+        let template = match LayerTemplate::read(Path::new(path)) {
+            Ok(template) => template,
+            Err(error) => {
+                self.notify(error);
+                return;
+            }
+        };
+        let layers = &mut self.active_tab_mut().model.layer_manager;
+        let recolor = layers
+            .template_conflicts(&template)
+            .into_iter()
+            .map(|conflict| conflict.layer_id)
+            .collect();
+        layers.merge_template(&template, &recolor);
+    }
+}
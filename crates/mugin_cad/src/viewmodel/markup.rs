@@ -0,0 +1,202 @@
+//! Markup mode: canvas drags draw freehand pen and highlighter sketches
+//! or erase them, instead of selecting.
+
+use crate::commands::output::TerminalLine;
+use crate::model::Vector2;
+use crate::viewmodel::CadViewModel;
+use std::collections::HashSet;
+
+/// Screen pixels a stroke may stray from the drag that drew it
+const SIMPLIFY_PX: f32 = 1.5;
+
+/// What a drag does in markup mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MarkupTool {
+    #[default]
+    Pen,
+    Highlighter,
+    /// Deletes whole sketches under the pointer
+    Eraser,
+}
+
+impl MarkupTool {
+    pub fn label(self) -> &'static str {
+        match self {
+            MarkupTool::Pen => "Pen",
+            MarkupTool::Highlighter => "Highlighter",
+            MarkupTool::Eraser => "Eraser",
+        }
+    }
+
+    fn hint(self) -> &'static str {
+        match self {
+            MarkupTool::Pen => "Markup: drag to draw with the pen",
+            MarkupTool::Highlighter => "Markup: drag to highlight",
+            MarkupTool::Eraser => "Markup: click or drag over sketches to erase them",
+        }
+    }
+}
+
+/// Markup mode state, shared by all tabs
+#[derive(Debug, Clone, Default)]
+pub struct MarkupMode {
+    /// Canvas drags draw markup instead of selecting
+    pub active: bool,
+    pub tool: MarkupTool,
+    /// Pointer path of the stroke being drawn, in world coordinates
+    pub stroke: Vec<Vector2>,
+    /// Whether the eraser drag under way has already saved an undo step
+    erasing: bool,
+}
+
+impl CadViewModel {
+    /// Turn markup mode on or off. Turning it on ends the active command.
+    pub fn toggle_markup(&mut self) {
+        if self.markup.active {
+            self.markup.active = false;
+            self.markup.stroke.clear();
+            self.active_tab_mut().executor.status_message = "Markup off".to_string();
+        } else {
+            self.set_markup_tool(self.markup.tool);
+        }
+    }
+
+    /// Turn markup mode on with `tool`
+    pub fn set_markup_tool(&mut self, tool: MarkupTool) {
+        if !self.markup.active {
+            self.cancel_command();
+        }
+        self.markup.active = true;
+        self.markup.tool = tool;
+        self.markup.stroke.clear();
+        self.active_tab_mut().executor.status_message = tool.hint().to_string();
+    }
+
+    /// Leave markup mode, dropping a stroke being drawn. Returns false if
+    /// markup mode was off.
+    pub fn exit_markup(&mut self) -> bool {
+        if !self.markup.active {
+            return false;
+        }
+        self.toggle_markup();
+        true
+    }
+
+    /// Whether canvas drags draw markup now. A command started while
+    /// marking up takes the clicks until it ends.
+    pub fn markup_drawing(&self) -> bool {
+        self.markup.active && !self.active_tab().executor.is_active()
+    }
+
+    /// Pointer distance, in world units, a stroke is simplified within
+    fn markup_tolerance(&self) -> f32 {
+        SIMPLIFY_PX / self.active_tab().viewport.zoom
+    }
+
+    /// Start a stroke, or erasing, at `pos`. Returns false when not drawing
+    /// markup and the drag is handled as usual.
+    pub fn markup_drag_start(&mut self, pos: Vector2) -> bool {
+        if !self.markup_drawing() {
+            return false;
+        }
+        self.markup.erasing = false;
+        match self.markup.tool {
+            MarkupTool::Eraser => self.erase_markup_at(pos),
+            MarkupTool::Pen | MarkupTool::Highlighter => self.markup.stroke = vec![pos],
+        }
+        true
+    }
+
+    /// Extend the stroke, or erase, as the pointer moves on to `pos`
+    pub fn markup_drag_update(&mut self, pos: Vector2) -> bool {
+        if !self.markup_drawing() {
+            return false;
+        }
+        match self.markup.tool {
+            MarkupTool::Eraser => self.erase_markup_at(pos),
+            MarkupTool::Pen | MarkupTool::Highlighter => {
+                if !self.markup.stroke.is_empty() {
+                    self.markup.stroke.push(pos);
+                }
+            }
+        }
+        true
+    }
+
+    /// Place the stroke drawn as a sketch on the markup layer
+    pub fn markup_drag_end(&mut self) -> bool {
+        if !self.markup_drawing() {
+            return false;
+        }
+        self.markup.erasing = false;
+        let stroke = std::mem::take(&mut self.markup.stroke);
+        let tolerance = self.markup_tolerance();
+        // A stroke that never moved leaves nothing to keep
+        if !stroke.iter().any(|p| p.dist(stroke[0]) > tolerance) {
+            return true;
+        }
+        let highlighter = self.markup.tool == MarkupTool::Highlighter;
+        self.save_undo_state();
+        self.active_tab_mut()
+            .model
+            .add_sketch(&stroke, tolerance, highlighter);
+        true
+    }
+
+    /// A click in markup mode: the eraser removes the sketch under it, pens
+    /// need a drag. Returns false when not drawing markup.
+    pub fn markup_click(&mut self, pos: Vector2) -> bool {
+        if !self.markup_drawing() {
+            return false;
+        }
+        self.markup.erasing = false;
+        if self.markup.tool == MarkupTool::Eraser {
+            self.erase_markup_at(pos);
+        } else {
+            self.active_tab_mut().executor.status_message = self.markup.tool.hint().to_string();
+        }
+        true
+    }
+
+    /// Delete the sketch under `pos`, if any. One eraser drag is one undo
+    /// step however many sketches it removes.
+    fn erase_markup_at(&mut self, pos: Vector2) {
+        let tolerance = self
+            .config
+            .snap_config
+            .world_pick_tolerance(self.active_tab().viewport.zoom);
+        let Some(id) = self.active_tab().model.sketch_at(pos, tolerance) else {
+            return;
+        };
+        if !self.markup.erasing {
+            self.save_undo_state();
+            self.markup.erasing = true;
+        }
+        let tab = self.active_tab_mut();
+        tab.model.remove_entities_by_ids(&HashSet::from([id]));
+        tab.selection_manager.prune(&tab.model);
+        tab.executor.status_message = "Sketch erased".to_string();
+    }
+
+    /// Remove one sketch, from the Markup panel
+    pub fn remove_sketch(&mut self, id: u64) {
+        self.save_undo_state();
+        let tab = self.active_tab_mut();
+        tab.model.remove_entities_by_ids(&HashSet::from([id]));
+        tab.selection_manager.prune(&tab.model);
+    }
+
+    /// Remove every sketch in the drawing as one undo step
+    pub fn clear_markup(&mut self) {
+        if self.active_tab().model.sketches().is_empty() {
+            return;
+        }
+        self.save_undo_state();
+        let (tab, history) = self.active_tab_mut_and_history();
+        let removed = tab.model.clear_sketches();
+        tab.selection_manager.prune(&tab.model);
+        let line = TerminalLine::info(format!("Cleared {} sketches", removed));
+        tab.executor.status_message = line.text.clone();
+        history.push(line);
+    }
+}
@@ -0,0 +1,79 @@
+//! Clearance readout between two selected entities.
+
+use crate::commands::output::TerminalLine;
+use crate::model::math::closest::closest_between;
+use crate::model::shapes::annotation::TextAnnotation;
+use crate::model::{Entity, Vector2};
+use crate::viewmodel::CadViewModel;
+use crate::viewmodel::tab::ProjectTab;
+
+/// Distances between the two selected entities
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PairDistance {
+    /// Closest points on the two outlines
+    pub closest: (Vector2, Vector2),
+    /// Bounding box centers
+    pub centers: (Vector2, Vector2),
+}
+
+impl PairDistance {
+    /// Minimum distance between the outlines
+    pub fn gap(&self) -> f32 {
+        self.closest.0.dist(self.closest.1)
+    }
+
+    pub fn center_distance(&self) -> f32 {
+        self.centers.0.dist(self.centers.1)
+    }
+
+    /// X and Y components of the gap
+    pub fn delta(&self) -> Vector2 {
+        self.closest.1 - self.closest.0
+    }
+}
+
+impl ProjectTab {
+    /// Distances between the selected entities when exactly two are selected
+    pub fn selected_pair_distance(&self) -> Option<PairDistance> {
+        let ids = &self.selection_manager.selected_ids;
+        if ids.len() != 2 {
+            return None;
+        }
+        let mut entities = ids.iter().filter_map(|&id| self.model.find_by_id(id));
+        let (a, b) = (entities.next()?, entities.next()?);
+        let center = |entity: &Entity| {
+            let (min, max) = entity.bounding_box();
+            (min + max) * 0.5
+        };
+        Some(PairDistance {
+            closest: closest_between(a, b)?,
+            centers: (center(a), center(b)),
+        })
+    }
+}
+
+impl CadViewModel {
+    /// Drop a distance annotation for the gap between the two selected
+    /// entities, or between their centers when they touch
+    pub fn annotate_pair_distance(&mut self) {
+        let Some(pair) = self.active_tab().selected_pair_distance() else {
+            return;
+        };
+        let (start, end) = if pair.gap() > 1e-4 {
+            pair.closest
+        } else {
+            pair.centers
+        };
+
+        self.save_undo_state();
+        let (tab, history) = self.active_tab_mut_and_history();
+        let mut entity = Entity::text(TextAnnotation::new_distance(start, end));
+        entity.layer_id = tab.model.layer_manager.active_layer_id;
+        entity.color = tab.executor.defaults.color;
+        tab.model.add_entity(entity);
+        history.push(TerminalLine::result(format!(
+            "Distance: {:.2}",
+            start.dist(end)
+        )));
+    }
+}
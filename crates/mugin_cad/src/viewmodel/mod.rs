@@ -14,24 +14,84 @@
 //! 2. Processing user inputs and executing commands.
 //! 3. Converting model data into a format suitable for rendering (though direct rendering is handled in `view`).
 
+mod actions;
+mod audit;
+mod background;
+mod calculator;
 mod commands;
+mod editing;
+mod export_region;
+mod flags;
+mod handles;
 mod history;
 // mod index_helper;
 mod input;
+mod journal;
+mod layers;
+mod markup;
+mod measure;
+mod navigation;
 mod project;
+mod recovery;
+mod references;
+mod rooms;
+mod section;
 mod selection;
+mod selection_sets;
 mod snap;
+mod storeys;
+mod structure_library;
+mod symbols;
 pub mod tab;
+mod transform;
 
+pub use self::actions::{
+    ACTIONS, ActionCategory, ActionMatch, ActionSpec, AppAction, SettingsPage, find_action,
+};
+pub use self::background::BackgroundJob;
+pub use self::calculator::CalculatorPick;
+pub use self::commands::EscapeStage;
+pub use self::editing::CanvasTextEdit;
+pub use self::export_region::region_corners;
+pub use self::handles::{HANDLE_SIZE, SelectionHandle, selection_handles};
+pub use self::history::HistorySearch;
+pub use self::input::CanvasCursor;
+pub use self::layers::PendingLayerImport;
+pub use self::markup::{MarkupMode, MarkupTool};
+pub use self::snap::ResolvedCursor;
+pub use self::structure_library::{LibraryTransfer, PendingLibraryMerge};
 use self::tab::ProjectTab;
+pub use self::transform::QuickTransform;
 use crate::commands::InputModifiers;
+use crate::commands::output::TerminalLine;
+use crate::model::color_mode::ColorMode;
 use crate::model::config::AppConfig;
+use crate::model::input_history::InputHistory;
+use crate::model::recent::RecentProjects;
+use crate::model::recovery::CrashFile;
+use crate::model::system::symbols::SymbolLibrary;
+use crate::model::template::{TemplateEntry, available_templates};
+use crate::model::tools::calculator::Calculator;
 use crate::model::{Entity, Vector2};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Instant;
 
+pub use crate::model::config::LeftPanelTab;
+
+/// Short-lived notification shown over the canvas
+pub struct Toast {
+    pub message: String,
+    /// Time it first appeared (set by the view)
+    pub shown_at: Option<f64>,
+}
+
+/// Answer to the unsaved-changes prompt when closing a tab
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum LeftPanelTab {
-    Hierarchy,
-    Layers,
+pub enum CloseChoice {
+    Save,
+    Discard,
+    Cancel,
 }
 
 pub struct PendingLayerChange {
@@ -54,23 +114,85 @@ pub struct CadViewModel {
 
     // Global State
     pub command_input: String,
-    pub command_history: Vec<String>,
+    /// Terminal log
+    pub command_history: Vec<TerminalLine>,
+    /// Position in `input_history` while browsing with the arrow keys
     pub history_nav_index: Option<usize>,
+    /// Typed commands, persisted across restarts
+    pub input_history: InputHistory,
+    /// File `input_history` was loaded from and is saved to
+    pub input_history_path: Option<PathBuf>,
+    /// Location of the shared history file, set by the app at startup
+    pub global_history_path: Option<PathBuf>,
+    /// When the history first changed since it was last written
+    pub input_history_dirty: Option<Instant>,
+    /// Ctrl+R reverse search in the terminal
+    pub history_search: Option<HistorySearch>,
     pub config: AppConfig,
     pub show_settings_window: bool,
+    /// Settings section to open and scroll to next frame
+    pub settings_page: Option<SettingsPage>,
     pub tab_renaming_index: Option<usize>,
+    /// IDs of dirty tabs waiting on the unsaved-changes prompt
+    pub pending_tab_close: Vec<u64>,
+    /// Tab to close when its in-flight save finishes
+    pub close_after_save: Option<u64>,
+    /// Save, load or export running on a worker thread
+    pub background: Option<BackgroundJob>,
     pub hierarchy_renaming: bool,
+    /// Hierarchy shows only flagged entities and the groups holding them
+    pub hierarchy_flagged_only: bool,
     pub inspector_renaming: bool,
+    /// Scroll the inspector to the selected entity's properties next frame
+    pub reveal_in_inspector: bool,
     pub materials_manager_open: bool,
     pub column_manager_open: bool,
     pub beam_manager_open: bool,
+    pub storey_manager_open: bool,
+    /// Temporary recoloring of the canvas
+    pub color_mode: ColorMode,
+    /// Tint entities changed since the last save
+    pub highlight_unsaved: bool,
     pub active_column_type_id: Option<u64>,
     pub active_beam_type_id: Option<u64>,
     pub layer_change_prompt: Option<PendingLayerChange>,
+    pub layer_import_prompt: Option<PendingLayerImport>,
+    pub library_merge_prompt: Option<PendingLibraryMerge>,
     #[allow(dead_code)]
     pub clipboard: Clipboard,
     pub export_window: crate::view::ui::export::window::ExportWindow,
-    pub active_left_panel_tab: LeftPanelTab,
+    pub batch_export_window: crate::view::ui::export::batch_window::BatchExportWindow,
+    pub data_export_window: crate::view::ui::export::data_window::DataExportWindow,
+    pub find_replace_window: crate::view::ui::find_replace::FindReplaceWindow,
+    pub rooms_report_window: crate::view::ui::rooms_report::RoomsReportWindow,
+    pub calculator_window: crate::view::ui::calculator::CalculatorWindow,
+    pub markup_window: crate::view::ui::markup::MarkupWindow,
+    pub compare_window: crate::view::ui::compare::CompareWindow,
+    pub command_palette: crate::view::ui::command_palette::CommandPalette,
+    /// Calculator palette state, kept across tabs
+    pub calculator: Calculator,
+    /// Value the calculator is picking from the canvas
+    pub calculator_pick: Option<CalculatorPick>,
+    /// Markup mode and the stroke being drawn
+    pub markup: MarkupMode,
+    /// Show the welcome screen in place of the canvas
+    pub show_welcome: bool,
+    pub recent_projects: RecentProjects,
+    /// Directory saved drawing templates live in, set by the app at startup
+    pub templates_dir: Option<PathBuf>,
+    /// Templates offered on the welcome screen and in the Project menu
+    pub templates: Vec<TemplateEntry>,
+    /// Shared structure library file, set by the app at startup
+    pub structure_library_path: Option<PathBuf>,
+    /// Symbols offered by the palette
+    pub symbols: Arc<SymbolLibrary>,
+    /// File imported symbols are kept in, set by the app at startup
+    pub symbol_library_path: Option<PathBuf>,
+    /// Directory tabs are saved to when the app crashes, set by the app at startup
+    pub recovery_dir: Option<PathBuf>,
+    /// Tabs saved by a crash, offered for reopening at startup
+    pub crash_files: Vec<CrashFile>,
+    pub toasts: Vec<Toast>,
 }
 
 impl CadViewModel {
@@ -83,20 +205,56 @@ impl CadViewModel {
             command_input: String::new(),
             command_history: Vec::new(),
             history_nav_index: None,
+            input_history: InputHistory::default(),
+            input_history_path: None,
+            global_history_path: None,
+            input_history_dirty: None,
+            history_search: None,
             config: AppConfig::default(),
             show_settings_window: false,
+            settings_page: None,
             tab_renaming_index: None,
+            pending_tab_close: Vec::new(),
+            close_after_save: None,
+            background: None,
             hierarchy_renaming: false,
+            hierarchy_flagged_only: false,
             inspector_renaming: false,
+            reveal_in_inspector: false,
             materials_manager_open: false,
             column_manager_open: false,
             beam_manager_open: false,
+            storey_manager_open: false,
+            color_mode: ColorMode::Normal,
+            highlight_unsaved: false,
             active_column_type_id: None,
             active_beam_type_id: None,
             layer_change_prompt: None,
+            layer_import_prompt: None,
+            library_merge_prompt: None,
             clipboard: Clipboard::default(),
             export_window: crate::view::ui::export::window::ExportWindow::default(),
-            active_left_panel_tab: LeftPanelTab::Hierarchy,
+            batch_export_window: Default::default(),
+            data_export_window: Default::default(),
+            find_replace_window: Default::default(),
+            rooms_report_window: Default::default(),
+            calculator_window: Default::default(),
+            markup_window: Default::default(),
+            compare_window: Default::default(),
+            command_palette: Default::default(),
+            calculator: Calculator::default(),
+            calculator_pick: None,
+            markup: MarkupMode::default(),
+            show_welcome: true,
+            recent_projects: RecentProjects::default(),
+            templates_dir: None,
+            templates: available_templates(None),
+            structure_library_path: None,
+            symbols: Arc::default(),
+            symbol_library_path: None,
+            recovery_dir: None,
+            crash_files: Vec::new(),
+            toasts: Vec::new(),
         }
     }
 
@@ -111,7 +269,30 @@ impl CadViewModel {
     pub fn new_tab(&mut self) {
         let name = format!("Untitled {}", self.tabs.len() + 1);
         self.tabs.push(ProjectTab::new(name));
-        self.active_tab_index = self.tabs.len() - 1;
+        self.switch_tab(self.tabs.len() - 1);
+        self.apply_default_layer_template();
+        self.seed_from_structure_library();
+        self.show_welcome = false;
+    }
+
+    /// Make another tab active. Each tab keeps its own command state; the
+    /// one left behind is suspended and the new one resumed.
+    pub fn switch_tab(&mut self, index: usize) {
+        if index >= self.tabs.len() || index == self.active_tab_index {
+            return;
+        }
+        if self.active_tab_index < self.tabs.len() {
+            self.end_property_edit();
+        }
+        if let Some(tab) = self.tabs.get_mut(self.active_tab_index) {
+            tab.suspend();
+        }
+        // A half-picked distance belongs to the drawing left behind
+        self.calculator_pick = None;
+        self.markup.stroke.clear();
+        self.active_tab_index = index;
+        self.tabs[index].resume();
+        self.refresh_tab_references();
     }
 
     pub fn close_tab(&mut self, index: usize) {
@@ -121,7 +302,9 @@ impl CadViewModel {
             self.tabs.remove(index);
             self.tabs.push(ProjectTab::new("Untitled".to_string()));
             self.active_tab_index = 0;
+            self.show_welcome = true;
         } else {
+            let closing_active = index == self.active_tab_index;
             self.tabs.remove(index);
             if self.active_tab_index >= self.tabs.len() {
                 self.active_tab_index = self.tabs.len() - 1;
@@ -129,7 +312,118 @@ impl CadViewModel {
                 // If we closed a tab before the active one, shift index
                 self.active_tab_index -= 1;
             }
+            if closing_active {
+                self.tabs[self.active_tab_index].resume();
+            }
+        }
+    }
+
+    /// Close tabs, asking about unsaved changes first. Clean tabs close
+    /// immediately; dirty ones are queued for the prompt.
+    pub fn request_close_tabs(&mut self, indices: Vec<usize>) {
+        let ids: Vec<(u64, bool)> = indices
+            .iter()
+            .filter_map(|&i| self.tabs.get(i))
+            .map(|tab| (tab.id, tab.is_dirty))
+            .collect();
+
+        self.pending_tab_close.clear();
+        for (id, is_dirty) in ids {
+            if is_dirty {
+                self.pending_tab_close.push(id);
+            } else if let Some(index) = self.tab_index(id) {
+                self.close_tab(index);
+            }
+        }
+    }
+
+    /// Answer the unsaved-changes prompt for the first queued tab
+    pub fn resolve_pending_close(&mut self, choice: CloseChoice) {
+        let Some(&id) = self.pending_tab_close.first() else {
+            return;
+        };
+        let Some(index) = self.tab_index(id) else {
+            self.pending_tab_close.remove(0);
+            return;
+        };
+
+        match choice {
+            CloseChoice::Save => {
+                // The tab closes once the background save reports success
+                self.switch_tab(index);
+                self.save_project();
+                if self.background.is_some() {
+                    self.close_after_save = Some(id);
+                } else {
+                    // Save dialog was cancelled
+                    self.pending_tab_close.clear();
+                }
+            }
+            CloseChoice::Discard => {
+                self.close_tab(index);
+                self.pending_tab_close.remove(0);
+            }
+            CloseChoice::Cancel => self.pending_tab_close.clear(),
+        }
+    }
+
+    /// Current index of the tab with the given ID
+    pub fn tab_index(&self, id: u64) -> Option<usize> {
+        self.tabs.iter().position(|tab| tab.id == id)
+    }
+
+    /// Insert a copy of the tab right after it and switch to the copy
+    pub fn duplicate_tab(&mut self, index: usize) {
+        let name = format!("{} (copy)", self.tabs[index].name);
+        let copy = self.tabs[index].duplicate(name);
+        self.tabs.insert(index + 1, copy);
+        if index < self.active_tab_index {
+            self.active_tab_index += 1;
+        }
+        self.switch_tab(index + 1);
+    }
+
+    /// Move a tab to a new position, keeping the same tab active
+    pub fn move_tab(&mut self, from: usize, to: usize) {
+        if from == to || from >= self.tabs.len() || to >= self.tabs.len() {
+            return;
         }
+
+        let tab = self.tabs.remove(from);
+        self.tabs.insert(to, tab);
+
+        let active = self.active_tab_index;
+        self.active_tab_index = if active == from {
+            to
+        } else if from < active && to >= active {
+            active - 1
+        } else if from > active && to <= active {
+            active + 1
+        } else {
+            active
+        };
+    }
+
+    /// Switch to the next tab (or previous with `forward == false`), wrapping around
+    pub fn cycle_tab(&mut self, forward: bool) {
+        let count = self.tabs.len();
+        if count == 0 {
+            return;
+        }
+        let index = if forward {
+            (self.active_tab_index + 1) % count
+        } else {
+            (self.active_tab_index + count - 1) % count
+        };
+        self.switch_tab(index);
+    }
+
+    /// Queue a toast notification
+    pub fn notify(&mut self, message: impl Into<String>) {
+        self.toasts.push(Toast {
+            message: message.into(),
+            shown_at: None,
+        });
     }
 
     /// Get status message from active executor
@@ -138,7 +432,7 @@ impl CadViewModel {
     }
 
     /// Get active tab and history mutably simultaneously (to satisfy borrow checker)
-    pub fn active_tab_mut_and_history(&mut self) -> (&mut ProjectTab, &mut Vec<String>) {
+    pub fn active_tab_mut_and_history(&mut self) -> (&mut ProjectTab, &mut Vec<TerminalLine>) {
         (
             &mut self.tabs[self.active_tab_index],
             &mut self.command_history,
@@ -0,0 +1,25 @@
+use crate::viewmodel::CadViewModel;
+
+impl CadViewModel {
+    /// Fit the whole drawing into the canvas (ZE command, middle double-click).
+    /// Before the canvas is first laid out, the fit waits for its size.
+    pub fn zoom_extents(&mut self) {
+        let tab = self.active_tab_mut();
+        if tab.viewport.screen_size.x <= 0.0 || tab.viewport.screen_size.y <= 0.0 {
+            tab.fit_pending = true;
+            return;
+        }
+        tab.fit_pending = false;
+        let (min, max) = tab.model.bounds();
+        tab.viewport.zoom_to_bounds(min, max);
+        tab.executor.status_message = "Zoom extents".to_string();
+    }
+
+    /// Per-frame viewport animation. Returns true while a repaint is needed.
+    pub fn tick(&mut self, dt: f32) -> bool {
+        if self.tabs.is_empty() {
+            return false;
+        }
+        self.active_tab_mut().viewport.tick(dt)
+    }
+}
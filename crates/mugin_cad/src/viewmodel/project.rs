@@ -1,12 +1,29 @@
+use crate::commands::output::TerminalLine;
+use crate::export::attributes::attributes_csv;
+use crate::export::data::{DataExportOptions, export_data};
+use crate::model::backup::Backup;
+use crate::model::project::{ProjectData, ViewState};
+use crate::model::shapes::image::decode_png;
+use crate::model::template::{
+    DrawingTemplate, TEMPLATE_EXTENSION, TemplateEntry, available_templates,
+};
+use crate::model::tools::journal::Journal;
+use crate::model::tools::saved::SavedRevisions;
 use crate::model::undo::UndoManager;
+use crate::model::{Entity, ImageRef, Vector2};
 use crate::viewmodel::CadViewModel;
+use crate::viewmodel::selection::SelectionManager;
+use std::path::{Path, PathBuf};
 
 impl CadViewModel {
-    /// Save project to a file
+    /// Save project to a file. The file is written on a worker thread.
     pub fn save_project(&mut self) {
+        if self.is_busy() {
+            return;
+        }
+
         let tab_idx = self.active_tab_index;
         let default_name = format!("{}.mugin", self.tabs[tab_idx].name);
-        let tab = &mut self.tabs[tab_idx];
 
         if let Some(mut path) = rfd::FileDialog::new()
             .add_filter("MuginCAD Project", &["mugin"])
@@ -18,74 +35,356 @@ impl CadViewModel {
                 path.set_extension("mugin");
             }
 
-            // We need to access self.config. self.tabs is borrowed by 'tab'.
-            // self.config is disjoint, so this is valid.
-            let project_data = crate::model::project::ProjectData::new(
-                tab.model.entities.clone(),
-                tab.model.axis_manager.axes.clone(),
-                self.config.clone(),
-                tab.model.definitions.clone(),
-            );
-
-            if let Ok(json) = serde_json::to_string_pretty(&project_data) {
-                if std::fs::write(&path, json).is_ok() {
-                    tab.file_path = Some(path.clone());
-                    if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
-                        tab.name = name.to_string();
-                    }
-                    tab.is_dirty = false;
-                    self.command_history
-                        .push(format!("Saved project to {:?}", path));
-                }
+            self.save_project_to(path);
+        }
+    }
+
+    /// Save the active tab to `path`. The file is written on a worker thread.
+    pub fn save_project_to(&mut self, path: PathBuf) {
+        if self.is_busy() {
+            return;
+        }
+        // Snapshot everything the worker needs; the model keeps changing
+        let tab_idx = self.active_tab_index;
+        let project_data = self.project_data(tab_idx);
+        self.start_save(self.tabs[tab_idx].id, project_data, path);
+    }
+
+    /// Everything saved for the tab at `tab_idx`
+    pub(super) fn project_data(&self, tab_idx: usize) -> ProjectData {
+        let tab = &self.tabs[tab_idx];
+        let mut project_data = ProjectData::new(
+            tab.model.entities.clone(),
+            tab.model.axis_manager.axes.clone(),
+            self.config.clone(),
+            tab.model.definitions.clone(),
+            tab.executor.defaults.clone(),
+        );
+        project_data.layers = tab.model.layer_manager.clone();
+        project_data.storeys = tab.model.storeys.clone();
+        project_data.selection_sets = tab.selection_sets.clone();
+        project_data.view = Some(ViewState {
+            offset: tab.viewport.offset,
+            zoom: tab.viewport.zoom,
+            left_tab: self.config.gui_config.panels.left_tab,
+            active_layer: tab.model.layer_manager.active_layer_id,
+        });
+        project_data
+    }
+
+    /// Write the active tab's entity attributes to a CSV file chosen by the user
+    pub fn export_attributes(&mut self) {
+        let default_name = format!("{}_attributes.csv", self.active_tab().name);
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("CSV", &["csv"])
+            .set_file_name(&default_name)
+            .save_file()
+        else {
+            return;
+        };
+        let csv = attributes_csv(&self.active_tab().model);
+        match std::fs::write(&path, csv) {
+            Ok(()) => {
+                self.command_history.push(TerminalLine::info(format!(
+                    "Exported attributes to {:?}",
+                    path
+                )));
+                self.notify(format!("Exported {}", path.display()));
+            }
+            Err(e) => {
+                let message = format!("Could not export attributes: {}", e);
+                self.command_history.push(TerminalLine::error(&message));
+                self.notify(message);
             }
         }
     }
 
-    /// Load project from a file
+    /// Write the active tab's entity geometry to `path` as CSV or JSON
+    pub fn export_data(&mut self, options: &DataExportOptions, path: PathBuf) {
+        let tab = self.active_tab();
+        let data = export_data(&tab.model, &tab.selection_manager.selected_ids, options);
+        match std::fs::write(&path, data) {
+            Ok(()) => {
+                self.command_history
+                    .push(TerminalLine::info(format!("Exported data to {:?}", path)));
+                self.notify(format!("Exported {}", path.display()));
+            }
+            Err(e) => {
+                let message = format!("Could not export data: {}", e);
+                self.command_history.push(TerminalLine::error(&message));
+                self.notify(message);
+            }
+        }
+    }
+
+    /// Pick a PNG and place it, centered in the view, as a tracing underlay
+    pub fn insert_image(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("PNG Image", &["png"])
+            .pick_file()
+        else {
+            return;
+        };
+        let size = std::fs::read(&path)
+            .ok()
+            .and_then(|bytes| decode_png(&bytes))
+            .map(|(width, height, _)| [width as u32, height as u32]);
+        let Some(pixel_size) = size else {
+            let message = format!("Could not read {} as a PNG image", path.display());
+            self.command_history.push(TerminalLine::error(&message));
+            self.notify(message);
+            return;
+        };
+
+        self.save_undo_state();
+        let tab = self.active_tab_mut();
+        let origin = Vector2::new(0.0, 0.0);
+        let center = tab.viewport.screen_to_cad(origin, origin);
+        let mut image = ImageRef::new(path.to_string_lossy().into_owned(), center, pixel_size);
+        image.position = center - image.size() * 0.5;
+
+        let mut entity = Entity::image(image);
+        entity.layer_id = tab.model.layer_manager.active_layer_id;
+        let id = entity.id;
+        tab.model.add_entity(entity);
+        tab.selection_manager.selected_ids.clear();
+        tab.selection_manager.selected_ids.insert(id);
+        self.command_history.push(TerminalLine::info(format!(
+            "Inserted image {:?}. Use CALIBRATE to set its scale.",
+            path
+        )));
+    }
+
+    /// Pick a project file and load it on a worker thread
     pub fn load_project(&mut self) {
+        if self.is_busy() {
+            return;
+        }
         if let Some(path) = rfd::FileDialog::new()
             .add_filter("MuginCAD Project", &["mugin"])
             .pick_file()
         {
-            if let Ok(content) = std::fs::read_to_string(&path) {
-                if let Ok(project_data) =
-                    serde_json::from_str::<crate::model::project::ProjectData>(&content)
-                {
-                    // If current tab is active, we check its state
-                    let should_new_tab = {
-                        let tab = &self.tabs[self.active_tab_index];
-                        !tab.model.entities.is_empty() || tab.is_dirty || tab.file_path.is_some()
-                    };
-
-                    if should_new_tab {
-                        self.new_tab();
-                    }
-
-                    // Re-borrow active tab
-                    let tab_idx = self.active_tab_index;
-                    let tab = &mut self.tabs[tab_idx];
-
-                    tab.model.entities = project_data.entities;
-                    tab.model.axis_manager.axes = project_data.axes;
-                    tab.model.definitions = project_data.definitions;
-                    self.config = project_data.config;
-
-                    // Reset transient state
-                    tab.undo_manager = UndoManager::new(50);
-                    tab.selection_manager.selected_ids.clear();
-                    tab.current_snap = None;
-                    tab.executor.cancel();
-
-                    tab.file_path = Some(path.clone());
-                    if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
-                        tab.name = name.to_string();
-                    }
-                    tab.is_dirty = false;
-
-                    self.command_history
-                        .push(format!("Loaded project from {:?}", path));
-                }
+            self.start_load(path);
+        }
+    }
+
+    /// Open a project file right away (used for files given at startup).
+    /// Returns `false` (after a toast) if the file could not be read.
+    pub fn open_project(&mut self, path: &Path) -> bool {
+        match ProjectData::read(path) {
+            Ok(project_data) => {
+                self.apply_project(path, project_data);
+                true
+            }
+            Err(error) => {
+                self.notify(error);
+                false
             }
         }
     }
+
+    /// Put a loaded project in a tab (reusing the active tab if it is blank)
+    pub(super) fn apply_project(&mut self, path: &Path, project_data: ProjectData) {
+        self.fill_tab(project_data);
+
+        let tab = self.active_tab_mut();
+        tab.file_path = Some(path.to_path_buf());
+        if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+            tab.name = name.to_string();
+        }
+        tab.is_dirty = false;
+
+        self.recent_projects.touch(path);
+        self.command_history.push(TerminalLine::info(format!(
+            "Loaded project from {:?}",
+            path
+        )));
+    }
+
+    /// Open a backup of the active tab's file in a tab of its own. The
+    /// copy is untitled, so saving it never overwrites the backup.
+    pub fn open_backup(&mut self, backup: &Backup) {
+        let name = self.active_tab().name.clone();
+        let project_data = match ProjectData::read(&backup.path) {
+            Ok(project_data) => project_data,
+            Err(error) => {
+                self.notify(error);
+                return;
+            }
+        };
+        self.fill_tab(project_data);
+
+        let tab = self.active_tab_mut();
+        tab.name = format!("{} (backup {})", name, backup.index);
+        tab.is_dirty = true;
+        self.command_history.push(TerminalLine::info(format!(
+            "Opened backup {:?}",
+            backup.path
+        )));
+    }
+
+    /// Replace the drawing in the active tab, or a new one if it is not
+    /// blank, and reset its transient state
+    pub(super) fn fill_tab(&mut self, project_data: ProjectData) {
+        if !self.active_tab_is_blank() {
+            self.new_tab();
+        }
+
+        // Re-borrow active tab
+        let tab_idx = self.active_tab_index;
+        let tab = &mut self.tabs[tab_idx];
+
+        tab.model.entities = project_data.entities;
+        tab.model.changes.touch_all();
+        tab.saved_revisions = SavedRevisions::capture(&tab.model.entities);
+        tab.model.axis_manager.axes = project_data.axes;
+        tab.model.layer_manager = project_data.layers;
+        tab.model.definitions = project_data.definitions;
+        tab.model.storeys = project_data.storeys;
+        tab.executor.defaults = project_data.drafting;
+        // Panel layout and how projects open belong to the user, not the project
+        let panels = std::mem::take(&mut self.config.gui_config.panels);
+        let zoom_extents_on_open = self.config.gui_config.zoom_extents_on_open;
+        self.config = project_data.config;
+        self.config.gui_config.panels = panels;
+        self.config.gui_config.zoom_extents_on_open = zoom_extents_on_open;
+
+        // Reset transient state
+        tab.undo_manager = UndoManager::new(50);
+        tab.selection_manager = SelectionManager::new();
+        tab.selection_sets = project_data.selection_sets;
+        tab.current_snap = None;
+        tab.cursor = None;
+        tab.executor.cancel();
+        tab.journal = Journal::new(&tab.model);
+        tab.comparison = None;
+        self.show_welcome = false;
+        self.restore_view(project_data.view);
+    }
+
+    /// Put the active tab back where its project was saved. Files saved
+    /// without a view, or with the preference set, open fitted instead.
+    fn restore_view(&mut self, view: Option<ViewState>) {
+        let fit = self.config.gui_config.zoom_extents_on_open;
+        let Some(view) = view else {
+            self.zoom_extents();
+            return;
+        };
+        self.config.gui_config.panels.left_tab = view.left_tab;
+        let tab = self.active_tab_mut();
+        if tab
+            .model
+            .layer_manager
+            .get_layer(view.active_layer)
+            .is_some()
+        {
+            tab.model.layer_manager.active_layer_id = view.active_layer;
+        }
+        if fit {
+            self.zoom_extents();
+        } else {
+            self.active_tab_mut()
+                .viewport
+                .restore(view.offset, view.zoom);
+        }
+    }
+
+    /// Open an entry from the recent list, dropping it if the file is gone
+    pub fn open_recent(&mut self, path: &Path) {
+        if !path.exists() {
+            self.notify(format!("{} no longer exists", path.display()));
+            self.recent_projects.remove(path);
+            return;
+        }
+        self.start_load(path.to_path_buf());
+    }
+
+    /// Start an untitled drawing from a template. The active tab is
+    /// reused if it is blank.
+    pub fn new_from_template(&mut self, entry: &TemplateEntry) {
+        let template = match entry.load() {
+            Ok(template) => template,
+            Err(error) => {
+                self.command_history.push(TerminalLine::error(&error));
+                self.notify(error);
+                return;
+            }
+        };
+        if !self.active_tab_is_blank() {
+            self.new_tab();
+        }
+        let tab = self.active_tab_mut();
+        template.apply(&mut tab.model);
+        tab.executor.defaults = template.drafting;
+        if let Some(config) = template.config {
+            // Panel layout belongs to the user, not the template
+            let panels = std::mem::take(&mut self.config.gui_config.panels);
+            self.config = config;
+            self.config.gui_config.panels = panels;
+        }
+        self.seed_from_structure_library();
+        let tab = self.active_tab_mut();
+        tab.journal = Journal::new(&tab.model);
+        self.show_welcome = false;
+        self.command_history.push(TerminalLine::info(format!(
+            "New drawing from template \"{}\"",
+            template.name
+        )));
+    }
+
+    /// Save the active drawing as a template in the templates directory;
+    /// the file name becomes the template name
+    pub fn save_as_template(&mut self) {
+        let mut dialog = rfd::FileDialog::new()
+            .add_filter("MuginCAD Template", &[TEMPLATE_EXTENSION])
+            .set_file_name(format!("{}.{}", self.active_tab().name, TEMPLATE_EXTENSION));
+        if let Some(dir) = &self.templates_dir
+            && std::fs::create_dir_all(dir).is_ok()
+        {
+            dialog = dialog.set_directory(dir);
+        }
+        let Some(mut path) = dialog.save_file() else {
+            return;
+        };
+        if path.extension().and_then(|ext| ext.to_str()) != Some(TEMPLATE_EXTENSION) {
+            path.set_extension(TEMPLATE_EXTENSION);
+        }
+
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("Template")
+            .to_string();
+        let tab = self.active_tab();
+        let template =
+            DrawingTemplate::from_model(&name, &tab.model, &self.config, &tab.executor.defaults);
+        match template.write(&path) {
+            Ok(()) => {
+                self.refresh_templates();
+                self.command_history.push(TerminalLine::info(format!(
+                    "Saved template \"{}\" to {:?}",
+                    name, path
+                )));
+                self.notify(format!("Saved template {}", name));
+            }
+            Err(error) => {
+                self.command_history.push(TerminalLine::error(&error));
+                self.notify(error);
+            }
+        }
+    }
+
+    /// Re-read the template list from the templates directory
+    pub fn refresh_templates(&mut self) {
+        self.templates = available_templates(self.templates_dir.as_deref());
+    }
+
+    /// Whether the active tab is an untouched, unsaved drawing
+    fn active_tab_is_blank(&self) -> bool {
+        let tab = self.active_tab();
+        tab.model.entities.is_empty()
+            && tab.model.axis_manager.axes.is_empty()
+            && !tab.is_dirty
+            && tab.file_path.is_none()
+    }
 }
@@ -0,0 +1,70 @@
+//! Tabs saved when a frame panics, and reopening them at the next start.
+
+use crate::commands::output::TerminalLine;
+use crate::model::project::ProjectData;
+use crate::model::recovery::{CrashFile, list_crash_files, write_crash_file};
+use crate::viewmodel::CadViewModel;
+
+impl CadViewModel {
+    /// Find the tabs a crash left behind
+    pub fn load_crash_files(&mut self) {
+        if let Some(dir) = &self.recovery_dir {
+            self.crash_files = list_crash_files(dir);
+        }
+    }
+
+    /// Write every open tab to the recovery directory. Runs while a panic
+    /// unwinds, so it touches no UI; a tab that cannot be written is
+    /// skipped. Returns how many tabs were saved.
+    pub fn save_crash_files(&self) -> usize {
+        let Some(dir) = &self.recovery_dir else {
+            return 0;
+        };
+        (0..self.tabs.len())
+            .filter(|&tab_idx| {
+                let name = &self.tabs[tab_idx].name;
+                match write_crash_file(dir, name, || self.project_data(tab_idx)) {
+                    Ok(_) => true,
+                    Err(error) => {
+                        eprintln!("{}", error);
+                        false
+                    }
+                }
+            })
+            .count()
+    }
+
+    /// Reopen a tab saved by a crash as an unsaved drawing, then delete
+    /// the recovery file
+    pub fn open_crash_file(&mut self, file: &CrashFile) {
+        let project_data = match ProjectData::read(&file.path) {
+            Ok(project_data) => project_data,
+            Err(error) => {
+                self.notify(error);
+                return;
+            }
+        };
+        self.fill_tab(project_data);
+
+        let tab = self.active_tab_mut();
+        tab.name = format!("{} (recovered)", file.name);
+        tab.is_dirty = true;
+        self.command_history.push(TerminalLine::info(format!(
+            "Recovered \"{}\" from {:?}",
+            file.name, file.path
+        )));
+        self.discard_crash_file(file);
+    }
+
+    /// Delete a recovery file without opening it
+    pub fn discard_crash_file(&mut self, file: &CrashFile) {
+        if let Err(error) = std::fs::remove_file(&file.path) {
+            self.notify(format!(
+                "Could not delete {}: {}",
+                file.path.display(),
+                error
+            ));
+        }
+        self.crash_files.retain(|f| f.path != file.path);
+    }
+}
@@ -0,0 +1,65 @@
+use crate::commands::output::TerminalLine;
+use crate::model::reference::{Reference, ReferenceSource};
+use crate::viewmodel::CadViewModel;
+
+impl CadViewModel {
+    /// Show another open tab beneath the active one
+    pub fn add_tab_reference(&mut self, tab_id: u64) {
+        let Some(source) = self.tab_index(tab_id).map(|index| &self.tabs[index]) else {
+            return;
+        };
+        let mut reference = Reference::new(ReferenceSource::Tab(tab_id), source.name.clone());
+        reference.set_snapshot(&source.model);
+        self.active_tab_mut().references.push(reference);
+    }
+
+    /// Pick a project file and show it beneath the active tab
+    pub fn add_file_reference(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("MuginCAD Project", &["mugin"])
+            .pick_file()
+        else {
+            return;
+        };
+        let label = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+        let mut reference = Reference::new(ReferenceSource::File(path), label);
+        reference.reload_file();
+        if let Some(error) = &reference.error {
+            self.command_history.push(TerminalLine::error(error));
+            self.notify(error.clone());
+            return;
+        }
+        self.active_tab_mut().references.push(reference);
+    }
+
+    /// Copy the current geometry of referenced tabs into the active tab's
+    /// references. Called on tab switch, so edits made in the source show up.
+    pub fn refresh_tab_references(&mut self) {
+        let tab_idx = self.active_tab_index;
+        let mut references = std::mem::take(&mut self.tabs[tab_idx].references);
+        for reference in &mut references {
+            let ReferenceSource::Tab(id) = reference.source else {
+                continue;
+            };
+            match self.tab_index(id) {
+                Some(index) => {
+                    reference.label = self.tabs[index].name.clone();
+                    reference.set_snapshot(&self.tabs[index].model);
+                }
+                None => reference.error = Some("Tab was closed".to_string()),
+            }
+        }
+        self.tabs[tab_idx].references = references;
+    }
+
+    /// Refresh every reference of the active tab, reading files again
+    pub fn reload_references(&mut self) {
+        self.refresh_tab_references();
+        for reference in &mut self.active_tab_mut().references {
+            reference.reload_file();
+        }
+    }
+}
@@ -0,0 +1,65 @@
+//! ROOMS: rooms found in the walls of the plan, and their report.
+
+use crate::commands::output::TerminalLine;
+use crate::export::rooms::rooms_csv;
+use crate::model::Entity;
+use crate::model::tools::rooms::ROOM_GAP_TOLERANCE;
+use crate::viewmodel::CadViewModel;
+
+impl CadViewModel {
+    /// Find the rooms of the active drawing again as one undo step. Rooms
+    /// still enclosed take their new boundary and area; new loops become
+    /// rooms on the active layer, inside the edited group if there is one.
+    pub fn regenerate_rooms(&mut self) {
+        self.save_undo_state();
+        let (tab, history) = self.active_tab_mut_and_history();
+        let update = tab.model.regenerate_rooms(ROOM_GAP_TOLERANCE);
+        let added = update.added.len();
+        for room in update.added {
+            let mut entity = Entity::room(room);
+            entity.layer_id = tab.model.layer_manager.active_layer_id;
+            entity.color = tab.executor.defaults.color;
+            match tab.selection_manager.scope {
+                Some(group) => tab.model.add_child_entity(group, entity),
+                None => tab.model.add_entity(entity),
+            }
+        }
+        tab.model.update_hierarchy();
+
+        let message = format!("ROOMS: {} new, {} updated", added, update.updated);
+        history.push(TerminalLine::info(&message));
+        if update.unmatched > 0 {
+            history.push(TerminalLine::warning(format!(
+                "{} rooms are no longer enclosed and were left as they were",
+                update.unmatched
+            )));
+        }
+        tab.executor.status_message = message;
+    }
+
+    /// Write the active tab's rooms and their areas to a CSV file chosen
+    /// by the user
+    pub fn export_rooms(&mut self) {
+        let default_name = format!("{}_rooms.csv", self.active_tab().name);
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("CSV", &["csv"])
+            .set_file_name(&default_name)
+            .save_file()
+        else {
+            return;
+        };
+        let csv = rooms_csv(&self.active_tab().model);
+        match std::fs::write(&path, csv) {
+            Ok(()) => {
+                self.command_history
+                    .push(TerminalLine::info(format!("Exported rooms to {:?}", path)));
+                self.notify(format!("Exported {}", path.display()));
+            }
+            Err(e) => {
+                let message = format!("Could not export rooms: {}", e);
+                self.command_history.push(TerminalLine::error(&message));
+                self.notify(message);
+            }
+        }
+    }
+}
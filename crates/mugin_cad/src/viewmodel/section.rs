@@ -0,0 +1,48 @@
+//! Schematic sections generated from section marks.
+
+use crate::commands::output::TerminalLine;
+use crate::model::structure::section::section_entities;
+use crate::model::{SectionMark, Shape};
+use crate::viewmodel::CadViewModel;
+
+impl CadViewModel {
+    /// The first selected section mark, with its cut in world coordinates
+    fn selected_section_mark(&self) -> Option<SectionMark> {
+        let tab = self.active_tab();
+        let mut ids: Vec<u64> = tab.selection_manager.selected_ids.iter().copied().collect();
+        ids.sort();
+        ids.into_iter().find_map(|id| {
+            let entity = tab.model.find_by_id(id)?;
+            let Shape::Section(mark) = &entity.shape else {
+                return None;
+            };
+            let world = |p| entity.world_transform.transform_point2(p).into();
+            let mut mark = mark.clone();
+            mark.start = world(mark.start.into());
+            mark.end = world(mark.end.into());
+            Some(mark)
+        })
+    }
+
+    /// Draw the schematic section through the selected section mark in a
+    /// new tab
+    pub fn generate_section(&mut self) {
+        let Some(mark) = self.selected_section_mark() else {
+            self.notify("Select a section mark first");
+            return;
+        };
+        let tab = self.active_tab();
+        let entities = section_entities(&tab.model, &mark, tab.executor.defaults.storey_height);
+        let name = format!("{} – {}", tab.name, mark.title());
+
+        self.new_tab();
+        let tab = self.active_tab_mut();
+        tab.name = name;
+        for entity in entities {
+            tab.model.add_entity(entity);
+        }
+        self.zoom_extents();
+        self.command_history
+            .push(TerminalLine::info(format!("Generated {}", mark.title())));
+    }
+}
@@ -1,5 +1,8 @@
-use crate::model::{CadModel, Vector2};
-use std::collections::HashSet;
+use crate::model::{CadModel, Entity, Shape, Vector2};
+use std::collections::{HashSet, VecDeque};
+
+/// Number of recent selections kept for the status strip dropdown
+pub const SELECTION_HISTORY_LEN: usize = 8;
 
 #[derive(Clone, Debug)]
 pub struct SelectionManager {
@@ -7,6 +10,14 @@ pub struct SelectionManager {
     pub selection_rect_start: Option<Vector2>,
     pub selection_rect_current: Option<Vector2>,
     pub last_interacted_id: Option<u64>,
+    /// Selection as it was before the last clearing action
+    previous: Option<HashSet<u64>>,
+    /// Recent selections, most recent first
+    history: VecDeque<HashSet<u64>>,
+    /// Group being edited: picking only reaches its children
+    pub scope: Option<u64>,
+    /// Whether clicks and windows also select markup sketches
+    pub select_markup: bool,
 }
 
 impl SelectionManager {
@@ -16,17 +27,69 @@ impl SelectionManager {
             selection_rect_start: None,
             selection_rect_current: None,
             last_interacted_id: None,
+            previous: None,
+            history: VecDeque::new(),
+            scope: None,
+            select_markup: false,
         }
     }
 
+    /// Clear the selection, remembering it for `select_previous`
     pub fn clear(&mut self) {
-        self.selected_ids.clear();
+        if !self.selected_ids.is_empty() {
+            self.record();
+            self.previous = Some(std::mem::take(&mut self.selected_ids));
+        }
     }
 
     pub fn is_empty(&self) -> bool {
         self.selected_ids.is_empty()
     }
 
+    // ── Selection history ──────────────────────────────────────
+
+    /// Push the current selection onto the recent list. Called by commands
+    /// that consume a selection (move, copy, delete) and on clearing.
+    pub fn record(&mut self) {
+        if self.selected_ids.is_empty() || self.history.front() == Some(&self.selected_ids) {
+            return;
+        }
+        self.history.retain(|ids| *ids != self.selected_ids);
+        self.history.push_front(self.selected_ids.clone());
+        self.history.truncate(SELECTION_HISTORY_LEN);
+    }
+
+    /// Recent selections, most recent first
+    pub fn history(&self) -> impl Iterator<Item = &HashSet<u64>> {
+        self.history.iter()
+    }
+
+    /// Restore the selection from before the last clearing action.
+    /// Entities that no longer exist are skipped. Returns the number restored.
+    pub fn select_previous(&mut self, model: &CadModel) -> usize {
+        match self.previous.clone() {
+            Some(ids) => self.restore(&ids, model),
+            None => 0,
+        }
+    }
+
+    /// Replace the selection with `ids`, keeping the current one as previous.
+    /// Returns the number of entities selected.
+    pub fn restore(&mut self, ids: &HashSet<u64>, model: &CadModel) -> usize {
+        let ids: HashSet<u64> = ids
+            .iter()
+            .copied()
+            .filter(|&id| model.find_by_id(id).is_some())
+            .collect();
+        if ids.is_empty() {
+            return 0;
+        }
+        self.clear();
+        self.selected_ids = ids;
+        self.record();
+        self.selected_ids.len()
+    }
+
     /// Handle click selection logic
     /// Returns a status message string
     pub fn handle_click_selection(
@@ -38,7 +101,7 @@ impl SelectionManager {
         ctrl: bool,
     ) -> String {
         // Selection mode - single click selection
-        let picked_id = model.pick_entity_id(pos, tolerance);
+        let picked_id = self.pick(model, pos, tolerance);
 
         if let Some(id) = picked_id {
             if shift || ctrl {
@@ -51,14 +114,16 @@ impl SelectionManager {
                 }
             } else {
                 // Single selection
-                self.selected_ids.clear();
+                if !(self.selected_ids.len() == 1 && self.selected_ids.contains(&id)) {
+                    self.clear();
+                }
                 self.selected_ids.insert(id);
                 self.last_interacted_id = Some(id);
             }
             format!("Selected {} items", self.selected_ids.len())
         } else {
             if !shift && !ctrl {
-                self.selected_ids.clear();
+                self.clear();
                 "Selection cleared".to_string()
             } else {
                 // Maintained selection
@@ -67,6 +132,18 @@ impl SelectionManager {
         }
     }
 
+    /// Entity a click at `pos` would select, within the edited group
+    pub fn pick(&self, model: &CadModel, pos: Vector2, tolerance: f32) -> Option<u64> {
+        model.pick_where(self.scope, pos, tolerance, &|entity| {
+            self.selectable(entity)
+        })
+    }
+
+    /// Whether the selection filter lets `entity` be picked
+    fn selectable(&self, entity: &Entity) -> bool {
+        self.select_markup || !matches!(entity.shape, Shape::Sketch(_))
+    }
+
     pub fn start_selection_rect(&mut self, pos: Vector2) {
         self.selection_rect_start = Some(pos);
         self.selection_rect_current = Some(pos);
@@ -86,9 +163,21 @@ impl SelectionManager {
             let min = Vector2::new(start.x.min(end.x), start.y.min(end.y));
             let max = Vector2::new(start.x.max(end.x), start.y.max(end.y));
 
-            // Find entities in rect
-            for entity in &model.entities {
-                let (e_min, e_max) = entity.bounding_box();
+            // Find entities in rect, among the edited group's children
+            // inside group editing
+            let candidates = match self.scope.and_then(|id| model.find_by_id(id)) {
+                Some(group) => &group.children,
+                None => &model.entities,
+            };
+            for entity in candidates {
+                // Locked images stay out of window selections too
+                if matches!(&entity.shape, Shape::Image(image) if image.locked)
+                    || !self.selectable(entity)
+                    || !entity.on_active_storey(&model.storeys)
+                {
+                    continue;
+                }
+                let (e_min, e_max) = entity.bounding_box_in(&model.definitions);
 
                 // Check if entity is fully inside selection rect
                 if e_min.x >= min.x && e_max.x <= max.x && e_min.y >= min.y && e_max.y <= max.y {
@@ -103,12 +192,27 @@ impl SelectionManager {
         format!("Selected {} items", self.selected_ids.len())
     }
 
+    /// Drop IDs of entities that no longer exist (e.g. removed by a command)
+    pub fn prune(&mut self, model: &CadModel) {
+        self.selected_ids
+            .retain(|&id| model.find_by_id(id).is_some());
+        if self
+            .last_interacted_id
+            .is_some_and(|id| model.find_by_id(id).is_none())
+        {
+            self.last_interacted_id = None;
+        }
+        if self.scope.is_some_and(|id| model.find_by_id(id).is_none()) {
+            self.scope = None;
+        }
+    }
+
     /// Delete selected entities from the model
     /// Returns status message and the number of items deleted
     pub fn delete_selected(&mut self, model: &mut CadModel) -> (String, usize) {
         if !self.selected_ids.is_empty() {
             let count = model.remove_entities_by_ids(&self.selected_ids);
-            self.selected_ids.clear();
+            self.clear();
             (format!("Deleted {} items", count), count)
         } else {
             ("Nothing selected to delete".to_string(), 0)
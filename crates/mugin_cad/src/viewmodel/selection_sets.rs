@@ -0,0 +1,80 @@
+//! Saving and recalling named selections.
+
+use crate::commands::output::TerminalLine;
+use crate::viewmodel::CadViewModel;
+use std::collections::HashSet;
+
+impl CadViewModel {
+    /// Store the current selection under `name`
+    pub fn save_selection_set(&mut self, name: &str) {
+        let name = name.trim();
+        let (tab, history) = self.active_tab_mut_and_history();
+        let line = if name.is_empty() {
+            TerminalLine::error("Usage: SELSAVE <name>")
+        } else if tab.selection_manager.is_empty() {
+            TerminalLine::error("Nothing selected to save")
+        } else {
+            let ids = &tab.selection_manager.selected_ids;
+            let replaced = tab.selection_sets.save(name, ids);
+            tab.is_dirty = true;
+            TerminalLine::info(format!(
+                "{} selection set \"{}\" with {} items",
+                if replaced { "Replaced" } else { "Saved" },
+                name,
+                ids.len()
+            ))
+        };
+        tab.executor.status_message = line.text.clone();
+        history.push(line);
+    }
+
+    /// Select the entities of set `name` that still exist
+    pub fn load_selection_set(&mut self, name: &str) {
+        let name = name.trim();
+        let (tab, history) = self.active_tab_mut_and_history();
+        let line = match tab.selection_sets.get(name) {
+            None if name.is_empty() => TerminalLine::error("Usage: SELLOAD <name>"),
+            None => TerminalLine::error(format!("No selection set \"{}\"", name)),
+            Some(ids) => {
+                let ids: HashSet<u64> = ids.iter().copied().collect();
+                let count = tab.selection_manager.restore(&ids, &tab.model);
+                let missing = ids.len() - count;
+                match (count, missing) {
+                    (0, _) => TerminalLine::warning(format!(
+                        "The entities of \"{}\" no longer exist",
+                        name
+                    )),
+                    (_, 0) => {
+                        TerminalLine::info(format!("Selected {} items of \"{}\"", count, name))
+                    }
+                    _ => TerminalLine::warning(format!(
+                        "Selected {} items of \"{}\"; {} no longer exist",
+                        count, name, missing
+                    )),
+                }
+            }
+        };
+        tab.executor.status_message = line.text.clone();
+        history.push(line);
+    }
+
+    pub fn rename_selection_set(&mut self, from: &str, to: &str) {
+        let tab = self.active_tab_mut();
+        if tab.selection_sets.rename(from, to) {
+            tab.is_dirty = true;
+        } else {
+            self.notify(format!(
+                "Could not rename \"{}\" to \"{}\"",
+                from,
+                to.trim()
+            ));
+        }
+    }
+
+    pub fn remove_selection_set(&mut self, name: &str) {
+        let tab = self.active_tab_mut();
+        if tab.selection_sets.remove(name) {
+            tab.is_dirty = true;
+        }
+    }
+}
@@ -1,7 +1,24 @@
 use crate::commands::InputModifiers;
 use crate::model::Vector2;
+use crate::model::tools::alignment::{AlignmentGuide, align_bounds};
 use crate::viewmodel::CadViewModel;
 
+/// Moving entities snap to alignment guides within this many screen pixels
+const ALIGN_THRESHOLD_PX: f32 = 6.0;
+
+/// The cursor at each stage of resolution. Clicks, previews, drags and
+/// the coordinate readout all read from this so they agree.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResolvedCursor {
+    /// Position under the pointer
+    pub raw: Vector2,
+    /// After object/grid snap (Ctrl)
+    pub snapped: Vector2,
+    /// After the active command's constraints, e.g. Shift for ortho.
+    /// This is the point a click commits.
+    pub constrained: Vector2,
+}
+
 impl CadViewModel {
     /// Update snap point based on mouse position and modifiers
     pub fn update_snap(&mut self, pos: Vector2, modifiers: InputModifiers) {
@@ -10,18 +27,131 @@ impl CadViewModel {
             // Split borrow
             let config = &self.config;
             let tab = &mut self.tabs[tab_idx];
-            tab.current_snap = tab.snap_system.find_nearest(pos, &tab.model, config);
+            let preview = tab
+                .executor
+                .get_preview_points()
+                .and_then(|(_, points)| points.last().copied())
+                .map(|last| (last, pos));
+            let snap = tab
+                .snap_system
+                .find_nearest(pos, &tab.model, config, preview);
+            let reference_snap = if config.snap_config.snap_to_references {
+                tab.references
+                    .iter()
+                    .filter(|reference| reference.visible)
+                    .filter_map(|reference| {
+                        tab.snap_system.find_nearest_reference(
+                            pos,
+                            &reference.snapshot.entities,
+                            config.snap_config.tolerance,
+                        )
+                    })
+                    .min_by(|a, b| pos.dist(a.position).total_cmp(&pos.dist(b.position)))
+            } else {
+                None
+            };
+            let tracking_snap = tab.snap_system.find_nearest_tracking(
+                pos,
+                &tab.executor.tracking_points,
+                config.snap_config.tolerance,
+            );
+            tab.current_snap = [snap, reference_snap, tracking_snap]
+                .into_iter()
+                .flatten()
+                .min_by(|a, b| pos.dist(a.position).total_cmp(&pos.dist(b.position)));
         } else {
             self.active_tab_mut().current_snap = None;
         }
     }
 
-    /// Get the effective position (snapped if Ctrl is pressed)
-    pub fn get_effective_position(&self, pos: Vector2) -> Vector2 {
-        if let Some(snap) = &self.active_tab().current_snap {
-            snap.position
-        } else {
-            pos
+    /// Drop a tracking point on the hovered snap point, or remove the one
+    /// there
+    pub fn toggle_tracking_point(&mut self) {
+        let tab = self.active_tab_mut();
+        let Some(snap) = tab.current_snap else {
+            tab.executor.status_message = "Hover a snap point to track it.".to_string();
+            return;
+        };
+        if !tab.executor.toggle_tracking_point(snap.position) {
+            tab.executor.status_message = "Tracking points need a running command.".to_string();
+        }
+    }
+
+    /// Resolve a raw position through the current snap and the active
+    /// command's constraints
+    pub fn resolve_cursor(&self, pos: Vector2, modifiers: InputModifiers) -> ResolvedCursor {
+        self.resolve_with_guides(pos, modifiers).0
+    }
+
+    /// Snap and resolve the hovered position, remembering it for the
+    /// preview and the coordinate readout. Called once per frame.
+    pub fn update_cursor(&mut self, pos: Vector2, modifiers: InputModifiers) -> ResolvedCursor {
+        self.update_snap(pos, modifiers);
+        let (cursor, guides) = self.resolve_with_guides(pos, modifiers);
+        let tab = self.active_tab_mut();
+        tab.cursor = Some(cursor);
+        tab.last_cursor = Some(cursor.constrained);
+        tab.alignment_guides = guides;
+        cursor
+    }
+
+    /// `resolve_cursor`, plus the alignment guides the constrained point
+    /// was pulled onto while the active command carries entities
+    fn resolve_with_guides(
+        &self,
+        pos: Vector2,
+        modifiers: InputModifiers,
+    ) -> (ResolvedCursor, Vec<AlignmentGuide>) {
+        let tab = self.active_tab();
+        let snapped = tab.current_snap.as_ref().map_or(pos, |snap| snap.position);
+        let mut constrained = tab.executor.constrain(snapped, modifiers);
+        let mut guides = Vec::new();
+
+        // Object snaps and ortho win over alignment; Alt turns it off
+        if self.config.snap_config.smart_guides
+            && tab.current_snap.is_none()
+            && !modifiers.shift
+            && !modifiers.alt
+            && let Some(moving) = tab.executor.moving_bounds()
+        {
+            let (view_min, view_max) = tab.viewport.visible_bounds();
+            let layers = &tab.model.layer_manager;
+            let candidates: Vec<_> = tab
+                .model
+                .entities
+                .iter()
+                .filter(|entity| !moving.moving_ids.contains(&entity.id))
+                .filter(|entity| {
+                    layers
+                        .get_layer(entity.layer_id)
+                        .is_none_or(|layer| layer.is_visible)
+                })
+                .map(|entity| entity.bounding_box())
+                .filter(|(min, max)| {
+                    min.x <= max.x
+                        && min.y <= max.y
+                        && max.x >= view_min.x
+                        && min.x <= view_max.x
+                        && max.y >= view_min.y
+                        && min.y <= view_max.y
+                })
+                .collect();
+
+            let delta = constrained - moving.base;
+            let alignment = align_bounds(
+                (moving.min + delta, moving.max + delta),
+                &candidates,
+                ALIGN_THRESHOLD_PX / tab.viewport.zoom,
+            );
+            constrained = constrained + alignment.offset;
+            guides = alignment.guides;
         }
+
+        let cursor = ResolvedCursor {
+            raw: pos,
+            snapped,
+            constrained,
+        };
+        (cursor, guides)
     }
 }
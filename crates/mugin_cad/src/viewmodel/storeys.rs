@@ -0,0 +1,30 @@
+//! Storey edits that change the drawing.
+
+use crate::commands::output::TerminalLine;
+use crate::viewmodel::CadViewModel;
+
+impl CadViewModel {
+    /// Duplicate the members of storey `from` onto storey `to`
+    pub fn copy_storey(&mut self, from: u64, to: u64) {
+        if from == to {
+            self.notify("Pick a different storey to copy to");
+            return;
+        }
+        self.save_undo_state();
+        let model = &mut self.active_tab_mut().model;
+        let count = model.copy_storey(from, to);
+        let (from, to) = (
+            model.storeys.name_of(from).to_string(),
+            model.storeys.name_of(to).to_string(),
+        );
+        self.command_history.push(TerminalLine::info(format!(
+            "Copied {count} members from {from} to {to}"
+        )));
+    }
+
+    /// Remove a storey, leaving its members unassigned
+    pub fn remove_storey(&mut self, id: u64) {
+        self.save_undo_state();
+        self.active_tab_mut().model.remove_storey(id);
+    }
+}
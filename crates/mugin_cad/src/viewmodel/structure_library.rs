@@ -0,0 +1,164 @@
+//! Moving structural types between the active tab and the shared library.
+
+use crate::commands::output::TerminalLine;
+use crate::model::structure::definitions::StructureDefinitions;
+use crate::model::structure::library::{
+    DefinitionConflict, MergeSummary, read_library, write_library,
+};
+use crate::viewmodel::CadViewModel;
+use std::collections::HashSet;
+
+/// Which way definitions are being copied
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LibraryTransfer {
+    Import,
+    Export,
+}
+
+/// A library import or export waiting on the user to settle name conflicts
+pub struct PendingLibraryMerge {
+    pub transfer: LibraryTransfer,
+    /// Definitions being merged into the other side
+    pub incoming: StructureDefinitions,
+    /// Conflicts still to decide; the first one is being asked about
+    pub conflicts: Vec<DefinitionConflict>,
+    /// Conflicts where the incoming definition wins
+    pub replace: HashSet<DefinitionConflict>,
+}
+
+impl CadViewModel {
+    fn load_structure_library(&mut self) -> Option<StructureDefinitions> {
+        let Some(path) = self.structure_library_path.clone() else {
+            self.notify("No structure library location");
+            return None;
+        };
+        match read_library(&path) {
+            Ok(library) => Some(library),
+            Err(error) => {
+                self.command_history.push(TerminalLine::error(&error));
+                self.notify(error);
+                None
+            }
+        }
+    }
+
+    /// Copy the library's types into the active tab
+    pub fn import_structure_library(&mut self) {
+        let Some(library) = self.load_structure_library() else {
+            return;
+        };
+        let conflicts = self.active_tab().model.definitions.name_conflicts(&library);
+        self.start_library_merge(PendingLibraryMerge {
+            transfer: LibraryTransfer::Import,
+            incoming: library,
+            conflicts,
+            replace: HashSet::new(),
+        });
+    }
+
+    /// Copy the active tab's types into the library
+    pub fn export_structure_library(&mut self) {
+        let Some(library) = self.load_structure_library() else {
+            return;
+        };
+        let incoming = self.active_tab().model.definitions.clone();
+        let conflicts = library.name_conflicts(&incoming);
+        self.start_library_merge(PendingLibraryMerge {
+            transfer: LibraryTransfer::Export,
+            incoming,
+            conflicts,
+            replace: HashSet::new(),
+        });
+    }
+
+    fn start_library_merge(&mut self, pending: PendingLibraryMerge) {
+        if pending.conflicts.is_empty() {
+            self.finish_library_merge(pending);
+        } else {
+            self.library_merge_prompt = Some(pending);
+        }
+    }
+
+    /// Settle the conflict being asked about, or every remaining one
+    pub fn resolve_library_conflict(&mut self, use_incoming: bool, apply_to_all: bool) {
+        let Some(mut pending) = self.library_merge_prompt.take() else {
+            return;
+        };
+        let count = if apply_to_all {
+            pending.conflicts.len()
+        } else {
+            1.min(pending.conflicts.len())
+        };
+        for conflict in pending.conflicts.drain(..count) {
+            if use_incoming {
+                pending.replace.insert(conflict);
+            }
+        }
+        if pending.conflicts.is_empty() {
+            self.finish_library_merge(pending);
+        } else {
+            self.library_merge_prompt = Some(pending);
+        }
+    }
+
+    /// Drop a library transfer without changing either side
+    pub fn cancel_library_merge(&mut self) {
+        self.library_merge_prompt = None;
+    }
+
+    fn finish_library_merge(&mut self, pending: PendingLibraryMerge) {
+        let summary = match pending.transfer {
+            LibraryTransfer::Import => {
+                let tab = self.active_tab_mut();
+                let summary = tab
+                    .model
+                    .definitions
+                    .merge_by_name(&pending.incoming, &pending.replace);
+                if summary.added + summary.replaced > 0 {
+                    tab.is_dirty = true;
+                }
+                summary
+            }
+            LibraryTransfer::Export => {
+                let Some(mut library) = self.load_structure_library() else {
+                    return;
+                };
+                let summary = library.merge_by_name(&pending.incoming, &pending.replace);
+                let path = self.structure_library_path.clone().unwrap_or_default();
+                if let Err(error) = write_library(&path, &library) {
+                    self.command_history.push(TerminalLine::error(&error));
+                    self.notify(error);
+                    return;
+                }
+                summary
+            }
+        };
+        self.report_library_merge(pending.transfer, summary);
+    }
+
+    fn report_library_merge(&mut self, transfer: LibraryTransfer, summary: MergeSummary) {
+        let action = match transfer {
+            LibraryTransfer::Import => "Imported from",
+            LibraryTransfer::Export => "Exported to",
+        };
+        self.command_history.push(TerminalLine::info(format!(
+            "{} structure library: {} added, {} replaced, {} kept",
+            action, summary.added, summary.replaced, summary.kept
+        )));
+    }
+
+    /// Copy the library into the active tab when new tabs are seeded from
+    /// it. Names already in the tab keep their definitions.
+    pub(super) fn seed_from_structure_library(&mut self) {
+        if !self.config.gui_config.seed_structure_library {
+            return;
+        }
+        let Some(library) = self.load_structure_library() else {
+            return;
+        };
+        self.active_tab_mut()
+            .model
+            .definitions
+            .merge_by_name(&library, &HashSet::new());
+    }
+}
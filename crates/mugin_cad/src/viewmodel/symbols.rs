@@ -0,0 +1,72 @@
+//! The symbol palette's library: built-in symbols plus those imported from
+//! drawings, which are kept in the app's data directory.
+
+use crate::commands::output::TerminalLine;
+use crate::model::project::ProjectData;
+use crate::model::system::symbols::{read_symbols, symbols_from_project, write_symbols};
+use crate::viewmodel::CadViewModel;
+use std::sync::Arc;
+
+impl CadViewModel {
+    /// Load the symbols imported in earlier sessions
+    pub fn load_symbols(&mut self) {
+        let Some(path) = self.symbol_library_path.clone() else {
+            return;
+        };
+        match read_symbols(&path) {
+            Ok(symbols) => Arc::make_mut(&mut self.symbols).import(symbols),
+            Err(error) => {
+                self.command_history.push(TerminalLine::error(&error));
+                self.notify(error);
+            }
+        }
+    }
+
+    /// Pick a drawing and add its groups to the symbol library
+    pub fn import_symbols(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("MuginCAD Project", &["mugin"])
+            .pick_file()
+        else {
+            return;
+        };
+        let project = match ProjectData::read(&path) {
+            Ok(project) => project,
+            Err(error) => {
+                self.command_history.push(TerminalLine::error(&error));
+                self.notify(error);
+                return;
+            }
+        };
+        let fallback = path.file_stem().map_or("Symbol".to_string(), |stem| {
+            stem.to_string_lossy().into_owned()
+        });
+        let symbols = symbols_from_project(&project, &fallback);
+        if symbols.is_empty() {
+            self.notify(format!("{} has nothing to import", path.display()));
+            return;
+        }
+
+        let names: Vec<String> = symbols.iter().map(|s| s.name.clone()).collect();
+        let library = Arc::make_mut(&mut self.symbols);
+        library.import(symbols);
+        if let Some(library_path) = &self.symbol_library_path
+            && let Err(error) = write_symbols(library_path, &library.imported)
+        {
+            self.command_history.push(TerminalLine::error(&error));
+            self.notify(error);
+        }
+        self.command_history.push(TerminalLine::info(format!(
+            "Imported symbols: {}",
+            names.join(", ")
+        )));
+        self.notify(format!("Imported {} symbols", names.len()));
+    }
+
+    /// Start SYMBOL with `name` picked from the palette
+    pub fn place_symbol(&mut self, name: &str) {
+        let tab = self.active_tab_mut();
+        tab.executor.active_symbol = Some(name.to_string());
+        tab.start_command("symbol");
+    }
+}
@@ -1,13 +1,29 @@
+use crate::commands::CommandCategory;
 use crate::commands::executor::CommandExecutor;
-use crate::model::CadModel;
 use crate::model::Vector2;
+use crate::model::reference::Reference;
 use crate::model::snap::{SnapPoint, SnapSystem};
+use crate::model::tools::alignment::AlignmentGuide;
+use crate::model::tools::diff::Comparison;
+use crate::model::tools::journal::Journal;
+use crate::model::tools::saved::SavedRevisions;
+use crate::model::tools::selection_sets::SelectionSets;
 use crate::model::undo::UndoManager;
+use crate::model::{CadModel, Entity};
 use crate::view::viewport::Viewport;
+use crate::viewmodel::ResolvedCursor;
+use crate::viewmodel::editing::CanvasTextEdit;
+use crate::viewmodel::handles::HandleDrag;
 use crate::viewmodel::selection::SelectionManager;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+/// Counter for tab IDs, which stay stable while tabs are reordered or closed
+static NEXT_TAB_ID: AtomicU64 = AtomicU64::new(1);
 
 pub struct ProjectTab {
+    pub id: u64,
     pub name: String,
     pub file_path: Option<PathBuf>,
     pub is_dirty: bool,
@@ -16,33 +32,169 @@ pub struct ProjectTab {
     pub model: CadModel,
     pub executor: CommandExecutor,
     pub selection_manager: SelectionManager,
+    /// Named selections, saved with the project
+    pub selection_sets: SelectionSets,
+    /// Entity revisions as last saved or loaded
+    pub saved_revisions: SavedRevisions,
     pub snap_system: SnapSystem,
     pub current_snap: Option<SnapPoint>,
+    /// Hovered position as last resolved, None while off the canvas
+    pub cursor: Option<ResolvedCursor>,
+    /// Where the pointer last was on the canvas, kept while it is away,
+    /// e.g. over a palette
+    pub last_cursor: Option<Vector2>,
     pub undo_manager: UndoManager,
     pub viewport: Viewport,
+    /// Zoom extents asked for before the canvas had a size, done on the
+    /// next frame
+    pub fit_pending: bool,
 
     // Interaction State
     pub pending_delete_confirmation: bool,
-    pub dragging_label_index: Option<usize>,
+    pub dragging_label_id: Option<u64>,
     pub drag_last_pos: Option<Vector2>,
+    /// Where the current label drag began, so Escape can put it back
+    pub drag_origin: Option<Vector2>,
+    /// Selection box handle being dragged
+    pub handle_drag: Option<HandleDrag>,
+    /// Fixed corner of the export region while another is dragged
+    pub export_region_drag: Option<Vector2>,
+    /// Command preview is hidden until the cursor moves again after the
+    /// tab was switched back to or a step was typed in the terminal
+    pub preview_paused: bool,
+    /// Status line of a command left pending when the tab was switched away
+    suspended_prompt: Option<String>,
+    /// Command run whose inputs share the last undo snapshot
+    pub undo_run: Option<u64>,
+    /// Smart alignment guides for the entities following the cursor
+    pub alignment_guides: Vec<AlignmentGuide>,
+    /// When the selection was last nudged with the arrow keys
+    pub last_nudge: Option<Instant>,
+    /// Entity as it was when the running inspector edit began
+    pub property_edit: Option<Entity>,
+    /// Text being retyped on the canvas
+    pub text_edit: Option<CanvasTextEdit>,
+    /// Other drawings shown read-only beneath this one
+    pub references: Vec<Reference>,
+    /// Every change made to the drawing since it was opened
+    pub journal: Journal,
+    /// The drawing compared with a saved file, shown as an overlay
+    pub comparison: Option<Comparison>,
 }
 
 impl ProjectTab {
     pub fn new(name: String) -> Self {
+        let model = CadModel::new();
         Self {
+            id: NEXT_TAB_ID.fetch_add(1, Ordering::Relaxed),
             name,
             file_path: None,
             is_dirty: false,
-            model: CadModel::new(),
+            journal: Journal::new(&model),
+            model,
             executor: CommandExecutor::new(),
             selection_manager: SelectionManager::new(),
+            selection_sets: SelectionSets::default(),
+            saved_revisions: SavedRevisions::default(),
             snap_system: SnapSystem::new(),
             current_snap: None,
+            cursor: None,
+            last_cursor: None,
             undo_manager: UndoManager::new(50),
             viewport: Viewport::new(),
+            fit_pending: false,
             pending_delete_confirmation: false,
-            dragging_label_index: None,
+            dragging_label_id: None,
             drag_last_pos: None,
+            drag_origin: None,
+            handle_drag: None,
+            export_region_drag: None,
+            preview_paused: false,
+            suspended_prompt: None,
+            undo_run: None,
+            alignment_guides: Vec::new(),
+            last_nudge: None,
+            property_edit: None,
+            text_edit: None,
+            references: Vec::new(),
+            comparison: None,
+        }
+    }
+
+    /// Start a command on the current selection. Commands that consume the
+    /// selection record it so it can be reselected afterwards.
+    pub fn start_command(&mut self, name: &str) -> bool {
+        self.executor.group = self.selection_manager.scope;
+        let started = self
+            .executor
+            .start_command(name, &mut self.model, &self.selection_manager.selected_ids)
+            .is_ok();
+        self.record_consumed_selection();
+        started
+    }
+
+    /// Record the selection if the active command manipulates it
+    pub fn record_consumed_selection(&mut self) {
+        if self.executor.active_category() == Some(CommandCategory::Manipulation) {
+            self.selection_manager.record();
+        }
+    }
+
+    // ── Tab switching ───────────────────────────────────────────
+
+    /// The tab is being switched away from. Drops in-flight pointer state
+    /// and parks a pending command's prompt until the tab is shown again.
+    pub fn suspend(&mut self) {
+        self.current_snap = None;
+        self.cursor = None;
+        self.viewport.end_pan(false);
+        self.selection_manager.selection_rect_start = None;
+        self.selection_manager.selection_rect_current = None;
+        // A label dragged when the tab is left goes back, as on Escape
+        if let Some(id) = self.dragging_label_id.take()
+            && let (Some(origin), Some(last)) = (self.drag_origin, self.drag_last_pos)
+        {
+            self.model.move_label(id, origin - last);
         }
+        self.drag_last_pos = None;
+        self.drag_origin = None;
+
+        if self.executor.is_active() {
+            self.preview_paused = true;
+            self.suspended_prompt = Some(self.executor.status_message.clone());
+        }
+    }
+
+    /// The tab is shown again: restore a pending command's prompt
+    pub fn resume(&mut self) {
+        if let Some(prompt) = self.suspended_prompt.take()
+            && self.executor.is_active()
+        {
+            self.executor.status_message = prompt;
+        }
+    }
+
+    /// Name of the command waiting for input in this tab, if any
+    pub fn pending_command(&self) -> Option<&'static str> {
+        self.executor.active_command_name()
+    }
+
+    /// Copy of this tab's project under a new name: entities (with fresh
+    /// IDs), layers, axes, definitions, drafting defaults and viewport.
+    /// Undo history, selection and file path are not carried over.
+    pub fn duplicate(&self, name: String) -> Self {
+        let mut tab = Self::new(name);
+        tab.model.entities = self.model.entities.iter().map(|e| e.duplicate()).collect();
+        tab.model.axis_manager = self.model.axis_manager.clone();
+        tab.model.definitions = self.model.definitions.clone();
+        tab.model.layer_manager = self.model.layer_manager.clone();
+        tab.model.export_region = self.model.export_region;
+        tab.executor.defaults = self.executor.defaults.clone();
+        tab.viewport = self.viewport.clone();
+        tab.viewport.is_panning = false;
+        tab.viewport.pan_start = None;
+        tab.is_dirty = true;
+        tab.journal = Journal::new(&tab.model);
+        tab
     }
 }
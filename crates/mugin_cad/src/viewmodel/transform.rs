@@ -0,0 +1,166 @@
+//! Quick keyboard transforms of the selection.
+
+use crate::model::config::QuickTransformKeys;
+use crate::model::{Entity, Shape, Vector2};
+use crate::viewmodel::CadViewModel;
+use std::f32::consts::FRAC_PI_2;
+use std::time::{Duration, Instant};
+
+/// Nudges closer together than this share one undo step
+const NUDGE_COALESCE: Duration = Duration::from_millis(1000);
+
+/// One-step rotations and flips about the selection's center
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuickTransform {
+    RotateCw,
+    RotateCcw,
+    FlipHorizontal,
+    FlipVertical,
+}
+
+impl QuickTransform {
+    pub const ALL: [QuickTransform; 4] = [
+        QuickTransform::RotateCw,
+        QuickTransform::RotateCcw,
+        QuickTransform::FlipHorizontal,
+        QuickTransform::FlipVertical,
+    ];
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            QuickTransform::RotateCw => "Rotate 90° CW",
+            QuickTransform::RotateCcw => "Rotate 90° CCW",
+            QuickTransform::FlipHorizontal => "Flip Horizontal",
+            QuickTransform::FlipVertical => "Flip Vertical",
+        }
+    }
+
+    pub fn icon(self) -> &'static str {
+        match self {
+            QuickTransform::RotateCw => "⟳",
+            QuickTransform::RotateCcw => "⟲",
+            QuickTransform::FlipHorizontal => "⇔",
+            QuickTransform::FlipVertical => "⇕",
+        }
+    }
+
+    /// The user's key binding for this action
+    pub fn shortcut(self, keys: &QuickTransformKeys) -> &str {
+        match self {
+            QuickTransform::RotateCw => &keys.rotate_cw,
+            QuickTransform::RotateCcw => &keys.rotate_ccw,
+            QuickTransform::FlipHorizontal => &keys.flip_horizontal,
+            QuickTransform::FlipVertical => &keys.flip_vertical,
+        }
+    }
+
+    pub fn shortcut_mut(self, keys: &mut QuickTransformKeys) -> &mut String {
+        match self {
+            QuickTransform::RotateCw => &mut keys.rotate_cw,
+            QuickTransform::RotateCcw => &mut keys.rotate_ccw,
+            QuickTransform::FlipHorizontal => &mut keys.flip_horizontal,
+            QuickTransform::FlipVertical => &mut keys.flip_vertical,
+        }
+    }
+}
+
+impl CadViewModel {
+    /// Move the top-level selected entities by `steps` grid units
+    pub fn nudge_selection(&mut self, steps: Vector2) {
+        let grid_size = self.config.grid_config.grid_size;
+        let tab = self.active_tab();
+        if tab.selection_manager.is_empty() || tab.executor.is_active() || tab.handle_drag.is_some()
+        {
+            return;
+        }
+
+        let coalesce = tab
+            .last_nudge
+            .is_some_and(|last| last.elapsed() < NUDGE_COALESCE);
+        if coalesce {
+            self.active_tab_mut().is_dirty = true;
+        } else {
+            self.save_undo_state();
+        }
+
+        let tab = self.active_tab_mut();
+        let delta = steps * grid_size;
+        let ids = tab
+            .model
+            .get_top_level_selected_ids(&tab.selection_manager.selected_ids);
+        let connected = tab.executor.defaults.connected_move;
+        let ends = if connected {
+            tab.model.line_ends(&ids)
+        } else {
+            Vec::new()
+        };
+        for &id in &ids {
+            if let Some(entity) = tab.model.find_by_id_mut(id) {
+                entity.translate(delta);
+            }
+        }
+        tab.executor.status_message = format!("Nudged by {:.2}, {:.2}", delta.x, delta.y);
+        if connected {
+            let adjusted = tab.model.drag_connected(&ends, delta, &ids);
+            tab.executor.status_message += &format!(", {} neighbors adjusted", adjusted);
+        }
+        tab.model.update_hierarchy();
+        tab.last_nudge = Some(Instant::now());
+    }
+
+    /// Rotate or flip the top-level selected entities about the center of
+    /// the selection bounds, as one undo step
+    pub fn apply_quick_transform(&mut self, transform: QuickTransform) {
+        let rotate_text = self.config.gui_config.rotate_text;
+        let tab = self.active_tab();
+        if tab.executor.is_active() || tab.handle_drag.is_some() {
+            return;
+        }
+        let Some((min, max)) = tab.selection_bounds() else {
+            return;
+        };
+        let center = (min + max) * 0.5;
+
+        self.save_undo_state();
+        let tab = self.active_tab_mut();
+        for id in tab
+            .model
+            .get_top_level_selected_ids(&tab.selection_manager.selected_ids)
+        {
+            let Some(entity) = tab.model.find_by_id_mut(id) else {
+                continue;
+            };
+            match transform {
+                QuickTransform::RotateCw | QuickTransform::RotateCcw => {
+                    let angle = if transform == QuickTransform::RotateCw {
+                        -FRAC_PI_2
+                    } else {
+                        FRAC_PI_2
+                    };
+                    entity.rotate(center, angle);
+                    // Text is drawn upright unless asked to turn with the rest
+                    if rotate_text {
+                        turn_text(entity, angle);
+                    }
+                }
+                QuickTransform::FlipHorizontal => entity.mirror(center, Vector2::new(0.0, 1.0)),
+                QuickTransform::FlipVertical => entity.mirror(center, Vector2::new(1.0, 0.0)),
+            }
+        }
+        tab.model.update_hierarchy();
+        tab.executor.status_message = transform.label().to_string();
+    }
+}
+
+fn turn_text(entity: &mut Entity, angle: f32) {
+    if matches!(entity.shape, Shape::Text(_)) {
+        entity.modify(|shape| {
+            if let Shape::Text(text) = shape {
+                text.rotation += angle;
+            }
+        });
+    }
+    for child in &mut entity.children {
+        turn_text(child, angle);
+    }
+}
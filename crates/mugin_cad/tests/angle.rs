@@ -0,0 +1,207 @@
+mod common;
+
+use common::assert_near;
+use mugin_cad::commands::{DynamicInput, resolve_coordinates};
+use mugin_cad::model::Vector2;
+use mugin_cad::model::math::angle::{AngleDirection, AngleFormat, AngleUnit, parse_amount};
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, PI, TAU};
+
+fn format(unit: AngleUnit, direction: AngleDirection, precision: u8) -> AngleFormat {
+    AngleFormat {
+        unit,
+        direction,
+        precision,
+    }
+}
+
+/// Radians equal to within float rounding
+fn near(actual: f32, expected: f32) {
+    assert!(
+        (actual - expected).abs() < 1e-5,
+        "expected {}, got {}",
+        expected,
+        actual
+    );
+}
+
+fn dms(precision: u8) -> AngleFormat {
+    format(AngleUnit::DegMinSec, AngleDirection::EastCcw, precision)
+}
+
+#[test]
+fn decimal_degrees_by_default() {
+    let angles = AngleFormat::default();
+    assert_eq!(angles.format_direction(FRAC_PI_4), "45.0°");
+    assert_eq!(angles.format_direction(-FRAC_PI_2), "270.0°");
+    assert_eq!(angles.format_rotation(PI), "180.0°");
+    near(angles.parse_direction("45").unwrap(), FRAC_PI_4);
+    near(
+        angles.parse_direction("45.5°").unwrap(),
+        45.5_f32.to_radians(),
+    );
+    near(angles.parse_rotation("-90").unwrap(), -FRAC_PI_2);
+    assert_eq!(angles.parse_direction("forty"), None);
+    assert_eq!(angles.parse_direction(""), None);
+}
+
+#[test]
+fn dms_text_carries_rounded_seconds() {
+    let angles = dms(0);
+    assert_eq!(angles.format_direction(45.5_f32.to_radians()), "45°30'00\"");
+    assert_eq!(
+        angles.format_direction((12.0 + 34.0 / 60.0 + 56.0 / 3600.0_f32).to_radians()),
+        "12°34'56\""
+    );
+    // 29°59'59.7" shows as 30°00'00", not 29°59'60"
+    let almost = (29.0 + 59.0 / 60.0 + 59.7 / 3600.0_f32).to_radians();
+    assert_eq!(angles.format_direction(almost), "30°00'00\"");
+    assert_eq!(dms(1).format_direction(almost), "29°59'59.7\"");
+    // Just short of a full turn is 0, not 360
+    assert_eq!(angles.format_direction(-1e-7), "0°00'00\"");
+}
+
+#[test]
+fn dms_round_trips() {
+    for precision in [0, 1, 2] {
+        let angles = dms(precision);
+        for degrees in [
+            0.0,
+            0.5,
+            1.0 / 3.0,
+            12.582,
+            45.0,
+            89.999,
+            179.25,
+            271.1234,
+            359.5,
+        ] {
+            let text = angles.format_direction(f32::to_radians(degrees));
+            let parsed = angles.parse_direction(&text).unwrap();
+            assert_eq!(
+                angles.format_direction(parsed),
+                text,
+                "{} at {}",
+                degrees,
+                precision
+            );
+        }
+    }
+}
+
+#[test]
+fn dms_input_forms() {
+    let expected = (45.0 + 30.0 / 60.0 + 15.0 / 3600.0_f64).to_radians();
+    for text in [
+        "45°30'15\"",
+        "45d30'15\"",
+        "45d30'15",
+        "45° 30' 15''",
+        "45D30'15\"",
+    ] {
+        let parsed = parse_amount(text, AngleUnit::Degrees).unwrap();
+        assert!((parsed - expected).abs() < 1e-9, "{}", text);
+    }
+    assert!(
+        (parse_amount("45°30", AngleUnit::Degrees).unwrap() - 45.5_f64.to_radians()).abs() < 1e-9
+    );
+    assert!(
+        (parse_amount("-10°30'", AngleUnit::Degrees).unwrap() + 10.5_f64.to_radians()).abs() < 1e-9
+    );
+    // Out of order, repeated or out of range parts are refused
+    for text in [
+        "45'30°",
+        "45°30°",
+        "45°75'",
+        "45°30'75\"",
+        "°30'",
+        "45°30'15\"10",
+    ] {
+        assert_eq!(parse_amount(text, AngleUnit::Degrees), None, "{}", text);
+    }
+}
+
+#[test]
+fn gradians_and_named_units() {
+    let angles = format(AngleUnit::Gradians, AngleDirection::EastCcw, 2);
+    assert_eq!(angles.format_direction(FRAC_PI_2), "100.00 gon");
+    near(angles.parse_direction("50").unwrap(), FRAC_PI_4);
+    near(angles.parse_direction("100.00 gon").unwrap(), FRAC_PI_2);
+    // A typed unit wins over the setting
+    near(angles.parse_direction("45°").unwrap(), FRAC_PI_4);
+    near(angles.parse_direction("45deg").unwrap(), FRAC_PI_4);
+    near(angles.parse_direction("0.5rad").unwrap(), 0.5);
+    near(
+        AngleFormat::default().parse_direction("50g").unwrap(),
+        FRAC_PI_4,
+    );
+}
+
+#[test]
+fn north_clockwise_azimuths() {
+    let angles = format(AngleUnit::Degrees, AngleDirection::NorthCw, 1);
+    assert_eq!(angles.format_direction(FRAC_PI_2), "0.0°");
+    assert_eq!(angles.format_direction(0.0), "90.0°");
+    assert_eq!(angles.format_direction(PI), "270.0°");
+    near(angles.parse_direction("90").unwrap(), 0.0);
+    near(angles.parse_direction("180").unwrap(), 3.0 * FRAC_PI_2);
+    // Rotations keep their size and turn clockwise
+    assert_eq!(angles.format_rotation(-FRAC_PI_2), "90.0°");
+    near(angles.parse_rotation("90").unwrap(), -FRAC_PI_2);
+}
+
+#[test]
+fn quadrant_bearings() {
+    let angles = format(AngleUnit::DegMinSec, AngleDirection::Bearing, 0);
+    let azimuth = |degrees: f32| FRAC_PI_2 - degrees.to_radians();
+    assert_eq!(angles.format_direction(azimuth(45.0)), "N 45°00'00\" E");
+    assert_eq!(angles.format_direction(azimuth(120.5)), "S 59°30'00\" E");
+    assert_eq!(angles.format_direction(azimuth(200.0)), "S 20°00'00\" W");
+    assert_eq!(angles.format_direction(azimuth(315.0)), "N 45°00'00\" W");
+
+    for text in [
+        "N 45°00'00\" E",
+        "S 59°30'00\" E",
+        "S 20°00'00\" W",
+        "N 45°00'00\" W",
+    ] {
+        let parsed = angles.parse_direction(text).unwrap();
+        assert_eq!(angles.format_direction(parsed), text);
+    }
+    // Typed in any case and spacing, whatever the setting
+    let plain = AngleFormat::default();
+    near(plain.parse_direction("n45e").unwrap(), FRAC_PI_4);
+    near(
+        plain.parse_direction("S 30 W").unwrap(),
+        azimuth(210.0) + TAU,
+    );
+    assert_eq!(plain.parse_direction("N 95 E"), None);
+    assert_eq!(plain.parse_rotation("N 45 E"), None);
+}
+
+#[test]
+fn polar_input_reads_the_angle_format() {
+    let last = Some(Vector2::new(10.0, 10.0));
+    let bearings = format(AngleUnit::Degrees, AngleDirection::Bearing, 1);
+    let point = resolve_coordinates("@100<n 90 e", last, &bearings)
+        .unwrap()
+        .unwrap();
+    assert_near(point, Vector2::new(110.0, 10.0));
+
+    let gradians = format(AngleUnit::Gradians, AngleDirection::EastCcw, 1);
+    let point = resolve_coordinates("100<100", None, &gradians)
+        .unwrap()
+        .unwrap();
+    assert_near(point, Vector2::new(0.0, 100.0));
+
+    assert!(resolve_coordinates("@100<north", last, &bearings).is_err());
+
+    // Dynamic input shows and takes the same text
+    let input = DynamicInput::Polar {
+        base: Vector2::new(0.0, 0.0),
+    };
+    let values = input.values(Vector2::new(0.0, 50.0));
+    assert_eq!(input.field_text(1, values[1], &bearings), "N 0.0° E");
+    assert_eq!(input.field_text(0, values[0], &bearings), "50.00");
+    let angle = input.parse_field(1, "s 0 e", &bearings).unwrap();
+    near(angle, 3.0 * FRAC_PI_2);
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use common::{assert_near, v};
+use mugin_cad::model::{Arc, Geometry};
+use std::f32::consts::{FRAC_PI_2, PI};
+
+/// Unit arc from 300° to 60°, counter-clockwise through 0°
+fn across_zero() -> Arc {
+    Arc::from_three_points_directed(
+        v(0.0, 0.0),
+        v(0.5, -0.866_025_4),
+        v(0.5, 0.866_025_4),
+        false,
+        false,
+    )
+}
+
+/// The same endpoints, clockwise through 180°
+fn across_pi() -> Arc {
+    Arc {
+        clockwise: true,
+        ..across_zero()
+    }
+}
+
+#[test]
+fn sweep_follows_the_direction() {
+    assert!((across_zero().sweep() - 2.0 * PI / 3.0).abs() < 1e-4);
+    assert!((across_pi().sweep() + 4.0 * PI / 3.0).abs() < 1e-4);
+}
+
+#[test]
+fn hit_test_across_the_wrap() {
+    let tolerance = 0.05;
+    assert!(across_zero().hit_test(v(1.0, 0.0), tolerance));
+    assert!(!across_zero().hit_test(v(-1.0, 0.0), tolerance));
+    assert!(across_pi().hit_test(v(-1.0, 0.0), tolerance));
+    assert!(!across_pi().hit_test(v(1.0, 0.0), tolerance));
+}
+
+#[test]
+fn polyline_runs_from_start_to_end() {
+    for arc in [across_zero(), across_pi()] {
+        let points = arc.as_polyline();
+        assert_near(points[0], arc.start_point());
+        assert_near(*points.last().unwrap(), arc.end_point());
+    }
+    // The middle sample lies on the side the arc actually covers
+    assert_near(across_zero().as_polyline()[12], v(1.0, 0.0));
+    assert_near(across_pi().as_polyline()[12], v(-1.0, 0.0));
+}
+
+#[test]
+fn bounding_box_covers_only_the_arc() {
+    let (min, max) = across_zero().bounding_box();
+    assert_near(min, v(0.5, -0.866_025_4));
+    assert_near(max, v(1.0, 0.866_025_4));
+
+    let (min, max) = across_pi().bounding_box();
+    assert_near(min, v(-1.0, -1.0));
+    assert_near(max, v(0.5, 1.0));
+}
+
+#[test]
+fn clockwise_keeps_the_picked_endpoints() {
+    let start = v(0.0, 1.0);
+    let end = v(1.0, 0.0);
+    let arc = Arc::from_three_points_directed(v(0.0, 0.0), start, end, false, true);
+    assert_near(arc.start_point(), start);
+    assert_near(arc.end_point(), end);
+    // Quarter turn clockwise, not the three-quarter complement
+    assert!((arc.sweep() + FRAC_PI_2).abs() < 1e-4);
+    assert!(arc.hit_test(v(0.707, 0.707), 0.01));
+    assert!(!arc.hit_test(v(-0.707, -0.707), 0.01));
+}
+
+#[test]
+fn older_files_load_as_counter_clockwise() {
+    let json = r#"{"center":{"x":0.0,"y":0.0},"radius":1.0,"start_angle":0.0,"end_angle":1.0,"filled":false}"#;
+    let arc: Arc = serde_json::from_str(json).unwrap();
+    assert!(!arc.clockwise);
+
+    let round_trip: Arc =
+        serde_json::from_str(&serde_json::to_string(&across_pi()).unwrap()).unwrap();
+    assert_eq!(round_trip, across_pi());
+}
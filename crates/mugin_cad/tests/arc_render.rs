@@ -0,0 +1,191 @@
+//! Renders shapes through egui's tessellator and compares the coverage
+//! against golden masks in `tests/golden`. Run with `UPDATE_GOLDEN=1` to
+//! rewrite the masks after an intended change.
+
+#![cfg(feature = "gui")]
+
+use eframe::egui;
+use mugin_cad::model::config::DetailThresholds;
+use mugin_cad::model::dimension::DimensionStyle;
+use mugin_cad::model::layer::{DEFAULT_STROKE_WIDTH, LineStyle};
+use mugin_cad::model::structure::definitions::StructureDefinitions;
+use mugin_cad::model::{Arc, Flooring, Vector2};
+use mugin_cad::view::rendering::context::{DEFAULT_ENTITY_COLOR, DrawContext};
+use mugin_cad::view::rendering::renderable::Renderable;
+use std::f32::consts::PI;
+use std::path::PathBuf;
+
+const SIZE: usize = 48;
+
+/// Draw `draw` on a `SIZE` square canvas and return one char per pixel:
+/// `#` for strokes, `+` for translucent fills, `:` for faint anti-aliasing
+/// fringes and `.` for background
+fn render_mask(draw: impl Fn(&DrawContext)) -> String {
+    let ctx = egui::Context::default();
+    let rect = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(SIZE as f32, SIZE as f32));
+    let input = egui::RawInput {
+        screen_rect: Some(rect),
+        ..Default::default()
+    };
+    let output = ctx.run(input, |ctx| {
+        let painter = egui::Painter::new(ctx.clone(), egui::LayerId::background(), rect);
+        let dim_style = DimensionStyle::default();
+        draw(&DrawContext {
+            painter: &painter,
+            zoom: 1.0,
+            offset: Vector2::new(0.0, 0.0),
+            screen_center: Vector2::new(SIZE as f32 / 2.0, SIZE as f32 / 2.0),
+            transform: glam::Affine2::IDENTITY,
+            dim_style: &dim_style,
+            color: DEFAULT_ENTITY_COLOR,
+            emphasized: false,
+            line_style: LineStyle::Solid,
+            stroke_width: DEFAULT_STROKE_WIDTH,
+            detail: DetailThresholds::default(),
+            flooring: Default::default(),
+        });
+    });
+
+    let mut alpha = vec![0u8; SIZE * SIZE];
+    for primitive in ctx.tessellate(output.shapes, output.pixels_per_point) {
+        if let egui::epaint::Primitive::Mesh(mesh) = primitive.primitive {
+            for triangle in mesh.indices.chunks(3) {
+                let [a, b, c] = [0, 1, 2].map(|i| mesh.vertices[triangle[i] as usize]);
+                rasterize(&mut alpha, [a, b, c]);
+            }
+        }
+    }
+
+    alpha
+        .chunks(SIZE)
+        .map(|row| {
+            row.iter()
+                .map(|&a| match a {
+                    128.. => '#',
+                    32.. => '+',
+                    1.. => ':',
+                    0 => '.',
+                })
+                .chain(['\n'])
+                .collect::<String>()
+        })
+        .collect()
+}
+
+/// Keep the strongest alpha covering each pixel center
+fn rasterize(alpha: &mut [u8], [a, b, c]: [egui::epaint::Vertex; 3]) {
+    let edge = |p: egui::Pos2, q: egui::Pos2, r: egui::Pos2| {
+        (q.x - p.x) * (r.y - p.y) - (q.y - p.y) * (r.x - p.x)
+    };
+    let area = edge(a.pos, b.pos, c.pos);
+    if area.abs() < f32::EPSILON {
+        return;
+    }
+    let min_x = a.pos.x.min(b.pos.x).min(c.pos.x).floor().max(0.0) as usize;
+    let max_x = (a.pos.x.max(b.pos.x).max(c.pos.x).ceil() as usize).min(SIZE);
+    let min_y = a.pos.y.min(b.pos.y).min(c.pos.y).floor().max(0.0) as usize;
+    let max_y = (a.pos.y.max(b.pos.y).max(c.pos.y).ceil() as usize).min(SIZE);
+    for y in min_y..max_y {
+        for x in min_x..max_x {
+            let p = egui::pos2(x as f32 + 0.5, y as f32 + 0.5);
+            let wa = edge(b.pos, c.pos, p) / area;
+            let wb = edge(c.pos, a.pos, p) / area;
+            let wc = edge(a.pos, b.pos, p) / area;
+            if wa < 0.0 || wb < 0.0 || wc < 0.0 {
+                continue;
+            }
+            let value = wa * a.color.a() as f32 + wb * b.color.a() as f32 + wc * c.color.a() as f32;
+            let pixel = &mut alpha[y * SIZE + x];
+            *pixel = (*pixel).max(value.round() as u8);
+        }
+    }
+}
+
+#[track_caller]
+fn assert_golden(name: &str, actual: &str) {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "golden", name]
+        .iter()
+        .collect();
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&path).unwrap_or_default();
+    assert!(
+        expected == actual,
+        "{} differs from the golden mask\nexpected:\n{}\nactual:\n{}",
+        name,
+        expected,
+        actual
+    );
+}
+
+#[test]
+fn filled_reflex_arc() {
+    let arc = Arc {
+        center: Vector2::new(0.0, 0.0),
+        radius: 18.0,
+        start_angle: 0.0,
+        end_angle: 1.5 * PI,
+        filled: true,
+        clockwise: false,
+    };
+    let mask = render_mask(|ctx| arc.render(ctx, &StructureDefinitions::new(), false, false));
+    assert_golden("filled_arc_270.txt", &mask);
+}
+
+#[test]
+fn filled_reflex_arc_clockwise() {
+    // Clockwise from 270° back to 0° covers the same three quarters
+    let arc = Arc {
+        center: Vector2::new(0.0, 0.0),
+        radius: 18.0,
+        start_angle: 1.5 * PI,
+        end_angle: 0.0,
+        filled: true,
+        clockwise: true,
+    };
+    let mask = render_mask(|ctx| arc.render(ctx, &StructureDefinitions::new(), false, false));
+    assert_golden("filled_arc_270.txt", &mask);
+}
+
+#[test]
+fn tinted_l_shaped_flooring() {
+    // The notch in the top-right quarter stays empty
+    let floor = Flooring::new(
+        vec![
+            Vector2::new(-18.0, -18.0),
+            Vector2::new(18.0, -18.0),
+            Vector2::new(18.0, 0.0),
+            Vector2::new(0.0, 0.0),
+            Vector2::new(0.0, 18.0),
+            Vector2::new(-18.0, 18.0),
+        ],
+        1,
+        15.0,
+    );
+    let mask = render_mask(|ctx| floor.render(ctx, &StructureDefinitions::new(), false, false));
+    assert_golden("flooring_l_tint.txt", &mask);
+}
+
+#[test]
+fn tinted_flooring_with_an_opening() {
+    let mut floor = Flooring::new(
+        vec![
+            Vector2::new(-18.0, -18.0),
+            Vector2::new(18.0, -18.0),
+            Vector2::new(18.0, 18.0),
+            Vector2::new(-18.0, 18.0),
+        ],
+        1,
+        15.0,
+    );
+    floor.holes.push(vec![
+        Vector2::new(-8.0, -8.0),
+        Vector2::new(8.0, -8.0),
+        Vector2::new(8.0, 8.0),
+        Vector2::new(-8.0, 8.0),
+    ]);
+    let mask = render_mask(|ctx| floor.render(ctx, &StructureDefinitions::new(), false, false));
+    assert_golden("flooring_opening_tint.txt", &mask);
+}
@@ -0,0 +1,124 @@
+mod common;
+
+use common::{Harness, assert_near};
+use mugin_cad::commands::create::autodim::{DIMENSIONS_GROUP, DimensionChain};
+use mugin_cad::model::{Entity, Shape, Vector2};
+
+/// Measured length and label position of each dimension AUTODIM made,
+/// in the order they were added
+fn dimensions(h: &Harness) -> Vec<(f32, Vector2)> {
+    let group = h.model.entities.last().expect("no dimensions container");
+    assert_eq!(group.name, DIMENSIONS_GROUP);
+    group
+        .children
+        .iter()
+        .map(|child| match &child.shape {
+            Shape::Text(text) if text.is_dimension() => (
+                text.anchor_points[0].dist(text.anchor_points[1]),
+                text.position,
+            ),
+            other => panic!("expected a dimension, got {:?}", other),
+        })
+        .collect()
+}
+
+fn lengths(h: &Harness) -> Vec<f32> {
+    dimensions(h)
+        .into_iter()
+        .map(|(length, _)| length)
+        .collect()
+}
+
+fn add_line(h: &mut Harness, start: (f32, f32), end: (f32, f32)) -> u64 {
+    let line = Entity::line(Vector2::new(start.0, start.1), Vector2::new(end.0, end.1));
+    let id = line.id;
+    h.model.add_entity(line);
+    id
+}
+
+#[test]
+fn chains_a_column_grid_with_an_overall_dimension() {
+    let mut h = Harness::new();
+    // Out of order, one drawn backwards, one repeated and one crossing
+    let ids = vec![
+        add_line(&mut h, (750.0, 0.0), (750.0, 500.0)),
+        add_line(&mut h, (0.0, 0.0), (0.0, 400.0)),
+        add_line(&mut h, (1200.0, 450.0), (1200.0, 50.0)),
+        add_line(&mut h, (300.0, 0.0), (300.0, 500.0)),
+        add_line(&mut h, (300.0, 100.0), (300.0, 200.0)),
+        add_line(&mut h, (0.0, 0.0), (1200.0, 0.0)),
+    ];
+    h.select(ids);
+    h.run("autodim");
+    h.click(600.0, 600.0);
+
+    assert_eq!(h.executor.active_command_name(), None);
+    assert_eq!(h.ids().len(), 7);
+    assert_eq!(lengths(&h), vec![300.0, 450.0, 450.0, 1200.0]);
+    let dims = dimensions(&h);
+    // The chain at the picked distance past the longest line, the overall
+    // one a row further out
+    assert_near(dims[0].1, Vector2::new(150.0, 600.0));
+    assert_near(dims[2].1, Vector2::new(975.0, 600.0));
+    assert_near(dims[3].1, Vector2::new(600.0, 640.0));
+    let output: Vec<&str> = h.executor.output.iter().map(|l| l.text.as_str()).collect();
+    assert!(output.iter().any(|l| l.contains("1 line(s) not parallel")));
+    assert!(output.iter().any(|l| l.contains("1 line(s) at an already")));
+
+    // The whole chain is one undo step
+    assert!(h.undo());
+    assert_eq!(h.ids().len(), 6);
+}
+
+#[test]
+fn picks_below_flip_the_chain_and_spacing_can_be_set() {
+    let mut h = Harness::new();
+    let ids = vec![
+        add_line(&mut h, (0.0, 0.0), (400.0, 0.0)),
+        add_line(&mut h, (0.0, 250.0), (400.0, 250.0)),
+        add_line(&mut h, (0.0, 600.0), (400.0, 600.0)),
+    ];
+    h.select(ids);
+    h.run("autodim; s; 25; -50,300");
+
+    assert_eq!(lengths(&h), vec![250.0, 350.0, 600.0]);
+    let dims = dimensions(&h);
+    assert_near(dims[0].1, Vector2::new(-50.0, 125.0));
+    assert_near(dims[2].1, Vector2::new(-75.0, 300.0));
+}
+
+#[test]
+fn two_lines_get_a_single_dimension() {
+    let chain = DimensionChain::from_lines(&[
+        (Vector2::new(0.0, 0.0), Vector2::new(0.0, 100.0)),
+        (Vector2::new(80.0, 0.0), Vector2::new(80.0, 100.0)),
+    ]);
+    let dims = chain.layout(Vector2::new(40.0, 50.0), 30.0);
+    assert_eq!(dims.len(), 1);
+    // A pick between the ends still lands outside them
+    assert_near(dims[0][2], Vector2::new(40.0, 130.0));
+}
+
+#[test]
+fn dimensions_the_axis_grid() {
+    let mut h = Harness::new();
+    h.run("axis; v; 1100; 0; 500;");
+    h.run("autodim; v; 600,-100");
+
+    assert_eq!(lengths(&h), vec![500.0, 600.0, 1100.0]);
+    assert_near(dimensions(&h)[2].1, Vector2::new(550.0, -140.0));
+}
+
+#[test]
+fn needs_parallel_lines_or_axes() {
+    let mut h = Harness::new();
+    h.run("autodim; 0,0");
+    assert!(h.model.entities.is_empty());
+    assert_eq!(h.executor.active_command_name(), Some("AUTODIM"));
+
+    // A single axis is no chain either
+    h.run(";axis; h; 0;");
+    h.run("autodim; h");
+    assert!(h.executor.last_error.is_some());
+    assert!(h.model.entities.is_empty());
+}
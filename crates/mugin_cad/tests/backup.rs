@@ -0,0 +1,89 @@
+mod common;
+
+use common::scratch_dir;
+use mugin_cad::model::backup::{age_label, backup_path, list_backups, write_with_backups};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[test]
+fn saves_rotate_the_previous_versions() {
+    let dir = scratch_dir("rotate");
+    let path = dir.join("plan.mugin");
+    for version in 1..=5 {
+        write_with_backups(&path, format!("v{}", version).as_bytes(), 3).unwrap();
+    }
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), "v5");
+    for (index, expected) in [(1, "v4"), (2, "v3"), (3, "v2")] {
+        assert_eq!(
+            fs::read_to_string(backup_path(&path, index)).unwrap(),
+            expected
+        );
+    }
+    assert!(!backup_path(&path, 4).exists());
+    assert!(!dir.join("plan.mugin.tmp").exists());
+
+    let listed: Vec<usize> = list_backups(&path).iter().map(|b| b.index).collect();
+    assert_eq!(listed, vec![1, 2, 3]);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn a_failed_rotation_leaves_the_project_in_place() {
+    let dir = scratch_dir("failed_rotation");
+    let path = dir.join("plan.mugin");
+    write_with_backups(&path, b"v1", 2).unwrap();
+    write_with_backups(&path, b"v2", 2).unwrap();
+    // A non-empty directory where the oldest backup goes cannot be replaced
+    fs::create_dir_all(backup_path(&path, 2).join("blocked")).unwrap();
+
+    assert!(write_with_backups(&path, b"v3", 2).is_err());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "v2");
+    assert!(!dir.join("plan.mugin.tmp").exists());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn a_single_backup_is_replaced_each_save() {
+    let dir = scratch_dir("single");
+    let path = dir.join("plan.mugin");
+    for version in 1..=3 {
+        write_with_backups(&path, format!("v{}", version).as_bytes(), 1).unwrap();
+    }
+    assert_eq!(fs::read_to_string(&path).unwrap(), "v3");
+    assert_eq!(fs::read_to_string(backup_path(&path, 1)).unwrap(), "v2");
+    assert!(!backup_path(&path, 2).exists());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn no_backups_are_kept_when_disabled() {
+    let dir = scratch_dir("disabled");
+    let path = dir.join("plan.mugin");
+    write_with_backups(&path, b"old", 0).unwrap();
+    write_with_backups(&path, b"new", 0).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+    assert!(list_backups(&path).is_empty());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn backups_of_other_files_are_not_listed() {
+    let dir = scratch_dir("others");
+    let path = dir.join("plan.mugin");
+    fs::write(dir.join("plan.mugin.bak1"), "").unwrap();
+    fs::write(dir.join("plan.mugin.bakx"), "").unwrap();
+    fs::write(dir.join("other.mugin.bak1"), "").unwrap();
+    let listed: Vec<PathBuf> = list_backups(&path).into_iter().map(|b| b.path).collect();
+    assert_eq!(listed, vec![backup_path(&path, 1)]);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn ages_read_naturally() {
+    assert_eq!(age_label(Duration::from_secs(10)), "just now");
+    assert_eq!(age_label(Duration::from_secs(300)), "5 min ago");
+    assert_eq!(age_label(Duration::from_secs(7200)), "2 h ago");
+    assert_eq!(age_label(Duration::from_secs(3 * 86400)), "3 days ago");
+}
@@ -0,0 +1,156 @@
+mod common;
+
+use common::{Harness, scratch_dir};
+use mugin_cad::export::batch::{
+    BatchOutput, BatchSheet, DEFAULT_PATTERN, export_batch, file_name, file_names, storey_sheets,
+};
+use mugin_cad::export::settings::ExportSettings;
+use mugin_cad::model::CadModel;
+use mugin_cad::model::dimension::DimensionStyle;
+use mugin_cad::model::structure::column_type::ColumnType;
+use std::fs;
+
+fn sheet(name: &str, script: &str) -> BatchSheet {
+    let mut h = Harness::new();
+    h.run(script);
+    BatchSheet {
+        name: name.to_string(),
+        model: h.model,
+    }
+}
+
+fn export(sheets: &[BatchSheet], output: &BatchOutput) -> mugin_cad::export::batch::BatchReport {
+    export_batch(
+        sheets,
+        "tower",
+        output,
+        &ExportSettings::default(),
+        &DimensionStyle::default(),
+        |_| true,
+    )
+}
+
+#[test]
+fn patterns_name_files_after_project_and_tab() {
+    assert_eq!(
+        file_name(DEFAULT_PATTERN, "tower", "Ground"),
+        "tower-Ground.pdf"
+    );
+    assert_eq!(file_name("{tab}", "tower", "A/B: 1"), "A_B_ 1.pdf");
+    assert_eq!(file_name("sheet {tab}.PDF", "x", "1"), "sheet 1.PDF");
+
+    let sheets = [
+        sheet("Plan", ""),
+        sheet("plan", ""),
+        sheet("Plan", ""),
+        sheet("Roof", ""),
+    ];
+    assert_eq!(
+        file_names("{tab}.pdf", "tower", &sheets),
+        ["Plan.pdf", "plan (2).pdf", "Plan (3).pdf", "Roof.pdf"]
+    );
+}
+
+#[test]
+fn failing_sheets_are_reported_without_stopping_the_batch() {
+    let dir = scratch_dir("separate");
+    let sheets = [
+        sheet("Plan", "line; 0,0; 100,0;"),
+        sheet("Empty", ""),
+        sheet("Section", "circle; 0,0; 50"),
+    ];
+    let output = BatchOutput::Separate {
+        directory: dir.clone(),
+        pattern: DEFAULT_PATTERN.to_string(),
+    };
+    let report = export(&sheets, &output);
+
+    assert_eq!(
+        report.written,
+        [dir.join("tower-Plan.pdf"), dir.join("tower-Section.pdf")]
+    );
+    assert!(report.written.iter().all(|path| path.exists()));
+    assert_eq!(report.failures.len(), 1);
+    assert_eq!(report.failures[0].0, "Empty");
+    assert!(!report.cancelled);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn merged_batches_write_one_file_with_a_page_per_sheet() {
+    let dir = scratch_dir("merged");
+    let path = dir.join("set.pdf");
+    let sheets = [
+        sheet("Plan", "line; 0,0; 100,0;"),
+        sheet("Section", "circle; 0,0; 50"),
+    ];
+    let report = export(&sheets, &BatchOutput::Merged(path.clone()));
+
+    assert_eq!(report.pages, 2);
+    assert_eq!(report.written, vec![path.clone()]);
+    assert!(report.failures.is_empty());
+    assert!(path.exists());
+
+    // Nothing printable leaves no file behind
+    let empty = dir.join("empty.pdf");
+    let report = export(&[sheet("Empty", "")], &BatchOutput::Merged(empty.clone()));
+    assert!(report.written.is_empty());
+    assert_eq!(report.failures.len(), 1);
+    assert!(!empty.exists());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn stopping_a_batch_skips_the_remaining_sheets() {
+    let dir = scratch_dir("cancel");
+    let sheets = [
+        sheet("One", "line; 0,0; 100,0;"),
+        sheet("Two", "line; 0,0; 100,0;"),
+    ];
+    let output = BatchOutput::Separate {
+        directory: dir.clone(),
+        pattern: "{tab}".to_string(),
+    };
+    let mut seen = Vec::new();
+    let report = export_batch(
+        &sheets,
+        "tower",
+        &output,
+        &ExportSettings::default(),
+        &DimensionStyle::default(),
+        |done| {
+            seen.push(done);
+            false
+        },
+    );
+
+    assert_eq!(seen, [1]);
+    assert!(report.cancelled);
+    assert_eq!(report.written, [dir.join("One.pdf")]);
+    assert!(!dir.join("Two.pdf").exists());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn storey_sheets_keep_their_own_members() {
+    let mut h = Harness::new();
+    h.model
+        .definitions
+        .add_column_type(ColumnType::new(0, "C40x60", 40.0, 60.0, 0, 0, 0));
+    let ground = h.model.storeys.add("Ground", 300.0);
+    let first = h.model.storeys.add("First", 280.0);
+    h.model.storeys.active = Some(ground);
+    h.run("place_column; 0,0; 0");
+    h.model.storeys.active = Some(first);
+    h.run("place_column; 400,0; 0");
+    h.run("place_column; 800,0; 0");
+    h.run("line; 0,-100; 800,-100;");
+
+    let sheets = storey_sheets(&h.model);
+    let names: Vec<&str> = sheets.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, ["Ground", "First"]);
+    // The unassigned line prints on both
+    assert_eq!(sheets[0].model.entities.len(), 2);
+    assert_eq!(sheets[1].model.entities.len(), 3);
+    assert!(storey_sheets(&CadModel::new()).is_empty());
+}
@@ -0,0 +1,139 @@
+mod common;
+
+use common::{Harness, assert_near, v};
+use mugin_cad::model::structure::beam::BeamData;
+use mugin_cad::model::structure::beam_type::BeamType;
+use mugin_cad::model::structure::column_type::ColumnType;
+use mugin_cad::model::{Shape, Vector2};
+use std::collections::HashSet;
+
+/// A 40x60 column centred on (100, 0)
+fn harness() -> (Harness, u64) {
+    let mut h = Harness::new();
+    h.model
+        .definitions
+        .add_column_type(ColumnType::new(0, "C40x60", 40.0, 60.0, 0, 0, 0));
+    h.model
+        .definitions
+        .add_beam_type(BeamType::new(0, "B25x50", 25.0, 50.0, 0, 0));
+    h.run("place_column; 100,0; 0");
+    let column = h.ids()[0];
+    (h, column)
+}
+
+fn beam(h: &Harness, id: u64) -> &BeamData {
+    match &h.entity(id).shape {
+        Shape::Beam(beam) => beam,
+        other => panic!("expected a beam, got {:?}", other),
+    }
+}
+
+/// World-space ends of a beam
+fn ends(h: &Harness, id: u64) -> (Vector2, Vector2) {
+    let points = h.entity(id).as_polyline();
+    (points[0], points[1])
+}
+
+#[test]
+fn beam_into_a_column_stops_at_its_face() {
+    let (mut h, column) = harness();
+    h.run("place_beam; 0,0; 100,0;");
+    let id = h.ids()[1];
+
+    let beam = beam(&h, id);
+    assert_near(beam.start, v(0.0, 0.0));
+    assert_near(beam.end, v(80.0, 0.0));
+    assert!(beam.start_attachment.is_none());
+    assert_eq!(beam.end_attachment.unwrap().column_id, column);
+}
+
+#[test]
+fn beam_between_columns_is_trimmed_at_both_ends() {
+    let (mut h, first) = harness();
+    h.run("place_column; 300,0; 0");
+    let second = h.ids()[1];
+    h.run("place_beam; 100,0; 300,0;");
+
+    let beam = beam(&h, h.ids()[2]);
+    assert_near(beam.start, v(120.0, 0.0));
+    assert_near(beam.end, v(280.0, 0.0));
+    assert_eq!(beam.start_attachment.unwrap().column_id, first);
+    assert_eq!(beam.end_attachment.unwrap().column_id, second);
+}
+
+#[test]
+fn moving_the_column_drags_the_beam_end() {
+    let (mut h, column) = harness();
+    h.run("place_beam; 0,0; 100,0;");
+    let id = h.ids()[1];
+
+    h.select([column]);
+    h.run("move; 0,0; 0,50");
+    // The end aims at the column centre again and is cut at its face
+    let (start, end) = ends(&h, id);
+    assert_near(start, v(0.0, 0.0));
+    assert_near(end, v(80.0, 40.0));
+    assert!(beam(&h, id).end_attachment.is_some());
+
+    h.run("undo");
+    assert_near(ends(&h, id).1, v(80.0, 0.0));
+}
+
+#[test]
+fn deleting_the_column_detaches_the_beam() {
+    let (mut h, column) = harness();
+    h.run("place_beam; 0,0; 100,0;");
+    let id = h.ids()[1];
+
+    h.model.remove_entities_by_ids(&HashSet::from([column]));
+    h.model.resolve_attachments();
+
+    let beam = beam(&h, id);
+    assert!(beam.end_attachment.is_none());
+    assert_near(beam.end, v(80.0, 0.0));
+}
+
+#[test]
+fn moving_the_beam_off_the_column_detaches_it() {
+    let (mut h, _) = harness();
+    h.run("place_beam; 0,0; 100,0;");
+    let id = h.ids()[1];
+
+    h.select([id]);
+    h.run("move; 0,0; 0,200");
+    assert!(beam(&h, id).end_attachment.is_none());
+    assert_near(ends(&h, id).1, v(80.0, 200.0));
+}
+
+#[test]
+fn chain_mode_continues_from_the_clicked_point() {
+    let (mut h, column) = harness();
+    h.run("place_beam; 0,0; 100,0; 100,200;");
+    let [_, first, second] = h.ids()[..] else {
+        panic!("expected two beams");
+    };
+    assert_near(beam(&h, first).end, v(80.0, 0.0));
+    assert_near(beam(&h, second).start, v(100.0, 30.0));
+    assert_eq!(beam(&h, second).start_attachment.unwrap().column_id, column);
+}
+
+#[test]
+fn chain_option_turns_chaining_off() {
+    let (mut h, _) = harness();
+    h.run("place_beam; c; 0,0; 0,100; 200,0; 200,100;");
+    assert_eq!(h.ids().len(), 3);
+    assert_near(beam(&h, h.ids()[2]).start, v(200.0, 0.0));
+}
+
+#[test]
+fn attachments_round_trip() {
+    let (mut h, column) = harness();
+    h.run("place_beam; 0,0; 100,0;");
+    let beam = beam(&h, h.ids()[1]).clone();
+
+    let json = serde_json::to_string(&beam).unwrap();
+    let read: BeamData = serde_json::from_str(&json).unwrap();
+    assert_eq!(read.end_attachment.unwrap().column_id, column);
+    assert!(read.start_attachment.is_none());
+    assert!(!json.contains("start_attachment"));
+}
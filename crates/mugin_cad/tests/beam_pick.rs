@@ -0,0 +1,105 @@
+mod common;
+
+use common::v;
+use mugin_cad::model::structure::beam::{BeamAnchor, BeamData};
+use mugin_cad::model::structure::beam_type::BeamType;
+use mugin_cad::model::structure::junction::body_corners;
+use mugin_cad::model::{CadModel, Entity, Vector2};
+
+const TOLERANCE: f32 = 1.0;
+
+/// A model holding one beam of the given width from (0, 0) to (200, 100)
+fn model_with_beam(width: f32, anchor: BeamAnchor) -> (CadModel, BeamData) {
+    let mut model = CadModel::new();
+    let type_id = model
+        .definitions
+        .add_beam_type(BeamType::new(0, "B", width, 50.0, 0, 0));
+    let beam = BeamData::new(
+        v(0.0, 0.0),
+        v(200.0, 100.0),
+        type_id,
+        "K1".to_string(),
+        anchor,
+    );
+    model.add_entity(Entity::beam(beam.clone()));
+    model.update_hierarchy();
+    (model, beam)
+}
+
+/// Middle of each side of the drawn body and the unit normal pointing out
+fn sides(corners: [Vector2; 4]) -> Vec<(Vector2, Vector2)> {
+    let center = (corners[0] + corners[2]) * 0.5;
+    (0..4)
+        .map(|i| {
+            let mid = (corners[i] + corners[(i + 1) % 4]) * 0.5;
+            let out = mid - center;
+            (mid, out * (1.0 / out.length()))
+        })
+        .collect()
+}
+
+#[test]
+fn the_pick_band_matches_the_drawn_body() {
+    for width in [10.0, 30.0, 60.0] {
+        for anchor in [BeamAnchor::Center, BeamAnchor::Top, BeamAnchor::Bottom] {
+            let (model, beam) = model_with_beam(width, anchor);
+            let id = model.entities[0].id;
+            let outline = body_corners(beam.start, beam.end, width, anchor);
+            assert_eq!(beam.outline(width), Some(outline));
+            for (mid, out) in sides(outline) {
+                let case = format!("{} wide, {:?}, side at {:?}", width, anchor, mid);
+                assert_eq!(
+                    model.pick_entity_id(mid - out * 0.5, TOLERANCE),
+                    Some(id),
+                    "{}",
+                    case
+                );
+                assert_eq!(
+                    model.pick_entity_id(mid + out * 0.5, TOLERANCE),
+                    Some(id),
+                    "{}",
+                    case
+                );
+                assert_eq!(
+                    model.pick_entity_id(mid + out * 2.0, TOLERANCE),
+                    None,
+                    "{}",
+                    case
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn thin_beams_are_not_picked_beside_them_and_wide_ones_are_picked_across() {
+    // Perpendicular to the axis of (0, 0) -> (200, 100)
+    let across = v(-1.0, 2.0) * (1.0 / 5f32.sqrt());
+    let middle = v(100.0, 50.0);
+
+    let (thin, _) = model_with_beam(10.0, BeamAnchor::Center);
+    assert!(
+        thin.pick_entity_id(middle + across * 12.0, TOLERANCE)
+            .is_none()
+    );
+
+    let (wide, _) = model_with_beam(60.0, BeamAnchor::Center);
+    assert!(
+        wide.pick_entity_id(middle + across * 25.0, TOLERANCE)
+            .is_some()
+    );
+}
+
+#[test]
+fn window_bounds_follow_the_type_width() {
+    let (model, beam) = model_with_beam(10.0, BeamAnchor::Top);
+    let (min, max) = model.entities[0].bounding_box_in(&model.definitions);
+    let corners = beam.outline(10.0).unwrap();
+    for corner in corners {
+        assert!(corner.x >= min.x - 1e-3 && corner.x <= max.x + 1e-3);
+        assert!(corner.y >= min.y - 1e-3 && corner.y <= max.y + 1e-3);
+    }
+    // No slack beyond the body
+    assert!((min.x - corners.iter().map(|c| c.x).fold(f32::MAX, f32::min)).abs() < 1e-3);
+    assert!((max.y - corners.iter().map(|c| c.y).fold(f32::MIN, f32::max)).abs() < 1e-3);
+}
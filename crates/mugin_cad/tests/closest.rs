@@ -0,0 +1,95 @@
+mod common;
+
+use common::{assert_near, v};
+use mugin_cad::model::Entity;
+use mugin_cad::model::math::closest::{
+    Primitive, arc_arc, closest_between, closest_points, segment_arc, segment_segment,
+};
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+#[test]
+fn parallel_segments() {
+    let (a, b) = segment_segment(v(0.0, 0.0), v(10.0, 0.0), v(2.0, 5.0), v(20.0, 5.0));
+    assert!((a.dist(b) - 5.0).abs() < 1e-4);
+    assert!((a.y).abs() < 1e-4 && (b.y - 5.0).abs() < 1e-4);
+}
+
+#[test]
+fn segments_end_to_end() {
+    let (a, b) = segment_segment(v(0.0, 0.0), v(10.0, 0.0), v(13.0, 4.0), v(20.0, 4.0));
+    assert_near(a, v(10.0, 0.0));
+    assert_near(b, v(13.0, 4.0));
+}
+
+#[test]
+fn crossing_segments_touch() {
+    let (a, b) = segment_segment(v(0.0, 0.0), v(10.0, 10.0), v(0.0, 10.0), v(10.0, 0.0));
+    assert_near(a, v(5.0, 5.0));
+    assert_near(b, v(5.0, 5.0));
+}
+
+#[test]
+fn segment_above_circle() {
+    let (on_segment, on_arc) = segment_arc(v(-5.0, 10.0), v(5.0, 10.0), v(0.0, 0.0), 5.0, 0.0, TAU);
+    assert_near(on_segment, v(0.0, 10.0));
+    assert_near(on_arc, v(0.0, 5.0));
+}
+
+#[test]
+fn segment_to_arc_end() {
+    // Quarter arc in the first quadrant; the segment sits to the left
+    let (on_segment, on_arc) = segment_arc(
+        v(-10.0, 0.0),
+        v(-10.0, -10.0),
+        v(0.0, 0.0),
+        5.0,
+        0.0,
+        FRAC_PI_2,
+    );
+    assert_near(on_segment, v(-10.0, 0.0));
+    assert_near(on_arc, v(0.0, 5.0));
+}
+
+#[test]
+fn separate_circles() {
+    let (a, b) = arc_arc((v(0.0, 0.0), 1.0, 0.0, TAU), (v(10.0, 0.0), 2.0, 0.0, TAU));
+    assert_near(a, v(1.0, 0.0));
+    assert_near(b, v(8.0, 0.0));
+}
+
+#[test]
+fn primitive_order_is_kept() {
+    let segment = Primitive::Segment(v(-5.0, 10.0), v(5.0, 10.0));
+    let circle = Primitive::Arc {
+        center: v(0.0, 0.0),
+        radius: 5.0,
+        start: 0.0,
+        sweep: TAU,
+    };
+    let (a, b) = closest_points(&circle, &segment);
+    assert_near(a, v(0.0, 5.0));
+    assert_near(b, v(0.0, 10.0));
+}
+
+#[test]
+fn entities_line_and_circle() {
+    let line = Entity::line(v(0.0, 10.0), v(10.0, 10.0));
+    let circle = Entity::circle(v(5.0, 0.0), 4.0, false);
+    let (a, b) = closest_between(&line, &circle).unwrap();
+    assert!((a.dist(b) - 6.0).abs() < 1e-4);
+}
+
+#[test]
+fn intersecting_rectangle_and_line_have_no_gap() {
+    let rect = Entity::rectangle(v(0.0, 0.0), v(10.0, 10.0), false);
+    let line = Entity::line(v(5.0, -5.0), v(5.0, 5.0));
+    let (a, b) = closest_between(&rect, &line).unwrap();
+    assert!(a.dist(b) < 1e-4);
+}
+
+#[test]
+fn empty_container_has_no_outline() {
+    let group = Entity::empty("Group");
+    let line = Entity::line(v(0.0, 0.0), v(1.0, 0.0));
+    assert!(closest_between(&group, &line).is_none());
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use common::Harness;
+use ecolor::Color32;
+use mugin_cad::export::settings::ExportSettings;
+use mugin_cad::model::color_mode::{ColorMode, TYPE_PALETTE, type_color};
+
+#[test]
+fn the_mode_cycles_through_all_three() {
+    let mut mode = ColorMode::default();
+    assert_eq!(mode, ColorMode::Normal);
+    let mut seen = Vec::new();
+    for _ in 0..3 {
+        seen.push(mode.label());
+        mode = mode.next();
+    }
+    assert_eq!(mode, ColorMode::Normal);
+    assert_eq!(seen, ["Normal", "Color by Layer", "Color by Type"]);
+}
+
+#[test]
+fn entities_take_their_layer_or_type_color() {
+    let mut h = Harness::new();
+    let red = h
+        .model
+        .layer_manager
+        .add_layer("Red".into(), Color32::from_rgb(255, 0, 0));
+    h.model.layer_manager.set_active_layer(red);
+    h.run("line; 0,0; 100,0;");
+    h.run("circle; 0,0; 50");
+    let ids = h.ids();
+    let layer = h.model.layer_manager.get_layer(red);
+
+    let line = &h.entity(ids[0]).shape;
+    let circle = &h.entity(ids[1]).shape;
+    assert_eq!(ColorMode::Normal.color(line, layer), None);
+    assert_eq!(
+        ColorMode::ByLayer.color(circle, layer),
+        Some(Color32::from_rgb(255, 0, 0))
+    );
+    assert_eq!(ColorMode::ByType.color(line, layer), type_color("Line"));
+    assert_ne!(
+        ColorMode::ByType.color(line, layer),
+        ColorMode::ByType.color(circle, layer)
+    );
+}
+
+#[test]
+fn every_type_has_a_distinct_hue() {
+    for (i, (name, color)) in TYPE_PALETTE.iter().enumerate() {
+        assert_eq!(type_color(name), Some(*color));
+        assert!(TYPE_PALETTE[i + 1..].iter().all(|(_, c)| c != color));
+    }
+    assert_eq!(type_color("Wall"), None);
+}
+
+#[test]
+fn exports_print_in_black_unless_opted_in() {
+    assert_eq!(ExportSettings::default().color_mode, ColorMode::Normal);
+    let mut json = serde_json::to_value(ExportSettings::default()).unwrap();
+    json.as_object_mut().unwrap().remove("color_mode");
+    let settings: ExportSettings = serde_json::from_value(json).unwrap();
+    assert_eq!(settings.color_mode, ColorMode::Normal);
+}
+
+#[test]
+fn plot_styles_reduce_colors_for_printing() {
+    use mugin_cad::export::settings::PlotStyle;
+
+    let cyan = Color32::from_rgb(0, 255, 255);
+    assert_eq!(PlotStyle::FullColor.apply(cyan), cyan);
+    assert_eq!(PlotStyle::Monochrome.apply(cyan), Color32::BLACK);
+    // Rec. 709 luminance of cyan
+    assert_eq!(PlotStyle::Grayscale.apply(cyan), Color32::from_gray(201));
+    for style in PlotStyle::ALL {
+        assert_eq!(style.apply(Color32::WHITE), Color32::WHITE);
+    }
+    assert_eq!(ExportSettings::default().plot_style, PlotStyle::Monochrome);
+}
+
+#[test]
+fn prints_use_view_colors_only_in_color() {
+    use mugin_cad::export::settings::PlotStyle;
+
+    let mut h = Harness::new();
+    h.run("circle; 0,0; 50");
+    let circle = h.entity(h.ids()[0]);
+    let layer = h.model.layer_manager.get_layer(circle.layer_id);
+
+    let mut settings = ExportSettings {
+        color_mode: ColorMode::ByType,
+        plot_style: PlotStyle::FullColor,
+        ..Default::default()
+    };
+    assert_eq!(
+        settings.print_color(circle, layer),
+        type_color("Circle").unwrap()
+    );
+    settings.plot_style = PlotStyle::Monochrome;
+    assert_eq!(settings.print_color(circle, layer), Color32::BLACK);
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use common::{Harness, assert_near, v};
+use mugin_cad::model::structure::column::{ColumnAnchor, ColumnData};
+use mugin_cad::model::structure::column_type::ColumnType;
+use mugin_cad::model::{Geometry, Shape};
+use std::f32::consts::FRAC_PI_2;
+
+fn column(anchor: ColumnAnchor) -> ColumnData {
+    ColumnData::new(v(0.0, 0.0), 40.0, 60.0, 1, "S1".to_string(), anchor)
+}
+
+/// The single column in the harness drawing
+fn placed(h: &Harness) -> &ColumnData {
+    match &h.model.entities[..] {
+        [entity] => match &entity.shape {
+            Shape::Column(col) => col,
+            other => panic!("expected a column, got {:?}", other),
+        },
+        entities => panic!("expected one entity, got {}", entities.len()),
+    }
+}
+
+#[test]
+fn anchors_sit_where_their_names_say() {
+    let col = column(ColumnAnchor::Center);
+    let (min, max) = col.bounding_box();
+    for (anchor, expected) in [
+        (ColumnAnchor::Center, v(0.0, 0.0)),
+        (ColumnAnchor::TopLeft, v(min.x, max.y)),
+        (ColumnAnchor::TopRight, max),
+        (ColumnAnchor::BottomRight, v(max.x, min.y)),
+        (ColumnAnchor::BottomLeft, min),
+    ] {
+        let col = ColumnData {
+            anchor,
+            ..col.clone()
+        };
+        assert_near(col.anchor_point(), expected);
+    }
+}
+
+#[test]
+fn changing_the_anchor_only_moves_the_resize_pivot() {
+    let mut col = column(ColumnAnchor::Center);
+    col.rotation = FRAC_PI_2;
+    let corners = col.get_corners();
+
+    col.anchor = ColumnAnchor::BottomLeft;
+    assert_eq!(col.get_corners(), corners);
+
+    let pivot = col.anchor_point();
+    col.resize(80.0, 20.0);
+    assert_near(col.anchor_point(), pivot);
+    assert_eq!((col.width, col.height), (80.0, 20.0));
+}
+
+#[test]
+fn placement_uses_the_default_anchor() {
+    let mut h = Harness::new();
+    h.model
+        .definitions
+        .add_column_type(ColumnType::new(0, "C40x60", 40.0, 60.0, 0, 0, 0));
+    h.executor.defaults.column_anchor = ColumnAnchor::BottomLeft;
+    h.run("place_column; 100,100; 0");
+
+    let col = placed(&h);
+    assert_eq!(col.anchor, ColumnAnchor::BottomLeft);
+    assert_near(col.bounding_box().0, v(100.0, 100.0));
+}
+
+#[test]
+fn anchor_option_cycles_during_placement() {
+    let mut h = Harness::new();
+    h.model
+        .definitions
+        .add_column_type(ColumnType::new(0, "C40x60", 40.0, 60.0, 0, 0, 0));
+    h.run("place_column; a; 100,100; 0");
+
+    let col = placed(&h);
+    assert_eq!(col.anchor, ColumnAnchor::TopLeft);
+    assert_near(col.anchor_point(), v(100.0, 100.0));
+    assert_near(col.center, v(120.0, 70.0));
+}
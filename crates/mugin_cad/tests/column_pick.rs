@@ -0,0 +1,79 @@
+use mugin_cad::model::shapes::Geometry;
+use mugin_cad::model::structure::column::{ColumnAnchor, ColumnData};
+use mugin_cad::model::{CadModel, Entity, Vector2};
+
+const TOLERANCE: f32 = 1.0;
+
+/// 30 x 80 column at (100, 50), turned 30°
+fn column() -> ColumnData {
+    let mut column = ColumnData::new(
+        Vector2::new(100.0, 50.0),
+        30.0,
+        80.0,
+        1,
+        "C1".to_string(),
+        ColumnAnchor::Center,
+    );
+    column.rotation = 30f32.to_radians();
+    column
+}
+
+/// Middle of each side and the unit normal pointing out of it
+fn sides(column: &ColumnData) -> Vec<(Vector2, Vector2)> {
+    let corners = column.get_corners();
+    (0..4)
+        .map(|i| {
+            let (a, b) = (corners[i], corners[(i + 1) % 4]);
+            let mid = (a + b) * 0.5;
+            let out = mid - column.center;
+            (mid, out * (1.0 / out.length()))
+        })
+        .collect()
+}
+
+#[test]
+fn each_side_of_a_rotated_column_is_picked_where_it_is_drawn() {
+    let column = column();
+    for (mid, out) in sides(&column) {
+        assert!(
+            column.hit_test(mid - out * 0.5, TOLERANCE),
+            "just inside {:?}",
+            mid
+        );
+        assert!(
+            column.hit_test(mid + out * 0.5, TOLERANCE),
+            "on the edge band {:?}",
+            mid
+        );
+        assert!(
+            !column.hit_test(mid + out * 2.0, TOLERANCE),
+            "just outside {:?}",
+            mid
+        );
+    }
+    // The unrotated footprint reaches 40 up from the center; turned, that
+    // spot is well outside
+    assert!(!column.hit_test(Vector2::new(114.0, 89.0), TOLERANCE));
+}
+
+#[test]
+fn the_band_around_a_corner_is_round() {
+    let column = column();
+    for corner in column.get_corners() {
+        let out = corner - column.center;
+        let out = out * (1.0 / out.length());
+        assert!(column.hit_test(corner + out * 0.9, TOLERANCE));
+        assert!(!column.hit_test(corner + out * 1.2, TOLERANCE));
+    }
+}
+
+#[test]
+fn the_model_picks_a_rotated_column_by_its_footprint() {
+    let mut model = CadModel::new();
+    model.add_entity(Entity::column(column()));
+    model.update_hierarchy();
+    let id = model.entities[0].id;
+    let (mid, out) = sides(&column())[1];
+    assert_eq!(model.pick_entity_id(mid - out * 0.5, TOLERANCE), Some(id));
+    assert_eq!(model.pick_entity_id(mid + out * 2.0, TOLERANCE), None);
+}
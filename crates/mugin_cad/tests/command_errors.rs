@@ -0,0 +1,151 @@
+mod common;
+
+use common::Harness;
+use mugin_cad::commands::CommandError;
+
+#[test]
+fn unreadable_input_is_invalid_input_for_every_command() {
+    for command in [
+        "line",
+        "circle",
+        "rect",
+        "arc",
+        "axis",
+        "dim",
+        "stair",
+        "flooring",
+        "opening",
+        "place_beam",
+        "revcloud",
+        "symbol; north arrow",
+    ] {
+        let mut h = Harness::new();
+        h.run(command);
+        assert!(h.executor.last_error.is_none(), "{}", command);
+        h.run("zz");
+        assert!(
+            matches!(
+                &h.executor.last_error,
+                Some(CommandError::InvalidInput { input, .. }) if input == "zz"
+            ),
+            "{}: {:?}",
+            command,
+            h.executor.last_error
+        );
+        // The command keeps running and the next good input clears it
+        assert!(h.executor.is_active(), "{}", command);
+    }
+
+    let mut h = Harness::new();
+    h.run("line; zz; 0,0");
+    assert_eq!(h.executor.last_error, None);
+}
+
+#[test]
+fn unknown_names_and_empty_selections_are_told_apart() {
+    let mut h = Harness::new();
+    h.run("frobnicate");
+    assert_eq!(
+        h.executor.last_error,
+        Some(CommandError::UnknownCommand("frobnicate".to_string()))
+    );
+
+    for command in ["move", "rotate", "scale", "copy"] {
+        h.run(command);
+        assert_eq!(
+            h.executor.last_error,
+            Some(CommandError::NothingSelected { what: "entities" }),
+            "{}",
+            command
+        );
+        assert!(!h.executor.is_active());
+    }
+    h.run("offset");
+    assert_eq!(
+        h.executor.last_error,
+        Some(CommandError::NothingSelected { what: "lines" })
+    );
+    h.run("calibrate");
+    assert_eq!(
+        h.executor.last_error,
+        Some(CommandError::NothingSelected { what: "image" })
+    );
+    assert_eq!(
+        h.executor.status_message,
+        "No image selected. Select an image first."
+    );
+
+    h.run("sym; compass rose");
+    assert_eq!(
+        h.executor.last_error,
+        Some(CommandError::NotFound {
+            kind: "symbol",
+            name: "compass rose".to_string()
+        })
+    );
+}
+
+#[test]
+fn values_out_of_range_and_missing_steps_have_their_own_variants() {
+    let mut h = Harness::new();
+    h.run("revcloud; a; -5");
+    assert!(matches!(
+        h.executor.last_error,
+        Some(CommandError::InvalidValue(_))
+    ));
+
+    h.run(";line; 0,0; 10,0;");
+    h.select(h.ids());
+    h.run("offset; -3");
+    assert!(matches!(
+        h.executor.last_error,
+        Some(CommandError::InvalidValue(_))
+    ));
+
+    h.run(";text; hello");
+    assert!(matches!(
+        h.executor.last_error,
+        Some(CommandError::MissingPrerequisite(_))
+    ));
+
+    h.run(";label");
+    assert!(matches!(
+        h.executor.last_error,
+        Some(CommandError::MissingPrerequisite(_))
+    ));
+}
+
+#[test]
+fn picks_that_hit_nothing_are_rejected_and_the_step_repeats() {
+    let mut h = Harness::new();
+    h.run("trim");
+    h.click(500.0, 500.0);
+    assert_eq!(
+        h.executor.last_error,
+        Some(CommandError::NothingThere { what: "line" })
+    );
+    assert!(h.executor.is_active());
+    assert!(
+        h.executor
+            .status_message
+            .ends_with("Click on a line to trim:")
+    );
+
+    h.run(";revcloud; o");
+    h.click(500.0, 500.0);
+    assert_eq!(
+        h.executor.last_error,
+        Some(CommandError::NothingThere {
+            what: "closed shape"
+        })
+    );
+
+    // No column types are defined in a new drawing
+    h.run(";place_column");
+    h.click(0.0, 0.0);
+    assert!(matches!(
+        h.executor.last_error,
+        Some(CommandError::MissingPrerequisite(_))
+    ));
+    assert!(h.ids().is_empty());
+}
@@ -0,0 +1,165 @@
+//! The action registry, its fuzzy search and the Ctrl+P palette's dispatch.
+
+#![cfg(feature = "gui")]
+
+use mugin_cad::commands::executor::CommandRegistry;
+use mugin_cad::model::tools::fuzzy::fuzzy_match;
+use mugin_cad::model::{Entity, Vector2};
+use mugin_cad::view::ui::toolbar::{TOOLBAR, ToolbarSpec};
+use mugin_cad::viewmodel::{ACTIONS, AppAction, CadViewModel, SettingsPage, find_action};
+use std::collections::HashSet;
+
+fn top_ids(vm: &CadViewModel, query: &str) -> Vec<&'static str> {
+    vm.search_actions(query)
+        .iter()
+        .take(3)
+        .map(|found| found.spec.id)
+        .collect()
+}
+
+#[test]
+fn fuzzy_matching_prefers_word_starts_and_runs() {
+    let found = fuzzy_match("rc", "Revision Cloud").unwrap();
+    assert_eq!(found.positions, vec![0, 9]);
+    assert!(found.score > fuzzy_match("rc", "Rectangle").unwrap().score);
+
+    // Case and query spaces don't matter; order does
+    assert_eq!(
+        fuzzy_match("ZOOM ext", "Zoom Extents").unwrap().positions,
+        vec![0, 1, 2, 3, 5, 6, 7]
+    );
+    assert!(fuzzy_match("nil", "Line").is_none());
+    assert_eq!(fuzzy_match("", "Line").unwrap().score, 0);
+
+    // A run beats the same letters spread out
+    assert!(
+        fuzzy_match("lin", "Line").unwrap().score
+            > fuzzy_match("lin", "Label Last Measurement").map_or(0, |m| m.score)
+    );
+}
+
+#[test]
+fn registry_ids_are_unique_and_cover_the_toolbar() {
+    let mut ids = HashSet::new();
+    for spec in ACTIONS {
+        assert!(ids.insert(spec.id), "{} listed twice", spec.id);
+    }
+    for spec in TOOLBAR {
+        let tools = match spec {
+            ToolbarSpec::Tool(tool) => std::slice::from_ref(tool),
+            ToolbarSpec::Flyout { tools, .. } => tools,
+            _ => continue,
+        };
+        for tool in tools {
+            let action = find_action(tool.command).expect(tool.command);
+            // Tools starting a command in one of its modes name the mode
+            if tool.option.is_none() {
+                assert_eq!(tool.label(), action.label);
+            }
+        }
+    }
+    // Every command the registry types in starts a command or is handled
+    // by the terminal itself
+    let commands = CommandRegistry::new();
+    let immediate = [
+        "delete",
+        "cleanup",
+        "rooms",
+        "fill",
+        "undo",
+        "redo",
+        "previous",
+        "ze",
+        "audit",
+        "audit fix",
+    ];
+    for spec in ACTIONS {
+        if let AppAction::Terminal(name) = spec.action {
+            assert!(
+                immediate.contains(&name) || commands.contains(name),
+                "{}",
+                name
+            );
+        }
+    }
+}
+
+#[test]
+fn search_ranks_labels_and_terminal_names() {
+    let vm = CadViewModel::new();
+    assert_eq!(vm.search_actions("").len(), ACTIONS.len());
+    assert_eq!(top_ids(&vm, "revision")[0], "revcloud");
+    // Word starts tie with "Rotate 90° CW" but beat "Rectangle"
+    assert!(top_ids(&vm, "rc").contains(&"revcloud"));
+    assert!(!top_ids(&vm, "rc").contains(&"rect"));
+    // Found by its terminal name too
+    assert_eq!(top_ids(&vm, "perim")[0], "perim");
+    assert_eq!(top_ids(&vm, "dim")[0], "settings_dimensions");
+    assert!(vm.search_actions("qqq").is_empty());
+}
+
+#[test]
+fn shortcuts_come_from_bindings_and_aliases() {
+    let mut vm = CadViewModel::new();
+    let shortcut = |vm: &CadViewModel, id| vm.action_shortcut(find_action(id).unwrap());
+    assert_eq!(shortcut(&vm, "line").as_deref(), Some("L"));
+    assert_eq!(shortcut(&vm, "copy").as_deref(), Some("Ctrl+C"));
+    assert_eq!(shortcut(&vm, "command_palette").as_deref(), Some("Ctrl+P"));
+    assert_eq!(shortcut(&vm, "arc"), None);
+
+    vm.config.gui_config.quick_transform_keys.rotate_cw = "Ctrl+Shift+R".to_string();
+    assert_eq!(shortcut(&vm, "rotate_cw").as_deref(), Some("Ctrl+Shift+R"));
+    vm.config.gui_config.quick_transform_keys.rotate_cw.clear();
+    assert_eq!(shortcut(&vm, "rotate_cw"), None);
+}
+
+#[test]
+fn running_a_command_types_its_name() {
+    let mut vm = CadViewModel::new();
+    let line = vm.search_actions("line")[0].spec;
+    vm.run_action(line);
+    assert_eq!(vm.active_tab().executor.active_command_name(), Some("LINE"));
+    assert_eq!(vm.command_history.last().unwrap().text, "line");
+
+    // Commands needing a selection wait for one
+    vm.escape();
+    let rotate = find_action("rotate").unwrap();
+    assert!(!vm.action_enabled(rotate));
+    vm.run_action(rotate);
+    assert_eq!(vm.active_tab().executor.active_command_name(), None);
+
+    let entity = Entity::line(Vector2::new(0.0, 0.0), Vector2::new(10.0, 0.0));
+    let id = entity.id;
+    vm.active_tab_mut().model.add_entity(entity);
+    vm.active_tab_mut()
+        .selection_manager
+        .selected_ids
+        .insert(id);
+    assert!(vm.action_enabled(rotate));
+    vm.run_action(rotate);
+    assert_eq!(
+        vm.active_tab().executor.active_command_name(),
+        Some("ROTATE")
+    );
+}
+
+#[test]
+fn actions_open_windows_and_settings_pages() {
+    let mut vm = CadViewModel::new();
+    vm.run_action(find_action("settings_toolbar").unwrap());
+    assert!(vm.show_settings_window);
+    assert_eq!(vm.settings_page, Some(SettingsPage::Toolbar));
+
+    vm.run_action(find_action("calc").unwrap());
+    assert!(vm.calculator_window.open);
+
+    let palette = find_action("command_palette").unwrap();
+    vm.run_action(palette);
+    assert!(vm.command_palette.open);
+    vm.command_palette.query = "line".to_string();
+    // The shortcut again closes it
+    vm.run_action(palette);
+    assert!(!vm.command_palette.open);
+    vm.run_action(palette);
+    assert!(vm.command_palette.query.is_empty());
+}
@@ -0,0 +1,170 @@
+mod common;
+
+use common::{Harness, assert_near};
+use mugin_cad::model::Vector2;
+
+fn ends(h: &Harness, id: u64) -> Vec<Vector2> {
+    h.entity(id).as_polyline()
+}
+
+#[test]
+fn line_move_undo_redo() {
+    let mut h = Harness::new();
+    h.run("line; 0,0; 10,0;");
+    assert_eq!(h.ids().len(), 1);
+    let line = h.ids()[0];
+
+    h.select([line]);
+    h.run("move; 0,0; 5,5");
+    assert!(!h.executor.is_active());
+    let moved = ends(&h, line);
+    assert_near(moved[0], Vector2::new(5.0, 5.0));
+    assert_near(moved[1], Vector2::new(15.0, 5.0));
+
+    h.run("undo");
+    let back = ends(&h, line);
+    assert_near(back[0], Vector2::new(0.0, 0.0));
+    assert_near(back[1], Vector2::new(10.0, 0.0));
+
+    h.run("undo");
+    assert!(h.ids().is_empty());
+
+    h.run("redo; redo");
+    assert_near(ends(&h, line)[0], Vector2::new(5.0, 5.0));
+}
+
+#[test]
+fn line_chain_is_one_undo_step() {
+    let mut h = Harness::new();
+    h.run("line; 0,0; 10,0; 10,10; 0,10;");
+    assert_eq!(h.ids().len(), 3);
+    h.run("undo");
+    assert!(h.ids().is_empty());
+}
+
+#[test]
+fn line_undo_option_removes_last_segment() {
+    let mut h = Harness::new();
+    h.run("line; 0,0; 10,0; 10,10; u");
+    // "u" is LINE's own option here, not the global undo
+    assert!(h.executor.is_active());
+    assert_eq!(h.ids().len(), 1);
+    h.run("5,5;");
+    assert_eq!(h.ids().len(), 2);
+}
+
+#[test]
+fn line_undo_stays_within_the_chain_after_a_cancelled_step() {
+    let mut h = Harness::new();
+    h.run("line; 0,0; 10,0");
+    let first = h.ids()[0];
+    assert!(h.executor.cancel_step());
+
+    h.run("20,0; 30,0; 40,0; u; u");
+    assert!(h.executor.is_active());
+    assert_eq!(h.ids(), vec![first]);
+}
+
+#[test]
+fn clicks_and_typed_points_mix() {
+    let mut h = Harness::new();
+    h.run("line");
+    h.click(0.0, 0.0);
+    h.input("3,4");
+    h.input("");
+    let line = h.ids()[0];
+    assert_near(ends(&h, line)[1], Vector2::new(3.0, 4.0));
+}
+
+#[test]
+fn move_needs_a_selection() {
+    let mut h = Harness::new();
+    h.run("move");
+    assert!(!h.executor.is_active());
+}
+
+#[test]
+fn offset_by_typed_distance() {
+    let mut h = Harness::new();
+    h.run("line; 0,0; 10,0;");
+    let line = h.ids()[0];
+    h.select([line]);
+    h.run("offset; 2; 5,-7");
+    let ids = h.ids();
+    assert_eq!(ids.len(), 2);
+    let copy = ends(&h, ids[1]);
+    assert_near(copy[0], Vector2::new(0.0, -2.0));
+    assert_near(copy[1], Vector2::new(10.0, -2.0));
+}
+
+#[test]
+fn transform_commands_preview_where_the_cursor_puts_the_selection() {
+    let mut h = Harness::new();
+    h.run("line; 0,0; 10,0;");
+    let line = h.ids()[0];
+    h.select([line]);
+
+    // Nothing to preview before the base point
+    h.run("move");
+    assert!(
+        h.executor
+            .transform_preview(Vector2::new(3.0, 4.0))
+            .is_none()
+    );
+    h.run("0,0");
+    let preview = h
+        .executor
+        .transform_preview(Vector2::new(3.0, 4.0))
+        .unwrap();
+    assert_eq!(preview.entity_ids, vec![line]);
+    assert!(!preview.copy);
+
+    // The preview is what the click then does
+    let mut ghost = h.entity(line).clone();
+    preview.transform.apply(&mut ghost);
+    ghost.update_transforms(glam::Affine2::IDENTITY);
+    h.run("3,4");
+    assert_near(ghost.as_polyline()[1], ends(&h, line)[1]);
+    assert!(
+        h.executor
+            .transform_preview(Vector2::new(3.0, 4.0))
+            .is_none()
+    );
+
+    h.run("scale; 0,0; c");
+    let preview = h
+        .executor
+        .transform_preview(Vector2::new(2.0, 0.0))
+        .unwrap();
+    assert!(preview.copy);
+
+    h.input("");
+    h.run("mirror; 0,0");
+    let preview = h
+        .executor
+        .transform_preview(Vector2::new(0.0, 1.0))
+        .unwrap();
+    assert!(preview.copy);
+}
+
+#[test]
+fn mirror_keeps_the_source_unless_erased() {
+    let mut h = Harness::new();
+    h.run("line; 1,0; 10,5;");
+    let line = h.ids()[0];
+
+    h.select([line]);
+    h.run("mirror; 0,0; 0,1");
+    assert!(!h.executor.is_active());
+    let ids = h.ids();
+    assert_eq!(ids.len(), 2);
+    assert_near(ends(&h, line)[1], Vector2::new(10.0, 5.0));
+    let copy = ends(&h, ids[1]);
+    assert_near(copy[0], Vector2::new(-1.0, 0.0));
+    assert_near(copy[1], Vector2::new(-10.0, 5.0));
+
+    h.select([line]);
+    h.run("mirror; e; 0,0; 1,0");
+    assert_eq!(h.ids().len(), 2);
+    assert_near(ends(&h, line)[1], Vector2::new(10.0, -5.0));
+}
@@ -0,0 +1,162 @@
+//! Headless driver for command flows: feeds terminal input and canvas
+//! clicks to a `CommandExecutor` the way the view model does, with the same
+//! one-undo-step-per-command-run rule.
+
+#![allow(dead_code)]
+
+use mugin_cad::commands::executor::CommandExecutor;
+use mugin_cad::model::tools::undo::UndoManager;
+use mugin_cad::model::{CadModel, Entity, Vector2};
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+
+pub struct Harness {
+    pub model: CadModel,
+    pub executor: CommandExecutor,
+    pub selection: HashSet<u64>,
+    undo: UndoManager,
+    /// Command run the last undo snapshot was taken for
+    undo_run: Option<u64>,
+}
+
+impl Harness {
+    pub fn new() -> Self {
+        Self {
+            model: CadModel::new(),
+            executor: CommandExecutor::new(),
+            selection: HashSet::new(),
+            undo: UndoManager::new(50),
+            undo_run: None,
+        }
+    }
+
+    /// Run terminal inputs separated by `;`, e.g. `"line; 0,0; 10,0;"`.
+    /// An empty entry presses Enter.
+    pub fn run(&mut self, script: &str) {
+        for input in script.split(';') {
+            self.input(input);
+        }
+    }
+
+    /// One line typed into the terminal
+    pub fn input(&mut self, input: &str) {
+        let input = input.trim();
+        if input.is_empty() {
+            self.executor.cancel();
+            return;
+        }
+        let clean = input.to_lowercase();
+        if !self.executor.is_option(&clean) && !self.executor.takes_text() {
+            match clean.as_str() {
+                "u" | "undo" => {
+                    self.undo();
+                    return;
+                }
+                "redo" => {
+                    self.redo();
+                    return;
+                }
+                _ => {}
+            }
+        }
+        self.save_command_undo_state();
+        self.executor
+            .process_input(input, &mut self.model, &self.selection);
+        self.model.update_hierarchy();
+        self.model.resolve_attachments();
+        self.model
+            .refresh_junctions(self.executor.defaults.auto_cleanup);
+    }
+
+    /// A click on the canvas at world position `(x, y)`
+    pub fn click(&mut self, x: f32, y: f32) {
+        self.save_command_undo_state();
+        self.executor
+            .push_point(Vector2::new(x, y), &mut self.model, &self.selection);
+        self.model.update_hierarchy();
+        self.model.resolve_attachments();
+        self.model
+            .refresh_junctions(self.executor.defaults.auto_cleanup);
+    }
+
+    pub fn select(&mut self, ids: impl IntoIterator<Item = u64>) {
+        self.selection = ids.into_iter().collect();
+    }
+
+    pub fn undo(&mut self) -> bool {
+        self.undo_run = None;
+        let Some(state) = self.undo.undo(&self.model.entities, &self.selection) else {
+            return false;
+        };
+        self.restore(state.entities, state.selection);
+        true
+    }
+
+    pub fn redo(&mut self) -> bool {
+        self.undo_run = None;
+        let Some(state) = self.undo.redo(&self.model.entities, &self.selection) else {
+            return false;
+        };
+        self.restore(state.entities, state.selection);
+        true
+    }
+
+    /// Ids of the top-level entities, in drawing order
+    pub fn ids(&self) -> Vec<u64> {
+        self.model.entities.iter().map(|entity| entity.id).collect()
+    }
+
+    pub fn entity(&self, id: u64) -> &Entity {
+        self.model
+            .find_by_id(id)
+            .unwrap_or_else(|| panic!("no entity {}", id))
+    }
+
+    fn save_command_undo_state(&mut self) {
+        let Some(run) = self.executor.active_run() else {
+            return;
+        };
+        if self.undo_run != Some(run) {
+            self.undo.save_state(&self.model.entities, &self.selection);
+            self.undo_run = Some(run);
+        }
+    }
+
+    fn restore(&mut self, entities: Vec<Entity>, selection: HashSet<u64>) {
+        self.model.entities = entities;
+        self.model.changes.touch_all();
+        self.selection = selection;
+        self.model.update_hierarchy();
+        self.model.resolve_attachments();
+        self.model
+            .refresh_junctions(self.executor.defaults.auto_cleanup);
+    }
+}
+
+/// Assert two points are within `1e-4` of each other
+#[track_caller]
+pub fn assert_near(actual: Vector2, expected: Vector2) {
+    assert!(
+        actual.dist(expected) < 1e-4,
+        "expected {:?}, got {:?}",
+        expected,
+        actual
+    );
+}
+
+/// Shorthand for `Vector2::new`
+pub fn v(x: f32, y: f32) -> Vector2 {
+    Vector2::new(x, y)
+}
+
+/// An empty scratch directory for one test, under the target dir and
+/// apart from those of other test files
+pub fn scratch_dir(name: &str) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR"))
+        .join(env!("CARGO_CRATE_NAME"))
+        .join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
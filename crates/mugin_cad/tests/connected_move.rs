@@ -0,0 +1,103 @@
+mod common;
+
+use common::{Harness, assert_near, v};
+use mugin_cad::commands::InputModifiers;
+use mugin_cad::model::tools::spatial::PointIndex;
+use mugin_cad::model::{Shape, Vector2};
+
+/// A 200x100 room outline drawn as four lines: bottom, right, top, left
+fn room() -> (Harness, Vec<u64>) {
+    let mut h = Harness::new();
+    h.run("line; 0,0; 200,0;");
+    h.run("line; 200,0; 200,100;");
+    h.run("line; 200,100; 0,100;");
+    h.run("line; 0,100; 0,0;");
+    let ids = h.ids();
+    (h, ids)
+}
+
+fn ends(h: &Harness, id: u64) -> (Vector2, Vector2) {
+    let points = h.entity(id).as_polyline();
+    (points[0], points[1])
+}
+
+#[test]
+fn neighbors_follow_a_moved_line() {
+    let (mut h, ids) = room();
+    h.executor.defaults.connected_move = true;
+    h.select([ids[2]]);
+    h.run("move; 0,0; 0,50");
+
+    assert_eq!(ends(&h, ids[2]), (v(200.0, 150.0), v(0.0, 150.0)));
+    assert_eq!(ends(&h, ids[1]), (v(200.0, 0.0), v(200.0, 150.0)));
+    assert_eq!(ends(&h, ids[3]), (v(0.0, 150.0), v(0.0, 0.0)));
+    assert_eq!(ends(&h, ids[0]), (v(0.0, 0.0), v(200.0, 0.0)));
+    assert_eq!(
+        h.executor.status_message,
+        "Connected move: 2 neighbors adjusted"
+    );
+}
+
+#[test]
+fn ctrl_inverts_the_setting() {
+    let (mut h, ids) = room();
+    h.select([ids[1]]);
+    h.run("move; 0,0; 50,0");
+    assert_eq!(ends(&h, ids[0]).1, v(200.0, 0.0));
+
+    h.undo();
+    h.executor.set_modifiers(InputModifiers {
+        ctrl: true,
+        ..Default::default()
+    });
+    h.run("move; 0,0; 50,0");
+    assert_eq!(ends(&h, ids[0]).1, v(250.0, 0.0));
+    assert_eq!(ends(&h, ids[2]).0, v(250.0, 100.0));
+}
+
+#[test]
+fn one_undo_restores_the_neighbors() {
+    let (mut h, ids) = room();
+    h.executor.defaults.connected_move = true;
+    h.select([ids[2]]);
+    h.run("move; 0,0; 30,30");
+    assert!(h.undo());
+    assert_eq!(ends(&h, ids[1]), (v(200.0, 0.0), v(200.0, 100.0)));
+    assert_eq!(ends(&h, ids[2]), (v(200.0, 100.0), v(0.0, 100.0)));
+}
+
+#[test]
+fn neighbors_are_adjusted_in_world_space() {
+    let (mut h, ids) = room();
+    // Shift the left wall's frame so its own and world ends differ
+    let wall = h.model.find_by_id_mut(ids[3]).unwrap();
+    wall.translate(v(10.0, 0.0));
+    wall.modify(|shape| {
+        if let Shape::Line(line) = shape {
+            line.start = v(-10.0, 100.0);
+            line.end = v(-10.0, 0.0);
+        }
+    });
+    h.model.update_hierarchy();
+    let moved = h.model.line_ends(&[ids[0]]);
+    assert_eq!(moved, vec![v(0.0, 0.0), v(200.0, 0.0)]);
+
+    let delta = v(0.0, -20.0);
+    h.model.find_by_id_mut(ids[0]).unwrap().translate(delta);
+    assert_eq!(h.model.drag_connected(&moved, delta, &[ids[0]]), 2);
+    h.model.update_hierarchy();
+    assert_near(ends(&h, ids[3]).1, v(0.0, -20.0));
+    assert_near(ends(&h, ids[1]).0, v(200.0, -20.0));
+}
+
+#[test]
+fn point_index_finds_points_across_cells() {
+    let mut index = PointIndex::new(1.0);
+    index.insert(v(0.95, 0.0), 'a');
+    index.insert(v(1.05, 0.0), 'b');
+    index.insert(v(3.0, 0.0), 'c');
+    let mut near: Vec<char> = index.near(v(1.0, 0.0), 0.1).map(|(_, &c)| c).collect();
+    near.sort();
+    assert_eq!(near, vec!['a', 'b']);
+    assert_eq!(index.near(v(-3.0, 0.0), 0.5).count(), 0);
+}
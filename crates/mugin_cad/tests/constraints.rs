@@ -0,0 +1,222 @@
+//! Parametric constraints: properties driven by expressions over other
+//! entities, evaluated in dependency order.
+
+mod common;
+
+use common::{Harness, v};
+use mugin_cad::model::tools::constraints::{ConstraintError, property, reference_name};
+use mugin_cad::model::{Entity, Shape};
+
+/// Add a named entity, returning its ID
+fn add(h: &mut Harness, name: &str, mut entity: Entity) -> u64 {
+    entity.name = name.to_string();
+    let id = entity.id;
+    h.model.add_entity(entity);
+    id
+}
+
+fn constrain(h: &mut Harness, id: u64, property: &str, expression: &str) {
+    h.model
+        .find_by_id_mut(id)
+        .unwrap()
+        .constraints
+        .insert(property.to_string(), expression.to_string());
+}
+
+fn value(h: &Harness, id: u64, name: &str) -> f32 {
+    property(&h.entity(id).shape, name).unwrap()
+}
+
+#[test]
+fn names_become_references() {
+    assert_eq!(reference_name("Rectangle 1"), "Rectangle_1");
+    assert_eq!(reference_name("Wall-A"), "Wall_A");
+    assert_eq!(reference_name("Grid.B"), "Grid.B");
+    assert_eq!(reference_name("1st floor"), "_1st_floor");
+}
+
+#[test]
+fn chains_are_evaluated_sources_first() {
+    let mut h = Harness::new();
+    // Added last-to-first, so file order alone would read stale values
+    let c = add(&mut h, "C", Entity::circle(v(0.0, 0.0), 1.0, false));
+    let b = add(&mut h, "Wall B", Entity::line(v(0.0, 0.0), v(3.0, 4.0)));
+    let a = add(
+        &mut h,
+        "Rectangle 1",
+        Entity::rectangle(v(0.0, 0.0), v(10.0, 4.0), false),
+    );
+    constrain(&mut h, c, "radius", "Wall_B.length / 2");
+    constrain(&mut h, b, "length", "Rectangle_1.width * 2");
+    constrain(&mut h, a, "width", "12");
+    h.model.apply_constraints();
+
+    assert_eq!(value(&h, a, "width"), 12.0);
+    assert_eq!(value(&h, a, "height"), 4.0);
+    assert_eq!(value(&h, b, "length"), 24.0);
+    // The line keeps its start and direction
+    match &h.entity(b).shape {
+        Shape::Line(line) => {
+            assert_eq!(line.start, v(0.0, 0.0));
+            assert!(line.end.dist(v(14.4, 19.2)) < 1e-4);
+        }
+        other => panic!("expected a line, got {:?}", other),
+    }
+    assert_eq!(value(&h, c, "radius"), 12.0);
+    assert_eq!(h.model.constraint_warning(c), None);
+
+    // Editing the source carries down the chain
+    constrain(&mut h, a, "width", "5");
+    h.model.apply_constraints();
+    assert_eq!(value(&h, b, "length"), 10.0);
+    assert_eq!(value(&h, c, "radius"), 5.0);
+}
+
+#[test]
+fn entities_can_be_referred_to_by_id() {
+    let mut h = Harness::new();
+    let a = add(&mut h, "A", Entity::circle(v(0.0, 0.0), 2.5, false));
+    let b = add(&mut h, "B", Entity::circle(v(10.0, 0.0), 1.0, false));
+    constrain(
+        &mut h,
+        b,
+        "radius",
+        &format!("id{}.radius * 2 + pi - pi", a),
+    );
+    h.model.apply_constraints();
+    assert!((value(&h, b, "radius") - 5.0).abs() < 1e-5);
+}
+
+#[test]
+fn settled_constraints_leave_the_model_alone() {
+    let mut h = Harness::new();
+    let a = add(&mut h, "A", Entity::circle(v(0.0, 0.0), 2.0, false));
+    let b = add(&mut h, "B", Entity::circle(v(10.0, 0.0), 1.0, false));
+    constrain(&mut h, b, "radius", "A.radius");
+    h.model.apply_constraints();
+    assert_eq!(value(&h, b, "radius"), 2.0);
+
+    // A touch that changes nothing writes nothing back
+    let geometry = h.entity(b).revision();
+    h.model.find_by_id_mut(a);
+    let touched = h.model.changes.revision();
+    h.model.apply_constraints();
+    assert_eq!(h.model.changes.revision(), touched);
+    assert_eq!(h.entity(b).revision(), geometry);
+}
+
+#[test]
+fn cycles_are_reported_and_left_unchanged() {
+    let mut h = Harness::new();
+    let a = add(&mut h, "A", Entity::circle(v(0.0, 0.0), 1.0, false));
+    let b = add(&mut h, "B", Entity::circle(v(10.0, 0.0), 2.0, false));
+    let c = add(&mut h, "C", Entity::circle(v(20.0, 0.0), 3.0, false));
+    let d = add(&mut h, "D", Entity::circle(v(30.0, 0.0), 4.0, false));
+    constrain(&mut h, a, "radius", "B.radius + 1");
+    constrain(&mut h, b, "radius", "C.radius + 1");
+    constrain(&mut h, c, "radius", "A.radius + 1");
+    // Fed by the cycle, so it can't be ordered either
+    constrain(&mut h, d, "radius", "A.radius");
+    h.model.apply_constraints();
+
+    for (id, radius) in [(a, 1.0), (b, 2.0), (c, 3.0), (d, 4.0)] {
+        assert_eq!(value(&h, id, "radius"), radius);
+        assert_eq!(
+            h.model.constraint_error(id, "radius"),
+            Some(&ConstraintError::Cycle)
+        );
+    }
+
+    // A property referring to itself is a cycle too
+    let e = add(&mut h, "E", Entity::circle(v(40.0, 0.0), 5.0, false));
+    constrain(&mut h, e, "radius", "E.radius * 2");
+    h.model.apply_constraints();
+    assert_eq!(value(&h, e, "radius"), 5.0);
+    assert_eq!(
+        h.model.constraint_error(e, "radius"),
+        Some(&ConstraintError::Cycle)
+    );
+
+    // Breaking the loop lets the rest through
+    h.model.find_by_id_mut(c).unwrap().constraints.clear();
+    h.model.apply_constraints();
+    assert_eq!(value(&h, b, "radius"), 4.0);
+    assert_eq!(value(&h, a, "radius"), 5.0);
+    assert_eq!(value(&h, d, "radius"), 5.0);
+    assert_eq!(h.model.constraint_warning(a), None);
+}
+
+#[test]
+fn broken_references_mark_the_entity() {
+    let mut h = Harness::new();
+    let a = add(&mut h, "A", Entity::circle(v(0.0, 0.0), 1.0, false));
+    let twin = add(&mut h, "Twin", Entity::circle(v(5.0, 0.0), 1.0, false));
+    add(&mut h, "Twin", Entity::circle(v(9.0, 0.0), 1.0, false));
+    let r = add(
+        &mut h,
+        "R",
+        Entity::rectangle(v(0.0, 0.0), v(2.0, 2.0), false),
+    );
+
+    let cases = [
+        (
+            "Missing.radius",
+            ConstraintError::UnknownEntity("Missing".into()),
+        ),
+        ("Twin.radius", ConstraintError::AmbiguousName("Twin".into())),
+        (
+            "A.width",
+            ConstraintError::UnknownProperty("A.width".into()),
+        ),
+        ("A.radius - 1", ConstraintError::NotPositive(0.0)),
+    ];
+    for (expression, error) in cases {
+        constrain(&mut h, r, "width", expression);
+        h.model.apply_constraints();
+        assert_eq!(
+            h.model.constraint_error(r, "width"),
+            Some(&error),
+            "{}",
+            expression
+        );
+        assert_eq!(value(&h, r, "width"), 2.0);
+    }
+    constrain(&mut h, r, "width", "A.radius +");
+    constrain(&mut h, r, "depth", "1");
+    h.model.apply_constraints();
+    assert!(matches!(
+        h.model.constraint_error(r, "width"),
+        Some(ConstraintError::Expression(_))
+    ));
+    let warning = h.model.constraint_warning(r).unwrap();
+    assert_eq!(warning.lines().count(), 2);
+    assert!(warning.starts_with("depth: Unknown property"));
+
+    // Clearing up the name fixes it
+    constrain(&mut h, r, "width", "Twin.radius * 3");
+    h.model.entities.retain(|e| e.id != twin);
+    h.model
+        .find_by_id_mut(r)
+        .unwrap()
+        .constraints
+        .remove("depth");
+    h.model.apply_constraints();
+    assert_eq!(h.model.constraint_warning(r), None);
+    assert_eq!(value(&h, r, "width"), 3.0);
+    assert_eq!(h.model.constraint_warning(a), None);
+}
+
+#[test]
+fn constraints_are_saved_with_the_entity() {
+    let mut entity = Entity::circle(v(0.0, 0.0), 1.0, false);
+    let plain = serde_json::to_string(&entity).unwrap();
+    assert!(!plain.contains("constraints"));
+
+    entity
+        .constraints
+        .insert("radius".into(), "Other.radius / 2".into());
+    let json = serde_json::to_string(&entity).unwrap();
+    let loaded: Entity = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.constraints, entity.constraints);
+    assert!(loaded.same_content(&entity));
+}
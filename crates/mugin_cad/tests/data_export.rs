@@ -0,0 +1,114 @@
+mod common;
+
+use common::v;
+use mugin_cad::export::data::{
+    COLUMNS, DataExportOptions, DataRow, csv_fields, data_csv, data_json, data_rows, json_object,
+};
+use mugin_cad::model::{CadModel, Entity, TextAnnotation};
+use serde_json::json;
+
+/// CSV value of the named column
+fn field(row: &DataRow, column: &str) -> String {
+    let index = COLUMNS.iter().position(|c| *c == column).unwrap();
+    csv_fields(row)[index].clone()
+}
+
+fn export_rows(model: &CadModel, selected: &[u64], options: DataExportOptions) -> Vec<DataRow> {
+    data_rows(model, &selected.iter().copied().collect(), &options)
+}
+
+#[test]
+fn columns_per_shape() {
+    let mut model = CadModel::new();
+    model.add_entity(Entity::line(v(1.0, 2.0), v(3.0, 4.0)));
+    model.add_entity(Entity::circle(v(5.0, 6.0), 7.0, false));
+    model.add_entity(Entity::rectangle(v(0.0, 0.0), v(2.0, 1.0), false));
+    model.add_entity(Entity::text(TextAnnotation::new_custom(
+        v(8.0, 9.0),
+        "A, \"quoted\"".to_string(),
+    )));
+    model.update_hierarchy();
+    let rows = export_rows(&model, &[], DataExportOptions::default());
+    assert_eq!(rows.len(), 4);
+
+    let line = &rows[0];
+    assert_eq!(field(line, "type"), "Line");
+    assert_eq!(
+        ["x1", "y1", "x2", "y2"].map(|c| field(line, c)),
+        ["1", "2", "3", "4"]
+    );
+    assert_eq!(field(line, "r"), "");
+
+    let circle = &rows[1];
+    assert_eq!(["cx", "cy", "r"].map(|c| field(circle, c)), ["5", "6", "7"]);
+    assert_eq!(field(circle, "x1"), "");
+
+    let rect = &rows[2];
+    assert_eq!(field(rect, "corners").split(';').count(), 4);
+
+    let text = &rows[3];
+    assert_eq!(field(text, "label"), "A, \"quoted\"");
+    assert_eq!(field(text, "x1"), "8");
+    assert!(data_csv(&rows).contains("\"A, \"\"quoted\"\"\""));
+}
+
+#[test]
+fn json_fields() {
+    let mut model = CadModel::new();
+    let line = Entity::line(v(1.0, 2.0), v(3.0, 4.0));
+    let id = line.id;
+    model.add_entity(line);
+    model.update_hierarchy();
+    let rows = export_rows(&model, &[], DataExportOptions::default());
+    let object = json_object(&rows[0]);
+    assert_eq!(object["id"], json!(id));
+    assert_eq!(object["type"], json!("Line"));
+    assert_eq!(object["parent_id"], json!(null));
+    assert_eq!(object["x2"], json!(3.0));
+    assert!(object.get("r").is_none());
+}
+
+fn grouped_model() -> (CadModel, u64, u64, u64) {
+    let mut model = CadModel::new();
+    let mut group = Entity::empty("Group");
+    let child = Entity::line(v(0.0, 0.0), v(1.0, 0.0));
+    let child_id = child.id;
+    group.children.push(child);
+    let group_id = group.id;
+    model.add_entity(group);
+    let other = Entity::circle(v(0.0, 0.0), 1.0, false);
+    let other_id = other.id;
+    model.add_entity(other);
+    model.update_hierarchy();
+    (model, group_id, child_id, other_id)
+}
+
+#[test]
+fn selected_only_includes_children() {
+    let (model, group_id, child_id, _) = grouped_model();
+    let options = DataExportOptions {
+        selected_only: true,
+        ..Default::default()
+    };
+    let rows = export_rows(&model, &[group_id], options);
+    let ids: Vec<u64> = rows.iter().map(|row| row.id).collect();
+    assert_eq!(ids, [group_id, child_id]);
+    assert_eq!(rows[1].parent_id, Some(group_id));
+}
+
+#[test]
+fn flatten_drops_containers() {
+    let (model, group_id, child_id, other_id) = grouped_model();
+    let options = DataExportOptions {
+        flatten: true,
+        ..Default::default()
+    };
+    let rows = export_rows(&model, &[], options);
+    let ids: Vec<u64> = rows.iter().map(|row| row.id).collect();
+    assert_eq!(ids, [child_id, other_id]);
+    assert!(!ids.contains(&group_id));
+
+    let nested = data_json(&export_rows(&model, &[], Default::default()), false);
+    let nested: serde_json::Value = serde_json::from_str(&nested).unwrap();
+    assert_eq!(nested[0]["children"][0]["id"], json!(child_id));
+}
@@ -0,0 +1,149 @@
+//! Geometry below the limits is refused by the commands drawing it, and
+//! found and removed in an existing drawing.
+
+mod common;
+
+use common::Harness;
+use mugin_cad::commands::CommandError;
+use mugin_cad::model::tools::degenerate::GeometryLimits;
+use mugin_cad::model::{Entity, Shape, Vector2};
+
+fn refused(h: &Harness) -> String {
+    match &h.executor.last_error {
+        Some(error @ CommandError::Degenerate(_)) => error.to_string(),
+        other => panic!("expected a degenerate shape error, got {:?}", other),
+    }
+}
+
+#[test]
+fn line_refuses_a_segment_below_the_minimum_length() {
+    let mut h = Harness::new();
+    h.run("line; 0,0; 0.0009,0");
+    assert_eq!(
+        refused(&h),
+        "Line length 0.0009 below minimum 0.01 — ignored."
+    );
+    assert!(h.model.entities.is_empty());
+
+    // Still drawing from the first point
+    h.run("10,0; 10,10");
+    assert_eq!(h.executor.last_error, None);
+    assert_eq!(h.ids().len(), 2);
+    assert!(
+        h.executor
+            .output
+            .iter()
+            .any(|line| line.text.contains("ignored"))
+    );
+}
+
+#[test]
+fn closing_a_line_onto_its_start_is_refused() {
+    let mut h = Harness::new();
+    h.run("line; 0,0; 10,0; 10,10; 0,0.001; c");
+    refused(&h);
+    assert_eq!(h.ids().len(), 3);
+    assert_eq!(h.executor.active_command_name(), Some("LINE"));
+}
+
+#[test]
+fn circle_refuses_a_tiny_radius_and_asks_again() {
+    let mut h = Harness::new();
+    h.run("circle; 5,5; 0.001");
+    assert_eq!(
+        refused(&h),
+        "Circle radius 0.001 below minimum 0.01 — ignored."
+    );
+    h.run("5.000001,5");
+    refused(&h);
+    assert!(h.model.entities.is_empty());
+
+    h.run("2");
+    match &h.entity(h.ids()[0]).shape {
+        Shape::Circle(circle) => assert_eq!(circle.radius, 2.0),
+        other => panic!("expected a circle, got {:?}", other),
+    }
+}
+
+#[test]
+fn hairline_rectangles_are_refused() {
+    let mut h = Harness::new();
+    h.run("rect; 0,0; 10,0.005");
+    assert_eq!(
+        refused(&h),
+        "Rectangle height 0.005 below minimum 0.01 — ignored."
+    );
+    h.run("10,5");
+    assert_eq!(h.ids().len(), 1);
+
+    // A zero first edge goes back to picking its end
+    h.run("rect; 3p; 0,0; 0,0; 5,5");
+    assert!(refused(&h).starts_with("Rectangle width 0 "));
+    assert!(
+        h.executor
+            .status_message
+            .ends_with("Specify end of first edge:")
+    );
+    h.run("10,0; 10,4");
+    assert_eq!(h.ids().len(), 2);
+}
+
+#[test]
+fn arcs_need_a_radius_and_a_sweep() {
+    let mut h = Harness::new();
+    h.run("arc; 0,0; 0,0; 5,5");
+    assert!(refused(&h).starts_with("Arc radius 0 "));
+    h.run("10,0; 0,10");
+    assert_eq!(h.ids().len(), 1);
+}
+
+#[test]
+fn limits_are_configurable() {
+    let mut h = Harness::new();
+    h.executor.limits = GeometryLimits {
+        min_length: 1.0,
+        min_radius: 5.0,
+    };
+    h.run("line; 0,0; 0.5,0;");
+    assert_eq!(
+        refused(&h),
+        "Line length 0.50 below minimum 1.00 — ignored."
+    );
+    h.run("circle; 0,0; 4;");
+    refused(&h);
+    assert!(h.model.entities.is_empty());
+}
+
+#[test]
+fn existing_degenerate_entities_are_found_and_removed() {
+    let mut h = Harness::new();
+    let point = Vector2::new(3.0, 3.0);
+    h.model.add_entity(Entity::line(point, point));
+    h.model
+        .add_entity(Entity::line(point, Vector2::new(10.0, 3.0)));
+    h.model.add_entity(Entity::circle(point, 0.0, false));
+    let mut group = Entity::empty("Group");
+    group
+        .children
+        .push(Entity::rectangle(point, Vector2::new(3.0, 9.0), false));
+    group.children.push(Entity::circle(point, 4.0, false));
+    let group_id = group.id;
+    h.model.add_entity(group);
+
+    let limits = GeometryLimits::default();
+    let found = h.model.degenerate_entities(&limits);
+    let problems: Vec<String> = found.iter().map(|(_, p)| p.to_string()).collect();
+    assert_eq!(
+        problems,
+        vec![
+            "Line length 0 below minimum 0.01",
+            "Circle radius 0 below minimum 0.01",
+            "Rectangle width 0 below minimum 0.01",
+        ]
+    );
+
+    assert_eq!(h.model.remove_degenerate(&limits), 3);
+    assert_eq!(h.ids().len(), 2);
+    assert_eq!(h.entity(group_id).children.len(), 1);
+    assert!(h.model.degenerate_entities(&limits).is_empty());
+}
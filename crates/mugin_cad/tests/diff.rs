@@ -0,0 +1,265 @@
+//! Model diffs and the change journal, on synthetic edits.
+
+mod common;
+
+use common::{Harness, v};
+use ecolor::Color32;
+use mugin_cad::model::tools::diff::{Change, Comparison, MODIFIED_COLOR, diff};
+use mugin_cad::model::tools::journal::Journal;
+use mugin_cad::model::{CadModel, Entity};
+use std::collections::HashSet;
+
+/// Kind and entity ID (0 for layers and axes) of each change
+fn summary(changes: &[Change]) -> Vec<(&'static str, u64)> {
+    changes
+        .iter()
+        .map(|change| {
+            let kind = match change {
+                Change::EntityAdded { .. } => "added",
+                Change::EntityRemoved { .. } => "removed",
+                Change::EntityModified { .. } => "modified",
+                Change::LayerAdded { .. } => "layer added",
+                Change::LayerRemoved { .. } => "layer removed",
+                Change::LayerModified { .. } => "layer modified",
+                Change::AxesChanged { .. } => "axes",
+            };
+            (kind, change.entity_id().unwrap_or(0))
+        })
+        .collect()
+}
+
+#[test]
+fn identical_models_have_no_differences() {
+    let mut model = CadModel::new();
+    model.add_entity(Entity::line(v(0.0, 0.0), v(10.0, 0.0)));
+    model.axis_manager.add_vertical(5.0);
+    assert!(diff(&model, &model.clone()).is_empty());
+    assert!(diff(&CadModel::new(), &CadModel::new()).is_empty());
+}
+
+#[test]
+fn entities_are_matched_by_id() {
+    let mut before = CadModel::new();
+    let kept = Entity::line(v(0.0, 0.0), v(10.0, 0.0));
+    let moved = Entity::circle(v(0.0, 0.0), 5.0, false);
+    let gone = Entity::rectangle(v(0.0, 0.0), v(4.0, 4.0), false);
+    let (kept_id, moved_id, gone_id) = (kept.id, moved.id, gone.id);
+    before.add_entity(kept);
+    before.add_entity(moved);
+    before.add_entity(gone);
+
+    let mut after = before.clone();
+    after
+        .find_by_id_mut(moved_id)
+        .unwrap()
+        .translate(v(20.0, 0.0));
+    after.remove_entities_by_ids(&HashSet::from([gone_id]));
+    let new = Entity::line(v(0.0, 5.0), v(10.0, 5.0));
+    let new_id = new.id;
+    after.add_entity(new);
+
+    let changes = diff(&before, &after);
+    assert_eq!(
+        summary(&changes),
+        vec![
+            ("modified", moved_id),
+            ("added", new_id),
+            ("removed", gone_id)
+        ]
+    );
+    match &changes[0] {
+        Change::EntityModified { before, after } => {
+            assert_eq!(before.local_transform.translation.x, 0.0);
+            assert_eq!(after.local_transform.translation.x, 20.0);
+        }
+        other => panic!("expected a modification, got {:?}", other),
+    }
+    assert!(!summary(&changes).contains(&("modified", kept_id)));
+
+    // Renames and notes count as modifications too
+    let mut renamed = before.clone();
+    renamed.find_by_id_mut(kept_id).unwrap().name = "Baseline".into();
+    renamed.find_by_id_mut(gone_id).unwrap().notes = "check".into();
+    assert_eq!(
+        summary(&diff(&before, &renamed)),
+        vec![("modified", kept_id), ("modified", gone_id)]
+    );
+    assert_eq!(
+        diff(&before, &renamed)[0].describe(),
+        "Modified Baseline (was Line 1)"
+    );
+}
+
+#[test]
+fn children_have_entries_of_their_own() {
+    let mut before = CadModel::new();
+    let mut group = Entity::empty("Group");
+    let child = Entity::line(v(0.0, 0.0), v(10.0, 0.0));
+    let child_id = child.id;
+    group.children.push(child);
+    let group_id = group.id;
+    before.add_entity(group);
+
+    // Editing a child leaves the group alone
+    let mut edited = before.clone();
+    edited
+        .find_by_id_mut(child_id)
+        .unwrap()
+        .translate(v(0.0, 3.0));
+    assert_eq!(
+        summary(&diff(&before, &edited)),
+        vec![("modified", child_id)]
+    );
+
+    // Adding one changes the group's children too
+    let mut grown = before.clone();
+    let extra = Entity::circle(v(0.0, 0.0), 1.0, false);
+    let extra_id = extra.id;
+    grown.find_by_id_mut(group_id).unwrap().children.push(extra);
+    let changes = diff(&before, &grown);
+    assert_eq!(
+        summary(&changes),
+        vec![("modified", group_id), ("added", extra_id)]
+    );
+    // Payloads leave children out
+    match &changes[0] {
+        Change::EntityModified { before, after } => {
+            assert!(before.children.is_empty() && after.children.is_empty());
+        }
+        other => panic!("expected a modification, got {:?}", other),
+    }
+
+    // Removing the group removes its children as well
+    let mut emptied = before.clone();
+    emptied.entities.clear();
+    assert_eq!(
+        summary(&diff(&before, &emptied)),
+        vec![("removed", group_id), ("removed", child_id)]
+    );
+}
+
+#[test]
+fn layers_and_axes_are_compared() {
+    let before = CadModel::new();
+    let mut after = before.clone();
+    let walls = after
+        .layer_manager
+        .add_layer("Walls".into(), Color32::from_rgb(200, 0, 0));
+    after.layer_manager.layers.get_mut(&0).unwrap().is_visible = false;
+    after.axis_manager.add_vertical(100.0);
+
+    let changes = diff(&before, &after);
+    assert_eq!(
+        summary(&changes),
+        vec![("layer modified", 0), ("layer added", 0), ("axes", 0)]
+    );
+    match &changes[1] {
+        Change::LayerAdded { layer } => assert_eq!(layer.id, walls),
+        other => panic!("expected a new layer, got {:?}", other),
+    }
+
+    let mut removed = after.clone();
+    removed.layer_manager.remove_layer(walls);
+    assert_eq!(summary(&diff(&after, &removed)), vec![("layer removed", 0)]);
+}
+
+#[test]
+fn the_journal_appends_each_change_once() {
+    let mut h = Harness::new();
+    let mut journal = Journal::new(&h.model);
+    assert_eq!(journal.record(&h.model), 0);
+
+    h.run("line; 0,0; 100,0;;");
+    h.run("circle; 50,50; 10;");
+    let (line, circle) = (h.ids()[0], h.ids()[1]);
+    assert_eq!(journal.record(&h.model), 2);
+    // Nothing new since
+    assert_eq!(journal.record(&h.model), 0);
+
+    h.model
+        .find_by_id_mut(line)
+        .unwrap()
+        .translate(v(0.0, 10.0));
+    h.model
+        .layer_manager
+        .add_layer("Walls".into(), Color32::RED);
+    assert_eq!(journal.record(&h.model), 2);
+
+    // Undoing the circle goes back past the move as well
+    h.undo();
+    assert_eq!(journal.record(&h.model), 2);
+
+    let entries = journal.entries();
+    let sequences: Vec<u64> = entries.iter().map(|e| e.sequence).collect();
+    assert_eq!(sequences, vec![1, 2, 3, 4, 5, 6]);
+    let changes: Vec<Change> = entries.iter().map(|e| e.change.clone()).collect();
+    assert_eq!(
+        summary(&changes),
+        vec![
+            ("added", line),
+            ("added", circle),
+            ("modified", line),
+            ("layer added", 0),
+            ("modified", line),
+            ("removed", circle),
+        ]
+    );
+}
+
+#[test]
+fn journals_export_as_json() {
+    let mut model = CadModel::new();
+    let mut journal = Journal::new(&model);
+    let line = Entity::line(v(0.0, 0.0), v(10.0, 0.0));
+    let id = line.id;
+    model.add_entity(line);
+    model.find_by_id_mut(id).unwrap().name = "Edge".into();
+    journal.record(&model);
+
+    let json = journal.to_json().unwrap();
+    let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let entries = parsed.as_array().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["sequence"], 1);
+    assert_eq!(entries[0]["kind"], "entity_added");
+    assert_eq!(entries[0]["entity"]["name"], "Edge");
+    assert_eq!(entries[0]["entity"]["id"], id);
+    assert!(entries[0]["time"].as_u64().unwrap() > 0);
+}
+
+#[test]
+fn comparisons_overlay_the_changes_in_world_coordinates() {
+    let mut file = CadModel::new();
+    let mut group = Entity::empty("Group");
+    group.translate(v(100.0, 0.0));
+    let child = Entity::line(v(0.0, 0.0), v(10.0, 0.0));
+    let child_id = child.id;
+    group.children.push(child);
+    file.add_entity(group);
+    file.update_hierarchy();
+
+    let mut current = file.clone();
+    current
+        .find_by_id_mut(child_id)
+        .unwrap()
+        .modify(|shape| shape.scale(v(0.0, 0.0), 2.0));
+    current
+        .layer_manager
+        .add_layer("Only here".into(), Color32::RED);
+    current.update_hierarchy();
+
+    let comparison = Comparison::new("old.mugin".into(), &file, &current);
+    // Layers aren't saved in project files, so they aren't compared
+    assert_eq!(summary(&comparison.changes), vec![("modified", child_id)]);
+    assert_eq!(comparison.counts(), (0, 0, 1));
+
+    // Both versions, placed where the group puts them
+    let overlay = &comparison.overlay.entities;
+    assert_eq!(overlay.len(), 2);
+    for entity in overlay {
+        assert_eq!(entity.color, Some(MODIFIED_COLOR));
+        assert_eq!(entity.world_transform.translation.x, 100.0);
+    }
+    assert!(overlay[0].line_style.is_some());
+    assert!(overlay[1].line_style.is_none());
+}
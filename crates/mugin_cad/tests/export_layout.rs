@@ -0,0 +1,168 @@
+mod common;
+
+use common::v;
+use mugin_cad::cli::{CliAction, parse};
+use mugin_cad::export::layout::MM_PER_UNIT;
+use mugin_cad::export::settings::{
+    ExportSettings, ExportSource, PageOrientation, PageSize, ScaleType,
+};
+use mugin_cad::model::Vector2;
+
+/// A4 portrait, 10 mm margins, printing the region from `min` to `max`
+fn settings(scale_type: ScaleType, min: Vector2, max: Vector2) -> ExportSettings {
+    ExportSettings {
+        page_size: PageSize::A4,
+        orientation: PageOrientation::Portrait,
+        scale_type,
+        source: ExportSource::Viewport(min, max),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn one_to_fifty_prints_a_meter_as_two_centimeters() {
+    let layout = settings(ScaleType::Custom(50.0), v(0.0, 0.0), v(100.0, 100.0))
+        .layout((v(0.0, 0.0), v(0.0, 0.0)));
+    let span = layout.to_page(v(100.0, 0.0)) - layout.to_page(v(0.0, 0.0));
+    assert!((span.x - 20.0).abs() < 1e-4);
+    assert!((layout.ratio() - 50.0).abs() < 1e-3);
+    assert!((layout.scale - MM_PER_UNIT / 50.0).abs() < 1e-6);
+    // Centered on the page
+    let center = layout.to_page(v(50.0, 50.0));
+    assert!((center.x - 105.0).abs() < 1e-3 && (center.y - 148.5).abs() < 1e-3);
+    assert!(layout.clipped().is_empty());
+}
+
+#[test]
+fn content_off_the_page_is_reported() {
+    // 20 m wide at 1:50 is 400 mm, on a 190 mm printable width
+    let layout = settings(ScaleType::Custom(50.0), v(0.0, 0.0), v(2000.0, 100.0))
+        .layout((v(0.0, 0.0), v(0.0, 0.0)));
+    let clipped = layout.clipped();
+    assert_eq!(clipped.len(), 2);
+    let (left, right) = (clipped[0], clipped[1]);
+    assert!((left.1.x - 10.0).abs() < 1e-3);
+    assert!((right.0.x - 200.0).abs() < 1e-3);
+
+    let fit = settings(ScaleType::FitToPage, v(0.0, 0.0), v(2000.0, 100.0))
+        .layout((v(0.0, 0.0), v(0.0, 0.0)));
+    assert!(fit.clipped().is_empty());
+}
+
+#[test]
+fn scale_bars_have_round_lengths() {
+    let layout = settings(ScaleType::Standard(100.0), v(0.0, 0.0), v(100.0, 100.0))
+        .layout((v(0.0, 0.0), v(0.0, 0.0)));
+    let bar = layout.scale_bar();
+    // 50 mm at 1:100 is 5 m
+    assert_eq!(bar.label, "5 m");
+    assert_eq!(bar.ratio, "1:100");
+    assert!((bar.length - 50.0).abs() < 1e-3);
+    assert!(bar.origin.x + bar.length <= 200.0 + 1e-3);
+
+    let layout = settings(ScaleType::Custom(20.0), v(0.0, 0.0), v(10.0, 10.0))
+        .layout((v(0.0, 0.0), v(0.0, 0.0)));
+    let bar = layout.scale_bar();
+    assert_eq!(bar.label, "1 m");
+    assert!((bar.length - 50.0).abs() < 1e-3);
+
+    let layout = settings(ScaleType::Custom(75.0), v(0.0, 0.0), v(10.0, 10.0))
+        .layout((v(0.0, 0.0), v(0.0, 0.0)));
+    // 50 mm at 1:75 is 3.75 m, rounded down to 2 m
+    let bar = layout.scale_bar();
+    assert_eq!(bar.label, "2 m");
+    assert!((bar.length - 200.0 * MM_PER_UNIT / 75.0).abs() < 1e-3);
+}
+
+#[test]
+fn the_cli_asks_for_a_scale_bar() {
+    let args = [
+        "--export-pdf",
+        "out.pdf",
+        "--scale",
+        "50",
+        "--scale-bar",
+        "plan.mugin",
+    ];
+    let Ok(CliAction::ExportPdf { settings, .. }) = parse(args.map(String::from)) else {
+        panic!("expected an export");
+    };
+    assert!(settings.scale_bar);
+    assert_eq!(settings.scale_type, ScaleType::Custom(50.0));
+    assert!(parse(["--scale-bar".to_string()]).is_err());
+}
+
+#[test]
+fn pdfs_are_written_with_a_scale_bar() {
+    use mugin_cad::export::pdf::PdfExporter;
+    use mugin_cad::model::dimension::DimensionStyle;
+
+    let mut h = common::Harness::new();
+    h.run("line; 0,0; 500,0;");
+
+    let settings = ExportSettings {
+        scale_type: ScaleType::Custom(50.0),
+        scale_bar: true,
+        ..Default::default()
+    };
+    let path = std::env::temp_dir().join(format!("mugin_scale_bar_{}.pdf", std::process::id()));
+    PdfExporter::export_to_file(&h.model, &settings, &DimensionStyle::default(), &path).unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+    assert!(bytes.starts_with(b"%PDF"));
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn cancelled_exports_keep_the_previous_pdf() {
+    use mugin_cad::export::pdf::PdfExporter;
+    use mugin_cad::model::backup::temp_path;
+    use mugin_cad::model::dimension::DimensionStyle;
+    use std::sync::atomic::AtomicBool;
+
+    let mut h = common::Harness::new();
+    h.run("line; 0,0; 500,0;");
+    let settings = ExportSettings::default();
+    let style = DimensionStyle::default();
+    let path = std::env::temp_dir().join(format!("mugin_replace_{}.pdf", std::process::id()));
+    std::fs::write(&path, "previous").unwrap();
+
+    let cancel = AtomicBool::new(true);
+    let written = PdfExporter::export_replacing(&h.model, &settings, &style, &path, &cancel);
+    assert!(!written.unwrap());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "previous");
+    assert!(!temp_path(&path).exists());
+
+    let cancel = AtomicBool::new(false);
+    let written = PdfExporter::export_replacing(&h.model, &settings, &style, &path, &cancel);
+    assert!(written.unwrap());
+    assert!(std::fs::read(&path).unwrap().starts_with(b"%PDF"));
+    assert!(!temp_path(&path).exists());
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn line_weights_follow_stroke_width_or_layer() {
+    use mugin_cad::export::settings::{LineWeights, MIN_WEIGHT_MM, WeightMode};
+    use mugin_cad::model::layer::DEFAULT_STROKE_WIDTH;
+
+    let mut weights = LineWeights::default();
+    // Untouched drawings print with a 0.25 mm pen
+    assert!((weights.weight(DEFAULT_STROKE_WIDTH, 0) - 0.25).abs() < 1e-5);
+    assert!(
+        weights.weight(2.0 * DEFAULT_STROKE_WIDTH, 0) > weights.weight(DEFAULT_STROKE_WIDTH, 0)
+    );
+    assert_eq!(weights.weight(0.0, 0), MIN_WEIGHT_MM);
+
+    weights.layers.insert(3, 0.7);
+    // Layer weights apply only in per-layer mode
+    assert!((weights.weight(DEFAULT_STROKE_WIDTH, 3) - 0.25).abs() < 1e-5);
+    weights.mode = WeightMode::PerLayer;
+    assert_eq!(weights.weight(DEFAULT_STROKE_WIDTH, 3), 0.7);
+    assert!((weights.weight(DEFAULT_STROKE_WIDTH, 4) - 0.25).abs() < 1e-5);
+
+    // Settings saved before weights existed load with the defaults
+    let mut json = serde_json::to_value(ExportSettings::default()).unwrap();
+    json.as_object_mut().unwrap().remove("line_weights");
+    let settings: ExportSettings = serde_json::from_value(json).unwrap();
+    assert_eq!(settings.line_weights, LineWeights::default());
+}
@@ -0,0 +1,148 @@
+use mugin_cad::model::math::expression::{Expression, ExpressionError, evaluate};
+use mugin_cad::model::tools::calculator::{Calculator, format_value};
+
+fn calc(text: &str) -> Result<f64, ExpressionError> {
+    evaluate(text, &|_| None)
+}
+
+fn near(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-9,
+        "expected {}, got {}",
+        expected,
+        actual
+    );
+}
+
+#[test]
+fn precedence_and_associativity() {
+    assert_eq!(calc("1 + 2 * 3"), Ok(7.0));
+    assert_eq!(calc("(1 + 2) * 3"), Ok(9.0));
+    assert_eq!(calc("10 - 4 - 3"), Ok(3.0));
+    assert_eq!(calc("2 ^ 3 ^ 2"), Ok(512.0));
+    assert_eq!(calc("-2 ^ 2"), Ok(-4.0));
+    assert_eq!(calc("2 * -3"), Ok(-6.0));
+    assert_eq!(calc("7 % 4 + 1.5e2"), Ok(153.0));
+    // As typed from a symbol keyboard
+    assert_eq!(calc("(600 − 2×20) ÷ 4"), Ok(140.0));
+}
+
+#[test]
+fn functions_in_degrees_and_constants() {
+    near(calc("sin(30)").unwrap(), 0.5);
+    near(calc("atan2(1, 1)").unwrap(), 45.0);
+    near(calc("sqrt(hypot(3, 4) * 5)").unwrap(), 5.0);
+    assert_eq!(calc("max(3, 9, 4) - min(2, 1)"), Ok(8.0));
+    assert_eq!(calc("round(2.5) + floor(-0.5) + ceil(0.2)"), Ok(3.0));
+    near(calc("2 * pi").unwrap(), std::f64::consts::TAU);
+    near(calc("ln(e)").unwrap(), 1.0);
+}
+
+#[test]
+fn names_come_from_the_caller() {
+    let values = |name: &str| match name {
+        "roomWidth" => Some(600.0),
+        "wallThickness" => Some(20.0),
+        "A.width" => Some(40.0),
+        "e" => Some(2.0),
+        _ => None,
+    };
+    let text = "(roomWidth - 2*wallThickness)/3 + A.width/e";
+    let expression = Expression::parse(text).unwrap();
+    near(expression.eval(&values).unwrap(), 560.0 / 3.0 + 20.0);
+    assert_eq!(
+        expression.names(),
+        vec!["roomWidth", "wallThickness", "A.width", "e"]
+    );
+    assert_eq!(
+        evaluate("wall * 2", &values),
+        Err(ExpressionError::UnknownName("wall".to_string()))
+    );
+}
+
+#[test]
+fn errors_say_what_went_wrong() {
+    assert_eq!(calc("  "), Err(ExpressionError::Empty));
+    assert_eq!(calc("2 +"), Err(ExpressionError::UnexpectedEnd));
+    assert_eq!(calc("(2 + 3"), Err(ExpressionError::UnexpectedEnd));
+    assert_eq!(
+        calc("2 + 3)"),
+        Err(ExpressionError::Unexpected {
+            found: ")".to_string(),
+            at: 5
+        })
+    );
+    assert_eq!(
+        calc("4 $ 2"),
+        Err(ExpressionError::Unexpected {
+            found: "$".to_string(),
+            at: 2
+        })
+    );
+    assert_eq!(calc("1 / 0"), Err(ExpressionError::NotANumber));
+    assert_eq!(calc("sqrt(-1)"), Err(ExpressionError::NotANumber));
+    assert_eq!(
+        calc("frob(1)"),
+        Err(ExpressionError::UnknownFunction("frob".to_string()))
+    );
+    assert_eq!(
+        calc("sqrt(1, 2)").unwrap_err().to_string(),
+        "sqrt takes 1 argument."
+    );
+}
+
+#[test]
+fn deep_nesting_is_refused_instead_of_overflowing() {
+    let depth = 100_000;
+    let parens = format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+    assert_eq!(calc(&parens), Err(ExpressionError::TooDeep));
+    assert_eq!(
+        calc(&format!("{}1", "-".repeat(depth))),
+        Err(ExpressionError::TooDeep)
+    );
+    assert_eq!(
+        calc(&format!("{}1", "2^".repeat(depth))),
+        Err(ExpressionError::TooDeep)
+    );
+    // A long flat chain nests its left side just as deep
+    assert_eq!(
+        calc(&format!("1{}", "+1".repeat(depth))),
+        Err(ExpressionError::TooDeep)
+    );
+    // Plus signs add no nesting
+    assert_eq!(calc(&format!("{}1", "+".repeat(depth))), Ok(1.0));
+
+    // Formulas a person writes are nowhere near the limit
+    let nested = format!("{}1{}", "(".repeat(100), ")".repeat(100));
+    assert_eq!(calc(&nested), Ok(1.0));
+    assert_eq!(calc(&format!("0{}", "+1".repeat(100))), Ok(100.0));
+}
+
+#[test]
+fn calculator_keeps_names_and_the_last_answer() {
+    let mut calculator = Calculator::default();
+    calculator.input = "roomWidth = 600".to_string();
+    assert_eq!(calculator.evaluate(), Ok(600.0));
+    calculator.input = "wall = 15 + 5".to_string();
+    calculator.evaluate().unwrap();
+    calculator.input = "(roomWidth − 2×wall)/3".to_string();
+    near(calculator.evaluate().unwrap(), 560.0 / 3.0);
+    assert!(calculator.input.is_empty());
+    assert_eq!(format_value(calculator.answer().unwrap()), "186.666667");
+
+    calculator.input = "ans * 3".to_string();
+    near(calculator.evaluate().unwrap(), 560.0);
+    assert_eq!(calculator.variables().len(), 2);
+    assert_eq!(calculator.history.len(), 4);
+    assert_eq!(calculator.history[1].assigned.as_deref(), Some("wall"));
+
+    // A failed line stays for fixing and keeps the answer
+    calculator.input = "ans +".to_string();
+    assert!(calculator.evaluate().is_err());
+    assert_eq!(calculator.input, "ans +");
+    assert!(calculator.error.is_some());
+    calculator.insert(12.5);
+    assert_eq!(calculator.input, "ans +12.5");
+    assert_eq!(calculator.evaluate(), Ok(572.5));
+    assert_eq!(calculator.error, None);
+}
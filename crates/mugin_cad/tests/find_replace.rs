@@ -0,0 +1,123 @@
+use mugin_cad::model::tools::find_replace::{
+    FindField, FindScope, apply_matches, find_matches, replace_text,
+};
+use mugin_cad::model::{CadModel, Entity, Shape, TextAnnotation, Vector2};
+use std::collections::HashSet;
+
+fn text(s: &str) -> Entity {
+    Entity::text(TextAnnotation::new_custom(
+        Vector2::new(0.0, 0.0),
+        s.to_string(),
+    ))
+}
+
+fn text_of(model: &CadModel, id: u64) -> String {
+    match &model.find_by_id(id).unwrap().shape {
+        Shape::Text(text) => text.text.clone(),
+        _ => panic!("not a text"),
+    }
+}
+
+#[test]
+fn pattern_characters_are_literal() {
+    assert_eq!(
+        replace_text("a.b.c", ".", "-", true).as_deref(),
+        Some("a-b-c")
+    );
+    assert_eq!(
+        replace_text("f(x)*2", "(x)*", "[y]", true).as_deref(),
+        Some("f[y]2")
+    );
+    assert_eq!(replace_text("abc", ".", "-", true), None);
+}
+
+#[test]
+fn case_toggle() {
+    let source = "Door DOOR door";
+    assert_eq!(
+        replace_text(source, "door", "gate", false).as_deref(),
+        Some("gate gate gate")
+    );
+    assert_eq!(
+        replace_text(source, "door", "gate", true).as_deref(),
+        Some("Door DOOR gate")
+    );
+}
+
+#[test]
+fn matches_do_not_overlap() {
+    assert_eq!(replace_text("aaa", "aa", "b", true).as_deref(), Some("ba"));
+}
+
+#[test]
+fn empty_find_or_no_match() {
+    assert_eq!(replace_text("abc", "", "x", true), None);
+    assert_eq!(replace_text("abc", "z", "x", true), None);
+}
+
+#[test]
+fn scopes() {
+    let mut model = CadModel::new();
+    let other_layer = model
+        .layer_manager
+        .add_layer("Other".to_string(), ecolor::Color32::RED);
+
+    let mut group = Entity::empty("Group");
+    let child = text("Room 1");
+    let child_id = child.id;
+    group.children.push(child);
+    let group_id = group.id;
+    model.add_entity(group);
+
+    let mut loose = text("Room 2");
+    loose.layer_id = other_layer;
+    let loose_id = loose.id;
+    model.add_entity(loose);
+
+    let ids = |scope, selected: &HashSet<u64>| -> HashSet<u64> {
+        find_matches(&model, scope, selected, "Room", "Hall", true)
+            .into_iter()
+            .map(|m| m.entity_id)
+            .collect()
+    };
+
+    let none = HashSet::new();
+    assert_eq!(
+        ids(FindScope::Project, &none),
+        HashSet::from([child_id, loose_id])
+    );
+    assert_eq!(
+        ids(FindScope::Layer(other_layer), &none),
+        HashSet::from([loose_id])
+    );
+    // Selecting the group covers its children
+    assert_eq!(
+        ids(FindScope::Selection, &HashSet::from([group_id])),
+        HashSet::from([child_id])
+    );
+    assert!(ids(FindScope::Selection, &none).is_empty());
+}
+
+#[test]
+fn apply_counts_and_writes() {
+    let mut model = CadModel::new();
+    let mut entity = text("Room");
+    entity.name = "Room label".to_string();
+    let id = entity.id;
+    model.add_entity(entity);
+
+    let matches = find_matches(
+        &model,
+        FindScope::Project,
+        &HashSet::new(),
+        "Room",
+        "Hall",
+        true,
+    );
+    assert_eq!(matches.len(), 2);
+    assert!(matches.iter().any(|m| m.field == FindField::Name));
+
+    assert_eq!(apply_matches(&mut model, &matches), 2);
+    assert_eq!(text_of(&model, id), "Hall");
+    assert_eq!(model.find_by_id(id).unwrap().name, "Hall label");
+}
@@ -0,0 +1,49 @@
+use mugin_cad::model::{CadModel, Entity, FlagColor, Vector2};
+
+fn line(x: f32) -> Entity {
+    Entity::line(Vector2::new(x, 0.0), Vector2::new(x + 10.0, 0.0))
+}
+
+#[test]
+fn flags_are_found_at_any_depth_and_counted_per_color() {
+    let mut model = CadModel::new();
+    let mut group = Entity::empty("Group");
+    let nested = line(0.0);
+    let nested_id = nested.id;
+    group.children.push(nested);
+    let loose = line(20.0);
+    let loose_id = loose.id;
+    let unflagged = line(40.0);
+    model.add_entity(group);
+    model.add_entity(loose);
+    model.add_entity(unflagged);
+
+    assert_eq!(model.set_flag([nested_id], Some(FlagColor::Red)), 1);
+    assert_eq!(model.set_flag([loose_id, 9999], Some(FlagColor::Blue)), 1);
+
+    assert_eq!(model.flagged_ids(Some(FlagColor::Red)), vec![nested_id]);
+    assert_eq!(model.flagged_ids(None), vec![nested_id, loose_id]);
+    assert_eq!(
+        model.flag_counts(),
+        vec![(FlagColor::Red, 1), (FlagColor::Blue, 1)]
+    );
+
+    model.set_flag([nested_id], None);
+    assert_eq!(model.flagged_ids(None), vec![loose_id]);
+    assert_eq!(FlagColor::parse(" blue "), Some(FlagColor::Blue));
+    assert_eq!(FlagColor::parse("teal"), None);
+}
+
+#[test]
+fn flags_survive_saving_and_unflagged_entities_do_not_store_one() {
+    let mut flagged = line(0.0);
+    flagged.flag = Some(FlagColor::Green);
+    let json = serde_json::to_string(&flagged).unwrap();
+    let restored: Entity = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.flag, Some(FlagColor::Green));
+
+    let json = serde_json::to_string(&line(0.0)).unwrap();
+    assert!(!json.contains("flag"));
+    let restored: Entity = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.flag, None);
+}
@@ -0,0 +1,172 @@
+mod common;
+
+use common::{Harness, assert_near, v};
+use mugin_cad::export::data::{COLUMNS, DataExportOptions, csv_fields, data_rows, json_object};
+use mugin_cad::model::config::FlooringStyle;
+use mugin_cad::model::{Flooring, Geometry, Shape};
+
+/// An L-shaped slab: a 400x200 strip with a 200x200 wing on its left end
+fn l_shape() -> Flooring {
+    Flooring::new(
+        vec![
+            v(0.0, 0.0),
+            v(400.0, 0.0),
+            v(400.0, 200.0),
+            v(200.0, 200.0),
+            v(200.0, 400.0),
+            v(0.0, 400.0),
+        ],
+        1,
+        15.0,
+    )
+}
+
+fn floor(h: &Harness, id: u64) -> &Flooring {
+    match &h.entity(id).shape {
+        Shape::Flooring(floor) => floor,
+        other => panic!("expected flooring, got {:?}", other),
+    }
+}
+
+#[test]
+fn area_and_centroid_of_an_l_shape() {
+    let slab = l_shape();
+    assert_eq!(slab.area(), 120_000.0);
+    // Strip of 80 000 centred on (200, 100), wing of 40 000 on (100, 300)
+    assert_near(slab.centroid(), v(500.0 / 3.0, 500.0 / 3.0));
+
+    // Winding does not change the area
+    let mut reversed = slab.clone();
+    reversed.boundary.reverse();
+    assert_eq!(reversed.area(), 120_000.0);
+}
+
+#[test]
+fn hits_inside_but_not_in_the_notch() {
+    let slab = l_shape();
+    assert!(slab.hit_test(v(100.0, 100.0), 1.0));
+    assert!(slab.hit_test(v(100.0, 350.0), 1.0));
+    assert!(!slab.hit_test(v(300.0, 300.0), 1.0));
+    // On the outline within tolerance
+    assert!(slab.hit_test(v(300.0, 200.5), 1.0));
+}
+
+#[test]
+fn command_closes_on_the_first_corner() {
+    let mut h = Harness::new();
+    h.run("flooring; 20; 0,0; 300,0; 300,200; 0,200; 0,0");
+    let slab = floor(&h, h.ids()[0]);
+    assert_eq!(slab.boundary.len(), 4);
+    assert_eq!(slab.thickness, 20.0);
+    assert_eq!(slab.area(), 60_000.0);
+}
+
+#[test]
+fn close_option_finishes_the_outline() {
+    let mut h = Harness::new();
+    let ground = h.model.storeys.add("Ground", 300.0);
+    h.model.storeys.active = Some(ground);
+    h.run("slab; 0,0; 100,0; 0,100; c");
+
+    let slab = floor(&h, h.ids()[0]);
+    assert_eq!(slab.boundary.len(), 3);
+    assert_eq!(slab.thickness, 15.0);
+    assert_eq!(slab.storey_id, ground);
+}
+
+#[test]
+fn floor_types_cycle_through_the_colors() {
+    let style = FlooringStyle::default();
+    assert_eq!(style.color(1), style.colors[0]);
+    assert_eq!(style.color(4), style.colors[3]);
+    assert_eq!(style.color(5), style.colors[0]);
+}
+
+#[test]
+fn flooring_round_trips() {
+    let shape = Shape::Flooring(l_shape());
+    let json = serde_json::to_string(&shape).unwrap();
+    let read: Shape = serde_json::from_str(&json).unwrap();
+    assert_eq!(read, shape);
+}
+
+/// A 400x300 slab drawn with the flooring command
+fn slab() -> (Harness, u64) {
+    let mut h = Harness::new();
+    h.run("flooring; 0,0; 400,0; 400,300; 0,300; c");
+    let id = h.ids()[0];
+    (h, id)
+}
+
+#[test]
+fn openings_are_cut_as_holes() {
+    let (mut h, id) = slab();
+    h.run("opening; r; 100,100; 200,200");
+    h.run("opening; 250,50; 350,50; 300,150; c");
+
+    let slab = floor(&h, id);
+    assert_eq!(slab.boundary.len(), 4);
+    assert_eq!(slab.holes.len(), 2);
+    assert_eq!(slab.area(), 120_000.0 - 10_000.0 - 5_000.0);
+    assert!(!slab.hit_test(v(150.0, 150.0), 1.0));
+    assert!(slab.hit_test(v(50.0, 150.0), 1.0));
+    // The opening's edge still picks the slab
+    assert!(slab.hit_test(v(150.0, 100.5), 1.0));
+}
+
+#[test]
+fn openings_outside_or_overlapping_are_refused() {
+    let (mut h, id) = slab();
+    h.run("opening; r; 100,100; 200,200");
+    // Sticks out of the slab
+    h.run("opening; r; 350,100; 450,200;");
+    // Overlaps the first opening
+    h.run("opening; r; 150,150; 250,250;");
+    assert_eq!(floor(&h, id).holes.len(), 1);
+}
+
+#[test]
+fn openings_move_the_centroid_away() {
+    let mut slab = Flooring::new(
+        vec![v(0.0, 0.0), v(400.0, 0.0), v(400.0, 200.0), v(0.0, 200.0)],
+        1,
+        15.0,
+    );
+    slab.holes.push(vec![
+        v(200.0, 0.0),
+        v(400.0, 0.0),
+        v(400.0, 200.0),
+        v(200.0, 200.0),
+    ]);
+    assert_near(slab.centroid(), v(100.0, 100.0));
+}
+
+#[test]
+fn undo_removes_an_opening() {
+    let (mut h, id) = slab();
+    h.run("opening; r; 100,100; 200,200");
+    assert!(h.undo());
+    assert!(floor(&h, id).holes.is_empty());
+}
+
+#[test]
+fn holes_are_exported_and_optional_in_files() {
+    let (mut h, id) = slab();
+    h.run("opening; r; 100,100; 200,200");
+    let rows = data_rows(&h.model, &Default::default(), &DataExportOptions::default());
+    let holes = COLUMNS.iter().position(|c| *c == "holes").unwrap();
+    assert_eq!(
+        csv_fields(&rows[0])[holes],
+        "100 100;200 100;200 200;100 200"
+    );
+    assert_eq!(
+        json_object(&rows[0])["holes"][0].as_array().unwrap().len(),
+        4
+    );
+
+    // Slabs saved before openings existed still load
+    let mut json = serde_json::to_value(floor(&h, id)).unwrap();
+    json.as_object_mut().unwrap().remove("holes");
+    let read: Flooring = serde_json::from_value(json).unwrap();
+    assert!(read.holes.is_empty());
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use common::v;
+use mugin_cad::model::{Arc, Circle, Geometry, Line, Rectangle, Vector2};
+use std::f32::consts::PI;
+
+const TOLERANCE: f32 = 1.0;
+
+fn arc(filled: bool) -> Arc {
+    // Upper half of a radius 10 circle
+    Arc {
+        center: v(0.0, 0.0),
+        radius: 10.0,
+        start_angle: 0.0,
+        end_angle: PI,
+        filled,
+        clockwise: false,
+    }
+}
+
+/// Each case is `(point, expected hit)`; returns a line per mismatch
+fn check(name: &str, shape: &dyn Geometry, cases: &[(Vector2, bool)]) -> Vec<String> {
+    cases
+        .iter()
+        .filter(|(point, expected)| shape.hit_test(*point, TOLERANCE) != *expected)
+        .map(|(point, expected)| format!("{} at {:?}: expected {}", name, point, expected))
+        .collect()
+}
+
+#[test]
+fn hit_test_contract() {
+    let mut line = Line::new(v(0.0, 0.0), v(20.0, 0.0));
+    line.show_length = false;
+    let circle = |radius, filled| Circle::new(v(0.0, 0.0), radius, filled);
+    let rect = |filled| Rectangle::new(v(0.0, 0.0), v(20.0, 10.0), filled);
+
+    let failures = [
+        check(
+            "line",
+            &line,
+            &[
+                (v(10.0, 0.5), true),
+                (v(10.0, 1.5), false),
+                // Past the end
+                (v(21.5, 0.0), false),
+            ],
+        ),
+        check(
+            "circle",
+            &circle(10.0, false),
+            &[
+                (v(10.0, 0.0), true),
+                (v(11.0, 0.0), true),
+                (v(5.0, 0.0), false),
+                (v(12.0, 0.0), false),
+            ],
+        ),
+        check(
+            "filled circle",
+            &circle(10.0, true),
+            &[
+                (v(5.0, 0.0), true),
+                (v(10.5, 0.0), true),
+                (v(12.0, 0.0), false),
+            ],
+        ),
+        // The whole disk is within tolerance of the rim
+        check(
+            "tiny circle",
+            &circle(0.5, false),
+            &[(v(0.0, 0.0), true), (v(2.0, 0.0), false)],
+        ),
+        check(
+            "rectangle",
+            &rect(false),
+            &[
+                (v(10.0, 0.5), true),
+                (v(10.0, -0.5), true),
+                (v(10.0, 5.0), false),
+                // Diagonally past a corner: inside the grown box, too far away
+                (v(-0.8, -0.8), false),
+                (v(-0.6, -0.6), true),
+            ],
+        ),
+        check(
+            "filled rectangle",
+            &rect(true),
+            &[(v(10.0, 5.0), true), (v(10.0, -2.0), false)],
+        ),
+        check(
+            "arc",
+            &arc(false),
+            &[
+                (v(0.0, 10.0), true),
+                // On the circle but outside the sweep
+                (v(0.0, -10.0), false),
+                // Just past an endpoint
+                (v(10.0, -0.5), true),
+                (v(0.0, 5.0), false),
+            ],
+        ),
+        check(
+            "filled arc",
+            &arc(true),
+            &[
+                (v(0.0, 5.0), true),
+                (v(0.0, -5.0), false),
+                // Along a radial edge
+                (v(5.0, -0.5), true),
+                (v(0.0, 12.0), false),
+            ],
+        ),
+    ]
+    .concat();
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
@@ -0,0 +1,161 @@
+mod common;
+
+use common::{Harness, assert_near, v};
+use mugin_cad::model::Shape;
+use mugin_cad::model::structure::beam_type::BeamType;
+use mugin_cad::model::structure::junction::{BeamJoin, JunctionKind};
+
+/// Harness with 20 cm wide beams drawn between the given ends, written
+/// "x0,y0; x1,y1"
+fn beams(ends: &[&str]) -> (Harness, Vec<u64>) {
+    let mut h = Harness::new();
+    h.model
+        .definitions
+        .add_beam_type(BeamType::new(0, "B20x50", 20.0, 50.0, 0, 0));
+    for ends in ends {
+        h.run(&format!("place_beam; {};", ends));
+    }
+    let ids = h.ids();
+    (h, ids)
+}
+
+fn join(h: &Harness, id: u64) -> &BeamJoin {
+    &h.model.beam_joins.joins[&id]
+}
+
+fn assert_ranges(actual: &[(f32, f32)], expected: &[(f32, f32)]) {
+    assert_eq!(actual.len(), expected.len(), "{:?}", actual);
+    for (a, e) in actual.iter().zip(expected) {
+        assert!(
+            (a.0 - e.0).abs() < 1e-4 && (a.1 - e.1).abs() < 1e-4,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+}
+
+#[test]
+fn t_junction_extends_the_stem_and_opens_the_face() {
+    let (mut h, ids) = beams(&["0,0; 400,0", "200,10; 200,300"]);
+    let (main, stem) = (ids[0], ids[1]);
+    assert_eq!(h.model.cleanup_junctions(), 1);
+
+    let junction = h.model.beam_joins.junctions[0];
+    assert_eq!(junction.kind, JunctionKind::T);
+    assert_eq!(junction.beams, [stem, main]);
+    assert_near(junction.point, v(200.0, 0.0));
+
+    // The stem reaches the middle of the main beam
+    let stem_join = join(&h, stem);
+    assert_near(stem_join.extended().0, v(200.0, 0.0));
+    assert_ranges(&stem_join.hidden[0], &[(0.0, 10.0 / 300.0)]);
+    assert_ranges(&stem_join.hidden[2], &[(290.0 / 300.0, 1.0)]);
+    assert_ranges(&stem_join.hidden[3], &[(0.0, 1.0)]);
+
+    // Only the face the stem meets is opened
+    let main_join = join(&h, main);
+    assert_eq!(main_join.extended(), (v(0.0, 0.0), v(400.0, 0.0)));
+    assert!(main_join.hidden[0].is_empty());
+    assert_ranges(&main_join.hidden[2], &[(0.475, 0.525)]);
+    assert_ranges(&main_join.visible(2), &[(0.0, 0.475), (0.525, 1.0)]);
+}
+
+#[test]
+fn l_junction_closes_the_outer_corner() {
+    let (mut h, ids) = beams(&["0,0; 300,0", "0,0; 0,200"]);
+    assert_eq!(h.model.cleanup_junctions(), 1);
+    assert_eq!(h.model.beam_joins.junctions[0].kind, JunctionKind::L);
+
+    // Both beams reach the outer faces of each other
+    let across = join(&h, ids[0]);
+    let up = join(&h, ids[1]);
+    assert_near(across.extended().0, v(-10.0, 0.0));
+    assert_near(up.extended().0, v(0.0, -10.0));
+
+    // The inner faces stop at the other beam, the outer ones run through
+    assert!(across.hidden[0].is_empty());
+    assert_ranges(&across.hidden[2], &[(290.0 / 310.0, 1.0)]);
+    assert_ranges(&up.hidden[0], &[(0.0, 20.0 / 210.0)]);
+    assert!(up.hidden[2].is_empty());
+    // The start ends lie on the other beam's faces and stay drawn
+    assert!(across.hidden[3].is_empty());
+    assert!(up.hidden[3].is_empty());
+}
+
+#[test]
+fn x_junction_hides_faces_across_both_beams() {
+    let (mut h, ids) = beams(&["0,0; 400,0", "200,-200; 200,200"]);
+    assert_eq!(h.model.cleanup_junctions(), 1);
+    assert_eq!(h.model.beam_joins.junctions[0].kind, JunctionKind::X);
+
+    for id in ids {
+        let join = join(&h, id);
+        assert_eq!(join.start_extension, 0.0);
+        assert_eq!(join.end_extension, 0.0);
+        assert_ranges(&join.hidden[0], &[(0.475, 0.525)]);
+        assert_ranges(&join.hidden[2], &[(0.475, 0.525)]);
+    }
+}
+
+#[test]
+fn apart_or_parallel_beams_do_not_join() {
+    let (mut h, _) = beams(&["0,0; 400,0", "0,100; 400,100", "200,50; 200,80"]);
+    assert_eq!(h.model.cleanup_junctions(), 0);
+    assert!(h.model.beam_joins.joins.is_empty());
+}
+
+#[test]
+fn beams_on_other_storeys_do_not_join() {
+    let (mut h, ids) = beams(&["0,0; 400,0", "200,-200; 200,200"]);
+    let upper = h.model.storeys.add("First", 300.0);
+    h.model.find_by_id_mut(ids[1]).unwrap().modify(|shape| {
+        if let Shape::Beam(beam) = shape {
+            beam.storey_id = upper;
+        }
+    });
+    assert_eq!(h.model.cleanup_junctions(), 0);
+}
+
+#[test]
+fn moved_beams_lose_their_joins_until_cleaned_up_again() {
+    let (mut h, ids) = beams(&["0,0; 400,0", "200,10; 200,300"]);
+    h.model.cleanup_junctions();
+    h.model.find_by_id_mut(ids[1]).unwrap().modify(|shape| {
+        if let Shape::Beam(beam) = shape {
+            beam.start = v(300.0, 10.0);
+            beam.end = v(300.0, 300.0);
+        }
+    });
+    h.model.changes.touch(ids[1]);
+    h.model.refresh_junctions(false);
+    assert!(h.model.beam_joins.junctions.is_empty());
+    assert!(h.model.beam_joins.joins.is_empty());
+
+    // Geometry is left alone by the pass
+    assert_eq!(h.model.cleanup_junctions(), 1);
+    match &h.entity(ids[1]).shape {
+        Shape::Beam(beam) => assert_eq!(beam.start, v(300.0, 10.0)),
+        other => panic!("expected a beam, got {:?}", other),
+    }
+}
+
+#[test]
+fn automatic_cleanup_joins_beams_as_they_are_placed() {
+    let (mut h, _) = beams(&["0,0; 400,0"]);
+    h.run("place_beam; 200,10; 200,300;");
+    assert!(h.model.beam_joins.junctions.is_empty());
+
+    h.executor.defaults.auto_cleanup = true;
+    h.run("place_beam; 0,0; 0,300;");
+    let kinds: Vec<_> = h
+        .model
+        .beam_joins
+        .junctions
+        .iter()
+        .map(|j| j.kind)
+        .collect();
+    assert_eq!(kinds.len(), 2);
+    assert!(kinds.contains(&JunctionKind::T));
+    assert!(kinds.contains(&JunctionKind::L));
+}
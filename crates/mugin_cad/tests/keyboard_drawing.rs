@@ -0,0 +1,142 @@
+//! A small plan drawn from the terminal alone: commands, typed points,
+//! options and Escape, without a single click.
+
+#![cfg(feature = "gui")]
+
+mod common;
+
+use common::v;
+use mugin_cad::commands::CommandError;
+use mugin_cad::model::structure::beam::BeamAnchor;
+use mugin_cad::model::structure::beam_type::BeamType;
+use mugin_cad::model::structure::column::ColumnAnchor;
+use mugin_cad::model::structure::column_type::ColumnType;
+use mugin_cad::model::{Shape, Vector2};
+use mugin_cad::viewmodel::CadViewModel;
+
+/// Type each `;`-separated entry and press Enter
+fn type_in(vm: &mut CadViewModel, script: &str) {
+    for input in script.split(';') {
+        vm.command_input = input.trim().to_string();
+        vm.submit_command_input();
+    }
+}
+
+fn near(a: Vector2, b: Vector2) -> bool {
+    a.dist(b) < 1e-3
+}
+
+fn shapes(vm: &CadViewModel) -> Vec<Shape> {
+    vm.active_tab()
+        .model
+        .entities
+        .iter()
+        .map(|e| e.shape.clone())
+        .collect()
+}
+
+#[test]
+fn a_plan_is_drawn_with_typed_input_only() {
+    let mut vm = CadViewModel::new();
+    let definitions = &mut vm.active_tab_mut().model.definitions;
+    definitions.add_column_type(ColumnType::new(0, "S40", 40.0, 40.0, 0, 0, 0));
+    definitions.add_beam_type(BeamType::new(0, "B25", 25.0, 50.0, 0, 0));
+
+    // Walls: relative, polar and absolute points, closed with an option
+    type_in(&mut vm, "line; 0,0; @400,0; @0,300; @400<180; c");
+    let walls: Vec<(Vector2, Vector2)> = shapes(&vm)
+        .iter()
+        .map(|shape| match shape {
+            Shape::Line(line) => (line.start, line.end),
+            other => panic!("expected a line, got {:?}", other),
+        })
+        .collect();
+    assert_eq!(walls.len(), 4);
+    for (i, corner) in [v(0.0, 0.0), v(400.0, 0.0), v(400.0, 300.0), v(0.0, 300.0)]
+        .into_iter()
+        .enumerate()
+    {
+        assert!(near(walls[i].0, corner), "wall {}: {:?}", i, walls[i]);
+        assert!(
+            near(walls[(i + 3) % 4].1, corner),
+            "wall {}: {:?}",
+            i,
+            walls
+        );
+    }
+
+    // A table by its center, a lamp by radius, a door swing reversed
+    type_in(&mut vm, "rect; c; 200,150; @50,50");
+    type_in(&mut vm, "circle; 300,200; 25");
+    type_in(&mut vm, "arc; 100,0; @60,0; r; @-60<90");
+    // A column turned a quarter, and a beam hung from its top face
+    type_in(&mut vm, "place_column; a; 400,300; 90");
+    // An empty entry ends the beam chain, as Enter does
+    type_in(&mut vm, "place_beam; a; 0,300; @400,0;");
+
+    let shapes = shapes(&vm);
+    assert_eq!(shapes.len(), 9, "{:?}", shapes);
+    match &shapes[4] {
+        Shape::Rectangle(rect) => {
+            assert!(near(rect.min, v(150.0, 100.0)) && near(rect.max, v(250.0, 200.0)));
+        }
+        other => panic!("expected a rectangle, got {:?}", other),
+    }
+    assert!(matches!(&shapes[5], Shape::Circle(c) if (c.radius - 25.0).abs() < 1e-3));
+    assert!(matches!(&shapes[6], Shape::Arc(arc) if arc.clockwise));
+    match &shapes[7] {
+        Shape::Column(column) => {
+            assert_ne!(column.anchor, ColumnAnchor::Center);
+            assert!(near(column.anchor_point(), v(400.0, 300.0)));
+            assert!((column.rotation.to_degrees() - 90.0).abs() < 1e-3);
+        }
+        other => panic!("expected a column, got {:?}", other),
+    }
+    match &shapes[8] {
+        Shape::Beam(beam) => {
+            assert_ne!(beam.anchor, BeamAnchor::Center);
+            assert!(near(beam.end, v(400.0, 300.0)));
+        }
+        other => panic!("expected a beam, got {:?}", other),
+    }
+    assert!(!vm.active_tab().executor.is_active());
+}
+
+#[test]
+fn escape_backs_out_of_a_typed_command() {
+    let mut vm = CadViewModel::new();
+    type_in(&mut vm, "line; 0,0");
+    assert!(vm.active_tab().executor.is_active());
+    // The typed step leaves the pointer's preview out of the way
+    assert!(vm.active_tab().preview_paused);
+
+    vm.escape();
+    vm.escape();
+    assert!(!vm.active_tab().executor.is_active());
+    assert!(vm.active_tab().model.entities.is_empty());
+}
+
+#[test]
+fn relative_input_needs_a_point_to_start_from() {
+    let mut vm = CadViewModel::new();
+    type_in(&mut vm, "line; @10,0");
+    assert!(matches!(
+        vm.active_tab().executor.last_error,
+        Some(CommandError::MissingPrerequisite(_))
+    ));
+    type_in(&mut vm, "5,5; @10<abc");
+    assert!(matches!(
+        vm.active_tab().executor.last_error,
+        Some(CommandError::InvalidInput { .. })
+    ));
+    type_in(&mut vm, "@; @3,4");
+    // "@" repeats the last point, which makes a zero-length segment first
+    let ends: Vec<Vector2> = shapes(&vm)
+        .iter()
+        .filter_map(|shape| match shape {
+            Shape::Line(line) => Some(line.end),
+            _ => None,
+        })
+        .collect();
+    assert!(ends.iter().any(|&end| near(end, v(8.0, 9.0))), "{:?}", ends);
+}
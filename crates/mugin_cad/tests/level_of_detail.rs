@@ -0,0 +1,108 @@
+//! Culling of length labels when zoomed out, and the frame time it saves.
+
+#![cfg(feature = "gui")]
+
+mod common;
+
+use common::v;
+use eframe::egui;
+use mugin_cad::model::config::DetailThresholds;
+use mugin_cad::model::dimension::DimensionStyle;
+use mugin_cad::model::layer::{DEFAULT_STROKE_WIDTH, LineStyle};
+use mugin_cad::model::structure::definitions::StructureDefinitions;
+use mugin_cad::model::{Entity, Shape};
+use mugin_cad::view::rendering::context::{DEFAULT_ENTITY_COLOR, DrawContext};
+use mugin_cad::view::rendering::renderable::Renderable;
+use std::time::{Duration, Instant};
+
+/// 20 000 labelled lines on a 200 x 100 grid
+fn labelled_lines() -> Vec<Entity> {
+    let mut entities = Vec::new();
+    for i in 0..200 {
+        for j in 0..100 {
+            let start = v(i as f32 * 100.0, j as f32 * 100.0);
+            let mut entity = Entity::line(start, start + v(40.0, 0.0));
+            if let Shape::Line(line) = &mut entity.shape {
+                line.show_length = true;
+            }
+            entities.push(entity);
+        }
+    }
+    entities
+}
+
+/// Draw every entity at `zoom` once; returns the time taken and the
+/// number of shapes painted
+fn render_frame(entities: &[Entity], zoom: f32) -> (Duration, usize) {
+    let ctx = egui::Context::default();
+    let rect = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(1600.0, 900.0));
+    let input = egui::RawInput {
+        screen_rect: Some(rect),
+        ..Default::default()
+    };
+    let definitions = StructureDefinitions::new();
+    let dim_style = DimensionStyle::default();
+    let mut elapsed = Duration::ZERO;
+    let output = ctx.run(input, |ctx| {
+        let painter = egui::Painter::new(ctx.clone(), egui::LayerId::background(), rect);
+        let draw = DrawContext {
+            painter: &painter,
+            zoom,
+            offset: v(0.0, 0.0),
+            screen_center: v(800.0, 450.0),
+            transform: glam::Affine2::IDENTITY,
+            dim_style: &dim_style,
+            color: DEFAULT_ENTITY_COLOR,
+            emphasized: false,
+            line_style: LineStyle::Solid,
+            stroke_width: DEFAULT_STROKE_WIDTH,
+            detail: DetailThresholds::default(),
+            flooring: Default::default(),
+        };
+        let start = Instant::now();
+        for entity in entities {
+            entity.render(&draw, &definitions, false, false);
+        }
+        elapsed = start.elapsed();
+    });
+    (elapsed, output.shapes.len())
+}
+
+#[test]
+fn zoomed_out_labels_are_skipped() {
+    let entities = labelled_lines();
+
+    // 12-unit labels are 1.2 px tall at this zoom, under the 4 px threshold
+    let (_, culled_shapes) = render_frame(&entities, 0.1);
+    let (_, full_shapes) = render_frame(&entities, 1.0);
+    assert_eq!(culled_shapes, entities.len());
+    assert!(full_shapes > 2 * entities.len());
+}
+
+/// Timing depends on the machine, so this only runs on request:
+/// `cargo test --release --test level_of_detail -- --ignored`
+#[test]
+#[ignore = "benchmark"]
+fn zoomed_out_frame_fits_the_budget() {
+    let entities = labelled_lines();
+    let (culled, _) = render_frame(&entities, 0.1);
+    let (full, _) = render_frame(&entities, 1.0);
+
+    // Well under a frame in release; unoptimized test builds get headroom
+    let budget = if cfg!(debug_assertions) {
+        Duration::from_millis(250)
+    } else {
+        Duration::from_millis(16)
+    };
+    assert!(
+        culled < budget,
+        "20k lines with skipped labels took {:?}",
+        culled
+    );
+    assert!(
+        culled < full,
+        "skipping labels took {:?}, drawing them {:?}",
+        culled,
+        full
+    );
+}
@@ -0,0 +1,130 @@
+//! Freehand markup sketches: path simplification, the markup layer and
+//! finding sketches under the pointer.
+
+mod common;
+
+use common::{Harness, v};
+use mugin_cad::model::math::geometry::simplify_path;
+use mugin_cad::model::tools::markup::MARKUP_LAYER;
+use mugin_cad::model::{Entity, Geometry, Shape, Sketch, Vector2};
+
+#[test]
+fn simplify_keeps_short_paths_and_the_ends() {
+    assert!(simplify_path(&[], 1.0).is_empty());
+    assert_eq!(simplify_path(&[v(0.0, 0.0)], 1.0), vec![v(0.0, 0.0)]);
+    let pair = [v(0.0, 0.0), v(5.0, 5.0)];
+    assert_eq!(simplify_path(&pair, 100.0), pair.to_vec());
+
+    // Collinear points in between go at any tolerance
+    let straight: Vec<Vector2> = (0..=10).map(|i| v(i as f32, 0.0)).collect();
+    assert_eq!(
+        simplify_path(&straight, 0.0),
+        vec![v(0.0, 0.0), v(10.0, 0.0)]
+    );
+}
+
+#[test]
+fn simplify_drops_only_points_within_the_tolerance() {
+    let path = [v(0.0, 0.0), v(5.0, 0.4), v(10.0, 0.0), v(10.0, 10.0)];
+    // The wobble is 0.4 off the chord, the corner 10 away
+    assert_eq!(
+        simplify_path(&path, 0.5),
+        vec![v(0.0, 0.0), v(10.0, 0.0), v(10.0, 10.0)]
+    );
+    assert_eq!(simplify_path(&path, 0.3), path.to_vec());
+    assert_eq!(simplify_path(&path, 20.0), vec![v(0.0, 0.0), v(10.0, 10.0)]);
+}
+
+#[test]
+fn simplify_follows_a_jittery_stroke() {
+    // A hand-drawn L: two straight runs with sub-pixel jitter
+    let mut stroke: Vec<Vector2> = (0..=50)
+        .map(|i| v(i as f32, if i % 2 == 0 { 0.1 } else { -0.1 }))
+        .collect();
+    stroke.extend((1..=50).map(|i| v(50.0 + if i % 2 == 0 { 0.1 } else { -0.1 }, i as f32)));
+
+    let simple = simplify_path(&stroke, 0.5);
+    assert_eq!(simple.len(), 3);
+    assert_eq!(simple[0], stroke[0]);
+    assert_eq!(simple[2], *stroke.last().unwrap());
+    assert!(simple[1].dist(v(50.0, 0.0)) < 0.5);
+
+    // Every dropped point stays within the tolerance of the result
+    for p in &stroke {
+        let off = simple
+            .windows(2)
+            .map(|pair| p.dist_to_line(pair[0], pair[1]))
+            .fold(f32::MAX, f32::min);
+        assert!(off <= 0.5, "{:?} is {} off the simplified path", p, off);
+    }
+}
+
+#[test]
+fn strokes_become_unplotted_sketches_on_the_markup_layer() {
+    let mut h = Harness::new();
+    let stroke = [v(0.0, 0.0), v(10.0, 0.1), v(20.0, 0.0), v(20.0, 20.0)];
+    let id = h.model.add_sketch(&stroke, 0.5, false).unwrap();
+    let highlight = h
+        .model
+        .add_sketch(&[v(0.0, 50.0), v(40.0, 50.0)], 0.5, true)
+        .unwrap();
+
+    match &h.entity(id).shape {
+        Shape::Sketch(sketch) => {
+            assert_eq!(
+                sketch.points,
+                vec![v(0.0, 0.0), v(20.0, 0.0), v(20.0, 20.0)]
+            );
+            assert!(!sketch.highlighter);
+        }
+        other => panic!("expected a sketch, got {:?}", other),
+    }
+    let layer = h
+        .model
+        .layer_manager
+        .get_layer(h.entity(id).layer_id)
+        .unwrap();
+    assert_eq!(layer.name, MARKUP_LAYER);
+    assert!(!layer.plottable);
+    assert_eq!(h.entity(highlight).layer_id, layer.id);
+
+    // A stroke that never left its first point adds nothing
+    assert_eq!(
+        h.model.add_sketch(&[v(5.0, 5.0), v(5.2, 5.0)], 0.5, false),
+        None
+    );
+    assert_eq!(h.model.sketches().len(), 2);
+}
+
+#[test]
+fn sketches_give_no_snap_points() {
+    let sketch = Shape::Sketch(Sketch::new(vec![v(0.0, 0.0), v(10.0, 0.0)], false));
+    assert!(sketch.snap_points().is_empty());
+}
+
+#[test]
+fn only_sketches_are_found_for_erasing_and_clearing() {
+    let mut h = Harness::new();
+    let line = Entity::line(v(0.0, 0.0), v(100.0, 0.0));
+    let line_id = line.id;
+    h.model.add_entity(line);
+    let sketch = h
+        .model
+        .add_sketch(&[v(0.0, -20.0), v(50.0, 0.0), v(100.0, -20.0)], 0.5, false)
+        .unwrap();
+
+    // The sketch is drawn last, over the line
+    assert_eq!(h.model.pick_entity_id(v(50.0, 0.0), 1.0), Some(sketch));
+    assert_eq!(h.model.sketch_at(v(50.0, 0.0), 1.0), Some(sketch));
+    assert_eq!(
+        h.model.pick_where(None, v(50.0, 0.0), 1.0, &|e| !matches!(
+            e.shape,
+            Shape::Sketch(_)
+        )),
+        Some(line_id)
+    );
+    assert_eq!(h.model.sketch_at(v(90.0, 0.0), 1.0), None);
+
+    assert_eq!(h.model.clear_sketches(), 1);
+    assert_eq!(h.ids(), vec![line_id]);
+}
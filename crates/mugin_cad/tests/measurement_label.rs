@@ -0,0 +1,61 @@
+mod common;
+
+use common::{Harness, assert_near};
+use mugin_cad::commands::measure::MeasurementKind;
+use mugin_cad::model::Shape;
+use mugin_cad::model::Vector2;
+use mugin_cad::model::shapes::annotation::{AnnotationType, TextAnnotation};
+
+fn text(h: &Harness, id: u64) -> &TextAnnotation {
+    match &h.entity(id).shape {
+        Shape::Text(text) => text,
+        other => panic!("expected text, got {:?}", other),
+    }
+}
+
+#[test]
+fn label_places_the_last_distance_with_a_leader() {
+    let mut h = Harness::new();
+    h.executor.dimension_style.suffix = " cm".to_string();
+    h.run("distance; 0,0; 30,40");
+    let measurement = h.executor.last_measurement.unwrap();
+    assert_eq!(measurement.kind, MeasurementKind::Distance);
+
+    h.run("label; 100,100");
+    assert!(!h.executor.is_active());
+    let label = text(&h, *h.ids().last().unwrap());
+    assert_eq!(label.text, "50.00 cm");
+    assert_eq!(label.annotation_type, AnnotationType::Leader);
+    assert_near(label.position, Vector2::new(100.0, 100.0));
+    assert_near(label.leader_target().unwrap(), Vector2::new(15.0, 20.0));
+}
+
+#[test]
+fn areas_are_labelled_in_square_units() {
+    let mut h = Harness::new();
+    h.executor.dimension_style.suffix = " m".to_string();
+    h.executor.dimension_style.precision = 1;
+    h.run("line; 0,0; 10,0; 10,20; 0,20; c");
+    h.run("area; 5,5");
+    assert_eq!(
+        h.executor.last_measurement.unwrap().kind,
+        MeasurementKind::Area
+    );
+
+    h.run("text; 50,50; room lastresult");
+    let label = text(&h, *h.ids().last().unwrap());
+    assert_eq!(label.text, "room 200.0 m²");
+    assert_eq!(label.leader_target(), None);
+}
+
+#[test]
+fn nothing_to_label_before_measuring() {
+    let mut h = Harness::new();
+    h.run("label");
+    assert!(!h.executor.is_active());
+    assert!(h.executor.status_message.starts_with("Nothing measured"));
+
+    h.run("text; 0,0; lastresult");
+    assert!(h.ids().is_empty());
+    assert!(h.executor.is_active());
+}
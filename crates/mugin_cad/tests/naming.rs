@@ -0,0 +1,84 @@
+mod common;
+
+use common::{Harness, v};
+use mugin_cad::model::{CadModel, Entity};
+use std::collections::HashSet;
+
+fn names(model: &CadModel) -> Vec<&str> {
+    model.entities.iter().map(|e| e.name.as_str()).collect()
+}
+
+#[test]
+fn new_entities_are_numbered_per_type() {
+    let mut h = Harness::new();
+    h.run("line; 0,0; 10,0; 10,10;");
+    h.run("circle; 0,0; 5");
+    h.run("line; 0,20; 10,20;");
+    assert_eq!(names(&h.model), ["Line 1", "Line 2", "Circle 1", "Line 3"]);
+}
+
+#[test]
+fn explicit_names_are_kept() {
+    let mut model = CadModel::new();
+    let mut wall = Entity::line(v(0.0, 0.0), v(10.0, 0.0));
+    wall.name = "Wall A".to_string();
+    model.add_entity(wall);
+    assert_eq!(names(&model), ["Wall A"]);
+}
+
+#[test]
+fn loaded_names_stay_and_numbering_continues() {
+    let mut model = CadModel::new();
+    let mut numbered = Entity::line(v(0.0, 0.0), v(1.0, 0.0));
+    numbered.name = "Line 7".to_string();
+    // As loaded from an older file, without going through `add_entity`
+    model.entities = vec![Entity::line(v(0.0, 0.0), v(1.0, 0.0)), numbered];
+
+    model.add_entity(Entity::line(v(0.0, 0.0), v(1.0, 0.0)));
+    assert_eq!(names(&model), ["Line", "Line 7", "Line 8"]);
+}
+
+#[test]
+fn unique_name_appends_copy_numbers() {
+    let mut model = CadModel::new();
+    assert_eq!(model.unique_name("Wall A"), "Wall A");
+
+    for name in ["Wall A", "Wall A (copy)"] {
+        let mut entity = Entity::line(v(0.0, 0.0), v(1.0, 0.0));
+        entity.name = name.to_string();
+        model.add_entity(entity);
+    }
+    assert_eq!(model.unique_name("Wall A"), "Wall A (copy 2)");
+    // Copying a copy counts on from the original
+    assert_eq!(model.unique_name("Wall A (copy)"), "Wall A (copy 2)");
+}
+
+#[test]
+fn copy_gets_fresh_ids_and_copy_names() {
+    let mut h = Harness::new();
+    h.run("line; 0,0; 10,0;");
+    let original = h.ids()[0];
+    h.select([original]);
+    h.run("copy; 0,0; 0,10");
+    h.run("copy; 0,0; 0,20");
+
+    assert_eq!(
+        names(&h.model),
+        ["Line 1", "Line 1 (copy)", "Line 1 (copy 2)"]
+    );
+    let ids: HashSet<u64> = h.ids().into_iter().collect();
+    assert_eq!(ids.len(), 3);
+}
+
+#[test]
+fn cut_moves_the_originals() {
+    let mut h = Harness::new();
+    h.run("line; 0,0; 10,0;");
+    let original = h.ids()[0];
+    h.select([original]);
+    h.run("cut; 0,0; 0,10");
+
+    assert_eq!(h.ids(), [original]);
+    assert_eq!(h.entity(original).name, "Line 1");
+    assert_eq!(h.entity(original).as_polyline()[0], v(0.0, 10.0));
+}
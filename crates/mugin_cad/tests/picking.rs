@@ -0,0 +1,88 @@
+mod common;
+
+use common::v;
+use mugin_cad::model::config::{AppearanceConfig, CrosshairStyle, SnapConfig};
+use mugin_cad::model::{Arc, CadModel, Entity};
+use std::f32::consts::FRAC_PI_2;
+
+fn model_with(entity: Entity) -> (CadModel, u64) {
+    let mut model = CadModel::new();
+    let id = entity.id;
+    model.add_entity(entity);
+    model.update_hierarchy();
+    (model, id)
+}
+
+/// Tolerance at a zoom where the shapes below are far larger than it
+fn tolerance() -> f32 {
+    SnapConfig::default().world_pick_tolerance(10.0)
+}
+
+#[test]
+fn filled_rectangle_picks_inside() {
+    let (model, id) = model_with(Entity::rectangle(v(0.0, 0.0), v(100.0, 50.0), true));
+    assert_eq!(model.pick_entity_id(v(50.0, 25.0), tolerance()), Some(id));
+}
+
+#[test]
+fn outlined_rectangle_needs_the_edge() {
+    let (model, id) = model_with(Entity::rectangle(v(0.0, 0.0), v(100.0, 50.0), false));
+    assert_eq!(model.pick_entity_id(v(50.0, 25.0), tolerance()), None);
+    assert_eq!(model.pick_entity_id(v(50.0, 0.0), tolerance()), Some(id));
+}
+
+#[test]
+fn rectangle_corners_in_any_order() {
+    let (model, id) = model_with(Entity::rectangle(v(100.0, 50.0), v(0.0, 0.0), true));
+    assert_eq!(model.pick_entity_id(v(50.0, 25.0), tolerance()), Some(id));
+}
+
+#[test]
+fn filled_arc_picks_its_slice() {
+    let arc = Arc {
+        center: v(0.0, 0.0),
+        radius: 100.0,
+        start_angle: 0.0,
+        end_angle: FRAC_PI_2,
+        filled: true,
+        clockwise: false,
+    };
+    let (model, id) = model_with(Entity::arc(arc));
+    assert_eq!(model.pick_entity_id(v(30.0, 30.0), tolerance()), Some(id));
+    assert_eq!(model.pick_entity_id(v(-30.0, -30.0), tolerance()), None);
+}
+
+#[test]
+fn tolerance_scales_with_zoom() {
+    let config = SnapConfig::default();
+    assert!(config.world_pick_tolerance(2.0) < config.world_pick_tolerance(1.0));
+}
+
+#[test]
+fn hidden_layer_entities_are_not_picked() {
+    let (mut model, id) = model_with(Entity::rectangle(v(0.0, 0.0), v(100.0, 50.0), true));
+    assert_eq!(model.pick_entity_id(v(50.0, 25.0), tolerance()), Some(id));
+
+    let layer_id = model.find_by_id(id).unwrap().layer_id;
+    let layer = model.layer_manager.layers.get_mut(&layer_id).unwrap();
+    layer.is_visible = false;
+    assert_eq!(model.pick_entity_id(v(50.0, 25.0), tolerance()), None);
+}
+
+#[test]
+fn crosshair_spans_a_share_of_the_canvas_or_all_of_it() {
+    let mut crosshair = CrosshairStyle::default();
+    assert!(crosshair.full_screen);
+    assert_eq!(crosshair.half_extent(800.0, 600.0), (800.0, 600.0));
+
+    crosshair.full_screen = false;
+    crosshair.size_percent = 10.0;
+    let (half_w, half_h) = crosshair.half_extent(800.0, 600.0);
+    assert!((half_w - 40.0).abs() < 1e-4 && (half_h - 30.0).abs() < 1e-4);
+
+    // Settings saved before the crosshair options load with the defaults
+    let appearance: AppearanceConfig =
+        serde_json::from_str(r#"{"background_color":[0,0,0],"selection_color":[255,215,0]}"#)
+            .unwrap();
+    assert_eq!(appearance.crosshair, CrosshairStyle::default());
+}
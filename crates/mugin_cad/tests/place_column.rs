@@ -0,0 +1,100 @@
+mod common;
+
+use common::{Harness, assert_near};
+use mugin_cad::commands::InputModifiers;
+use mugin_cad::model::structure::column::ColumnData;
+use mugin_cad::model::structure::column_type::ColumnType;
+use mugin_cad::model::{Shape, Vector2};
+
+fn harness() -> Harness {
+    let mut h = Harness::new();
+    h.model
+        .definitions
+        .add_column_type(ColumnType::new(0, "C40x60", 40.0, 60.0, 0, 0, 0));
+    h
+}
+
+fn columns(h: &Harness) -> Vec<&ColumnData> {
+    h.model
+        .entities
+        .iter()
+        .filter_map(|entity| match &entity.shape {
+            Shape::Column(col) => Some(col),
+            _ => None,
+        })
+        .collect()
+}
+
+#[track_caller]
+fn assert_angle(actual: f32, expected_degrees: f32) {
+    let diff = (actual.to_degrees() - expected_degrees).rem_euclid(360.0);
+    assert!(
+        diff.min(360.0 - diff) < 1e-3,
+        "expected {}°, got {}°",
+        expected_degrees,
+        actual.to_degrees()
+    );
+}
+
+#[test]
+fn rotation_waits_for_the_second_point() {
+    let mut h = harness();
+    h.run("place_column; 0,0");
+    assert!(columns(&h).is_empty());
+
+    h.click(10.0, 100.0);
+    let placed = columns(&h);
+    assert_eq!(placed.len(), 1);
+    assert_near(placed[0].center, Vector2::new(0.0, 0.0));
+    assert_angle(placed[0].rotation, 90.0);
+}
+
+#[test]
+fn cursor_direction_snaps_to_quarter_turns() {
+    let mut h = harness();
+    h.run("place_column; 0,0; 100,30");
+    h.run("0,0; -100,-60");
+    let angles: Vec<f32> = columns(&h).iter().map(|col| col.rotation).collect();
+    assert_angle(angles[0], 0.0);
+    assert_angle(angles[1], 180.0);
+}
+
+#[test]
+fn shift_rotates_freely() {
+    let mut h = harness();
+    h.executor.set_modifiers(InputModifiers {
+        shift: true,
+        ..Default::default()
+    });
+    h.run("place_column; 0,0; 100,100");
+    assert_angle(columns(&h)[0].rotation, 45.0);
+}
+
+#[test]
+fn typed_angle_is_exact() {
+    let mut h = harness();
+    h.run("place_column; 50,50; 30");
+    let col = columns(&h)[0];
+    assert_angle(col.rotation, 30.0);
+    assert_near(col.center, Vector2::new(50.0, 50.0));
+}
+
+#[test]
+fn faces_snap_to_the_axis_under_the_cursor() {
+    let mut h = harness();
+    h.model.axis_manager.add_vertical(200.0);
+    // 34° off the base: a quarter-turn snap would pick 0°, but the
+    // cursor is on the vertical axis
+    h.run("place_column; 170,0; 200,20");
+    assert_angle(columns(&h)[0].rotation, 90.0);
+}
+
+#[test]
+fn escape_goes_back_to_the_insertion_point() {
+    let mut h = harness();
+    h.run("place_column; 0,0");
+    assert!(h.executor.cancel_step());
+    h.run("100,0; 0");
+    let col = columns(&h)[0];
+    assert_near(col.center, Vector2::new(100.0, 0.0));
+}
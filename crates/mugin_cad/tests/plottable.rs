@@ -0,0 +1,117 @@
+mod common;
+
+use common::v;
+use mugin_cad::model::layer::{DEFAULT_LAYER_COLOR, Layer, LayerManager, LayerTemplate};
+use mugin_cad::model::{CadModel, Entity};
+use std::collections::HashSet;
+
+/// A model with a 100×100 square on the default layer and a long
+/// construction line on its own layer
+fn model_with_construction() -> (CadModel, u64, u64) {
+    let mut model = CadModel::new();
+    model.add_entity(Entity::rectangle(v(0.0, 0.0), v(100.0, 100.0), false));
+    let construction = model
+        .layer_manager
+        .add_layer("Construction".to_string(), DEFAULT_LAYER_COLOR);
+    let mut line = Entity::line(v(-1000.0, 50.0), v(1000.0, 50.0));
+    line.layer_id = construction;
+    let line_id = line.id;
+    model.add_entity(line);
+    model.update_hierarchy();
+    (model, construction, line_id)
+}
+
+#[test]
+fn layers_plot_unless_turned_off() {
+    let (mut model, construction, line_id) = model_with_construction();
+    assert_eq!(model.plotted_entities().len(), 2);
+    assert_eq!(model.plot_bounds(), model.bounds());
+    assert!(model.layer_manager.unplotted_layers().is_empty());
+
+    model
+        .layer_manager
+        .layers
+        .get_mut(&construction)
+        .unwrap()
+        .plottable = false;
+    let plotted = model.plotted_entities();
+    assert_eq!(plotted.len(), 1);
+    assert!(plotted.iter().all(|entity| entity.id != line_id));
+    assert_eq!(model.plot_bounds(), (v(0.0, 0.0), v(100.0, 100.0)));
+    assert_eq!(model.layer_manager.unplotted_layers().len(), 1);
+    // The canvas still has it
+    assert_eq!(model.bounds().0.x, -1000.0);
+}
+
+#[test]
+fn entities_override_their_layer() {
+    let (mut model, construction, line_id) = model_with_construction();
+    model
+        .layer_manager
+        .layers
+        .get_mut(&construction)
+        .unwrap()
+        .plottable = false;
+
+    // A line printed from a construction layer
+    model.find_by_id_mut(line_id).unwrap().plottable = Some(true);
+    assert_eq!(model.plotted_entities().len(), 2);
+
+    // And a square kept off a printing one
+    let square = model.entities[0].id;
+    model.find_by_id_mut(square).unwrap().plottable = Some(false);
+    let plotted = model.plotted_entities();
+    assert_eq!(plotted.len(), 1);
+    assert_eq!(plotted[0].id, line_id);
+
+    let layer = model.layer_manager.get_layer(0);
+    assert!(!model.find_by_id(square).unwrap().style(layer).plottable);
+}
+
+#[test]
+fn groups_follow_their_own_setting_only() {
+    let mut model = CadModel::new();
+    let hidden = model
+        .layer_manager
+        .add_layer("Hidden".to_string(), DEFAULT_LAYER_COLOR);
+    model
+        .layer_manager
+        .layers
+        .get_mut(&hidden)
+        .unwrap()
+        .plottable = false;
+
+    // A group on an unplotted layer still prints its children...
+    let mut group = Entity::empty("Group");
+    group.layer_id = hidden;
+    group.children.push(Entity::line(v(0.0, 0.0), v(10.0, 0.0)));
+    let group_id = group.id;
+    model.add_entity(group);
+    model.update_hierarchy();
+    assert_eq!(model.plotted_entities()[0].children.len(), 1);
+
+    // ...unless it is turned off itself
+    model.find_by_id_mut(group_id).unwrap().plottable = Some(false);
+    assert!(model.plotted_entities().is_empty());
+    assert_eq!(model.plot_bounds(), (v(0.0, 0.0), v(100.0, 100.0)));
+}
+
+#[test]
+fn older_files_and_templates_default_to_plottable() {
+    let layer: Layer =
+        serde_json::from_str(r#"{"id":3,"name":"Walls","color":[255,0,0,255],"is_visible":true}"#)
+            .unwrap();
+    assert!(layer.plottable);
+    let entity = Entity::line(v(0.0, 0.0), v(1.0, 0.0));
+    let json = serde_json::to_string(&entity).unwrap();
+    assert!(!json.contains("plottable"));
+
+    // Templates carry the flag
+    let mut source = LayerManager::new();
+    let markup = source.add_layer("Markup".to_string(), DEFAULT_LAYER_COLOR);
+    source.layers.get_mut(&markup).unwrap().plottable = false;
+    let template = LayerTemplate::from_layers(&source);
+    let mut target = LayerManager::new();
+    target.merge_template(&template, &HashSet::new());
+    assert_eq!(target.unplotted_layers()[0].name, "Markup");
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use common::scratch_dir;
+use mugin_cad::model::Entity;
+use mugin_cad::model::Vector2;
+use mugin_cad::model::config::AppConfig;
+use mugin_cad::model::drafting::DraftingDefaults;
+use mugin_cad::model::project::ProjectData;
+use mugin_cad::model::recovery::{CRASH_LOG, list_crash_files, log_panic, write_crash_file};
+use mugin_cad::model::structure::definitions::StructureDefinitions;
+
+fn project(entities: Vec<Entity>) -> ProjectData {
+    ProjectData::new(
+        entities,
+        Vec::new(),
+        AppConfig::default(),
+        StructureDefinitions::new(),
+        DraftingDefaults::default(),
+    )
+}
+
+#[test]
+fn crashed_tabs_are_written_and_listed_for_recovery() {
+    let dir = scratch_dir("write");
+    let line = Entity::line(Vector2::new(0.0, 0.0), Vector2::new(10.0, 0.0));
+    let first = write_crash_file(&dir, "Plan/A", || project(vec![line])).unwrap();
+    // A second tab of the same name does not overwrite the first
+    let second = write_crash_file(&dir, "Plan/A", || project(Vec::new())).unwrap();
+    assert_ne!(first, second);
+    assert_eq!(first.file_name().unwrap(), "Plan_A-crash.mugin");
+
+    let files = list_crash_files(&dir);
+    assert_eq!(files.len(), 2);
+    assert!(files.iter().any(|f| f.name == "Plan_A"));
+    let restored = ProjectData::read(&first).unwrap();
+    assert_eq!(restored.entities.len(), 1);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn a_tab_that_panics_while_saving_is_skipped() {
+    let dir = scratch_dir("panic");
+    let result = write_crash_file(&dir, "Broken", || panic!("model is corrupt"));
+    assert!(result.is_err());
+    assert!(list_crash_files(&dir).is_empty());
+
+    // The other tabs still get saved
+    assert!(write_crash_file(&dir, "Fine", || project(Vec::new())).is_ok());
+    assert_eq!(list_crash_files(&dir).len(), 1);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn panics_are_appended_to_the_crash_log() {
+    let dir = scratch_dir("log");
+    log_panic(&dir, "first failure").unwrap();
+    log_panic(&dir, "second failure").unwrap();
+    let log = std::fs::read_to_string(dir.join(CRASH_LOG)).unwrap();
+    assert!(log.contains("first failure"));
+    assert!(log.contains("second failure"));
+    // The log is not mistaken for a recovery file
+    assert!(list_crash_files(&dir).is_empty());
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use common::{Harness, v};
+use mugin_cad::model::tools::find_replace::{FindScope, apply_matches, find_matches};
+use mugin_cad::model::{CadModel, Entity, Shape, TextAnnotation};
+use std::collections::HashSet;
+
+/// Assert that `edit` changes the entity's revision
+#[track_caller]
+fn bumps(entity: &mut Entity, edit: impl FnOnce(&mut Entity)) {
+    let before = entity.revision();
+    edit(entity);
+    assert_ne!(entity.revision(), before);
+}
+
+#[test]
+fn transforms_bump() {
+    let mut entity = Entity::line(v(0.0, 0.0), v(10.0, 0.0));
+    bumps(&mut entity, |e| e.translate(v(1.0, 2.0)));
+    bumps(&mut entity, |e| e.rotate(v(0.0, 0.0), 0.5));
+    bumps(&mut entity, |e| e.mirror(v(0.0, 0.0), v(0.0, 1.0)));
+    bumps(&mut entity, |e| e.scale(v(0.0, 0.0), 2.0));
+}
+
+#[test]
+fn scale_bumps_children() {
+    let mut group = Entity::empty("Group");
+    group.children.push(Entity::line(v(0.0, 0.0), v(1.0, 0.0)));
+    let child_before = group.children[0].revision();
+    group.scale(v(0.0, 0.0), 3.0);
+    assert_ne!(group.children[0].revision(), child_before);
+}
+
+#[test]
+fn modify_bumps() {
+    let mut entity = Entity::circle(v(0.0, 0.0), 1.0, false);
+    bumps(&mut entity, |e| {
+        e.modify(|shape| {
+            if let Shape::Circle(circle) = shape {
+                circle.radius = 2.0;
+            }
+        })
+    });
+}
+
+#[test]
+fn clones_and_style_edits_keep_it() {
+    let mut entity = Entity::line(v(0.0, 0.0), v(1.0, 0.0));
+    let before = entity.revision();
+    assert_eq!(entity.clone().revision(), before);
+    entity.color = Some([255, 0, 0]);
+    entity.layer_id = 7;
+    entity.name = "Edge".to_string();
+    assert_eq!(entity.revision(), before);
+}
+
+#[test]
+fn revisions_are_never_reused() {
+    let mut entity = Entity::line(v(0.0, 0.0), v(1.0, 0.0));
+    let snapshot = entity.clone();
+    entity.translate(v(1.0, 0.0));
+    let moved = entity.revision();
+    // Put the old geometry back, as undo does, then edit again
+    entity = snapshot;
+    entity.translate(v(2.0, 0.0));
+    assert_ne!(entity.revision(), moved);
+}
+
+#[test]
+fn label_moves_bump() {
+    let mut model = CadModel::new();
+    let line = Entity::line(v(0.0, 0.0), v(10.0, 0.0));
+    let id = line.id;
+    model.add_entity(line);
+    let before = model.find_by_id(id).unwrap().revision();
+    model.move_label(id, v(0.0, 5.0));
+    assert_ne!(model.find_by_id(id).unwrap().revision(), before);
+}
+
+#[test]
+fn replacing_text_bumps_but_renaming_does_not() {
+    let mut model = CadModel::new();
+    let mut note = Entity::text(TextAnnotation::new_custom(v(0.0, 0.0), "old".to_string()));
+    note.name = "note".to_string();
+    let note_id = note.id;
+    model.add_entity(note);
+    let mut named = Entity::line(v(0.0, 0.0), v(1.0, 0.0));
+    named.name = "old line".to_string();
+    let named_id = named.id;
+    model.add_entity(named);
+
+    let revision = |model: &CadModel, id| model.find_by_id(id).unwrap().revision();
+    let (note_before, named_before) = (revision(&model, note_id), revision(&model, named_id));
+    let matches = find_matches(
+        &model,
+        FindScope::Project,
+        &HashSet::new(),
+        "old",
+        "new",
+        true,
+    );
+    assert_eq!(apply_matches(&mut model, &matches), 2);
+    assert_ne!(revision(&model, note_id), note_before);
+    assert_eq!(revision(&model, named_id), named_before);
+}
+
+#[test]
+fn trim_bumps() {
+    let mut h = Harness::new();
+    h.run("line; 0,0; 10,0;");
+    h.run("line; 5,-5; 5,5;");
+    let horizontal = h.ids()[0];
+    let before = h.entity(horizontal).revision();
+    h.run("trim; 8,0;");
+    assert_ne!(h.entity(horizontal).revision(), before);
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use common::Harness;
+use mugin_cad::commands::create::revision_cloud::MARKUP_LAYER;
+use mugin_cad::model::{Geometry, RevisionCloud, Shape, Vector2};
+
+fn cloud(h: &Harness, id: u64) -> RevisionCloud {
+    match &h.entity(id).shape {
+        Shape::RevisionCloud(cloud) => cloud.clone(),
+        other => panic!("expected a revision cloud, got {:?}", other),
+    }
+}
+
+#[test]
+fn traced_clouds_bulge_outward_on_the_markup_layer() {
+    let mut h = Harness::new();
+    // "a" is also the AXIS alias; here it is the revision letter
+    h.run("revcloud; a; 25; t; a; 0,0; 100,0; 100,100; 0,100; c");
+
+    let id = h.ids()[0];
+    let first = cloud(&h, id);
+    assert_eq!(first.boundary.len(), 4);
+    assert_eq!(first.arc_size, 25.0);
+    assert_eq!(first.tag.as_deref(), Some("A"));
+    // Four bulges per side, each rising past the boundary
+    assert_eq!(first.chords().len(), 16);
+    let (min, max) = first.as_polyline().iter().fold(
+        (
+            Vector2::new(f32::MAX, f32::MAX),
+            Vector2::new(f32::MIN, f32::MIN),
+        ),
+        |(min, max), p| {
+            (
+                Vector2::new(min.x.min(p.x), min.y.min(p.y)),
+                Vector2::new(max.x.max(p.x), max.y.max(p.y)),
+            )
+        },
+    );
+    assert!(min.x < -5.0 && min.y < -5.0, "{:?}", min);
+    assert!(max.x > 105.0 && max.y > 105.0, "{:?}", max);
+    // The tag sits on the top-right corner
+    assert!(first.hit_test(Vector2::new(100.0, 100.0 - 50.0 * 3f32.sqrt() / 6.0), 0.5));
+
+    let markup = h.entity(id).layer_id;
+    assert_eq!(
+        h.model.layer_manager.get_layer(markup).unwrap().name,
+        MARKUP_LAYER
+    );
+    // Markup stays off printed sheets
+    assert!(!h.model.layer_manager.get_layer(markup).unwrap().plottable);
+
+    // Clockwise outlines bulge outward too, on the same layer
+    h.run("revcloud; 200,0; 200,100; 300,100; 300,0; 200,0");
+    let second = h.ids()[1];
+    let (min, _) = h.entity(second).bounding_box();
+    assert!(min.x < 195.0, "{:?}", min);
+    assert_eq!(h.entity(second).layer_id, markup);
+    assert_eq!(
+        h.model
+            .layer_manager
+            .layers
+            .values()
+            .filter(|layer| layer.name == MARKUP_LAYER)
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn picked_shapes_turn_into_clouds() {
+    let mut h = Harness::new();
+    h.run("rect; 0,0; 60,40");
+    h.run("revcloud; o; 60,20");
+    let ids = h.ids();
+    assert_eq!(ids.len(), 1);
+    let from_rect = cloud(&h, ids[0]);
+    assert_eq!(from_rect.boundary.len(), 4);
+    assert!(from_rect.tag.is_none());
+
+    // A loop of lines is found around the click and replaced
+    h.run("line; 100,0; 150,0; 150,50; 100,50; c");
+    h.run("revcloud; o; 125,25");
+    let ids = h.ids();
+    assert_eq!(ids.len(), 2);
+    let from_lines = cloud(&h, ids[1]);
+    assert_eq!(from_lines.boundary.len(), 4);
+    for corner in [Vector2::new(100.0, 0.0), Vector2::new(150.0, 50.0)] {
+        assert!(from_lines.boundary.iter().any(|p| p.dist(corner) < 1e-3));
+    }
+
+    h.run("revcloud; o; 500,500");
+    assert!(h.executor.status_message.contains("No closed shape"));
+}
@@ -0,0 +1,145 @@
+mod common;
+
+use common::v;
+use mugin_cad::export::rooms::rooms_csv;
+use mugin_cad::model::tools::rooms::find_loops;
+use mugin_cad::model::{CadModel, Entity, Geometry, Room, Shape, Vector2};
+
+/// Walls around the rectangle from `min` to `max`
+fn box_walls(min: Vector2, max: Vector2) -> Vec<(Vector2, Vector2)> {
+    let corners = [min, v(max.x, min.y), max, v(min.x, max.y)];
+    (0..4).map(|i| (corners[i], corners[(i + 1) % 4])).collect()
+}
+
+fn areas(loops: &[Vec<Vector2>]) -> Vec<f32> {
+    let mut areas: Vec<f32> = loops
+        .iter()
+        .map(|boundary| Room::new(boundary.clone(), String::new()).area)
+        .collect();
+    areas.sort_by(f32::total_cmp);
+    areas
+}
+
+#[test]
+fn a_partition_ending_on_walls_makes_two_rooms() {
+    let mut walls = box_walls(v(0.0, 0.0), v(600.0, 400.0));
+    walls.push((v(200.0, 0.0), v(200.0, 400.0)));
+    let loops = find_loops(&walls, 5.0);
+    assert_eq!(areas(&loops), vec![200.0 * 400.0, 400.0 * 400.0]);
+    // Counter-clockwise, without the split points along the outer walls
+    for boundary in &loops {
+        assert_eq!(boundary.len(), 4);
+    }
+}
+
+#[test]
+fn t_junctions_and_stubs() {
+    // A corridor wall stopping on the partition, and a stub into a room
+    let mut walls = box_walls(v(0.0, 0.0), v(600.0, 400.0));
+    walls.push((v(300.0, 0.0), v(300.0, 400.0)));
+    walls.push((v(300.0, 200.0), v(600.0, 200.0)));
+    walls.push((v(0.0, 200.0), v(100.0, 200.0)));
+    let loops = find_loops(&walls, 5.0);
+    assert_eq!(
+        areas(&loops),
+        vec![300.0 * 200.0, 300.0 * 200.0, 300.0 * 400.0]
+    );
+}
+
+#[test]
+fn nearly_closed_gaps_close_within_the_tolerance() {
+    // The last wall stops 3 short of the corner, and a partition 4 short
+    // of the far wall
+    let walls = vec![
+        (v(0.0, 0.0), v(400.0, 0.0)),
+        (v(400.0, 0.0), v(400.0, 300.0)),
+        (v(400.0, 300.0), v(0.0, 300.0)),
+        (v(0.0, 300.0), v(0.0, 3.0)),
+        (v(200.0, 0.0), v(200.0, 296.0)),
+    ];
+    let loops = find_loops(&walls, 5.0);
+    assert_eq!(loops.len(), 2);
+    for area in areas(&loops) {
+        assert!((area - 200.0 * 300.0).abs() < 2000.0, "area {}", area);
+    }
+
+    // A wider gap leaves the plan open
+    assert!(find_loops(&walls, 2.0).len() < 2);
+    let open = vec![
+        (v(0.0, 0.0), v(400.0, 0.0)),
+        (v(400.0, 0.0), v(400.0, 300.0)),
+        (v(400.0, 300.0), v(0.0, 300.0)),
+        (v(0.0, 300.0), v(0.0, 20.0)),
+    ];
+    assert!(find_loops(&open, 5.0).is_empty());
+}
+
+#[test]
+fn walls_crossing_at_the_corners_still_enclose() {
+    let walls = vec![
+        (v(-20.0, 0.0), v(420.0, 0.0)),
+        (v(400.0, -20.0), v(400.0, 320.0)),
+        (v(420.0, 300.0), v(-20.0, 300.0)),
+        (v(0.0, 320.0), v(0.0, -20.0)),
+    ];
+    let loops = find_loops(&walls, 5.0);
+    assert_eq!(areas(&loops), vec![400.0 * 300.0]);
+}
+
+#[test]
+fn rooms_are_labelled_with_their_area_and_offer_no_snaps() {
+    let room = Room::new(
+        vec![v(0.0, 0.0), v(400.0, 0.0), v(400.0, 365.0), v(0.0, 365.0)],
+        "Room 1".to_string(),
+    );
+    assert_eq!(room.label(), "Room 1 — 14.6 m²");
+    assert_eq!(room.center(), v(200.0, 182.5));
+    assert!(room.hit_test(v(200.0, 182.5), 1.0));
+    assert!(!room.hit_test(v(10.0, 10.0), 1.0));
+    assert!(Shape::Room(room).snap_points().is_empty());
+}
+
+#[test]
+fn regenerating_keeps_names_and_updates_areas() {
+    let mut model = CadModel::new();
+    for (a, b) in box_walls(v(0.0, 0.0), v(600.0, 400.0)) {
+        model.add_entity(Entity::line(a, b));
+    }
+    model.add_entity(Entity::line(v(200.0, 0.0), v(200.0, 400.0)));
+    let partition = model.entities[4].id;
+    model.update_hierarchy();
+
+    let update = model.regenerate_rooms(5.0);
+    assert_eq!((update.added.len(), update.updated), (2, 0));
+    for room in update.added {
+        model.add_entity(Entity::room(room));
+    }
+    let mut names: Vec<String> = model.rooms().iter().map(|(e, _)| e.name.clone()).collect();
+    names.sort();
+    assert_eq!(names, vec!["Room 1", "Room 2"]);
+
+    // Moving the partition changes both areas, not the names
+    model
+        .find_by_id_mut(partition)
+        .unwrap()
+        .translate(v(100.0, 0.0));
+    model.update_hierarchy();
+    let update = model.regenerate_rooms(5.0);
+    assert_eq!((update.added.len(), update.updated), (0, 2));
+    let mut rooms: Vec<(String, f32)> = model
+        .rooms()
+        .iter()
+        .map(|(_, room)| (room.name.clone(), room.area))
+        .collect();
+    rooms.sort_by(|a, b| a.1.total_cmp(&b.1));
+    assert_eq!(rooms.len(), 2);
+    assert_eq!(rooms[0].1, 300.0 * 400.0);
+    assert_eq!(rooms[1].1, 300.0 * 400.0);
+
+    let csv = rooms_csv(&model);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "id,name,area_m2,corners");
+    assert_eq!(lines.len(), 4);
+    assert!(lines[1].contains(",12.00,4"));
+    assert_eq!(lines[3], ",Total,24.00,");
+}
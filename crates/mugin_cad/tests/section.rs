@@ -0,0 +1,122 @@
+mod common;
+
+use common::{Harness, assert_near, v};
+use mugin_cad::model::structure::beam_type::BeamType;
+use mugin_cad::model::structure::column_type::ColumnType;
+use mugin_cad::model::structure::section::section_entities;
+use mugin_cad::model::{Entity, Geometry, SectionMark, Shape, Vector2};
+
+/// A 40x60 column at (100, 0) and (300, 0), joined by a beam, with a
+/// second beam crossing the first bay
+fn frame() -> Harness {
+    let mut h = Harness::new();
+    h.model
+        .definitions
+        .add_column_type(ColumnType::new(0, "C40x60", 40.0, 60.0, 0, 0, 0));
+    h.model
+        .definitions
+        .add_beam_type(BeamType::new(0, "B25x50", 25.0, 50.0, 0, 0));
+    h.run("place_column; 100,0; 0");
+    h.run("place_column; 300,0; 0");
+    h.run("place_beam; 100,0; 300,0;");
+    h.run("place_beam; 200,-100; 200,100;");
+    h
+}
+
+fn marks(h: &Harness) -> Vec<&SectionMark> {
+    h.model
+        .entities
+        .iter()
+        .filter_map(|entity| match &entity.shape {
+            Shape::Section(mark) => Some(mark),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Rectangles of the section as (min, max, filled)
+fn rectangles(entities: &[Entity]) -> Vec<(Vector2, Vector2, bool)> {
+    entities
+        .iter()
+        .filter_map(|entity| match &entity.shape {
+            Shape::Rectangle(rect) => Some((rect.min, rect.max, rect.filled)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Whether the section has a rectangle from `min` to `max`
+#[track_caller]
+fn assert_has(rects: &[(Vector2, Vector2, bool)], min: Vector2, max: Vector2, filled: bool) {
+    assert!(
+        rects
+            .iter()
+            .any(|r| r.0.dist(min) < 1e-3 && r.1.dist(max) < 1e-3 && r.2 == filled),
+        "no rectangle {:?}-{:?} in {:?}",
+        min,
+        max,
+        rects
+    );
+}
+
+#[test]
+fn marks_are_lettered_and_look_toward_the_picked_side() {
+    let mut h = Harness::new();
+    h.run("section; 0,0; 100,0; 50,20");
+    h.run("section; 0,0; 100,0; 50,-20");
+
+    let marks = marks(&h);
+    assert_eq!(marks[0].label, "A");
+    assert_near(marks[0].direction(), v(0.0, 1.0));
+    assert_eq!(marks[1].label, "B");
+    assert_near(marks[1].direction(), v(0.0, -1.0));
+    assert_eq!(marks[1].title(), "Section B–B");
+}
+
+#[test]
+fn cut_along_the_frame_shows_columns_and_the_beam() {
+    let h = frame();
+    let mark = SectionMark::new(v(0.0, 0.0), v(400.0, 0.0), "A");
+    let section = section_entities(&h.model, &mark, 300.0);
+
+    let rects = rectangles(&section);
+    // Two columns, the beam in the cut and the crossing beam's section
+    assert_eq!(rects.len(), 4);
+    assert_has(&rects, v(80.0, 0.0), v(120.0, 300.0), false);
+    assert_has(&rects, v(280.0, 0.0), v(320.0, 300.0), false);
+    assert_has(&rects, v(120.0, 250.0), v(280.0, 300.0), false);
+    assert_has(&rects, v(187.5, 250.0), v(212.5, 300.0), true);
+}
+
+#[test]
+fn cut_across_a_column_uses_its_depth() {
+    let h = frame();
+    let mark = SectionMark::new(v(100.0, -100.0), v(100.0, 100.0), "B");
+    let rects = rectangles(&section_entities(&h.model, &mark, 300.0));
+
+    // The beam stops at the column face, so the cut misses it
+    assert_eq!(rects.len(), 1);
+    assert_has(&rects, v(70.0, 0.0), v(130.0, 300.0), false);
+}
+
+#[test]
+fn flipped_marks_mirror_the_section() {
+    let h = frame();
+    let mut mark = SectionMark::new(v(0.0, 0.0), v(500.0, 0.0), "A");
+    mark.flipped = true;
+    let rects = rectangles(&section_entities(&h.model, &mark, 300.0));
+    // Seen from below, x = 300 is 200 from the right-hand end
+    assert_has(&rects, v(180.0, 0.0), v(220.0, 300.0), false);
+    assert_has(&rects, v(380.0, 0.0), v(420.0, 300.0), false);
+}
+
+#[test]
+fn section_marks_round_trip() {
+    let mut mark = SectionMark::new(v(0.0, 0.0), v(10.0, 0.0), "C");
+    mark.flipped = true;
+    let shape = Shape::Section(mark);
+    let json = serde_json::to_string(&shape).unwrap();
+    let read: Shape = serde_json::from_str(&json).unwrap();
+    assert_eq!(read, shape);
+    assert!(read.hit_test(v(5.0, 0.1), 0.5));
+}
@@ -0,0 +1,45 @@
+use mugin_cad::model::project::ProjectData;
+use mugin_cad::model::structure::definitions::StructureDefinitions;
+use mugin_cad::model::tools::selection_sets::SelectionSets;
+use std::collections::HashSet;
+
+fn ids(ids: &[u64]) -> HashSet<u64> {
+    ids.iter().copied().collect()
+}
+
+#[test]
+fn sets_are_saved_renamed_and_removed_by_name() {
+    let mut sets = SelectionSets::default();
+    assert!(!sets.save("walls", &ids(&[3, 1, 2])));
+    assert!(sets.save("walls", &ids(&[4])));
+    sets.save("beams", &ids(&[7, 8]));
+    let names: Vec<&str> = sets.iter().map(|(name, _)| name).collect();
+    assert_eq!(names, ["beams", "walls"]);
+
+    assert!(!sets.rename("walls", "beams"));
+    assert!(!sets.rename("walls", " "));
+    assert!(sets.rename("walls", " shear walls "));
+    assert_eq!(sets.get("shear walls").unwrap().len(), 1);
+
+    assert!(sets.remove("beams"));
+    assert!(!sets.remove("beams"));
+    assert_eq!(sets.iter().count(), 1);
+}
+
+#[test]
+fn sets_are_stored_in_the_project_file() {
+    let mut project = ProjectData::new(
+        Vec::new(),
+        Vec::new(),
+        Default::default(),
+        StructureDefinitions::new(),
+        Default::default(),
+    );
+    let json = serde_json::to_value(&project).unwrap();
+    assert!(json.get("selection_sets").is_none());
+
+    project.selection_sets.save("walls", &ids(&[5, 6]));
+    let json = serde_json::to_string(&project).unwrap();
+    let read: ProjectData = serde_json::from_str(&json).unwrap();
+    assert_eq!(read.selection_sets, project.selection_sets);
+}
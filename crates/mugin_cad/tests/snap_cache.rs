@@ -0,0 +1,107 @@
+mod common;
+
+use common::v;
+use mugin_cad::model::config::AppConfig;
+use mugin_cad::model::tools::changes::Changes;
+use mugin_cad::model::tools::snap::{SnapPointType, SnapSystem};
+use mugin_cad::model::{CadModel, Entity, Shape};
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+/// 5000 short lines on a 100 x 50 grid, 100 units apart
+fn grid_model() -> CadModel {
+    let mut model = CadModel::new();
+    for i in 0..100 {
+        for j in 0..50 {
+            let start = v(i as f32 * 100.0, j as f32 * 100.0);
+            model.add_entity(Entity::line(start, start + v(40.0, 0.0)));
+        }
+    }
+    model.update_hierarchy();
+    model
+}
+
+#[test]
+fn finds_endpoints_in_a_large_model() {
+    let model = grid_model();
+    let config = AppConfig::default();
+    let mut snap = SnapSystem::new();
+    let hit = snap
+        .find_nearest(v(5003.0, 2001.0), &model, &config, None)
+        .unwrap();
+    assert_eq!(hit.point_type, SnapPointType::Endpoint);
+    assert_eq!(hit.position, v(5000.0, 2000.0));
+    assert!(
+        snap.find_nearest(v(5070.0, 2050.0), &model, &config, None)
+            .is_none()
+    );
+}
+
+#[test]
+fn edits_refresh_only_the_touched_entity() {
+    let mut model = grid_model();
+    let config = AppConfig::default();
+    let mut snap = SnapSystem::new();
+    snap.find_nearest(v(0.0, 0.0), &model, &config, None);
+
+    let before = model.changes.revision();
+    let id = model.entities[0].id;
+    model.find_by_id_mut(id).unwrap().modify(|shape| {
+        if let Shape::Line(line) = shape {
+            line.end = v(0.0, 60.0);
+        }
+    });
+    assert_eq!(
+        model.changes.since(before),
+        Changes::Entities(HashSet::from([id]))
+    );
+
+    let hit = snap
+        .find_nearest(v(1.0, 59.0), &model, &config, None)
+        .unwrap();
+    assert_eq!(hit.position, v(0.0, 60.0));
+    assert!(
+        snap.find_nearest(v(40.0, 1.0), &model, &config, None)
+            .is_none()
+    );
+}
+
+#[test]
+fn removed_entities_stop_snapping() {
+    let mut model = grid_model();
+    let config = AppConfig::default();
+    let mut snap = SnapSystem::new();
+    assert!(
+        snap.find_nearest(v(1.0, 1.0), &model, &config, None)
+            .is_some()
+    );
+    let id = model.entities[0].id;
+    model.remove_entities_by_ids(&HashSet::from([id]));
+    assert!(
+        snap.find_nearest(v(1.0, 1.0), &model, &config, None)
+            .is_none()
+    );
+}
+
+#[test]
+fn warm_queries_are_fast() {
+    let model = grid_model();
+    let config = AppConfig::default();
+    let mut snap = SnapSystem::new();
+    snap.find_nearest(v(0.0, 0.0), &model, &config, None);
+
+    let queries = 100;
+    let start = Instant::now();
+    for k in 0..queries {
+        let pos = v((k * 97 % 10_000) as f32, (k * 31 % 5_000) as f32);
+        snap.find_nearest(pos, &model, &config, None);
+    }
+    let per_query = start.elapsed() / queries;
+    // Sub-millisecond in release; unoptimized test builds get headroom
+    let budget = if cfg!(debug_assertions) {
+        Duration::from_millis(10)
+    } else {
+        Duration::from_millis(1)
+    };
+    assert!(per_query < budget, "warm snap query took {:?}", per_query);
+}
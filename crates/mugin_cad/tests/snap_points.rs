@@ -0,0 +1,75 @@
+use mugin_cad::model::Vector2;
+use mugin_cad::model::shapes::Geometry;
+use mugin_cad::model::shapes::annotation::TextAnnotation;
+use mugin_cad::model::structure::column::{ColumnAnchor, ColumnData};
+use mugin_cad::model::tools::snap::SnapPointType;
+use std::f32::consts::FRAC_PI_2;
+
+fn of_type(points: &[(SnapPointType, Vector2)], kind: SnapPointType) -> Vec<Vector2> {
+    points
+        .iter()
+        .filter(|(t, _)| *t == kind)
+        .map(|&(_, p)| p)
+        .collect()
+}
+
+fn contains(points: &[Vector2], x: f32, y: f32) -> bool {
+    points
+        .iter()
+        .any(|p| (p.x - x).abs() < 1e-3 && (p.y - y).abs() < 1e-3)
+}
+
+#[test]
+fn rotated_column_snaps_to_center_corners_and_side_midpoints() {
+    let mut column = ColumnData::new(
+        Vector2::new(10.0, 20.0),
+        40.0,
+        20.0,
+        1,
+        "C1".to_string(),
+        ColumnAnchor::Center,
+    );
+    column.rotation = FRAC_PI_2;
+    let points = column.snap_points();
+
+    assert_eq!(of_type(&points, SnapPointType::Center).len(), 1);
+    assert!(contains(
+        &of_type(&points, SnapPointType::Center),
+        10.0,
+        20.0
+    ));
+
+    // 40 wide and 20 high, turned a quarter: 20 wide and 40 high
+    let corners = of_type(&points, SnapPointType::Corner);
+    assert_eq!(corners.len(), 4);
+    for (x, y) in [(0.0, 0.0), (20.0, 0.0), (20.0, 40.0), (0.0, 40.0)] {
+        assert!(
+            contains(&corners, x, y),
+            "corner {},{}: {:?}",
+            x,
+            y,
+            corners
+        );
+    }
+
+    let midpoints = of_type(&points, SnapPointType::Midpoint);
+    assert_eq!(midpoints.len(), 4);
+    for (x, y) in [(10.0, 0.0), (20.0, 20.0), (10.0, 40.0), (0.0, 20.0)] {
+        assert!(
+            contains(&midpoints, x, y),
+            "midpoint {},{}: {:?}",
+            x,
+            y,
+            midpoints
+        );
+    }
+}
+
+#[test]
+fn text_snaps_to_its_insertion_point() {
+    let text = TextAnnotation::new_custom(Vector2::new(5.0, -3.0), "Note".to_string());
+    let points = text.snap_points();
+    assert_eq!(points.len(), 1);
+    assert_eq!(points[0].0, SnapPointType::Insertion);
+    assert!(contains(&[points[0].1], 5.0, -3.0));
+}
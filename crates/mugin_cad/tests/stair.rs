@@ -0,0 +1,73 @@
+mod common;
+
+use common::{Harness, assert_near, v};
+use mugin_cad::export::data::{COLUMNS, DataExportOptions, csv_fields, data_rows, json_object};
+use mugin_cad::model::structure::stair::MAX_RISER_HEIGHT;
+use mugin_cad::model::{Geometry, Shape, StairData};
+
+fn stair(h: &Harness, id: u64) -> &StairData {
+    match &h.entity(id).shape {
+        Shape::Stair(stair) => stair,
+        other => panic!("expected a stair, got {:?}", other),
+    }
+}
+
+#[test]
+fn risers_follow_the_storey_height() {
+    let mut h = Harness::new();
+    h.run("stair; 0,0; 0,450");
+    let stair = stair(&h, h.ids()[0]);
+
+    // 300 / 17.5 rounds up to 18 risers of 16.67, over 17 treads
+    assert_eq!(stair.risers, 18);
+    assert!(stair.riser_height <= MAX_RISER_HEIGHT);
+    assert!((stair.total_rise() - 300.0).abs() < 1e-3);
+    assert!((stair.total_run() - 450.0).abs() < 1e-3);
+    assert_near(stair.direction, v(0.0, 1.0));
+    assert_eq!(stair.width, 120.0);
+}
+
+#[test]
+fn typed_width_sets_the_flight_width() {
+    let mut h = Harness::new();
+    h.run("stair; 90; 0,0; 300,0");
+    let stair = stair(&h, h.ids()[0]);
+    assert_eq!(stair.width, 90.0);
+    assert_near(stair.corners()[0], v(0.0, -45.0));
+    assert_near(stair.corners()[2], v(300.0, 45.0));
+}
+
+#[test]
+fn hit_test_follows_the_outline() {
+    let stair = StairData::new(v(0.0, 0.0), v(1.0, 0.0), 100.0, 11, 30.0, 17.0);
+    assert!(stair.hit_test(v(150.0, 50.0), 1.0));
+    assert!(stair.hit_test(v(300.0, 0.0), 1.0));
+    assert!(!stair.hit_test(v(150.0, 0.0), 1.0));
+    assert!(!stair.hit_test(v(350.0, 0.0), 1.0));
+}
+
+#[test]
+fn export_reports_the_riser_count() {
+    let mut h = Harness::new();
+    h.run("stair; 0,0; 0,450");
+    let rows = data_rows(&h.model, &Default::default(), &DataExportOptions::default());
+    let risers = COLUMNS.iter().position(|c| *c == "risers").unwrap();
+    assert_eq!(csv_fields(&rows[0])[2], "Stair");
+    assert_eq!(csv_fields(&rows[0])[risers], "18");
+    assert_eq!(json_object(&rows[0])["risers"], 18);
+}
+
+#[test]
+fn stairs_round_trip() {
+    let shape = Shape::Stair(StairData::new(
+        v(10.0, 0.0),
+        v(0.0, 2.0),
+        120.0,
+        16,
+        28.0,
+        18.0,
+    ));
+    let json = serde_json::to_string(&shape).unwrap();
+    let read: Shape = serde_json::from_str(&json).unwrap();
+    assert_eq!(read, shape);
+}
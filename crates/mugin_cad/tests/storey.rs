@@ -0,0 +1,155 @@
+mod common;
+
+use common::Harness;
+use mugin_cad::model::structure::beam_type::BeamType;
+use mugin_cad::model::structure::column::{ColumnAnchor, ColumnData};
+use mugin_cad::model::structure::column_type::ColumnType;
+use mugin_cad::model::structure::storey::StoreyManager;
+use mugin_cad::model::{Shape, Vector2};
+
+/// A ground floor and a first floor, with the ground floor active
+fn harness() -> (Harness, u64, u64) {
+    let mut h = Harness::new();
+    h.model
+        .definitions
+        .add_column_type(ColumnType::new(0, "C40x60", 40.0, 60.0, 0, 0, 0));
+    h.model
+        .definitions
+        .add_beam_type(BeamType::new(0, "B25x50", 25.0, 50.0, 0, 0));
+    let ground = h.model.storeys.add("Ground", 300.0);
+    let first = h.model.storeys.add("First", 280.0);
+    h.model.storeys.active = Some(ground);
+    (h, ground, first)
+}
+
+fn storey_of(h: &Harness, id: u64) -> u64 {
+    h.entity(id).shape.storey_id().unwrap()
+}
+
+#[test]
+fn storeys_stack_by_elevation() {
+    let mut storeys = StoreyManager::new();
+    let ground = storeys.add("Ground", 300.0);
+    let first = storeys.add("First", 280.0);
+    storeys.add("Second", 280.0);
+    assert_eq!(storeys.get(first).unwrap().elevation, 300.0);
+    assert_eq!(storeys.storeys[2].elevation, 580.0);
+
+    storeys.storeys[0].elevation = 1000.0;
+    storeys.sort();
+    assert_eq!(storeys.storeys[2].id, ground);
+    assert_eq!(storeys.name_of(0), "—");
+}
+
+#[test]
+fn members_are_placed_on_the_active_storey() {
+    let (mut h, ground, first) = harness();
+    h.run("place_column; 0,0; 0");
+    h.model.storeys.active = Some(first);
+    h.run("place_beam; 0,0; 300,0;");
+
+    let ids = h.ids();
+    assert_eq!(storey_of(&h, ids[0]), ground);
+    assert_eq!(storey_of(&h, ids[1]), first);
+}
+
+#[test]
+fn members_of_other_storeys_cannot_be_picked() {
+    let (mut h, ground, first) = harness();
+    h.run("place_column; 0,0; 0");
+    let column = h.ids()[0];
+    assert_eq!(
+        h.model.pick_entity_id(Vector2::new(0.0, 0.0), 1.0),
+        Some(column)
+    );
+
+    h.model.storeys.active = Some(first);
+    assert_eq!(h.model.pick_entity_id(Vector2::new(0.0, 0.0), 1.0), None);
+
+    // With every storey shown, everything is editable again
+    h.model.storeys.active = None;
+    assert_eq!(
+        h.model.pick_entity_id(Vector2::new(0.0, 0.0), 1.0),
+        Some(column)
+    );
+    assert!(h.model.storeys.is_editable(ground));
+}
+
+#[test]
+fn copying_a_storey_duplicates_members_with_fresh_ids() {
+    let (mut h, ground, first) = harness();
+    h.run("place_column; 0,0; 0");
+    h.run("place_column; 400,0; 0");
+    h.run("place_beam; 0,0; 400,0;");
+    let originals = h.ids();
+
+    assert_eq!(h.model.copy_storey(ground, first), 3);
+    h.model.update_hierarchy();
+    h.model.resolve_attachments();
+
+    let copies: Vec<u64> = h
+        .ids()
+        .into_iter()
+        .filter(|id| !originals.contains(id))
+        .collect();
+    assert_eq!(copies.len(), 3);
+    for &id in &copies {
+        assert_eq!(storey_of(&h, id), first);
+    }
+    // The copied beam hangs on the copied columns
+    let beam = copies
+        .iter()
+        .find_map(|&id| match &h.entity(id).shape {
+            Shape::Beam(beam) => Some(beam.clone()),
+            _ => None,
+        })
+        .unwrap();
+    let start = beam.start_attachment.unwrap().column_id;
+    let end = beam.end_attachment.unwrap().column_id;
+    assert!(copies.contains(&start));
+    assert!(copies.contains(&end));
+}
+
+#[test]
+fn removing_a_storey_unassigns_its_members() {
+    let (mut h, ground, _) = harness();
+    h.run("place_column; 0,0; 0");
+    let column = h.ids()[0];
+
+    h.model.remove_storey(ground);
+    assert_eq!(storey_of(&h, column), 0);
+    assert_eq!(h.model.storeys.active, None);
+    assert_eq!(h.model.storeys.storeys.len(), 1);
+}
+
+#[test]
+fn undo_restores_the_storey_of_a_member() {
+    let (mut h, ground, first) = harness();
+    h.run("place_column; 0,0; 0");
+    h.model.storeys.active = Some(first);
+    h.run("place_column; 400,0; 0");
+    h.undo();
+
+    assert_eq!(h.ids().len(), 1);
+    assert_eq!(storey_of(&h, h.ids()[0]), ground);
+}
+
+#[test]
+fn files_without_storeys_still_load() {
+    let col = ColumnData::new(
+        Vector2::new(0.0, 0.0),
+        40.0,
+        60.0,
+        1,
+        "C1".to_string(),
+        ColumnAnchor::Center,
+    );
+    let mut json = serde_json::to_value(&col).unwrap();
+    json.as_object_mut().unwrap().remove("storey_id");
+    let read: ColumnData = serde_json::from_value(json).unwrap();
+    assert_eq!(read.storey_id, 0);
+
+    let storeys: StoreyManager = serde_json::from_str("{}").unwrap();
+    assert!(storeys.storeys.is_empty());
+    assert_eq!(storeys.active, None);
+}
@@ -0,0 +1,114 @@
+use mugin_cad::model::structure::beam_type::BeamType;
+use mugin_cad::model::structure::column_type::ColumnType;
+use mugin_cad::model::structure::definitions::StructureDefinitions;
+use mugin_cad::model::structure::library::{
+    DefinitionConflict, DefinitionKind, MergeSummary, read_library, write_library,
+};
+use mugin_cad::model::structure::material::Material;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+/// A library whose IDs overlap the project's
+fn library() -> StructureDefinitions {
+    let mut defs = StructureDefinitions::new();
+    let c30 = defs.add_material(Material::new_concrete(0, "C30", "C30"));
+    let s420 = defs.add_material(Material::new_steel(0, "S420", "S420", None));
+    defs.add_column_type(ColumnType::new(0, "C40x40", 40.0, 40.0, c30, s420, s420));
+    defs.add_column_type(ColumnType::new(0, "C30x60", 30.0, 60.0, c30, s420, s420));
+    defs.add_beam_type(BeamType::new(0, "B25x50", 25.0, 50.0, c30, s420));
+    defs
+}
+
+fn project() -> StructureDefinitions {
+    let mut defs = StructureDefinitions::new();
+    let s420 = defs.add_material(Material::new_steel(0, "S420", "S420", None));
+    let c25 = defs.add_material(Material::new_concrete(0, "C25", "C25"));
+    defs.add_column_type(ColumnType::new(0, "C40x40", 40.0, 40.0, c25, s420, s420));
+    defs
+}
+
+fn column_id(defs: &StructureDefinitions, name: &str) -> u64 {
+    defs.column_types
+        .values()
+        .find(|c| c.name == name)
+        .unwrap()
+        .id
+}
+
+fn material_id(defs: &StructureDefinitions, name: &str) -> u64 {
+    defs.materials.values().find(|m| m.name == name).unwrap().id
+}
+
+#[test]
+fn import_keeps_existing_ids_and_remaps_materials() {
+    let mut defs = project();
+    // A placed column refers to this ID
+    let placed = column_id(&defs, "C40x40");
+    let s420 = material_id(&defs, "S420");
+
+    let summary = defs.merge_by_name(&library(), &HashSet::new());
+    assert_eq!(
+        summary,
+        MergeSummary {
+            added: 3,
+            kept: 2,
+            replaced: 0
+        }
+    );
+
+    assert_eq!(column_id(&defs, "C40x40"), placed);
+    let kept = defs.get_column_type(placed).unwrap();
+    assert_eq!(kept.concrete_material_id, material_id(&defs, "C25"));
+
+    let added = defs.get_column_type(column_id(&defs, "C30x60")).unwrap();
+    assert_ne!(added.id, placed);
+    assert_eq!(added.concrete_material_id, material_id(&defs, "C30"));
+    assert_eq!(added.long_rebar_material_id, s420);
+    let beam = defs.beam_types.values().next().unwrap();
+    assert_eq!(beam.steel_material_id, s420);
+
+    let mut ids: Vec<u64> = defs.materials.keys().copied().collect();
+    ids.extend(defs.column_types.keys());
+    ids.extend(defs.beam_types.keys());
+    assert_eq!(ids.iter().collect::<HashSet<_>>().len(), ids.len());
+}
+
+#[test]
+fn conflicts_are_names_with_different_contents() {
+    let defs = project();
+    assert_eq!(
+        defs.name_conflicts(&library()),
+        [DefinitionConflict {
+            kind: DefinitionKind::ColumnType,
+            name: "C40x40".to_string(),
+        }]
+    );
+    // A project compared with its own copy has nothing to settle
+    assert!(defs.name_conflicts(&defs.clone()).is_empty());
+}
+
+#[test]
+fn replacing_a_conflict_keeps_its_id() {
+    let mut defs = project();
+    let placed = column_id(&defs, "C40x40");
+    let replace: HashSet<_> = defs.name_conflicts(&library()).into_iter().collect();
+
+    let summary = defs.merge_by_name(&library(), &replace);
+    assert_eq!(summary.replaced, 1);
+    let column = defs.get_column_type(placed).unwrap();
+    assert_eq!(column.id, placed);
+    assert_eq!(column.concrete_material_id, material_id(&defs, "C30"));
+}
+
+#[test]
+fn library_file_round_trips() {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("structure_library");
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("library.json");
+    assert!(read_library(&path).unwrap().materials.is_empty());
+
+    write_library(&path, &library()).unwrap();
+    let read = read_library(&path).unwrap();
+    assert!(read.name_conflicts(&library()).is_empty());
+    assert_eq!(read.column_types.len(), 2);
+}
@@ -0,0 +1,107 @@
+mod common;
+
+use common::{Harness, assert_near};
+use mugin_cad::model::config::AppConfig;
+use mugin_cad::model::drafting::DraftingDefaults;
+use mugin_cad::model::project::ProjectData;
+use mugin_cad::model::structure::definitions::StructureDefinitions;
+use mugin_cad::model::system::symbols::{SymbolLibrary, symbols_from_project};
+use mugin_cad::model::{Entity, Shape, Vector2};
+use std::f32::consts::FRAC_PI_2;
+
+fn project(entities: Vec<Entity>) -> ProjectData {
+    ProjectData::new(
+        entities,
+        Vec::new(),
+        AppConfig::default(),
+        StructureDefinitions::new(),
+        DraftingDefaults::default(),
+    )
+}
+
+#[test]
+fn placed_symbols_are_turned_groups_picked_whole() {
+    let mut h = Harness::new();
+    h.executor.active_symbol = Some("North Arrow".to_string());
+    h.run("symbol; 100,0; 90");
+
+    let id = h.ids()[0];
+    let arrow = h.entity(id);
+    assert_eq!(arrow.name, "North Arrow");
+    assert_eq!(arrow.symbol.as_deref(), Some("North Arrow"));
+    assert!(!arrow.children.is_empty());
+
+    // The arrow's tip and the N above it now stick out on the left only
+    let (min, max) = arrow.bounding_box();
+    assert!(min.x <= 40.0, "{:?}", min);
+    assert!((max.x - 150.0).abs() < 1e-3, "{:?}", max);
+    assert_near(
+        arrow
+            .world_transform
+            .transform_point2(glam::Vec2::ZERO)
+            .into(),
+        Vector2::new(100.0, 0.0),
+    );
+
+    // Clicking any part selects the symbol, not the part
+    assert_eq!(
+        h.model.pick_entity_id(Vector2::new(150.0, 0.0), 1.0),
+        Some(id)
+    );
+}
+
+#[test]
+fn symbols_are_picked_by_name_and_rotate_toward_a_point() {
+    let mut h = Harness::new();
+    h.run("sym; grid bubble; 0,0; 0,10; 200,0; 200,10;");
+    let ids = h.ids();
+    assert_eq!(ids.len(), 2);
+    let second = h.entity(ids[1]);
+    assert_eq!(second.symbol.as_deref(), Some("Grid Bubble"));
+    let turned: Vector2 = second
+        .world_transform
+        .transform_vector2(glam::Vec2::X)
+        .into();
+    assert_near(turned, Vector2::new(FRAC_PI_2.cos(), FRAC_PI_2.sin()));
+
+    h.run("sym; compass rose");
+    assert!(h.executor.status_message.contains("No symbol named"));
+}
+
+#[test]
+fn drawing_groups_import_as_symbols_around_their_center() {
+    let mut group = Entity::empty("Door Tag");
+    group.children = vec![
+        Entity::rectangle(
+            Vector2::new(100.0, 100.0),
+            Vector2::new(140.0, 120.0),
+            false,
+        ),
+        Entity::line(Vector2::new(100.0, 110.0), Vector2::new(140.0, 110.0)),
+    ];
+    let symbols = symbols_from_project(&project(vec![group]), "tags");
+    assert_eq!(symbols.len(), 1);
+    assert_eq!(symbols[0].name, "Door Tag");
+    let (min, max) = symbols[0].bounds();
+    assert_near(min, Vector2::new(-20.0, -10.0));
+    assert_near(max, Vector2::new(20.0, 10.0));
+
+    // Without groups the whole drawing is one symbol named after the file
+    let loose = vec![Entity::circle(Vector2::new(10.0, 10.0), 5.0, false)];
+    let symbols = symbols_from_project(&project(loose), "Manhole");
+    assert_eq!(symbols[0].name, "Manhole");
+    assert!(matches!(symbols[0].entities[0].shape, Shape::Circle(_)));
+
+    // Imports replace built-ins of the same name
+    let mut library = SymbolLibrary::default();
+    let count = library.symbols().count();
+    let mut arrow = symbols[0].clone();
+    arrow.name = "North Arrow".to_string();
+    library.import(vec![arrow]);
+    assert_eq!(library.symbols().count(), count);
+    assert!(matches!(
+        library.get("north arrow").unwrap().entities[0].shape,
+        Shape::Circle(_)
+    ));
+    assert_eq!(library.get("north arrow").unwrap().entities.len(), 1);
+}
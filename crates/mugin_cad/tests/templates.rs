@@ -0,0 +1,93 @@
+mod common;
+
+use common::scratch_dir;
+use mugin_cad::model::config::AppConfig;
+use mugin_cad::model::drafting::DraftingDefaults;
+use mugin_cad::model::template::{
+    DrawingTemplate, TEMPLATE_EXTENSION, TemplateSource, available_templates,
+};
+use mugin_cad::model::{CadModel, Entity, Vector2};
+
+fn drawing() -> CadModel {
+    let mut model = CadModel::new();
+    let walls = model
+        .layer_manager
+        .add_layer("Walls".to_string(), ecolor::Color32::WHITE);
+    let mut group = Entity::empty("Core");
+    let mut wall = Entity::line(Vector2::new(0.0, 0.0), Vector2::new(100.0, 0.0));
+    wall.layer_id = walls;
+    group.children.push(wall);
+    model.add_entity(group);
+    model.axis_manager.add_vertical(0.0);
+    model
+}
+
+#[test]
+fn builtin_templates_load() {
+    let templates = available_templates(None);
+    assert!(!templates.is_empty());
+    for entry in &templates {
+        assert!(matches!(entry.source, TemplateSource::Builtin(_)));
+        let template = entry.load().unwrap();
+        assert_eq!(template.name, entry.name);
+        let mut model = CadModel::new();
+        template.apply(&mut model);
+        assert!(model.layer_manager.layers.len() > 1);
+    }
+}
+
+#[test]
+fn saved_template_round_trips_with_fresh_ids() {
+    let source = drawing();
+    let dir = scratch_dir("template_round_trip");
+    let path = dir.join(format!("Office.{}", TEMPLATE_EXTENSION));
+    DrawingTemplate::from_model(
+        "Office",
+        &source,
+        &AppConfig::default(),
+        &DraftingDefaults::default(),
+    )
+    .write(&path)
+    .unwrap();
+
+    let template = DrawingTemplate::read(&path).unwrap();
+    let (mut first, mut second) = (CadModel::new(), CadModel::new());
+    template.apply(&mut first);
+    template.apply(&mut second);
+
+    for model in [&first, &second] {
+        assert_eq!(model.axis_manager.axes.len(), 1);
+        let group = &model.entities[0];
+        assert_eq!(group.name, "Core");
+        let wall = &group.children[0];
+        let layer = model.layer_manager.get_layer(wall.layer_id).unwrap();
+        assert_eq!(layer.name, "Walls");
+    }
+    let original = &source.entities[0];
+    assert_ne!(first.entities[0].id, original.id);
+    assert_ne!(first.entities[0].children[0].id, original.children[0].id);
+    assert_ne!(first.entities[0].id, second.entities[0].id);
+}
+
+#[test]
+fn saved_templates_are_listed_after_builtins() {
+    let dir = scratch_dir("template_listing");
+    let config = AppConfig::default();
+    let drafting = DraftingDefaults::default();
+    for name in ["zeta", "Alpha"] {
+        let path = dir.join(format!("{}.{}", name, TEMPLATE_EXTENSION));
+        DrawingTemplate::from_model(name, &CadModel::new(), &config, &drafting)
+            .write(&path)
+            .unwrap();
+    }
+    std::fs::write(dir.join(format!("broken.{}", TEMPLATE_EXTENSION)), "{").unwrap();
+    std::fs::write(dir.join("notes.txt"), "not a template").unwrap();
+
+    let builtin = available_templates(None).len();
+    let names: Vec<String> = available_templates(Some(&dir))
+        .into_iter()
+        .skip(builtin)
+        .map(|entry| entry.name)
+        .collect();
+    assert_eq!(names, ["Alpha", "zeta"]);
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use common::{Harness, assert_near, v};
+use mugin_cad::model::tools::snap::{SnapPointType, SnapSystem};
+
+#[test]
+fn tracking_points_line_up_x_of_one_with_y_of_another() {
+    let snap = SnapSystem::new();
+    let tracking = [v(0.0, 0.0), v(100.0, 50.0)];
+
+    // The x of the first with the y of the second
+    let hit = snap
+        .find_nearest_tracking(v(3.0, 48.0), &tracking, 10.0)
+        .unwrap();
+    assert_eq!(hit.point_type, SnapPointType::Tracking);
+    assert_near(hit.position, v(0.0, 50.0));
+
+    // A tracking point itself
+    let hit = snap
+        .find_nearest_tracking(v(98.0, 52.0), &tracking, 10.0)
+        .unwrap();
+    assert_near(hit.position, v(100.0, 50.0));
+
+    // Far from both, the cursor slides along a line through one
+    let hit = snap
+        .find_nearest_tracking(v(40.0, 4.0), &tracking, 10.0)
+        .unwrap();
+    assert_near(hit.position, v(40.0, 0.0));
+
+    assert!(
+        snap.find_nearest_tracking(v(40.0, 25.0), &tracking, 10.0)
+            .is_none()
+    );
+}
+
+#[test]
+fn tracking_points_last_for_one_command() {
+    let mut h = Harness::new();
+    // Nothing to track without a running command
+    assert!(!h.executor.toggle_tracking_point(v(0.0, 0.0)));
+
+    h.run("line; 0,0");
+    assert!(h.executor.toggle_tracking_point(v(10.0, 10.0)));
+    assert!(h.executor.toggle_tracking_point(v(20.0, 20.0)));
+    // Dropping one on itself picks it up again
+    h.executor.toggle_tracking_point(v(10.0, 10.0));
+    assert_eq!(h.executor.tracking_points, vec![v(20.0, 20.0)]);
+
+    h.run("10,0;");
+    assert!(h.executor.tracking_points.is_empty());
+    assert_eq!(h.ids().len(), 1);
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use common::Harness;
+use mugin_cad::model::tools::saved::{SavedRevisions, UnsavedCount};
+
+fn saved_room() -> (Harness, Vec<u64>, SavedRevisions) {
+    let mut h = Harness::new();
+    h.run("line; 0,0; 200,0;");
+    h.run("line; 200,0; 200,100;");
+    h.run("circle; 50,50; 20");
+    let ids = h.ids();
+    let saved = SavedRevisions::capture(&h.model.entities);
+    (h, ids, saved)
+}
+
+#[test]
+fn a_fresh_snapshot_has_no_changes() {
+    let (h, _, saved) = saved_room();
+    assert!(saved.unsaved(&h.model.entities).is_empty());
+}
+
+#[test]
+fn moved_and_created_entities_are_changed() {
+    let (mut h, ids, saved) = saved_room();
+    h.select([ids[1]]);
+    h.run("move; 0,0; 10,0");
+    h.run("line; 0,100; 0,0;");
+
+    assert!(!saved.is_changed(h.entity(ids[0])));
+    assert!(saved.is_changed(h.entity(ids[1])));
+    let created = *h.ids().last().unwrap();
+    assert!(saved.is_changed(h.entity(created)));
+    assert_eq!(
+        saved.unsaved(&h.model.entities),
+        UnsavedCount {
+            changed: 2,
+            deleted: 0
+        }
+    );
+}
+
+#[test]
+fn deletions_are_counted_and_undo_restores_the_saved_state() {
+    let (mut h, ids, saved) = saved_room();
+    h.select([ids[2]]);
+    h.run("move; 0,0; 5,5");
+    assert_eq!(saved.unsaved(&h.model.entities).changed, 1);
+    h.undo();
+    assert!(saved.unsaved(&h.model.entities).is_empty());
+
+    h.model.entities.retain(|e| e.id != ids[0]);
+    assert_eq!(
+        saved.unsaved(&h.model.entities),
+        UnsavedCount {
+            changed: 0,
+            deleted: 1
+        }
+    );
+}
+
+#[test]
+fn a_new_snapshot_clears_the_changes() {
+    let (mut h, ids, _) = saved_room();
+    h.select([ids[0]]);
+    h.run("move; 0,0; 0,10");
+    let saved = SavedRevisions::capture(&h.model.entities);
+    assert!(saved.unsaved(&h.model.entities).is_empty());
+}
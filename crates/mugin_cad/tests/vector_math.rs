@@ -0,0 +1,92 @@
+mod common;
+
+use common::{assert_near, v};
+use mugin_cad::model::math::segment;
+use std::f32::consts::{FRAC_PI_2, PI};
+
+#[test]
+fn cross_and_perp_turn_counterclockwise() {
+    assert_eq!(v(1.0, 0.0).cross(v(0.0, 1.0)), 1.0);
+    assert_eq!(v(0.0, 1.0).cross(v(1.0, 0.0)), -1.0);
+    assert_eq!(v(2.0, 4.0).cross(v(1.0, 2.0)), 0.0);
+    assert_eq!(v(3.0, 1.0).perp(), v(-1.0, 3.0));
+    assert_eq!(v(3.0, 1.0).dot(v(3.0, 1.0).perp()), 0.0);
+}
+
+#[test]
+fn lerp_rotate_and_angle() {
+    assert_eq!(v(0.0, 0.0).lerp(v(10.0, 20.0), 0.25), v(2.5, 5.0));
+    assert_near(v(1.0, 0.0).rotated(FRAC_PI_2), v(0.0, 1.0));
+    assert_near(v(12.0, 10.0).rotate_around(v(10.0, 10.0), PI), v(8.0, 10.0));
+
+    let a = v(1.0, 0.0);
+    assert!((a.angle_to(v(0.0, 2.0)) - FRAC_PI_2).abs() < 1e-6);
+    assert!((a.angle_to(v(0.0, -2.0)) + FRAC_PI_2).abs() < 1e-6);
+    assert_eq!(a.angle_to(a), 0.0);
+}
+
+#[test]
+fn projection_onto_a_zero_vector_is_zero() {
+    assert_eq!(v(3.0, 4.0).project_onto(v(2.0, 0.0)), v(3.0, 0.0));
+    assert_eq!(v(3.0, 4.0).project_onto(v(0.0, 0.0)), v(0.0, 0.0));
+}
+
+#[test]
+fn points_against_a_segment() {
+    let (a, b) = (v(0.0, 0.0), v(10.0, 0.0));
+    assert_eq!(segment::project(v(15.0, 3.0), a, b), 1.5);
+    assert_eq!(segment::closest_param(v(15.0, 3.0), a, b), 1.0);
+    assert_eq!(segment::closest_point(v(4.0, 3.0), a, b), v(4.0, 0.0));
+    assert_eq!(segment::distance(v(13.0, 4.0), a, b), 5.0);
+
+    // A segment of no length is its start point
+    assert_eq!(segment::project(v(5.0, 5.0), a, a), 0.0);
+    assert_eq!(segment::distance(v(3.0, 4.0), a, a), 5.0);
+}
+
+#[test]
+fn segments_cross_only_within_their_ends() {
+    let (a1, a2) = (v(0.0, 0.0), v(10.0, 0.0));
+    assert_eq!(
+        segment::intersection(a1, a2, v(5.0, -5.0), v(5.0, 5.0)),
+        Some(v(5.0, 0.0))
+    );
+    assert_eq!(
+        segment::intersection(a1, a2, v(15.0, -5.0), v(15.0, 5.0)),
+        None
+    );
+    assert_eq!(
+        segment::line_intersection(a1, a2, v(15.0, -5.0), v(15.0, 5.0)),
+        Some(v(15.0, 0.0))
+    );
+    // Parallel, even when overlapping
+    assert_eq!(
+        segment::intersection(a1, a2, v(2.0, 0.0), v(8.0, 0.0)),
+        None
+    );
+    assert_eq!(
+        segment::line_intersection(a1, a2, v(0.0, 1.0), v(10.0, 1.0)),
+        None
+    );
+}
+
+#[test]
+fn segments_against_circles() {
+    let center = v(0.0, 0.0);
+    assert_eq!(
+        segment::circle_intersections(v(-10.0, 0.0), v(10.0, 0.0), center, 5.0),
+        vec![v(-5.0, 0.0), v(5.0, 0.0)]
+    );
+    // Starting inside, only the way out counts
+    assert_eq!(
+        segment::circle_intersections(v(0.0, 0.0), v(10.0, 0.0), center, 5.0),
+        vec![v(5.0, 0.0)]
+    );
+    // Touching gives a single point
+    assert_eq!(
+        segment::circle_intersections(v(-10.0, 5.0), v(10.0, 5.0), center, 5.0),
+        vec![v(0.0, 5.0)]
+    );
+    assert!(segment::circle_intersections(v(-10.0, 6.0), v(10.0, 6.0), center, 5.0).is_empty());
+    assert!(segment::circle_intersections(v(5.0, 0.0), v(5.0, 0.0), center, 5.0).is_empty());
+}
@@ -0,0 +1,999 @@
+//! Flows that go through `CadViewModel`, such as tabs and staged Escape.
+
+#![cfg(feature = "gui")]
+
+mod common;
+
+use common::v;
+use mugin_cad::commands::InputModifiers;
+use mugin_cad::model::project::{ProjectData, ViewState};
+use mugin_cad::model::structure::column::{ColumnAnchor, ColumnData};
+use mugin_cad::model::structure::definitions::StructureDefinitions;
+use mugin_cad::model::{Entity, FlagColor, Shape, Vector2};
+use mugin_cad::viewmodel::tab::ProjectTab;
+use mugin_cad::viewmodel::{CadViewModel, EscapeStage, LeftPanelTab, MarkupTool};
+use std::collections::HashSet;
+
+fn type_in(vm: &mut CadViewModel, script: &str) {
+    for input in script.split(';') {
+        vm.command_input = input.trim().to_string();
+        vm.process_command();
+    }
+}
+
+fn ctrl() -> InputModifiers {
+    InputModifiers {
+        ctrl: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn selection_drops_removed_entities() {
+    let mut vm = CadViewModel::new();
+    type_in(&mut vm, "line; 0,0; 10,0; 10,10");
+    let last = vm.active_tab().model.entities.last().unwrap().id;
+    vm.active_tab_mut()
+        .selection_manager
+        .selected_ids
+        .insert(last);
+    // LINE's "u" option removes the segment just drawn
+    type_in(&mut vm, "u");
+    assert!(vm.active_tab().model.find_by_id(last).is_none());
+    assert!(vm.active_tab().selection_manager.selected_ids.is_empty());
+}
+
+/// Three labelled lines A, B, C in drawing order
+fn three_lines(vm: &mut CadViewModel) -> [u64; 3] {
+    let tab = vm.active_tab_mut();
+    [0.0, 20.0, 40.0].map(|y| {
+        let mut entity = Entity::line(v(0.0, y), v(10.0, y));
+        if let Shape::Line(line) = &mut entity.shape {
+            line.show_length = true;
+        }
+        let id = entity.id;
+        tab.model.add_entity(entity);
+        id
+    })
+}
+
+/// Where to grab the length label of line `id`
+fn label_grab(vm: &CadViewModel, id: u64) -> Vector2 {
+    let tab = vm.active_tab();
+    match &tab.model.find_by_id(id).unwrap().shape {
+        Shape::Line(line) => line.label_position(5.0 / tab.viewport.zoom),
+        _ => unreachable!(),
+    }
+}
+
+fn label_offset(vm: &CadViewModel, id: u64) -> Vector2 {
+    match &vm.active_tab().model.find_by_id(id).unwrap().shape {
+        Shape::Line(line) => line.label_offset,
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn selection_follows_the_entity_when_an_earlier_one_is_deleted() {
+    let mut vm = CadViewModel::new();
+    let [a, b, c] = three_lines(&mut vm);
+    vm.active_tab_mut().selection_manager.selected_ids.insert(b);
+
+    // A sits before B in the entity list
+    vm.active_tab_mut()
+        .model
+        .remove_entities_by_ids(&HashSet::from([a]));
+    assert_eq!(
+        vm.active_tab().selection_manager.selected_ids,
+        HashSet::from([b])
+    );
+
+    // B is still what a delete removes
+    type_in(&mut vm, "delete; y");
+    let ids: Vec<u64> = vm
+        .active_tab()
+        .model
+        .entities
+        .iter()
+        .map(|e| e.id)
+        .collect();
+    assert_eq!(ids, vec![c]);
+    assert!(vm.active_tab().selection_manager.selected_ids.is_empty());
+}
+
+#[test]
+fn label_drag_follows_the_entity_when_an_earlier_one_is_deleted() {
+    let mut vm = CadViewModel::new();
+    let [a, b, c] = three_lines(&mut vm);
+    let grab = label_grab(&vm, b);
+
+    vm.handle_drag_start(grab, InputModifiers::default());
+    assert_eq!(vm.active_tab().dragging_label_id, Some(b));
+    vm.active_tab_mut()
+        .model
+        .remove_entities_by_ids(&HashSet::from([a]));
+    vm.handle_drag_update(grab + v(0.0, 3.0), InputModifiers::default());
+
+    assert_eq!(label_offset(&vm, b), v(0.0, 3.0));
+    assert_eq!(label_offset(&vm, c), Vector2::default());
+}
+
+#[test]
+fn escape_unwinds_one_stage_at_a_time() {
+    let mut vm = CadViewModel::new();
+    type_in(&mut vm, "line; 0,0; 10,0;");
+    let id = vm.active_tab().model.entities[0].id;
+    vm.active_tab_mut()
+        .selection_manager
+        .selected_ids
+        .insert(id);
+
+    type_in(&mut vm, "line");
+    vm.handle_click(v(20.0, 0.0), InputModifiers::default());
+    vm.command_input = "30".to_string();
+
+    assert_eq!(vm.escape(), EscapeStage::ClearInput);
+    assert_eq!(vm.escape(), EscapeStage::CancelStep);
+    assert!(vm.active_tab().executor.is_active());
+    assert_eq!(vm.escape(), EscapeStage::CancelCommand);
+    assert_eq!(vm.escape(), EscapeStage::ClearSelection);
+    assert_eq!(vm.escape(), EscapeStage::Idle);
+}
+
+#[test]
+fn escape_dismisses_the_delete_prompt_first() {
+    let mut vm = CadViewModel::new();
+    type_in(&mut vm, "line; 0,0; 10,0;");
+    let id = vm.active_tab().model.entities[0].id;
+    vm.active_tab_mut()
+        .selection_manager
+        .selected_ids
+        .insert(id);
+    type_in(&mut vm, "delete");
+    assert_eq!(vm.escape(), EscapeStage::CancelPrompt);
+    assert_eq!(vm.active_tab().model.entities.len(), 1);
+}
+
+#[test]
+fn escape_puts_a_dragged_label_back() {
+    let mut vm = CadViewModel::new();
+    let [_, b, _] = three_lines(&mut vm);
+    let grab = label_grab(&vm, b);
+    vm.handle_drag_start(grab, InputModifiers::default());
+    vm.handle_drag_update(grab + v(4.0, 4.0), InputModifiers::default());
+    assert_eq!(label_offset(&vm, b), v(4.0, 4.0));
+
+    assert_eq!(vm.escape(), EscapeStage::AbortDrag);
+    assert_eq!(label_offset(&vm, b), Vector2::default());
+    assert_eq!(vm.active_tab().dragging_label_id, None);
+}
+
+#[test]
+fn label_grab_sizes_the_label_with_the_active_dimension_style() {
+    let mut vm = CadViewModel::new();
+    let [_, b, _] = three_lines(&mut vm);
+    let unit = 5.0 / vm.active_tab().viewport.zoom;
+    // Past the end of "10.00", inside "10.00 millimetres"
+    let grab = label_grab(&vm, b) + v(7.0 * unit, 0.0);
+
+    vm.handle_drag_start(grab, InputModifiers::default());
+    assert_eq!(vm.active_tab().dragging_label_id, None);
+    vm.handle_drag_end(InputModifiers::default());
+
+    vm.config.dimension_style.suffix = " millimetres".to_string();
+    vm.handle_drag_start(grab, InputModifiers::default());
+    assert_eq!(vm.active_tab().dragging_label_id, Some(b));
+}
+
+#[test]
+fn tabs_keep_their_own_command() {
+    let mut vm = CadViewModel::new();
+    type_in(&mut vm, "line; 0,0");
+    let prompt = vm.active_tab().executor.status_message.clone();
+
+    vm.new_tab();
+    assert!(!vm.active_tab().executor.is_active());
+    type_in(&mut vm, "circle");
+    assert_eq!(
+        vm.active_tab().executor.active_command_name(),
+        Some("CIRCLE")
+    );
+
+    vm.switch_tab(0);
+    let tab = vm.active_tab();
+    assert_eq!(tab.executor.active_command_name(), Some("LINE"));
+    assert_eq!(tab.executor.status_message, prompt);
+    // No preview at the stale cursor until the pointer moves
+    assert!(tab.preview_paused);
+
+    vm.handle_click(v(10.0, 0.0), InputModifiers::default());
+    assert_eq!(vm.active_tab().model.entities.len(), 1);
+    assert!(vm.tabs[1].model.entities.is_empty());
+}
+
+#[test]
+fn leaving_a_tab_puts_a_dragged_label_back() {
+    let mut vm = CadViewModel::new();
+    let [_, b, _] = three_lines(&mut vm);
+    let grab = label_grab(&vm, b);
+    vm.handle_drag_start(grab, InputModifiers::default());
+    vm.handle_drag_update(grab + v(4.0, 4.0), InputModifiers::default());
+
+    vm.new_tab();
+    vm.switch_tab(0);
+    assert_eq!(label_offset(&vm, b), Vector2::default());
+    assert_eq!(vm.active_tab().dragging_label_id, None);
+    // The pointer moving on afterwards drags nothing
+    vm.handle_drag_update(grab + v(8.0, 8.0), InputModifiers::default());
+    assert_eq!(label_offset(&vm, b), Vector2::default());
+}
+
+#[test]
+fn click_commits_the_previewed_snap() {
+    let mut vm = CadViewModel::new();
+    vm.active_tab_mut()
+        .model
+        .add_entity(Entity::line(v(0.0, 0.0), v(100.0, 0.0)));
+    vm.active_tab_mut().model.update_hierarchy();
+    type_in(&mut vm, "line");
+
+    let pointer = v(97.0, 2.0);
+    let cursor = vm.update_cursor(pointer, ctrl());
+    assert_eq!(cursor.snapped, v(100.0, 0.0));
+    vm.handle_click(pointer, ctrl());
+    type_in(&mut vm, "100,50;");
+
+    let line = vm.active_tab().model.entities.last().unwrap();
+    assert_eq!(line.as_polyline()[0], cursor.constrained);
+}
+
+#[test]
+fn new_tabs_are_seeded_from_the_structure_library() {
+    use mugin_cad::model::structure::material::Material;
+
+    let path = std::path::PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("seed_library.json");
+    let _ = std::fs::remove_file(&path);
+    let mut vm = CadViewModel::new();
+    vm.structure_library_path = Some(path);
+    vm.active_tab_mut()
+        .model
+        .definitions
+        .add_material(Material::new_concrete(0, "C30", "C30"));
+    vm.export_structure_library();
+    assert!(vm.library_merge_prompt.is_none());
+
+    vm.new_tab();
+    assert!(vm.active_tab().model.definitions.materials.is_empty());
+
+    vm.config.gui_config.seed_structure_library = true;
+    vm.new_tab();
+    let materials = &vm.active_tab().model.definitions.materials;
+    assert_eq!(materials.values().next().unwrap().name, "C30");
+}
+
+#[test]
+fn generate_section_opens_a_new_tab() {
+    use mugin_cad::model::Shape;
+
+    let mut vm = CadViewModel::new();
+    vm.generate_section();
+    assert_eq!(vm.tabs.len(), 1);
+
+    type_in(&mut vm, "section; 0,0; 100,0; 50,10");
+    let mark = vm.active_tab().model.entities[0].id;
+    vm.active_tab_mut()
+        .selection_manager
+        .selected_ids
+        .insert(mark);
+    vm.generate_section();
+
+    assert_eq!(vm.tabs.len(), 2);
+    assert_eq!(vm.active_tab_index, 1);
+    assert!(vm.active_tab().name.ends_with("Section A–A"));
+    assert!(
+        vm.active_tab()
+            .model
+            .entities
+            .iter()
+            .any(|e| matches!(&e.shape, Shape::Text(text) if text.text == "Section A–A"))
+    );
+}
+
+#[test]
+fn cleanup_reports_the_junctions_found() {
+    use mugin_cad::model::structure::beam_type::BeamType;
+
+    let mut vm = CadViewModel::new();
+    vm.active_tab_mut()
+        .model
+        .definitions
+        .add_beam_type(BeamType::new(0, "B20x50", 20.0, 50.0, 0, 0));
+    type_in(
+        &mut vm,
+        "place_beam; 0,0; 400,0; ; place_beam; 200,-200; 200,200; ; cleanup",
+    );
+    assert_eq!(vm.active_tab().model.beam_joins.junctions.len(), 1);
+    assert_eq!(
+        vm.command_history.last().unwrap().text,
+        "Cleaned up 1 beam junctions"
+    );
+}
+
+#[test]
+fn selection_sets_survive_deleting_members() {
+    let mut vm = CadViewModel::new();
+    type_in(
+        &mut vm,
+        "line; 0,0; 10,0; ; line; 0,5; 10,5; ; selsave Walls",
+    );
+    assert!(
+        vm.command_history
+            .last()
+            .unwrap()
+            .text
+            .contains("Nothing selected")
+    );
+
+    let ids: Vec<u64> = vm
+        .active_tab()
+        .model
+        .entities
+        .iter()
+        .map(|e| e.id)
+        .collect();
+    vm.active_tab_mut()
+        .selection_manager
+        .selected_ids
+        .extend(ids.iter().copied());
+    type_in(&mut vm, "selsave Shear Walls");
+    vm.active_tab_mut().selection_manager.clear();
+    vm.active_tab_mut()
+        .model
+        .remove_entities_by_ids(&[ids[0]].into());
+
+    type_in(&mut vm, "SELLOAD Shear Walls");
+    let tab = vm.active_tab();
+    assert_eq!(tab.selection_manager.selected_ids, [ids[1]].into());
+    assert_eq!(
+        tab.executor.status_message,
+        "Selected 1 items of \"Shear Walls\"; 1 no longer exist"
+    );
+
+    type_in(&mut vm, "selload nope");
+    assert_eq!(
+        vm.command_history.last().unwrap().text,
+        "No selection set \"nope\""
+    );
+}
+
+#[test]
+fn export_region_is_picked_from_the_export_window() {
+    use mugin_cad::export::settings::ExportSource;
+    use mugin_cad::view::ui::export::window::RegionPick;
+
+    let mut vm = CadViewModel::new();
+    // The window's Pick Region button hides it and asks for a pick
+    vm.export_window.region_pick = Some(RegionPick::Requested);
+    vm.update_export_region_pick();
+    assert!(vm.active_tab().executor.is_active());
+    assert!(!vm.export_window.open);
+
+    vm.handle_click(v(0.0, 0.0), InputModifiers::default());
+    vm.update_export_region_pick();
+    assert!(!vm.export_window.open);
+    vm.handle_click(v(200.0, 100.0), InputModifiers::default());
+    vm.update_export_region_pick();
+    assert!(vm.export_window.open);
+    assert_eq!(vm.export_window.region_pick, None);
+    assert_eq!(
+        vm.export_window.settings.source,
+        ExportSource::Viewport(v(0.0, 0.0), v(200.0, 100.0))
+    );
+
+    // Dragging a corner grip leaves the opposite one in place
+    vm.handle_drag_start(v(200.0, 100.0), InputModifiers::default());
+    vm.handle_drag_update(v(300.0, 150.0), InputModifiers::default());
+    vm.handle_drag_end(InputModifiers::default());
+    assert_eq!(
+        vm.active_tab().model.export_region,
+        Some((v(0.0, 0.0), v(300.0, 150.0)))
+    );
+    assert!(
+        vm.active_tab()
+            .selection_manager
+            .selection_rect_start
+            .is_none()
+    );
+}
+
+#[test]
+fn flagged_entities_are_selected_again_by_color() {
+    let mut vm = CadViewModel::new();
+    type_in(&mut vm, "line; 0,0; 10,0;;line; 0,5; 10,5;");
+    let ids: Vec<u64> = vm
+        .active_tab()
+        .model
+        .entities
+        .iter()
+        .map(|e| e.id)
+        .collect();
+    vm.active_tab_mut()
+        .selection_manager
+        .selected_ids
+        .insert(ids[0]);
+    vm.flag_selection(Some(FlagColor::Orange));
+    vm.active_tab_mut().selection_manager.clear();
+
+    type_in(&mut vm, "selectflag ORANGE");
+    let selected = &vm.active_tab().selection_manager.selected_ids;
+    assert_eq!(selected.len(), 1);
+    assert!(selected.contains(&ids[0]));
+
+    type_in(&mut vm, "selectflag teal");
+    assert!(vm.status_message().starts_with("Unknown flag"));
+
+    // Flagging is undone like any other edit
+    vm.undo();
+    assert!(vm.active_tab().model.flagged_ids(None).is_empty());
+}
+
+#[test]
+fn window_selection_uses_the_rotated_column_footprint() {
+    let mut vm = CadViewModel::new();
+    let mut column = ColumnData::new(
+        v(100.0, 50.0),
+        30.0,
+        80.0,
+        1,
+        "C1".to_string(),
+        ColumnAnchor::Center,
+    );
+    column.rotation = 30f32.to_radians();
+    let tab = vm.active_tab_mut();
+    tab.model.add_entity(Entity::column(column));
+    tab.model.update_hierarchy();
+
+    // The unrotated 30 x 80 box cuts off the turned corners
+    fn window(tab: &mut ProjectTab, min: Vector2, max: Vector2) -> usize {
+        tab.selection_manager.start_selection_rect(min);
+        tab.selection_manager.update_selection_rect(max);
+        tab.selection_manager.end_selection_rect(&tab.model);
+        tab.selection_manager.selected_ids.len()
+    }
+    assert_eq!(window(tab, v(84.0, 9.0), v(116.0, 91.0)), 0);
+    assert_eq!(window(tab, v(66.0, 7.0), v(134.0, 93.0)), 1);
+}
+
+#[test]
+fn an_idle_app_only_wakes_up_to_save_typed_history() {
+    let mut vm = CadViewModel::new();
+    assert_eq!(vm.history_flush_due_in(), None);
+    assert!(!vm.tick(0.016));
+
+    vm.record_input("line");
+    let due = vm.history_flush_due_in().unwrap();
+    assert!(due > std::time::Duration::ZERO && due <= std::time::Duration::from_secs(2));
+    vm.flush_input_history(true);
+    assert_eq!(vm.history_flush_due_in(), None);
+}
+
+#[test]
+fn a_dragged_property_is_one_undo_step() {
+    use mugin_cad::model::Shape;
+
+    let mut vm = CadViewModel::new();
+    type_in(&mut vm, "circle; 0,0; 10");
+    let id = vm.active_tab().model.entities[0].id;
+    let steps = vm.active_tab().undo_manager.undo_count();
+    let radius = |vm: &CadViewModel| match &vm.active_tab().model.entities[0].shape {
+        Shape::Circle(circle) => circle.radius,
+        _ => unreachable!(),
+    };
+
+    // What the inspector does each frame of a drag on the radius
+    for r in [11.0, 12.0, 15.0] {
+        let entity = vm.active_tab_mut().model.find_by_id_mut(id).unwrap();
+        let before = entity.clone();
+        entity.modify(|shape| {
+            if let Shape::Circle(circle) = shape {
+                circle.radius = r;
+            }
+        });
+        vm.begin_property_edit(before);
+    }
+    assert_eq!(vm.active_tab().undo_manager.undo_count(), steps);
+    vm.end_property_edit();
+    assert_eq!(vm.active_tab().undo_manager.undo_count(), steps + 1);
+    assert_eq!(radius(&vm), 15.0);
+
+    vm.undo();
+    assert_eq!(radius(&vm), 10.0);
+
+    // Dragging back to where it started records nothing
+    let before = vm.active_tab().model.entities[0].clone();
+    vm.begin_property_edit(before);
+    vm.end_property_edit();
+    assert_eq!(vm.active_tab().undo_manager.undo_count(), steps);
+}
+
+#[test]
+fn the_canvas_cursor_follows_the_command_and_hover() {
+    use mugin_cad::model::ImageRef;
+    use mugin_cad::viewmodel::CanvasCursor;
+
+    let mut vm = CadViewModel::new();
+    let mut image = ImageRef::new("plan.png".to_string(), v(100.0, 0.0), [50, 50]);
+    image.locked = true;
+    let tab = vm.active_tab_mut();
+    tab.model
+        .add_entity(Entity::line(v(0.0, 0.0), v(50.0, 0.0)));
+    tab.model.add_entity(Entity::image(image));
+    tab.model.update_hierarchy();
+    let line = tab.model.entities[0].id;
+
+    vm.update_cursor(v(300.0, 300.0), InputModifiers::default());
+    assert_eq!(vm.canvas_cursor(None), CanvasCursor::Idle);
+    assert_eq!(vm.canvas_cursor(Some(line)), CanvasCursor::Pick);
+    vm.update_cursor(v(120.0, 20.0), InputModifiers::default());
+    assert_eq!(vm.canvas_cursor(None), CanvasCursor::Locked);
+
+    type_in(&mut vm, "line");
+    assert_eq!(vm.canvas_cursor(None), CanvasCursor::Point);
+    vm.cancel_command();
+    vm.active_tab_mut()
+        .selection_manager
+        .selected_ids
+        .insert(line);
+    type_in(&mut vm, "move");
+    assert_eq!(vm.canvas_cursor(None), CanvasCursor::Move);
+    vm.cancel_command();
+    type_in(&mut vm, "rotate");
+    assert_eq!(vm.canvas_cursor(None), CanvasCursor::Rotate);
+}
+
+#[test]
+fn double_click_fits_enters_groups_and_edits_text() {
+    use mugin_cad::model::{Shape, TextAnnotation};
+
+    let mut vm = CadViewModel::new();
+    let tab = vm.active_tab_mut();
+    tab.viewport.screen_size = v(800.0, 600.0);
+    let mut group = Entity::empty("Door");
+    group
+        .children
+        .push(Entity::line(v(0.0, 0.0), v(100.0, 0.0)));
+    tab.model.add_entity(group);
+    tab.model
+        .add_entity(Entity::text(TextAnnotation::new_custom(
+            v(300.0, 300.0),
+            "Kitchen".to_string(),
+        )));
+    tab.model.update_hierarchy();
+    let group = tab.model.entities[0].id;
+    let line = tab.model.entities[0].children[0].id;
+    let label = tab.model.entities[1].id;
+
+    vm.handle_double_click(v(-500.0, -500.0), InputModifiers::default());
+    assert_eq!(vm.status_message(), "Zoom extents");
+
+    vm.handle_double_click(v(50.0, 0.0), InputModifiers::default());
+    assert_eq!(vm.active_tab().selection_manager.scope, Some(group));
+    assert_eq!(vm.group_path(), vec![(group, "Door".to_string())]);
+    // Inside the group its children are picked, and the label is out of reach
+    vm.handle_click(v(50.0, 0.0), InputModifiers::default());
+    assert!(
+        vm.active_tab()
+            .selection_manager
+            .selected_ids
+            .contains(&line)
+    );
+    vm.handle_click(v(300.0, 300.0), InputModifiers::default());
+    assert!(vm.active_tab().selection_manager.is_empty());
+    assert_eq!(vm.escape(), EscapeStage::ExitGroup);
+    assert_eq!(vm.active_tab().selection_manager.scope, None);
+
+    vm.handle_double_click(v(300.0, 300.0), InputModifiers::default());
+    assert_eq!(vm.active_tab().text_edit.as_ref().unwrap().text, "Kitchen");
+    vm.active_tab_mut().text_edit.as_mut().unwrap().text = "Pantry".to_string();
+    vm.commit_text_edit();
+    let text = |vm: &CadViewModel| match &vm.active_tab().model.find_by_id(label).unwrap().shape {
+        Shape::Text(text) => text.text.clone(),
+        _ => unreachable!(),
+    };
+    assert_eq!(text(&vm), "Pantry");
+    vm.undo();
+    assert_eq!(text(&vm), "Kitchen");
+}
+
+#[test]
+fn group_editing_scopes_selection_and_collects_new_entities() {
+    use mugin_cad::model::Shape;
+
+    let mut vm = CadViewModel::new();
+    let tab = vm.active_tab_mut();
+    let mut inner = Entity::empty("Hinge");
+    inner.children.push(Entity::line(v(0.0, 0.0), v(10.0, 0.0)));
+    let mut door = Entity::empty("Door");
+    door.local_transform = glam::Affine2::from_translation(glam::Vec2::new(100.0, 0.0));
+    door.children
+        .push(Entity::line(v(0.0, 50.0), v(20.0, 50.0)));
+    door.children.push(inner);
+    tab.model.add_entity(door);
+    tab.model
+        .add_entity(Entity::line(v(0.0, 200.0), v(20.0, 200.0)));
+    tab.model.update_hierarchy();
+    let door = tab.model.entities[0].id;
+    let leaf = tab.model.entities[0].children[0].id;
+    let hinge = tab.model.entities[0].children[1].id;
+
+    // The menu steps into the outermost group below the edited one
+    assert_eq!(vm.enclosing_group(leaf), Some(door));
+    vm.enter_group(door);
+    assert_eq!(vm.enclosing_group(leaf), None);
+    assert_eq!(vm.enclosing_group(hinge), Some(hinge));
+
+    // A window around everything selects only the group's children
+    let selection = &mut vm.active_tab_mut().selection_manager;
+    selection.start_selection_rect(v(-50.0, -50.0));
+    selection.update_selection_rect(v(500.0, 500.0));
+    let tab = vm.active_tab_mut();
+    tab.selection_manager.end_selection_rect(&tab.model);
+    assert_eq!(
+        tab.selection_manager.selected_ids,
+        [leaf, hinge].into_iter().collect()
+    );
+
+    // Drawn in world coordinates, kept where it was drawn
+    type_in(&mut vm, "line; 100,100; 150,100; ");
+    vm.active_tab_mut().model.update_hierarchy();
+    let group = vm.active_tab().model.find_by_id(door).unwrap();
+    assert_eq!(group.children.len(), 3);
+    let line = &group.children[2];
+    let Shape::Line(shape) = &line.shape else {
+        panic!("expected a line");
+    };
+    let start: Vector2 = line
+        .world_transform
+        .transform_point2(shape.start.into())
+        .into();
+    assert_eq!(start, v(100.0, 100.0));
+    assert_eq!(vm.active_tab().model.entities.len(), 2);
+
+    vm.enter_group(hinge);
+    assert_eq!(vm.group_path().len(), 2);
+    vm.set_group_scope(None);
+    assert_eq!(vm.active_tab().selection_manager.scope, None);
+    type_in(&mut vm, "line; 0,300; 50,300; ");
+    assert_eq!(vm.active_tab().model.entities.len(), 3);
+}
+
+#[test]
+fn a_dimension_label_is_overridden_until_retyped_as_measured() {
+    use mugin_cad::model::{Shape, TextAnnotation};
+
+    let mut vm = CadViewModel::new();
+    let tab = vm.active_tab_mut();
+    tab.model
+        .add_entity(Entity::text(TextAnnotation::new_distance(
+            v(0.0, 0.0),
+            v(250.0, 0.0),
+        )));
+    let id = tab.model.entities[0].id;
+    let label = |vm: &CadViewModel| match &vm.active_tab().model.entities[0].shape {
+        Shape::Text(text) => text.label_override.clone(),
+        _ => unreachable!(),
+    };
+
+    vm.begin_text_edit(id);
+    let measured = vm.active_tab().text_edit.as_ref().unwrap().text.clone();
+    assert_eq!(measured, vm.config.dimension_style.format_length(250.0));
+    vm.active_tab_mut().text_edit.as_mut().unwrap().text = "EQ".to_string();
+    vm.commit_text_edit();
+    assert_eq!(label(&vm).as_deref(), Some("EQ"));
+
+    vm.begin_text_edit(id);
+    vm.active_tab_mut().text_edit.as_mut().unwrap().text = measured;
+    vm.commit_text_edit();
+    assert_eq!(label(&vm), None);
+}
+
+#[test]
+fn calculator_picks_a_distance_and_sends_a_length_to_line() {
+    use mugin_cad::viewmodel::CalculatorPick;
+
+    let mut vm = CadViewModel::new();
+    vm.active_tab_mut()
+        .model
+        .add_entity(Entity::line(v(0.0, 0.0), v(600.0, 0.0)));
+    vm.active_tab_mut().model.update_hierarchy();
+
+    // Snapped ends of the wall, without selecting it
+    vm.start_calculator_pick(CalculatorPick::Distance { first: None });
+    vm.update_cursor(v(3.0, 2.0), ctrl());
+    vm.handle_click(v(3.0, 2.0), ctrl());
+    vm.update_cursor(v(598.0, -1.0), ctrl());
+    vm.handle_click(v(598.0, -1.0), ctrl());
+    assert_eq!(vm.calculator_pick, None);
+    assert!(vm.active_tab().selection_manager.is_empty());
+    assert_eq!(vm.calculator.input, "600");
+
+    vm.calculator.input.push_str(" / 3");
+    vm.calculator.evaluate().unwrap();
+
+    type_in(&mut vm, "line; 0,100");
+    vm.update_cursor(v(50.0, 100.0), InputModifiers::default());
+    vm.send_calculator_answer();
+    type_in(&mut vm, "");
+    let end = vm.active_tab().model.entities.last().unwrap().as_polyline()[1];
+    assert_eq!(end, v(200.0, 100.0));
+
+    // Escape stops a pick before anything else
+    vm.start_calculator_pick(CalculatorPick::X);
+    assert_eq!(vm.escape(), EscapeStage::CancelPick);
+    assert_eq!(vm.calculator_pick, None);
+}
+
+#[test]
+fn audit_fix_removes_degenerate_geometry_as_one_step() {
+    let mut vm = CadViewModel::new();
+    type_in(&mut vm, "line; 0,0; 10,0;");
+    let point = v(4.0, 4.0);
+    let tab = vm.active_tab_mut();
+    tab.model.add_entity(Entity::line(point, point));
+    tab.model.add_entity(Entity::circle(point, 0.001, false));
+
+    type_in(&mut vm, "audit");
+    assert_eq!(vm.active_tab().model.entities.len(), 3);
+    assert_eq!(
+        vm.active_tab().executor.status_message,
+        "AUDIT: 2 degenerate entities"
+    );
+
+    type_in(&mut vm, "audit fix");
+    assert_eq!(vm.active_tab().model.entities.len(), 1);
+    type_in(&mut vm, "u");
+    assert_eq!(vm.active_tab().model.entities.len(), 3);
+}
+
+#[test]
+fn markup_mode_draws_erases_and_stays_out_of_selection() {
+    let mut vm = CadViewModel::new();
+    type_in(&mut vm, "line; 0,0; 100,0;");
+    let line = vm.active_tab().model.entities[0].id;
+    let none = InputModifiers::default();
+
+    vm.set_markup_tool(MarkupTool::Highlighter);
+    vm.handle_drag_start(v(0.0, 20.0), none);
+    for x in 1..=10 {
+        vm.handle_drag_update(v(x as f32 * 10.0, 20.0), none);
+    }
+    vm.handle_drag_update(v(100.0, 0.0), none);
+    vm.handle_drag_end(none);
+    let sketches: Vec<(u64, Vec<Vector2>, bool)> = vm
+        .active_tab()
+        .model
+        .sketches()
+        .into_iter()
+        .map(|(entity, sketch)| (entity.id, sketch.points.clone(), sketch.highlighter))
+        .collect();
+    assert_eq!(
+        sketches[0].1,
+        vec![v(0.0, 20.0), v(100.0, 20.0), v(100.0, 0.0)]
+    );
+    assert!(sketches[0].2);
+    let sketch = sketches[0].0;
+
+    // Out of markup mode, clicks and windows pass over sketches
+    assert_eq!(vm.escape(), EscapeStage::ExitMarkup);
+    vm.handle_click(v(100.0, 0.0), none);
+    let selected = &vm.active_tab().selection_manager.selected_ids;
+    assert!(selected.contains(&line) && !selected.contains(&sketch));
+    vm.handle_click(v(50.0, 20.0), none);
+    assert!(vm.active_tab().selection_manager.is_empty());
+    vm.handle_drag_start(v(-10.0, -10.0), none);
+    vm.handle_drag_update(v(110.0, 30.0), none);
+    vm.handle_drag_end(none);
+    assert_eq!(vm.active_tab().selection_manager.selected_ids.len(), 1);
+    vm.active_tab_mut().selection_manager.select_markup = true;
+    vm.handle_click(v(50.0, 20.0), none);
+    assert!(
+        vm.active_tab()
+            .selection_manager
+            .selected_ids
+            .contains(&sketch)
+    );
+
+    // The eraser takes sketches only, and undo brings them back
+    vm.set_markup_tool(MarkupTool::Eraser);
+    vm.handle_click(v(50.0, 0.0), none);
+    vm.handle_click(v(50.0, 20.0), none);
+    assert!(vm.active_tab().model.sketches().is_empty());
+    assert!(vm.active_tab().model.find_by_id(line).is_some());
+    type_in(&mut vm, "u");
+    assert_eq!(vm.active_tab().model.sketches().len(), 1);
+    vm.clear_markup();
+    assert_eq!(vm.active_tab().model.entities.len(), 1);
+}
+
+/// A project file with one line from 0,0 to 400,200, and `view` if given
+fn project_file(name: &str, view: Option<ViewState>) -> std::path::PathBuf {
+    let mut project = ProjectData::new(
+        vec![Entity::line(v(0.0, 0.0), v(400.0, 200.0))],
+        Vec::new(),
+        Default::default(),
+        StructureDefinitions::new(),
+        Default::default(),
+    );
+    project.view = view;
+    let path =
+        std::env::temp_dir().join(format!("mugin_view_{}_{}.mugin", name, std::process::id()));
+    project.write(&path, 0).unwrap();
+    path
+}
+
+#[test]
+fn projects_reopen_where_their_view_was_left() {
+    let view = ViewState {
+        offset: v(-120.0, 35.0),
+        zoom: 2.5,
+        left_tab: LeftPanelTab::Layers,
+        active_layer: 0,
+    };
+    let path = project_file("saved", Some(view.clone()));
+    let json = std::fs::read_to_string(&path).unwrap();
+    let read: ProjectData = serde_json::from_str(&json).unwrap();
+    assert_eq!(read.view, Some(view));
+
+    let mut vm = CadViewModel::new();
+    assert!(vm.open_project(&path));
+    let viewport = &vm.active_tab().viewport;
+    assert_eq!((viewport.offset, viewport.zoom), (v(-120.0, 35.0), 2.5));
+    assert_eq!(vm.config.gui_config.panels.left_tab, LeftPanelTab::Layers);
+    assert!(!vm.active_tab().fit_pending);
+
+    // The preference fits the drawing instead, once the canvas has a size
+    let mut vm = CadViewModel::new();
+    vm.config.gui_config.zoom_extents_on_open = true;
+    assert!(vm.open_project(&path));
+    assert!(vm.active_tab().fit_pending);
+    assert!(vm.config.gui_config.zoom_extents_on_open);
+    vm.active_tab_mut().viewport.screen_size = v(800.0, 600.0);
+    vm.zoom_extents();
+    assert!(!vm.active_tab().fit_pending);
+    assert_ne!(vm.active_tab().viewport.zoom, 2.5);
+    std::fs::remove_file(path).ok();
+}
+
+#[test]
+fn projects_saved_without_a_view_open_fitted() {
+    let path = project_file("old", None);
+    let json = std::fs::read_to_string(&path).unwrap();
+    assert!(!json.contains("\"view\""));
+
+    let mut vm = CadViewModel::new();
+    vm.active_tab_mut().viewport.screen_size = v(800.0, 600.0);
+    assert!(vm.open_project(&path));
+    let viewport = &vm.active_tab().viewport;
+    assert_ne!((viewport.offset, viewport.zoom), (v(0.0, 0.0), 1.0));
+    // The whole line is in view
+    let (min, max) = viewport.visible_bounds();
+    assert!(min.x <= 0.0 && min.y <= 0.0 && max.x >= 400.0 && max.y >= 200.0);
+    std::fs::remove_file(path).ok();
+}
+
+#[test]
+fn tabs_journal_their_edits_and_compare_with_a_file() {
+    let path = project_file("compare", None);
+    let mut vm = CadViewModel::new();
+    assert!(vm.open_project(&path));
+    // Opening the project is where the journal starts
+    vm.record_journal();
+    assert!(vm.active_tab().journal.is_empty());
+    let line = vm.active_tab().model.entities[0].id;
+
+    type_in(&mut vm, "circle; 50,50; 10");
+    let circle = vm.active_tab().model.entities[1].id;
+    vm.active_tab_mut()
+        .model
+        .find_by_id_mut(line)
+        .unwrap()
+        .translate(v(0.0, 25.0));
+    // Recorded once the inspector edit under way is over
+    let before = vm.active_tab().model.entities[0].clone();
+    vm.begin_property_edit(before);
+    vm.record_journal();
+    assert!(vm.active_tab().journal.is_empty());
+    vm.active_tab_mut().property_edit = None;
+    vm.record_journal();
+    let recorded: Vec<Option<u64>> = vm
+        .active_tab()
+        .journal
+        .entries()
+        .iter()
+        .map(|entry| entry.change.entity_id())
+        .collect();
+    assert_eq!(recorded, vec![Some(line), Some(circle)]);
+
+    assert!(vm.compare_with_path(&path));
+    assert!(vm.compare_window.open);
+    let comparison = vm.active_tab().comparison.as_ref().unwrap();
+    assert_eq!(comparison.counts(), (1, 0, 1));
+    assert_eq!(comparison.overlay.entities.len(), 3);
+
+    vm.close_comparison();
+    assert!(vm.active_tab().comparison.is_none());
+    assert!(!vm.compare_with_path(&path.with_extension("missing")));
+    std::fs::remove_file(path).ok();
+}
+
+/// Poll the background job the way the view does each frame until it ends
+fn finish_background(vm: &mut CadViewModel) {
+    for _ in 0..1000 {
+        vm.poll_background();
+        if !vm.is_busy() {
+            return;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+    panic!("background job did not finish");
+}
+
+#[test]
+fn edits_made_during_a_save_stay_unsaved() {
+    let path =
+        std::env::temp_dir().join(format!("mugin_view_pending_{}.mugin", std::process::id()));
+    let mut vm = CadViewModel::new();
+    vm.config.gui_config.backup_count = 0;
+    type_in(&mut vm, "line; 0,0; 10,0;");
+    vm.save_project_to(path.clone());
+    finish_background(&mut vm);
+    assert!(!vm.active_tab().is_dirty);
+
+    vm.save_project_to(path.clone());
+    type_in(&mut vm, "line; 0,10; 10,10;");
+    finish_background(&mut vm);
+    let tab = vm.active_tab();
+    assert_eq!(tab.file_path.as_deref(), Some(path.as_path()));
+    assert!(tab.is_dirty);
+    assert_eq!(tab.saved_revisions.unsaved(&tab.model.entities).changed, 1);
+    std::fs::remove_file(path).ok();
+}
+
+#[test]
+fn layers_survive_save_and_reopen() {
+    use mugin_cad::model::layer::LineStyle;
+
+    let path = std::env::temp_dir().join(format!("mugin_view_layers_{}.mugin", std::process::id()));
+    let mut vm = CadViewModel::new();
+    vm.config.gui_config.backup_count = 0;
+    let layers = &mut vm.active_tab_mut().model.layer_manager;
+    let walls = layers.add_layer("Walls".to_string(), eframe::egui::Color32::RED);
+    let layer = layers.layers.get_mut(&walls).unwrap();
+    layer.line_style = LineStyle::DashDot;
+    layer.stroke_width = 3.0;
+    layer.plottable = false;
+    let saved = layer.clone();
+    layers.set_active_layer(walls);
+    type_in(&mut vm, "line; 0,0; 10,0;");
+    vm.save_project_to(path.clone());
+    finish_background(&mut vm);
+
+    let mut reopened = CadViewModel::new();
+    assert!(reopened.open_project(&path));
+    let layers = &reopened.active_tab().model.layer_manager;
+    assert_eq!(layers.get_layer(walls), Some(&saved));
+    assert_eq!(layers.active_layer_id, walls);
+    assert_eq!(reopened.active_tab().model.entities[0].layer_id, walls);
+
+    // Printing from the file sees the same layer table
+    let model = ProjectData::read(&path).unwrap().into_model();
+    assert_eq!(model.layer_manager.get_layer(walls), Some(&saved));
+
+    // Files from before layers were saved open with the default layer
+    let mut json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    json.as_object_mut().unwrap().remove("layers");
+    let old: ProjectData = serde_json::from_value(json).unwrap();
+    assert_eq!(old.layers.get_sorted_layers().len(), 1);
+    std::fs::remove_file(path).ok();
+}
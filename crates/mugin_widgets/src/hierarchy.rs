@@ -1,7 +1,8 @@
 //! Reusable tree-view widget for hierarchy panels.
 //!
 //! Supports collapsible nodes, single-click selection,
-//! double-click inline rename, and drag-and-drop reparenting.
+//! double-click inline rename, drag-and-drop reparenting, colored row
+//! markers, warning badges and a right-click menu per row.
 
 use eframe::egui;
 use std::collections::HashSet;
@@ -14,6 +15,10 @@ pub struct TreeNode {
     pub id: u64,
     pub label: String,
     pub icon: &'static str,
+    /// Colored dot drawn after the label
+    pub marker: Option<egui::Color32>,
+    /// Problem with the entity, shown as a warning badge with this hover text
+    pub warning: Option<String>,
     pub children: Vec<TreeNode>,
 }
 
@@ -33,15 +38,18 @@ const DRAG_KEY: &str = "tree_drag_ids";
 
 // ─── Widget ──────────────────────────────────────────────────────────────
 
+/// Show `nodes`. `context_menu` fills the right-click menu of a row with
+/// the row's node id.
 pub fn tree_view(
     ui: &mut egui::Ui,
     nodes: &[TreeNode],
     selected_ids: &HashSet<u64>,
+    mut context_menu: impl FnMut(&mut egui::Ui, u64),
 ) -> TreeResponse {
     let mut response = TreeResponse::default();
 
     for node in nodes {
-        render_node(ui, node, selected_ids, &mut response, 0);
+        render_node(ui, node, selected_ids, &mut response, &mut context_menu, 0);
     }
 
     // Retrieve drag state
@@ -68,41 +76,39 @@ pub fn tree_view(
             );
         }
 
-        if hovered && ui.input(|i| i.pointer.any_released()) {
-            if let Some(ids) = drag_ids {
-                response.reparent = Some((ids, None));
-            }
+        if hovered
+            && ui.input(|i| i.pointer.any_released())
+            && let Some(ids) = drag_ids
+        {
+            response.reparent = Some((ids, None));
         }
     }
 
     // Ghost visual
-    if let Some((_, label)) = drag_state {
-        if let Some(pointer_pos) = ui.ctx().pointer_hover_pos() {
-            let painter = ui.ctx().layer_painter(egui::LayerId::new(
-                egui::Order::Tooltip,
-                egui::Id::new("drag_ghost"),
-            ));
-
-            let text = format!("Move: {}", label);
-            // Draw text with background
-            let galley = painter.layout_no_wrap(
-                text,
-                egui::FontId::proportional(14.0),
-                egui::Color32::WHITE,
-            );
-
-            let rect = egui::Rect::from_min_size(
-                pointer_pos + egui::vec2(10.0, 10.0),
-                galley.size() + egui::vec2(10.0, 5.0),
-            );
-
-            painter.rect_filled(rect, 3.0, egui::Color32::from_black_alpha(200));
-            painter.galley(
-                rect.min + egui::vec2(5.0, 2.5),
-                galley,
-                egui::Color32::BLACK,
-            );
-        }
+    if let Some((_, label)) = drag_state
+        && let Some(pointer_pos) = ui.ctx().pointer_hover_pos()
+    {
+        let painter = ui.ctx().layer_painter(egui::LayerId::new(
+            egui::Order::Tooltip,
+            egui::Id::new("drag_ghost"),
+        ));
+
+        let text = format!("Move: {}", label);
+        // Draw text with background
+        let galley =
+            painter.layout_no_wrap(text, egui::FontId::proportional(14.0), egui::Color32::WHITE);
+
+        let rect = egui::Rect::from_min_size(
+            pointer_pos + egui::vec2(10.0, 10.0),
+            galley.size() + egui::vec2(10.0, 5.0),
+        );
+
+        painter.rect_filled(rect, 3.0, egui::Color32::from_black_alpha(200));
+        painter.galley(
+            rect.min + egui::vec2(5.0, 2.5),
+            galley,
+            egui::Color32::BLACK,
+        );
     }
 
     // Clear drag state on pointer release
@@ -120,6 +126,7 @@ fn render_node(
     node: &TreeNode,
     selected_ids: &HashSet<u64>,
     response: &mut TreeResponse,
+    context_menu: &mut dyn FnMut(&mut egui::Ui, u64),
     depth: usize,
 ) {
     let is_selected = selected_ids.contains(&node.id);
@@ -241,15 +248,15 @@ fn render_node(
             );
             te.request_focus();
 
-            if te.lost_focus() || ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+            // Escape also takes focus away, so check it first to discard
+            if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                renaming = false;
+            } else if te.lost_focus() || ui.input(|i| i.key_pressed(egui::Key::Enter)) {
                 renaming = false;
                 if !buf.is_empty() {
                     response.renamed = Some((node.id, buf.clone()));
                 }
             }
-            if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
-                renaming = false;
-            }
             ui.data_mut(|d| d.insert_temp(buf_key, buf));
         } else {
             let text = if is_selected {
@@ -261,6 +268,14 @@ fn render_node(
             };
 
             let r = ui.add(egui::Label::new(text).sense(egui::Sense::click_and_drag()));
+            if let Some(marker) = node.marker {
+                ui.label(egui::RichText::new("●").color(marker));
+            }
+            if let Some(warning) = &node.warning {
+                ui.label(egui::RichText::new("⚠").color(egui::Color32::from_rgb(230, 170, 40)))
+                    .on_hover_text(warning);
+            }
+            r.context_menu(|ui| context_menu(ui, node.id));
 
             if r.clicked() {
                 // Determine modifier state
@@ -302,13 +317,14 @@ fn render_node(
         }
 
         // Drop on this node
-        if is_drop_target && ui.input(|i| i.pointer.any_released()) {
-            if let Some(dids) = drag_ids {
-                // Don't drop 'node' onto itself
-                if !dids.contains(&node.id) {
-                    response.reparent = Some((dids, Some(node.id)));
-                    ui.data_mut(|d| d.remove_temp::<(Vec<u64>, String)>(drag_key));
-                }
+        if is_drop_target
+            && ui.input(|i| i.pointer.any_released())
+            && let Some(dids) = drag_ids
+        {
+            // Don't drop 'node' onto itself
+            if !dids.contains(&node.id) {
+                response.reparent = Some((dids, Some(node.id)));
+                ui.data_mut(|d| d.remove_temp::<(Vec<u64>, String)>(drag_key));
             }
         }
     });
@@ -318,7 +334,7 @@ fn render_node(
     // Children
     if has_children && is_open {
         for child in &node.children {
-            render_node(ui, child, selected_ids, response, depth + 1);
+            render_node(ui, child, selected_ids, response, context_menu, depth + 1);
         }
     }
 }
@@ -7,22 +7,30 @@
 //!
 //! - **`window`** — Modal dialogs and resizable windows
 //! - **`properties`** — Property editors (point, float, angle, color)
-//! - **`toolbar`** — Toolbar buttons and menu items
+//! - **`toolbar`** — Toolbar buttons, flyouts and menu items
 //!
 //! ## Quick Start
 //!
 //! ```rust
 //! use mugin_widgets::properties;
 //! use mugin_widgets::window;
+//! # use eframe::egui;
+//! # fn show(ui: &mut egui::Ui, ctx: &egui::Context) {
+//! # let (mut x, mut y, mut open, mut name) = (0.0, 0.0, false, String::new());
 //!
 //! // Edit a 2D point with labeled X/Y drag values
 //! properties::point2(ui, "Position", &mut x, &mut y);
 //!
 //! // Show a centered modal dialog
-//! window::modal("Rename", ctx, &mut open, |ui| {
+//! window::Modal::new("Rename").show(ctx, &mut open, |ui, enter| {
 //!     ui.text_edit_singleline(&mut name);
-//!     ui.button("OK").clicked()
+//!     if ui.button("OK").clicked() || enter {
+//!         window::ModalResponse::Accepted(())
+//!     } else {
+//!         window::ModalResponse::Pending
+//!     }
 //! });
+//! # }
 //! ```
 
 pub mod card;
@@ -53,3 +53,125 @@ pub fn right_panel(
         )
         .show(ctx, body);
 }
+
+/// Width of a collapsed side panel
+const COLLAPSED_WIDTH: f32 = 28.0;
+
+/// Renders a left side panel with a title row and a collapse button.
+///
+/// `width` is used when the panel first appears and receives the width
+/// the user dragged it to. While `collapsed` is set only a narrow strip
+/// with an expand button is shown.
+///
+/// # Arguments
+/// * `id` - Unique ID for the panel
+/// * `title` - Heading shown next to the collapse button
+/// * `width` - Panel width, read and updated
+/// * `collapsed` - Collapsed state, toggled by the panel's buttons
+/// * `ctx` - Egui context
+/// * `body` - Closure to render panel content
+pub fn left_panel_collapsible(
+    id: impl Into<egui::Id>,
+    title: &str,
+    width: &mut f32,
+    collapsed: &mut bool,
+    ctx: &egui::Context,
+    body: impl FnOnce(&mut egui::Ui),
+) {
+    collapsible_panel(
+        egui::panel::Side::Left,
+        id.into(),
+        title,
+        width,
+        collapsed,
+        ctx,
+        body,
+    );
+}
+
+/// Renders a right side panel with a title row and a collapse button.
+///
+/// See [`left_panel_collapsible`].
+pub fn right_panel_collapsible(
+    id: impl Into<egui::Id>,
+    title: &str,
+    width: &mut f32,
+    collapsed: &mut bool,
+    ctx: &egui::Context,
+    body: impl FnOnce(&mut egui::Ui),
+) {
+    collapsible_panel(
+        egui::panel::Side::Right,
+        id.into(),
+        title,
+        width,
+        collapsed,
+        ctx,
+        body,
+    );
+}
+
+/// Forget the width the user dragged a panel to, so `width` applies again.
+pub fn reset_panel(ctx: &egui::Context, id: impl Into<egui::Id>) {
+    let id = id.into();
+    ctx.data_mut(|d| d.remove::<egui::panel::PanelState>(id));
+}
+
+fn collapsible_panel(
+    side: egui::panel::Side,
+    id: egui::Id,
+    title: &str,
+    width: &mut f32,
+    collapsed: &mut bool,
+    ctx: &egui::Context,
+    body: impl FnOnce(&mut egui::Ui),
+) {
+    let frame = egui::Frame::none()
+        .fill(PANEL_FILL)
+        .inner_margin(PANEL_MARGIN);
+    // Arrows point the way the panel will move
+    let (collapse_icon, expand_icon) = match side {
+        egui::panel::Side::Left => ("◀", "▶"),
+        egui::panel::Side::Right => ("▶", "◀"),
+    };
+
+    if *collapsed {
+        // Separate id so the strip does not overwrite the stored width
+        egui::SidePanel::new(side, id.with("collapsed"))
+            .resizable(false)
+            .exact_width(COLLAPSED_WIDTH)
+            .frame(egui::Frame::none().fill(PANEL_FILL).inner_margin(4.0))
+            .show(ctx, |ui| {
+                if ui
+                    .small_button(expand_icon)
+                    .on_hover_text(format!("Show {}", title))
+                    .clicked()
+                {
+                    *collapsed = false;
+                }
+            });
+        return;
+    }
+
+    let response = egui::SidePanel::new(side, id)
+        .resizable(true)
+        .default_width(*width)
+        .frame(frame)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.heading(title);
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui
+                        .small_button(collapse_icon)
+                        .on_hover_text(format!("Collapse {}", title))
+                        .clicked()
+                    {
+                        *collapsed = true;
+                    }
+                });
+            });
+            ui.separator();
+            body(ui);
+        });
+    *width = response.response.rect.width();
+}
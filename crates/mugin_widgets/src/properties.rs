@@ -40,6 +40,141 @@ pub fn point2_speed(ui: &mut egui::Ui, label: &str, x: &mut f32, y: &mut f32, sp
     });
 }
 
+/// Settings for [`point2_ex`].
+#[derive(Debug, Clone, Copy)]
+pub struct Point2Options {
+    /// Point that polar entry and the aspect lock are relative to
+    pub origin: [f32; 2],
+    /// Show the aspect lock toggle
+    pub lockable: bool,
+    /// Drag speed of the value fields
+    pub speed: f64,
+}
+
+impl Default for Point2Options {
+    fn default() -> Self {
+        Self {
+            origin: [0.0, 0.0],
+            lockable: false,
+            speed: 0.1,
+        }
+    }
+}
+
+/// Entry mode and lock state of one [`point2_ex`] editor.
+#[derive(Clone, Copy, Default)]
+struct Point2State {
+    polar: bool,
+    locked: bool,
+}
+
+/// Renders a point editor that can switch between X/Y and distance/angle
+/// entry relative to `options.origin`, with an optional aspect lock that
+/// keeps the point on its line through the origin while one axis is edited.
+///
+/// The returned response reports `changed()` when the point moved.
+///
+/// ```text
+/// ┌ End Point ──────────────────────┐
+/// │ X: [123.4]  Y: [56.7]  [∠] [🔓] │
+/// └─────────────────────────────────┘
+/// ```
+///
+/// # Example
+///
+/// ```rust
+/// # use eframe::egui;
+/// # use mugin_widgets::properties::{self, Point2Options};
+/// # fn show(ui: &mut egui::Ui, start: [f32; 2], end: &mut [f32; 2]) {
+/// let [x, y] = end;
+/// let options = Point2Options { origin: start, ..Default::default() };
+/// if properties::point2_ex(ui, "End Point", x, y, options).changed() {
+///     println!("moved");
+/// }
+/// # }
+/// ```
+pub fn point2_ex(
+    ui: &mut egui::Ui,
+    label: &str,
+    x: &mut f32,
+    y: &mut f32,
+    options: Point2Options,
+) -> egui::Response {
+    let state_id = ui.id().with(("point2_ex", label));
+    let mut state = ui
+        .data(|d| d.get_temp::<Point2State>(state_id))
+        .unwrap_or_default();
+    let [ox, oy] = options.origin;
+
+    let response = ui
+        .group(|ui| {
+            ui.label(label);
+            ui.horizontal(|ui| {
+                let response = if state.polar {
+                    let (dx, dy) = (*x - ox, *y - oy);
+                    let mut distance = dx.hypot(dy);
+                    let mut degrees = dy.atan2(dx).to_degrees();
+                    ui.label("D:");
+                    let d = ui.add(
+                        egui::DragValue::new(&mut distance)
+                            .speed(options.speed)
+                            .range(0.0..=f32::INFINITY),
+                    );
+                    ui.label("∠:");
+                    let a = ui.add(egui::DragValue::new(&mut degrees).speed(1.0).suffix("°"));
+                    let response = d.union(a);
+                    if response.changed() {
+                        let (sin, cos) = degrees.to_radians().sin_cos();
+                        *x = ox + distance * cos;
+                        *y = oy + distance * sin;
+                    }
+                    response
+                } else {
+                    let (old_x, old_y) = (*x, *y);
+                    ui.label("X:");
+                    let rx = ui.add(egui::DragValue::new(x).speed(options.speed));
+                    ui.label("Y:");
+                    let ry = ui.add(egui::DragValue::new(y).speed(options.speed));
+                    if state.locked {
+                        // Scale the other axis by the same factor
+                        if rx.changed() && (old_x - ox).abs() > f32::EPSILON {
+                            *y = oy + (old_y - oy) * (*x - ox) / (old_x - ox);
+                        } else if ry.changed() && (old_y - oy).abs() > f32::EPSILON {
+                            *x = ox + (old_x - ox) * (*y - oy) / (old_y - oy);
+                        }
+                    }
+                    rx.union(ry)
+                };
+
+                let mode = if state.polar { "XY" } else { "∠" };
+                let mode_hint = if state.polar {
+                    "Enter X and Y"
+                } else {
+                    "Enter distance and angle"
+                };
+                if ui.small_button(mode).on_hover_text(mode_hint).clicked() {
+                    state.polar = !state.polar;
+                }
+                if options.lockable && !state.polar {
+                    let icon = if state.locked { "🔒" } else { "🔓" };
+                    if ui
+                        .small_button(icon)
+                        .on_hover_text("Lock aspect ratio")
+                        .clicked()
+                    {
+                        state.locked = !state.locked;
+                    }
+                }
+                response
+            })
+            .inner
+        })
+        .inner;
+
+    ui.data_mut(|d| d.insert_temp(state_id, state));
+    response
+}
+
 // ─── Scalar Editors ──────────────────────────────────────────────────────
 
 /// Renders a labeled drag-value for a single float.
@@ -90,6 +225,125 @@ pub fn angle_degrees(ui: &mut egui::Ui, label: &str, radians: &mut f32) {
     });
 }
 
+/// Renders an angle editor with a unit toggle and a compass dial.
+///
+/// The value is stored in radians and shown in degrees (0..360) or
+/// radians. Clicking or dragging on the dial points the needle at the
+/// cursor. Edited values are normalized to one turn. The returned response
+/// reports `changed()` when the angle changed.
+///
+/// ```text
+/// Rotation: [45.0°] [°] (↗)
+/// ```
+pub fn angle(ui: &mut egui::Ui, label: &str, radians: &mut f32) -> egui::Response {
+    let unit_id = ui.id().with(("angle_in_radians", label));
+    let mut in_radians = ui.data(|d| d.get_temp::<bool>(unit_id)).unwrap_or(false);
+    let turn = std::f32::consts::TAU;
+
+    let response = ui
+        .horizontal(|ui| {
+            ui.label(label);
+
+            let mut value = radians.rem_euclid(turn);
+            let field = if in_radians {
+                ui.add(
+                    egui::DragValue::new(&mut value)
+                        .speed(0.01)
+                        .max_decimals(4)
+                        .suffix(" rad"),
+                )
+            } else {
+                let mut degrees = value.to_degrees();
+                let field = ui.add(egui::DragValue::new(&mut degrees).speed(1.0).suffix("°"));
+                value = degrees.to_radians();
+                field
+            };
+            if field.changed() {
+                *radians = value.rem_euclid(turn);
+            }
+
+            let unit = if in_radians { "rad" } else { "°" };
+            if ui
+                .small_button(unit)
+                .on_hover_text("Switch between degrees and radians")
+                .clicked()
+            {
+                in_radians = !in_radians;
+            }
+
+            let dial = compass(ui, radians);
+            field.union(dial)
+        })
+        .inner;
+
+    ui.data_mut(|d| d.insert_temp(unit_id, in_radians));
+    response
+}
+
+/// Renders an angle editor whose text the caller formats and parses, e.g.
+/// in degrees-minutes-seconds or as a bearing, next to the compass dial.
+///
+/// The value is stored in radians and normalized to one turn when edited.
+/// Dragging the field turns it by about half a degree per pixel.
+///
+/// ```text
+/// Start Angle: [N 45°00'00" E] (↗)
+/// ```
+pub fn angle_text(
+    ui: &mut egui::Ui,
+    label: &str,
+    radians: &mut f32,
+    format: impl Fn(f32) -> String,
+    parse: impl Fn(&str) -> Option<f32>,
+) -> egui::Response {
+    let turn = std::f32::consts::TAU;
+    ui.horizontal(|ui| {
+        ui.label(label);
+        let mut value = *radians as f64;
+        let field = ui.add(
+            egui::DragValue::new(&mut value)
+                .speed(0.01)
+                .custom_formatter(|value, _| format(value as f32))
+                .custom_parser(|text| parse(text).map(f64::from)),
+        );
+        if field.changed() {
+            *radians = (value as f32).rem_euclid(turn);
+        }
+        let dial = compass(ui, radians);
+        field.union(dial)
+    })
+    .inner
+}
+
+/// Small dial showing `radians` as a needle; click or drag to set it.
+fn compass(ui: &mut egui::Ui, radians: &mut f32) -> egui::Response {
+    let size = ui.spacing().interact_size.y;
+    let (rect, mut response) =
+        ui.allocate_exact_size(egui::vec2(size, size), egui::Sense::click_and_drag());
+
+    if (response.clicked() || response.dragged())
+        && let Some(pointer) = response.interact_pointer_pos()
+    {
+        let d = pointer - rect.center();
+        if d.length() > 1.0 {
+            // Screen Y runs down, angles run counter-clockwise
+            let angle = (-d.y).atan2(d.x).rem_euclid(std::f32::consts::TAU);
+            if angle != *radians {
+                *radians = angle;
+                response.mark_changed();
+            }
+        }
+    }
+
+    let visuals = ui.style().interact(&response);
+    let radius = rect.width() / 2.0 - 1.0;
+    let painter = ui.painter();
+    painter.circle(rect.center(), radius, visuals.bg_fill, visuals.fg_stroke);
+    let tip = rect.center() + radius * egui::vec2(radians.cos(), -radians.sin());
+    painter.line_segment([rect.center(), tip], visuals.fg_stroke);
+    response.on_hover_text("Drag to set the angle")
+}
+
 // ─── Color Editor ────────────────────────────────────────────────────────
 
 /// Renders a labeled color picker for an `[u8; 3]` RGB color.
@@ -124,11 +378,29 @@ pub fn section(ui: &mut egui::Ui, title: &str, body: impl FnOnce(&mut egui::Ui))
 
 /// Renders a collapsible section — useful for settings panels.
 pub fn collapsible_section(ui: &mut egui::Ui, title: &str, body: impl FnOnce(&mut egui::Ui)) {
-    ui.collapsing(title, |ui| {
-        ui.group(|ui| {
-            body(ui);
+    revealable_section(ui, title, false, body);
+}
+
+/// Collapsible section that opens and scrolls into view when `reveal` is
+/// set, e.g. when jumped to from a search.
+pub fn revealable_section(
+    ui: &mut egui::Ui,
+    title: &str,
+    reveal: bool,
+    body: impl FnOnce(&mut egui::Ui),
+) {
+    let response = egui::CollapsingHeader::new(title)
+        .open(reveal.then_some(true))
+        .show(ui, |ui| {
+            ui.group(|ui| {
+                body(ui);
+            });
         });
-    });
+    if reveal {
+        response
+            .header_response
+            .scroll_to_me(Some(egui::Align::TOP));
+    }
 }
 
 // ─── Text Input ─────────────────────────────────────────────────────────
@@ -15,9 +15,13 @@ use eframe::egui;
 /// # Example
 ///
 /// ```rust
+/// # use eframe::egui;
+/// # fn start_move_command() {}
+/// # fn show(ui: &mut egui::Ui) {
 /// if mugin_widgets::toolbar::icon_button(ui, "M", "Move (W)", true, [36.0, 36.0]) {
 ///     start_move_command();
 /// }
+/// # }
 /// ```
 pub fn icon_button(
     ui: &mut egui::Ui,
@@ -39,6 +43,128 @@ pub fn tool_button(ui: &mut egui::Ui, label: &str, tooltip: &str, enabled: bool)
     icon_button(ui, label, tooltip, enabled, [36.0, 36.0])
 }
 
+/// Seconds a toolbar button must be held before its flyout opens.
+const FLYOUT_HOLD_TIME: f64 = 0.4;
+
+/// Renders a toolbar button that also owns a flyout of related buttons.
+///
+/// The flyout opens on click-and-hold, on a click on the chevron in the
+/// bottom-right corner, or on a secondary click. It closes on the next
+/// click anywhere. Returns `true` when the button face itself is clicked.
+///
+/// # Example
+///
+/// ```rust
+/// # use eframe::egui;
+/// # fn start_circle_command() {}
+/// # fn start_arc_command() {}
+/// # fn show(ui: &mut egui::Ui) {
+/// use mugin_widgets::toolbar;
+///
+/// if toolbar::flyout_button(ui, "O", "Circle (C)", true, |ui| {
+///     if toolbar::tool_button(ui, "(", "Arc", true) {
+///         start_arc_command();
+///     }
+/// }) {
+///     start_circle_command();
+/// }
+/// # }
+/// ```
+pub fn flyout_button(
+    ui: &mut egui::Ui,
+    label: &str,
+    tooltip: &str,
+    enabled: bool,
+    flyout: impl FnOnce(&mut egui::Ui),
+) -> bool {
+    let response = ui
+        .add_enabled(
+            enabled,
+            egui::Button::new(label).min_size(egui::vec2(36.0, 36.0)),
+        )
+        .on_hover_text(tooltip);
+
+    let rect = response.rect;
+    let popup_id = response.id.with("flyout");
+    let held_id = popup_id.with("held");
+
+    // Chevron marking the flyout
+    let chevron = egui::Rect::from_min_max(
+        rect.right_bottom() - egui::vec2(10.0, 10.0),
+        rect.right_bottom(),
+    );
+    let color = ui.style().interact(&response).fg_stroke.color;
+    ui.painter().add(egui::Shape::convex_polygon(
+        vec![
+            chevron.right_top() + egui::vec2(-2.0, 2.0),
+            chevron.right_bottom() + egui::vec2(-2.0, -2.0),
+            chevron.left_bottom() + egui::vec2(2.0, -2.0),
+        ],
+        color,
+        egui::Stroke::NONE,
+    ));
+
+    let was_open = ui.memory(|m| m.is_popup_open(popup_id));
+
+    // Click-and-hold
+    if response.is_pointer_button_down_on() && !was_open {
+        let held = ui.input(|i| i.pointer.press_start_time().map(|t| i.time - t));
+        match held {
+            Some(held) if held >= FLYOUT_HOLD_TIME => {
+                ui.memory_mut(|m| m.open_popup(popup_id));
+                ui.data_mut(|d| d.insert_temp(held_id, true));
+            }
+            Some(held) => ui
+                .ctx()
+                .request_repaint_after(std::time::Duration::from_secs_f64(FLYOUT_HOLD_TIME - held)),
+            None => {}
+        }
+    }
+
+    let mut clicked = false;
+    if response.clicked() {
+        let on_chevron = response
+            .interact_pointer_pos()
+            .is_some_and(|pos| chevron.contains(pos));
+        if ui
+            .data_mut(|d| d.remove_temp::<bool>(held_id))
+            .unwrap_or(false)
+        {
+            // Release that ends a click-and-hold keeps the flyout open
+        } else if on_chevron {
+            ui.memory_mut(|m| m.toggle_popup(popup_id));
+        } else if was_open {
+            ui.memory_mut(|m| m.close_popup());
+        } else {
+            clicked = true;
+        }
+    }
+    if response.secondary_clicked() {
+        ui.memory_mut(|m| m.toggle_popup(popup_id));
+    }
+
+    if ui.memory(|m| m.is_popup_open(popup_id)) {
+        egui::Area::new(popup_id)
+            .order(egui::Order::Foreground)
+            .fixed_pos(rect.right_top() + egui::vec2(4.0, 0.0))
+            .show(ui.ctx(), |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.horizontal(flyout);
+                });
+            });
+
+        // Any other click (including one on a flyout entry) closes it
+        let other_click = ui.input(|i| i.pointer.any_click())
+            && !response.clicked()
+            && !response.secondary_clicked();
+        if was_open && other_click {
+            ui.memory_mut(|m| m.close_popup());
+        }
+    }
+
+    clicked
+}
+
 // ─── Menu Items ──────────────────────────────────────────────────────────
 
 /// Renders a menu item button. Returns `true` if clicked.
@@ -48,9 +174,13 @@ pub fn tool_button(ui: &mut egui::Ui, label: &str, tooltip: &str, enabled: bool)
 /// # Example
 ///
 /// ```rust
+/// # use eframe::egui;
+/// # fn start_line_command() {}
+/// # fn show(ui: &mut egui::Ui) {
 /// if mugin_widgets::toolbar::menu_item(ui, "Line (L)", true) {
 ///     start_line_command();
 /// }
+/// # }
 /// ```
 pub fn menu_item(ui: &mut egui::Ui, label: &str, enabled: bool) -> bool {
     let clicked = ui.add_enabled(enabled, egui::Button::new(label)).clicked();
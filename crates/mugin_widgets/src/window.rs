@@ -7,72 +7,296 @@ use eframe::egui;
 
 // ─── Modal Dialog ────────────────────────────────────────────────────────
 
-/// Shows a centered modal dialog. Returns `true` when the dialog closes.
+/// Outcome of showing a [`Modal`] for one frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModalResponse<T> {
+    /// Still open, no decision made.
+    Pending,
+    /// Dismissed: Escape, the X button, or the body returned `Closed`.
+    Closed,
+    /// The body accepted with a value.
+    Accepted(T),
+}
+
+/// Modals shown this frame and last frame, bottom to top. The topmost
+/// one of last frame is the one that gets keyboard input.
+#[derive(Clone, Default)]
+struct ModalStack {
+    pass: u64,
+    current: Vec<egui::Id>,
+    previous: Vec<egui::Id>,
+}
+
+fn modal_stack_id() -> egui::Id {
+    egui::Id::new("mugin_modal_stack")
+}
+
+/// Register a modal for this frame. Returns whether it is on top.
+fn push_modal(ctx: &egui::Context, id: egui::Id) -> bool {
+    let pass = ctx.cumulative_pass_nr();
+    ctx.data_mut(|d| {
+        let stack = d.get_temp_mut_or_default::<ModalStack>(modal_stack_id());
+        if stack.pass != pass {
+            let current = std::mem::take(&mut stack.current);
+            stack.previous = if stack.pass + 1 == pass {
+                current
+            } else {
+                Vec::new()
+            };
+            stack.pass = pass;
+        }
+        stack.current.push(id);
+        stack.previous.last().is_none_or(|top| *top == id)
+    })
+}
+
+/// Whether a modal is showing. Callers that grab keyboard focus (e.g. a
+/// command line) should leave it alone while this is true.
+pub fn is_modal_open(ctx: &egui::Context) -> bool {
+    let pass = ctx.cumulative_pass_nr();
+    ctx.data(|d| {
+        d.get_temp::<ModalStack>(modal_stack_id())
+            .is_some_and(|stack| {
+                if stack.pass == pass {
+                    !stack.current.is_empty() || !stack.previous.is_empty()
+                } else {
+                    stack.pass + 1 == pass && !stack.current.is_empty()
+                }
+            })
+    })
+}
+
+/// A centered modal dialog that dims and blocks everything beneath it.
 ///
-/// The `body` closure should render the dialog content. Return `true`
-/// from the body to signal that the dialog should close (e.g., when
-/// the user presses "OK" or hits Enter).
+/// Only the topmost modal reacts to Escape and Enter, and keyboard focus
+/// is kept inside it, so a modal opened from another modal (or from a
+/// window) does not fight it for input.
 ///
 /// # Example
 ///
 /// ```rust
-/// let closed = mugin_widgets::window::modal("Rename", ctx, &mut is_open, |ui| {
+/// # use eframe::egui;
+/// # use mugin_widgets::window::{Modal, ModalResponse};
+/// # fn show(ctx: &egui::Context, is_open: &mut bool, name: &mut String) {
+/// let response = Modal::new("Rename").show(ctx, is_open, |ui, enter| {
 ///     ui.label("Enter new name:");
-///     let r = ui.text_edit_singleline(&mut name);
-///     r.request_focus();
-///     ui.button("OK").clicked() // returns true → closes dialog
+///     ui.text_edit_singleline(name).request_focus();
+///     if ui.button("OK").clicked() || enter {
+///         ModalResponse::Accepted(name.clone())
+///     } else {
+///         ModalResponse::Pending
+///     }
 /// });
+/// if let ModalResponse::Accepted(new_name) = response {
+///     println!("renamed to {new_name}");
+/// }
+/// # }
 /// ```
-pub fn modal(
-    title: &str,
-    ctx: &egui::Context,
-    open: &mut bool,
-    body: impl FnOnce(&mut egui::Ui) -> bool,
-) -> bool {
-    if !*open {
-        return false;
-    }
+///
+/// Escape closes the modal and clears `open`:
+///
+/// ```rust
+/// # use eframe::egui;
+/// # use mugin_widgets::window::{Modal, ModalResponse};
+/// let ctx = egui::Context::default();
+/// let mut open = true;
+/// let mut response = ModalResponse::Pending;
+/// let escape = egui::Event::Key {
+///     key: egui::Key::Escape,
+///     physical_key: None,
+///     pressed: true,
+///     repeat: false,
+///     modifiers: egui::Modifiers::NONE,
+/// };
+/// let input = egui::RawInput { events: vec![escape], ..Default::default() };
+/// let _ = ctx.run(input, |ctx| {
+///     response = Modal::new("Test").show(ctx, &mut open, |_, _| ModalResponse::<()>::Pending);
+/// });
+/// assert_eq!(response, ModalResponse::Closed);
+/// assert!(!open);
+/// ```
+///
+/// With `esc_closes(false)` Escape is ignored, and Enter is passed to the
+/// body as `enter`:
+///
+/// ```rust
+/// # use eframe::egui;
+/// # use mugin_widgets::window::{Modal, ModalResponse};
+/// # let key = |key| egui::Event::Key {
+/// #     key, physical_key: None, pressed: true, repeat: false,
+/// #     modifiers: egui::Modifiers::NONE,
+/// # };
+/// let ctx = egui::Context::default();
+/// let mut open = true;
+/// let mut response = ModalResponse::Pending;
+/// let input = egui::RawInput {
+///     events: vec![key(egui::Key::Escape), key(egui::Key::Enter)],
+///     ..Default::default()
+/// };
+/// let _ = ctx.run(input, |ctx| {
+///     response = Modal::new("Test").esc_closes(false).show(ctx, &mut open, |_, enter| {
+///         if enter { ModalResponse::Accepted(7) } else { ModalResponse::Pending }
+///     });
+/// });
+/// assert_eq!(response, ModalResponse::Accepted(7));
+/// assert!(!open);
+/// ```
+///
+/// Only the topmost modal handles Escape; the one beneath stays open:
+///
+/// ```rust
+/// # use eframe::egui;
+/// # use mugin_widgets::window::{Modal, ModalResponse};
+/// # let escape = egui::Event::Key {
+/// #     key: egui::Key::Escape, physical_key: None, pressed: true, repeat: false,
+/// #     modifiers: egui::Modifiers::NONE,
+/// # };
+/// let ctx = egui::Context::default();
+/// let (mut outer, mut inner) = (true, true);
+/// let mut frame = |events: Vec<egui::Event>, outer: &mut bool, inner: &mut bool| {
+///     let input = egui::RawInput { events, ..Default::default() };
+///     let _ = ctx.run(input, |ctx| {
+///         Modal::new("Outer").show(ctx, outer, |_, _| ModalResponse::<()>::Pending);
+///         Modal::new("Inner").show(ctx, inner, |_, _| ModalResponse::<()>::Pending);
+///     });
+/// };
+/// frame(vec![], &mut outer, &mut inner);
+/// frame(vec![escape], &mut outer, &mut inner);
+/// assert!(outer);
+/// assert!(!inner);
+/// ```
+pub struct Modal<'a> {
+    title: &'a str,
+    min_size: Option<egui::Vec2>,
+    esc_closes: bool,
+    enter_accepts: bool,
+}
 
-    let mut should_close = false;
+impl<'a> Modal<'a> {
+    pub fn new(title: &'a str) -> Self {
+        Self {
+            title,
+            min_size: None,
+            esc_closes: true,
+            enter_accepts: true,
+        }
+    }
 
-    let mut window_open = true;
-    egui::Window::new(title)
-        .open(&mut window_open)
-        .collapsible(false)
-        .resizable(false)
-        .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, -100.0))
-        .show(ctx, |ui| {
-            if body(ui) {
-                should_close = true;
-            }
-        });
+    /// Minimum content size
+    pub fn min_size(mut self, size: [f32; 2]) -> Self {
+        self.min_size = Some(egui::vec2(size[0], size[1]));
+        self
+    }
 
-    // Window X button was clicked
-    if !window_open {
-        should_close = true;
+    /// Whether Escape closes the modal (default `true`)
+    pub fn esc_closes(mut self, esc_closes: bool) -> Self {
+        self.esc_closes = esc_closes;
+        self
     }
 
-    if should_close {
-        *open = false;
+    /// Whether Enter is consumed and passed to the body as `enter`
+    /// (default `true`). Turn off for bodies with multiline text.
+    pub fn enter_accepts(mut self, enter_accepts: bool) -> Self {
+        self.enter_accepts = enter_accepts;
+        self
     }
 
-    should_close
+    /// Show the modal while `open` is set. `body` gets the UI and whether
+    /// Enter was pressed this frame, and returns `Accepted` or `Closed` to
+    /// finish. `open` is cleared when the modal finishes.
+    pub fn show<T>(
+        self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        body: impl FnOnce(&mut egui::Ui, bool) -> ModalResponse<T>,
+    ) -> ModalResponse<T> {
+        if !*open {
+            return ModalResponse::Pending;
+        }
+
+        let id = egui::Id::new("mugin_modal").with(self.title);
+        let window_layer = egui::LayerId::new(egui::Order::Foreground, id);
+        let is_top = push_modal(ctx, id);
+
+        // Keys go to the topmost modal only, and never reach the app
+        let (escape, enter) = if is_top {
+            ctx.input_mut(|i| {
+                let escape =
+                    self.esc_closes && i.consume_key(egui::Modifiers::NONE, egui::Key::Escape);
+                let enter =
+                    self.enter_accepts && i.consume_key(egui::Modifiers::NONE, egui::Key::Enter);
+                (escape, enter)
+            })
+        } else {
+            (false, false)
+        };
+
+        // Keep keyboard focus inside the topmost modal
+        if is_top
+            && let Some(focused) = ctx.memory(|m| m.focused())
+            && ctx
+                .read_response(focused)
+                .is_some_and(|response| response.layer_id != window_layer)
+        {
+            ctx.memory_mut(|m| m.surrender_focus(focused));
+        }
+
+        // Dim and block everything beneath
+        let screen = ctx.screen_rect();
+        let backdrop = egui::Area::new(id.with("backdrop"))
+            .order(egui::Order::Foreground)
+            .fixed_pos(screen.min)
+            .show(ctx, |ui| {
+                ui.painter()
+                    .rect_filled(screen, 0.0, egui::Color32::from_black_alpha(120));
+                ui.allocate_response(screen.size(), egui::Sense::click_and_drag());
+            });
+        ctx.move_to_top(backdrop.response.layer_id);
+
+        let mut result = ModalResponse::Pending;
+        let mut window_open = true;
+        let mut window = egui::Window::new(self.title)
+            .id(id)
+            .order(egui::Order::Foreground)
+            .open(&mut window_open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, -100.0));
+        if let Some(size) = self.min_size {
+            window = window.min_width(size.x).min_height(size.y);
+        }
+        window.show(ctx, |ui| {
+            result = body(ui, enter);
+        });
+        ctx.move_to_top(window_layer);
+
+        if escape || !window_open {
+            result = ModalResponse::Closed;
+        }
+        if !matches!(result, ModalResponse::Pending) {
+            *open = false;
+        }
+        result
+    }
 }
 
 // ─── Resizable Dialog ────────────────────────────────────────────────────
 
 /// Shows a resizable dialog window with a fixed minimum size.
 ///
-/// Unlike [`modal`], this window can be resized and moved freely.
+/// Unlike [`Modal`], this window can be resized and moved freely.
 /// The `body` closure returns `true` to close the window.
 ///
 /// # Example
 ///
 /// ```rust
-/// mugin_widgets::window::dialog("Export PDF", ctx, &mut is_open, [800.0, 600.0], |ui| {
+/// # use eframe::egui;
+/// # fn show(ctx: &egui::Context, is_open: &mut bool) {
+/// mugin_widgets::window::dialog("Export PDF", ctx, is_open, [800.0, 600.0], |ui| {
 ///     // render settings and preview...
 ///     ui.button("Cancel").clicked()
 /// });
+/// # }
 /// ```
 pub fn dialog(
     title: &str,
@@ -121,39 +345,42 @@ pub enum ConfirmResult {
 /// # Example
 ///
 /// ```rust
-/// match mugin_widgets::window::confirm("Delete?", ctx, &mut open, "Are you sure?") {
+/// # use eframe::egui;
+/// # use mugin_widgets::window::ConfirmResult;
+/// # fn delete_entity() {}
+/// # fn show(ctx: &egui::Context, open: &mut bool) {
+/// match mugin_widgets::window::confirm("Delete?", ctx, open, "Are you sure?") {
 ///     ConfirmResult::Confirmed => { delete_entity(); }
 ///     ConfirmResult::Cancelled => { /* nothing */ }
 ///     ConfirmResult::Pending => { /* still showing */ }
 /// }
+/// # }
 /// ```
 pub fn confirm(title: &str, ctx: &egui::Context, open: &mut bool, message: &str) -> ConfirmResult {
     if !*open {
         return ConfirmResult::Pending;
     }
 
-    let mut result = ConfirmResult::Pending;
-
-    let closed = modal(title, ctx, open, |ui| {
+    let response = Modal::new(title).show(ctx, open, |ui, enter| {
         ui.label(message);
         ui.add_space(10.0);
         ui.horizontal(|ui| {
-            if ui.button("OK").clicked() {
-                result = ConfirmResult::Confirmed;
+            if ui.button("OK").clicked() || enter {
+                ModalResponse::Accepted(())
+            } else if ui.button("Cancel").clicked() {
+                ModalResponse::Closed
+            } else {
+                ModalResponse::Pending
             }
-            if ui.button("Cancel").clicked() {
-                result = ConfirmResult::Cancelled;
-            }
-        });
-        result != ConfirmResult::Pending
+        })
+        .inner
     });
 
-    // If window was closed via X button
-    if closed && result == ConfirmResult::Pending {
-        result = ConfirmResult::Cancelled;
+    match response {
+        ModalResponse::Pending => ConfirmResult::Pending,
+        ModalResponse::Closed => ConfirmResult::Cancelled,
+        ModalResponse::Accepted(()) => ConfirmResult::Confirmed,
     }
-
-    result
 }
 
 // ─── Generic Window ──────────────────────────────────────────────────────
@@ -171,9 +398,12 @@ pub fn confirm(title: &str, ctx: &egui::Context, open: &mut bool, message: &str)
 /// # Example
 ///
 /// ```rust
-/// mugin_widgets::window::window("My Window", ctx, &mut open, [400.0, 300.0], true, |ui| {
+/// # use eframe::egui;
+/// # fn show(ctx: &egui::Context, open: &mut bool) {
+/// mugin_widgets::window::window("My Window", ctx, open, [400.0, 300.0], true, |ui| {
 ///     ui.label("Content");
 /// });
+/// # }
 /// ```
 pub fn window(
     title: &str,